@@ -0,0 +1,936 @@
+//! Browsing into archives as if they were folders.
+//!
+//! A path that runs *through* an archive file, such as
+//! `C:\downloads\tools.zip\bin`, is a virtual path: the part up to the
+//! archive names a real file, and the rest names a folder inside it.
+//! [`list_directory`](crate::list_directory) uses [`split_archive_path`] to
+//! recognise these and lists them from the archive's central directory.
+//! [`extract_archive`] writes entries back out to disk, and [`create_archive`]
+//! packs files and folders into a new zip.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use tracing::{debug, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{CancellationToken, EntryKind, EntryMeta, Progress, ZError, ZResult};
+
+/// Archive formats that can be browsed (lowercase extensions).
+pub const BROWSABLE_ARCHIVE_EXTENSIONS: &[&str] = &["zip"];
+
+/// Buffer size used when writing extracted files.
+const EXTRACT_CHUNK: usize = 64 * 1024;
+
+/// Summary of a finished extraction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractResult {
+    /// Number of files written.
+    pub files: usize,
+    /// Number of folder entries created.
+    pub folders: usize,
+    /// Number of files left out because their target already existed.
+    pub skipped: usize,
+    /// Uncompressed bytes written.
+    pub bytes: u64,
+}
+
+/// Summary of a newly created archive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressReport {
+    /// The archive that was written.
+    pub archive: PathBuf,
+    /// Number of files added.
+    pub files: usize,
+    /// Number of folders added.
+    pub folders: usize,
+    /// Uncompressed size of the files added.
+    pub bytes_in: u64,
+    /// Size of the finished archive.
+    pub bytes_out: u64,
+    /// Items left out of the archive, with the reason.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl CompressReport {
+    /// Whether every item made it into the archive.
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+/// A file or folder scheduled to be added to a new archive.
+struct PendingItem {
+    source: PathBuf,
+    /// Name inside the archive, `/`-separated.
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+/// An archive entry scheduled for extraction.
+struct PlannedEntry {
+    index: usize,
+    /// Target path relative to the destination.
+    relative: PathBuf,
+    is_dir: bool,
+    size: u64,
+}
+
+/// Returns `true` if `path` has an archive extension that can be browsed.
+///
+/// Only the name is checked; the file is not opened.
+pub fn is_browsable_archive(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            BROWSABLE_ARCHIVE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Split a path running through an archive into the archive file and the
+/// folder inside it.
+///
+/// Returns `None` for ordinary paths. The archive root itself yields an empty
+/// inner path. Only ancestors with an archive extension are checked on disk,
+/// so this is cheap for regular directories.
+pub fn split_archive_path(path: impl AsRef<Path>) -> Option<(PathBuf, PathBuf)> {
+    let path = path.as_ref();
+    let archive = path
+        .ancestors()
+        .find(|p| is_browsable_archive(p) && p.is_file())?;
+    let inner = path.strip_prefix(archive).ok()?.to_path_buf();
+    Some((archive.to_path_buf(), inner))
+}
+
+/// List the entries of the folder `inner` inside `archive`.
+///
+/// Folders that only exist implicitly (as a prefix of a file name) are listed
+/// as well. Entries with unsafe names (absolute or containing `..`) are
+/// skipped. Each entry's path is the virtual path `archive/inner/name`.
+pub fn list_archive(archive: impl AsRef<Path>, inner: impl AsRef<Path>) -> ZResult<Vec<EntryMeta>> {
+    let archive = archive.as_ref();
+    let inner = inner.as_ref();
+    debug!(archive = %archive.display(), inner = %inner.display(), "Listing archive");
+
+    let mut zip = open_archive(archive)?;
+    let base = archive.join(inner);
+    // Keyed by name so implicit and explicit folder entries collapse into one
+    let mut children: BTreeMap<String, EntryMeta> = BTreeMap::new();
+    let mut found_inner = inner.as_os_str().is_empty();
+
+    for index in 0..zip.len() {
+        let file = match zip.by_index_raw(index) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!(archive = %archive.display(), index, error = %e, "Skipping archive entry");
+                continue;
+            }
+        };
+        let Some(name) = file.enclosed_name() else {
+            continue;
+        };
+        let Ok(rest) = name.strip_prefix(inner) else {
+            continue;
+        };
+        let mut components = rest.components().filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        });
+        let Some(first) = components.next() else {
+            // The folder entry for `inner` itself
+            found_inner = true;
+            continue;
+        };
+        found_inner = true;
+
+        let is_dir = components.next().is_some() || file.is_dir();
+        let path = base.join(&first);
+
+        if is_dir {
+            children
+                .entry(first.clone())
+                .or_insert_with(|| EntryMeta::new(first, path, EntryKind::Directory));
+        } else {
+            let mut meta = EntryMeta::new(first.clone(), path, EntryKind::File);
+            meta.size = file.size();
+            meta.modified = file.last_modified().and_then(zip_time_to_utc);
+            children.insert(first, meta);
+        }
+    }
+
+    if !found_inner {
+        return Err(ZError::NotFound { path: base });
+    }
+
+    Ok(children.into_values().collect())
+}
+
+/// Extract `entries` from `archive` into `destination`.
+///
+/// Entries are paths inside the archive; virtual paths running through the
+/// archive are accepted as well. Each one lands in `destination` under its
+/// own name, and folders bring everything below them. An empty `entries`
+/// extracts the whole archive.
+///
+/// A file whose target already exists goes to `on_conflict` with the
+/// archive entry and the target. It returns where to write the file instead,
+/// the target itself to overwrite it, or `None` to skip the file; an error
+/// stops the extraction. `on_progress` is called before each file and after
+/// every chunk written. If extraction fails or is cancelled part-way through
+/// a file, that file is removed again.
+pub fn extract_archive(
+    archive: impl AsRef<Path>,
+    entries: &[PathBuf],
+    destination: impl AsRef<Path>,
+    cancel: &CancellationToken,
+    mut on_conflict: impl FnMut(&EntryMeta, &Path) -> ZResult<Option<PathBuf>>,
+    mut on_progress: impl FnMut(&Progress),
+) -> ZResult<ExtractResult> {
+    let archive = archive.as_ref();
+    let destination = destination.as_ref();
+    debug!(
+        archive = %archive.display(),
+        destination = %destination.display(),
+        entries = entries.len(),
+        "Extracting archive"
+    );
+
+    let mut zip = open_archive(archive)?;
+    let selected: Vec<&Path> = entries
+        .iter()
+        .map(|e| e.strip_prefix(archive).unwrap_or(e))
+        .collect();
+    let plan = plan_extraction(&mut zip, archive, &selected)?;
+
+    let files = plan.iter().filter(|e| !e.is_dir).count();
+    let total_bytes = plan.iter().map(|e| e.size).sum();
+    let mut progress = Progress::new(files, Some(total_bytes));
+    let mut result = ExtractResult::default();
+
+    for entry in plan {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let target = destination.join(&entry.relative);
+
+        if entry.is_dir {
+            fs::create_dir_all(&target).map_err(|e| ZError::from_io(&target, e))?;
+            result.folders += 1;
+            continue;
+        }
+
+        let mut reader = zip.by_index(entry.index).map_err(|e| archive_error(archive, e))?;
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        let target = if target.symlink_metadata().is_ok() {
+            let name = target.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let mut source = EntryMeta::new(name, archive.join(reader.name()), EntryKind::File);
+            source.size = reader.size();
+            source.modified = reader.last_modified().and_then(zip_time_to_utc);
+            match on_conflict(&source, &target)? {
+                None => {
+                    debug!(target = %target.display(), "Skipping existing file");
+                    result.skipped += 1;
+                    progress.items_done += 1;
+                    progress.bytes_done += entry.size;
+                    continue;
+                }
+                Some(path) => {
+                    if path == target {
+                        options.create_new(false).create(true).truncate(true);
+                    }
+                    path
+                }
+            }
+        } else {
+            target
+        };
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| ZError::from_io(parent, e))?;
+        }
+        progress.current_item = Some(target.clone());
+        on_progress(&progress);
+
+        let mut out = options.open(&target).map_err(|e| ZError::from_io(&target, e))?;
+        let written = write_entry(&mut reader, &mut out, archive, &target, cancel, |n| {
+            progress.bytes_done += n;
+            on_progress(&progress);
+        });
+        drop(out);
+
+        match written {
+            Ok(bytes) => {
+                result.files += 1;
+                result.bytes += bytes;
+                progress.items_done += 1;
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&target);
+                return Err(e);
+            }
+        }
+    }
+
+    progress.current_item = None;
+    on_progress(&progress);
+    Ok(result)
+}
+
+/// Work out which archive entries to extract and where each one goes.
+fn plan_extraction(
+    zip: &mut ZipArchive<BufReader<File>>,
+    archive: &Path,
+    selected: &[&Path],
+) -> ZResult<Vec<PlannedEntry>> {
+    let mut matched = vec![false; selected.len()];
+    let mut plan = Vec::new();
+
+    for index in 0..zip.len() {
+        let file = match zip.by_index_raw(index) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!(archive = %archive.display(), index, error = %e, "Skipping archive entry");
+                continue;
+            }
+        };
+        let Some(name) = file.enclosed_name() else {
+            continue;
+        };
+
+        let relative = if selected.is_empty() {
+            Some(name.clone())
+        } else {
+            // Every selection that covers the entry counts as found, but the
+            // entry is extracted once, under the first of them
+            let mut relative = None;
+            for (i, sel) in selected.iter().enumerate() {
+                let Ok(rest) = name.strip_prefix(sel) else {
+                    continue;
+                };
+                matched[i] = true;
+                if relative.is_none() {
+                    // Keep the selected entry's own name, like copying it would
+                    let mut path = sel.file_name().map(PathBuf::from).unwrap_or_default();
+                    if !rest.as_os_str().is_empty() {
+                        path.push(rest);
+                    }
+                    relative = Some(path);
+                }
+            }
+            relative
+        };
+
+        if let Some(relative) = relative {
+            plan.push(PlannedEntry {
+                index,
+                relative,
+                is_dir: file.is_dir(),
+                size: file.size(),
+            });
+        }
+    }
+
+    if let Some(i) = matched.iter().position(|m| !m) {
+        return Err(ZError::NotFound {
+            path: archive.join(selected[i]),
+        });
+    }
+
+    Ok(plan)
+}
+
+/// Copy one decompressed entry to `out`, checking for cancellation per chunk.
+fn write_entry(
+    reader: &mut impl Read,
+    out: &mut File,
+    archive: &Path,
+    target: &Path,
+    cancel: &CancellationToken,
+    mut on_chunk: impl FnMut(u64),
+) -> ZResult<u64> {
+    let mut buf = vec![0u8; EXTRACT_CHUNK];
+    let mut written = 0;
+
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        // Corrupt data and checksum mismatches surface here as read errors
+        let n = reader.read(&mut buf).map_err(|e| ZError::io(archive, e))?;
+        if n == 0 {
+            return Ok(written);
+        }
+        out.write_all(&buf[..n]).map_err(|e| ZError::from_io(target, e))?;
+        written += n as u64;
+        on_chunk(n as u64);
+    }
+}
+
+/// Create the zip archive `destination` from `sources`.
+///
+/// Files are stored under their own name and folders with everything below
+/// them, mirroring how they would be copied. `destination` must not exist
+/// yet; it is never overwritten. Symbolic links, unreadable items and names
+/// that collide with an earlier item are left out and listed in
+/// [`CompressReport::skipped`].
+///
+/// `on_progress` is called before each file and after every chunk. On error
+/// or cancellation the partially written archive is removed.
+pub fn create_archive(
+    sources: &[PathBuf],
+    destination: impl AsRef<Path>,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&Progress),
+) -> ZResult<CompressReport> {
+    let destination = destination.as_ref();
+    debug!(
+        destination = %destination.display(),
+        sources = sources.len(),
+        "Creating archive"
+    );
+
+    if destination.symlink_metadata().is_ok() {
+        return Err(ZError::AlreadyExists {
+            path: destination.to_path_buf(),
+        });
+    }
+
+    let mut report = CompressReport {
+        archive: destination.to_path_buf(),
+        ..CompressReport::default()
+    };
+    let items = plan_compression(sources, cancel, &mut report)?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(destination)
+        .map_err(|e| ZError::from_io(destination, e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let written = write_items(&mut zip, items, destination, cancel, &mut report, &mut on_progress)
+        .and_then(|progress| {
+            let mut out = zip.finish().map_err(|e| write_error(destination, e))?;
+            out.flush().map_err(|e| ZError::from_io(destination, e))?;
+            Ok(progress)
+        });
+
+    let mut progress = match written {
+        Ok(progress) => progress,
+        Err(e) => {
+            let _ = fs::remove_file(destination);
+            return Err(e);
+        }
+    };
+
+    report.bytes_out = fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+    progress.current_item = None;
+    on_progress(&progress);
+    Ok(report)
+}
+
+/// Walk `sources` and collect everything that goes into the archive.
+///
+/// A missing or unreadable source is an error; problems further down only
+/// skip the affected item.
+fn plan_compression(
+    sources: &[PathBuf],
+    cancel: &CancellationToken,
+    report: &mut CompressReport,
+) -> ZResult<Vec<PendingItem>> {
+    let mut items = Vec::new();
+    let mut names = HashSet::new();
+    let mut pending: Vec<(PathBuf, String)> = Vec::new();
+
+    for source in sources {
+        let name = source
+            .file_name()
+            .ok_or_else(|| ZError::InvalidPath {
+                path: source.clone(),
+                reason: "No file name".to_string(),
+            })?
+            .to_string_lossy()
+            .into_owned();
+        fs::symlink_metadata(source).map_err(|e| ZError::from_io(source, e))?;
+        pending.push((source.clone(), name));
+
+        while let Some((path, name)) = pending.pop() {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            let metadata = match fs::symlink_metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    report.skipped.push((path, e.to_string()));
+                    continue;
+                }
+            };
+            if metadata.file_type().is_symlink() {
+                report.skipped.push((path, "Symbolic links are not archived".to_string()));
+                continue;
+            }
+            if !names.insert(name.to_lowercase()) {
+                report.skipped.push((path, format!("'{name}' is already in the archive")));
+                continue;
+            }
+
+            if metadata.is_dir() {
+                let children = match fs::read_dir(&path) {
+                    Ok(children) => children,
+                    Err(e) => {
+                        report.skipped.push((path, e.to_string()));
+                        continue;
+                    }
+                };
+                for child in children.flatten() {
+                    let child_name = format!("{name}/{}", child.file_name().to_string_lossy());
+                    pending.push((child.path(), child_name));
+                }
+                items.push(PendingItem {
+                    source: path,
+                    name,
+                    is_dir: true,
+                    size: 0,
+                });
+            } else {
+                items.push(PendingItem {
+                    source: path,
+                    name,
+                    is_dir: false,
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Add the planned items to `zip`, returning the final progress.
+fn write_items(
+    zip: &mut ZipWriter<BufWriter<File>>,
+    items: Vec<PendingItem>,
+    destination: &Path,
+    cancel: &CancellationToken,
+    report: &mut CompressReport,
+    on_progress: &mut impl FnMut(&Progress),
+) -> ZResult<Progress> {
+    let files = items.iter().filter(|i| !i.is_dir).count();
+    let total_bytes = items.iter().map(|i| i.size).sum();
+    let mut progress = Progress::new(files, Some(total_bytes));
+
+    for item in items {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+
+        let mut options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(item.size > u64::from(u32::MAX));
+        if let Some(time) = fs::metadata(&item.source)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| utc_to_zip_time(t.into()))
+        {
+            options = options.last_modified_time(time);
+        }
+
+        if item.is_dir {
+            zip.add_directory(item.name.as_str(), options)
+                .map_err(|e| write_error(destination, e))?;
+            report.folders += 1;
+            continue;
+        }
+
+        let mut source = match File::open(&item.source) {
+            Ok(file) => file,
+            Err(e) => {
+                report.skipped.push((item.source, e.to_string()));
+                continue;
+            }
+        };
+        progress.current_item = Some(item.source.clone());
+        on_progress(&progress);
+
+        zip.start_file(item.name.as_str(), options)
+            .map_err(|e| write_error(destination, e))?;
+        let mut buf = vec![0u8; EXTRACT_CHUNK];
+        let mut added = 0;
+        loop {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            let n = match source.read(&mut buf) {
+                Ok(n) => n,
+                Err(e) => {
+                    // Drop the half-written entry but keep going with the rest
+                    zip.abort_file().map_err(|e| write_error(destination, e))?;
+                    report.skipped.push((item.source.clone(), e.to_string()));
+                    progress.bytes_done = progress.bytes_done.saturating_sub(added);
+                    added = 0;
+                    break;
+                }
+            };
+            if n == 0 {
+                report.files += 1;
+                report.bytes_in += added;
+                progress.items_done += 1;
+                break;
+            }
+            zip.write_all(&buf[..n]).map_err(|e| ZError::from_io(destination, e))?;
+            added += n as u64;
+            progress.bytes_done += n as u64;
+            on_progress(&progress);
+        }
+        // Files that stop short of their planned size still count as done
+        progress.bytes_done += item.size.saturating_sub(added);
+    }
+
+    Ok(progress)
+}
+
+fn open_archive(archive: &Path) -> ZResult<ZipArchive<BufReader<File>>> {
+    let file = File::open(archive).map_err(|e| ZError::from_io(archive, e))?;
+    ZipArchive::new(BufReader::new(file)).map_err(|e| archive_error(archive, e))
+}
+
+fn write_error(archive: &Path, error: zip::result::ZipError) -> ZError {
+    ZError::InvalidOperation {
+        operation: "write archive".to_string(),
+        reason: format!("{}: {}", archive.display(), error),
+    }
+}
+
+fn archive_error(archive: &Path, error: zip::result::ZipError) -> ZError {
+    ZError::InvalidOperation {
+        operation: "read archive".to_string(),
+        reason: format!("{}: {}", archive.display(), error),
+    }
+}
+
+/// Convert a zip (MS-DOS) timestamp, stored without a timezone, to UTC as-is.
+fn zip_time_to_utc(time: zip::DateTime) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())?;
+    let datetime = date.and_hms_opt(
+        time.hour().into(),
+        time.minute().into(),
+        time.second().into(),
+    )?;
+    Some(datetime.and_utc())
+}
+
+/// Convert a UTC timestamp to a zip timestamp; `None` outside 1980..=2107.
+fn utc_to_zip_time(time: DateTime<Utc>) -> Option<zip::DateTime> {
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn create_zip(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("Tools.ZIP");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = SimpleFileOptions::default();
+
+        writer.start_file("readme.txt", options).unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.add_directory("empty/", options).unwrap();
+        // `bin/` has no explicit folder entry
+        writer.start_file("bin/tool.exe", options).unwrap();
+        writer.write_all(b"MZ....").unwrap();
+        writer.start_file("bin/sub/deep.dll", options).unwrap();
+        writer.write_all(b"dll").unwrap();
+        writer.start_file("../escape.txt", options).unwrap();
+        writer.write_all(b"nope").unwrap();
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_split_archive_path() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+
+        assert_eq!(
+            split_archive_path(zip.join("bin").join("sub")),
+            Some((zip.clone(), PathBuf::from("bin").join("sub")))
+        );
+        assert_eq!(split_archive_path(&zip), Some((zip, PathBuf::new())));
+        assert_eq!(split_archive_path(dir.path()), None);
+
+        // A folder named like an archive is not one
+        let fake = dir.path().join("folder.zip");
+        std::fs::create_dir(&fake).unwrap();
+        assert_eq!(split_archive_path(fake.join("x")), None);
+    }
+
+    #[test]
+    fn test_list_archive_root() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+
+        let entries = list_archive(&zip, "").unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["bin", "empty", "readme.txt"]);
+
+        let bin = &entries[0];
+        assert!(bin.is_directory());
+        assert_eq!(bin.path, zip.join("bin"));
+
+        let readme = &entries[2];
+        assert!(readme.is_file());
+        assert_eq!(readme.size, 5);
+        assert_eq!(readme.extension.as_deref(), Some("txt"));
+        assert!(readme.modified.is_some());
+    }
+
+    #[test]
+    fn test_list_archive_subfolder() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+
+        let entries = list_archive(&zip, "bin").unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["sub", "tool.exe"]);
+        assert_eq!(entries[1].path, zip.join("bin").join("tool.exe"));
+
+        assert!(list_archive(&zip, "empty").unwrap().is_empty());
+        assert!(matches!(
+            list_archive(&zip, "missing"),
+            Err(ZError::NotFound { .. })
+        ));
+    }
+
+    fn extract(zip: &Path, entries: &[&str], dest: &Path) -> ZResult<ExtractResult> {
+        let entries: Vec<PathBuf> = entries.iter().map(PathBuf::from).collect();
+        extract_archive(zip, &entries, dest, &CancellationToken::new(), |_, _| Ok(None), |_| {})
+    }
+
+    #[test]
+    fn test_extract_whole_archive() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+        let dest = dir.path().join("out");
+
+        let mut last = Progress::default();
+        let cancel = CancellationToken::new();
+        let result = extract_archive(&zip, &[], &dest, &cancel, |_, _| Ok(None), |p| {
+            last = p.clone();
+        })
+        .unwrap();
+
+        assert_eq!(result.files, 3);
+        assert_eq!(result.folders, 1);
+        assert_eq!(result.bytes, 14);
+        assert_eq!(std::fs::read(dest.join("readme.txt")).unwrap(), b"hello");
+        assert_eq!(std::fs::read(dest.join("bin/sub/deep.dll")).unwrap(), b"dll");
+        assert!(dest.join("empty").is_dir());
+        assert!(!dir.path().join("escape.txt").exists());
+        assert_eq!((last.items_done, last.bytes_done), (3, 14));
+        assert_eq!(last.percentage_int(), 100);
+    }
+
+    #[test]
+    fn test_extract_selected_entries() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+        let dest = dir.path().join("out");
+        let virtual_path = zip.join("bin").join("tool.exe");
+
+        let result = extract(&zip, &["bin/sub", virtual_path.to_str().unwrap()], &dest).unwrap();
+
+        assert_eq!(result.files, 2);
+        assert!(dest.join("sub").join("deep.dll").is_file());
+        assert!(dest.join("tool.exe").is_file());
+        assert!(!dest.join("readme.txt").exists());
+
+        // A file inside a selected folder is found too, and extracted once
+        let overlap = dir.path().join("overlap");
+        let result = extract(&zip, &["bin", "bin/tool.exe"], &overlap).unwrap();
+        assert_eq!(result.files, 2);
+        assert!(overlap.join("bin").join("tool.exe").is_file());
+        assert!(!overlap.join("tool.exe").exists());
+
+        assert!(matches!(
+            extract(&zip, &["missing"], &dest),
+            Err(ZError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_extract_into_folder_with_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+        let dest = dir.path().join("out");
+        std::fs::create_dir(&dest).unwrap();
+        std::fs::write(dest.join("readme.txt"), b"mine").unwrap();
+        let extract_with = |on_conflict: &dyn Fn(&EntryMeta, &Path) -> ZResult<Option<PathBuf>>| {
+            extract_archive(&zip, &[], &dest, &CancellationToken::new(), on_conflict, |_| {})
+        };
+
+        // Skipped, while the rest of the archive still comes out
+        let seen = std::cell::RefCell::new(Vec::new());
+        let result = extract_with(&|source, target| {
+            seen.borrow_mut().push((source.path.clone(), source.size, target.to_path_buf()));
+            Ok(None)
+        })
+        .unwrap();
+        assert_eq!((result.files, result.skipped), (2, 1));
+        assert_eq!(seen.into_inner(), [(zip.join("readme.txt"), 5, dest.join("readme.txt"))]);
+        assert_eq!(std::fs::read(dest.join("readme.txt")).unwrap(), b"mine");
+        assert!(dest.join("bin").join("sub").join("deep.dll").is_file());
+
+        // Renamed
+        let renamed = dest.join("readme (1).txt");
+        let result = extract_with(&|source, target| {
+            let readme = source.name == "readme.txt";
+            Ok(Some(if readme { renamed.clone() } else { target.to_path_buf() }))
+        })
+        .unwrap();
+        assert_eq!((result.files, result.skipped), (3, 0));
+        assert_eq!(std::fs::read(&renamed).unwrap(), b"hello");
+        assert_eq!(std::fs::read(dest.join("readme.txt")).unwrap(), b"mine");
+
+        // Overwritten
+        extract_with(&|_, target| Ok(Some(target.to_path_buf()))).unwrap();
+        assert_eq!(std::fs::read(dest.join("readme.txt")).unwrap(), b"hello");
+
+        // Cancelled at the conflict
+        assert!(matches!(extract_with(&|_, _| Err(ZError::Cancelled)), Err(ZError::Cancelled)));
+    }
+
+    #[test]
+    fn test_extract_cancelled() {
+        let dir = TempDir::new().unwrap();
+        let zip = create_zip(&dir);
+        let dest = dir.path().join("out");
+        let token = CancellationToken::new();
+        token.cancel();
+
+        assert!(matches!(
+            extract_archive(&zip, &[], &dest, &token, |_, _| Ok(None), |_| {}),
+            Err(ZError::Cancelled)
+        ));
+        assert!(!dest.join("readme.txt").exists());
+    }
+
+    #[test]
+    fn test_create_archive_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir_all(project.join("src").join("empty")).unwrap();
+        std::fs::write(project.join("src").join("main.rs"), b"fn main() {}").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"notes").unwrap();
+        let archive = dir.path().join("bundle.zip");
+
+        let mut last = Progress::default();
+        let report = create_archive(
+            &[project.clone(), dir.path().join("notes.txt")],
+            &archive,
+            &CancellationToken::new(),
+            |p| last = p.clone(),
+        )
+        .unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!((report.files, report.folders), (2, 3));
+        assert_eq!(report.bytes_in, 17);
+        assert!(report.bytes_out > 0);
+        assert_eq!(last.percentage_int(), 100);
+
+        let root = list_archive(&archive, "").unwrap();
+        let names: Vec<_> = root.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["notes.txt", "project"]);
+        let src = list_archive(&archive, Path::new("project").join("src")).unwrap();
+        assert_eq!(src.len(), 2);
+        assert!(src.iter().any(|e| e.name == "main.rs" && e.size == 12));
+    }
+
+    #[test]
+    fn test_create_archive_conflicts() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+        std::fs::write(a.join("same.txt"), b"one").unwrap();
+        std::fs::write(b.join("same.txt"), b"two").unwrap();
+        let token = CancellationToken::new();
+
+        // The existing archive is left alone
+        let existing = dir.path().join("existing.zip");
+        std::fs::write(&existing, b"keep").unwrap();
+        assert!(matches!(
+            create_archive(&[a.join("same.txt")], &existing, &token, |_| {}),
+            Err(ZError::AlreadyExists { .. })
+        ));
+        assert_eq!(std::fs::read(&existing).unwrap(), b"keep");
+
+        let archive = dir.path().join("out.zip");
+        let report =
+            create_archive(&[a.join("same.txt"), b.join("same.txt")], &archive, &token, |_| {})
+                .unwrap();
+        assert_eq!(report.files, 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, b.join("same.txt"));
+    }
+
+    #[test]
+    fn test_create_archive_inside_source_and_cancelled() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("file.txt"), b"data").unwrap();
+        let archive = dir.path().join("self.zip");
+
+        let report = create_archive(
+            &[dir.path().to_path_buf()],
+            &archive,
+            &CancellationToken::new(),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(report.files, 1);
+        assert!(report.is_complete());
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let cancelled = dir.path().join("cancelled.zip");
+        assert!(matches!(
+            create_archive(&[dir.path().join("file.txt")], &cancelled, &token, |_| {}),
+            Err(ZError::Cancelled)
+        ));
+        assert!(!cancelled.exists());
+    }
+
+    #[test]
+    fn test_invalid_archive() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("broken.zip");
+        std::fs::write(&path, b"not a zip").unwrap();
+
+        assert!(matches!(
+            list_archive(&path, ""),
+            Err(ZError::InvalidOperation { .. })
+        ));
+    }
+}
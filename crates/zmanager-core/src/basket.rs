@@ -0,0 +1,265 @@
+//! File baskets: named sets of files collected from any directories.
+//!
+//! A basket gathers files and folders from all over the disk so they can be
+//! copied, moved or deleted together later. A pane shows a basket at the
+//! virtual path `basket:<name>`, listing its items as if they were in one
+//! directory. Baskets are stored in `baskets.toml` next to the config file.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::visits::same_path;
+use crate::{get_entry_meta, Config, EntryMeta, ZError, ZResult};
+
+/// Prefix of the virtual path a basket is shown at.
+pub const BASKET_PATH_PREFIX: &str = "basket:";
+
+/// The virtual path a pane shows the basket `name` at.
+pub fn basket_path(name: &str) -> PathBuf {
+    PathBuf::from(format!("{BASKET_PATH_PREFIX}{name}"))
+}
+
+/// The basket name of a virtual basket path, or `None` for any other path.
+pub fn split_basket_path(path: &Path) -> Option<&str> {
+    path.to_str()?
+        .strip_prefix(BASKET_PATH_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+/// A named set of files and folders.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Basket {
+    /// Name the basket is shown and picked by.
+    pub name: String,
+    /// Items in the order they were added.
+    #[serde(default)]
+    paths: Vec<PathBuf>,
+}
+
+impl Basket {
+    /// Create an empty basket.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Items in the order they were added.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Add `paths`, skipping items already in the basket. Returns how many
+    /// were added.
+    pub fn add(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> usize {
+        let before = self.paths.len();
+        for path in paths {
+            if !self.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        self.paths.len() - before
+    }
+
+    /// Remove `path`. Returns whether it was in the basket.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let before = self.paths.len();
+        self.paths.retain(|p| !same_path(p, path));
+        self.paths.len() != before
+    }
+
+    /// Check if `path` is in the basket.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.iter().any(|p| same_path(p, path))
+    }
+
+    /// Drop items that no longer exist, e.g. after they were moved or
+    /// deleted. Returns how many were dropped.
+    pub fn prune_missing(&mut self) -> usize {
+        let before = self.paths.len();
+        self.paths.retain(|p| p.symlink_metadata().is_ok());
+        before - self.paths.len()
+    }
+
+    /// Metadata of the items for listing; items that can't be read are
+    /// skipped.
+    pub fn entries(&self) -> Vec<EntryMeta> {
+        self.paths
+            .iter()
+            .filter_map(|path| get_entry_meta(path).ok())
+            .collect()
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Check if the basket has no items.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// All baskets, persisted between sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Baskets {
+    baskets: Vec<Basket>,
+}
+
+impl Baskets {
+    /// Get the default baskets file path, next to the config file.
+    pub fn default_path() -> ZResult<PathBuf> {
+        Ok(Config::default_path()?.with_file_name("baskets.toml"))
+    }
+
+    /// Load the baskets from the default location.
+    pub fn load() -> ZResult<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load the baskets from a specific path; a missing file means no
+    /// baskets.
+    pub fn load_from(path: &Path) -> ZResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        debug!(path = %path.display(), "Loading baskets");
+
+        let content = std::fs::read_to_string(path).map_err(|e| ZError::io(path, e))?;
+        toml::from_str(&content).map_err(|e| ZError::Config {
+            message: format!("Failed to parse baskets: {e}"),
+        })
+    }
+
+    /// Save the baskets to the default location.
+    pub fn save(&self) -> ZResult<()> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    /// Save the baskets to a specific path.
+    pub fn save_to(&self, path: &Path) -> ZResult<()> {
+        debug!(path = %path.display(), baskets = self.baskets.len(), "Saving baskets");
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ZError::io(parent, e))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| ZError::Config {
+            message: format!("Failed to serialize baskets: {e}"),
+        })?;
+        std::fs::write(path, content).map_err(|e| ZError::io(path, e))
+    }
+
+    /// The basket called `name`.
+    pub fn get(&self, name: &str) -> Option<&Basket> {
+        self.baskets.iter().find(|b| b.name == name)
+    }
+
+    /// The basket called `name`, for changing it.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Basket> {
+        self.baskets.iter_mut().find(|b| b.name == name)
+    }
+
+    /// The basket called `name`, created empty if there is none.
+    pub fn get_or_create(&mut self, name: &str) -> &mut Basket {
+        let index = match self.baskets.iter().position(|b| b.name == name) {
+            Some(index) => index,
+            None => {
+                self.baskets.push(Basket::new(name));
+                self.baskets.len() - 1
+            }
+        };
+        &mut self.baskets[index]
+    }
+
+    /// Delete the basket called `name`. Returns whether there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.baskets.len();
+        self.baskets.retain(|b| b.name != name);
+        self.baskets.len() != before
+    }
+
+    /// All baskets, in the order they were created.
+    pub fn iter(&self) -> impl Iterator<Item = &Basket> {
+        self.baskets.iter()
+    }
+
+    /// Number of baskets.
+    pub fn len(&self) -> usize {
+        self.baskets.len()
+    }
+
+    /// Check if there are no baskets.
+    pub fn is_empty(&self) -> bool {
+        self.baskets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_basket_paths() {
+        assert_eq!(split_basket_path(&basket_path("Photos")), Some("Photos"));
+        assert_eq!(split_basket_path(Path::new("basket:")), None);
+        assert_eq!(split_basket_path(Path::new("C:\\basket:x")), None);
+    }
+
+    #[test]
+    fn test_add_skips_duplicates() {
+        let mut basket = Basket::new("Work");
+        let added = basket.add([PathBuf::from("/a/one.txt"), PathBuf::from("/b/two.txt")]);
+        assert_eq!(added, 2);
+        assert_eq!(basket.add([PathBuf::from("/a/one.txt")]), 0);
+        assert_eq!(basket.len(), 2);
+
+        assert!(basket.remove(Path::new("/a/one.txt")));
+        assert!(!basket.remove(Path::new("/a/one.txt")));
+        assert_eq!(basket.paths(), [PathBuf::from("/b/two.txt")]);
+    }
+
+    #[test]
+    fn test_entries_and_prune_missing() {
+        let temp = TempDir::new().unwrap();
+        let kept = temp.path().join("kept.txt");
+        std::fs::write(&kept, "x").unwrap();
+        let mut basket = Basket::new("Work");
+        basket.add([kept.clone(), temp.path().join("gone.txt")]);
+
+        let entries = basket.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, kept);
+
+        assert_eq!(basket.prune_missing(), 1);
+        assert_eq!(basket.paths(), [kept]);
+    }
+
+    #[test]
+    fn test_baskets_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ZManager").join("baskets.toml");
+
+        assert!(Baskets::load_from(&path).unwrap().is_empty());
+
+        let mut baskets = Baskets::default();
+        baskets.get_or_create("Photos").add([PathBuf::from("C:\\a.jpg")]);
+        baskets.get_or_create("Photos").add([PathBuf::from("D:\\b.jpg")]);
+        baskets.get_or_create("Empty");
+        baskets.save_to(&path).unwrap();
+
+        let loaded = Baskets::load_from(&path).unwrap();
+        assert_eq!(loaded, baskets);
+        assert_eq!(loaded.get("Photos").unwrap().len(), 2);
+
+        let mut loaded = loaded;
+        assert!(loaded.remove("Empty"));
+        assert!(loaded.get("Empty").is_none());
+        assert_eq!(loaded.len(), 1);
+    }
+}
@@ -0,0 +1,441 @@
+//! Renaming many files at once from a name pattern.
+//!
+//! A [`RenamePattern`] such as `[N]_[C:3].[E]` describes the new name of each
+//! item from its current name (`[N]`), extension (`[E]`) and a running
+//! counter (`[C]`, or `[C:width]` for a zero-padded counter). A
+//! [`RenamePlan`] applies a pattern to a list of paths and flags every new
+//! name that can't be used, so the result can be previewed before anything
+//! is touched. [`bulk_rename`] then carries the plan out as a single
+//! transaction: either every item gets its new name or all of them keep
+//! their old one.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, warn};
+
+use crate::operations::{rename, validate_filename};
+use crate::{ZError, ZResult};
+
+/// One piece of a parsed rename pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Text copied as-is.
+    Literal(String),
+    /// The current name without its extension.
+    Name,
+    /// The current extension, without the dot.
+    Extension,
+    /// The counter, zero-padded to `width` digits.
+    Counter { width: usize },
+}
+
+/// A pattern describing the new name of each item in a bulk rename.
+///
+/// Supported placeholders (case-insensitive):
+/// * `[N]` - current name without extension
+/// * `[E]` - current extension without the dot
+/// * `[C]` - counter, `[C:3]` pads it to three digits
+///
+/// `[[` inserts a literal `[`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamePattern {
+    tokens: Vec<Token>,
+    /// First counter value.
+    pub counter_start: u64,
+    /// Amount the counter grows by for each item.
+    pub counter_step: u64,
+}
+
+impl RenamePattern {
+    /// Parse a pattern. The counter starts at 1 and counts up by 1.
+    ///
+    /// # Errors
+    /// * `ZError::InvalidOperation` - Unknown or unterminated placeholder
+    ///
+    /// # Example
+    /// ```
+    /// use zmanager_core::bulk_rename::RenamePattern;
+    /// let pattern = RenamePattern::parse("[N]_[C:3].[E]").unwrap();
+    /// assert_eq!(pattern.apply("photo.jpg", false, 0), "photo_001.jpg");
+    /// ```
+    pub fn parse(pattern: &str) -> ZResult<Self> {
+        let invalid = |reason: String| ZError::InvalidOperation {
+            operation: "parse rename pattern".to_string(),
+            reason,
+        };
+
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find('[') {
+            literal.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+
+            if let Some(after) = rest.strip_prefix('[') {
+                literal.push('[');
+                rest = after;
+                continue;
+            }
+
+            let end = rest
+                .find(']')
+                .ok_or_else(|| invalid(format!("Unterminated placeholder in '{pattern}'")))?;
+            let placeholder = &rest[..end];
+            rest = &rest[end + 1..];
+
+            let token = match placeholder.to_ascii_uppercase().as_str() {
+                "N" => Token::Name,
+                "E" => Token::Extension,
+                "C" => Token::Counter { width: 1 },
+                upper => match upper.strip_prefix("C:").map(str::parse::<usize>) {
+                    Some(Ok(width)) if (1..=20).contains(&width) => Token::Counter { width },
+                    _ => return Err(invalid(format!("Unknown placeholder '[{placeholder}]'"))),
+                },
+            };
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(token);
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        if tokens.is_empty() {
+            return Err(invalid("Pattern cannot be empty".to_string()));
+        }
+
+        Ok(Self {
+            tokens,
+            counter_start: 1,
+            counter_step: 1,
+        })
+    }
+
+    /// Set the first counter value and the step between items.
+    pub fn with_counter(mut self, start: u64, step: u64) -> Self {
+        self.counter_start = start;
+        self.counter_step = step;
+        self
+    }
+
+    /// Build the new name for the item at `index` (0-based) currently named
+    /// `name`.
+    ///
+    /// Folders have no extension, so `[N]` is their whole name. When the
+    /// extension is empty, dots left dangling at the end are dropped, so
+    /// `[N].[E]` leaves such names unchanged.
+    pub fn apply(&self, name: &str, is_dir: bool, index: usize) -> String {
+        let (stem, extension) = split_name(name, is_dir);
+        let counter = self
+            .counter_start
+            .saturating_add(self.counter_step.saturating_mul(index as u64));
+
+        let mut result = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(text) => result.push_str(text),
+                Token::Name => result.push_str(stem),
+                Token::Extension => result.push_str(extension),
+                Token::Counter { width } => result.push_str(&format!("{counter:0width$}")),
+            }
+        }
+
+        if extension.is_empty() {
+            result.truncate(result.trim_end_matches('.').len());
+        }
+        result
+    }
+}
+
+/// Split a name into stem and extension. Leading dots (as in `.gitignore`)
+/// don't start an extension.
+fn split_name(name: &str, is_dir: bool) -> (&str, &str) {
+    if is_dir {
+        return (name, "");
+    }
+    match name.rfind('.') {
+        Some(dot) if name[..dot].chars().any(|c| c != '.') => (&name[..dot], &name[dot + 1..]),
+        _ => (name, ""),
+    }
+}
+
+/// One item of a [`RenamePlan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamePreview {
+    /// Current path.
+    pub from: PathBuf,
+    /// Path after the rename.
+    pub to: PathBuf,
+    /// Why the new name can't be used, if it can't.
+    pub problem: Option<String>,
+}
+
+impl RenamePreview {
+    /// Whether the rename leaves this item as it is.
+    pub fn is_unchanged(&self) -> bool {
+        self.from == self.to
+    }
+}
+
+/// The new names a pattern gives a set of paths, checked for problems.
+#[derive(Debug, Clone, Default)]
+pub struct RenamePlan {
+    /// One entry per path, in the order the paths were given.
+    pub items: Vec<RenamePreview>,
+}
+
+impl RenamePlan {
+    /// Apply `pattern` to `paths`, numbering them in the given order.
+    ///
+    /// Each new name is checked with [`validate_filename`], against the other
+    /// new names, and against files already on disk that are not part of the
+    /// rename. Names are compared case-insensitively, as Windows does.
+    pub fn new(paths: &[PathBuf], pattern: &RenamePattern) -> Self {
+        let sources: HashSet<String> = paths.iter().map(|p| fold_case(p)).collect();
+        let mut targets = HashSet::new();
+
+        let items = paths
+            .iter()
+            .enumerate()
+            .map(|(index, from)| {
+                let name = from
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let new_name = pattern.apply(&name, from.is_dir(), index);
+                let to = from.with_file_name(&new_name);
+
+                let problem = if let Err(e) = validate_filename(&new_name) {
+                    Some(match e {
+                        ZError::InvalidPath { reason, .. } => reason,
+                        other => other.to_string(),
+                    })
+                } else if !targets.insert(fold_case(&to)) {
+                    Some(format!("Duplicate name '{new_name}'"))
+                } else if to.exists() && !sources.contains(&fold_case(&to)) {
+                    Some(format!("'{new_name}' already exists"))
+                } else {
+                    None
+                };
+
+                RenamePreview {
+                    from: from.clone(),
+                    to,
+                    problem,
+                }
+            })
+            .collect();
+
+        Self { items }
+    }
+
+    /// Whether every item can be renamed.
+    pub fn is_valid(&self) -> bool {
+        self.problems().next().is_none()
+    }
+
+    /// Items whose new name can't be used.
+    pub fn problems(&self) -> impl Iterator<Item = &RenamePreview> {
+        self.items.iter().filter(|item| item.problem.is_some())
+    }
+
+    /// Number of items that actually get a new name.
+    pub fn changed_count(&self) -> usize {
+        self.items.iter().filter(|item| !item.is_unchanged()).count()
+    }
+}
+
+fn fold_case(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// Carry out a [`RenamePlan`], returning the number of items renamed.
+///
+/// Every item is first moved to a temporary name next to it and then to its
+/// new name, so items may swap names or change only the case of their name.
+/// If any step fails, the items already renamed are put back and the error
+/// is returned.
+///
+/// # Errors
+/// * `ZError::InvalidOperation` - The plan has problems, or an item could not
+///   be restored after a failure
+/// * Any error from [`rename`] for the step that failed
+pub fn bulk_rename(plan: &RenamePlan) -> ZResult<usize> {
+    if let Some(item) = plan.problems().next() {
+        return Err(ZError::InvalidOperation {
+            operation: "bulk rename".to_string(),
+            reason: format!(
+                "{}: {}",
+                item.from.display(),
+                item.problem.as_deref().unwrap_or_default()
+            ),
+        });
+    }
+
+    let items: Vec<&RenamePreview> = plan.items.iter().filter(|i| !i.is_unchanged()).collect();
+    debug!(count = items.len(), "Bulk renaming");
+
+    let mut staged: Vec<(&RenamePreview, PathBuf)> = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let temp = temp_path(&item.from, index);
+        if let Err(e) = rename(&item.from, &temp) {
+            return Err(roll_back(&staged, 0, e));
+        }
+        staged.push((item, temp));
+    }
+
+    for (done, (item, temp)) in staged.iter().enumerate() {
+        if let Err(e) = rename(temp, &item.to) {
+            return Err(roll_back(&staged, done, e));
+        }
+    }
+
+    debug!(count = staged.len(), "Bulk rename successful");
+    Ok(staged.len())
+}
+
+/// A free name next to `path` to park it under during a bulk rename.
+fn temp_path(path: &Path, index: usize) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut attempt = 0;
+    loop {
+        let temp = path.with_file_name(format!(
+            ".{name}.zmrename-{}-{index}-{attempt}",
+            std::process::id()
+        ));
+        if !temp.exists() {
+            return temp;
+        }
+        attempt += 1;
+    }
+}
+
+/// Undo the first `finished` final renames and every staging rename, then
+/// turn `error` into the error to report.
+fn roll_back(staged: &[(&RenamePreview, PathBuf)], finished: usize, error: ZError) -> ZError {
+    warn!(error = %error, "Bulk rename failed, rolling back");
+
+    for (item, temp) in staged[..finished].iter().rev() {
+        if let Err(e) = rename(&item.to, temp) {
+            warn!(path = %item.to.display(), error = %e, "Could not undo rename");
+        }
+    }
+
+    let mut stranded = Vec::new();
+    for (item, temp) in staged.iter().rev() {
+        if let Err(e) = rename(temp, &item.from) {
+            warn!(path = %item.from.display(), error = %e, "Could not restore original name");
+            stranded.push(&item.from);
+        }
+    }
+
+    match stranded.first() {
+        None => error,
+        Some(first) => ZError::InvalidOperation {
+            operation: "bulk rename".to_string(),
+            reason: format!(
+                "{error}; {} item(s) could not be restored, e.g. '{}'",
+                stranded.len(),
+                first.display()
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn files(dir: &Path, names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                fs::write(&path, name).unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pattern_apply() {
+        let pattern = RenamePattern::parse("[N]_[C:3].[E]").unwrap();
+        assert_eq!(pattern.apply("photo.jpg", false, 0), "photo_001.jpg");
+        assert_eq!(pattern.apply("archive.tar.gz", false, 11), "archive.tar_012.gz");
+        assert_eq!(pattern.apply("Makefile", false, 1), "Makefile_002");
+        assert_eq!(pattern.apply(".gitignore", false, 2), ".gitignore_003");
+        assert_eq!(pattern.apply("v1.2", true, 3), "v1.2_004");
+
+        let pattern = RenamePattern::parse("[[draft] [c] [n]").unwrap().with_counter(10, 5);
+        assert_eq!(pattern.apply("notes.txt", false, 2), "[draft] 20 notes");
+    }
+
+    #[test]
+    fn test_pattern_rejects_bad_placeholders() {
+        assert!(RenamePattern::parse("[X]").is_err());
+        assert!(RenamePattern::parse("[C:0]").is_err());
+        assert!(RenamePattern::parse("[N").is_err());
+        assert!(RenamePattern::parse("").is_err());
+    }
+
+    #[test]
+    fn test_plan_flags_problems() {
+        let dir = TempDir::new().unwrap();
+        let paths = files(dir.path(), &["a.txt", "b.txt"]);
+        files(dir.path(), &["taken.txt"]);
+
+        let plan = RenamePlan::new(&paths, &RenamePattern::parse("same.[E]").unwrap());
+        assert_eq!(plan.items[0].problem, None);
+        assert_eq!(plan.items[1].problem.as_deref(), Some("Duplicate name 'same.txt'"));
+
+        let plan = RenamePlan::new(&paths[..1], &RenamePattern::parse("taken.[E]").unwrap());
+        assert_eq!(plan.items[0].problem.as_deref(), Some("'taken.txt' already exists"));
+
+        let plan = RenamePlan::new(&paths[..1], &RenamePattern::parse("a?.[E]").unwrap());
+        assert!(!plan.is_valid());
+        assert!(bulk_rename(&plan).is_err());
+        assert!(paths[0].exists());
+    }
+
+    #[test]
+    fn test_bulk_rename_swaps_names() {
+        let dir = TempDir::new().unwrap();
+        let paths = files(dir.path(), &["2.txt", "1.txt", "notes.md"]);
+
+        let plan = RenamePlan::new(&paths, &RenamePattern::parse("[C].[E]").unwrap());
+        assert!(plan.is_valid());
+        assert_eq!(plan.changed_count(), 3);
+        assert_eq!(bulk_rename(&plan).unwrap(), 3);
+
+        assert_eq!(fs::read_to_string(dir.path().join("1.txt")).unwrap(), "2.txt");
+        assert_eq!(fs::read_to_string(dir.path().join("2.txt")).unwrap(), "1.txt");
+        assert_eq!(fs::read_to_string(dir.path().join("3.md")).unwrap(), "notes.md");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_bulk_rename_rolls_back_on_failure() {
+        let dir = TempDir::new().unwrap();
+        let paths = files(dir.path(), &["a.txt", "b.txt"]);
+        let plan = RenamePlan::new(&paths, &RenamePattern::parse("new_[N].[E]").unwrap());
+        assert!(plan.is_valid());
+
+        // Someone takes the second name after the preview was made
+        fs::write(dir.path().join("new_b.txt"), "intruder").unwrap();
+
+        assert!(matches!(bulk_rename(&plan), Err(ZError::AlreadyExists { .. })));
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), "a.txt");
+        assert_eq!(fs::read_to_string(&paths[1]).unwrap(), "b.txt");
+        assert!(!dir.path().join("new_a.txt").exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+}
@@ -0,0 +1,467 @@
+//! Comparing two files by content.
+//!
+//! Files are compared byte by byte, finding the first difference and how
+//! many bytes differ, or line by line, which also finds the lines added and
+//! removed between them. Line comparisons hold both files in memory, so
+//! they are limited to [`MAX_LINE_COMPARE_SIZE`].
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fs::extended_path;
+use crate::viewer::{TextEncoding, SAMPLE_SIZE};
+use crate::{CancellationToken, Progress, ZError, ZResult};
+
+/// Read size while comparing.
+const READ_CHUNK: usize = 1024 * 1024;
+
+/// Largest file compared line by line.
+pub const MAX_LINE_COMPARE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Changed lines searched for the shortest diff before falling back to
+/// replacing the whole changed region.
+const MAX_DIFF_EDITS: usize = 1000;
+
+/// How two files are compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareMode {
+    /// Byte by byte, for any file
+    #[default]
+    Bytes,
+    /// Line by line, for text files
+    Lines,
+}
+
+impl CompareMode {
+    /// Display name of the mode.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bytes => "Byte-wise",
+            Self::Lines => "Line-wise",
+        }
+    }
+
+    /// The other mode.
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Bytes => Self::Lines,
+            Self::Lines => Self::Bytes,
+        }
+    }
+}
+
+impl std::fmt::Display for CompareMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One step from the left file's lines to the right file's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    /// A line in both files: its index on the left and on the right
+    Same(usize, usize),
+    /// A line only on the left
+    Removed(usize),
+    /// A line only on the right
+    Added(usize),
+}
+
+/// Lines added, removed and kept between two files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    /// Lines only in the right file.
+    pub added: usize,
+    /// Lines only in the left file.
+    pub removed: usize,
+    /// Lines in both files.
+    pub unchanged: usize,
+}
+
+/// Both files' lines and the steps from one to the other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextDiff {
+    /// Lines of the left file.
+    pub left: Vec<String>,
+    /// Lines of the right file.
+    pub right: Vec<String>,
+    /// The steps, in order through both files.
+    pub ops: Vec<DiffOp>,
+}
+
+impl TextDiff {
+    /// Diff the lines of two texts.
+    pub fn new(left: Vec<String>, right: Vec<String>) -> Self {
+        let ops = diff_lines(&left, &right);
+        Self { left, right, ops }
+    }
+
+    /// Count the lines added, removed and kept.
+    pub fn stats(&self) -> DiffStats {
+        let mut stats = DiffStats::default();
+        for op in &self.ops {
+            match op {
+                DiffOp::Same(..) => stats.unchanged += 1,
+                DiffOp::Removed(_) => stats.removed += 1,
+                DiffOp::Added(_) => stats.added += 1,
+            }
+        }
+        stats
+    }
+}
+
+/// Outcome of comparing two files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileComparison {
+    /// Size of the left file.
+    pub left_size: u64,
+    /// Size of the right file.
+    pub right_size: u64,
+    /// Offset of the first byte that differs, or None if the files are
+    /// identical. A file that is the start of the other differs where it ends.
+    pub first_difference: Option<u64>,
+    /// Bytes that differ within the length of the shorter file.
+    pub differing_bytes: u64,
+    /// Line differences, when compared line by line.
+    pub text: Option<TextDiff>,
+}
+
+impl FileComparison {
+    /// Check if the files have the same contents.
+    pub fn is_identical(&self) -> bool {
+        self.first_difference.is_none()
+    }
+}
+
+/// Compare two files, reporting bytes read to `on_progress`.
+///
+/// Progress is reported after every chunk read, so callers should throttle
+/// it.
+///
+/// # Errors
+/// * `ZError::InvalidOperation` - Comparing line by line, a file is binary
+///   or larger than [`MAX_LINE_COMPARE_SIZE`]
+/// * `ZError::Cancelled` - The comparison was cancelled
+/// * `ZError::Io` - A file could not be read
+pub fn compare_files_with_progress(
+    left: &Path,
+    right: &Path,
+    mode: CompareMode,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&Progress),
+) -> ZResult<FileComparison> {
+    let metadata = |path: &Path| {
+        std::fs::metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))
+    };
+    let (left_size, right_size) = (metadata(left)?.len(), metadata(right)?.len());
+    let mut progress = Progress::new(2, Some(left_size + right_size));
+    progress.current_item = Some(left.to_path_buf());
+    on_progress(&progress);
+
+    let (first_difference, differing_bytes, text) = match mode {
+        CompareMode::Bytes => {
+            let (first, differing) = compare_streams(left, right, cancel, |n| {
+                progress.bytes_done += n;
+                on_progress(&progress);
+            })?;
+            (first, differing, None)
+        }
+        CompareMode::Lines => {
+            let (left_bytes, left_lines) = read_text(left)?;
+            let (right_bytes, right_lines) = read_text(right)?;
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            let (first, differing) = compare_bytes(&left_bytes, &right_bytes);
+            (first, differing, Some(TextDiff::new(left_lines, right_lines)))
+        }
+    };
+
+    progress.bytes_done = left_size + right_size;
+    progress.items_done = 2;
+    progress.current_item = None;
+    on_progress(&progress);
+    Ok(FileComparison {
+        left_size,
+        right_size,
+        first_difference,
+        differing_bytes,
+        text,
+    })
+}
+
+/// Compare two files a chunk at a time, calling `on_read` with the bytes
+/// read from both. Returns what [`compare_bytes`] does for the whole files.
+fn compare_streams(
+    left: &Path,
+    right: &Path,
+    cancel: &CancellationToken,
+    mut on_read: impl FnMut(u64),
+) -> ZResult<(Option<u64>, u64)> {
+    let open = |path: &Path| File::open(extended_path(path)).map_err(|e| ZError::from_io(path, e));
+    let (mut left_file, mut right_file) = (open(left)?, open(right)?);
+    let mut left_buf = vec![0u8; READ_CHUNK];
+    let mut right_buf = vec![0u8; READ_CHUNK];
+    let mut offset = 0u64;
+    let mut first_difference = None;
+    let mut differing_bytes = 0;
+
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let left_len =
+            read_full(&mut left_file, &mut left_buf).map_err(|e| ZError::from_io(left, e))?;
+        let right_len =
+            read_full(&mut right_file, &mut right_buf).map_err(|e| ZError::from_io(right, e))?;
+        let (first, differing) = compare_bytes(&left_buf[..left_len], &right_buf[..right_len]);
+        if first_difference.is_none() {
+            first_difference = first.map(|first| offset + first);
+        }
+        differing_bytes += differing;
+        offset += left_len.min(right_len) as u64;
+        on_read((left_len + right_len) as u64);
+        // Whatever is left of the longer file is past the end of the other
+        if left_len < READ_CHUNK || right_len < READ_CHUNK {
+            break;
+        }
+    }
+    Ok((first_difference, differing_bytes))
+}
+
+/// Fill `buf` unless the file ends first, returning the bytes read.
+fn read_full(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// First offset where `left` and `right` differ, and the bytes that differ
+/// within the shorter of them.
+fn compare_bytes(left: &[u8], right: &[u8]) -> (Option<u64>, u64) {
+    let mut first = None;
+    let mut differing = 0;
+    for (i, (a, b)) in left.iter().zip(right).enumerate() {
+        if a != b {
+            first.get_or_insert(i as u64);
+            differing += 1;
+        }
+    }
+    if first.is_none() && left.len() != right.len() {
+        first = Some(left.len().min(right.len()) as u64);
+    }
+    (first, differing)
+}
+
+/// Read a text file for a line comparison: its bytes and its lines.
+fn read_text(path: &Path) -> ZResult<(Vec<u8>, Vec<String>)> {
+    let refuse = |reason: String| ZError::InvalidOperation {
+        operation: "compare lines".to_string(),
+        reason,
+    };
+    let meta = std::fs::metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    if meta.len() > MAX_LINE_COMPARE_SIZE {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        return Err(refuse(format!("{name} is too large to compare by line")));
+    }
+    let bytes = std::fs::read(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    let encoding = TextEncoding::detect(&bytes[..bytes.len().min(SAMPLE_SIZE)]);
+    let single_byte = matches!(encoding, TextEncoding::Utf8 | TextEncoding::Ansi);
+    if single_byte && bytes.contains(&0) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        return Err(refuse(format!("{name} is not a text file")));
+    }
+    let body = &bytes[encoding.bom_len(&bytes) as usize..];
+    let lines = encoding.decode_all(body).lines().map(str::to_string).collect();
+    Ok((bytes, lines))
+}
+
+/// The shortest steps from `left` to `right`, found with Myers' algorithm
+/// after setting aside the lines both start and end with.
+///
+/// Past [`MAX_DIFF_EDITS`] changed lines, the changed region is shown as
+/// removed from the left and added on the right instead.
+pub fn diff_lines(left: &[String], right: &[String]) -> Vec<DiffOp> {
+    let prefix = left.iter().zip(right).take_while(|(a, b)| a == b).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &left[prefix..left.len() - suffix];
+    let b = &right[prefix..right.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = (0..prefix).map(|i| DiffOp::Same(i, i)).collect();
+    match shortest_edit(a, b) {
+        Some(middle) => ops.extend(middle.into_iter().map(|op| match op {
+            DiffOp::Same(i, j) => DiffOp::Same(prefix + i, prefix + j),
+            DiffOp::Removed(i) => DiffOp::Removed(prefix + i),
+            DiffOp::Added(j) => DiffOp::Added(prefix + j),
+        })),
+        None => {
+            ops.extend((0..a.len()).map(|i| DiffOp::Removed(prefix + i)));
+            ops.extend((0..b.len()).map(|j| DiffOp::Added(prefix + j)));
+        }
+    }
+    let (left_end, right_end) = (left.len() - suffix, right.len() - suffix);
+    ops.extend((0..suffix).map(|i| DiffOp::Same(left_end + i, right_end + i)));
+    ops
+}
+
+/// Myers' shortest edit script from `a` to `b`, or None if it takes more
+/// than [`MAX_DIFF_EDITS`] steps.
+fn shortest_edit(a: &[String], b: &[String]) -> Option<Vec<DiffOp>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let limit = (a.len() + b.len()).min(MAX_DIFF_EDITS) as isize;
+    let offset = limit + 1;
+    // Furthest x reached on each diagonal k = x - y, at index k + offset
+    let mut v = vec![0isize; 2 * limit as usize + 3];
+    // The diagonals -d..=d of `v` before each round d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=limit {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+                v[i + 1]
+            } else {
+                v[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+/// Walk the rounds of [`shortest_edit`] back from the end to the start.
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<DiffOp> {
+    let (mut x, mut y) = (n, m);
+    let mut ops = Vec::new();
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let k = x - y;
+            let at = |k: isize| v[(k + d) as usize];
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+            let prev_x = at(prev_k);
+            (prev_x, prev_x - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(DiffOp::Same(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                y -= 1;
+                ops.push(DiffOp::Added(y as usize));
+            } else {
+                x -= 1;
+                ops.push(DiffOp::Removed(x as usize));
+            }
+        }
+    }
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn compare(left: &[u8], right: &[u8], mode: CompareMode) -> ZResult<FileComparison> {
+        let temp = TempDir::new().unwrap();
+        let (a, b) = (temp.path().join("a"), temp.path().join("b"));
+        std::fs::write(&a, left).unwrap();
+        std::fs::write(&b, right).unwrap();
+        compare_files_with_progress(&a, &b, mode, &CancellationToken::new(), |_| {})
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        use DiffOp::*;
+
+        let diff = TextDiff::new(lines("a\nb\nc\nd"), lines("a\nc\nx\nd\ne"));
+        assert_eq!(
+            diff.ops,
+            [Same(0, 0), Removed(1), Same(2, 1), Added(2), Same(3, 3), Added(4)]
+        );
+        let stats = diff.stats();
+        assert_eq!((stats.added, stats.removed, stats.unchanged), (2, 1, 3));
+
+        assert_eq!(diff_lines(&[], &lines("new")), [Added(0)]);
+        assert_eq!(diff_lines(&lines("same"), &lines("same")), [Same(0, 0)]);
+    }
+
+    #[test]
+    fn test_diff_lines_gives_up_on_large_changes() {
+        let left: Vec<String> = (0..MAX_DIFF_EDITS).map(|i| format!("left {i}")).collect();
+        let right: Vec<String> = (0..MAX_DIFF_EDITS).map(|i| format!("right {i}")).collect();
+        let ops = diff_lines(&left, &right);
+        assert_eq!(ops.len(), 2 * MAX_DIFF_EDITS);
+        assert_eq!(ops[0], DiffOp::Removed(0));
+        assert_eq!(ops[MAX_DIFF_EDITS], DiffOp::Added(0));
+    }
+
+    #[test]
+    fn test_compare_bytes() {
+        let same = compare(b"same bytes", b"same bytes", CompareMode::Bytes).unwrap();
+        assert!(same.is_identical());
+        assert_eq!(same.left_size, 10);
+
+        let differ = compare(b"abcdef", b"abXdeY", CompareMode::Bytes).unwrap();
+        assert_eq!(differ.first_difference, Some(2));
+        assert_eq!(differ.differing_bytes, 2);
+        assert_eq!(differ.text, None);
+
+        // One file is the start of the other
+        let longer = compare(b"abc", b"abcdef", CompareMode::Bytes).unwrap();
+        assert_eq!(longer.first_difference, Some(3));
+        assert_eq!((longer.differing_bytes, longer.right_size), (0, 6));
+
+        // Past the first chunk
+        let mut big = vec![7u8; READ_CHUNK + 10];
+        let copy = big.clone();
+        big[READ_CHUNK + 5] = 0;
+        let differ = compare(&big, &copy, CompareMode::Bytes).unwrap();
+        assert_eq!(differ.first_difference, Some(READ_CHUNK as u64 + 5));
+    }
+
+    #[test]
+    fn test_compare_lines() {
+        let result = compare(b"one\r\ntwo\r\n", b"one\r\n2\r\n", CompareMode::Lines).unwrap();
+        let text = result.text.unwrap();
+        assert_eq!(text.left, ["one", "two"]);
+        assert_eq!(text.stats().removed, 1);
+        assert_eq!(result.first_difference, Some(5));
+
+        let binary = compare(b"MZ\0\0", b"text", CompareMode::Lines);
+        assert!(matches!(binary, Err(ZError::InvalidOperation { .. })));
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! This module handles loading, saving, and validating the TOML configuration file.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
-use crate::{ZError, ZResult};
+use crate::{Collation, SortField, SortOrder, SortSpec, ZError, ZResult};
 
 /// The main configuration for ZManager.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -19,8 +21,16 @@ pub struct Config {
     pub appearance: AppearanceConfig,
     /// File operation settings.
     pub operations: OperationsConfig,
+    /// External terminal and editor commands.
+    pub tools: ToolsConfig,
+    /// Notifications when background jobs finish.
+    pub notifications: NotificationsConfig,
     /// Favorites/Quick Access entries.
     pub favorites: Vec<Favorite>,
+    /// Key chords by action name, e.g. `copy = ["C", "f5"]`. A listed action
+    /// gets exactly these chords instead of its built-in ones.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keybindings: BTreeMap<String, Vec<String>>,
     /// Session state (last directories, etc.).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session: Option<SessionState>,
@@ -105,6 +115,20 @@ impl Config {
         Ok(config_dir.join("ZManager").join("config.toml"))
     }
 
+    /// Get the directory holding templates for new files.
+    ///
+    /// On Windows: `%APPDATA%\ZManager\templates`
+    pub fn templates_dir() -> ZResult<PathBuf> {
+        Ok(Self::default_path()?.with_file_name("templates"))
+    }
+
+    /// Get the directory holding plugins that add custom commands.
+    ///
+    /// On Windows: `%APPDATA%\ZManager\plugins`
+    pub fn plugins_dir() -> ZResult<PathBuf> {
+        Ok(Self::default_path()?.with_file_name("plugins"))
+    }
+
     /// Validate the configuration.
     pub fn validate(&self) -> ZResult<()> {
         // Validate history limits
@@ -114,6 +138,33 @@ impl Config {
             });
         }
 
+        if self.general.open_selected_max == 0 {
+            return Err(ZError::Config {
+                message: "open_selected_max must be greater than 0".to_string(),
+            });
+        }
+        // A threshold above the limit could never ask for confirmation
+        if self.general.open_selected_confirm_threshold > self.general.open_selected_max {
+            return Err(ZError::Config {
+                message: "open_selected_confirm_threshold must not exceed open_selected_max"
+                    .to_string(),
+            });
+        }
+
+        // Validate operation limits; zero would stall every transfer
+        let operations = [
+            ("max_concurrent_jobs", self.operations.max_concurrent_jobs),
+            ("network_concurrent_jobs", self.operations.network_concurrent_jobs),
+            ("copy_buffer_size_kb", self.operations.copy_buffer_size_kb),
+        ];
+        for (name, value) in operations {
+            if value == 0 {
+                return Err(ZError::Config {
+                    message: format!("{name} must be greater than 0"),
+                });
+            }
+        }
+
         // Validate favorites
         for fav in &self.favorites {
             if fav.name.is_empty() {
@@ -249,8 +300,32 @@ pub struct GeneralConfig {
     pub default_sort_field: String,
     /// Default sort order (asc/desc).
     pub default_sort_ascending: bool,
+    /// When sorting by size, compute folder sizes in the background and sort
+    /// folders among files by them instead of listing folders first.
+    pub sort_dirs_by_computed_size: bool,
+    /// Whether to list directories before files.
+    pub sort_directories_first: bool,
+    /// Compare numbers in names by value (`file2` before `file10`).
+    pub natural_sort: bool,
+    /// How names compare: `ordinal` or `locale` (accents next to their
+    /// base letter).
+    pub sort_collation: Collation,
     /// Starting directory (empty = last used or home).
     pub start_directory: Option<PathBuf>,
+    /// Maximum number of files "open selected" will launch at once.
+    pub open_selected_max: usize,
+    /// Ask for confirmation when opening more than this many files.
+    pub open_selected_confirm_threshold: usize,
+    /// What "open selected" does with directories in the selection.
+    pub open_selected_directories: OpenDirectoryBehavior,
+    /// When entering a directory, skip through chains of directories that
+    /// contain only a single subdirectory (e.g. `pkg/pkg-1.0/`).
+    pub auto_descend_single_child: bool,
+    /// What opening an executable (`.exe`, `.bat`, ...) does.
+    pub executable_open: ExecutableOpenBehavior,
+    /// Save the last directories, sort and hidden-file setting on exit and
+    /// restore them on the next start.
+    pub restore_session: bool,
 }
 
 impl Default for GeneralConfig {
@@ -263,11 +338,45 @@ impl Default for GeneralConfig {
             max_history: 100,
             default_sort_field: "name".to_string(),
             default_sort_ascending: true,
+            sort_dirs_by_computed_size: false,
+            sort_directories_first: true,
+            natural_sort: false,
+            sort_collation: Collation::Ordinal,
             start_directory: None,
+            open_selected_max: 50,
+            open_selected_confirm_threshold: 5,
+            open_selected_directories: OpenDirectoryBehavior::default(),
+            auto_descend_single_child: false,
+            executable_open: ExecutableOpenBehavior::default(),
+            restore_session: true,
         }
     }
 }
 
+/// How directories are treated when opening a multi-selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenDirectoryBehavior {
+    /// Ignore directories and open only the files.
+    #[default]
+    Skip,
+    /// Navigate into the first selected directory after opening the files.
+    Navigate,
+}
+
+/// What opening an executable file does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutableOpenBehavior {
+    /// Run it like any other file.
+    Run,
+    /// Ask for confirmation, showing the full path, before running it.
+    #[default]
+    Prompt,
+    /// Do nothing; executables only run through the explicit run action.
+    Nothing,
+}
+
 /// Appearance and display settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -284,6 +393,8 @@ pub struct AppearanceConfig {
     pub human_readable_sizes: bool,
     /// Column widths (for TUI/GUI).
     pub column_widths: ColumnWidths,
+    /// Whether to show a row of drive letters above each pane.
+    pub show_drive_bar: bool,
 }
 
 impl Default for AppearanceConfig {
@@ -295,6 +406,7 @@ impl Default for AppearanceConfig {
             show_extensions: true,
             human_readable_sizes: true,
             column_widths: ColumnWidths::default(),
+            show_drive_bar: true,
         }
     }
 }
@@ -332,8 +444,77 @@ pub struct OperationsConfig {
     pub fast_move_same_volume: bool,
     /// Whether to preserve timestamps when copying.
     pub preserve_timestamps: bool,
-    /// Whether to follow symlinks when copying.
-    pub follow_symlinks: bool,
+    /// How copy, move and delete treat symlinks and junctions.
+    pub link_handling: LinkHandling,
+    /// Whether to ask before transfers that read from or write to a network drive.
+    pub confirm_network_transfers: bool,
+    /// Concurrency limit for transfers that involve a network drive.
+    pub network_concurrent_jobs: usize,
+    /// Whether to compare checksums of source and destination after each file copy.
+    pub verify_after_copy: bool,
+    /// Checksum used when verifying copies.
+    pub verify_algorithm: ChecksumAlgorithm,
+    /// Whether permanent deletes overwrite file contents with zeros before
+    /// removing them. Slow, and not a guarantee on SSDs or copy-on-write
+    /// storage, where old blocks may survive elsewhere.
+    pub wipe_permanent_deletes: bool,
+    /// Whether copies and moves check the destination has enough free space
+    /// before they start.
+    pub check_free_space: bool,
+    /// Whether huge files are copied without the system file cache, so a
+    /// multi-gigabyte copy doesn't push everything else out of it.
+    pub unbuffered_copy: bool,
+    /// Smallest file copied without the cache (in MB).
+    pub unbuffered_min_size_mb: u64,
+    /// Whether large files are copied so that a copy cut short, even by a
+    /// crash, picks up where it stopped. These copies keep the modification
+    /// time, but not the attributes, creation time, alternate data streams
+    /// or permissions.
+    pub resumable_copy: bool,
+    /// Smallest file copied resumably (in MB).
+    pub resumable_min_size_mb: u64,
+    /// When not empty, copies and moves only transfer files matching one of
+    /// these wildcard patterns, like `*.jpg`.
+    pub include_patterns: Vec<String>,
+    /// Whether copies and moves run in the background by default, rather
+    /// than behind a progress dialog until they finish.
+    pub background_transfers: bool,
+    /// Files and folders copies and moves leave out, as wildcard patterns
+    /// like `node_modules` or `*.tmp`. Patterns with a path separator match
+    /// the path below the copied folder, like `build/*.o`.
+    pub exclude_patterns: Vec<String>,
+}
+
+impl OperationsConfig {
+    /// Concurrency limit for a transfer, reduced when a network drive is involved.
+    ///
+    /// Both limits are at least 1 in a config that passed [`Config::validate`].
+    pub fn concurrency_for(&self, involves_network: bool) -> usize {
+        if involves_network {
+            self.max_concurrent_jobs.min(self.network_concurrent_jobs)
+        } else {
+            self.max_concurrent_jobs
+        }
+    }
+
+    /// Checksum to verify copies with, or None when verification is off.
+    pub fn verification(&self) -> Option<ChecksumAlgorithm> {
+        self.verify_after_copy.then_some(self.verify_algorithm)
+    }
+
+    /// Smallest file to copy without the system file cache, in bytes, or
+    /// None when unbuffered copies are off.
+    pub fn unbuffered_threshold(&self) -> Option<u64> {
+        self.unbuffered_copy
+            .then(|| self.unbuffered_min_size_mb.saturating_mul(1024 * 1024))
+    }
+
+    /// Smallest file to copy resumably, in bytes, or None when resumable
+    /// copies are off.
+    pub fn resumable_threshold(&self) -> Option<u64> {
+        self.resumable_copy
+            .then(|| self.resumable_min_size_mb.saturating_mul(1024 * 1024))
+    }
 }
 
 impl Default for OperationsConfig {
@@ -343,11 +524,106 @@ impl Default for OperationsConfig {
             copy_buffer_size_kb: 64, // 64 KB buffer
             fast_move_same_volume: true,
             preserve_timestamps: true,
-            follow_symlinks: false,
+            link_handling: LinkHandling::default(),
+            confirm_network_transfers: false,
+            network_concurrent_jobs: 1,
+            verify_after_copy: false,
+            verify_algorithm: ChecksumAlgorithm::default(),
+            wipe_permanent_deletes: false,
+            check_free_space: true,
+            unbuffered_copy: false,
+            unbuffered_min_size_mb: 1024,
+            resumable_copy: false,
+            resumable_min_size_mb: 256,
+            include_patterns: Vec::new(),
+            background_transfers: true,
+            exclude_patterns: Vec::new(),
         }
     }
 }
 
+/// External tools launched from the file panes.
+///
+/// Each is a command line run in the current directory. `{path}` stands for
+/// the item at the cursor (or the current directory when there is none) and
+/// `{dir}` for the current directory; quote words with double quotes, but
+/// not the placeholders, which always become a single argument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// Terminal, e.g. `cmd.exe`, `powershell.exe -NoLogo` or `wt.exe -d {dir}`.
+    pub terminal: String,
+    /// Editor for files, e.g. `notepad.exe {path}` or `code.cmd {path}`.
+    pub editor: String,
+    /// Largest text file, in KiB, edited in the built-in editor rather than
+    /// `editor`; 0 always uses `editor`.
+    pub quick_edit_max_kb: u64,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            terminal: "powershell.exe -NoLogo".to_string(),
+            editor: "notepad.exe {path}".to_string(),
+            quick_edit_max_kb: 256,
+        }
+    }
+}
+
+/// Notifications when a background job finishes while ZManager isn't
+/// focused: a toast in the GUI, the terminal bell in the TUI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Whether to notify at all.
+    pub enabled: bool,
+    /// Shortest running time, in seconds, of a job worth notifying about.
+    pub min_duration_secs: u64,
+    /// Whether the TUI rings the terminal bell.
+    pub bell: bool,
+}
+
+impl NotificationsConfig {
+    /// Check if a job that ran for `running_time` is worth a notification.
+    pub fn should_notify(&self, running_time: Duration) -> bool {
+        self.enabled && running_time >= Duration::from_secs(self.min_duration_secs)
+    }
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_duration_secs: 10,
+            bell: true,
+        }
+    }
+}
+
+/// Checksum algorithm for verifying copied files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumAlgorithm {
+    /// 64-bit xxHash3: fast, catches corruption but not tampering.
+    #[default]
+    XxHash,
+    /// SHA-256: slower, cryptographically strong.
+    Sha256,
+}
+
+/// How file operations treat symlinks and junctions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkHandling {
+    /// Operate on the link itself: transfers recreate the link at the
+    /// destination, delete removes only the link.
+    #[default]
+    CopyLink,
+    /// Operate on what the link points to: transfers copy the target's
+    /// contents, delete removes the target along with the link.
+    Follow,
+}
+
 /// A favorite/quick access entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Favorite {
@@ -405,6 +681,7 @@ impl Favorite {
 
 /// Session state that can be saved/restored between runs.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SessionState {
     /// Last active directory for the left pane.
     pub last_left_dir: Option<PathBuf>,
@@ -416,6 +693,14 @@ pub struct SessionState {
     /// Last used sort settings.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_sort: Option<SortSettings>,
+    /// Whether hidden files were shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_hidden: Option<bool>,
+    /// Whether the right pane was the active one.
+    pub right_pane_active: bool,
+    /// Paths typed into the location bar, most recent first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path_history: Vec<String>,
 }
 
 /// Window state for GUI.
@@ -433,6 +718,59 @@ pub struct WindowState {
 pub struct SortSettings {
     pub field: String,
     pub ascending: bool,
+    /// Whether files were grouped by type.
+    #[serde(default)]
+    pub group_by_type: bool,
+    /// Whether directories were listed first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directories_first: Option<bool>,
+    /// Whether numbers in names compared by value.
+    #[serde(default)]
+    pub natural: bool,
+    /// How names compared.
+    #[serde(default)]
+    pub collation: Collation,
+    /// Secondary sort field, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub then_by: Option<String>,
+}
+
+impl SortSettings {
+    /// Capture the field, order and options of a sort specification.
+    pub fn from_spec(spec: &SortSpec) -> Self {
+        Self {
+            field: spec.field.key().to_string(),
+            ascending: spec.order == SortOrder::Ascending,
+            group_by_type: spec.group_by_type,
+            directories_first: Some(spec.directories_first),
+            natural: spec.natural,
+            collation: spec.collation,
+            then_by: spec.then_by.map(|field| field.key().to_string()),
+        }
+    }
+
+    /// Apply the stored field, order and options to `spec`.
+    ///
+    /// An unknown field name leaves the field unchanged.
+    pub fn apply_to(&self, spec: &mut SortSpec) {
+        if let Some(field) = SortField::from_key(&self.field) {
+            spec.field = field;
+        } else {
+            warn!(field = %self.field, "Unknown sort field in session state");
+        }
+        spec.order = if self.ascending {
+            SortOrder::Ascending
+        } else {
+            SortOrder::Descending
+        };
+        spec.group_by_type = self.group_by_type;
+        if let Some(directories_first) = self.directories_first {
+            spec.directories_first = directories_first;
+        }
+        spec.natural = self.natural;
+        spec.collation = self.collation;
+        spec.then_by = self.then_by.as_deref().and_then(SortField::from_key);
+    }
 }
 
 #[cfg(test)]
@@ -525,6 +863,220 @@ mod tests {
 
         config.general.max_history = 0;
         assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.general.open_selected_max = 0;
+        assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.general.open_selected_confirm_threshold = config.general.open_selected_max;
+        assert!(config.validate().is_ok());
+        config.general.open_selected_confirm_threshold += 1;
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("open_selected_confirm_threshold"));
+
+        let mut config = Config::default();
+        config.operations.network_concurrent_jobs = 0;
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("network_concurrent_jobs"));
+    }
+
+    #[test]
+    fn test_network_concurrency() {
+        let mut ops = OperationsConfig::default();
+        assert!(!ops.confirm_network_transfers);
+        assert_eq!(ops.concurrency_for(false), 2);
+        assert_eq!(ops.concurrency_for(true), 1);
+
+        // Never raises the limit above the general one
+        ops.network_concurrent_jobs = 8;
+        assert_eq!(ops.concurrency_for(true), 2);
+        assert_eq!(ops.concurrency_for(false), 2);
+        ops.max_concurrent_jobs = 4;
+        ops.network_concurrent_jobs = 3;
+        assert_eq!(ops.concurrency_for(true), 3);
+        assert_eq!(ops.concurrency_for(false), 4);
+    }
+
+    #[test]
+    fn test_verify_settings() {
+        let mut config = Config::default();
+        assert_eq!(config.operations.verification(), None);
+
+        config.operations.verify_after_copy = true;
+        config.operations.verify_algorithm = ChecksumAlgorithm::Sha256;
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("verify_algorithm = \"sha256\""));
+
+        let loaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(loaded.operations.verification(), Some(ChecksumAlgorithm::Sha256));
+    }
+
+    #[test]
+    fn test_unbuffered_copy_settings() {
+        let mut ops = OperationsConfig::default();
+        assert_eq!(ops.unbuffered_threshold(), None);
+
+        ops.unbuffered_copy = true;
+        assert_eq!(ops.unbuffered_threshold(), Some(1024 * 1024 * 1024));
+
+        // Older config files without the settings still load
+        let loaded: OperationsConfig = toml::from_str("verify_after_copy = true").unwrap();
+        assert!(!loaded.unbuffered_copy);
+        assert_eq!(loaded.unbuffered_min_size_mb, 1024);
+    }
+
+    #[test]
+    fn test_resumable_copy_settings() {
+        let mut ops = OperationsConfig::default();
+        assert_eq!(ops.resumable_threshold(), None);
+
+        ops.resumable_copy = true;
+        ops.resumable_min_size_mb = 512;
+        assert_eq!(ops.resumable_threshold(), Some(512 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_link_handling_serde() {
+        let mut config = Config::default();
+        assert_eq!(config.operations.link_handling, LinkHandling::CopyLink);
+
+        config.operations.link_handling = LinkHandling::Follow;
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("link_handling = \"follow\""));
+
+        let loaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(loaded.operations.link_handling, LinkHandling::Follow);
+    }
+
+    #[test]
+    fn test_keybindings_serde() {
+        let config = Config::default();
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(!toml_str.contains("[keybindings]"));
+
+        let loaded: Config = toml::from_str(
+            r#"
+            [keybindings]
+            copy = ["C", "f5"]
+            quit = []
+            "#,
+        )
+        .unwrap();
+        assert_eq!(loaded.keybindings["copy"], vec!["C", "f5"]);
+        assert!(loaded.keybindings["quit"].is_empty());
+
+        let toml_str = toml::to_string_pretty(&loaded).unwrap();
+        let reloaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(reloaded.keybindings, loaded.keybindings);
+    }
+
+    #[test]
+    fn test_tools_serde() {
+        let loaded: Config = toml::from_str(
+            r#"
+            [tools]
+            terminal = "wt.exe -d {dir}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(loaded.tools.terminal, "wt.exe -d {dir}");
+        assert_eq!(loaded.tools.editor, ToolsConfig::default().editor);
+
+        let toml_str = toml::to_string_pretty(&loaded).unwrap();
+        let reloaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(reloaded.tools, loaded.tools);
+    }
+
+    #[test]
+    fn test_notifications_serde() {
+        let loaded: Config = toml::from_str(
+            r#"
+            [notifications]
+            min_duration_secs = 30
+            "#,
+        )
+        .unwrap();
+        let notifications = &loaded.notifications;
+        assert!(notifications.enabled && notifications.bell);
+        assert!(!notifications.should_notify(Duration::from_secs(29)));
+        assert!(notifications.should_notify(Duration::from_secs(30)));
+
+        let toml_str = toml::to_string_pretty(&loaded).unwrap();
+        let mut reloaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(reloaded.notifications, loaded.notifications);
+
+        reloaded.notifications.enabled = false;
+        assert!(!reloaded.notifications.should_notify(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_open_selected_directories_serde() {
+        let mut config = Config::default();
+        assert_eq!(config.general.open_selected_directories, OpenDirectoryBehavior::Skip);
+
+        config.general.open_selected_directories = OpenDirectoryBehavior::Navigate;
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("open_selected_directories = \"navigate\""));
+
+        let loaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(loaded.general.open_selected_directories, OpenDirectoryBehavior::Navigate);
+    }
+
+    #[test]
+    fn test_executable_open_defaults_to_prompt() {
+        let mut config = Config::default();
+        assert_eq!(config.general.executable_open, ExecutableOpenBehavior::Prompt);
+
+        config.general.executable_open = ExecutableOpenBehavior::Nothing;
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("executable_open = \"nothing\""));
+
+        let loaded: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(loaded.general.executable_open, ExecutableOpenBehavior::Nothing);
+    }
+
+    #[test]
+    fn test_session_state_round_trip() {
+        let mut config = Config::default();
+        assert!(config.general.restore_session);
+
+        let sort = SortSpec {
+            field: SortField::Modified,
+            order: SortOrder::Descending,
+            group_by_type: true,
+            natural: true,
+            collation: Collation::Locale,
+            then_by: Some(SortField::Name),
+            directories_first: false,
+            ..SortSpec::default()
+        };
+        config.session = Some(SessionState {
+            last_left_dir: Some(PathBuf::from("C:\\Projects")),
+            last_sort: Some(SortSettings::from_spec(&sort)),
+            show_hidden: Some(true),
+            right_pane_active: true,
+            path_history: vec!["%USERPROFILE%\\Downloads".to_string()],
+            ..SessionState::default()
+        });
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let loaded: Config = toml::from_str(&toml_str).unwrap();
+        let session = loaded.session.unwrap();
+        assert_eq!(session.last_left_dir, Some(PathBuf::from("C:\\Projects")));
+        assert_eq!(session.last_right_dir, None);
+        assert_eq!(session.show_hidden, Some(true));
+        assert!(session.right_pane_active);
+        assert_eq!(session.path_history, ["%USERPROFILE%\\Downloads"]);
+
+        let mut restored = SortSpec::default();
+        session.last_sort.unwrap().apply_to(&mut restored);
+        assert_eq!(restored, sort);
+
+        // Sessions written before these fields existed still load
+        let old: SessionState = toml::from_str("last_left_dir = 'C:\\'").unwrap();
+        assert_eq!(old.show_hidden, None);
+        assert!(!old.right_pane_active);
     }
 
     #[test]
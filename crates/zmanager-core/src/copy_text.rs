@@ -0,0 +1,180 @@
+//! Text describing items for the clipboard.
+//!
+//! Frontends copy the selected items as text in several forms: their full
+//! paths or names one per line, quoted arguments to paste into a command
+//! line, their UNC paths on mapped network drives, or the contents of a
+//! single small text file. Lines end in CRLF, as Windows clipboard text does.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::drives::unc_path;
+use crate::fs::extended_path;
+use crate::text_file::TextFile;
+use crate::{ZError, ZResult};
+
+/// Largest file whose contents can be copied as text.
+pub const MAX_COPY_CONTENTS_SIZE: u64 = 1024 * 1024;
+
+/// Line break between items, and in copied file contents.
+const LINE_BREAK: &str = "\r\n";
+
+/// Form in which items are copied as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyFormat {
+    /// Full paths, one per line.
+    Paths,
+    /// File names, one per line.
+    Names,
+    /// Full paths in double quotes, separated by spaces.
+    QuotedArgs,
+    /// UNC paths of items on mapped network drives, one per line.
+    Unc,
+    /// The contents of a single small text file.
+    Contents,
+}
+
+impl CopyFormat {
+    /// All formats, in menu order.
+    pub const ALL: [Self; 5] = [
+        Self::Paths,
+        Self::Names,
+        Self::QuotedArgs,
+        Self::Unc,
+        Self::Contents,
+    ];
+
+    /// Human-readable name for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Paths => "Full path",
+            Self::Names => "Name",
+            Self::QuotedArgs => "Quoted command-line arguments",
+            Self::Unc => "UNC path",
+            Self::Contents => "File contents",
+        }
+    }
+}
+
+/// Build the text copying `paths` in `format`.
+///
+/// # Errors
+/// * `ZError::InvalidOperation` - No paths were given, an item isn't on a
+///   network drive (UNC), or the contents asked for aren't those of exactly
+///   one text file of at most [`MAX_COPY_CONTENTS_SIZE`] bytes
+/// * `ZError::Io` - The file could not be read
+///
+/// # Example
+/// ```
+/// use std::path::PathBuf;
+/// use zmanager_core::{copy_text, CopyFormat};
+/// let paths = [PathBuf::from("My Files"), PathBuf::from("notes.txt")];
+/// let text = copy_text(&paths, CopyFormat::QuotedArgs).unwrap();
+/// assert_eq!(text, r#""My Files" "notes.txt""#);
+/// ```
+pub fn copy_text(paths: &[PathBuf], format: CopyFormat) -> ZResult<String> {
+    if paths.is_empty() {
+        return Err(refuse("Nothing to copy"));
+    }
+    let lines: Vec<String> = match format {
+        CopyFormat::Paths => paths.iter().map(|p| p.display().to_string()).collect(),
+        CopyFormat::Names => paths
+            .iter()
+            .map(|path| match path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                // Drive roots have no name of their own
+                None => path.display().to_string(),
+            })
+            .collect(),
+        CopyFormat::QuotedArgs => {
+            let quoted: Vec<String> = paths
+                .iter()
+                .map(|p| format!("\"{}\"", p.display()))
+                .collect();
+            return Ok(quoted.join(" "));
+        }
+        CopyFormat::Unc => paths
+            .iter()
+            .map(|path| {
+                unc_path(path)
+                    .map(|unc| unc.display().to_string())
+                    .ok_or_else(|| refuse(format!("{} is not on a network drive", path.display())))
+            })
+            .collect::<ZResult<_>>()?,
+        CopyFormat::Contents => match paths {
+            [path] => return file_contents(path),
+            _ => return Err(refuse("Select a single file to copy its contents")),
+        },
+    };
+    Ok(lines.join(LINE_BREAK))
+}
+
+/// Read a small text file's contents, with CRLF line endings.
+fn file_contents(path: &Path) -> ZResult<String> {
+    let meta = std::fs::metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    if meta.is_dir() {
+        return Err(refuse("Only a file's contents can be copied"));
+    }
+    if meta.len() > MAX_COPY_CONTENTS_SIZE {
+        return Err(refuse(format!("File is larger than {MAX_COPY_CONTENTS_SIZE} bytes")));
+    }
+    let bytes = std::fs::read(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    let file = TextFile::decode(&bytes).ok_or_else(|| refuse("Not a plain text file"))?;
+    Ok(file.lines.join(LINE_BREAK))
+}
+
+/// Error refusing to copy items as text.
+fn refuse(reason: impl Into<String>) -> ZError {
+    ZError::InvalidOperation {
+        operation: "copy as text".to_string(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copies_paths_names_and_arguments() {
+        let docs = PathBuf::from("docs");
+        let paths = [docs.join("a.txt"), docs.join("b c.txt")];
+        let copy = |format| copy_text(&paths, format).unwrap();
+
+        assert_eq!(
+            copy(CopyFormat::Paths),
+            format!("{}\r\n{}", paths[0].display(), paths[1].display())
+        );
+        assert_eq!(copy(CopyFormat::Names), "a.txt\r\nb c.txt");
+        assert_eq!(
+            copy(CopyFormat::QuotedArgs),
+            format!("\"{}\" \"{}\"", paths[0].display(), paths[1].display())
+        );
+        assert_eq!(
+            copy_text(&[PathBuf::from("/")], CopyFormat::Names).unwrap(),
+            "/"
+        );
+        assert!(copy_text(&[], CopyFormat::Paths).is_err());
+    }
+
+    #[test]
+    fn test_copies_contents_of_one_small_text_file() {
+        let temp = TempDir::new().unwrap();
+        let notes = temp.path().join("notes.txt");
+        std::fs::write(&notes, "first\nsecond\r\n").unwrap();
+        let binary = temp.path().join("image.bin");
+        std::fs::write(&binary, b"MZ\0\0\x90").unwrap();
+        let contents = |paths: &[PathBuf]| copy_text(paths, CopyFormat::Contents);
+
+        assert_eq!(
+            contents(std::slice::from_ref(&notes)).unwrap(),
+            "first\r\nsecond\r\n"
+        );
+        assert!(contents(&[binary]).is_err());
+        assert!(contents(&[temp.path().to_path_buf()]).is_err());
+        assert!(contents(&[notes.clone(), notes]).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Drive change detection: USB sticks, card readers and network drives
+//! coming and going.
+//!
+//! The set of drive roots is polled, which is cheap (a single
+//! `GetLogicalDrives` call on Windows), and the full drive list is only read
+//! again when that set changes. Polling needs no window to receive
+//! `WM_DEVICECHANGE` on, so it works the same for every front end.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use crate::drives::{drive_roots, list_drives, DriveInfo};
+
+/// Default time between checks for added or removed drives (2s).
+pub const DEFAULT_DRIVE_POLL_MS: u64 = 2000;
+
+/// Drives were added or removed.
+#[derive(Debug, Clone)]
+pub struct DriveChange {
+    /// Roots of the drives that appeared.
+    pub added: Vec<PathBuf>,
+    /// Roots of the drives that went away.
+    pub removed: Vec<PathBuf>,
+    /// All drives after the change.
+    pub drives: Vec<DriveInfo>,
+}
+
+/// Watches for drives being added or removed.
+pub struct DriveWatcher {
+    interval: Duration,
+    event_tx: broadcast::Sender<DriveChange>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl DriveWatcher {
+    /// Create a drive watcher checking every [`DEFAULT_DRIVE_POLL_MS`].
+    pub fn new() -> Self {
+        Self::with_interval(Duration::from_millis(DEFAULT_DRIVE_POLL_MS))
+    }
+
+    /// Create a drive watcher checking every `interval`.
+    pub fn with_interval(interval: Duration) -> Self {
+        let (event_tx, _) = broadcast::channel(16);
+        Self {
+            interval,
+            event_tx,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Subscribe to drive changes.
+    pub fn subscribe(&self) -> broadcast::Receiver<DriveChange> {
+        self.event_tx.subscribe()
+    }
+
+    /// Start checking for changes in a background task.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn start(&mut self) {
+        self.shutdown.store(false, Ordering::SeqCst);
+        let event_tx = self.event_tx.clone();
+        let shutdown = self.shutdown.clone();
+        let interval = self.interval;
+
+        tokio::spawn(async move {
+            let mut roots = drive_roots();
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                if shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let current = drive_roots();
+                let (added, removed) = diff_roots(&roots, &current);
+                if added.is_empty() && removed.is_empty() {
+                    continue;
+                }
+                roots = current;
+                debug!(?added, ?removed, "Drives changed");
+
+                // Volume queries can block on slow or disconnected drives
+                let drives = match tokio::task::spawn_blocking(list_drives).await {
+                    Ok(Ok(drives)) => drives,
+                    Ok(Err(e)) => {
+                        warn!("Failed to list drives: {}", e);
+                        continue;
+                    }
+                    Err(_) => continue,
+                };
+                let _ = event_tx.send(DriveChange {
+                    added,
+                    removed,
+                    drives,
+                });
+            }
+            info!("Drive watcher stopped");
+        });
+
+        info!("Drive watcher started");
+    }
+
+    /// Stop checking for changes.
+    pub fn stop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Default for DriveWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DriveWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Roots in `new` but not `old`, and roots in `old` but not `new`.
+fn diff_roots(old: &[PathBuf], new: &[PathBuf]) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let added = new.iter().filter(|r| !old.contains(r)).cloned().collect();
+    let removed = old.iter().filter(|r| !new.contains(r)).cloned().collect();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_roots() {
+        let old = [PathBuf::from("C:\\"), PathBuf::from("E:\\")];
+        let new = [PathBuf::from("C:\\"), PathBuf::from("F:\\")];
+
+        let (added, removed) = diff_roots(&old, &new);
+        assert_eq!(added, [PathBuf::from("F:\\")]);
+        assert_eq!(removed, [PathBuf::from("E:\\")]);
+        assert_eq!(diff_roots(&new, &new), (vec![], vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_start_and_stop() {
+        let mut watcher = DriveWatcher::with_interval(Duration::from_millis(10));
+        let mut rx = watcher.subscribe();
+        watcher.start();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        watcher.stop();
+
+        // The drives of the test machine don't change while it runs
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -3,12 +3,14 @@
 //! This module provides functionality to list available drives
 //! with their labels, types, and free space.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::ZResult;
+#[cfg(not(windows))]
+use crate::ZError;
 
 /// Type of drive/volume.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,6 +29,8 @@ pub enum DriveType {
     CdRom,
     /// RAM disk.
     RamDisk,
+    /// Phone, camera or other device browsed over MTP.
+    Portable,
 }
 
 impl DriveType {
@@ -40,6 +44,7 @@ impl DriveType {
             Self::Network => "Network Drive",
             Self::CdRom => "CD/DVD Drive",
             Self::RamDisk => "RAM Disk",
+            Self::Portable => "Portable Device",
         }
     }
 
@@ -53,6 +58,38 @@ impl DriveType {
             Self::Network => "network",
             Self::CdRom => "disc",
             Self::RamDisk => "memory",
+            Self::Portable => "phone",
+        }
+    }
+}
+
+/// BitLocker protection of a volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitLockerStatus {
+    /// Not encrypted.
+    Off,
+    /// Encrypted and unlocked.
+    On,
+    /// Encrypted and locked: unreadable until unlocked.
+    Locked,
+    /// Being encrypted.
+    Encrypting,
+    /// Being decrypted.
+    Decrypting,
+    /// Encrypted, with protection suspended.
+    Suspended,
+}
+
+impl BitLockerStatus {
+    /// Get a human-readable description.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::On => "On",
+            Self::Locked => "Locked",
+            Self::Encrypting => "Encrypting",
+            Self::Decrypting => "Decrypting",
+            Self::Suspended => "Suspended",
         }
     }
 }
@@ -74,11 +111,29 @@ pub struct DriveInfo {
     pub free_bytes: Option<u64>,
     /// Whether the drive is ready/accessible.
     pub is_ready: bool,
+    /// Volume serial number.
+    #[serde(default)]
+    pub volume_serial: Option<u32>,
+    /// Every path the volume is mounted at: its drive letter and any
+    /// folders it is mounted in.
+    #[serde(default)]
+    pub mount_points: Vec<PathBuf>,
+    /// BitLocker protection, or None if the volume can't be encrypted.
+    #[serde(default)]
+    pub bitlocker: Option<BitLockerStatus>,
+    /// Allocation unit (cluster) size in bytes.
+    #[serde(default)]
+    pub cluster_size: Option<u32>,
 }
 
 impl DriveInfo {
     /// Get the display name for the drive.
     pub fn display_name(&self) -> String {
+        // Devices have no letter, and their virtual paths mean little
+        if self.drive_type == DriveType::Portable {
+            return self.label.clone();
+        }
+
         let letter = self
             .path
             .to_str()
@@ -118,12 +173,31 @@ impl DriveInfo {
             .unwrap_or_else(|| "N/A".to_string())
     }
 
+    /// Format used space as human-readable string.
+    pub fn used_space_display(&self) -> String {
+        self.used_bytes()
+            .map(format_bytes)
+            .unwrap_or_else(|| "N/A".to_string())
+    }
+
     /// Format total space as human-readable string.
     pub fn total_space_display(&self) -> String {
         self.total_bytes
             .map(format_bytes)
             .unwrap_or_else(|| "N/A".to_string())
     }
+
+    /// Format the volume serial number the way `dir` shows it, e.g.
+    /// "1A2B-3C4D".
+    pub fn serial_display(&self) -> Option<String> {
+        self.volume_serial
+            .map(|serial| format!("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF))
+    }
+
+    /// Format the cluster size as human-readable string.
+    pub fn cluster_size_display(&self) -> Option<String> {
+        self.cluster_size.map(|size| format_bytes(u64::from(size)))
+    }
 }
 
 /// Format bytes as human-readable string.
@@ -212,6 +286,30 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
                 continue;
             }
 
+            // A disconnected network drive can hang the volume queries below
+            // for a long time, so ask the network provider first
+            let connection = if drive_type == DriveType::Network {
+                wnet::connection(&format!("{}:", letter))
+            } else {
+                None
+            };
+            if let Some((remote, false)) = &connection {
+                drives.push(DriveInfo {
+                    path: PathBuf::from(&root),
+                    label: remote.clone(),
+                    drive_type,
+                    file_system: None,
+                    total_bytes: None,
+                    free_bytes: None,
+                    is_ready: false,
+                    volume_serial: None,
+                    mount_points: vec![PathBuf::from(&root)],
+                    bitlocker: None,
+                    cluster_size: None,
+                });
+                continue;
+            }
+
             // Get volume info
             let mut label_buf = [0u16; 256];
             let mut fs_buf = [0u16; 256];
@@ -232,7 +330,7 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
                 )
             };
 
-            let (label, file_system, is_ready) = if volume_result != 0 {
+            let (label, file_system, volume_serial, is_ready) = if volume_result != 0 {
                 let label_end = label_buf.iter().position(|&c| c == 0).unwrap_or(0);
                 let fs_end = fs_buf.iter().position(|&c| c == 0).unwrap_or(0);
 
@@ -243,9 +341,15 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
                     .to_string_lossy()
                     .to_string();
 
-                (label, Some(file_system), true)
+                // Unlabeled shares are better known by their remote path
+                let label = match connection {
+                    Some((remote, _)) if label.is_empty() => remote,
+                    _ => label,
+                };
+
+                (label, Some(file_system), Some(serial), true)
             } else {
-                (String::new(), None, false)
+                (String::new(), None, None, false)
             };
 
             // Get disk space
@@ -272,6 +376,25 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
                 (None, None)
             };
 
+            // A locked BitLocker volume isn't ready, but still says so
+            let bitlocker = match drive_type {
+                DriveType::Fixed | DriveType::Removable => volume::bitlocker_status(&root_wide),
+                _ => None,
+            };
+            let mut mount_points = if is_ready {
+                volume::mount_points(&root_wide)
+            } else {
+                Vec::new()
+            };
+            if mount_points.is_empty() {
+                mount_points.push(PathBuf::from(&root));
+            }
+            let cluster_size = if is_ready {
+                volume::cluster_size(&root_wide)
+            } else {
+                None
+            };
+
             drives.push(DriveInfo {
                 path: PathBuf::from(&root),
                 label,
@@ -280,6 +403,10 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
                 total_bytes,
                 free_bytes,
                 is_ready,
+                volume_serial,
+                mount_points,
+                bitlocker,
+                cluster_size,
             });
         }
     }
@@ -295,10 +422,7 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
 
     debug!("Enumerating mount points (non-Windows)");
 
-    // On non-Windows, we'll list common mount points
-    let mount_points = ["/", "/home", "/tmp", "/mnt", "/media"];
-
-    let drives = mount_points
+    let drives = MOUNT_POINTS
         .iter()
         .filter(|p| fs::metadata(p).is_ok())
         .map(|p| DriveInfo {
@@ -309,14 +433,749 @@ pub fn list_drives() -> ZResult<Vec<DriveInfo>> {
             total_bytes: None,
             free_bytes: None,
             is_ready: true,
+            volume_serial: None,
+            mount_points: vec![PathBuf::from(p)],
+            bitlocker: None,
+            cluster_size: None,
         })
         .collect();
 
     Ok(drives)
 }
 
+/// Common mount points listed as drives on non-Windows systems.
+#[cfg(not(windows))]
+const MOUNT_POINTS: [&str; 5] = ["/", "/home", "/tmp", "/mnt", "/media"];
+
+/// Roots of the drives currently present, without querying the volumes.
+///
+/// Much cheaper than [`list_drives`], for noticing drives coming and going.
+#[cfg(windows)]
+pub(crate) fn drive_roots() -> Vec<PathBuf> {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetLogicalDrives() -> u32;
+    }
+
+    let bitmask = unsafe { GetLogicalDrives() };
+    (0..26u8)
+        .filter(|i| bitmask & (1 << i) != 0)
+        .map(|i| PathBuf::from(format!("{}:\\", (b'A' + i) as char)))
+        .collect()
+}
+
+/// Roots of the drives currently present (non-Windows fallback).
+#[cfg(not(windows))]
+pub(crate) fn drive_roots() -> Vec<PathBuf> {
+    MOUNT_POINTS
+        .iter()
+        .filter(|p| std::fs::metadata(p).is_ok())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Normalize a path for drive-prefix comparison.
+///
+/// Strips the `\\?\` long-path prefix (turning `\\?\UNC\` back into a plain
+/// UNC path), unifies separators and lowercases, since drive letters and
+/// share names are case-insensitive.
+fn normalize_for_drive_match(path: &Path) -> String {
+    let raw = path.to_string_lossy().replace('/', "\\");
+    let raw = if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc)
+    } else if let Some(local) = raw.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        raw
+    };
+    raw.to_lowercase()
+}
+
+/// Classify the drive a path lives on, using an already-enumerated drive list.
+///
+/// UNC paths (`\\server\share\...`) are reported as [`DriveType::Network`]
+/// without consulting `drives`. Returns None if no drive contains the path.
+pub fn drive_type_for_path(path: impl AsRef<Path>, drives: &[DriveInfo]) -> Option<DriveType> {
+    let path = path.as_ref();
+    if is_unc_path(path) {
+        return Some(DriveType::Network);
+    }
+    drive_for_path(path, drives).map(|drive| drive.drive_type)
+}
+
+/// Find the drive a path lives on in an already-enumerated drive list: the
+/// one with the longest root containing the path.
+pub fn drive_for_path(path: impl AsRef<Path>, drives: &[DriveInfo]) -> Option<&DriveInfo> {
+    let normalized = normalize_for_drive_match(path.as_ref());
+    drives
+        .iter()
+        .map(|d| (normalize_for_drive_match(&d.path), d))
+        .filter(|(root, _)| normalized.starts_with(root.as_str()))
+        .max_by_key(|(root, _)| root.len())
+        .map(|(_, drive)| drive)
+}
+
+/// Check whether a path is on a network share or mapped network drive.
+pub fn is_network_path(path: impl AsRef<Path>, drives: &[DriveInfo]) -> bool {
+    drive_type_for_path(path, drives) == Some(DriveType::Network)
+}
+
+/// Check whether a path is a UNC path (`\\server\share\...`), including the
+/// `\\?\UNC\` long form.
+pub fn is_unc_path(path: impl AsRef<Path>) -> bool {
+    normalize_for_drive_match(path.as_ref()).starts_with(r"\\")
+}
+
+/// Get the server named by a bare server path such as `\\fileserver`.
+///
+/// Such a path has no share and can't be read as a directory; it is listed
+/// as the server's shares instead (see [`list_server_shares`]). Returns None
+/// for anything else, including paths into a share.
+pub fn unc_server(path: impl AsRef<Path>) -> Option<String> {
+    let raw = path.as_ref().to_string_lossy().replace('/', "\\");
+    let server = raw.strip_prefix(r"\\")?.trim_end_matches('\\');
+    let is_device = server == "?" || server == ".";
+    (!server.is_empty() && !is_device && !server.contains('\\')).then(|| server.to_string())
+}
+
+/// Get the UNC form of a path (`Z:\docs` on a drive mapped to
+/// `\\server\share` becomes `\\server\share\docs`).
+///
+/// UNC paths come back without any `\\?\UNC\` prefix. Returns None for
+/// paths on drives that aren't mapped network drives.
+pub fn unc_path(path: impl AsRef<Path>) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let connection = |drive: &str| wnet::connection(drive).map(|(remote, _)| remote);
+    #[cfg(not(windows))]
+    let connection = |_: &str| None;
+    unc_path_with(path.as_ref(), connection)
+}
+
+/// [`unc_path`] with the remote path of a mapped drive ("Z:") looked up by
+/// `connection`.
+fn unc_path_with(path: &Path, connection: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let raw = path.to_string_lossy().replace('/', "\\");
+    let raw = if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc)
+    } else if let Some(local) = raw.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        raw
+    };
+    if raw.starts_with(r"\\") {
+        return Some(PathBuf::from(raw));
+    }
+    let drive = raw.get(..2).filter(|d| d.ends_with(':'))?;
+    let remote = connection(&drive.to_uppercase())?;
+    Some(PathBuf::from(format!("{}{}", remote.trim_end_matches('\\'), &raw[2..])))
+}
+
+/// A shared folder on a network server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkShare {
+    /// UNC path of the share (e.g., `\\server\public`).
+    pub path: PathBuf,
+    /// Share name (e.g., "public").
+    pub name: String,
+    /// Comment set on the share by the server.
+    pub comment: String,
+    /// Drive the share is mapped to (e.g., "Z:"), if any.
+    pub local_name: Option<String>,
+}
+
+impl NetworkShare {
+    /// Build a share from its UNC path, taking the name from the last
+    /// component.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn from_unc(path: String, comment: String, local_name: Option<String>) -> Self {
+        let name = path
+            .trim_end_matches('\\')
+            .rsplit('\\')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Self {
+            path: PathBuf::from(path),
+            name,
+            comment,
+            local_name,
+        }
+    }
+}
+
+/// List the network shares mapped to drive letters.
+#[cfg(windows)]
+pub fn list_mapped_drives() -> ZResult<Vec<NetworkShare>> {
+    debug!("Enumerating mapped drives");
+    let shares = wnet::enumerate(wnet::RESOURCE_CONNECTED, None)?;
+    Ok(shares.into_iter().filter(|s| s.local_name.is_some()).collect())
+}
+
+/// List the network shares mapped to drive letters (non-Windows fallback).
+///
+/// There are no mapped drives outside Windows, so this is always empty.
+#[cfg(not(windows))]
+pub fn list_mapped_drives() -> ZResult<Vec<NetworkShare>> {
+    Ok(Vec::new())
+}
+
+/// List the disk shares a server offers.
+///
+/// `server` is a name or a bare server path (`fileserver` or
+/// `\\fileserver`). This asks the server over the network and can take a
+/// long time if it is slow or unreachable, so keep it off the UI thread.
+#[cfg(windows)]
+pub fn list_server_shares(server: &str) -> ZResult<Vec<NetworkShare>> {
+    let server = format!(r"\\{}", server.trim_start_matches('\\').trim_end_matches('\\'));
+    debug!(server = %server, "Enumerating server shares");
+    wnet::enumerate(wnet::RESOURCE_GLOBALNET, Some(&server))
+}
+
+/// List the disk shares a server offers (non-Windows fallback).
+#[cfg(not(windows))]
+pub fn list_server_shares(server: &str) -> ZResult<Vec<NetworkShare>> {
+    Err(ZError::InvalidOperation {
+        operation: format!("list shares of {}", server),
+        reason: "Network shares can only be listed on Windows".to_string(),
+    })
+}
+
+/// Network resource enumeration through the Windows networking API (mpr).
+#[cfg(windows)]
+mod wnet {
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use super::NetworkShare;
+    use crate::{ZError, ZResult};
+
+    #[repr(C)]
+    #[allow(non_snake_case, clippy::upper_case_acronyms)]
+    struct NETRESOURCEW {
+        dwScope: u32,
+        dwType: u32,
+        dwDisplayType: u32,
+        dwUsage: u32,
+        lpLocalName: *mut u16,
+        lpRemoteName: *mut u16,
+        lpComment: *mut u16,
+        lpProvider: *mut u16,
+    }
+
+    #[link(name = "mpr")]
+    unsafe extern "system" {
+        fn WNetOpenEnumW(
+            dwScope: u32,
+            dwType: u32,
+            dwUsage: u32,
+            lpNetResource: *const NETRESOURCEW,
+            lphEnum: *mut *mut c_void,
+        ) -> u32;
+        fn WNetEnumResourceW(
+            hEnum: *mut c_void,
+            lpcCount: *mut u32,
+            lpBuffer: *mut c_void,
+            lpBufferSize: *mut u32,
+        ) -> u32;
+        fn WNetCloseEnum(hEnum: *mut c_void) -> u32;
+        fn WNetGetConnectionW(
+            lpLocalName: *const u16,
+            lpRemoteName: *mut u16,
+            lpnLength: *mut u32,
+        ) -> u32;
+    }
+
+    pub const RESOURCE_CONNECTED: u32 = 1;
+    pub const RESOURCE_GLOBALNET: u32 = 2;
+    const RESOURCETYPE_DISK: u32 = 1;
+    const RESOURCEUSAGE_CONTAINER: u32 = 2;
+    const NO_ERROR: u32 = 0;
+    const ERROR_MORE_DATA: u32 = 234;
+    const ERROR_NO_MORE_ITEMS: u32 = 259;
+    const ERROR_CONNECTION_UNAVAIL: u32 = 1201;
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Read a NUL-terminated wide string; null reads as empty.
+    unsafe fn from_wide(p: *const u16) -> String {
+        if p.is_null() {
+            return String::new();
+        }
+        let len = (0..).take_while(|&i| unsafe { *p.add(i) } != 0).count();
+        String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(p, len) })
+    }
+
+    fn error(code: u32, what: &str) -> ZError {
+        ZError::Windows {
+            code,
+            message: format!(
+                "Failed to {}: {}",
+                what,
+                std::io::Error::from_raw_os_error(code as i32)
+            ),
+        }
+    }
+
+    /// Enumerate the disk resources in `scope`, inside the `container`
+    /// (e.g., a `\\server`) if given.
+    pub fn enumerate(scope: u32, container: Option<&str>) -> ZResult<Vec<NetworkShare>> {
+        let mut remote = container.map(to_wide);
+        let container = remote.as_mut().map(|remote| NETRESOURCEW {
+            dwScope: scope,
+            dwType: RESOURCETYPE_DISK,
+            dwDisplayType: 0,
+            dwUsage: RESOURCEUSAGE_CONTAINER,
+            lpLocalName: ptr::null_mut(),
+            lpRemoteName: remote.as_mut_ptr(),
+            lpComment: ptr::null_mut(),
+            lpProvider: ptr::null_mut(),
+        });
+        let container_ptr = container.as_ref().map_or(ptr::null(), |c| c as *const _);
+
+        let mut handle = ptr::null_mut();
+        let status =
+            unsafe { WNetOpenEnumW(scope, RESOURCETYPE_DISK, 0, container_ptr, &mut handle) };
+        if status != NO_ERROR {
+            return Err(error(status, "enumerate network resources"));
+        }
+
+        // u64 elements keep the buffer aligned for NETRESOURCEW
+        let mut buffer = vec![0u64; 2048];
+        let mut shares = Vec::new();
+        let result = loop {
+            let mut count = u32::MAX;
+            let mut size = (buffer.len() * 8) as u32;
+            let status = unsafe {
+                WNetEnumResourceW(handle, &mut count, buffer.as_mut_ptr().cast(), &mut size)
+            };
+            match status {
+                NO_ERROR => {
+                    let resources = unsafe {
+                        std::slice::from_raw_parts(
+                            buffer.as_ptr().cast::<NETRESOURCEW>(),
+                            count as usize,
+                        )
+                    };
+                    for resource in resources.iter().filter(|r| r.dwType == RESOURCETYPE_DISK) {
+                        let (remote, comment, local) = unsafe {
+                            (
+                                from_wide(resource.lpRemoteName),
+                                from_wide(resource.lpComment),
+                                from_wide(resource.lpLocalName),
+                            )
+                        };
+                        let local = (!local.is_empty()).then_some(local);
+                        shares.push(NetworkShare::from_unc(remote, comment, local));
+                    }
+                }
+                ERROR_NO_MORE_ITEMS => break Ok(shares),
+                ERROR_MORE_DATA => buffer.resize(size as usize / 8 + 1, 0),
+                code => break Err(error(code, "enumerate network resources")),
+            }
+        };
+
+        unsafe { WNetCloseEnum(handle) };
+        result
+    }
+
+    /// Remote path of a mapped drive (e.g., "Z:"), and whether the
+    /// connection is currently available.
+    pub fn connection(drive: &str) -> Option<(String, bool)> {
+        let local = to_wide(drive);
+        let mut remote = [0u16; 1024];
+        let mut len = remote.len() as u32;
+        let status = unsafe { WNetGetConnectionW(local.as_ptr(), remote.as_mut_ptr(), &mut len) };
+        match status {
+            NO_ERROR | ERROR_CONNECTION_UNAVAIL => {
+                let remote = unsafe { from_wide(remote.as_ptr()) };
+                Some((remote, status == NO_ERROR))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Volume details beyond `GetVolumeInformationW`: mount points, cluster
+/// size and BitLocker protection.
+#[cfg(windows)]
+mod volume {
+    use std::ffi::c_void;
+    use std::path::PathBuf;
+    use std::ptr;
+
+    use super::BitLockerStatus;
+
+    #[repr(C)]
+    #[allow(clippy::upper_case_acronyms)]
+    struct GUID {
+        data1: u32,
+        data2: u16,
+        data3: u16,
+        data4: [u8; 8],
+    }
+
+    #[repr(C)]
+    #[allow(clippy::upper_case_acronyms)]
+    struct PROPERTYKEY {
+        fmtid: GUID,
+        pid: u32,
+    }
+
+    /// Large enough for a PROPVARIANT on any target; only integers are read.
+    #[repr(C)]
+    #[allow(clippy::upper_case_acronyms)]
+    struct PROPVARIANT {
+        vt: u16,
+        reserved: [u16; 3],
+        value: [u64; 2],
+    }
+
+    /// The start of the `IPropertyStore` vtable, up to `GetValue`.
+    #[repr(C)]
+    struct IPropertyStoreVtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(this: *mut c_void) -> u32,
+        get_count: usize,
+        get_at: usize,
+        get_value: unsafe extern "system" fn(
+            this: *mut c_void,
+            key: *const PROPERTYKEY,
+            value: *mut PROPVARIANT,
+        ) -> i32,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetVolumeNameForVolumeMountPointW(
+            lpszVolumeMountPoint: *const u16,
+            lpszVolumeName: *mut u16,
+            cchBufferLength: u32,
+        ) -> i32;
+        fn GetVolumePathNamesForVolumeNameW(
+            lpszVolumeName: *const u16,
+            lpszVolumePathNames: *mut u16,
+            cchBufferLength: u32,
+            lpcchReturnLength: *mut u32,
+        ) -> i32;
+        fn GetDiskFreeSpaceW(
+            lpRootPathName: *const u16,
+            lpSectorsPerCluster: *mut u32,
+            lpBytesPerSector: *mut u32,
+            lpNumberOfFreeClusters: *mut u32,
+            lpTotalNumberOfClusters: *mut u32,
+        ) -> i32;
+    }
+
+    #[link(name = "ole32")]
+    unsafe extern "system" {
+        fn CoInitializeEx(pvReserved: *mut c_void, dwCoInit: u32) -> i32;
+        fn PropVariantClear(pvar: *mut PROPVARIANT) -> i32;
+    }
+
+    #[link(name = "shell32")]
+    unsafe extern "system" {
+        fn SHGetPropertyStoreFromParsingName(
+            pszPath: *const u16,
+            pbc: *mut c_void,
+            flags: i32,
+            riid: *const GUID,
+            ppv: *mut *mut c_void,
+        ) -> i32;
+    }
+
+    const COINIT_APARTMENTTHREADED: u32 = 0x2;
+    const GPS_DEFAULT: i32 = 0;
+    const VT_I4: u16 = 3;
+    const VT_UI4: u16 = 19;
+
+    const IID_IPROPERTYSTORE: GUID = GUID {
+        data1: 0x886d_8eeb,
+        data2: 0x8cf2,
+        data3: 0x4446,
+        data4: [0x8d, 0x02, 0xcd, 0xba, 0x1d, 0xbd, 0xcf, 0x99],
+    };
+
+    /// `System.Volume.BitLockerProtection`, which Explorer's drive icons
+    /// are drawn from.
+    const PKEY_VOLUME_BITLOCKER_PROTECTION: PROPERTYKEY = PROPERTYKEY {
+        fmtid: GUID {
+            data1: 0x6d74_8de2,
+            data2: 0x8d38,
+            data3: 0x4cc3,
+            data4: [0xac, 0x60, 0xf0, 0x09, 0xb0, 0x57, 0xc5, 0x57],
+        },
+        pid: 4,
+    };
+
+    /// All the paths the volume at `root` is mounted at, drive letter first.
+    pub fn mount_points(root: &[u16]) -> Vec<PathBuf> {
+        let mut volume = [0u16; 64];
+        let len = volume.len() as u32;
+        let found =
+            unsafe { GetVolumeNameForVolumeMountPointW(root.as_ptr(), volume.as_mut_ptr(), len) };
+        if found == 0 {
+            return Vec::new();
+        }
+
+        let mut names = vec![0u16; 256];
+        loop {
+            let mut needed = 0u32;
+            let ok = unsafe {
+                GetVolumePathNamesForVolumeNameW(
+                    volume.as_ptr(),
+                    names.as_mut_ptr(),
+                    names.len() as u32,
+                    &mut needed,
+                )
+            };
+            if ok != 0 {
+                break;
+            }
+            // Too small: `needed` says how big it has to be
+            if needed as usize <= names.len() {
+                return Vec::new();
+            }
+            names.resize(needed as usize, 0);
+        }
+
+        // A list of NUL-terminated paths, ended by an empty one
+        names
+            .split(|&c| c == 0)
+            .take_while(|name| !name.is_empty())
+            .map(|name| PathBuf::from(String::from_utf16_lossy(name)))
+            .collect()
+    }
+
+    /// The cluster size of the volume at `root`, in bytes.
+    pub fn cluster_size(root: &[u16]) -> Option<u32> {
+        let mut sectors_per_cluster = 0u32;
+        let mut bytes_per_sector = 0u32;
+        let mut free_clusters = 0u32;
+        let mut total_clusters = 0u32;
+        let ok = unsafe {
+            GetDiskFreeSpaceW(
+                root.as_ptr(),
+                &mut sectors_per_cluster,
+                &mut bytes_per_sector,
+                &mut free_clusters,
+                &mut total_clusters,
+            )
+        };
+        (ok != 0).then(|| sectors_per_cluster.saturating_mul(bytes_per_sector))
+    }
+
+    /// The BitLocker protection of the volume at `root`, as the shell
+    /// reports it. None if it can't be encrypted or can't be asked.
+    pub fn bitlocker_status(root: &[u16]) -> Option<BitLockerStatus> {
+        let mut store: *mut c_void = ptr::null_mut();
+        let mut value = PROPVARIANT {
+            vt: 0,
+            reserved: [0; 3],
+            value: [0; 2],
+        };
+        unsafe {
+            // Already initialized, maybe in another mode, is fine too
+            CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+            let hr = SHGetPropertyStoreFromParsingName(
+                root.as_ptr(),
+                ptr::null_mut(),
+                GPS_DEFAULT,
+                &IID_IPROPERTYSTORE,
+                &mut store,
+            );
+            if hr < 0 || store.is_null() {
+                return None;
+            }
+            let vtbl = *(store as *const *const IPropertyStoreVtbl);
+            let hr = ((*vtbl).get_value)(store, &PKEY_VOLUME_BITLOCKER_PROTECTION, &mut value);
+            ((*vtbl).release)(store);
+            if hr < 0 {
+                return None;
+            }
+        }
+
+        let protection = match value.vt {
+            VT_I4 | VT_UI4 => Some(value.value[0] as u32),
+            _ => None,
+        };
+        unsafe { PropVariantClear(&mut value) };
+        match protection? {
+            1 => Some(BitLockerStatus::On),
+            2 => Some(BitLockerStatus::Off),
+            3 => Some(BitLockerStatus::Encrypting),
+            4 => Some(BitLockerStatus::Decrypting),
+            5 => Some(BitLockerStatus::Suspended),
+            6 => Some(BitLockerStatus::Locked),
+            _ => None,
+        }
+    }
+}
+
+/// Check if two paths are on the same volume (for move optimization).
+#[cfg(windows)]
+pub fn same_volume(path1: &Path, path2: &Path) -> bool {
+    // Get the root of each path
+    let root1 = get_volume_root(path1);
+    let root2 = get_volume_root(path2);
+
+    match (root1, root2) {
+        (Some(r1), Some(r2)) => r1.eq_ignore_ascii_case(&r2),
+        _ => false,
+    }
+}
+
+#[cfg(windows)]
+fn get_volume_root(path: &Path) -> Option<String> {
+    let path_str = path.to_string_lossy();
+
+    // Handle UNC paths: \\server\share
+    if path_str.starts_with("\\\\") {
+        let parts: Vec<&str> = path_str.trim_start_matches("\\\\").splitn(3, '\\').collect();
+        if parts.len() >= 2 {
+            return Some(format!("\\\\{}\\{}", parts[0], parts[1]));
+        }
+    }
+
+    // Handle drive letters: C:\
+    if path_str.len() >= 2 {
+        let chars: Vec<char> = path_str.chars().take(2).collect();
+        if chars[1] == ':' {
+            return Some(format!("{}:", chars[0].to_ascii_uppercase()));
+        }
+    }
+
+    None
+}
+
+#[cfg(not(windows))]
+pub fn same_volume(path1: &Path, path2: &Path) -> bool {
+    // On non-Windows, compare mount points using stat
+    use std::os::unix::fs::MetadataExt;
+
+    let meta1 = std::fs::metadata(path1).ok();
+    let meta2 = std::fs::metadata(path2).ok();
+
+    match (meta1, meta2) {
+        (Some(m1), Some(m2)) => m1.dev() == m2.dev(),
+        _ => false,
+    }
+}
+
+/// Check if the filesystem holding `path` can store NTFS alternate data
+/// streams.
+///
+/// Returns None if the filesystem can't be queried.
+#[cfg(windows)]
+pub fn supports_alternate_streams(path: impl AsRef<Path>) -> Option<bool> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetVolumePathNameW(
+            lpszFileName: *const u16,
+            lpszVolumePathName: *mut u16,
+            cchBufferLength: u32,
+        ) -> i32;
+        fn GetVolumeInformationW(
+            lpRootPathName: *const u16,
+            lpVolumeNameBuffer: *mut u16,
+            nVolumeNameSize: u32,
+            lpVolumeSerialNumber: *mut u32,
+            lpMaximumComponentLength: *mut u32,
+            lpFileSystemFlags: *mut u32,
+            lpFileSystemNameBuffer: *mut u16,
+            nFileSystemNameSize: u32,
+        ) -> i32;
+    }
+
+    const FILE_NAMED_STREAMS: u32 = 0x0004_0000;
+
+    let path_wide: Vec<u16> = path
+        .as_ref()
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut root = [0u16; 1024];
+    if unsafe { GetVolumePathNameW(path_wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0
+    {
+        return None;
+    }
+
+    let mut fs_flags = 0u32;
+    let result = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut fs_flags,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (result != 0).then_some(fs_flags & FILE_NAMED_STREAMS != 0)
+}
+
+/// Check if the filesystem holding `path` can store NTFS alternate data
+/// streams (non-Windows fallback).
+///
+/// There's no way to tell, so this always returns None.
+#[cfg(not(windows))]
+pub fn supports_alternate_streams(_path: impl AsRef<Path>) -> Option<bool> {
+    None
+}
+
+/// Free space available to the caller on the volume holding `path`.
+///
+/// `path` need not exist yet; its nearest existing ancestor is queried.
+/// Returns None if the volume can't be queried.
+#[cfg(windows)]
+pub fn available_space(path: impl AsRef<Path>) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetDiskFreeSpaceExW(
+            lpDirectoryName: *const u16,
+            lpFreeBytesAvailableToCaller: *mut u64,
+            lpTotalNumberOfBytes: *mut u64,
+            lpTotalNumberOfFreeBytes: *mut u64,
+        ) -> i32;
+    }
+
+    let existing = path.as_ref().ancestors().find(|p| p.is_dir())?;
+    let path_wide: Vec<u16> = existing
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut free_caller = 0u64;
+    let result = unsafe {
+        GetDiskFreeSpaceExW(
+            path_wide.as_ptr(),
+            &mut free_caller,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (result != 0).then_some(free_caller)
+}
+
+/// Free space available on the volume holding `path` (non-Windows fallback).
+///
+/// Not queried, so this always returns None.
+#[cfg(not(windows))]
+pub fn available_space(_path: impl AsRef<Path>) -> Option<u64> {
+    None
+}
+
 /// Get information about a specific drive.
-pub fn get_drive_info(path: impl AsRef<std::path::Path>) -> ZResult<Option<DriveInfo>> {
+pub fn get_drive_info(path: impl AsRef<Path>) -> ZResult<Option<DriveInfo>> {
     let drives = list_drives()?;
     let path = path.as_ref();
 
@@ -355,6 +1214,10 @@ mod tests {
             total_bytes: Some(500 * 1024 * 1024 * 1024),
             free_bytes: Some(100 * 1024 * 1024 * 1024),
             is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
         };
 
         assert_eq!(drive.display_name(), "Windows (C:)");
@@ -367,9 +1230,22 @@ mod tests {
             total_bytes: None,
             free_bytes: None,
             is_ready: false,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
         };
 
         assert_eq!(unlabeled.display_name(), "Local Disk (D:)");
+
+        let phone = DriveInfo {
+            path: PathBuf::from("mtp://Pixel 7"),
+            label: "Pixel 7".to_string(),
+            drive_type: DriveType::Portable,
+            ..unlabeled
+        };
+
+        assert_eq!(phone.display_name(), "Pixel 7");
     }
 
     #[test]
@@ -382,12 +1258,128 @@ mod tests {
             total_bytes: Some(1000),
             free_bytes: Some(400),
             is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
         };
 
         assert_eq!(drive.used_bytes(), Some(600));
         assert!((drive.usage_percent().unwrap() - 0.6).abs() < 0.001);
     }
 
+    #[test]
+    fn test_drive_info_volume_details() {
+        let drive = DriveInfo {
+            volume_serial: Some(0x1A2B_3C4D),
+            cluster_size: Some(4096),
+            bitlocker: Some(BitLockerStatus::Locked),
+            ..drive("C:\\", DriveType::Fixed)
+        };
+
+        assert_eq!(drive.serial_display().as_deref(), Some("1A2B-3C4D"));
+        assert_eq!(drive.cluster_size_display().as_deref(), Some("4.00 KB"));
+        assert_eq!(drive.bitlocker.unwrap().description(), "Locked");
+
+        // Drive lists saved before volume details were kept still load
+        let json = r#"{"path":"D:\\","label":"Data","drive_type":"Fixed","file_system":null,
+            "total_bytes":null,"free_bytes":null,"is_ready":true}"#;
+        let loaded: DriveInfo = serde_json::from_str(json).unwrap();
+        assert!(loaded.mount_points.is_empty());
+        assert_eq!(loaded.serial_display(), None);
+    }
+
+    fn drive(path: &str, drive_type: DriveType) -> DriveInfo {
+        DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        }
+    }
+
+    #[test]
+    fn test_network_path_detection() {
+        let drives = [drive("C:\\", DriveType::Fixed), drive("Z:\\", DriveType::Network)];
+
+        assert!(is_network_path(r"Z:\Shared\report.docx", &drives));
+        assert!(is_network_path(r"z:\shared", &drives));
+        assert!(is_network_path(r"\\?\Z:\Shared", &drives));
+        assert!(is_network_path(r"\\server\share\file.txt", &drives));
+        assert!(is_network_path(r"\\?\UNC\server\share", &drives));
+        assert!(!is_network_path(r"C:\Users\me", &drives));
+        assert!(!is_network_path(r"Q:\unknown", &drives));
+        assert_eq!(drive_type_for_path(r"C:\Windows", &drives), Some(DriveType::Fixed));
+    }
+
+    #[test]
+    fn test_unc_path_of_mapped_drive() {
+        let connection = |drive: &str| (drive == "Z:").then(|| r"\\nas\public".to_string());
+        let unc = |path: &str| unc_path_with(Path::new(path), connection);
+
+        assert_eq!(unc(r"z:\Shared\a.txt"), Some(PathBuf::from(r"\\nas\public\Shared\a.txt")));
+        assert_eq!(unc(r"Z:\"), Some(PathBuf::from(r"\\nas\public\")));
+        assert_eq!(unc(r"\\?\UNC\srv\docs\b.txt"), Some(PathBuf::from(r"\\srv\docs\b.txt")));
+        assert_eq!(unc(r"C:\Users\me"), None);
+        assert_eq!(unc("relative"), None);
+    }
+
+    #[test]
+    fn test_drive_for_path_prefers_longest_root() {
+        let drives = [
+            drive("C:\\", DriveType::Fixed),
+            drive("C:\\Mount\\", DriveType::Removable),
+        ];
+
+        let found = |path| drive_for_path(path, &drives).map(|d| d.drive_type);
+        assert_eq!(found(r"c:\mount\photos"), Some(DriveType::Removable));
+        assert_eq!(found(r"C:\Users"), Some(DriveType::Fixed));
+        assert_eq!(found(r"D:\"), None);
+    }
+
+    #[test]
+    fn test_unc_paths() {
+        assert!(is_unc_path(r"\\server\share\dir"));
+        assert!(is_unc_path(r"\\?\UNC\server\share"));
+        assert!(!is_unc_path(r"\\?\C:\Windows"));
+        assert!(!is_unc_path(r"C:\Users"));
+
+        assert_eq!(unc_server(r"\\fileserver").as_deref(), Some("fileserver"));
+        assert_eq!(unc_server(r"\\fileserver\").as_deref(), Some("fileserver"));
+        assert_eq!(unc_server("//nas").as_deref(), Some("nas"));
+        assert_eq!(unc_server(r"\\fileserver\public"), None);
+        assert_eq!(unc_server(r"\\?\C:\"), None);
+        assert_eq!(unc_server(r"\\."), None);
+        assert_eq!(unc_server(r"C:\"), None);
+    }
+
+    #[test]
+    fn test_network_share_from_unc() {
+        let share = NetworkShare::from_unc(
+            r"\\server\public".to_string(),
+            "Team files".to_string(),
+            Some("Z:".to_string()),
+        );
+
+        assert_eq!(share.name, "public");
+        assert_eq!(share.path, PathBuf::from(r"\\server\public"));
+        assert_eq!(share.local_name.as_deref(), Some("Z:"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_network_shares_unsupported() {
+        assert!(list_mapped_drives().unwrap().is_empty());
+        assert!(list_server_shares("server").is_err());
+    }
+
     #[test]
     #[cfg(windows)]
     fn test_list_drives_windows() {
@@ -0,0 +1,342 @@
+//! Content-based duplicate detection.
+//!
+//! A [`DuplicateIndex`] records every file under a root directory by size and
+//! lazily hashes the candidates that share a size with a file being checked.
+//! Hashes are cached on the index, so checking several files (or checking
+//! again later) against the same tree only reads each candidate once. A hash
+//! match is always confirmed by a byte-for-byte comparison before a file is
+//! reported as a duplicate.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use crate::{CancellationToken, EntryMeta, ZError, ZResult};
+
+/// Buffer size used when reading file contents.
+const READ_CHUNK: usize = 64 * 1024;
+
+/// A file that is byte-identical to a file in the indexed tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateMatch {
+    /// The checked file.
+    pub path: PathBuf,
+    /// The identical file found in the indexed tree.
+    pub original: PathBuf,
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+/// Result of checking a set of files against a [`DuplicateIndex`].
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateReport {
+    /// Files that have an identical copy in the indexed tree.
+    pub matches: Vec<DuplicateMatch>,
+    /// Combined size of the matched files, i.e. what deleting them frees.
+    pub reclaimable_bytes: u64,
+}
+
+impl DuplicateReport {
+    /// Number of duplicates found.
+    pub fn count(&self) -> usize {
+        self.matches.len()
+    }
+}
+
+/// Index of the files below a directory, used to find identical files.
+#[derive(Debug, Clone)]
+pub struct DuplicateIndex {
+    root: PathBuf,
+    by_size: HashMap<u64, Vec<PathBuf>>,
+    hashes: HashMap<PathBuf, u64>,
+}
+
+impl DuplicateIndex {
+    /// Walk `root` recursively and record every non-empty file by size.
+    ///
+    /// Symlinks and junctions are not followed. Unreadable subdirectories are
+    /// skipped; only an unreadable `root` is an error.
+    pub fn build(root: impl AsRef<Path>, cancel: &CancellationToken) -> ZResult<Self> {
+        let root = root.as_ref();
+        debug!(root = %root.display(), "Building duplicate index");
+
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        let mut pending = vec![root.to_path_buf()];
+        let mut is_root = true;
+
+        while let Some(dir) = pending.pop() {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if is_root => return Err(ZError::from_io(&dir, e)),
+                Err(_) => continue,
+            };
+            is_root = false;
+
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    pending.push(entry.path());
+                } else if file_type.is_file() {
+                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    // Empty files are trivially identical and free nothing
+                    if size > 0 {
+                        by_size.entry(size).or_default().push(entry.path());
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            by_size,
+            hashes: HashMap::new(),
+        })
+    }
+
+    /// The directory this index was built from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Number of indexed files.
+    pub fn file_count(&self) -> usize {
+        self.by_size.values().map(Vec::len).sum()
+    }
+
+    /// Find a file in the index with exactly the same contents as `path`.
+    ///
+    /// `path` itself is never reported as its own duplicate.
+    pub fn find_match(
+        &mut self,
+        path: &Path,
+        size: u64,
+        cancel: &CancellationToken,
+    ) -> ZResult<Option<PathBuf>> {
+        let Some(candidates) = self.by_size.get(&size) else {
+            return Ok(None);
+        };
+        let candidates: Vec<PathBuf> = candidates.iter().filter(|c| *c != path).cloned().collect();
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let hash = self.cached_hash(path, cancel)?;
+        for candidate in candidates {
+            // Files that vanished or can't be read since indexing are not matches
+            let candidate_hash = match self.cached_hash(&candidate, cancel) {
+                Ok(h) => h,
+                Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+                Err(_) => continue,
+            };
+            if candidate_hash != hash {
+                continue;
+            }
+            match files_identical(path, &candidate, cancel) {
+                Ok(true) => return Ok(Some(candidate)),
+                Ok(false) => {}
+                Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+                Err(_) => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Check every file in `entries` against the index.
+    ///
+    /// Directories, links and files that can't be read are ignored.
+    pub fn find_duplicates(
+        &mut self,
+        entries: &[EntryMeta],
+        cancel: &CancellationToken,
+    ) -> ZResult<DuplicateReport> {
+        let mut report = DuplicateReport::default();
+
+        for entry in entries.iter().filter(|e| e.is_file() && e.size > 0) {
+            let original = match self.find_match(&entry.path, entry.size, cancel) {
+                Ok(Some(original)) => original,
+                Ok(None) => continue,
+                Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+                Err(_) => continue,
+            };
+            report.reclaimable_bytes += entry.size;
+            report.matches.push(DuplicateMatch {
+                path: entry.path.clone(),
+                original,
+                size: entry.size,
+            });
+        }
+
+        Ok(report)
+    }
+
+    fn cached_hash(&mut self, path: &Path, cancel: &CancellationToken) -> ZResult<u64> {
+        if let Some(&hash) = self.hashes.get(path) {
+            return Ok(hash);
+        }
+        let hash = content_hash(path, cancel)?;
+        self.hashes.insert(path.to_path_buf(), hash);
+        Ok(hash)
+    }
+}
+
+/// Hash the contents of a file.
+///
+/// The hash is only used to narrow down candidates; it is not collision
+/// resistant, so equal hashes must be confirmed with [`files_identical`].
+pub fn content_hash(path: impl AsRef<Path>, cancel: &CancellationToken) -> ZResult<u64> {
+    let path = path.as_ref();
+    let mut file = File::open(path).map_err(|e| ZError::from_io(path, e))?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buf = vec![0u8; READ_CHUNK];
+
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let n = file.read(&mut buf).map_err(|e| ZError::from_io(path, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.write(&buf[..n]);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Compare two files byte by byte.
+pub fn files_identical(
+    a: impl AsRef<Path>,
+    b: impl AsRef<Path>,
+    cancel: &CancellationToken,
+) -> ZResult<bool> {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let mut file_a = File::open(a).map_err(|e| ZError::from_io(a, e))?;
+    let mut file_b = File::open(b).map_err(|e| ZError::from_io(b, e))?;
+
+    let len_a = file_a.metadata().map_err(|e| ZError::from_io(a, e))?.len();
+    let len_b = file_b.metadata().map_err(|e| ZError::from_io(b, e))?.len();
+    if len_a != len_b {
+        return Ok(false);
+    }
+
+    let mut buf_a = vec![0u8; READ_CHUNK];
+    let mut buf_b = vec![0u8; READ_CHUNK];
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let n = read_full(&mut file_a, &mut buf_a).map_err(|e| ZError::from_io(a, e))?;
+        let m = read_full(&mut file_b, &mut buf_b).map_err(|e| ZError::from_io(b, e))?;
+        if n != m || buf_a[..n] != buf_b[..m] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Fill `buf` as far as possible, returning fewer bytes only at end of file.
+fn read_full(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::get_entry_meta;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, contents: &[u8]) -> PathBuf {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, contents).unwrap();
+        path.to_path_buf()
+    }
+
+    #[test]
+    fn test_files_identical() {
+        let dir = TempDir::new().unwrap();
+        let token = CancellationToken::new();
+        let a = write(&dir.path().join("a"), b"same contents");
+        let b = write(&dir.path().join("b"), b"same contents");
+        let c = write(&dir.path().join("c"), b"same contentz");
+
+        assert!(files_identical(&a, &b, &token).unwrap());
+        assert!(!files_identical(&a, &c, &token).unwrap());
+        assert_eq!(
+            content_hash(&a, &token).unwrap(),
+            content_hash(&b, &token).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates_in_nested_tree() {
+        let active = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+        let token = CancellationToken::new();
+
+        let dup = write(&active.path().join("photo.jpg"), b"jpeg bytes");
+        let same_size = write(&active.path().join("notes.txt"), b"other text");
+        let unique = write(&active.path().join("unique.bin"), b"nothing like it");
+        let original = write(&other.path().join("deep/nested/copy.jpg"), b"jpeg bytes");
+        write(&other.path().join("text.txt"), b"Other text");
+        write(&other.path().join("empty"), b"");
+
+        let mut index = DuplicateIndex::build(other.path(), &token).unwrap();
+        assert_eq!(index.file_count(), 2);
+
+        let entries: Vec<EntryMeta> = [&dup, &same_size, &unique]
+            .iter()
+            .map(|p| get_entry_meta(p).unwrap())
+            .collect();
+        let report = index.find_duplicates(&entries, &token).unwrap();
+
+        assert_eq!(report.count(), 1);
+        assert_eq!(report.matches[0].path, dup);
+        assert_eq!(report.matches[0].original, original);
+        assert_eq!(report.reclaimable_bytes, 10);
+    }
+
+    #[test]
+    fn test_file_is_not_its_own_duplicate() {
+        let dir = TempDir::new().unwrap();
+        let token = CancellationToken::new();
+        let file = write(&dir.path().join("only.txt"), b"contents");
+
+        let mut index = DuplicateIndex::build(dir.path(), &token).unwrap();
+        assert_eq!(index.find_match(&file, 8, &token).unwrap(), None);
+    }
+
+    #[test]
+    fn test_cancelled_build() {
+        let dir = TempDir::new().unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        assert!(matches!(
+            DuplicateIndex::build(dir.path(), &token),
+            Err(ZError::Cancelled)
+        ));
+    }
+}
@@ -4,6 +4,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// Extensions (lowercase) that Windows runs rather than opens with a viewer.
+pub const EXECUTABLE_EXTENSIONS: &[&str] =
+    &["exe", "com", "bat", "cmd", "msi", "ps1", "vbs", "scr"];
+
 /// The kind of a file system entry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -114,6 +118,11 @@ pub struct EntryMeta {
     /// The file extension (lowercase, without the dot).
     /// `None` for directories or files without extensions.
     pub extension: Option<String>,
+
+    /// For directories: total size of the contents, once calculated.
+    /// `None` until a background size calculation fills it in.
+    #[serde(default)]
+    pub computed_size: Option<u64>,
 }
 
 impl EntryMeta {
@@ -139,6 +148,7 @@ impl EntryMeta {
             link_target: None,
             is_broken_link: false,
             extension,
+            computed_size: None,
         }
     }
 
@@ -162,6 +172,15 @@ impl EntryMeta {
         self.kind.is_link()
     }
 
+    /// Returns `true` if this is a file that launches a program when opened.
+    pub fn is_executable(&self) -> bool {
+        self.is_file()
+            && self
+                .extension
+                .as_deref()
+                .is_some_and(|ext| EXECUTABLE_EXTENSIONS.contains(&ext))
+    }
+
     /// Get a human-readable size string (e.g., "1.5 MB").
     pub fn size_display(&self) -> String {
         format_size(self.size)
@@ -289,6 +308,29 @@ mod tests {
         assert_eq!(meta.extension, None);
     }
 
+    #[test]
+    fn test_entry_meta_executable() {
+        let exe = EntryMeta::new(
+            "setup.EXE".to_string(),
+            PathBuf::from("C:\\test\\setup.EXE"),
+            EntryKind::File,
+        );
+        let doc = EntryMeta::new(
+            "notes.txt".to_string(),
+            PathBuf::from("C:\\test\\notes.txt"),
+            EntryKind::File,
+        );
+        let dir = EntryMeta::new(
+            "tools.bat".to_string(),
+            PathBuf::from("C:\\test\\tools.bat"),
+            EntryKind::Directory,
+        );
+
+        assert!(exe.is_executable());
+        assert!(!doc.is_executable());
+        assert!(!dir.is_executable());
+    }
+
     #[test]
     fn test_entry_meta_hidden() {
         let mut meta = EntryMeta::new(
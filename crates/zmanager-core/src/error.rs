@@ -63,6 +63,16 @@ pub enum ZError {
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
 
+    /// The destination volume doesn't have room for a transfer.
+    #[error(
+        "Not enough free space on '{path}': {required} bytes needed, {available} bytes available"
+    )]
+    InsufficientSpace {
+        path: PathBuf,
+        required: u64,
+        available: u64,
+    },
+
     /// Configuration error.
     #[error("Configuration error: {message}")]
     Config { message: String },
@@ -125,6 +135,7 @@ impl ZError {
             | Self::AlreadyExists { path }
             | Self::DirectoryNotEmpty { path }
             | Self::InvalidPath { path, .. }
+            | Self::InsufficientSpace { path, .. }
             | Self::LinkResolutionFailed { path, .. } => Some(path),
             _ => None,
         }
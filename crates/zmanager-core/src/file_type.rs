@@ -0,0 +1,138 @@
+//! File type groups by extension.
+//!
+//! Files are classified into broad groups (images, video, code, ...) that
+//! frontends use to color and decorate listings, and that listings can be
+//! grouped by (see [`SortSpec::group_by_type`](crate::SortSpec)).
+
+use serde::{Deserialize, Serialize};
+
+use crate::entry::{EntryMeta, EXECUTABLE_EXTENSIONS};
+
+/// A broad group of file types, in the order listings group them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileGroup {
+    /// Word processing, spreadsheets, PDFs and plain text.
+    Document,
+    /// Raster and vector images.
+    Image,
+    /// Video files.
+    Video,
+    /// Audio files.
+    Audio,
+    /// Source code, markup and configuration.
+    Code,
+    /// Compressed archives and disk images.
+    Archive,
+    /// Programs and scripts Windows runs.
+    Executable,
+    /// Anything else, including files without an extension.
+    Other,
+}
+
+const DOCUMENT: &[&str] = &[
+    "txt", "md", "rtf", "pdf", "doc", "docx", "odt", "xls", "xlsx", "ods", "csv", "ppt", "pptx",
+    "odp", "epub", "log",
+];
+const IMAGE: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "bmp", "webp", "svg", "ico", "tif", "tiff", "heic", "raw", "psd",
+];
+const VIDEO: &[&str] = &["mp4", "mkv", "avi", "mov", "wmv", "webm", "flv", "m4v", "mpg", "mpeg"];
+const AUDIO: &[&str] = &["mp3", "wav", "flac", "ogg", "m4a", "aac", "wma", "opus", "mid"];
+const CODE: &[&str] = &[
+    "rs", "c", "h", "cpp", "hpp", "cs", "java", "kt", "go", "py", "rb", "js", "ts", "jsx", "tsx",
+    "html", "css", "scss", "json", "toml", "yaml", "yml", "xml", "ini", "sql", "sh", "lua", "php",
+];
+const ARCHIVE: &[&str] = &["zip", "rar", "7z", "tar", "gz", "bz2", "xz", "zst", "cab", "iso"];
+
+impl FileGroup {
+    /// All groups, in grouping order.
+    pub const ALL: [Self; 8] = [
+        Self::Document,
+        Self::Image,
+        Self::Video,
+        Self::Audio,
+        Self::Code,
+        Self::Archive,
+        Self::Executable,
+        Self::Other,
+    ];
+
+    /// The group of files with extension `ext` (without the dot, any case).
+    pub fn from_extension(ext: &str) -> Self {
+        let ext = ext.to_ascii_lowercase();
+        let ext = ext.as_str();
+        if EXECUTABLE_EXTENSIONS.contains(&ext) {
+            Self::Executable
+        } else if DOCUMENT.contains(&ext) {
+            Self::Document
+        } else if IMAGE.contains(&ext) {
+            Self::Image
+        } else if VIDEO.contains(&ext) {
+            Self::Video
+        } else if AUDIO.contains(&ext) {
+            Self::Audio
+        } else if CODE.contains(&ext) {
+            Self::Code
+        } else if ARCHIVE.contains(&ext) {
+            Self::Archive
+        } else {
+            Self::Other
+        }
+    }
+
+    /// The group of an entry; `None` for directories and junctions.
+    pub fn of(entry: &EntryMeta) -> Option<Self> {
+        if entry.kind.is_directory() {
+            return None;
+        }
+        Some(entry.extension.as_deref().map_or(Self::Other, Self::from_extension))
+    }
+
+    /// Human-readable name for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Document => "Documents",
+            Self::Image => "Images",
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+            Self::Code => "Code",
+            Self::Archive => "Archives",
+            Self::Executable => "Programs",
+            Self::Other => "Other",
+        }
+    }
+}
+
+impl std::fmt::Display for FileGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntryKind;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_classifies_by_extension() {
+        assert_eq!(FileGroup::from_extension("PNG"), FileGroup::Image);
+        assert_eq!(FileGroup::from_extension("mkv"), FileGroup::Video);
+        assert_eq!(FileGroup::from_extension("flac"), FileGroup::Audio);
+        assert_eq!(FileGroup::from_extension("rs"), FileGroup::Code);
+        assert_eq!(FileGroup::from_extension("7z"), FileGroup::Archive);
+        assert_eq!(FileGroup::from_extension("docx"), FileGroup::Document);
+        assert_eq!(FileGroup::from_extension("ps1"), FileGroup::Executable);
+        assert_eq!(FileGroup::from_extension("xyz"), FileGroup::Other);
+    }
+
+    #[test]
+    fn test_directories_have_no_group() {
+        let dir = EntryMeta::new("src".into(), PathBuf::from("C:\\src"), EntryKind::Directory);
+        let bare = EntryMeta::new("LICENSE".into(), PathBuf::from("C:\\LICENSE"), EntryKind::File);
+        assert_eq!(FileGroup::of(&dir), None);
+        assert_eq!(FileGroup::of(&bare), Some(FileGroup::Other));
+    }
+}
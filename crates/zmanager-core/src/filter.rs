@@ -1,10 +1,11 @@
 //! Filtering specifications for directory listings.
 
 use crate::EntryMeta;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A specification for filtering directory entries.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FilterSpec {
     /// Text pattern to match against entry names (case-insensitive).
     /// If `None`, all entries match.
@@ -25,6 +26,26 @@ pub struct FilterSpec {
 
     /// Maximum file size in bytes. `None` means no maximum.
     pub max_size: Option<u64>,
+
+    /// Only files modified at or after this time. `None` means no limit.
+    #[serde(default)]
+    pub modified_after: Option<DateTime<Utc>>,
+
+    /// Only files modified before this time. `None` means no limit.
+    #[serde(default)]
+    pub modified_before: Option<DateTime<Utc>>,
+
+    /// Only read-only files (`Some(true)`) or only writable ones
+    /// (`Some(false)`). `None` shows both.
+    #[serde(default)]
+    pub readonly: Option<bool>,
+
+    /// Show the complement of this filter: files it would hide, and hide the
+    /// files it would show. Directories are not inverted; they keep their
+    /// normal result so the tree stays navigable while inspecting what a
+    /// filter hides.
+    #[serde(default)]
+    pub inverted: bool,
 }
 
 impl FilterSpec {
@@ -77,8 +98,51 @@ impl FilterSpec {
         self
     }
 
-    /// Check if an entry matches this filter.
+    /// Set whether to show system files, independently of hidden ones.
+    pub fn with_system(mut self, show: bool) -> Self {
+        self.show_system = show;
+        self
+    }
+
+    /// Set modification time range filter.
+    pub fn with_modified_range(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.modified_after = after;
+        self.modified_before = before;
+        self
+    }
+
+    /// Set the read-only attribute filter.
+    pub fn with_readonly(mut self, readonly: Option<bool>) -> Self {
+        self.readonly = readonly;
+        self
+    }
+
+    /// Set whether the filter is inverted.
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Toggle inversion of the filter.
+    pub fn toggle_inverted(&mut self) {
+        self.inverted = !self.inverted;
+    }
+
+    /// Check if an entry matches this filter, honoring [`FilterSpec::inverted`].
     pub fn matches(&self, entry: &EntryMeta) -> bool {
+        let matched = self.matches_uninverted(entry);
+        if self.inverted && !entry.is_directory() {
+            !matched
+        } else {
+            matched
+        }
+    }
+
+    fn matches_uninverted(&self, entry: &EntryMeta) -> bool {
         // Hidden file check
         if !self.show_hidden && entry.is_hidden() {
             return false;
@@ -120,6 +184,25 @@ impl FilterSpec {
             }
         }
 
+        // Modification time range check (only for files). Files without a
+        // known time can't be shown to fall in the range.
+        if entry.is_file() && (self.modified_after.is_some() || self.modified_before.is_some()) {
+            let Some(modified) = entry.modified else {
+                return false;
+            };
+            if self.modified_after.is_some_and(|after| modified < after) {
+                return false;
+            }
+            if self.modified_before.is_some_and(|before| modified >= before) {
+                return false;
+            }
+        }
+
+        // Attribute check (only for files)
+        if entry.is_file() && self.readonly.is_some_and(|r| entry.attributes.readonly != r) {
+            return false;
+        }
+
         true
     }
 
@@ -145,6 +228,10 @@ impl FilterSpec {
             && self.extensions.is_empty()
             && self.min_size.is_none()
             && self.max_size.is_none()
+            && self.modified_after.is_none()
+            && self.modified_before.is_none()
+            && self.readonly.is_none()
+            && !self.inverted
     }
 
     /// Returns a description of active filters for display.
@@ -159,6 +246,10 @@ impl FilterSpec {
             parts.push("hidden".to_string());
         }
 
+        if self.show_system {
+            parts.push("system".to_string());
+        }
+
         if !self.extensions.is_empty() {
             parts.push(format!(".{}", self.extensions.join(", .")));
         }
@@ -174,6 +265,26 @@ impl FilterSpec {
             }
         }
 
+        let date = |time: DateTime<Utc>| time.with_timezone(&chrono::Local).format("%Y-%m-%d");
+        match (self.modified_after, self.modified_before) {
+            (Some(after), Some(before)) => {
+                parts.push(format!("modified {} - {}", date(after), date(before)))
+            }
+            (Some(after), None) => parts.push(format!("modified since {}", date(after))),
+            (None, Some(before)) => parts.push(format!("modified before {}", date(before))),
+            (None, None) => {}
+        }
+
+        match self.readonly {
+            Some(true) => parts.push("read-only".to_string()),
+            Some(false) => parts.push("writable".to_string()),
+            None => {}
+        }
+
+        if self.inverted {
+            parts.push("inverted".to_string());
+        }
+
         if parts.is_empty() {
             None
         } else {
@@ -278,6 +389,57 @@ mod tests {
         assert!(filter.matches(&dir));
     }
 
+    #[test]
+    fn test_modified_range_filter() {
+        let at = |day| DateTime::parse_from_rfc3339(day).unwrap().with_timezone(&Utc);
+        let filter = FilterSpec::new().with_modified_range(
+            Some(at("2024-01-01T00:00:00Z")),
+            Some(at("2024-02-01T00:00:00Z")),
+        );
+
+        let mut old = make_file("old.txt", 1, false);
+        old.modified = Some(at("2023-12-31T23:59:59Z"));
+        let mut january = make_file("january.txt", 1, false);
+        january.modified = Some(at("2024-01-01T00:00:00Z"));
+        let mut february = make_file("february.txt", 1, false);
+        february.modified = Some(at("2024-02-01T00:00:00Z"));
+        let unknown = make_file("unknown.txt", 1, false);
+
+        assert!(!filter.matches(&old));
+        assert!(filter.matches(&january));
+        // The end of the range is exclusive
+        assert!(!filter.matches(&february));
+        assert!(!filter.matches(&unknown));
+        // Directories are not filtered by date
+        assert!(filter.matches(&make_dir("folder", false)));
+        assert!(filter.active_filters_description().unwrap().starts_with("modified 202"));
+    }
+
+    #[test]
+    fn test_attribute_filters() {
+        let mut system = make_file("pagefile.sys", 1, false);
+        system.attributes.system = true;
+        let mut hidden = make_file(".env", 1, true);
+        let mut locked = make_file("locked.txt", 1, false);
+        locked.attributes.readonly = true;
+        let plain = make_file("plain.txt", 1, false);
+
+        // System files show without also showing hidden ones
+        let filter = FilterSpec::new().with_system(true);
+        assert!(filter.matches(&system));
+        assert!(!filter.matches(&hidden));
+        hidden.attributes.system = true;
+        assert!(!filter.matches(&hidden));
+
+        let filter = FilterSpec::new().with_readonly(Some(true));
+        assert!(filter.matches(&locked));
+        assert!(!filter.matches(&plain));
+        let filter = FilterSpec::new().with_readonly(Some(false));
+        assert!(!filter.matches(&locked));
+        assert!(filter.matches(&plain));
+        assert!(!filter.is_default());
+    }
+
     #[test]
     fn test_combined_filters() {
         let filter = FilterSpec::new()
@@ -355,6 +517,38 @@ mod tests {
         assert!(!filter.show_hidden);
     }
 
+    #[test]
+    fn test_inverted_filter_shows_complement() {
+        let filter = FilterSpec::new().with_pattern("doc").with_inverted(true);
+
+        let matching = make_file("document.txt", 100, false);
+        let other = make_file("image.png", 100, false);
+        let hidden = make_file("doc-hidden.txt", 100, true);
+
+        assert!(!filter.matches(&matching));
+        assert!(filter.matches(&other));
+        // Hidden files are excluded by the base filter, so inversion shows them
+        assert!(filter.matches(&hidden));
+        assert!(!filter.is_default());
+    }
+
+    #[test]
+    fn test_inverted_filter_keeps_directory_result() {
+        let mut filter = FilterSpec::new().with_extension("txt");
+        let dir = make_dir("folder", false);
+        let hidden_dir = make_dir(".git", true);
+
+        filter.toggle_inverted();
+        assert!(filter.inverted);
+        // Directories are evaluated as if the filter were not inverted
+        assert!(filter.matches(&dir));
+        assert!(!filter.matches(&hidden_dir));
+
+        filter.toggle_inverted();
+        assert!(!filter.inverted);
+        assert!(filter.matches(&dir));
+    }
+
     #[test]
     fn test_filter_serialization() {
         let filter = FilterSpec::new()
@@ -1,8 +1,12 @@
 //! Directory listing and file system operations.
 
+use crate::archive::{list_archive, split_archive_path};
+use crate::drives::{is_unc_path, list_server_shares, unc_server};
 use crate::{DirListing, EntryAttributes, EntryKind, EntryMeta, FilterSpec, SortSpec, ZError, ZResult};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{debug, instrument, warn};
 
 #[cfg(windows)]
@@ -20,15 +24,19 @@ mod win_attrs {
 }
 
 /// Prefix a path with `\\?\` for long path support on Windows.
-/// Returns the original path if already prefixed or on non-Windows.
+/// UNC paths become `\\?\UNC\server\share\...`.
+/// Returns the original path if already prefixed, relative, or on non-Windows.
 pub fn to_long_path(path: &Path) -> PathBuf {
     #[cfg(windows)]
     {
         let path_str = path.to_string_lossy();
-        if path_str.starts_with(r"\\?\") {
+        // Verbatim paths take no forward slashes
+        if path_str.starts_with(r"\\?\") || path_str.starts_with(r"\\.\") {
             path.to_path_buf()
+        } else if let Some(unc) = path_str.strip_prefix(r"\\") {
+            PathBuf::from(format!(r"\\?\UNC\{}", unc.replace('/', r"\")))
         } else if path.is_absolute() {
-            PathBuf::from(format!(r"\\?\{}", path_str))
+            PathBuf::from(format!(r"\\?\{}", path_str.replace('/', r"\")))
         } else {
             path.to_path_buf()
         }
@@ -39,13 +47,42 @@ pub fn to_long_path(path: &Path) -> PathBuf {
     }
 }
 
+/// Undo [`to_long_path`], for paths read back from a prefixed directory.
+pub fn from_long_path(path: &Path) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    if let Some(unc) = path_str.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(local) = path_str.strip_prefix(r"\\?\") {
+        PathBuf::from(local)
+    } else {
+        path.to_path_buf()
+    }
+}
+
 /// Check if a path is "long" (>= 240 characters, leaving room for filenames).
 pub fn is_long_path(path: &Path) -> bool {
     path.to_string_lossy().len() >= 240
 }
 
+/// The form of `path` to pass to the file system: prefixed with `\\?\` when
+/// it is long, so operations work past the 260 character `MAX_PATH` limit.
+///
+/// Every listing, delete, rename, mkdir and transfer goes through this;
+/// paths shown to the user and stored in entries stay unprefixed.
+pub fn extended_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if is_long_path(path) {
+        to_long_path(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
 /// List the contents of a directory.
 ///
+/// Paths into a browsable archive (see [`crate::archive`]) are listed from the
+/// archive itself, so `C:\x\tools.zip\bin` behaves like a folder.
+///
 /// # Arguments
 /// * `path` - The directory path to list
 /// * `sort` - Optional sorting specification
@@ -60,49 +97,19 @@ pub fn list_directory(
     filter: Option<&FilterSpec>,
 ) -> ZResult<DirListing> {
     let path = path.as_ref();
-    let read_path = if is_long_path(path) {
-        to_long_path(path)
-    } else {
-        path.to_path_buf()
-    };
 
     debug!(path = %path.display(), "Listing directory");
 
-    // Verify path exists and is a directory
-    let metadata = fs::metadata(&read_path).map_err(|e| ZError::from_io(path, e))?;
-    if !metadata.is_dir() {
-        return Err(ZError::NotADirectory {
-            path: path.to_path_buf(),
-        });
+    if let Some((archive, inner)) = split_archive_path(path) {
+        let mut entries = list_archive(&archive, &inner)?;
+        entries.retain(|meta| filter.is_none_or(|f| f.matches(meta)));
+        sort.copied().unwrap_or_default().sort(&mut entries);
+        return Ok(DirListing::new(path.to_path_buf(), entries));
     }
 
-    // Read directory entries
-    let read_dir = fs::read_dir(&read_path).map_err(|e| ZError::from_io(path, e))?;
-
-    let mut entries = Vec::new();
-
-    for entry_result in read_dir {
-        match entry_result {
-            Ok(entry) => {
-                match read_entry_meta(&entry) {
-                    Ok(meta) => {
-                        // Apply filter if provided
-                        let include = filter.is_none_or(|f| f.matches(&meta));
-                        if include {
-                            entries.push(meta);
-                        }
-                    }
-                    Err(e) => {
-                        // Log but continue on individual entry errors
-                        warn!("Failed to read entry {:?}: {}", entry.path(), e);
-                    }
-                }
-            }
-            Err(e) => {
-                warn!("Failed to read directory entry: {}", e);
-            }
-        }
-    }
+    let mut entries: Vec<EntryMeta> = open_entries(path)?
+        .filter(|meta| filter.is_none_or(|f| f.matches(meta)))
+        .collect();
 
     // Apply sorting if provided
     if let Some(sort_spec) = sort {
@@ -115,9 +122,279 @@ pub fn list_directory(
     Ok(DirListing::new(path.to_path_buf(), entries))
 }
 
+/// Entries per batch of a streamed listing, a good default for
+/// [`list_directory_streamed`].
+pub const LISTING_BATCH_SIZE: usize = 1000;
+
+/// List the contents of a directory in the background, in batches.
+///
+/// Meant for directories too large to list in one go: entries arrive on the
+/// returned channel in batches of up to `batch_size` as they are read, so a
+/// caller can show them before the whole directory has been read. Batches
+/// are unsorted; the channel closes once the listing is complete. Dropping
+/// the receiver stops the listing.
+///
+/// A local directory is opened before returning, so a missing or unreadable
+/// directory is an error here rather than on the channel. Network paths can
+/// take a long time to answer, so they are opened in the background and a
+/// failure arrives as an `Err` on the channel. Archive paths are listed in a
+/// single batch.
+pub fn list_directory_streamed(
+    path: impl AsRef<Path>,
+    filter: Option<FilterSpec>,
+    batch_size: usize,
+) -> ZResult<mpsc::Receiver<ZResult<Vec<EntryMeta>>>> {
+    let path = path.as_ref();
+    let batch_size = batch_size.max(1);
+    let (tx, rx) = mpsc::channel(4);
+
+    debug!(path = %path.display(), "Streaming directory listing");
+
+    if let Some((archive, inner)) = split_archive_path(path) {
+        let mut entries = list_archive(&archive, &inner)?;
+        entries.retain(|meta| filter.as_ref().is_none_or(|f| f.matches(meta)));
+        // Capacity is reserved for the first batch, so this never waits
+        let _ = tx.try_send(Ok(entries));
+        return Ok(rx);
+    }
+
+    let local = if is_unc_path(path) {
+        None
+    } else {
+        Some(open_entries(path)?)
+    };
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        match local.map_or_else(|| open_entries(&path), Ok) {
+            Ok(entries) => send_batches(&tx, entries, filter.as_ref(), batch_size),
+            Err(e) => {
+                let _ = tx.blocking_send(Err(e));
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// A place directories are listed from other than the local file system,
+/// such as a remote server.
+///
+/// Its directories have virtual paths a pane can show, the way folders
+/// inside archives do.
+pub trait DirectoryProvider: Send + Sync {
+    /// Check if `path` is one of the provider's.
+    fn handles(&self, path: &Path) -> bool;
+
+    /// List the entries of the directory at `path`. May block on the network.
+    fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>>;
+}
+
+/// List a directory of `provider` in the background, in batches.
+///
+/// Works like [`list_directory_streamed`] does for network paths: the
+/// directory is read on another thread, and a failure arrives as an `Err`
+/// on the channel.
+pub fn list_provider_streamed(
+    provider: Arc<dyn DirectoryProvider>,
+    path: impl AsRef<Path>,
+    filter: Option<FilterSpec>,
+    batch_size: usize,
+) -> mpsc::Receiver<ZResult<Vec<EntryMeta>>> {
+    let path = path.as_ref().to_path_buf();
+    let batch_size = batch_size.max(1);
+    let (tx, rx) = mpsc::channel(4);
+
+    debug!(path = %path.display(), "Streaming provider listing");
+
+    std::thread::spawn(move || match provider.list(&path) {
+        Ok(entries) => send_batches(&tx, entries.into_iter(), filter.as_ref(), batch_size),
+        Err(e) => {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+
+    rx
+}
+
+/// List every file below a directory in the background, in batches: a flat
+/// "branch view" of the whole tree.
+///
+/// Works like [`list_directory_streamed`], but descends into subdirectories
+/// at any depth and lists the files and links found there instead of the
+/// directories themselves. Linked directories are not followed, so a link
+/// cycle can't trap the walk, and subdirectories that can't be read are
+/// skipped. When `filter` hides hidden or system entries, hidden or system
+/// directories are not descended into either. Archives can't be listed
+/// flat.
+pub fn list_directory_flat_streamed(
+    path: impl AsRef<Path>,
+    filter: Option<FilterSpec>,
+    batch_size: usize,
+) -> ZResult<mpsc::Receiver<ZResult<Vec<EntryMeta>>>> {
+    let path = path.as_ref();
+    let batch_size = batch_size.max(1);
+    let (tx, rx) = mpsc::channel(4);
+
+    debug!(path = %path.display(), "Streaming flat directory listing");
+
+    if split_archive_path(path).is_some() || unc_server(path).is_some() {
+        return Err(ZError::InvalidOperation {
+            operation: "flat view".to_string(),
+            reason: "Only folders can be listed flat".to_string(),
+        });
+    }
+
+    let local = if is_unc_path(path) {
+        None
+    } else {
+        Some(open_directory(path)?)
+    };
+    let path = path.to_path_buf();
+    std::thread::spawn(move || match local.map_or_else(|| open_directory(&path), Ok) {
+        Ok(read_dir) => {
+            let entries = FlatEntries {
+                current: Box::new(read_entries(read_dir)),
+                pending: Vec::new(),
+                filter: filter.clone(),
+            };
+            send_batches(&tx, entries, filter.as_ref(), batch_size);
+        }
+        Err(e) => {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Send the entries passing `filter` in batches of `batch_size`, stopping
+/// early if the receiver is dropped.
+fn send_batches(
+    tx: &mpsc::Sender<ZResult<Vec<EntryMeta>>>,
+    entries: impl Iterator<Item = EntryMeta>,
+    filter: Option<&FilterSpec>,
+    batch_size: usize,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    for meta in entries {
+        if filter.is_none_or(|f| f.matches(&meta)) {
+            batch.push(meta);
+        }
+        if batch.len() == batch_size {
+            let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+            if tx.blocking_send(Ok(full)).is_err() {
+                // Receiver dropped: nobody wants the rest
+                return;
+            }
+        }
+    }
+    if !batch.is_empty() {
+        let _ = tx.blocking_send(Ok(batch));
+    }
+}
+
+/// The files below a directory at any depth, read one directory at a time.
+struct FlatEntries {
+    /// Entries of the directory being read.
+    current: Entries,
+    /// Directories still to read.
+    pending: Vec<PathBuf>,
+    /// Listing filter, deciding whether hidden and system directories are
+    /// entered.
+    filter: Option<FilterSpec>,
+}
+
+impl FlatEntries {
+    fn enters(&self, dir: &EntryMeta) -> bool {
+        enters_directory(self.filter.as_ref(), dir)
+    }
+}
+
+/// Check whether a walk filtered by `filter` descends into `dir`: hidden and
+/// system directories are only entered when the filter shows them.
+pub(crate) fn enters_directory(filter: Option<&FilterSpec>, dir: &EntryMeta) -> bool {
+    filter.is_none_or(|f| {
+        (f.show_hidden || !dir.is_hidden()) && (f.show_system || !dir.attributes.system)
+    })
+}
+
+impl Iterator for FlatEntries {
+    type Item = EntryMeta;
+
+    fn next(&mut self) -> Option<EntryMeta> {
+        loop {
+            while let Some(meta) = self.current.next() {
+                if !meta.kind.is_directory() {
+                    return Some(meta);
+                }
+                if self.enters(&meta) {
+                    self.pending.push(meta.path);
+                }
+            }
+            let dir = self.pending.pop()?;
+            match open_directory(&dir) {
+                Ok(read_dir) => self.current = Box::new(read_entries(read_dir)),
+                Err(e) => warn!(path = %dir.display(), error = %e, "Skipping unreadable folder"),
+            }
+        }
+    }
+}
+
+/// Boxed iterator over the entries of a listing.
+type Entries = Box<dyn Iterator<Item = EntryMeta> + Send>;
+
+/// Start reading the entries of a directory, or the shares of a bare
+/// `\\server` path, which list as directories.
+fn open_entries(path: &Path) -> ZResult<Entries> {
+    match unc_server(path) {
+        Some(server) => {
+            let shares = list_server_shares(&server)?;
+            Ok(Box::new(shares.into_iter().map(|share| {
+                EntryMeta::new(share.name, share.path, EntryKind::Directory)
+            })))
+        }
+        None => Ok(Box::new(read_entries(open_directory(path)?))),
+    }
+}
+
+/// Open a directory for reading, checking that it is one.
+pub(crate) fn open_directory(path: &Path) -> ZResult<fs::ReadDir> {
+    let read_path = extended_path(path);
+
+    // Verify path exists and is a directory
+    let metadata = fs::metadata(&read_path).map_err(|e| ZError::from_io(path, e))?;
+    if !metadata.is_dir() {
+        return Err(ZError::NotADirectory {
+            path: path.to_path_buf(),
+        });
+    }
+
+    fs::read_dir(&read_path).map_err(|e| ZError::from_io(path, e))
+}
+
+/// Read the metadata of each directory entry, skipping (and logging) entries
+/// that can't be read.
+pub(crate) fn read_entries(read_dir: fs::ReadDir) -> impl Iterator<Item = EntryMeta> {
+    read_dir.filter_map(|entry_result| match entry_result {
+        Ok(entry) => match read_entry_meta(&entry) {
+            Ok(meta) => Some(meta),
+            Err(e) => {
+                warn!("Failed to read entry {:?}: {}", entry.path(), e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("Failed to read directory entry: {}", e);
+            None
+        }
+    })
+}
+
 /// Read metadata for a single directory entry.
 fn read_entry_meta(entry: &fs::DirEntry) -> ZResult<EntryMeta> {
-    let path = entry.path();
+    // Entries of a long directory come back prefixed; keep them plain
+    let os_path = entry.path();
+    let path = from_long_path(&os_path);
     let name = entry
         .file_name()
         .to_string_lossy()
@@ -127,7 +404,7 @@ fn read_entry_meta(entry: &fs::DirEntry) -> ZResult<EntryMeta> {
     let metadata = entry.metadata().map_err(|e| ZError::from_io(&path, e))?;
 
     // Determine entry kind and attributes
-    let (kind, attributes, link_target, is_broken_link) = analyze_entry(&path, &metadata)?;
+    let (kind, attributes, link_target, is_broken_link) = analyze_entry(&os_path, &metadata)?;
 
     // Extract timestamps
     let created = metadata
@@ -182,6 +459,7 @@ fn read_entry_meta(entry: &fs::DirEntry) -> ZResult<EntryMeta> {
         link_target,
         is_broken_link,
         extension,
+        computed_size: None,
     })
 }
 
@@ -229,7 +507,9 @@ fn analyze_entry(
         // Read symlink target
         match fs::read_link(path) {
             Ok(target) => {
-                let is_broken = !target.exists();
+                // Relative targets resolve against the link's folder, which
+                // following the link itself takes care of
+                let is_broken = !path.exists();
                 Ok((EntryKind::Symlink, attributes, Some(target), is_broken))
             }
             Err(_) => Ok((EntryKind::Symlink, attributes, None, true)),
@@ -251,7 +531,7 @@ fn analyze_reparse_point(
     match fs::read_link(path) {
         Ok(target) => {
             // Check if target exists
-            let is_broken = !target.exists() && !path.join(&target).exists();
+            let is_broken = !path.exists();
 
             // Determine if it's a symlink or junction
             // Junctions are directory-only and typically have absolute paths
@@ -282,11 +562,7 @@ fn analyze_reparse_point(
 pub fn get_entry_meta(path: impl AsRef<Path>) -> ZResult<EntryMeta> {
     let path = path.as_ref();
     debug!(path = %path.display(), "Getting entry metadata");
-    let read_path = if is_long_path(path) {
-        to_long_path(path)
-    } else {
-        path.to_path_buf()
-    };
+    let read_path = extended_path(path);
 
     let metadata = fs::symlink_metadata(&read_path).map_err(|e| ZError::from_io(path, e))?;
 
@@ -295,7 +571,7 @@ pub fn get_entry_meta(path: impl AsRef<Path>) -> ZResult<EntryMeta> {
         .map(|n| n.to_string_lossy().into_owned())
         .unwrap_or_else(|| path.to_string_lossy().into_owned());
 
-    let (kind, attributes, link_target, is_broken_link) = analyze_entry(path, &metadata)?;
+    let (kind, attributes, link_target, is_broken_link) = analyze_entry(&read_path, &metadata)?;
 
     let created = metadata
         .created()
@@ -347,9 +623,44 @@ pub fn get_entry_meta(path: impl AsRef<Path>) -> ZResult<EntryMeta> {
         link_target,
         is_broken_link,
         extension,
+        computed_size: None,
     })
 }
 
+/// Upper bound on how many single-child levels [`descend_single_children`] follows.
+const MAX_SINGLE_CHILD_DEPTH: usize = 64;
+
+/// Follow a chain of directories that each contain exactly one subdirectory.
+///
+/// Starting at `path`, keeps descending while the current directory holds a
+/// single entry that is a real directory, and returns the first directory with
+/// files, several entries or no entries. Symlinks and junctions are never
+/// followed, so link cycles cannot trap the walk. Unreadable directories stop
+/// the walk where they are.
+pub fn descend_single_children(path: &Path) -> PathBuf {
+    let mut current = path.to_path_buf();
+
+    for _ in 0..MAX_SINGLE_CHILD_DEPTH {
+        let Ok(mut entries) = fs::read_dir(&current) else {
+            break;
+        };
+        let only = match (entries.next(), entries.next()) {
+            (Some(Ok(only)), None) => only,
+            _ => break,
+        };
+        // `DirEntry::file_type` does not traverse links, so a symlinked or
+        // junctioned directory reports as a link and ends the chain.
+        match only.file_type() {
+            Ok(file_type) if file_type.is_dir() && !file_type.is_symlink() => {
+                current = only.path();
+            }
+            _ => break,
+        }
+    }
+
+    current
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,6 +754,120 @@ mod tests {
         assert!(matches!(result.unwrap_err(), ZError::NotADirectory { .. }));
     }
 
+    #[test]
+    fn test_list_directory_streamed() {
+        let dir = setup_test_dir();
+        let mut batches = list_directory_streamed(dir.path(), None, 2).unwrap();
+
+        let mut sizes = Vec::new();
+        let mut names = Vec::new();
+        while let Some(batch) = batches.blocking_recv() {
+            let batch = batch.unwrap();
+            sizes.push(batch.len());
+            names.extend(batch.into_iter().map(|e| e.name));
+        }
+        names.sort();
+
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert_eq!(names, vec![".hidden", "file1.txt", "file2.md", "large.bin", "subdir"]);
+    }
+
+    #[test]
+    fn test_list_directory_streamed_with_filter() {
+        let dir = setup_test_dir();
+        let filter = FilterSpec::new().with_pattern("file");
+        let mut batches = list_directory_streamed(dir.path(), Some(filter), 10).unwrap();
+
+        let batch = batches.blocking_recv().unwrap().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert!(batches.blocking_recv().is_none());
+    }
+
+    #[test]
+    fn test_list_directory_streamed_errors_up_front() {
+        let dir = setup_test_dir();
+
+        let result = list_directory_streamed(dir.path().join("missing"), None, 10);
+        assert!(result.unwrap_err().is_not_found());
+
+        let result = list_directory_streamed(dir.path().join("file1.txt"), None, 10);
+        assert!(matches!(result.unwrap_err(), ZError::NotADirectory { .. }));
+    }
+
+    /// Lists three files under `mem:`, or fails for any other directory.
+    struct MemoryProvider;
+
+    impl DirectoryProvider for MemoryProvider {
+        fn handles(&self, path: &Path) -> bool {
+            path.starts_with("mem:")
+        }
+
+        fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>> {
+            if path != Path::new("mem:") {
+                return Err(ZError::NotFound {
+                    path: path.to_path_buf(),
+                });
+            }
+            Ok(["a.txt", "b.txt", "c.md"]
+                .into_iter()
+                .map(|name| EntryMeta::new(name.to_string(), path.join(name), EntryKind::File))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_list_provider_streamed() {
+        let provider: Arc<dyn DirectoryProvider> = Arc::new(MemoryProvider);
+        let filter = FilterSpec::new().with_pattern("txt");
+        let mut batches = list_provider_streamed(provider.clone(), "mem:", Some(filter), 1);
+
+        let mut names = Vec::new();
+        while let Some(batch) = batches.blocking_recv() {
+            names.extend(batch.unwrap().into_iter().map(|e| e.name));
+        }
+        assert_eq!(names, vec!["a.txt", "b.txt"]);
+
+        // Failures arrive on the channel
+        let mut batches = list_provider_streamed(provider, "mem:missing", None, 10);
+        assert!(batches.blocking_recv().unwrap().unwrap_err().is_not_found());
+        assert!(batches.blocking_recv().is_none());
+    }
+
+    #[test]
+    fn test_list_directory_flat_streamed() {
+        let dir = setup_test_dir();
+        let nested = dir.path().join("subdir").join("deeper");
+        fs::create_dir(&nested).unwrap();
+        File::create(nested.join("inner.txt")).unwrap();
+        fs::create_dir(dir.path().join(".cache")).unwrap();
+        File::create(dir.path().join(".cache").join("cached.txt")).unwrap();
+
+        let flat_names = |filter: Option<FilterSpec>| {
+            let mut batches = list_directory_flat_streamed(dir.path(), filter, 2).unwrap();
+            let mut entries = Vec::new();
+            while let Some(batch) = batches.blocking_recv() {
+                entries.extend(batch.unwrap());
+            }
+            let mut names: Vec<_> = entries.into_iter().map(|e| e.name).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            flat_names(None),
+            vec![".hidden", "cached.txt", "file1.txt", "file2.md", "inner.txt", "large.bin"]
+        );
+        // Hidden folders are skipped along with hidden files
+        assert_eq!(
+            flat_names(Some(FilterSpec::new())),
+            vec!["file1.txt", "file2.md", "inner.txt", "large.bin"]
+        );
+        assert_eq!(flat_names(Some(FilterSpec::new().with_pattern("inner"))), vec!["inner.txt"]);
+
+        let result = list_directory_flat_streamed(dir.path().join("missing"), None, 10);
+        assert!(result.unwrap_err().is_not_found());
+    }
+
     #[test]
     fn test_entry_metadata() {
         let dir = setup_test_dir();
@@ -481,14 +906,129 @@ mod tests {
         assert_eq!(meta.extension, Some("txt".to_string()));
     }
 
+    #[test]
+    fn test_descend_single_children() {
+        let dir = TempDir::new().unwrap();
+        let chain = dir.path().join("pkg").join("pkg-1.0").join("src");
+        fs::create_dir_all(&chain).unwrap();
+        File::create(chain.join("main.rs")).unwrap();
+        File::create(chain.join("lib.rs")).unwrap();
+
+        // Stops at the first directory with more than one entry
+        assert_eq!(descend_single_children(&dir.path().join("pkg")), chain);
+
+        // A lone file ends the chain at its parent
+        let single_file = dir.path().join("wrapper");
+        fs::create_dir(&single_file).unwrap();
+        File::create(single_file.join("readme.txt")).unwrap();
+        assert_eq!(descend_single_children(&single_file), single_file);
+
+        // Empty directories are returned as-is
+        let empty = dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        assert_eq!(descend_single_children(&empty), empty);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_descend_single_children_ignores_symlink_loop() {
+        let dir = TempDir::new().unwrap();
+        let outer = dir.path().join("outer");
+        fs::create_dir(&outer).unwrap();
+        std::os::unix::fs::symlink(&outer, outer.join("loop")).unwrap();
+
+        assert_eq!(descend_single_children(&outer), outer);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_entry_meta_link_target() {
+        let dir = TempDir::new().unwrap();
+        File::create(dir.path().join("file.txt")).unwrap();
+        std::os::unix::fs::symlink("file.txt", dir.path().join("link")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", dir.path().join("broken")).unwrap();
+
+        let link = get_entry_meta(dir.path().join("link")).unwrap();
+        assert_eq!(link.kind, EntryKind::Symlink);
+        assert_eq!(link.link_target, Some(PathBuf::from("file.txt")));
+        assert!(!link.is_broken_link);
+
+        let broken = get_entry_meta(dir.path().join("broken")).unwrap();
+        assert!(broken.is_broken_link);
+    }
+
+    #[test]
+    fn test_list_directory_inside_zip() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("bundle.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("docs/guide.md", options).unwrap();
+        writer.write_all(b"# Guide").unwrap();
+        writer.start_file("docs/notes.txt", options).unwrap();
+        writer.write_all(b"notes").unwrap();
+        writer.finish().unwrap();
+
+        let root = list_directory(&archive, None, None).unwrap();
+        assert_eq!(root.dir_count, 1);
+        assert_eq!(root.entries[0].path, archive.join("docs"));
+
+        let filter = FilterSpec::new().with_extension("md");
+        let docs = list_directory(archive.join("docs"), None, Some(&filter)).unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs.entries[0].name, "guide.md");
+        assert_eq!(docs.total_size, 7);
+    }
+
     #[test]
     fn test_long_path_conversion() {
         let short = Path::new(r"C:\Users\test");
         assert!(!is_long_path(short));
+        assert_eq!(extended_path(short), short);
+        assert_eq!(
+            from_long_path(Path::new(r"\\?\UNC\server\share")),
+            Path::new(r"\\server\share")
+        );
 
         let long_path = to_long_path(short);
         #[cfg(windows)]
-        assert!(long_path.to_string_lossy().starts_with(r"\\?\"));
+        {
+            assert!(long_path.to_string_lossy().starts_with(r"\\?\"));
+            assert_eq!(to_long_path(&long_path), long_path);
+            assert_eq!(from_long_path(&long_path), short);
+            assert_eq!(
+                to_long_path(Path::new(r"\\server\share\dir")),
+                Path::new(r"\\?\UNC\server\share\dir")
+            );
+        }
+        #[cfg(not(windows))]
+        assert_eq!(long_path, short);
+    }
+
+    /// Create nested folders under `root` until the path is over 300
+    /// characters long, past Windows' `MAX_PATH`.
+    fn create_deep_tree(root: &Path) -> PathBuf {
+        let mut path = root.to_path_buf();
+        while path.as_os_str().len() <= 300 {
+            path.push("a_rather_long_folder_name_to_get_past_max_path");
+        }
+        fs::create_dir_all(extended_path(&path)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_deep_directory() {
+        let dir = TempDir::new().unwrap();
+        let deep = create_deep_tree(dir.path());
+        fs::write(extended_path(deep.join("file.txt")), "hello").unwrap();
+        assert!(is_long_path(&deep));
+
+        let listing = list_directory(&deep, None, None).unwrap();
+        assert_eq!(listing.len(), 1);
+        assert_eq!(listing.entries[0].path, deep.join("file.txt"));
+
+        let meta = get_entry_meta(deep.join("file.txt")).unwrap();
+        assert_eq!(meta.size, 5);
     }
 
     #[test]
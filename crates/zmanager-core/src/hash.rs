@@ -0,0 +1,334 @@
+//! File checksums.
+//!
+//! Hashes file contents with MD5, SHA-1, SHA-256 or xxHash (64-bit XXH3),
+//! reporting progress as the files are read. Checksums are lowercase hex, the
+//! form tools like `sha256sum` print, so a checksum pasted from a download
+//! page can be checked with [`hash_matches`].
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::{CancellationToken, ChecksumAlgorithm, Progress, ZError, ZResult};
+
+/// Read size while hashing.
+const READ_CHUNK: usize = 1024 * 1024;
+
+/// Algorithm to hash files with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    /// Still found on older download pages
+    Md5,
+    /// Used by git and many mirrors
+    Sha1,
+    /// The most commonly published checksum
+    #[default]
+    Sha256,
+    /// Not cryptographic, but much faster
+    XxHash,
+}
+
+impl HashAlgorithm {
+    /// All algorithms, in the order they are offered.
+    pub const ALL: [Self; 4] = [Self::Md5, Self::Sha1, Self::Sha256, Self::XxHash];
+
+    /// Display name of the algorithm.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::XxHash => "xxHash",
+        }
+    }
+
+    /// Number of hex digits in a checksum.
+    pub fn hex_len(self) -> usize {
+        match self {
+            Self::Md5 => 32,
+            Self::Sha1 => 40,
+            Self::Sha256 => 64,
+            Self::XxHash => 16,
+        }
+    }
+
+    /// Guess the algorithm that produced `checksum` from its length.
+    pub fn detect(checksum: &str) -> Option<Self> {
+        let checksum = normalize_hash(checksum);
+        if !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Self::ALL.into_iter().find(|a| a.hex_len() == checksum.len())
+    }
+}
+
+impl std::fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<ChecksumAlgorithm> for HashAlgorithm {
+    fn from(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::XxHash => Self::XxHash,
+            ChecksumAlgorithm::Sha256 => Self::Sha256,
+        }
+    }
+}
+
+/// Incremental hasher for any algorithm.
+enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    XxHash(Box<Xxh3>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::XxHash => Self::XxHash(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(hasher) => hasher.update(data),
+            Self::Sha1(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::XxHash(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        let digest = match self {
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+            Self::Sha1(hasher) => hasher.finalize().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::XxHash(hasher) => return format!("{:016x}", hasher.digest()),
+        };
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+/// Checksum of one file in a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHash {
+    /// The file that was hashed.
+    pub path: PathBuf,
+    /// Size of the file when the batch started.
+    pub size: u64,
+    /// The checksum as lowercase hex, or why the file couldn't be read.
+    pub hash: Result<String, String>,
+}
+
+impl FileHash {
+    /// Check if both files were read and have the same contents.
+    pub fn matches(&self, other: &FileHash) -> bool {
+        matches!((&self.hash, &other.hash), (Ok(a), Ok(b)) if a == b)
+    }
+}
+
+/// Hash bytes in memory, returning the checksum as lowercase hex.
+pub fn hash_bytes(data: &[u8], algorithm: HashAlgorithm) -> String {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(data);
+    hasher.finish()
+}
+
+/// Hash the contents of a file, returning the checksum as lowercase hex.
+pub fn hash_file(
+    path: impl AsRef<Path>,
+    algorithm: HashAlgorithm,
+    cancel: &CancellationToken,
+) -> ZResult<String> {
+    hash_file_with_progress(path, algorithm, cancel, |_| {})
+}
+
+/// Hash the contents of a file, calling `on_read` with the number of bytes
+/// read after each chunk.
+pub fn hash_file_with_progress(
+    path: impl AsRef<Path>,
+    algorithm: HashAlgorithm,
+    cancel: &CancellationToken,
+    mut on_read: impl FnMut(u64),
+) -> ZResult<String> {
+    let path = path.as_ref();
+    let mut file = File::open(path).map_err(|e| ZError::from_io(path, e))?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; READ_CHUNK];
+
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let n = file.read(&mut buf).map_err(|e| ZError::from_io(path, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        on_read(n as u64);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Hash several files, reporting bytes and files done to `on_progress`.
+///
+/// A file that can't be read is recorded with its error and the rest are
+/// still hashed; only cancellation stops the batch. Progress is reported
+/// after every chunk read, so callers should throttle it.
+pub fn hash_files_with_progress(
+    paths: &[PathBuf],
+    algorithm: HashAlgorithm,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&Progress),
+) -> ZResult<Vec<FileHash>> {
+    let sizes: Vec<u64> = paths
+        .iter()
+        .map(|path| std::fs::metadata(path).map_or(0, |m| m.len()))
+        .collect();
+    let mut progress = Progress::new(paths.len(), Some(sizes.iter().sum()));
+    let mut hashes = Vec::with_capacity(paths.len());
+
+    for (path, size) in paths.iter().zip(sizes) {
+        progress.current_item = Some(path.clone());
+        on_progress(&progress);
+
+        let hash = hash_file_with_progress(path, algorithm, cancel, |n| {
+            progress.bytes_done += n;
+            on_progress(&progress);
+        });
+        let hash = match hash {
+            Ok(hash) => Ok(hash),
+            Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+            Err(e) => Err(e.to_string()),
+        };
+        hashes.push(FileHash {
+            path: path.clone(),
+            size,
+            hash,
+        });
+        progress.items_done += 1;
+    }
+
+    progress.current_item = None;
+    on_progress(&progress);
+    Ok(hashes)
+}
+
+/// Normalize a checksum as typed or pasted: the first word (so a line of
+/// `sha256sum` output works), without a `0x` prefix, in lowercase.
+pub fn normalize_hash(text: &str) -> String {
+    let word = text.split_whitespace().next().unwrap_or_default();
+    let word = word.strip_prefix("0x").unwrap_or(word);
+    word.to_ascii_lowercase()
+}
+
+/// Check a computed checksum against an expected one as typed or pasted.
+pub fn hash_matches(expected: &str, actual: &str) -> bool {
+    let expected = normalize_hash(expected);
+    !expected.is_empty() && expected == actual.to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, contents: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_known_hashes() {
+        let temp = TempDir::new().unwrap();
+        let path = write(temp.path(), "abc.txt", b"abc");
+        let cancel = CancellationToken::new();
+        let hash = |algorithm| hash_file(&path, algorithm, &cancel).unwrap();
+
+        assert_eq!(hash(HashAlgorithm::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hash(HashAlgorithm::Sha1), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(
+            hash(HashAlgorithm::Sha256),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash(HashAlgorithm::XxHash),
+            format!("{:016x}", xxhash_rust::xxh3::xxh3_64(b"abc"))
+        );
+        for algorithm in HashAlgorithm::ALL {
+            assert_eq!(hash(algorithm).len(), algorithm.hex_len());
+            assert_eq!(hash_bytes(b"abc", algorithm), hash(algorithm));
+        }
+    }
+
+    #[test]
+    fn test_hash_files_with_progress() {
+        let temp = TempDir::new().unwrap();
+        let a = write(temp.path(), "a.bin", &[1u8; 100]);
+        let b = write(temp.path(), "b.bin", &[1u8; 100]);
+        let missing = temp.path().join("missing.bin");
+        let paths = vec![a, missing.clone(), b];
+
+        let mut updates = Vec::new();
+        let hashes = hash_files_with_progress(
+            &paths,
+            HashAlgorithm::Sha256,
+            &CancellationToken::new(),
+            |progress| updates.push(progress.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(hashes.len(), 3);
+        assert!(hashes[0].matches(&hashes[2]));
+        assert!(!hashes[0].matches(&hashes[1]));
+        assert_eq!(hashes[1].path, missing);
+        assert!(hashes[1].hash.is_err());
+
+        let last = updates.last().unwrap();
+        assert_eq!(last.total_bytes, Some(200));
+        assert_eq!(last.bytes_done, 200);
+        assert_eq!(last.items_done, 3);
+        assert!(last.current_item.is_none());
+    }
+
+    #[test]
+    fn test_cancelled_hashing() {
+        let temp = TempDir::new().unwrap();
+        let paths = vec![write(temp.path(), "a.bin", b"data")];
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = hash_files_with_progress(&paths, HashAlgorithm::Md5, &cancel, |_| {});
+        assert!(matches!(result, Err(ZError::Cancelled)));
+    }
+
+    #[test]
+    fn test_pasted_hashes() {
+        let sha1 = "a9993e364706816aba3e25717850c26c9cd0d89d";
+        assert!(hash_matches(&sha1.to_uppercase(), sha1));
+        assert!(hash_matches(&format!("  {sha1}  abc.txt\n"), sha1));
+        assert!(hash_matches(&format!("0x{sha1}"), sha1));
+        assert!(!hash_matches("", sha1));
+        assert!(!hash_matches("a9993e36", sha1));
+
+        assert_eq!(HashAlgorithm::detect(sha1), Some(HashAlgorithm::Sha1));
+        assert_eq!(HashAlgorithm::detect(&"F".repeat(64)), Some(HashAlgorithm::Sha256));
+        assert_eq!(HashAlgorithm::detect(&"z".repeat(32)), None);
+        assert_eq!(HashAlgorithm::detect("abc"), None);
+    }
+}
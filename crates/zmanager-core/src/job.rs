@@ -3,13 +3,20 @@
 //! This module defines the core types for the job system that manages
 //! long-running file operations like copy, move, and delete.
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
+use crate::compare::CompareMode;
+use crate::drives::same_volume;
+use crate::error::{ZError, ZResult};
+use crate::hash::HashAlgorithm;
+use crate::text_file::TextConversion;
+
 /// Unique identifier for a job.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct JobId(pub u64);
@@ -54,6 +61,34 @@ pub enum JobKind {
     DeletePermanent { paths: Vec<PathBuf> },
     /// Calculate folder size (async operation)
     CalculateSize { path: PathBuf },
+    /// Pack files/directories into a new archive file
+    Compress {
+        sources: Vec<PathBuf>,
+        destination: PathBuf,
+    },
+    /// Extract entries from an archive into a destination folder
+    Extract {
+        archive: PathBuf,
+        /// Paths inside the archive; empty extracts everything
+        entries: Vec<PathBuf>,
+        destination: PathBuf,
+    },
+    /// Hash the contents of files
+    Checksum {
+        paths: Vec<PathBuf>,
+        algorithm: HashAlgorithm,
+    },
+    /// Compare the contents of two files
+    Compare {
+        left: PathBuf,
+        right: PathBuf,
+        mode: CompareMode,
+    },
+    /// Convert text files to another encoding or line ending
+    ConvertText {
+        paths: Vec<PathBuf>,
+        conversion: TextConversion,
+    },
 }
 
 impl JobKind {
@@ -87,15 +122,109 @@ impl JobKind {
             Self::CalculateSize { path } => {
                 format!("Calculating size of {}", path.display())
             }
+            Self::Compress { sources, destination } => match sources.len() {
+                1 => format!("Compressing {}", sources[0].display()),
+                count => format!("Compressing {count} items into {}", destination.display()),
+            },
+            Self::Extract { archive, entries, .. } => match entries.len() {
+                0 => format!("Extracting {}", archive.display()),
+                1 => format!("Extracting {}", entries[0].display()),
+                count => format!("Extracting {count} items from {}", archive.display()),
+            },
+            Self::Checksum { paths, algorithm } => match paths.len() {
+                1 => format!("Computing {algorithm} of {}", paths[0].display()),
+                count => format!("Computing {algorithm} of {count} files"),
+            },
+            Self::Compare { left, right, .. } => {
+                format!("Comparing {} with {}", left.display(), right.display())
+            }
+            Self::ConvertText { paths, .. } => match paths.len() {
+                1 => format!("Converting {}", paths[0].display()),
+                count => format!("Converting {count} files"),
+            },
         }
     }
 
     /// Get the total number of items this job will process.
     pub fn item_count(&self) -> usize {
         match self {
-            Self::Copy { sources, .. } | Self::Move { sources, .. } => sources.len(),
-            Self::Delete { paths } | Self::DeletePermanent { paths } => paths.len(),
+            Self::Copy { sources, .. }
+            | Self::Move { sources, .. }
+            | Self::Compress { sources, .. } => sources.len(),
+            Self::Delete { paths }
+            | Self::DeletePermanent { paths }
+            | Self::Checksum { paths, .. }
+            | Self::ConvertText { paths, .. } => paths.len(),
             Self::CalculateSize { .. } => 1,
+            Self::Compare { .. } => 2,
+            // Refined into a file count once the archive has been read
+            Self::Extract { entries, .. } => entries.len().max(1),
+        }
+    }
+
+    /// Get the category this job counts against for concurrency limits.
+    ///
+    /// Copies and moves are checked against the filesystem to tell whether
+    /// they cross volumes.
+    pub fn category(&self) -> JobCategory {
+        match self {
+            Self::Copy { sources, destination } | Self::Move { sources, destination } => {
+                if sources.iter().all(|source| same_volume(source, destination)) {
+                    JobCategory::Transfer
+                } else {
+                    JobCategory::CrossVolumeTransfer
+                }
+            }
+            Self::Delete { .. } | Self::DeletePermanent { .. } => JobCategory::Delete,
+            Self::CalculateSize { .. } => JobCategory::CalculateSize,
+            Self::Compress { .. } | Self::Extract { .. } => JobCategory::Archive,
+            Self::Checksum { .. } | Self::Compare { .. } => JobCategory::Checksum,
+            Self::ConvertText { .. } => JobCategory::Convert,
+        }
+    }
+}
+
+/// Groups of job kinds that share a concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JobCategory {
+    /// Copy or move within a single volume
+    Transfer,
+    /// Copy or move between volumes
+    CrossVolumeTransfer,
+    /// Delete to the Recycle Bin or permanently
+    Delete,
+    /// Folder size calculation
+    CalculateSize,
+    /// Archive creation or extraction
+    Archive,
+    /// File checksum calculation and comparison
+    Checksum,
+    /// Text file conversion
+    Convert,
+}
+
+/// Scheduling priority of a job.
+///
+/// Pending jobs start in priority order, oldest first within a priority.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Priority {
+    /// Starts ahead of everything else
+    High,
+    /// The default priority
+    #[default]
+    Normal,
+    /// Starts once nothing else is waiting
+    Low,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::High => write!(f, "High"),
+            Self::Normal => write!(f, "Normal"),
+            Self::Low => write!(f, "Low"),
         }
     }
 }
@@ -192,6 +321,17 @@ impl Progress {
     pub fn percentage_int(&self) -> u8 {
         (self.percentage() * 100.0).round() as u8
     }
+
+    /// Set the transfer speed, estimating the time remaining from it.
+    pub fn set_speed(&mut self, bytes_per_sec: Option<u64>) {
+        self.speed_bytes_per_sec = bytes_per_sec;
+        self.eta = match (bytes_per_sec, self.total_bytes) {
+            (Some(speed), Some(total)) if speed > 0 => Some(Duration::from_secs(
+                total.saturating_sub(self.bytes_done) / speed,
+            )),
+            _ => None,
+        };
+    }
 }
 
 impl Default for Progress {
@@ -200,6 +340,84 @@ impl Default for Progress {
     }
 }
 
+/// Transfer speed over the last few seconds.
+///
+/// Executors record the bytes done as they go; the speed is taken over a
+/// rolling window rather than the whole transfer, so it follows real changes
+/// (a slow disk, a pause, a burst of small files) without jumping about on
+/// every chunk.
+#[derive(Debug, Clone)]
+pub struct SpeedEstimator {
+    /// How far back the speed looks.
+    window: Duration,
+    /// Bytes done at points in time, oldest first.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl SpeedEstimator {
+    /// Default span the speed is taken over.
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(5);
+
+    /// Least spacing kept between samples; closer ones are merged.
+    const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Least span the samples must cover before there is a speed.
+    const MIN_SPAN: Duration = Duration::from_millis(200);
+
+    /// Create an estimator over [`Self::DEFAULT_WINDOW`].
+    pub fn new() -> Self {
+        Self::with_window(Self::DEFAULT_WINDOW)
+    }
+
+    /// Create an estimator over `window`.
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record the bytes done at `now` and return the speed.
+    pub fn record(&mut self, bytes_done: u64, now: Instant) -> Option<u64> {
+        let len = self.samples.len();
+        if len > 1
+            && self.samples[len - 1]
+                .0
+                .saturating_duration_since(self.samples[len - 2].0)
+                < Self::SAMPLE_INTERVAL
+        {
+            self.samples.pop_back();
+        }
+        self.samples.push_back((now, bytes_done));
+
+        // Keep one sample at or past the window's edge as the baseline
+        while self.samples.len() > 2
+            && now.saturating_duration_since(self.samples[1].0) >= self.window
+        {
+            self.samples.pop_front();
+        }
+        self.speed()
+    }
+
+    /// Speed in bytes per second, once the samples span long enough.
+    pub fn speed(&self) -> Option<u64> {
+        let (&(first, first_bytes), &(last, last_bytes)) =
+            (self.samples.front()?, self.samples.back()?);
+        let span = last.saturating_duration_since(first);
+        if span < Self::MIN_SPAN {
+            return None;
+        }
+        let bytes = last_bytes.saturating_sub(first_bytes);
+        Some((bytes as u128 * 1000 / span.as_millis()) as u64)
+    }
+}
+
+impl Default for SpeedEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A cancellation token for cooperative cancellation of jobs.
 ///
 /// Jobs should check this token periodically and stop gracefully when cancelled.
@@ -238,6 +456,181 @@ impl Default for CancellationToken {
     }
 }
 
+/// How often a parked job re-checks its pause and cancellation tokens.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A pause token for cooperative pausing of jobs.
+///
+/// Executors check this token between units of work (chunks or files) and
+/// park while it is set. A paused job can still be cancelled.
+#[derive(Debug, Clone)]
+pub struct PauseToken {
+    paused: Arc<AtomicBool>,
+}
+
+impl PauseToken {
+    /// Create a new, unpaused token.
+    pub fn new() -> Self {
+        Self {
+            paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Request a pause.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Lift a pause.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if a pause has been requested.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Block the current thread while paused.
+    ///
+    /// Returns [`ZError::Cancelled`] if `cancel` fires before or during the wait.
+    pub fn wait_blocking(&self, cancel: &CancellationToken) -> ZResult<()> {
+        while self.is_paused() && !cancel.is_cancelled() {
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Wait asynchronously while paused.
+    ///
+    /// Returns [`ZError::Cancelled`] if `cancel` fires before or during the wait.
+    pub async fn wait(&self, cancel: &CancellationToken) -> ZResult<()> {
+        while self.is_paused() && !cancel.is_cancelled() {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+impl Default for PauseToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How far a throttled job may fall behind its limit before the shortfall is
+/// forgotten, so an idle stretch (a pause, a slow disk) doesn't turn into a
+/// burst at full speed.
+const SPEED_LIMIT_SLACK: Duration = Duration::from_secs(1);
+
+/// A shared bandwidth limit for a job, in bytes per second.
+///
+/// Executors report each chunk they move with [`SpeedLimit::throttle_blocking`],
+/// which sleeps just long enough to keep the job's average at the limit. All
+/// clones share one budget, so concurrent copies of one job split the limit.
+/// The limit can be changed or lifted while the job runs.
+#[derive(Debug, Clone)]
+pub struct SpeedLimit {
+    /// Bytes per second; zero means no limit.
+    bytes_per_sec: Arc<AtomicU64>,
+    window: Arc<Mutex<Option<ThrottleWindow>>>,
+}
+
+/// Bytes moved since `started` under a limit of `limit` bytes per second.
+#[derive(Debug, Clone, Copy)]
+struct ThrottleWindow {
+    started: Instant,
+    bytes: u64,
+    limit: u64,
+}
+
+impl SpeedLimit {
+    /// Create a token with no limit.
+    pub fn new() -> Self {
+        Self {
+            bytes_per_sec: Arc::new(AtomicU64::new(0)),
+            window: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Set the limit in bytes per second; `None` (or zero) lifts it.
+    pub fn set(&self, bytes_per_sec: Option<u64>) {
+        self.bytes_per_sec
+            .store(bytes_per_sec.unwrap_or(0), Ordering::SeqCst);
+    }
+
+    /// Get the current limit in bytes per second.
+    pub fn get(&self) -> Option<u64> {
+        Some(self.bytes_per_sec.load(Ordering::SeqCst)).filter(|limit| *limit > 0)
+    }
+
+    /// Account for `bytes` just transferred, blocking the current thread as
+    /// long as needed to stay within the limit.
+    ///
+    /// Returns [`ZError::Cancelled`] if `cancel` fires before or during the wait.
+    pub fn throttle_blocking(&self, bytes: u64, cancel: &CancellationToken) -> ZResult<()> {
+        let resume_at = Instant::now() + self.delay_for(bytes);
+        while !cancel.is_cancelled() {
+            let remaining = resume_at.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            std::thread::sleep(remaining.min(PAUSE_POLL_INTERVAL));
+        }
+        Err(ZError::Cancelled)
+    }
+
+    /// Record `bytes` against the current window and work out how far ahead
+    /// of the limit the job now is.
+    fn delay_for(&self, bytes: u64) -> Duration {
+        let mut window = self
+            .window
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(limit) = self.get() else {
+            *window = None;
+            return Duration::ZERO;
+        };
+
+        let now = Instant::now();
+        let current = match *window {
+            // A new limit starts a fresh window
+            Some(current) if current.limit == limit => current,
+            _ => ThrottleWindow {
+                started: now,
+                bytes: 0,
+                limit,
+            },
+        };
+        let bytes = current.bytes + bytes;
+        let due = Duration::from_secs_f64(bytes as f64 / limit as f64);
+        let elapsed = now.duration_since(current.started);
+
+        if elapsed > due + SPEED_LIMIT_SLACK {
+            *window = Some(ThrottleWindow {
+                started: now,
+                bytes: 0,
+                limit,
+            });
+            return Duration::ZERO;
+        }
+        *window = Some(ThrottleWindow { bytes, ..current });
+        due.saturating_sub(elapsed)
+    }
+}
+
+impl Default for SpeedLimit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A complete job with all its metadata.
 #[derive(Debug, Clone)]
 pub struct Job {
@@ -247,6 +640,8 @@ pub struct Job {
     pub kind: JobKind,
     /// Current state
     pub state: JobState,
+    /// Scheduling priority
+    pub priority: Priority,
     /// Progress information
     pub progress: Progress,
     /// When the job was created
@@ -259,6 +654,17 @@ pub struct Job {
     pub error: Option<String>,
     /// Cancellation token
     pub cancellation: CancellationToken,
+    /// Pause token, set while the job is paused
+    pub pause: PauseToken,
+    /// Bandwidth limit, changeable while the job runs
+    pub speed_limit: SpeedLimit,
+    /// When the current pause began (if paused)
+    pub paused_at: Option<Instant>,
+    /// Total time spent in earlier, finished pauses
+    pub paused_time: Duration,
+    /// Jobs that redo just the items that failed, set when the job failed
+    /// part way
+    pub retry: Vec<JobKind>,
 }
 
 impl Job {
@@ -269,12 +675,18 @@ impl Job {
             id: JobId::new(),
             kind,
             state: JobState::Pending,
+            priority: Priority::default(),
             progress: Progress::new(total_items, None),
             created_at: Instant::now(),
             started_at: None,
             finished_at: None,
             error: None,
             cancellation: CancellationToken::new(),
+            pause: PauseToken::new(),
+            speed_limit: SpeedLimit::new(),
+            paused_at: None,
+            paused_time: Duration::ZERO,
+            retry: Vec::new(),
         }
     }
 
@@ -290,6 +702,8 @@ impl Job {
     pub fn pause(&mut self) {
         if self.state == JobState::Running {
             self.state = JobState::Paused;
+            self.paused_at = Some(Instant::now());
+            self.pause.pause();
         }
     }
 
@@ -297,12 +711,22 @@ impl Job {
     pub fn resume(&mut self) {
         if self.state == JobState::Paused {
             self.state = JobState::Running;
+            self.end_pause();
+        }
+    }
+
+    /// Fold an ongoing pause into `paused_time` and release the executor.
+    fn end_pause(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_time += paused_at.elapsed();
         }
+        self.pause.resume();
     }
 
     /// Mark the job as completed successfully.
     pub fn complete(&mut self) {
         if !self.state.is_terminal() {
+            self.end_pause();
             self.state = JobState::Completed;
             self.finished_at = Some(Instant::now());
         }
@@ -311,6 +735,7 @@ impl Job {
     /// Mark the job as failed with an error.
     pub fn fail(&mut self, error: impl Into<String>) {
         if !self.state.is_terminal() {
+            self.end_pause();
             self.state = JobState::Failed;
             self.error = Some(error.into());
             self.finished_at = Some(Instant::now());
@@ -321,6 +746,7 @@ impl Job {
     pub fn cancel(&mut self) {
         if !self.state.is_terminal() {
             self.cancellation.cancel();
+            self.end_pause();
             self.state = JobState::Cancelled;
             self.finished_at = Some(Instant::now());
         }
@@ -331,12 +757,17 @@ impl Job {
         self.created_at.elapsed()
     }
 
-    /// Get the running time (from start to finish or now).
+    /// Get the running time (from start to finish or now), excluding time
+    /// spent paused.
     pub fn running_time(&self) -> Option<Duration> {
         self.started_at.map(|start| {
-            self.finished_at
-                .map(|end| end.duration_since(start))
-                .unwrap_or_else(|| start.elapsed())
+            let end = self.finished_at.unwrap_or_else(Instant::now);
+            let current_pause = self
+                .paused_at
+                .map(|at| end.saturating_duration_since(at))
+                .unwrap_or_default();
+            end.duration_since(start)
+                .saturating_sub(self.paused_time + current_pause)
         })
     }
 }
@@ -349,6 +780,7 @@ pub struct JobInfo {
     pub id: JobId,
     pub description: String,
     pub state: JobState,
+    pub priority: Priority,
     pub progress_percent: u8,
     pub items_done: usize,
     pub total_items: usize,
@@ -356,6 +788,7 @@ pub struct JobInfo {
     pub total_bytes: Option<u64>,
     pub current_item: Option<PathBuf>,
     pub speed_bytes_per_sec: Option<u64>,
+    pub speed_limit: Option<u64>,
     pub eta_secs: Option<u64>,
     pub error: Option<String>,
 }
@@ -366,6 +799,7 @@ impl From<&Job> for JobInfo {
             id: job.id,
             description: job.kind.description(),
             state: job.state,
+            priority: job.priority,
             progress_percent: job.progress.percentage_int(),
             items_done: job.progress.items_done,
             total_items: job.progress.total_items,
@@ -373,6 +807,7 @@ impl From<&Job> for JobInfo {
             total_bytes: job.progress.total_bytes,
             current_item: job.progress.current_item.clone(),
             speed_bytes_per_sec: job.progress.speed_bytes_per_sec,
+            speed_limit: job.speed_limit.get(),
             eta_secs: job.progress.eta.map(|d| d.as_secs()),
             error: job.error.clone(),
         }
@@ -436,6 +871,20 @@ mod tests {
             paths: vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")],
         };
         assert!(delete.description().contains("3 items"));
+
+        let extract = JobKind::Extract {
+            archive: PathBuf::from("tools.zip"),
+            entries: vec![],
+            destination: PathBuf::from("dest"),
+        };
+        assert_eq!(extract.description(), "Extracting tools.zip");
+        assert_eq!(extract.item_count(), 1);
+
+        let compress = JobKind::Compress {
+            sources: vec![PathBuf::from("a"), PathBuf::from("b")],
+            destination: PathBuf::from("out.zip"),
+        };
+        assert_eq!(compress.description(), "Compressing 2 items into out.zip");
     }
 
     #[test]
@@ -477,6 +926,40 @@ mod tests {
         assert_eq!(progress.percentage(), 0.5);
     }
 
+    #[test]
+    fn test_progress_eta_from_speed() {
+        let mut progress = Progress::new(1, Some(1000));
+        progress.bytes_done = 400;
+        progress.set_speed(Some(100));
+        assert_eq!(progress.speed_bytes_per_sec, Some(100));
+        assert_eq!(progress.eta, Some(Duration::from_secs(6)));
+
+        progress.set_speed(Some(0));
+        assert_eq!(progress.eta, None);
+    }
+
+    #[test]
+    fn test_speed_estimator_window() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut speed = SpeedEstimator::with_window(Duration::from_secs(2));
+
+        assert_eq!(speed.record(0, start), None);
+        assert_eq!(speed.record(100, at(100)), None);
+        assert_eq!(speed.record(1000, at(1000)), Some(1000));
+
+        // A stall only drags the speed down as it fills the window
+        assert_eq!(speed.record(2000, at(2000)), Some(1000));
+        assert_eq!(speed.record(2000, at(3000)), Some(500));
+        assert_eq!(speed.record(2000, at(4000)), Some(0));
+
+        // Samples closer than the interval are merged
+        speed.record(2100, at(4050));
+        speed.record(2200, at(4080));
+        assert_eq!(speed.samples.len(), 4);
+        assert_eq!(speed.samples.back(), Some(&(at(4080), 2200)));
+    }
+
     #[test]
     fn test_cancellation_token() {
         let token = CancellationToken::new();
@@ -554,6 +1037,96 @@ mod tests {
         assert!(job.cancellation.is_cancelled());
     }
 
+    #[test]
+    fn test_job_pause_sets_token() {
+        let mut job = Job::new(JobKind::CalculateSize {
+            path: PathBuf::from("test"),
+        });
+
+        job.start();
+        job.pause();
+        assert!(job.pause.is_paused());
+        assert!(job.paused_at.is_some());
+
+        job.resume();
+        assert!(!job.pause.is_paused());
+        assert!(job.paused_at.is_none());
+
+        // Cancelling a paused job releases the executor
+        job.pause();
+        job.cancel();
+        assert!(!job.pause.is_paused());
+        assert_eq!(job.state, JobState::Cancelled);
+    }
+
+    #[test]
+    fn test_running_time_excludes_pauses() {
+        let mut job = Job::new(JobKind::CalculateSize {
+            path: PathBuf::from("test"),
+        });
+        let start = Instant::now();
+        job.state = JobState::Running;
+        job.started_at = Some(start);
+        job.paused_time = Duration::from_secs(3);
+        job.finished_at = Some(start + Duration::from_secs(10));
+        assert_eq!(job.running_time(), Some(Duration::from_secs(7)));
+
+        // An ongoing pause counts up to the finish time
+        job.paused_at = Some(start + Duration::from_secs(8));
+        assert_eq!(job.running_time(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_pause_token_wait() {
+        let pause = PauseToken::new();
+        let cancel = CancellationToken::new();
+        assert!(pause.wait_blocking(&cancel).is_ok());
+
+        pause.pause();
+        let waiter = {
+            let pause = pause.clone();
+            let cancel = cancel.clone();
+            std::thread::spawn(move || pause.wait_blocking(&cancel))
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+        pause.resume();
+        assert!(waiter.join().unwrap().is_ok());
+
+        // Cancellation wins over a pause
+        pause.pause();
+        cancel.cancel();
+        assert!(matches!(pause.wait_blocking(&cancel), Err(ZError::Cancelled)));
+    }
+
+    #[test]
+    fn test_speed_limit_throttles() {
+        let limit = SpeedLimit::new();
+        let cancel = CancellationToken::new();
+        assert_eq!(limit.get(), None);
+
+        // Without a limit nothing waits
+        let start = Instant::now();
+        limit.throttle_blocking(1 << 30, &cancel).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // 10 KB at 100 KB/s takes about 100ms
+        limit.set(Some(100_000));
+        assert_eq!(limit.clone().get(), Some(100_000));
+        let start = Instant::now();
+        for _ in 0..10 {
+            limit.throttle_blocking(1_000, &cancel).unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        limit.set(Some(1));
+        cancel.cancel();
+        assert!(matches!(limit.throttle_blocking(1_000, &cancel), Err(ZError::Cancelled)));
+
+        limit.set(Some(0));
+        assert_eq!(limit.get(), None);
+    }
+
     #[test]
     fn test_job_info_from_job() {
         let mut job = Job::new(JobKind::Copy {
@@ -570,9 +1143,34 @@ mod tests {
         assert_eq!(info.id, job.id);
         assert!(info.description.contains("Copying"));
         assert_eq!(info.state, JobState::Running);
+        assert_eq!(info.priority, Priority::Normal);
         assert_eq!(info.progress_percent, 50);
     }
 
+    #[test]
+    fn test_job_category() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let copy = JobKind::Copy {
+            sources: vec![temp.path().to_path_buf()],
+            destination: temp.path().to_path_buf(),
+        };
+        assert_eq!(copy.category(), JobCategory::Transfer);
+
+        let size = JobKind::CalculateSize {
+            path: temp.path().to_path_buf(),
+        };
+        assert_eq!(size.category(), JobCategory::CalculateSize);
+
+        let checksum = JobKind::Checksum {
+            paths: vec![temp.path().join("a"), temp.path().join("b")],
+            algorithm: HashAlgorithm::Md5,
+        };
+        assert_eq!(checksum.category(), JobCategory::Checksum);
+        assert_eq!(checksum.item_count(), 2);
+        assert_eq!(checksum.description(), "Computing MD5 of 2 files");
+        assert!(Priority::High < Priority::Normal && Priority::Normal < Priority::Low);
+    }
+
     #[test]
     fn test_job_stats() {
         let stats = JobStats {
@@ -6,48 +6,135 @@
 //! This crate is platform-agnostic and contains:
 //! - Domain types (`EntryKind`, `EntryMeta`, `DirListing`)
 //! - Sorting and filtering specifications
+//! - File type groups for coloring and grouping listings
 //! - Error types and result aliases
 //! - File system operations
 //! - Navigation state management
 //! - Selection model
 //! - File operations (rename, delete, mkdir)
+//! - Bulk renaming from name patterns
 //! - Job system for async operations
 //! - Configuration management
-//! - Drive enumeration
+//! - Drive enumeration and change detection
 //! - File/folder properties
+//! - Paths, names and small file contents as clipboard text
+//! - Owners and access control lists, and taking ownership
 //! - Directory watching with debouncing
+//! - Recursive search by name, attributes and contents
+//! - Content-based duplicate detection
+//! - File checksums (MD5, SHA-1, SHA-256, xxHash)
+//! - Comparing two files byte by byte or line by line
+//! - Browsing into, extracting from and creating zip archives
+//! - Typed location helpers (env expansion, path completion, history)
+//! - Directory visit history ranked by frecency
+//! - File baskets collecting files from several directories
+//! - Listings from other places than the local file system, such as servers
+//! - Paging through file contents as text or hex, for the built-in viewer
+//! - Loading and saving small text files for the built-in editor
+//! - Converting text files between encodings and line endings
+//! - Plugins adding custom commands that run external programs
 //!
 //! Both the TUI and GUI frontends depend on this crate.
 
+pub mod archive;
+pub mod basket;
+pub mod bulk_rename;
+pub mod compare;
 pub mod config;
+pub mod copy_text;
+pub mod drive_watcher;
 pub mod drives;
+pub mod duplicates;
 pub mod entry;
 pub mod error;
+pub mod file_type;
 pub mod filter;
 pub mod fs;
+pub mod hash;
 pub mod job;
+pub mod location;
 pub mod navigation;
 pub mod operations;
+pub mod plugins;
 pub mod properties;
 pub mod recycle;
 pub mod scheduler;
+pub mod search;
+pub mod security;
 pub mod selection;
 pub mod sort;
+pub mod text_file;
+pub mod viewer;
+pub mod visits;
 pub mod watcher;
 
 // Re-export main types for convenience
-pub use config::{Config, Favorite, SessionState};
-pub use drives::{list_drives, DriveInfo, DriveType};
+pub use archive::{
+    create_archive, extract_archive, is_browsable_archive, list_archive, split_archive_path,
+    CompressReport, ExtractResult,
+};
+pub use basket::{basket_path, split_basket_path, Basket, Baskets, BASKET_PATH_PREFIX};
+pub use bulk_rename::{bulk_rename, RenamePattern, RenamePlan, RenamePreview};
+pub use compare::{
+    compare_files_with_progress, diff_lines, CompareMode, DiffOp, DiffStats, FileComparison,
+    TextDiff, MAX_LINE_COMPARE_SIZE,
+};
+pub use config::{
+    ChecksumAlgorithm, Config, ExecutableOpenBehavior, Favorite, LinkHandling,
+    NotificationsConfig, OpenDirectoryBehavior, SessionState, SortSettings, ToolsConfig,
+    WindowState,
+};
+pub use drive_watcher::{DriveChange, DriveWatcher};
+pub use copy_text::{copy_text, CopyFormat, MAX_COPY_CONTENTS_SIZE};
+pub use drives::{
+    available_space, drive_for_path, drive_type_for_path, is_network_path, is_unc_path, list_drives,
+    list_mapped_drives, list_server_shares, same_volume, supports_alternate_streams, unc_path,
+    unc_server, BitLockerStatus, DriveInfo, DriveType, NetworkShare,
+};
+pub use duplicates::{DuplicateIndex, DuplicateMatch, DuplicateReport};
 pub use entry::{DirListing, EntryAttributes, EntryKind, EntryMeta};
 pub use error::{ZError, ZResult};
+pub use file_type::FileGroup;
 pub use filter::FilterSpec;
-pub use fs::{get_entry_meta, list_directory};
-pub use job::{CancellationToken, Job, JobId, JobInfo, JobKind, JobState, JobStats, Progress};
+pub use fs::{
+    descend_single_children, extended_path, get_entry_meta, list_directory,
+    list_directory_flat_streamed, list_directory_streamed, list_provider_streamed,
+    DirectoryProvider, LISTING_BATCH_SIZE,
+};
+pub use hash::{
+    hash_bytes, hash_file, hash_file_with_progress, hash_files_with_progress, hash_matches,
+    normalize_hash, FileHash, HashAlgorithm,
+};
+pub use job::{
+    CancellationToken, Job, JobCategory, JobId, JobInfo, JobKind, JobState, JobStats, PauseToken,
+    Priority, Progress, SpeedEstimator, SpeedLimit,
+};
+pub use location::{complete_path, expand_env_vars, remember_path, PATH_HISTORY_LIMIT};
 pub use navigation::NavigationState;
-pub use operations::{delete_permanent, mkdir, open_default, rename};
-pub use properties::{calculate_folder_stats, get_properties, FolderStats, Properties};
+pub use operations::{
+    copy_link, create_file, delete_path, delete_permanent, expand_tool_command, find_template,
+    launch_tool, mkdir, open_default, remove_link, rename, reveal_in_explorer, validate_filename,
+    wipe_path, write_file_atomic,
+};
+pub use plugins::{PluginCommand, Plugins};
+pub use properties::{
+    calculate_folder_stats, calculate_folder_stats_with_progress,
+    calculate_multi_properties_with_progress, get_multi_properties, get_properties,
+    list_alternate_streams, set_properties, would_lose_alternate_streams, AlternateStream,
+    FolderStats, MultiProperties, Properties, PropertyChanges,
+};
 pub use recycle::{move_multiple_to_recycle_bin, move_to_recycle_bin};
-pub use scheduler::{Scheduler, SchedulerConfig, SchedulerEvent, SchedulerHandle};
-pub use selection::{ClickModifiers, Selection};
-pub use sort::{SortField, SortOrder, SortSpec};
+pub use scheduler::{
+    ConcurrencyLimit, Scheduler, SchedulerConfig, SchedulerEvent, SchedulerHandle,
+};
+pub use search::{search, SearchQuery, SearchSummary};
+pub use security::{get_security, take_ownership, AccessEntry, AccessLevel, SecurityInfo};
+pub use selection::{wildcard_match, ClickModifiers, Selection};
+pub use sort::{Collation, SortField, SortOrder, SortSpec};
+pub use text_file::{
+    convert_text_file, convert_text_files_with_progress, ConversionOutcome, FileConversion,
+    LineEnding, TextConversion, TextFile, MAX_CONVERT_SIZE,
+};
+pub use viewer::{find_in_file, ContentLine, ContentSearch, FileContent, HexRow, TextEncoding};
+pub use visits::{Visit, VisitHistory, MAX_VISITS};
 pub use watcher::{DirectoryWatcher, WatcherConfig, WatchEvent, WatchEventKind};
@@ -0,0 +1,156 @@
+//! Typed locations.
+//!
+//! Helpers for a location bar where paths are typed or pasted: expanding
+//! `%VARIABLE%` references, completing path components and keeping a
+//! history of the paths that were typed.
+
+use std::fs;
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// Most typed paths kept in the history.
+pub const PATH_HISTORY_LIMIT: usize = 50;
+
+/// Expand `%NAME%` environment variable references, as in `%USERPROFILE%`.
+///
+/// References to unset variables, and a `%` without a closing one, are left
+/// as typed.
+pub fn expand_env_vars(input: &str) -> String {
+    expand_with(input, |name| std::env::var(name).ok())
+}
+
+/// Expand `%NAME%` references with `lookup`.
+fn expand_with(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match (!name.is_empty()).then(|| lookup(name)).flatten() {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                // Keep the `%` and retry from the closing one, which may
+                // open a real reference
+                expanded.push('%');
+                expanded.push_str(name);
+                rest = &after[end..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Complete the last component of a typed path to the directories it could
+/// name.
+///
+/// The text up to the last separator is kept as typed and resolved against
+/// `base` (after expanding environment variables); the part after it is
+/// matched case-insensitively against the names in that directory. Each
+/// completion is the full input with the name and a trailing separator, in
+/// name order. An unreadable directory has no completions.
+pub fn complete_path(input: &str, base: &Path) -> Vec<String> {
+    let split = input.rfind(['\\', '/']).map_or(0, |i| i + 1);
+    let (dir, partial) = input.split_at(split);
+    let directory = base.join(expand_env_vars(dir));
+    let partial = partial.to_lowercase();
+
+    let Ok(read_dir) = fs::read_dir(&directory) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = read_dir
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.to_lowercase().starts_with(&partial))
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+
+    names
+        .into_iter()
+        .map(|name| format!("{dir}{name}{MAIN_SEPARATOR}"))
+        .collect()
+}
+
+/// Put `path` at the front of a typed path `history`, most recent first.
+///
+/// An earlier entry for the same path is dropped and the history is capped
+/// at [`PATH_HISTORY_LIMIT`].
+pub fn remember_path(history: &mut Vec<String>, path: &str) {
+    let trimmed = |p: &str| p.trim_end_matches(['\\', '/']).to_lowercase();
+    let key = trimmed(path);
+    history.retain(|entry| trimmed(entry) != key);
+    history.insert(0, path.to_string());
+    history.truncate(PATH_HISTORY_LIMIT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "USERPROFILE" => Some(r"C:\Users\me".to_string()),
+            "X" => Some("x".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        assert_eq!(expand_with(r"%USERPROFILE%\Downloads", lookup), r"C:\Users\me\Downloads");
+        assert_eq!(expand_with("%X%%X%", lookup), "xx");
+        assert_eq!(expand_with("no vars", lookup), "no vars");
+        assert_eq!(expand_with("%MISSING%", lookup), "%MISSING%");
+        assert_eq!(expand_with("100%", lookup), "100%");
+        assert_eq!(expand_with("50% off %X%", lookup), "50% off x");
+        assert_eq!(expand_with("%%", lookup), "%%");
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let temp = TempDir::new().unwrap();
+        for dir in ["Documents", "downloads", "Music", "Documents/Work"] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        fs::write(temp.path().join("dofile.txt"), b"x").unwrap();
+        let sep = MAIN_SEPARATOR;
+
+        assert_eq!(
+            complete_path("do", temp.path()),
+            [format!("Documents{sep}"), format!("downloads{sep}")]
+        );
+        assert_eq!(complete_path("Documents/w", temp.path()), [format!("Documents/Work{sep}")]);
+        assert_eq!(complete_path("", temp.path()).len(), 3);
+        assert!(complete_path("missing/", temp.path()).is_empty());
+
+        let absolute = format!("{}{sep}mu", temp.path().display());
+        assert_eq!(
+            complete_path(&absolute, Path::new("/elsewhere")),
+            [format!("{}{sep}Music{sep}", temp.path().display())]
+        );
+    }
+
+    #[test]
+    fn test_remember_path() {
+        let mut history = Vec::new();
+        remember_path(&mut history, r"C:\Windows");
+        remember_path(&mut history, r"D:\Games");
+        remember_path(&mut history, r"c:\windows\");
+        assert_eq!(history, [r"c:\windows\", r"D:\Games"]);
+
+        for i in 0..PATH_HISTORY_LIMIT + 5 {
+            remember_path(&mut history, &format!(r"E:\{i}"));
+        }
+        assert_eq!(history.len(), PATH_HISTORY_LIMIT);
+        assert_eq!(history[0], format!(r"E:\{}", PATH_HISTORY_LIMIT + 4));
+    }
+}
@@ -110,6 +110,27 @@ impl NavigationState {
         self.cached_listing = None;
     }
 
+    /// Move to `path` in place of a current directory that no longer exists.
+    ///
+    /// Unlike [`navigate_to`](Self::navigate_to) no history entry is
+    /// recorded, and the missing directory and everything under it is
+    /// dropped from the back and forward history, so going back or forward
+    /// never lands in it again.
+    pub fn replace_missing(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf();
+        let missing = std::mem::replace(&mut self.current_path, path);
+        debug!(from = %missing.display(), to = %self.current_path.display(), "Replacing missing directory");
+
+        for stack in [&mut self.back_stack, &mut self.forward_stack] {
+            stack.retain(|p| !p.starts_with(&missing));
+            stack.dedup();
+            while stack.last() == Some(&self.current_path) {
+                stack.pop();
+            }
+        }
+        self.cached_listing = None;
+    }
+
     /// Go back to the previous directory.
     /// Returns the new current path, or None if back stack is empty.
     #[instrument(skip(self))]
@@ -172,6 +193,25 @@ impl NavigationState {
         }
     }
 
+    /// Go up `levels` parent directories in a single navigation.
+    ///
+    /// Stops at the root if there are fewer ancestors than requested, and
+    /// records one history entry so going back returns to the starting
+    /// directory. Returns None if no movement was possible.
+    #[instrument(skip(self))]
+    pub fn go_up_levels(&mut self, levels: usize) -> Option<&Path> {
+        let target = self
+            .current_path
+            .ancestors()
+            .skip(1)
+            .take(levels)
+            .last()?
+            .to_path_buf();
+        debug!(to = %target.display(), levels, "Going up multiple levels");
+        self.navigate_to(target);
+        Some(&self.current_path)
+    }
+
     /// Refresh the current directory listing.
     pub fn refresh(&mut self) -> ZResult<&DirListing> {
         self.cached_listing = None;
@@ -347,6 +387,29 @@ mod tests {
         assert!(nav.can_go_back()); // Should have history
     }
 
+    #[test]
+    fn test_go_up_levels() {
+        let (root, _, _, _) = setup_nav_dirs();
+        let deep = root.path().join("x").join("y").join("z");
+        fs::create_dir_all(&deep).unwrap();
+        let mut nav = NavigationState::new(&deep);
+
+        let up = nav.go_up_levels(2).map(|p| p.to_path_buf());
+        assert_eq!(up, Some(root.path().join("x")));
+
+        // A single back step returns to where we started
+        nav.go_back();
+        assert_eq!(nav.current_path(), deep);
+        assert!(!nav.can_go_back());
+
+        // Clamp at the filesystem root
+        let top = deep.ancestors().last().unwrap().to_path_buf();
+        assert_eq!(nav.go_up_levels(10_000).map(|p| p.to_path_buf()), Some(top.clone()));
+        assert!(nav.go_up_levels(1).is_none());
+        assert!(nav.go_up_levels(0).is_none());
+        assert_eq!(nav.current_path(), top);
+    }
+
     #[test]
     fn test_navigate_to_same_path() {
         let (_root, dir_a, _, _) = setup_nav_dirs();
@@ -357,6 +420,23 @@ mod tests {
         assert!(!nav.can_go_back()); // No history added
     }
 
+    #[test]
+    fn test_replace_missing_drops_it_from_history() {
+        let root = TempDir::new().unwrap();
+        let doomed = root.path().join("a").join("b");
+        let mut nav = NavigationState::new(root.path());
+        nav.navigate_to(root.path().join("a"));
+        nav.navigate_to(&doomed);
+        nav.navigate_to(doomed.join("c"));
+        nav.go_back();
+
+        nav.replace_missing(root.path().join("a"));
+
+        assert_eq!(nav.current_path(), root.path().join("a"));
+        assert_eq!(nav.back_history(), [root.path().to_path_buf()]);
+        assert!(nav.forward_history().is_empty());
+    }
+
     #[test]
     fn test_history_size_limit() {
         let root = TempDir::new().unwrap();
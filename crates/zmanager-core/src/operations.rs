@@ -1,12 +1,15 @@
-//! File operations: rename, mkdir, open_default
+//! File operations: rename, mkdir, new files, atomic writes, open_default,
+//! reveal in Explorer, delete, wipe, external tools
 //!
 //! This module provides basic file system operations with proper error handling.
 
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::debug;
 
-use crate::{ZError, ZResult};
+use crate::fs::extended_path;
+use crate::{LinkHandling, ZError, ZResult};
 
 /// Rename or move a file/directory from one path to another.
 ///
@@ -30,28 +33,94 @@ pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> ZResult<()> {
     let to = to.as_ref();
 
     debug!(from = %from.display(), to = %to.display(), "Renaming");
+    let (os_from, os_to) = (extended_path(from), extended_path(to));
 
     // Check source exists
-    if !from.exists() {
+    if !os_from.exists() {
         return Err(ZError::NotFound {
             path: from.to_path_buf(),
         });
     }
 
     // Check destination doesn't exist (conflict detection)
-    if to.exists() {
+    if os_to.exists() {
         return Err(ZError::AlreadyExists {
             path: to.to_path_buf(),
         });
     }
 
     // Perform the rename
-    std::fs::rename(from, to).map_err(|e| ZError::from_io(from, e))?;
+    std::fs::rename(&os_from, &os_to).map_err(|e| ZError::from_io(from, e))?;
 
     debug!("Rename successful");
     Ok(())
 }
 
+/// Characters that Windows does not allow in file or folder names.
+const INVALID_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names reserved by Windows, regardless of extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Validate a single file or folder name (not a path).
+///
+/// Rejects empty names, `.`/`..`, path separators, characters Windows forbids,
+/// control characters (including line breaks), trailing dots or spaces, and
+/// reserved device names such as `CON` or `LPT1`.
+///
+/// # Errors
+/// * `ZError::InvalidPath` - The name is not usable, with a human-readable reason
+///
+/// # Example
+/// ```
+/// use zmanager_core::operations::validate_filename;
+/// assert!(validate_filename("report.txt").is_ok());
+/// assert!(validate_filename("a/b").is_err());
+/// ```
+pub fn validate_filename(name: &str) -> ZResult<()> {
+    let invalid = |reason: &str| ZError::InvalidPath {
+        path: PathBuf::from(name),
+        reason: reason.to_string(),
+    };
+
+    if name.trim().is_empty() {
+        return Err(invalid("Name cannot be empty"));
+    }
+
+    if name == "." || name == ".." {
+        return Err(invalid("Name cannot be '.' or '..'"));
+    }
+
+    if name.contains(['\n', '\r']) {
+        return Err(invalid("Name cannot span multiple lines"));
+    }
+
+    if let Some(c) = name.chars().find(|c| c.is_control()) {
+        return Err(invalid(&format!(
+            "Name cannot contain control character U+{:04X}",
+            c as u32
+        )));
+    }
+
+    if let Some(c) = name.chars().find(|c| INVALID_NAME_CHARS.contains(c)) {
+        return Err(invalid(&format!("Name cannot contain '{c}'")));
+    }
+
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(invalid("Name cannot end with a dot or space"));
+    }
+
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return Err(invalid(&format!("'{stem}' is a reserved name")));
+    }
+
+    Ok(())
+}
+
 /// Create a new directory at the specified path.
 ///
 /// Creates parent directories if they don't exist (like `mkdir -p`).
@@ -73,19 +142,145 @@ pub fn mkdir(path: impl AsRef<Path>) -> ZResult<()> {
     let path = path.as_ref();
 
     debug!(path = %path.display(), "Creating directory");
+    let os_path = extended_path(path);
 
-    if path.exists() {
+    if os_path.exists() {
         return Err(ZError::AlreadyExists {
             path: path.to_path_buf(),
         });
     }
 
-    std::fs::create_dir_all(path).map_err(|e| ZError::from_io(path, e))?;
+    std::fs::create_dir_all(&os_path).map_err(|e| ZError::from_io(path, e))?;
 
     debug!("Directory created");
     Ok(())
 }
 
+/// Create a new file, empty or as a copy of `template`.
+///
+/// # Arguments
+/// * `path` - Path of the new file
+/// * `template` - File whose contents the new file starts with
+///
+/// # Errors
+/// * `ZError::AlreadyExists` - Path already exists
+/// * `ZError::NotFound` - Parent directory or template does not exist
+/// * `ZError::PermissionDenied` - Insufficient permissions
+/// * `ZError::Io` - Other I/O errors
+///
+/// # Example
+/// ```no_run
+/// use zmanager_core::operations::create_file;
+/// create_file("notes.txt", None).unwrap();
+/// ```
+pub fn create_file(path: impl AsRef<Path>, template: Option<&Path>) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), ?template, "Creating file");
+
+    let mut source = match template {
+        Some(template) => {
+            let file = std::fs::File::open(extended_path(template));
+            Some(file.map_err(|e| ZError::from_io(template, e))?)
+        }
+        None => None,
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(extended_path(path))
+        .map_err(|e| ZError::from_io(path, e))?;
+    if let Some(source) = &mut source {
+        std::io::copy(source, &mut file).map_err(|e| ZError::from_io(path, e))?;
+    }
+
+    debug!("File created");
+    Ok(())
+}
+
+/// Replace the contents of the file at `path` with `data`, or create it.
+///
+/// The data goes to a temporary file next to `path` that then takes its
+/// place, so a failed write leaves the old contents untouched. The file
+/// keeps its permissions and, on Windows, its hidden and system attributes.
+///
+/// # Errors
+/// * `ZError::PermissionDenied` - The file is read-only
+/// * `ZError::Io` - The data could not be written or moved into place
+pub fn write_file_atomic(path: impl AsRef<Path>, data: &[u8]) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), len = data.len(), "Writing file atomically");
+
+    let original = std::fs::metadata(extended_path(path)).ok();
+    if original.as_ref().is_some_and(|meta| meta.permissions().readonly()) {
+        return Err(ZError::PermissionDenied {
+            path: path.to_path_buf(),
+        });
+    }
+    let attributes = original.as_ref().and_then(|_| crate::get_entry_meta(path).ok());
+
+    let name = path.file_name().ok_or_else(|| ZError::InvalidPath {
+        path: path.to_path_buf(),
+        reason: "No file name".to_string(),
+    })?;
+    let temp = path.with_file_name(format!(".{}.zmanager-save", name.to_string_lossy()));
+    let written = (|| {
+        let mut file = std::fs::File::create(extended_path(&temp))?;
+        file.write_all(data)?;
+        if let Some(meta) = &original {
+            file.set_permissions(meta.permissions())?;
+        }
+        file.sync_all()
+    })();
+    if let Err(e) = written.and_then(|_| std::fs::rename(extended_path(&temp), extended_path(path)))
+    {
+        let _ = std::fs::remove_file(extended_path(&temp));
+        return Err(ZError::from_io(path, e));
+    }
+
+    // Attributes belong to the file that was replaced; put them back
+    let attributes = attributes
+        .map(|meta| meta.attributes)
+        .filter(|attributes| cfg!(windows) && (attributes.hidden || attributes.system));
+    if let Some(attributes) = attributes {
+        let changes = crate::PropertyChanges {
+            hidden: Some(attributes.hidden),
+            system: Some(attributes.system),
+            ..Default::default()
+        };
+        if let Err(e) = crate::set_properties(path, &changes) {
+            debug!(error = %e, "Could not restore attributes");
+        }
+    }
+
+    debug!("File written");
+    Ok(())
+}
+
+/// Find the template for a new file named `name` in `templates_dir`.
+///
+/// A template is any file with the same extension as `name`, compared
+/// without case, so `templates\Report.docx` is used for `budget.docx`. With
+/// several candidates the first by name wins. Names without an extension
+/// and missing template directories have no template.
+pub fn find_template(templates_dir: &Path, name: &str) -> Option<PathBuf> {
+    let extension = Path::new(name).extension()?.to_string_lossy().to_lowercase();
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(templates_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.to_string_lossy().to_lowercase() == extension)
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
 /// Open a file or directory with its default application.
 ///
 /// Uses Windows ShellExecute via the `explorer` command.
@@ -135,6 +330,147 @@ pub fn open_default(path: impl AsRef<Path>) -> ZResult<()> {
     Ok(())
 }
 
+/// Open the folder containing a file or directory in Windows Explorer, with
+/// the item selected.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::Io` - Failed to launch Explorer
+///
+/// # Example
+/// ```no_run
+/// use zmanager_core::operations::reveal_in_explorer;
+/// reveal_in_explorer("C:\\Users\\me\\report.pdf").unwrap();
+/// ```
+pub fn reveal_in_explorer(path: impl AsRef<Path>) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), "Revealing in Explorer");
+
+    if !path.exists() {
+        return Err(ZError::NotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
+    // Explorer parses its own command line, so the path is quoted inside
+    // the switch rather than passed as a separately escaped argument
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path.display()))
+            .spawn()
+            .map_err(|e| ZError::io(path, e))?;
+    }
+
+    #[cfg(not(windows))]
+    {
+        // Fallback for non-Windows: open the containing folder
+        let parent = path.parent().unwrap_or(path);
+        Command::new("xdg-open")
+            .arg(parent)
+            .spawn()
+            .map_err(|e| ZError::io(path, e))?;
+    }
+
+    Ok(())
+}
+
+/// Split an external tool command template into a program and its arguments.
+///
+/// Words are separated by whitespace, and double quotes group words that
+/// contain spaces. `{path}` and `{dir}` are replaced inside each word after
+/// splitting, so a path with spaces stays a single argument.
+///
+/// # Errors
+/// * `ZError::Config` - The template is empty or has an unclosed quote
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use zmanager_core::operations::expand_tool_command;
+/// let args = expand_tool_command("wt.exe -d {dir}", Path::new("a.txt"), Path::new("My Files"));
+/// assert_eq!(args.unwrap(), ["wt.exe", "-d", "My Files"]);
+/// ```
+pub fn expand_tool_command(template: &str, path: &Path, dir: &Path) -> ZResult<Vec<String>> {
+    Ok(split_tool_command(template)?
+        .iter()
+        .map(|word| expand_placeholders(word, path, dir))
+        .collect())
+}
+
+/// Split a tool command template into its words, placeholders untouched.
+///
+/// # Errors
+/// * `ZError::Config` - The template is empty or has an unclosed quote
+pub(crate) fn split_tool_command(template: &str) -> ZResult<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for c in template.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err(ZError::Config {
+            message: format!("Unclosed quote in tool command: {template}"),
+        });
+    }
+    words.extend(word);
+    if words.is_empty() {
+        return Err(ZError::Config {
+            message: "Tool command is empty".to_string(),
+        });
+    }
+    Ok(words)
+}
+
+/// Replace `{path}` and `{dir}` in one word of a tool command template.
+pub(crate) fn expand_placeholders(word: &str, path: &Path, dir: &Path) -> String {
+    word.replace("{path}", &path.to_string_lossy()).replace("{dir}", &dir.to_string_lossy())
+}
+
+/// Launch an external tool, such as a terminal or an editor, in `dir`.
+///
+/// See [`expand_tool_command`] for the template syntax. On Windows the tool
+/// gets a console of its own instead of sharing the caller's.
+///
+/// # Errors
+/// * `ZError::Config` - The template is invalid
+/// * `ZError::NotFound` - The program does not exist
+/// * `ZError::Io` - Failed to launch process
+pub fn launch_tool(template: &str, path: &Path, dir: &Path) -> ZResult<()> {
+    let args = expand_tool_command(template, path, dir)?;
+    spawn_tool(&args, dir)
+}
+
+/// Start the program `args[0]` with the remaining arguments in `dir`, in a
+/// console of its own on Windows.
+pub(crate) fn spawn_tool(args: &[String], dir: &Path) -> ZResult<()> {
+    debug!(command = ?args, dir = %dir.display(), "Launching external tool");
+
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).current_dir(dir);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+        command.creation_flags(CREATE_NEW_CONSOLE);
+    }
+
+    command.spawn().map_err(|e| ZError::from_io(&args[0], e))?;
+    Ok(())
+}
+
 /// Delete a file or directory permanently (bypasses Recycle Bin).
 ///
 /// For Recycle Bin deletion, use `recycle::move_to_recycle_bin()` instead.
@@ -152,21 +488,22 @@ pub fn delete_permanent(path: impl AsRef<Path>, recursive: bool) -> ZResult<()>
     let path = path.as_ref();
 
     debug!(path = %path.display(), recursive, "Permanently deleting");
+    let os_path = extended_path(path);
 
-    if !path.exists() {
+    if !os_path.exists() {
         return Err(ZError::NotFound {
             path: path.to_path_buf(),
         });
     }
 
-    let result = if path.is_dir() {
+    let result = if os_path.is_dir() {
         if recursive {
-            std::fs::remove_dir_all(path)
+            std::fs::remove_dir_all(&os_path)
         } else {
-            std::fs::remove_dir(path)
+            std::fs::remove_dir(&os_path)
         }
     } else {
-        std::fs::remove_file(path)
+        std::fs::remove_file(&os_path)
     };
 
     result.map_err(|e| {
@@ -187,6 +524,163 @@ pub fn delete_permanent(path: impl AsRef<Path>, recursive: bool) -> ZResult<()>
     Ok(())
 }
 
+/// Delete a file, directory or link permanently, treating links as `links`
+/// says.
+///
+/// Directories are deleted with their contents. Links inside a deleted
+/// directory are removed by themselves, never followed.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::PermissionDenied` - Insufficient permissions
+/// * `ZError::Io` - Other I/O errors
+pub fn delete_path(path: impl AsRef<Path>, links: LinkHandling) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), ?links, "Deleting");
+
+    let metadata =
+        std::fs::symlink_metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    if !metadata.file_type().is_symlink() {
+        return delete_permanent(path, true);
+    }
+
+    if links == LinkHandling::Follow {
+        // A broken link has nothing to follow
+        if let Ok(target) = std::fs::canonicalize(extended_path(path)) {
+            delete_permanent(&target, true)?;
+        }
+    }
+    remove_link(path)
+}
+
+/// Remove a symlink or junction itself, leaving its target alone.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::Io` - Other I/O errors
+pub fn remove_link(path: impl AsRef<Path>) -> ZResult<()> {
+    let path = path.as_ref();
+    let os_path = extended_path(path);
+
+    // Directory links (including junctions) are removed like directories
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::FileTypeExt;
+
+        let metadata =
+            std::fs::symlink_metadata(&os_path).map_err(|e| ZError::from_io(path, e))?;
+        if metadata.file_type().is_symlink_dir() {
+            return std::fs::remove_dir(&os_path).map_err(|e| ZError::from_io(path, e));
+        }
+    }
+
+    std::fs::remove_file(&os_path).map_err(|e| ZError::from_io(path, e))
+}
+
+/// Size of the zero-filled chunks [`wipe_path`] overwrites files with.
+const WIPE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Overwrite a file, or every file under a directory, with zeros and then
+/// delete it permanently.
+///
+/// Each file is overwritten in place and flushed to disk before anything is
+/// removed, so an error leaves the item in place, possibly already zeroed.
+/// `on_progress` is called with the total bytes overwritten so far. Links
+/// are never followed while overwriting; `links` decides what the delete
+/// afterwards removes, as for [`delete_path`].
+///
+/// Overwriting in place does not reach old copies of the data that SSDs or
+/// copy-on-write file systems keep elsewhere.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::PermissionDenied` - A file can't be opened for writing
+/// * `ZError::Io` - Other I/O errors
+pub fn wipe_path(
+    path: impl AsRef<Path>,
+    links: LinkHandling,
+    mut on_progress: impl FnMut(u64),
+) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), "Wiping");
+
+    let mut wiped = 0;
+    overwrite_tree(&extended_path(path), &mut wiped, &mut on_progress)?;
+    delete_path(path, links)?;
+
+    debug!(bytes = wiped, "Wiped successfully");
+    Ok(())
+}
+
+/// Overwrite every file under `path`, skipping links. `path` is already
+/// extended, and so are the entries read from it.
+fn overwrite_tree(path: &Path, wiped: &mut u64, on_progress: &mut dyn FnMut(u64)) -> ZResult<()> {
+    let metadata = std::fs::symlink_metadata(path).map_err(|e| ZError::from_io(path, e))?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| ZError::from_io(path, e))? {
+            let entry = entry.map_err(|e| ZError::from_io(path, e))?;
+            overwrite_tree(&entry.path(), wiped, on_progress)?;
+        }
+        return Ok(());
+    }
+
+    let io_err = |e| ZError::from_io(path, e);
+    let mut file = std::fs::OpenOptions::new().write(true).open(path).map_err(io_err)?;
+    let zeros = vec![0u8; WIPE_CHUNK_SIZE];
+    let mut remaining = metadata.len();
+    while remaining > 0 {
+        let chunk = remaining.min(WIPE_CHUNK_SIZE as u64);
+        file.write_all(&zeros[..chunk as usize]).map_err(io_err)?;
+        remaining -= chunk;
+        *wiped += chunk;
+        on_progress(*wiped);
+    }
+    file.sync_all().map_err(io_err)
+}
+
+/// Create a link at `destination` pointing where the link at `source` does.
+///
+/// The target is copied as stored, so relative links stay relative. On
+/// Windows, junctions are recreated as directory symlinks.
+///
+/// # Errors
+/// * `ZError::NotFound` - Source does not exist
+/// * `ZError::AlreadyExists` - Destination already exists
+/// * `ZError::PermissionDenied` - Not allowed to create links
+/// * `ZError::Io` - Other I/O errors
+pub fn copy_link(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> ZResult<()> {
+    let source = source.as_ref();
+    let destination = destination.as_ref();
+
+    debug!(source = %source.display(), destination = %destination.display(), "Copying link");
+
+    let (os_source, os_destination) = (extended_path(source), extended_path(destination));
+    let target = std::fs::read_link(&os_source).map_err(|e| ZError::from_io(source, e))?;
+
+    #[cfg(windows)]
+    let result = {
+        use std::os::windows::fs::{symlink_dir, symlink_file, FileTypeExt};
+
+        let metadata =
+            std::fs::symlink_metadata(&os_source).map_err(|e| ZError::from_io(source, e))?;
+        if metadata.file_type().is_symlink_dir() {
+            symlink_dir(&target, &os_destination)
+        } else {
+            symlink_file(&target, &os_destination)
+        }
+    };
+
+    #[cfg(not(windows))]
+    let result = std::os::unix::fs::symlink(&target, &os_destination);
+
+    result.map_err(|e| ZError::from_io(destination, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,6 +761,25 @@ mod tests {
         assert!(path.is_dir());
     }
 
+    #[test]
+    fn test_operations_on_deep_paths() {
+        let temp = TempDir::new().unwrap();
+        let mut deep = temp.path().to_path_buf();
+        while deep.as_os_str().len() <= 300 {
+            deep.push("a_rather_long_folder_name_to_get_past_max_path");
+        }
+
+        mkdir(&deep).unwrap();
+        create_file(deep.join("old.txt"), None).unwrap();
+        rename(deep.join("old.txt"), deep.join("new.txt")).unwrap();
+        assert!(extended_path(deep.join("new.txt")).is_file());
+
+        delete_permanent(deep.join("new.txt"), false).unwrap();
+        let top = temp.path().join("a_rather_long_folder_name_to_get_past_max_path");
+        delete_path(&top, LinkHandling::default()).unwrap();
+        assert!(!top.exists());
+    }
+
     #[test]
     fn test_mkdir_already_exists() {
         let temp = TempDir::new().unwrap();
@@ -278,6 +791,62 @@ mod tests {
         assert!(matches!(result, Err(ZError::AlreadyExists { .. })));
     }
 
+    #[test]
+    fn test_create_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("notes.txt");
+
+        create_file(&path, None).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"");
+
+        let result = create_file(&path, None);
+        assert!(matches!(result, Err(ZError::AlreadyExists { .. })));
+    }
+
+    #[test]
+    fn test_write_file_atomic() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("notes.txt");
+
+        write_file_atomic(&path, b"first").unwrap();
+        write_file_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        // Nothing is left behind next to it
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 1);
+
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+        let result = write_file_atomic(&path, b"third");
+        assert!(matches!(result, Err(ZError::PermissionDenied { .. })));
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+    }
+
+    #[test]
+    fn test_create_file_from_template() {
+        let temp = TempDir::new().unwrap();
+        let templates = temp.path().join("templates");
+        std::fs::create_dir(&templates).unwrap();
+        std::fs::write(templates.join("Report.MD"), "# Title\n").unwrap();
+        std::fs::write(templates.join("sheet.csv"), "a,b\n").unwrap();
+
+        let template = find_template(&templates, "weekly.md").unwrap();
+        assert_eq!(template, templates.join("Report.MD"));
+        assert_eq!(find_template(&templates, "weekly.txt"), None);
+        assert_eq!(find_template(&templates, "README"), None);
+        assert_eq!(find_template(&temp.path().join("missing"), "a.md"), None);
+
+        let path = temp.path().join("weekly.md");
+        create_file(&path, Some(&template)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Title\n");
+
+        // A missing template leaves no file behind
+        let other = temp.path().join("other.md");
+        let result = create_file(&other, Some(&templates.join("gone.md")));
+        assert!(matches!(result, Err(ZError::NotFound { .. })));
+        assert!(!other.exists());
+    }
+
     #[test]
     fn test_delete_permanent_file() {
         let temp = TempDir::new().unwrap();
@@ -336,5 +905,130 @@ mod tests {
         assert!(matches!(result, Err(ZError::NotFound { .. })));
     }
 
-    // Note: open_default is not tested as it launches external processes
+    #[cfg(unix)]
+    #[test]
+    fn test_delete_path_link_handling() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("target");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("file.txt"), "content").unwrap();
+        let link = temp.path().join("link");
+
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        delete_path(&link, LinkHandling::CopyLink).unwrap();
+        assert!(link.symlink_metadata().is_err());
+        assert!(target.join("file.txt").exists());
+
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        delete_path(&link, LinkHandling::Follow).unwrap();
+        assert!(link.symlink_metadata().is_err());
+        assert!(!target.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_link_keeps_relative_target() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("file.txt"), "content").unwrap();
+        let link = temp.path().join("link");
+        std::os::unix::fs::symlink("file.txt", &link).unwrap();
+
+        let copy = temp.path().join("copy");
+        copy_link(&link, &copy).unwrap();
+        assert_eq!(std::fs::read_link(&copy).unwrap(), PathBuf::from("file.txt"));
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "content");
+
+        let result = copy_link(&link, &copy);
+        assert!(matches!(result, Err(ZError::AlreadyExists { .. })));
+
+        remove_link(&copy).unwrap();
+        assert!(copy.symlink_metadata().is_err());
+        assert!(temp.path().join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_validate_filename_accepts_normal_names() {
+        assert!(validate_filename("report.txt").is_ok());
+        assert!(validate_filename(".gitignore").is_ok());
+        assert!(validate_filename("My Document (1).docx").is_ok());
+        assert!(validate_filename("console.log").is_ok());
+    }
+
+    #[test]
+    fn test_validate_filename_rejects_invalid_names() {
+        for name in ["", "   ", ".", "..", "a/b", "a\\b", "what?", "x:y", "trailing.", "trailing "] {
+            let result = validate_filename(name);
+            assert!(
+                matches!(result, Err(ZError::InvalidPath { .. })),
+                "expected {name:?} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_filename_rejects_multiline() {
+        let result = validate_filename("first\nsecond");
+        match result {
+            Err(ZError::InvalidPath { reason, .. }) => assert!(reason.contains("multiple lines")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_filename_rejects_reserved_names() {
+        assert!(validate_filename("CON").is_err());
+        assert!(validate_filename("nul.txt").is_err());
+        assert!(validate_filename("Lpt1").is_err());
+    }
+
+    #[test]
+    fn test_wipe_path() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("secrets");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.txt"), [b'a'; 100]).unwrap();
+        std::fs::write(dir.join("nested").join("b.txt"), [b'b'; 50]).unwrap();
+
+        let mut reports = Vec::new();
+        wipe_path(&dir, LinkHandling::CopyLink, |bytes| reports.push(bytes)).unwrap();
+
+        assert!(!dir.exists());
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports.last(), Some(&150));
+    }
+
+    #[test]
+    fn test_wipe_path_not_found() {
+        let temp = TempDir::new().unwrap();
+        let result = wipe_path(temp.path().join("missing"), LinkHandling::CopyLink, |_| {});
+        assert!(matches!(result, Err(ZError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_expand_tool_command() {
+        let path = Path::new(r"C:\My Files\notes.txt");
+        let dir = Path::new(r"C:\My Files");
+
+        let args = expand_tool_command(r#"cmd.exe /K "echo {path}""#, path, dir).unwrap();
+        assert_eq!(args, ["cmd.exe", "/K", r"echo C:\My Files\notes.txt"]);
+
+        let args = expand_tool_command("  wt.exe  -d {dir} ", path, dir).unwrap();
+        assert_eq!(args, ["wt.exe", "-d", r"C:\My Files"]);
+
+        // An empty quoted word is still an argument
+        let args = expand_tool_command(r#"start "" {path}"#, path, dir).unwrap();
+        assert_eq!(args, ["start", "", r"C:\My Files\notes.txt"]);
+    }
+
+    #[test]
+    fn test_expand_tool_command_rejects_invalid_templates() {
+        let path = Path::new("a.txt");
+        for template in ["", "   ", r#"code "{path}"#] {
+            let result = expand_tool_command(template, path, path);
+            assert!(matches!(result, Err(ZError::Config { .. })), "{template:?}");
+        }
+    }
+
+    // Note: open_default, reveal_in_explorer and launch_tool are not tested as they
+    // launch external processes
 }
@@ -0,0 +1,293 @@
+//! Plugins adding custom commands.
+//!
+//! A plugin is a TOML file in the plugins directory
+//! ([`Config::plugins_dir`](crate::Config::plugins_dir)), or a folder there
+//! with a `plugin.toml` and the files the plugin ships, declaring one or more
+//! commands:
+//!
+//! ```toml
+//! [[commands]]
+//! name = "Optimize PNG"
+//! command = "oxipng.exe -o 4 {path}"
+//! patterns = ["*.png"]
+//! key = "alt+1"
+//! ```
+//!
+//! Commands are templates like those of external tools (see
+//! [`expand_tool_command`](crate::operations::expand_tool_command)) and run
+//! once for each item they apply to. In a plugin folder, words of the
+//! template naming a file in the folder are resolved to it, so a plugin can
+//! ship its own program, or a WebAssembly module run by a runtime on the
+//! PATH (`command = "wasmtime run resize.wasm {path}"`).
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::operations::{expand_placeholders, spawn_tool, split_tool_command};
+use crate::{wildcard_match, Config, ZResult};
+
+/// A custom command added by a plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCommand {
+    /// Name shown in menus.
+    pub name: String,
+    /// Command template; `{path}` is the item and `{dir}` the directory the
+    /// pane shows.
+    pub command: String,
+    /// Wildcards of the file names the command applies to; empty matches
+    /// every file.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Whether the command also applies to folders.
+    #[serde(default)]
+    pub folders: bool,
+    /// Key chord running the command, e.g. `alt+1`.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Folder of the plugin, holding the files it ships; `None` for a plugin
+    /// file directly in the plugins directory.
+    #[serde(skip)]
+    pub plugin_dir: Option<PathBuf>,
+}
+
+impl PluginCommand {
+    /// Check whether the command applies to `path`, a folder if `is_dir`.
+    pub fn applies_to(&self, path: &Path, is_dir: bool) -> bool {
+        if is_dir {
+            return self.folders;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.patterns.is_empty() || self.patterns.iter().any(|p| wildcard_match(p, &name))
+    }
+
+    /// The program and arguments running the command on `path`.
+    ///
+    /// # Errors
+    /// * `ZError::Config` - The command template is invalid
+    pub fn args(&self, path: &Path, dir: &Path) -> ZResult<Vec<String>> {
+        // Bundled files are looked up before the placeholders are filled in,
+        // so an item that happens to share a bundled file's name stays as is
+        Ok(split_tool_command(&self.command)?
+            .iter()
+            .map(|word| match self.bundled_file(word) {
+                Some(file) => file.to_string_lossy().into_owned(),
+                None => expand_placeholders(word, path, dir),
+            })
+            .collect())
+    }
+
+    /// The file in the plugin's folder named by the template word `word`.
+    fn bundled_file(&self, word: &str) -> Option<PathBuf> {
+        let plugin_dir = self.plugin_dir.as_ref()?;
+        if word.contains("{path}") || word.contains("{dir}") || Path::new(word).is_absolute() {
+            return None;
+        }
+        let file = plugin_dir.join(word);
+        file.is_file().then_some(file)
+    }
+
+    /// Run the command on `path`, in `dir`.
+    ///
+    /// # Errors
+    /// * `ZError::Config` - The command template is invalid
+    /// * `ZError::NotFound` - The program does not exist
+    /// * `ZError::Io` - Failed to launch process
+    pub fn run(&self, path: &Path, dir: &Path) -> ZResult<()> {
+        debug!(command = %self.name, path = %path.display(), "Running plugin command");
+        spawn_tool(&self.args(path, dir)?, dir)
+    }
+}
+
+/// Name of the plugin file in a plugin folder.
+const PLUGIN_FILE: &str = "plugin.toml";
+
+/// Contents of a plugin file.
+#[derive(Debug, Default, Deserialize)]
+struct PluginManifest {
+    #[serde(default)]
+    commands: Vec<PluginCommand>,
+}
+
+/// Commands of all installed plugins.
+#[derive(Debug, Clone, Default)]
+pub struct Plugins {
+    commands: Vec<PluginCommand>,
+}
+
+impl Plugins {
+    /// Load the plugins in the default plugins directory.
+    ///
+    /// Returns the plugins along with any problems found, as
+    /// [`load_from`](Self::load_from) does.
+    pub fn load() -> (Self, Vec<String>) {
+        match Config::plugins_dir() {
+            Ok(dir) => Self::load_from(&dir),
+            Err(e) => {
+                warn!("No plugins directory: {}", e);
+                (Self::default(), Vec::new())
+            }
+        }
+    }
+
+    /// Load every `*.toml` plugin file and every folder with a
+    /// `plugin.toml` in `dir`, in name order.
+    ///
+    /// Returns the plugins along with any problems found: files that don't
+    /// parse and commands without a name or command. Faulty entries are left
+    /// out; a missing directory simply has no plugins.
+    pub fn load_from(dir: &Path) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut plugins: Vec<(PathBuf, Option<PathBuf>)> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter_map(|path| {
+                    if path.is_dir() {
+                        let file = path.join(PLUGIN_FILE);
+                        file.is_file().then_some((file, Some(path)))
+                    } else if path.is_file() && path.extension().is_some_and(|ext| ext == "toml") {
+                        Some((path, None))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            Err(_) => return (Self::default(), problems),
+        };
+        plugins.sort();
+
+        let mut commands = Vec::new();
+        for (file, plugin_dir) in plugins {
+            let manifest = std::fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|text| {
+                    toml::from_str::<PluginManifest>(&text).map_err(|e| e.to_string())
+                });
+            let manifest = match manifest {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    problems.push(format!("Invalid plugin {}: {}", file.display(), e));
+                    continue;
+                }
+            };
+            for mut command in manifest.commands {
+                if command.name.trim().is_empty() || command.command.trim().is_empty() {
+                    problems.push(format!(
+                        "Plugin {} has a command without a name or command",
+                        file.display()
+                    ));
+                    continue;
+                }
+                command.plugin_dir = plugin_dir.clone();
+                commands.push(command);
+            }
+        }
+        debug!(count = commands.len(), dir = %dir.display(), "Loaded plugin commands");
+
+        (Self { commands }, problems)
+    }
+
+    /// All plugin commands, in load order.
+    pub fn commands(&self) -> &[PluginCommand] {
+        &self.commands
+    }
+
+    /// The command at `index`.
+    pub fn get(&self, index: usize) -> Option<&PluginCommand> {
+        self.commands.get(index)
+    }
+
+    /// Check if no plugin added any commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_loads_commands_and_reports_faulty_plugins() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("images.toml"),
+            r#"
+[[commands]]
+name = "Optimize PNG"
+command = "oxipng -o 4 {path}"
+patterns = ["*.png"]
+key = "alt+1"
+
+[[commands]]
+name = ""
+command = "nothing"
+"#,
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("broken.toml"), "[[commands]\nname =").unwrap();
+        std::fs::write(temp.path().join("readme.txt"), "not a plugin").unwrap();
+        let resize = temp.path().join("resize");
+        std::fs::create_dir(&resize).unwrap();
+        std::fs::write(
+            resize.join("plugin.toml"),
+            "[[commands]]\nname = \"Resize\"\ncommand = \"wasmtime run resize.wasm {path}\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(temp.path().join("empty")).unwrap();
+
+        let (plugins, problems) = Plugins::load_from(temp.path());
+        assert_eq!(plugins.commands().len(), 2);
+        let command = plugins.get(0).unwrap();
+        assert_eq!(command.name, "Optimize PNG");
+        assert_eq!(command.key.as_deref(), Some("alt+1"));
+        assert_eq!(command.plugin_dir, None);
+        let command = plugins.get(1).unwrap();
+        assert_eq!(command.name, "Resize");
+        assert_eq!(command.plugin_dir.as_deref(), Some(resize.as_path()));
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("broken.toml"));
+
+        let (plugins, problems) = Plugins::load_from(&temp.path().join("missing"));
+        assert!(plugins.is_empty() && problems.is_empty());
+    }
+
+    #[test]
+    fn test_applies_by_pattern_and_resolves_bundled_files() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("resize.wasm"), b"\0asm").unwrap();
+        let command = PluginCommand {
+            name: "Resize".to_string(),
+            command: "wasmtime run resize.wasm {path}".to_string(),
+            patterns: vec!["*.jpg".to_string(), "*.png".to_string()],
+            folders: false,
+            key: None,
+            plugin_dir: Some(temp.path().to_path_buf()),
+        };
+
+        assert!(command.applies_to(Path::new("Photo.JPG"), false));
+        assert!(!command.applies_to(Path::new("notes.txt"), false));
+        assert!(!command.applies_to(Path::new("photos"), true));
+
+        let photo = temp.path().join("photo.png");
+        let args = command.args(&photo, temp.path()).unwrap();
+        let wasm = temp.path().join("resize.wasm").to_string_lossy().into_owned();
+        let photo = photo.to_string_lossy().into_owned();
+        assert_eq!(args, ["wasmtime", "run", wasm.as_str(), photo.as_str()]);
+
+        // An item named like a bundled file is passed on as given
+        let args = command.args(Path::new("resize.wasm"), temp.path()).unwrap();
+        assert_eq!(args, ["wasmtime", "run", wasm.as_str(), "resize.wasm"]);
+
+        // Without a folder of its own a plugin bundles nothing
+        let command = PluginCommand {
+            plugin_dir: None,
+            ..command
+        };
+        let args = command.args(Path::new("a.png"), temp.path()).unwrap();
+        assert_eq!(args, ["wasmtime", "run", "resize.wasm", "a.png"]);
+    }
+}
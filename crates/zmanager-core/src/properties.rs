@@ -9,8 +9,9 @@ use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
+use crate::drives::supports_alternate_streams;
 use crate::entry::EntryKind;
-use crate::{ZError, ZResult};
+use crate::{CancellationToken, ZError, ZResult};
 
 /// Detailed properties for a file or folder.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +48,18 @@ pub struct Properties {
     pub extension: Option<String>,
     /// MIME type (if determinable).
     pub mime_type: Option<String>,
+    /// NTFS alternate data streams (for files).
+    #[serde(default)]
+    pub alternate_streams: Vec<AlternateStream>,
+}
+
+/// An NTFS alternate data stream attached to a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlternateStream {
+    /// Stream name, without the leading colon or the `:$DATA` type suffix.
+    pub name: String,
+    /// Stream size in bytes.
+    pub size: u64,
 }
 
 impl Properties {
@@ -210,6 +223,13 @@ pub fn get_properties(path: impl AsRef<Path>) -> ZResult<Properties> {
     // Simple MIME type detection based on extension
     let mime_type = extension.as_ref().and_then(|ext| guess_mime_type(ext));
 
+    // Streams are extra; failing to list them shouldn't fail the whole lookup
+    let alternate_streams = if metadata.is_file() {
+        list_alternate_streams(path).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
     Ok(Properties {
         path: path.to_path_buf(),
         name,
@@ -227,13 +247,295 @@ pub fn get_properties(path: impl AsRef<Path>) -> ZResult<Properties> {
         link_target,
         extension,
         mime_type,
+        alternate_streams,
     })
 }
 
+/// Changes to an item's attributes and timestamps.
+///
+/// Fields left as `None` are not changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PropertyChanges {
+    /// Set or clear the read-only attribute.
+    pub readonly: Option<bool>,
+    /// Set or clear the hidden attribute.
+    pub hidden: Option<bool>,
+    /// Set or clear the system attribute.
+    pub system: Option<bool>,
+    /// New creation time (Windows only).
+    pub created: Option<SystemTime>,
+    /// New modification time.
+    pub modified: Option<SystemTime>,
+}
+
+impl PropertyChanges {
+    /// Check if nothing would be changed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Change the attributes and timestamps of a file or folder.
+///
+/// Timestamps are changed before attributes, so a file can be made read-only
+/// in the same call. Outside Windows only the read-only flag and the
+/// modification time can be changed; asking for anything else fails with
+/// `ZError::InvalidOperation` before the item is touched.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::PermissionDenied` - Insufficient permissions
+/// * `ZError::InvalidOperation` - The change isn't supported on this platform
+/// * `ZError::Io` - Other I/O errors
+pub fn set_properties(path: impl AsRef<Path>, changes: &PropertyChanges) -> ZResult<()> {
+    let path = path.as_ref();
+
+    debug!(path = %path.display(), ?changes, "Setting properties");
+
+    let metadata = std::fs::metadata(path).map_err(|e| ZError::from_io(path, e))?;
+
+    #[cfg(not(windows))]
+    {
+        // Hidden is a naming convention here and there is no system flag
+        let unsupported = |reason: &str| ZError::InvalidOperation {
+            operation: "set properties".to_string(),
+            reason: reason.to_string(),
+        };
+        let hidden = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        if changes.hidden.is_some_and(|h| h != hidden) {
+            return Err(unsupported("the hidden attribute can only be changed on Windows"));
+        }
+        if changes.system == Some(true) {
+            return Err(unsupported("the system attribute only exists on Windows"));
+        }
+        if changes.created.is_some() {
+            return Err(unsupported("the creation time can only be changed on Windows"));
+        }
+    }
+
+    if changes.created.is_some() || changes.modified.is_some() {
+        set_file_times(path, changes)?;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use std::os::windows::fs::MetadataExt;
+
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn SetFileAttributesW(lpFileName: *const u16, dwFileAttributes: u32) -> i32;
+        }
+
+        const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+        const FILE_ATTRIBUTE_NORMAL: u32 = 0x80;
+        // Attributes SetFileAttributesW accepts; the rest are read-only
+        const SETTABLE: u32 = 0x1 | 0x2 | 0x4 | 0x20 | 0x80 | 0x100 | 0x1000 | 0x2000;
+
+        let old = metadata.file_attributes() & SETTABLE;
+        let mut attrs = old;
+        for (flag, value) in [
+            (FILE_ATTRIBUTE_READONLY, changes.readonly),
+            (FILE_ATTRIBUTE_HIDDEN, changes.hidden),
+            (FILE_ATTRIBUTE_SYSTEM, changes.system),
+        ] {
+            match value {
+                Some(true) => attrs |= flag,
+                Some(false) => attrs &= !flag,
+                None => {}
+            }
+        }
+        if attrs != old {
+            // No attributes at all has to be spelled as "normal"
+            let attrs = if attrs == 0 { FILE_ATTRIBUTE_NORMAL } else { attrs };
+            let wide: Vec<u16> = crate::fs::extended_path(path)
+                .as_os_str()
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
+            if unsafe { SetFileAttributesW(wide.as_ptr(), attrs) } == 0 {
+                return Err(ZError::from_io(path, std::io::Error::last_os_error()));
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    if let Some(readonly) = changes.readonly {
+        let mut permissions = metadata.permissions();
+        if permissions.readonly() != readonly {
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(readonly);
+            std::fs::set_permissions(path, permissions).map_err(|e| ZError::from_io(path, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the creation and modification times of a file or folder.
+fn set_file_times(path: &Path, changes: &PropertyChanges) -> ZResult<()> {
+    let mut times = std::fs::FileTimes::new();
+    if let Some(modified) = changes.modified {
+        times = times.set_modified(modified);
+    }
+
+    #[cfg(windows)]
+    let file = {
+        use std::os::windows::fs::{FileTimesExt, OpenOptionsExt};
+
+        const FILE_WRITE_ATTRIBUTES: u32 = 0x100;
+        // Needed to open folders
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+
+        if let Some(created) = changes.created {
+            times = times.set_created(created);
+        }
+        // Writing attributes is allowed on read-only files
+        std::fs::OpenOptions::new()
+            .access_mode(FILE_WRITE_ATTRIBUTES)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)
+    };
+
+    #[cfg(not(windows))]
+    let file = std::fs::File::open(path);
+
+    file.and_then(|file| file.set_times(times))
+        .map_err(|e| ZError::from_io(path, e))
+}
+
+/// List the alternate data streams of a file.
+///
+/// The unnamed main stream isn't included. Filesystems without named streams
+/// report none.
+#[cfg(windows)]
+pub fn list_alternate_streams(path: impl AsRef<Path>) -> ZResult<Vec<AlternateStream>> {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+
+    #[repr(C)]
+    struct Win32FindStreamData {
+        stream_size: i64,
+        stream_name: [u16; 260 + 36], // MAX_PATH + 36
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn FindFirstStreamW(
+            lpFileName: *const u16,
+            InfoLevel: i32,
+            lpFindStreamData: *mut c_void,
+            dwFlags: u32,
+        ) -> isize;
+        fn FindNextStreamW(hFindStream: isize, lpFindStreamData: *mut c_void) -> i32;
+        fn FindClose(hFindFile: isize) -> i32;
+    }
+
+    const FIND_STREAM_INFO_STANDARD: i32 = 0;
+    const INVALID_HANDLE_VALUE: isize = -1;
+    const ERROR_HANDLE_EOF: i32 = 38;
+    // Returned by filesystems without stream support, like FAT
+    const ERROR_INVALID_PARAMETER: i32 = 87;
+
+    let path = path.as_ref();
+    let wide: Vec<u16> = crate::fs::extended_path(path)
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut data = Win32FindStreamData {
+        stream_size: 0,
+        stream_name: [0; 296],
+    };
+    let data_ptr = &mut data as *mut Win32FindStreamData as *mut c_void;
+
+    let handle = unsafe { FindFirstStreamW(wide.as_ptr(), FIND_STREAM_INFO_STANDARD, data_ptr, 0) };
+    if handle == INVALID_HANDLE_VALUE {
+        let error = std::io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(ERROR_HANDLE_EOF | ERROR_INVALID_PARAMETER) => Ok(Vec::new()),
+            _ => Err(ZError::from_io(path, error)),
+        };
+    }
+
+    let mut streams = Vec::new();
+    loop {
+        let len = data
+            .stream_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(data.stream_name.len());
+        let raw = String::from_utf16_lossy(&data.stream_name[..len]);
+        if let Some(name) = stream_name(&raw) {
+            streams.push(AlternateStream {
+                name: name.to_string(),
+                size: data.stream_size as u64,
+            });
+        }
+        if unsafe { FindNextStreamW(handle, data_ptr) } == 0 {
+            break;
+        }
+    }
+    unsafe { FindClose(handle) };
+
+    Ok(streams)
+}
+
+/// List the alternate data streams of a file (non-Windows fallback).
+///
+/// Alternate data streams are an NTFS feature, so there are never any.
+#[cfg(not(windows))]
+pub fn list_alternate_streams(path: impl AsRef<Path>) -> ZResult<Vec<AlternateStream>> {
+    let path = path.as_ref();
+    std::fs::metadata(path).map_err(|e| ZError::from_io(path, e))?;
+    Ok(Vec::new())
+}
+
+/// Extract the name from a raw stream name like `:Zone.Identifier:$DATA`.
+///
+/// Returns None for the unnamed main stream (`::$DATA`).
+#[cfg_attr(not(windows), allow(dead_code))]
+fn stream_name(raw: &str) -> Option<&str> {
+    let name = raw.strip_prefix(':')?;
+    let name = name.rsplit_once(':').map_or(name, |(name, _kind)| name);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Check if copying `sources` to `destination` would drop alternate data
+/// streams, because a source file has some and the destination filesystem
+/// can't store them.
+///
+/// Only the given files are checked, not the contents of given folders, so
+/// this stays cheap enough to ask before every transfer.
+pub fn would_lose_alternate_streams(sources: &[PathBuf], destination: impl AsRef<Path>) -> bool {
+    supports_alternate_streams(destination) == Some(false)
+        && sources.iter().any(|source| {
+            source.is_file()
+                && list_alternate_streams(source).is_ok_and(|streams| !streams.is_empty())
+        })
+}
+
 /// Calculate folder size and item counts.
 ///
 /// This can be slow for large directories, so it should be run async.
 pub fn calculate_folder_stats(path: impl AsRef<Path>) -> ZResult<FolderStats> {
+    calculate_folder_stats_with_progress(path, &CancellationToken::new(), |_| {})
+}
+
+/// Calculate folder size and item counts, reporting the running totals.
+///
+/// `on_progress` is called with the totals so far after each folder has been
+/// read, and `cancel` is checked between folders. Unreadable subfolders are
+/// skipped; only an unreadable `path` is an error. Links are counted but not
+/// followed, so link cycles can't make the walk run forever.
+pub fn calculate_folder_stats_with_progress(
+    path: impl AsRef<Path>,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&FolderStats),
+) -> ZResult<FolderStats> {
     let path = path.as_ref();
 
     debug!(path = %path.display(), "Calculating folder stats");
@@ -245,7 +547,46 @@ pub fn calculate_folder_stats(path: impl AsRef<Path>) -> ZResult<FolderStats> {
     }
 
     let mut stats = FolderStats::default();
-    calculate_folder_stats_recursive(path, &mut stats)?;
+    let mut pending = vec![path.to_path_buf()];
+    let mut is_root = true;
+
+    while let Some(dir) = pending.pop() {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if is_root => return Err(ZError::from_io(&dir, e)),
+            Err(_) => continue, // Skip inaccessible folders
+        };
+        is_root = false;
+
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stats.folder_count += 1;
+                pending.push(entry.path());
+            } else if file_type.is_symlink() {
+                // Count the link itself as what it points to, without descending
+                match entry.path().metadata() {
+                    Ok(m) if m.is_dir() => stats.folder_count += 1,
+                    Ok(m) => {
+                        stats.file_count += 1;
+                        stats.total_size += m.len();
+                    }
+                    Err(_) => {}
+                }
+            } else if let Ok(metadata) = entry.metadata() {
+                stats.file_count += 1;
+                stats.total_size += metadata.len();
+            }
+        }
+
+        on_progress(&stats);
+    }
 
     debug!(
         path = %path.display(),
@@ -258,29 +599,6 @@ pub fn calculate_folder_stats(path: impl AsRef<Path>) -> ZResult<FolderStats> {
     Ok(stats)
 }
 
-fn calculate_folder_stats_recursive(path: &Path, stats: &mut FolderStats) -> ZResult<()> {
-    let entries = std::fs::read_dir(path).map_err(|e| ZError::from_io(path, e))?;
-
-    for entry in entries.flatten() {
-        let entry_path = entry.path();
-        let metadata = match entry.metadata() {
-            Ok(m) => m,
-            Err(_) => continue, // Skip inaccessible entries
-        };
-
-        if metadata.is_dir() {
-            stats.folder_count += 1;
-            // Recursively process subdirectory
-            let _ = calculate_folder_stats_recursive(&entry_path, stats);
-        } else if metadata.is_file() {
-            stats.file_count += 1;
-            stats.total_size += metadata.len();
-        }
-    }
-
-    Ok(())
-}
-
 /// Statistics about a folder's contents.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FolderStats {
@@ -314,6 +632,131 @@ pub fn get_multiple_properties(paths: &[PathBuf]) -> Vec<ZResult<Properties>> {
     paths.iter().map(get_properties).collect()
 }
 
+/// Combined properties of several items.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiProperties {
+    /// Number of items asked about.
+    pub item_count: usize,
+    /// Selected items that are files.
+    pub selected_files: usize,
+    /// Selected items that are folders (or links to folders).
+    pub selected_folders: usize,
+    /// Combined size of the selected files and the files in selected folders.
+    pub total_size: u64,
+    /// Files, both selected and inside selected folders.
+    pub file_count: usize,
+    /// Folders, both selected and inside selected folders.
+    pub folder_count: usize,
+    /// Whether every item is read-only; `None` if only some are.
+    pub readonly: Option<bool>,
+    /// Whether every item is hidden; `None` if only some are.
+    pub hidden: Option<bool>,
+    /// Whether every item is a system file; `None` if only some are.
+    pub system: Option<bool>,
+    /// Whether every item has the archive flag; `None` if only some have.
+    pub archive: Option<bool>,
+    /// Oldest modification time among the items.
+    pub earliest_modified: Option<SystemTime>,
+    /// Newest modification time among the items.
+    pub latest_modified: Option<SystemTime>,
+    /// Items whose properties couldn't be read.
+    pub unreadable: usize,
+}
+
+impl MultiProperties {
+    /// Get a human-readable size string.
+    pub fn size_display(&self) -> String {
+        format_size(self.total_size)
+    }
+
+    /// Get a summary string (e.g., "10 files, 3 folders").
+    pub fn contents_summary(&self) -> String {
+        format!("{} files, {} folders", self.file_count, self.folder_count)
+    }
+
+    /// Format the modification time range for display.
+    pub fn modified_range_display(&self) -> Option<String> {
+        let format = |t: SystemTime| {
+            let datetime: chrono::DateTime<chrono::Local> = t.into();
+            datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+        };
+        let (earliest, latest) = (self.earliest_modified?, self.latest_modified?);
+        if earliest == latest {
+            Some(format(earliest))
+        } else {
+            Some(format!("{} to {}", format(earliest), format(latest)))
+        }
+    }
+}
+
+/// Combine the properties of `paths`, without looking inside folders.
+///
+/// Items that can't be read are counted in
+/// [`MultiProperties::unreadable`] and otherwise left out.
+pub fn get_multi_properties(paths: &[PathBuf]) -> MultiProperties {
+    let (items, unreadable): (Vec<_>, Vec<_>) =
+        paths.iter().map(get_properties).partition(Result::is_ok);
+    let items: Vec<Properties> = items.into_iter().flatten().collect();
+
+    // Files have a size; folders and links to folders don't
+    let selected_files = items.iter().filter(|p| p.size.is_some()).count();
+    let common = |flag: fn(&Properties) -> bool| {
+        let first = flag(items.first()?);
+        items.iter().all(|p| flag(p) == first).then_some(first)
+    };
+
+    MultiProperties {
+        item_count: paths.len(),
+        selected_files,
+        selected_folders: items.len() - selected_files,
+        total_size: items.iter().filter_map(|p| p.size).sum(),
+        file_count: selected_files,
+        folder_count: items.len() - selected_files,
+        readonly: common(|p| p.readonly),
+        hidden: common(|p| p.hidden),
+        system: common(|p| p.system),
+        archive: common(|p| p.archive),
+        earliest_modified: items.iter().filter_map(|p| p.modified).min(),
+        latest_modified: items.iter().filter_map(|p| p.modified).max(),
+        unreadable: unreadable.len(),
+    }
+}
+
+/// Combine the properties of `paths`, adding up the contents of folders.
+///
+/// `on_progress` is called with the totals once the items themselves have
+/// been read and then as each folder is walked; `cancel` is checked between
+/// folders. Folders whose contents can't be read add nothing to the totals.
+pub fn calculate_multi_properties_with_progress(
+    paths: &[PathBuf],
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&MultiProperties),
+) -> ZResult<MultiProperties> {
+    debug!(items = paths.len(), "Calculating properties of several items");
+
+    let mut totals = get_multi_properties(paths);
+    on_progress(&totals);
+
+    // Links to folders are counted but not followed
+    let folders = paths.iter().filter(|p| std::fs::symlink_metadata(p).is_ok_and(|m| m.is_dir()));
+    for folder in folders {
+        let before = totals.clone();
+        let result = calculate_folder_stats_with_progress(folder, cancel, |stats| {
+            totals.total_size = before.total_size + stats.total_size;
+            totals.file_count = before.file_count + stats.file_count;
+            totals.folder_count = before.folder_count + stats.folder_count;
+            on_progress(&totals);
+        });
+        match result {
+            Ok(_) => {}
+            Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+            Err(e) => debug!(path = %folder.display(), error = %e, "Skipping folder contents"),
+        }
+    }
+
+    Ok(totals)
+}
+
 /// Guess MIME type from file extension.
 fn guess_mime_type(extension: &str) -> Option<String> {
     let ext = extension.to_lowercase();
@@ -390,6 +833,139 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_multi_properties() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.txt");
+        let b = temp.path().join("b.txt");
+        let folder = temp.path().join("folder");
+        std::fs::write(&a, [0u8; 100]).unwrap();
+        std::fs::write(&b, [0u8; 20]).unwrap();
+        std::fs::create_dir_all(folder.join("sub")).unwrap();
+        std::fs::write(folder.join("sub").join("c.txt"), [0u8; 5]).unwrap();
+        let mut perms = std::fs::metadata(&a).unwrap().permissions();
+        perms.set_readonly(true);
+        std::fs::set_permissions(&a, perms).unwrap();
+
+        let paths = vec![a.clone(), b.clone(), folder, temp.path().join("missing")];
+        let quick = get_multi_properties(&paths);
+        assert_eq!(quick.item_count, 4);
+        assert_eq!((quick.selected_files, quick.selected_folders), (2, 1));
+        assert_eq!(quick.total_size, 120);
+        assert_eq!(quick.unreadable, 1);
+        assert_eq!(quick.readonly, None);
+        assert_eq!(quick.system, Some(false));
+        assert!(quick.earliest_modified <= quick.latest_modified);
+
+        let mut updates = 0;
+        let full =
+            calculate_multi_properties_with_progress(&paths, &CancellationToken::new(), |_| {
+                updates += 1;
+            })
+            .unwrap();
+        assert!(updates >= 2);
+        assert_eq!(full.total_size, 125);
+        assert_eq!((full.file_count, full.folder_count), (3, 2));
+        assert_eq!(full.contents_summary(), "3 files, 2 folders");
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = calculate_multi_properties_with_progress(&paths, &cancel, |_| {});
+        assert!(matches!(result, Err(ZError::Cancelled)));
+
+        // Read-only files can't be cleaned up on Windows otherwise
+        let mut perms = std::fs::metadata(&a).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        std::fs::set_permissions(&a, perms).unwrap();
+    }
+
+    #[test]
+    fn test_set_properties() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("file.txt");
+        std::fs::write(&file, "content").unwrap();
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+
+        let changes = PropertyChanges {
+            readonly: Some(true),
+            modified: Some(modified),
+            ..Default::default()
+        };
+        set_properties(&file, &changes).unwrap();
+        let props = get_properties(&file).unwrap();
+        assert!(props.readonly);
+        assert_eq!(props.modified, Some(modified));
+
+        // Times can still be changed on a read-only file
+        let later = modified + std::time::Duration::from_secs(60);
+        let changes = PropertyChanges {
+            modified: Some(later),
+            ..Default::default()
+        };
+        set_properties(&file, &changes).unwrap();
+        assert_eq!(get_properties(&file).unwrap().modified, Some(later));
+
+        let changes = PropertyChanges {
+            readonly: Some(false),
+            ..Default::default()
+        };
+        set_properties(&file, &changes).unwrap();
+        assert!(!get_properties(&file).unwrap().readonly);
+
+        assert!(PropertyChanges::default().is_empty());
+        let missing = temp.path().join("missing");
+        assert!(set_properties(&missing, &changes).unwrap_err().is_not_found());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_set_hidden_and_created() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("folder");
+        std::fs::create_dir(&dir).unwrap();
+        let created = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000);
+
+        let changes = PropertyChanges {
+            hidden: Some(true),
+            created: Some(created),
+            ..Default::default()
+        };
+        set_properties(&dir, &changes).unwrap();
+        let props = get_properties(&dir).unwrap();
+        assert!(props.hidden);
+        assert_eq!(props.created, Some(created));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_set_properties_rejects_windows_only_changes() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("file.txt");
+        std::fs::write(&file, "content").unwrap();
+
+        for changes in [
+            PropertyChanges {
+                hidden: Some(true),
+                ..Default::default()
+            },
+            PropertyChanges {
+                created: Some(SystemTime::now()),
+                ..Default::default()
+            },
+        ] {
+            let result = set_properties(&file, &changes);
+            assert!(matches!(result, Err(ZError::InvalidOperation { .. })));
+        }
+        // Asking for what's already true is fine
+        let changes = PropertyChanges {
+            hidden: Some(false),
+            system: Some(false),
+            ..Default::default()
+        };
+        set_properties(&file, &changes).unwrap();
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(0), "0 bytes");
@@ -451,6 +1027,29 @@ mod tests {
         assert_eq!(stats.total_size, 60);
     }
 
+    #[test]
+    fn test_folder_stats_progress_and_cancel() {
+        let temp = TempDir::new().unwrap();
+        let subdir = temp.path().join("a").join("b");
+        std::fs::create_dir_all(&subdir).unwrap();
+        std::fs::write(subdir.join("file.txt"), "0123456789").unwrap();
+
+        let mut updates = Vec::new();
+        let stats = calculate_folder_stats_with_progress(
+            temp.path(),
+            &CancellationToken::new(),
+            |s| updates.push(s.total_size),
+        )
+        .unwrap();
+        assert_eq!(stats.total_size, 10);
+        assert_eq!(updates, vec![0, 0, 10]);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = calculate_folder_stats_with_progress(temp.path(), &token, |_| {});
+        assert!(matches!(result, Err(ZError::Cancelled)));
+    }
+
     #[test]
     fn test_folder_stats_not_dir() {
         let temp = TempDir::new().unwrap();
@@ -480,12 +1079,35 @@ mod tests {
             link_target: None,
             extension: Some("txt".to_string()),
             mime_type: Some("text/plain".to_string()),
+            alternate_streams: Vec::new(),
         };
 
         assert_eq!(props.size_display(), "5.00 MB");
         assert_eq!(props.attributes_display(), "RHA");
     }
 
+    #[test]
+    fn test_stream_name() {
+        assert_eq!(stream_name(":Zone.Identifier:$DATA"), Some("Zone.Identifier"));
+        assert_eq!(stream_name(":notes.txt:$DATA"), Some("notes.txt"));
+        assert_eq!(stream_name("::$DATA"), None);
+        assert_eq!(stream_name(""), None);
+    }
+
+    #[test]
+    fn test_list_alternate_streams() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("file.txt");
+        std::fs::write(&file, "content").unwrap();
+
+        // A fresh file only has its main stream
+        assert!(list_alternate_streams(&file).unwrap().is_empty());
+        assert!(get_properties(&file).unwrap().alternate_streams.is_empty());
+
+        let result = list_alternate_streams(temp.path().join("missing.txt"));
+        assert!(matches!(result, Err(ZError::NotFound { .. })));
+    }
+
     #[test]
     fn test_guess_mime_type() {
         assert_eq!(
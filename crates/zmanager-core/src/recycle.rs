@@ -6,6 +6,7 @@
 use std::path::Path;
 use tracing::debug;
 
+use crate::fs::extended_path;
 use crate::{ZError, ZResult};
 
 /// Move a file or directory to the Windows Recycle Bin.
@@ -19,6 +20,7 @@ use crate::{ZError, ZResult};
 /// # Errors
 /// * `ZError::NotFound` - Path does not exist
 /// * `ZError::PermissionDenied` - Insufficient permissions
+/// * `ZError::InvalidOperation` - Path is too long for the Recycle Bin
 /// * `ZError::Windows` - Windows API error
 ///
 /// # Example
@@ -31,7 +33,7 @@ pub fn move_to_recycle_bin(path: impl AsRef<Path>) -> ZResult<()> {
 
     debug!(path = %path.display(), "Moving to Recycle Bin");
 
-    if !path.exists() {
+    if !extended_path(path).exists() {
         return Err(ZError::NotFound {
             path: path.to_path_buf(),
         });
@@ -50,7 +52,7 @@ pub fn move_to_recycle_bin(path: impl AsRef<Path>) -> ZResult<()> {
         std::fs::create_dir_all(&trash_dir).map_err(|e| ZError::io(&trash_dir, e))?;
 
         let dest = trash_dir.join(path.file_name().unwrap_or_default());
-        std::fs::rename(path, &dest).map_err(|e| ZError::from_io(path, e))?;
+        std::fs::rename(extended_path(path), &dest).map_err(|e| ZError::from_io(path, e))?;
     }
 
     debug!("Moved to Recycle Bin successfully");
@@ -84,6 +86,19 @@ fn move_to_recycle_bin_windows(path: &Path) -> ZResult<()> {
     const FOF_NOCONFIRMATION: u16 = 0x0010; // Don't prompt user
     const FOF_NOERRORUI: u16 = 0x0400; // Don't show error UI
     const FOF_SILENT: u16 = 0x0004; // Don't show progress
+    const MAX_PATH: usize = 260;
+
+    // The shell neither takes `\\?\` paths nor anything past MAX_PATH
+    if path.as_os_str().len() >= MAX_PATH {
+        return Err(ZError::InvalidOperation {
+            operation: "move to Recycle Bin".to_string(),
+            reason: format!(
+                "'{}' is longer than {} characters; delete it permanently instead",
+                path.display(),
+                MAX_PATH - 1
+            ),
+        });
+    }
 
     #[link(name = "shell32")]
     unsafe extern "system" {
@@ -176,6 +191,27 @@ mod tests {
         assert!(!dir.exists());
     }
 
+    #[test]
+    fn test_recycle_bin_deep_path() {
+        let temp = TempDir::new().unwrap();
+        let mut dir = temp.path().to_path_buf();
+        while dir.as_os_str().len() <= 300 {
+            dir.push("a_rather_long_folder_name_to_get_past_max_path");
+        }
+        std::fs::create_dir_all(extended_path(&dir)).unwrap();
+        let path = dir.join("deep_to_delete.txt");
+        std::fs::write(extended_path(&path), "content").unwrap();
+
+        let result = move_to_recycle_bin(&path);
+        #[cfg(windows)]
+        assert!(matches!(result, Err(ZError::InvalidOperation { .. })));
+        #[cfg(not(windows))]
+        {
+            result.unwrap();
+            assert!(!path.exists());
+        }
+    }
+
     #[test]
     fn test_recycle_bin_not_found() {
         let temp = TempDir::new().unwrap();
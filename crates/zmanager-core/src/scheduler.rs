@@ -9,7 +9,9 @@ use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
-use crate::job::{Job, JobId, JobInfo, JobKind, JobState, JobStats, Progress};
+use crate::job::{
+    Job, JobCategory, JobId, JobInfo, JobKind, JobState, JobStats, Priority, Progress,
+};
 
 /// Configuration for the job scheduler.
 #[derive(Debug, Clone)]
@@ -20,6 +22,9 @@ pub struct SchedulerConfig {
     pub progress_channel_size: usize,
     /// Maximum number of completed jobs to keep in history.
     pub max_history: usize,
+    /// Limits for particular job categories. Categories without an entry
+    /// are only bound by `max_concurrent_jobs`.
+    pub category_limits: HashMap<JobCategory, ConcurrencyLimit>,
 }
 
 impl Default for SchedulerConfig {
@@ -28,10 +33,24 @@ impl Default for SchedulerConfig {
             max_concurrent_jobs: 2,
             progress_channel_size: 256,
             max_history: 100,
+            category_limits: HashMap::from([
+                // Two copies between the same pair of disks only slow each other down
+                (JobCategory::CrossVolumeTransfer, ConcurrencyLimit::Max(1)),
+                (JobCategory::CalculateSize, ConcurrencyLimit::Unlimited),
+            ]),
         }
     }
 }
 
+/// How many jobs of one category may run at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyLimit {
+    /// At most this many, each still taking one of the `max_concurrent_jobs` slots.
+    Max(usize),
+    /// Any number, without taking a slot.
+    Unlimited,
+}
+
 /// Events emitted by the scheduler.
 #[derive(Debug, Clone)]
 pub enum SchedulerEvent {
@@ -51,6 +70,10 @@ pub enum SchedulerEvent {
     JobPaused(JobId),
     /// A job was resumed.
     JobResumed(JobId),
+    /// A job's priority changed.
+    JobPriorityChanged(JobId, Priority),
+    /// A job's bandwidth limit changed (bytes per second, None for unlimited).
+    JobSpeedLimitChanged(JobId, Option<u64>),
 }
 
 /// Commands sent to the scheduler.
@@ -64,6 +87,19 @@ pub enum SchedulerCommand {
     PauseJob(JobId),
     /// Resume a paused job.
     ResumeJob(JobId),
+    /// Change a job's priority.
+    SetPriority(JobId, Priority),
+    /// Change a job's bandwidth limit.
+    SetSpeedLimit(JobId, Option<u64>),
+    /// Report progress for a running job.
+    UpdateProgress(JobId, Progress),
+    /// Mark a job as completed.
+    CompleteJob(JobId),
+    /// Mark a job as failed with an error.
+    FailJob(JobId, String),
+    /// Mark a job as failed because some of its items failed, with the jobs
+    /// that would redo just those items.
+    FailItems(JobId, String, Vec<JobKind>),
     /// Clear completed/failed/cancelled jobs from history.
     ClearHistory,
     /// Shutdown the scheduler.
@@ -81,7 +117,13 @@ pub struct SchedulerHandle {
 impl SchedulerHandle {
     /// Submit a new job to the scheduler.
     pub async fn submit(&self, kind: JobKind) -> JobId {
-        let job = Job::new(kind);
+        self.submit_with_priority(kind, Priority::default()).await
+    }
+
+    /// Submit a new job with the given priority.
+    pub async fn submit_with_priority(&self, kind: JobKind, priority: Priority) -> JobId {
+        let mut job = Job::new(kind);
+        job.priority = priority;
         let id = job.id;
 
         // Add to local state first
@@ -131,6 +173,96 @@ impl SchedulerHandle {
             .is_ok()
     }
 
+    /// Change the priority of a job that hasn't finished.
+    ///
+    /// Waiting jobs are reordered on the next scheduling pass; a running
+    /// job keeps running.
+    pub async fn set_priority(&self, id: JobId, priority: Priority) -> bool {
+        let finished = self.jobs.read().await.get(&id).is_none_or(|job| job.state.is_terminal());
+        if finished {
+            return false;
+        }
+
+        self.command_tx
+            .send(SchedulerCommand::SetPriority(id, priority))
+            .await
+            .is_ok()
+    }
+
+    /// Change the bandwidth limit of a job that hasn't finished, in bytes
+    /// per second; `None` lifts it.
+    ///
+    /// A running copy picks the new limit up with its next chunk.
+    pub async fn set_speed_limit(&self, id: JobId, bytes_per_sec: Option<u64>) -> bool {
+        let finished = self.jobs.read().await.get(&id).is_none_or(|job| job.state.is_terminal());
+        if finished {
+            return false;
+        }
+
+        self.command_tx
+            .send(SchedulerCommand::SetSpeedLimit(id, bytes_per_sec))
+            .await
+            .is_ok()
+    }
+
+    /// Report progress for a running job (called by job executors).
+    pub async fn report_progress(&self, id: JobId, progress: Progress) {
+        let _ = self
+            .command_tx
+            .send(SchedulerCommand::UpdateProgress(id, progress))
+            .await;
+    }
+
+    /// Mark a job as completed (called by job executors).
+    pub async fn complete(&self, id: JobId) {
+        let _ = self.command_tx.send(SchedulerCommand::CompleteJob(id)).await;
+    }
+
+    /// Mark a job as failed (called by job executors).
+    pub async fn fail(&self, id: JobId, error: impl Into<String>) {
+        let _ = self
+            .command_tx
+            .send(SchedulerCommand::FailJob(id, error.into()))
+            .await;
+    }
+
+    /// Mark a job as failed because some of its items failed (called by job
+    /// executors).
+    ///
+    /// `retry` are the jobs that redo just the failed items, queued by
+    /// [`resubmit_failed`](Self::resubmit_failed).
+    pub async fn fail_items(&self, id: JobId, error: impl Into<String>, retry: Vec<JobKind>) {
+        let _ = self
+            .command_tx
+            .send(SchedulerCommand::FailItems(id, error.into(), retry))
+            .await;
+    }
+
+    /// Queue again just the items a failed job didn't finish, skipping the
+    /// ones that succeeded.
+    ///
+    /// Returns the ids of the new jobs; empty if the job has nothing to
+    /// retry.
+    pub async fn resubmit_failed(&self, id: JobId) -> Vec<JobId> {
+        let retry = match self.jobs.read().await.get(&id) {
+            Some(job) if job.state == JobState::Failed => job.retry.clone(),
+            _ => return Vec::new(),
+        };
+        let mut ids = Vec::with_capacity(retry.len());
+        for kind in retry {
+            ids.push(self.submit(kind).await);
+        }
+        ids
+    }
+
+    /// Get a job for execution.
+    ///
+    /// The clone shares the job's cancellation and pause tokens, so an
+    /// executor running it follows cancel/pause requests made here.
+    pub async fn job(&self, id: JobId) -> Option<Job> {
+        self.jobs.read().await.get(&id).cloned()
+    }
+
     /// Get information about a specific job.
     pub async fn get_job(&self, id: JobId) -> Option<JobInfo> {
         self.jobs.read().await.get(&id).map(JobInfo::from)
@@ -189,7 +321,10 @@ pub struct Scheduler {
     #[allow(dead_code)] // Kept for future internal command dispatch
     command_tx: mpsc::Sender<SchedulerCommand>,
     event_tx: broadcast::Sender<SchedulerEvent>,
-    running_count: usize,
+    /// Category of every known job, worked out once when it is added.
+    categories: HashMap<JobId, JobCategory>,
+    /// Paused jobs waiting for a free slot to resume, oldest request first.
+    resume_queue: Vec<JobId>,
 }
 
 impl Scheduler {
@@ -205,7 +340,8 @@ impl Scheduler {
             command_rx,
             command_tx: command_tx.clone(),
             event_tx: event_tx.clone(),
-            running_count: 0,
+            categories: HashMap::new(),
+            resume_queue: Vec::new(),
         };
 
         let handle = SchedulerHandle {
@@ -242,6 +378,29 @@ impl Scheduler {
                 SchedulerCommand::ResumeJob(id) => {
                     self.handle_resume(id).await;
                 }
+                SchedulerCommand::SetPriority(id, priority) => {
+                    self.handle_set_priority(id, priority).await;
+                }
+                SchedulerCommand::SetSpeedLimit(id, limit) => {
+                    self.handle_set_speed_limit(id, limit).await;
+                }
+                SchedulerCommand::UpdateProgress(id, progress) => {
+                    self.update_progress(id, progress).await;
+                }
+                SchedulerCommand::CompleteJob(id) => {
+                    self.complete_job(id).await;
+                }
+                SchedulerCommand::FailJob(id, error) => {
+                    self.fail_job(id, error).await;
+                }
+                SchedulerCommand::FailItems(id, error, retry) => {
+                    let mut jobs = self.jobs.write().await;
+                    if let Some(job) = jobs.get_mut(&id).filter(|job| !job.state.is_terminal()) {
+                        job.retry = retry;
+                    }
+                    drop(jobs);
+                    self.fail_job(id, error).await;
+                }
                 SchedulerCommand::ClearHistory => {
                     self.handle_clear_history().await;
                 }
@@ -262,6 +421,7 @@ impl Scheduler {
         let id = job.id;
         debug!(job_id = %id, "Adding job to queue");
 
+        self.categories.insert(id, job.kind.category());
         self.jobs.write().await.insert(id, job);
         let _ = self.event_tx.send(SchedulerEvent::JobAdded(id));
     }
@@ -271,14 +431,10 @@ impl Scheduler {
 
         if let Some(job) = jobs.get_mut(&id) {
             if !job.state.is_terminal() {
-                let was_running = job.state == JobState::Running;
                 job.cancel();
+                self.resume_queue.retain(|queued| *queued != id);
                 let _ = self.event_tx.send(SchedulerEvent::JobCancelled(id));
 
-                if was_running {
-                    self.running_count = self.running_count.saturating_sub(1);
-                }
-
                 info!(job_id = %id, "Job cancelled");
             }
         }
@@ -289,10 +445,13 @@ impl Scheduler {
 
         if let Some(job) = jobs.get_mut(&id) {
             if job.state == JobState::Running {
+                // The executor parks on the pause token, freeing its slot
                 job.pause();
-                self.running_count = self.running_count.saturating_sub(1);
                 let _ = self.event_tx.send(SchedulerEvent::JobPaused(id));
                 debug!(job_id = %id, "Job paused");
+            } else if job.state == JobState::Paused {
+                // Pausing again withdraws a queued resume
+                self.resume_queue.retain(|queued| *queued != id);
             }
         }
     }
@@ -300,20 +459,50 @@ impl Scheduler {
     async fn handle_resume(&mut self, id: JobId) {
         let mut jobs = self.jobs.write().await;
 
-        if let Some(job) = jobs.get_mut(&id) {
-            if job.state == JobState::Paused {
-                // Check if we can resume immediately or need to wait
-                if self.running_count < self.config.max_concurrent_jobs {
-                    job.resume();
-                    self.running_count += 1;
-                    let _ = self.event_tx.send(SchedulerEvent::JobResumed(id));
-                    debug!(job_id = %id, "Job resumed");
-                } else {
-                    // Put back to pending, will start when slot available
-                    job.state = JobState::Pending;
-                    debug!(job_id = %id, "Job queued for resume");
-                }
+        if jobs.get(&id).is_none_or(|job| job.state != JobState::Paused) {
+            return;
+        }
+
+        // Check if we can resume immediately or need to wait
+        if self.has_slot(&jobs, id) {
+            if let Some(job) = jobs.get_mut(&id) {
+                job.resume();
+                let _ = self.event_tx.send(SchedulerEvent::JobResumed(id));
+                debug!(job_id = %id, "Job resumed");
             }
+        } else if !self.resume_queue.contains(&id) {
+            // Stay paused until a slot frees up; resuming in place
+            // keeps the start time and paused-time accounting intact
+            self.resume_queue.push(id);
+            debug!(job_id = %id, "Job queued for resume");
+        }
+    }
+
+    async fn handle_set_priority(&mut self, id: JobId, priority: Priority) {
+        let mut jobs = self.jobs.write().await;
+
+        if let Some(job) = jobs.get_mut(&id).filter(|job| !job.state.is_terminal()) {
+            // The queue is ordered on every scheduling pass, so nothing
+            // else needs to move
+            job.priority = priority;
+            let _ = self
+                .event_tx
+                .send(SchedulerEvent::JobPriorityChanged(id, priority));
+            debug!(job_id = %id, %priority, "Job priority changed");
+        }
+    }
+
+    async fn handle_set_speed_limit(&mut self, id: JobId, limit: Option<u64>) {
+        let jobs = self.jobs.read().await;
+
+        if let Some(job) = jobs.get(&id).filter(|job| !job.state.is_terminal()) {
+            // The executor shares the token, so this reaches a running copy
+            job.speed_limit.set(limit);
+            let limit = job.speed_limit.get();
+            let _ = self
+                .event_tx
+                .send(SchedulerEvent::JobSpeedLimitChanged(id, limit));
+            debug!(job_id = %id, ?limit, "Job speed limit changed");
         }
     }
 
@@ -343,39 +532,83 @@ impl Scheduler {
                 jobs.remove(id);
             }
         }
+
+        self.categories.retain(|id, _| jobs.contains_key(id));
     }
 
-    async fn try_start_pending(&mut self) {
-        if self.running_count >= self.config.max_concurrent_jobs {
-            return;
+    /// Get the limit configured for a job's category, if any.
+    fn category_limit(&self, id: &JobId) -> Option<ConcurrencyLimit> {
+        let category = self.categories.get(id)?;
+        self.config.category_limits.get(category).copied()
+    }
+
+    /// Check whether a job can run now without going over the overall limit
+    /// or the limit of its category.
+    fn has_slot(&self, jobs: &HashMap<JobId, Job>, id: JobId) -> bool {
+        let limit = self.category_limit(&id);
+        if limit == Some(ConcurrencyLimit::Unlimited) {
+            return true;
         }
 
+        let category = self.categories.get(&id);
+        let mut running = 0;
+        let mut running_in_category = 0;
+        for job in jobs.values().filter(|job| job.state == JobState::Running) {
+            if self.category_limit(&job.id) != Some(ConcurrencyLimit::Unlimited) {
+                running += 1;
+            }
+            if self.categories.get(&job.id) == category {
+                running_in_category += 1;
+            }
+        }
+
+        running < self.config.max_concurrent_jobs
+            && match limit {
+                Some(ConcurrencyLimit::Max(max)) => running_in_category < max,
+                _ => true,
+            }
+    }
+
+    async fn try_start_pending(&mut self) {
         let mut jobs = self.jobs.write().await;
 
-        // Find pending jobs ordered by creation time
-        let mut pending: Vec<_> = jobs
-            .iter()
-            .filter(|(_, j)| j.state == JobState::Pending)
-            .map(|(id, j)| (*id, j.created_at))
+        // Queued resumes go ahead of jobs of the same priority that haven't
+        // started yet; both keep their own order within a priority
+        let resumes = self.resume_queue.iter().enumerate().filter_map(|(order, id)| {
+            let job = jobs.get(id).filter(|job| job.state == JobState::Paused)?;
+            Some((job.priority, false, order, *id))
+        });
+        let mut pending: Vec<&Job> = jobs
+            .values()
+            .filter(|job| job.state == JobState::Pending)
             .collect();
+        pending.sort_by_key(|job| job.created_at);
+        let pending = pending
+            .iter()
+            .enumerate()
+            .map(|(order, job)| (job.priority, true, order, job.id));
 
-        pending.sort_by_key(|(_, created)| *created);
+        let mut queue: Vec<_> = resumes.chain(pending).collect();
+        queue.sort_by_key(|&(priority, is_new, order, _)| (priority, is_new, order));
 
-        // Start jobs up to the concurrency limit
-        for (id, _) in pending {
-            if self.running_count >= self.config.max_concurrent_jobs {
-                break;
+        // A job whose category is full doesn't hold up the ones behind it
+        for (_, is_new, _, id) in queue {
+            if !self.has_slot(&jobs, id) {
+                continue;
             }
+            let Some(job) = jobs.get_mut(&id) else {
+                continue;
+            };
 
-            if let Some(job) = jobs.get_mut(&id) {
+            if is_new {
                 job.start();
-                self.running_count += 1;
                 let _ = self.event_tx.send(SchedulerEvent::JobStarted(id));
                 info!(job_id = %id, "Job started");
-
-                // Note: Actual job execution would be spawned here
-                // For now, we just mark it as started
-                // The transfer engine (Sprint 5+) will handle actual execution
+            } else {
+                self.resume_queue.retain(|queued| *queued != id);
+                job.resume();
+                let _ = self.event_tx.send(SchedulerEvent::JobResumed(id));
+                debug!(job_id = %id, "Queued job resumed");
             }
         }
     }
@@ -384,7 +617,7 @@ impl Scheduler {
     pub async fn update_progress(&self, id: JobId, progress: Progress) {
         let mut jobs = self.jobs.write().await;
 
-        if let Some(job) = jobs.get_mut(&id) {
+        if let Some(job) = jobs.get_mut(&id).filter(|job| !job.state.is_terminal()) {
             job.progress = progress.clone();
             let _ = self.event_tx.send(SchedulerEvent::JobProgress(id, progress));
         }
@@ -394,9 +627,8 @@ impl Scheduler {
     pub async fn complete_job(&mut self, id: JobId) {
         let mut jobs = self.jobs.write().await;
 
-        if let Some(job) = jobs.get_mut(&id) {
+        if let Some(job) = jobs.get_mut(&id).filter(|job| !job.state.is_terminal()) {
             job.complete();
-            self.running_count = self.running_count.saturating_sub(1);
             let _ = self.event_tx.send(SchedulerEvent::JobCompleted(id));
             info!(job_id = %id, "Job completed");
         }
@@ -406,9 +638,8 @@ impl Scheduler {
     pub async fn fail_job(&mut self, id: JobId, error: String) {
         let mut jobs = self.jobs.write().await;
 
-        if let Some(job) = jobs.get_mut(&id) {
+        if let Some(job) = jobs.get_mut(&id).filter(|job| !job.state.is_terminal()) {
             job.fail(&error);
-            self.running_count = self.running_count.saturating_sub(1);
             let _ = self
                 .event_tx
                 .send(SchedulerEvent::JobFailed(id, error.clone()));
@@ -619,4 +850,246 @@ mod tests {
         handle.shutdown().await;
         let _ = scheduler_handle.await;
     }
+
+    #[tokio::test]
+    async fn test_pause_frees_slot_and_queues_resume() {
+        let (scheduler, handle) = Scheduler::new(SchedulerConfig {
+            max_concurrent_jobs: 1,
+            ..Default::default()
+        });
+
+        let scheduler_handle = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_millis(500), scheduler.run())
+                .await
+                .ok();
+        });
+
+        let first = handle
+            .submit(JobKind::Delete {
+                paths: vec![PathBuf::from("a")],
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let second = handle
+            .submit(JobKind::Delete {
+                paths: vec![PathBuf::from("b")],
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Pausing the first job lets the second one run
+        handle.pause(first).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handle.get_job(first).await.unwrap().state, JobState::Paused);
+        assert_eq!(handle.get_job(second).await.unwrap().state, JobState::Running);
+        assert!(handle.jobs.read().await[&first].pause.is_paused());
+
+        // No slot is free, so the resume waits without losing the start time
+        handle.resume(first).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handle.get_job(first).await.unwrap().state, JobState::Paused);
+
+        handle.cancel(second).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let jobs = handle.jobs.read().await;
+        let job = &jobs[&first];
+        assert_eq!(job.state, JobState::Running);
+        assert!(!job.pause.is_paused());
+        assert!(job.paused_time >= Duration::from_millis(100));
+        assert!(job.running_time().unwrap() < job.started_at.unwrap().elapsed());
+        drop(jobs);
+
+        handle.shutdown().await;
+        let _ = scheduler_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_executor_reports() {
+        let (scheduler, handle) = Scheduler::with_defaults();
+        let mut rx = handle.subscribe();
+
+        let scheduler_handle = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_millis(500), scheduler.run())
+                .await
+                .ok();
+        });
+
+        let id = handle
+            .submit(JobKind::Delete {
+                paths: vec![PathBuf::from("a"), PathBuf::from("b")],
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The executor's copy shares the job's tokens
+        let job = handle.job(id).await.unwrap();
+        assert_eq!(job.state, JobState::Running);
+        handle.pause(id).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(job.pause.is_paused());
+        handle.resume(id).await;
+        assert!(handle.set_speed_limit(id, Some(4096)).await);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(job.speed_limit.get(), Some(4096));
+        assert_eq!(handle.get_job(id).await.unwrap().speed_limit, Some(4096));
+
+        let mut progress = Progress::new(2, None);
+        progress.items_done = 1;
+        handle.report_progress(id, progress).await;
+        handle.complete(id).await;
+        // A late failure report doesn't override the outcome
+        handle.fail(id, "too late").await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!handle.set_speed_limit(id, None).await);
+
+        let info = handle.get_job(id).await.unwrap();
+        assert_eq!(info.state, JobState::Completed);
+        assert_eq!(info.items_done, 1);
+        assert!(info.error.is_none());
+
+        let mut completed = 0;
+        while let Ok(event) = rx.try_recv() {
+            completed += matches!(event, SchedulerEvent::JobCompleted(_)) as usize;
+            assert!(!matches!(event, SchedulerEvent::JobFailed(..)));
+        }
+        assert_eq!(completed, 1);
+
+        handle.shutdown().await;
+        let _ = scheduler_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_resubmit_failed() {
+        let (scheduler, handle) = Scheduler::with_defaults();
+        let scheduler_handle = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_millis(500), scheduler.run())
+                .await
+                .ok();
+        });
+
+        let id = handle
+            .submit(JobKind::Copy {
+                sources: vec![PathBuf::from("a"), PathBuf::from("b")],
+                destination: PathBuf::from("out"),
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Nothing to retry before the job fails
+        assert!(handle.resubmit_failed(id).await.is_empty());
+
+        let retry = JobKind::Copy {
+            sources: vec![PathBuf::from("b")],
+            destination: PathBuf::from("out"),
+        };
+        handle.fail_items(id, "1 of 2 item(s) failed", vec![retry]).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handle.get_job(id).await.unwrap().state, JobState::Failed);
+
+        let retried = handle.resubmit_failed(id).await;
+        assert_eq!(retried.len(), 1);
+        let job = handle.job(retried[0]).await.unwrap();
+        assert!(matches!(job.kind, JobKind::Copy { ref sources, .. } if sources.len() == 1));
+
+        handle.shutdown().await;
+        let _ = scheduler_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_priority_orders_pending() {
+        let (scheduler, handle) = Scheduler::new(SchedulerConfig {
+            max_concurrent_jobs: 1,
+            ..Default::default()
+        });
+
+        let scheduler_handle = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_millis(500), scheduler.run())
+                .await
+                .ok();
+        });
+
+        let delete = |name: &str| JobKind::Delete {
+            paths: vec![PathBuf::from(name)],
+        };
+        let running = handle.submit(delete("a")).await;
+        let low = handle.submit_with_priority(delete("b"), Priority::Low).await;
+        let normal = handle.submit(delete("c")).await;
+        let raised = handle.submit(delete("d")).await;
+        assert!(handle.set_priority(raised, Priority::High).await);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handle.get_job(raised).await.unwrap().priority, Priority::High);
+
+        // Each completion hands the slot to the next job in priority order
+        for id in [running, raised, normal, low] {
+            let running: Vec<_> = handle
+                .list_jobs()
+                .await
+                .into_iter()
+                .filter(|job| job.state == JobState::Running)
+                .map(|job| job.id)
+                .collect();
+            assert_eq!(running, [id]);
+            handle.complete(id).await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!handle.set_priority(low, Priority::High).await);
+
+        handle.shutdown().await;
+        let _ = scheduler_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_category_limits() {
+        let (scheduler, handle) = Scheduler::new(SchedulerConfig {
+            max_concurrent_jobs: 2,
+            category_limits: HashMap::from([
+                (JobCategory::Delete, ConcurrencyLimit::Max(1)),
+                (JobCategory::CalculateSize, ConcurrencyLimit::Unlimited),
+            ]),
+            ..Default::default()
+        });
+
+        let scheduler_handle = tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_millis(500), scheduler.run())
+                .await
+                .ok();
+        });
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let delete = |name: &str| JobKind::Delete {
+            paths: vec![PathBuf::from(name)],
+        };
+        let size = || JobKind::CalculateSize {
+            path: temp.path().to_path_buf(),
+        };
+        let first = handle.submit(delete("a")).await;
+        let second = handle.submit(delete("b")).await;
+        let copy = handle
+            .submit(JobKind::Copy {
+                sources: vec![temp.path().to_path_buf()],
+                destination: temp.path().to_path_buf(),
+            })
+            .await;
+        let sizes = [handle.submit(size()).await, handle.submit(size()).await];
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The second delete waits for its category without blocking the copy,
+        // and size calculations don't take a slot
+        let state = |id| {
+            let handle = handle.clone();
+            async move { handle.get_job(id).await.unwrap().state }
+        };
+        assert_eq!(state(first).await, JobState::Running);
+        assert_eq!(state(second).await, JobState::Pending);
+        assert_eq!(state(copy).await, JobState::Running);
+        for id in sizes {
+            assert_eq!(state(id).await, JobState::Running);
+        }
+
+        handle.complete(first).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(state(second).await, JobState::Running);
+
+        handle.shutdown().await;
+        let _ = scheduler_handle.await;
+    }
 }
@@ -0,0 +1,242 @@
+//! Recursive search of a directory tree.
+//!
+//! [`search`] walks a folder and everything below it the way a
+//! [`DuplicateIndex`](crate::DuplicateIndex) is built: one directory at a
+//! time, without following links, skipping subdirectories that can't be
+//! read. Entries are matched with a [`FilterSpec`], the filter the TUI's flat
+//! view applies, and optionally by text their contents must contain. Matches
+//! are handed over in batches as they are found, so a frontend can show them
+//! while the walk goes on.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::fs::{enters_directory, open_directory, read_entries};
+use crate::{
+    find_in_file, CancellationToken, ContentSearch, EntryMeta, FilterSpec, TextEncoding, ZError,
+    ZResult,
+};
+
+/// What a search looks for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// Entries to match by name, extension, size, date and attributes. Hidden
+    /// and system folders are only searched when the filter shows them.
+    pub filter: FilterSpec,
+    /// Text the contents of matching files must contain. Folders never match
+    /// a search for contents.
+    #[serde(default)]
+    pub contents: Option<String>,
+    /// Whether letters of `contents` must match in case.
+    #[serde(default)]
+    pub match_case: bool,
+    /// Whether folders can match as well as files.
+    #[serde(default)]
+    pub folders: bool,
+}
+
+/// Totals of a finished search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SearchSummary {
+    /// Entries that matched.
+    pub matches: usize,
+    /// Folders that were read.
+    pub folders_searched: usize,
+}
+
+/// Search `root` and every folder below it for entries matching `query`.
+///
+/// Matches are passed to `found` in batches of up to `batch_size`. Only an
+/// unreadable `root` is an error; so is cancelling, after the matches found
+/// so far have been passed on.
+///
+/// # Errors
+/// * `ZError::NotFound` - `root` does not exist
+/// * `ZError::NotADirectory` - `root` is not a folder
+/// * `ZError::PermissionDenied` - `root` can't be read
+/// * `ZError::Cancelled` - The search was cancelled
+pub fn search(
+    root: impl AsRef<Path>,
+    query: &SearchQuery,
+    batch_size: usize,
+    cancel: &CancellationToken,
+    mut found: impl FnMut(Vec<EntryMeta>),
+) -> ZResult<SearchSummary> {
+    let root = root.as_ref();
+    let batch_size = batch_size.max(1);
+    debug!(root = %root.display(), "Searching");
+
+    let contents = query.contents.as_ref().filter(|text| !text.is_empty()).map(|text| {
+        ContentSearch {
+            text: text.clone(),
+            match_case: query.match_case,
+            backward: false,
+        }
+    });
+    let mut summary = SearchSummary::default();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut pending = vec![root.to_path_buf()];
+    let mut is_root = true;
+
+    let result = 'walk: loop {
+        let Some(dir) = pending.pop() else {
+            break Ok(summary);
+        };
+        if cancel.is_cancelled() {
+            break Err(ZError::Cancelled);
+        }
+
+        let entries = match open_directory(&dir) {
+            Ok(entries) => entries,
+            Err(e) if is_root => break Err(e),
+            Err(_) => continue,
+        };
+        is_root = false;
+        summary.folders_searched += 1;
+
+        for meta in read_entries(entries) {
+            if meta.is_directory() && enters_directory(Some(&query.filter), &meta) {
+                pending.push(meta.path.clone());
+            }
+            match matches(query, contents.as_ref(), &meta, cancel) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => break 'walk Err(e),
+            }
+            summary.matches += 1;
+            batch.push(meta);
+            if batch.len() == batch_size {
+                found(std::mem::replace(&mut batch, Vec::with_capacity(batch_size)));
+            }
+        }
+    };
+
+    // Matches found before a cancellation are still passed on
+    if !batch.is_empty() {
+        found(batch);
+    }
+    result
+}
+
+/// Check whether `meta` is a match: it passes the filter and, when looking
+/// for `contents`, is a file containing them.
+fn matches(
+    query: &SearchQuery,
+    contents: Option<&ContentSearch>,
+    meta: &EntryMeta,
+    cancel: &CancellationToken,
+) -> ZResult<bool> {
+    if meta.is_directory() && (!query.folders || contents.is_some()) {
+        return Ok(false);
+    }
+    if !query.filter.matches(meta) {
+        return Ok(false);
+    }
+    let Some(contents) = contents else {
+        return Ok(true);
+    };
+    if !meta.is_file() {
+        return Ok(false);
+    }
+    // Files that can't be read don't contain anything
+    match find_in_file(&meta.path, TextEncoding::Utf8, contents, 0, cancel) {
+        Ok(found) => Ok(found.is_some()),
+        Err(ZError::Cancelled) => Err(ZError::Cancelled),
+        Err(_) => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn found_paths(root: &Path, query: &SearchQuery) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let token = CancellationToken::new();
+        search(root, query, 2, &token, |batch| {
+            assert!(batch.len() <= 2);
+            paths.extend(batch.into_iter().map(|meta| meta.path));
+        })
+        .unwrap();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_search_by_name_in_nested_tree() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(&root.join("report.txt"), "");
+        write(&root.join("a/b/old report.TXT"), "");
+        write(&root.join("a/notes.md"), "");
+        write(&root.join(".cache/report.txt"), "");
+        fs::create_dir_all(root.join("reports")).unwrap();
+
+        let mut query = SearchQuery {
+            filter: FilterSpec::new().with_pattern("report"),
+            ..Default::default()
+        };
+        assert_eq!(
+            found_paths(root, &query),
+            [root.join("a/b/old report.TXT"), root.join("report.txt")]
+        );
+
+        query.folders = true;
+        query.filter = query.filter.with_hidden(true);
+        assert_eq!(found_paths(root, &query).len(), 4);
+
+        let token = CancellationToken::new();
+        let summary = search(root, &query, 10, &token, |_| {}).unwrap();
+        assert_eq!(summary, SearchSummary { matches: 4, folders_searched: 5 });
+    }
+
+    #[test]
+    fn test_search_by_contents() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(&root.join("todo.txt"), "Buy MILK\n");
+        write(&root.join("deep/list.txt"), "milk and bread");
+        write(&root.join("other.txt"), "water");
+        fs::create_dir_all(root.join("milk")).unwrap();
+
+        let mut query = SearchQuery {
+            contents: Some("milk".to_string()),
+            folders: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            found_paths(root, &query),
+            [root.join("deep/list.txt"), root.join("todo.txt")]
+        );
+
+        query.match_case = true;
+        assert_eq!(found_paths(root, &query), [root.join("deep/list.txt")]);
+    }
+
+    #[test]
+    fn test_search_errors() {
+        let temp = TempDir::new().unwrap();
+        write(&temp.path().join("file.txt"), "");
+        let query = SearchQuery::default();
+        let token = CancellationToken::new();
+
+        let missing = temp.path().join("missing");
+        assert!(search(&missing, &query, 10, &token, |_| {}).is_err());
+
+        token.cancel();
+        assert!(matches!(
+            search(temp.path(), &query, 10, &token, |_| {}),
+            Err(ZError::Cancelled)
+        ));
+    }
+}
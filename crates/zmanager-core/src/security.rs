@@ -0,0 +1,433 @@
+//! Owners and access control lists of files and folders.
+//!
+//! [`get_security`] reads the owner and the access control list (DACL) of an
+//! item with `GetNamedSecurityInfoW`, and sums each entry's access mask up
+//! the way Explorer's Security tab does: Full control, Modify, Read &
+//! execute, Read or Write, and Special for anything else.
+//! [`take_ownership`] makes the current user the owner of an item and grants
+//! them full control; for items owned by someone else it needs
+//! administrator rights.
+//!
+//! Outside Windows the owner, group and others of the permission bits stand
+//! in for the access control list.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{ZError, ZResult};
+
+/// Access rights that make up each level, leaving out `SYNCHRONIZE`, which
+/// not every entry carries.
+const FULL_CONTROL: u32 = 0x000F_01FF;
+const MODIFY: u32 = 0x0003_01BF;
+const READ_EXECUTE: u32 = 0x0002_00A9;
+const READ: u32 = 0x0002_0089;
+const WRITE: u32 = 0x0000_0116;
+
+/// Generic rights and the file rights they stand for.
+const GENERIC_RIGHTS: [(u32, u32); 4] = [
+    (0x8000_0000, 0x0012_0089), // GENERIC_READ
+    (0x4000_0000, 0x0012_0116), // GENERIC_WRITE
+    (0x2000_0000, 0x0012_00A0), // GENERIC_EXECUTE
+    (0x1000_0000, 0x001F_01FF), // GENERIC_ALL
+];
+
+/// What an access control entry allows or denies, simplified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    /// Everything, including changing permissions and taking ownership.
+    FullControl,
+    /// Read, write and delete.
+    Modify,
+    /// Read files and run programs.
+    ReadExecute,
+    /// Read contents and attributes.
+    Read,
+    /// Write contents and attributes.
+    Write,
+    /// Any other combination of rights.
+    Special,
+}
+
+impl AccessLevel {
+    /// The level an access mask amounts to.
+    pub fn from_mask(mask: u32) -> Self {
+        let mask = GENERIC_RIGHTS
+            .iter()
+            .filter(|(generic, _)| mask & generic != 0)
+            .fold(mask, |mask, (_, specific)| mask | specific);
+        let has = |rights: u32| mask & rights == rights;
+        if has(FULL_CONTROL) {
+            Self::FullControl
+        } else if has(MODIFY) {
+            Self::Modify
+        } else if has(READ_EXECUTE) {
+            Self::ReadExecute
+        } else if has(READ) {
+            Self::Read
+        } else if has(WRITE) {
+            Self::Write
+        } else {
+            Self::Special
+        }
+    }
+
+    /// Human-readable name for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::FullControl => "Full control",
+            Self::Modify => "Modify",
+            Self::ReadExecute => "Read & execute",
+            Self::Read => "Read",
+            Self::Write => "Write",
+            Self::Special => "Special",
+        }
+    }
+}
+
+/// One entry of an access control list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessEntry {
+    /// User or group the entry applies to, e.g. `BUILTIN\Administrators`.
+    pub trustee: String,
+    /// Whether the entry allows the access rather than denying it.
+    pub allow: bool,
+    /// The access allowed or denied.
+    pub access: AccessLevel,
+    /// Whether the entry comes from a parent folder.
+    pub inherited: bool,
+}
+
+/// The owner and access control list of a file or folder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityInfo {
+    /// Owner of the item, if it could be looked up.
+    pub owner: Option<String>,
+    /// Access control entries, in the order they are checked.
+    pub entries: Vec<AccessEntry>,
+}
+
+/// Read the owner and access control list of `path`.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::PermissionDenied` - Not allowed to read the item's permissions
+#[cfg(windows)]
+pub fn get_security(path: impl AsRef<Path>) -> ZResult<SecurityInfo> {
+    use std::ffi::{c_void, OsStr};
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+
+    // Mirror the C layouts; only some fields are read
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct Acl {
+        revision: u8,
+        sbz1: u8,
+        size: u16,
+        ace_count: u16,
+        sbz2: u16,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct AceHeader {
+        ace_type: u8,
+        flags: u8,
+        size: u16,
+    }
+
+    #[link(name = "advapi32")]
+    unsafe extern "system" {
+        fn GetNamedSecurityInfoW(
+            pObjectName: *const u16,
+            ObjectType: u32,
+            SecurityInfo: u32,
+            ppsidOwner: *mut *mut c_void,
+            ppsidGroup: *mut *mut c_void,
+            ppDacl: *mut *mut Acl,
+            ppSacl: *mut *mut Acl,
+            ppSecurityDescriptor: *mut *mut c_void,
+        ) -> u32;
+        fn GetAce(pAcl: *const Acl, dwAceIndex: u32, pAce: *mut *mut c_void) -> i32;
+        fn LookupAccountSidW(
+            lpSystemName: *const u16,
+            Sid: *const c_void,
+            Name: *mut u16,
+            cchName: *mut u32,
+            ReferencedDomainName: *mut u16,
+            cchReferencedDomainName: *mut u32,
+            peUse: *mut u32,
+        ) -> i32;
+        fn ConvertSidToStringSidW(Sid: *const c_void, StringSid: *mut *mut u16) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn LocalFree(hMem: *mut c_void) -> *mut c_void;
+    }
+
+    const SE_FILE_OBJECT: u32 = 1;
+    const OWNER_SECURITY_INFORMATION: u32 = 0x1;
+    const DACL_SECURITY_INFORMATION: u32 = 0x4;
+    const ACCESS_ALLOWED_ACE_TYPE: u8 = 0;
+    const ACCESS_DENIED_ACE_TYPE: u8 = 1;
+    const INHERITED_ACE: u8 = 0x10;
+    /// Size of the ACE header and access mask before an entry's SID.
+    const SID_OFFSET: usize = 8;
+
+    /// `DOMAIN\name` of a SID, or the SID itself if it has no account.
+    unsafe fn account_name(sid: *const c_void) -> String {
+        let mut name = [0u16; 256];
+        let mut domain = [0u16; 256];
+        let (mut name_len, mut domain_len, mut sid_use) = (256u32, 256u32, 0u32);
+        let found = unsafe {
+            LookupAccountSidW(
+                ptr::null(),
+                sid,
+                name.as_mut_ptr(),
+                &mut name_len,
+                domain.as_mut_ptr(),
+                &mut domain_len,
+                &mut sid_use,
+            )
+        };
+        if found != 0 {
+            let name = String::from_utf16_lossy(&name[..name_len as usize]);
+            let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+            return if domain.is_empty() { name } else { format!("{domain}\\{name}") };
+        }
+
+        let mut text: *mut u16 = ptr::null_mut();
+        if unsafe { ConvertSidToStringSidW(sid, &mut text) } == 0 {
+            return "Unknown account".to_string();
+        }
+        let len = (0..).take_while(|&i| unsafe { *text.add(i) } != 0).count();
+        let sid = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(text, len) });
+        unsafe { LocalFree(text.cast()) };
+        sid
+    }
+
+    let path = path.as_ref();
+    debug!(path = %path.display(), "Reading security information");
+
+    let wide: Vec<u16> = OsStr::new(path).encode_wide().chain(std::iter::once(0)).collect();
+    let mut owner: *mut c_void = ptr::null_mut();
+    let mut dacl: *mut Acl = ptr::null_mut();
+    let mut descriptor: *mut c_void = ptr::null_mut();
+    let code = unsafe {
+        GetNamedSecurityInfoW(
+            wide.as_ptr(),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION,
+            &mut owner,
+            ptr::null_mut(),
+            &mut dacl,
+            ptr::null_mut(),
+            &mut descriptor,
+        )
+    };
+    if code != 0 {
+        return Err(ZError::from_io(path, std::io::Error::from_raw_os_error(code as i32)));
+    }
+
+    let mut info = SecurityInfo {
+        owner: (!owner.is_null()).then(|| unsafe { account_name(owner) }),
+        entries: Vec::new(),
+    };
+    if dacl.is_null() {
+        // No DACL at all lets everyone do anything
+        info.entries.push(AccessEntry {
+            trustee: "Everyone".to_string(),
+            allow: true,
+            access: AccessLevel::FullControl,
+            inherited: false,
+        });
+    } else {
+        let count = unsafe { (*dacl).ace_count };
+        for index in 0..u32::from(count) {
+            let mut ace: *mut c_void = ptr::null_mut();
+            if unsafe { GetAce(dacl, index, &mut ace) } == 0 {
+                continue;
+            }
+            let header = unsafe { &*(ace as *const AceHeader) };
+            // Object and callback entries don't apply to files
+            let allow = match header.ace_type {
+                ACCESS_ALLOWED_ACE_TYPE => true,
+                ACCESS_DENIED_ACE_TYPE => false,
+                _ => continue,
+            };
+            let mask = unsafe { *(ace.cast::<u8>().add(4) as *const u32) };
+            let sid = unsafe { ace.cast::<u8>().add(SID_OFFSET) } as *const c_void;
+            info.entries.push(AccessEntry {
+                trustee: unsafe { account_name(sid) },
+                allow,
+                access: AccessLevel::from_mask(mask),
+                inherited: header.flags & INHERITED_ACE != 0,
+            });
+        }
+    }
+    unsafe { LocalFree(descriptor) };
+
+    Ok(info)
+}
+
+/// Read the owner and permission bits of `path` (non-Windows fallback).
+///
+/// The owner, group and others of the permission bits become the entries.
+#[cfg(not(windows))]
+pub fn get_security(path: impl AsRef<Path>) -> ZResult<SecurityInfo> {
+    use std::os::unix::fs::MetadataExt;
+
+    let path = path.as_ref();
+    debug!(path = %path.display(), "Reading security information");
+
+    let metadata = std::fs::metadata(path).map_err(|e| ZError::from_io(path, e))?;
+    let mode = metadata.mode();
+    let entries = [
+        (format!("Owner (uid {})", metadata.uid()), mode >> 6),
+        (format!("Group (gid {})", metadata.gid()), mode >> 3),
+        ("Others".to_string(), mode),
+    ]
+    .into_iter()
+    .filter_map(|(trustee, bits)| {
+        let access = match (bits & 4 != 0, bits & 2 != 0, bits & 1 != 0) {
+            (true, true, true) => AccessLevel::FullControl,
+            (true, true, false) => AccessLevel::Modify,
+            (true, false, true) => AccessLevel::ReadExecute,
+            (true, false, false) => AccessLevel::Read,
+            (false, true, _) => AccessLevel::Write,
+            (false, false, true) => AccessLevel::Special,
+            (false, false, false) => return None,
+        };
+        Some(AccessEntry {
+            trustee,
+            allow: true,
+            access,
+            inherited: false,
+        })
+    })
+    .collect();
+
+    Ok(SecurityInfo {
+        owner: Some(format!("uid {}", metadata.uid())),
+        entries,
+    })
+}
+
+/// Make the current user the owner of `path` and grant them full control,
+/// through everything inside it too if `recursive`.
+///
+/// Runs `takeown` and then `icacls`. Taking ownership of an item owned by
+/// someone else needs administrator rights.
+///
+/// # Errors
+/// * `ZError::NotFound` - Path does not exist
+/// * `ZError::PermissionDenied` - Not allowed to take ownership
+/// * `ZError::InvalidOperation` - `takeown` or `icacls` failed otherwise
+#[cfg(windows)]
+pub fn take_ownership(path: impl AsRef<Path>, recursive: bool) -> ZResult<()> {
+    use std::process::Command;
+
+    let path = path.as_ref();
+    debug!(path = %path.display(), recursive, "Taking ownership");
+
+    if !path.exists() {
+        return Err(ZError::NotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let mut takeown = Command::new("takeown");
+    takeown.arg("/F").arg(path);
+    if recursive {
+        takeown.args(["/R", "/D", "Y"]);
+    }
+    run_tool(takeown, path, "take ownership of")?;
+
+    let user = match (std::env::var("USERDOMAIN"), std::env::var("USERNAME")) {
+        (Ok(domain), Ok(name)) => format!("{domain}\\{name}"),
+        (_, Ok(name)) => name,
+        _ => {
+            return Err(ZError::InvalidOperation {
+                operation: format!("grant full control of {}", path.display()),
+                reason: "The current user name is unknown".to_string(),
+            });
+        }
+    };
+    // Files and folders created inside a folder inherit the grant
+    let grant = if path.is_dir() { format!("{user}:(OI)(CI)F") } else { format!("{user}:F") };
+    let mut icacls = Command::new("icacls");
+    icacls.arg(path).args(["/grant", grant.as_str(), "/C", "/Q"]);
+    if recursive {
+        icacls.arg("/T");
+    }
+    run_tool(icacls, path, "grant full control of")
+}
+
+/// Run a command line tool, turning a failure into an error about `path`.
+#[cfg(windows)]
+fn run_tool(mut command: std::process::Command, path: &Path, what: &str) -> ZResult<()> {
+    let output = command.output().map_err(|e| ZError::io(path, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let text = String::from_utf8_lossy(&output.stderr);
+    let text = if text.trim().is_empty() {
+        String::from_utf8_lossy(&output.stdout)
+    } else {
+        text
+    };
+    if text.to_ascii_lowercase().contains("access is denied") {
+        return Err(ZError::PermissionDenied {
+            path: path.to_path_buf(),
+        });
+    }
+    Err(ZError::InvalidOperation {
+        operation: format!("{what} {}", path.display()),
+        reason: text.trim().to_string(),
+    })
+}
+
+/// Take ownership of `path` (non-Windows fallback).
+#[cfg(not(windows))]
+pub fn take_ownership(path: impl AsRef<Path>, _recursive: bool) -> ZResult<()> {
+    Err(ZError::InvalidOperation {
+        operation: format!("take ownership of {}", path.as_ref().display()),
+        reason: "Taking ownership is only available on Windows".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_masks_are_simplified() {
+        assert_eq!(AccessLevel::from_mask(0x001F_01FF), AccessLevel::FullControl);
+        assert_eq!(AccessLevel::from_mask(0x1000_0000), AccessLevel::FullControl);
+        assert_eq!(AccessLevel::from_mask(0x0013_01BF), AccessLevel::Modify);
+        assert_eq!(AccessLevel::from_mask(0x0012_00A9), AccessLevel::ReadExecute);
+        assert_eq!(AccessLevel::from_mask(0xA000_0000), AccessLevel::ReadExecute);
+        assert_eq!(AccessLevel::from_mask(0x0012_0089), AccessLevel::Read);
+        assert_eq!(AccessLevel::from_mask(0x0010_0116), AccessLevel::Write);
+        assert_eq!(AccessLevel::from_mask(0x0001_0000), AccessLevel::Special);
+    }
+
+    #[test]
+    fn test_reads_security_of_a_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, b"data").unwrap();
+
+        let info = get_security(&file).unwrap();
+        assert!(info.owner.is_some());
+        assert!(info.entries.iter().any(|entry| entry.allow));
+
+        let missing = get_security(dir.path().join("missing"));
+        assert!(matches!(missing, Err(ZError::NotFound { .. })));
+    }
+}
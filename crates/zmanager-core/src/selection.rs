@@ -4,6 +4,63 @@ use crate::EntryMeta;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+/// Whether `name` matches the wildcard `pattern`, ignoring case.
+///
+/// `*` matches any run of characters and `?` any single character. As on
+/// Windows, `*.*` matches every name, with or without an extension.
+///
+/// # Example
+///
+/// ```
+/// use zmanager_core::wildcard_match;
+///
+/// assert!(wildcard_match("*.jpg", "Holiday.JPG"));
+/// assert!(wildcard_match("img_??.*", "img_01.png"));
+/// assert!(!wildcard_match("*.jpg", "notes.txt"));
+/// ```
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    if pattern == "*.*" {
+        return true;
+    }
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Pattern position after the last `*`, and where in the name it stopped
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` take one more character and retry
+                Some((after, taken)) => {
+                    p = after;
+                    n = taken + 1;
+                    star = Some((after, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether `name` matches any of the `;`-separated wildcard `patterns`.
+fn matches_any(patterns: &str, name: &str) -> bool {
+    patterns
+        .split(';')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .any(|pattern| wildcard_match(pattern, name))
+}
+
 /// Selection state for a directory listing.
 #[derive(Debug, Clone, Default)]
 pub struct Selection {
@@ -47,8 +104,22 @@ impl Selection {
                 self.anchor = None;
             }
         }
-        // Remove selections for entries that no longer exist
-        // Note: We can't do this perfectly without knowing which paths were removed
+        // Selections of entries that no longer exist are dropped by
+        // `retain_existing` once the whole listing is known
+    }
+
+    /// Drop selected paths that are no longer among `entries`, e.g. after a
+    /// refresh.
+    pub fn retain_existing(&mut self, entries: &[EntryMeta]) {
+        let existing: HashSet<&Path> = entries.iter().map(|e| e.path.as_path()).collect();
+        self.selected.retain(|path| existing.contains(path.as_path()));
+    }
+
+    /// Keep a renamed entry selected under its new path.
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if self.selected.remove(from) {
+            self.selected.insert(to.to_path_buf());
+        }
     }
 
     /// Get the current cursor position.
@@ -218,6 +289,66 @@ impl Selection {
         }
     }
 
+    /// Add entries whose name matches any of the `;`-separated wildcard
+    /// `patterns`, e.g. `*.jpg; *.png`. Returns how many matched.
+    pub fn select_matching(&mut self, entries: &[EntryMeta], patterns: &str) -> usize {
+        let mut matched = 0;
+        for entry in entries.iter().filter(|e| matches_any(patterns, &e.name)) {
+            self.selected.insert(entry.path.clone());
+            matched += 1;
+        }
+        matched
+    }
+
+    /// Remove entries whose name matches any of the `;`-separated wildcard
+    /// `patterns`. Returns how many matched.
+    pub fn deselect_matching(&mut self, entries: &[EntryMeta], patterns: &str) -> usize {
+        let mut matched = 0;
+        for entry in entries.iter().filter(|e| matches_any(patterns, &e.name)) {
+            self.selected.remove(&entry.path);
+            matched += 1;
+        }
+        matched
+    }
+
+    /// Add the files with the same extension as the file at the cursor (or
+    /// with no extension, if it has none). Returns how many matched; none
+    /// when the cursor is not on a file.
+    pub fn select_same_extension(&mut self, entries: &[EntryMeta]) -> usize {
+        let Some(current) = self.cursor_entry(entries).filter(|e| e.is_file()) else {
+            return 0;
+        };
+        let extension = current.extension.clone();
+        let mut matched = 0;
+        for entry in entries.iter().filter(|e| e.is_file() && e.extension == extension) {
+            self.selected.insert(entry.path.clone());
+            matched += 1;
+        }
+        matched
+    }
+
+    /// Add the files modified after the newest of the operation targets
+    /// (the selection, or the cursor entry if nothing is selected). Returns
+    /// how many were newer; none if no target has a modification time.
+    pub fn select_newer(&mut self, entries: &[EntryMeta]) -> usize {
+        let Some(newest) = self
+            .operation_targets(entries)
+            .iter()
+            .filter_map(|e| e.modified)
+            .max()
+        else {
+            return 0;
+        };
+        let mut matched = 0;
+        for entry in entries {
+            if entry.is_file() && entry.modified.is_some_and(|modified| modified > newest) {
+                self.selected.insert(entry.path.clone());
+                matched += 1;
+            }
+        }
+        matched
+    }
+
     /// Clear the selection.
     pub fn clear(&mut self) {
         self.selected.clear();
@@ -557,4 +688,69 @@ mod tests {
         sel.set_entry_count(5);
         assert_eq!(sel.cursor(), 4); // Clamped
     }
+
+    #[test]
+    fn test_retain_existing_and_rename() {
+        let entries = make_entries(&["a", "b", "c"]);
+        let mut sel = Selection::with_count(3);
+        sel.select_all(&entries);
+
+        sel.rename(Path::new("/test/b"), Path::new("/test/renamed"));
+        sel.rename(Path::new("/test/unselected"), Path::new("/test/other"));
+        assert!(sel.is_selected(Path::new("/test/renamed")));
+        assert!(!sel.is_selected(Path::new("/test/b")));
+        assert!(!sel.is_selected(Path::new("/test/other")));
+
+        // "c" was deleted
+        let refreshed = make_entries(&["a", "renamed"]);
+        sel.retain_existing(&refreshed);
+        assert_eq!(sel.count(), 2);
+        assert!(!sel.is_selected(Path::new("/test/c")));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.jpg", "photo.JPG"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*.*", "Makefile"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(wildcard_match("report-????.pdf", "report-2024.pdf"));
+        assert!(!wildcard_match("report-????.pdf", "report-24.pdf"));
+        assert!(!wildcard_match("*.jpg", "photo.jpeg"));
+        assert!(!wildcard_match("a*b", "ab c"));
+    }
+
+    #[test]
+    fn test_select_and_deselect_matching() {
+        let entries = make_entries(&["a.jpg", "b.PNG", "c.txt", "d.jpg"]);
+        let mut sel = Selection::with_count(4);
+
+        assert_eq!(sel.select_matching(&entries, "*.jpg; *.png"), 3);
+        assert!(!sel.is_selected(Path::new("/test/c.txt")));
+        assert_eq!(sel.deselect_matching(&entries, "d.*"), 1);
+        assert_eq!(sel.count(), 2);
+        assert_eq!(sel.select_matching(&entries, " ; "), 0);
+    }
+
+    #[test]
+    fn test_select_same_extension_and_newer() {
+        use chrono::{TimeZone, Utc};
+
+        let mut entries = make_entries(&["old.txt", "a.md", "new.txt", "newer.md"]);
+        for (i, entry) in entries.iter_mut().enumerate() {
+            entry.modified = Some(Utc.timestamp_opt(1_000 * i as i64, 0).unwrap());
+        }
+        let mut sel = Selection::with_count(4);
+
+        sel.set_cursor(1);
+        assert_eq!(sel.select_same_extension(&entries), 2);
+        assert!(sel.is_selected(Path::new("/test/newer.md")));
+
+        // Newer than the newest selected entry, then than the cursor entry
+        assert_eq!(sel.select_newer(&entries), 0);
+        sel.clear();
+        assert_eq!(sel.select_newer(&entries), 2);
+        assert!(sel.is_selected(Path::new("/test/new.txt")));
+        assert!(!sel.is_selected(Path::new("/test/a.md")));
+    }
 }
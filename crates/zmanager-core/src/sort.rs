@@ -1,6 +1,8 @@
 //! Sorting specifications for directory listings.
 
-use crate::EntryMeta;
+use std::cmp::Ordering;
+
+use crate::{EntryMeta, FileGroup};
 use serde::{Deserialize, Serialize};
 
 /// The field to sort entries by.
@@ -34,6 +36,32 @@ impl SortField {
             Self::Kind => "Kind",
         }
     }
+
+    /// Stable name used in configuration files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Size => "size",
+            Self::Modified => "modified",
+            Self::Created => "created",
+            Self::Extension => "extension",
+            Self::Kind => "kind",
+        }
+    }
+
+    /// Parse a name produced by [`SortField::key`] (case-insensitive).
+    pub fn from_key(key: &str) -> Option<Self> {
+        [
+            Self::Name,
+            Self::Size,
+            Self::Modified,
+            Self::Created,
+            Self::Extension,
+            Self::Kind,
+        ]
+        .into_iter()
+        .find(|field| field.key().eq_ignore_ascii_case(key))
+    }
 }
 
 impl std::fmt::Display for SortField {
@@ -71,6 +99,107 @@ impl SortOrder {
     }
 }
 
+/// How names and extensions compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collation {
+    /// Character by character, ignoring case.
+    #[default]
+    Ordinal,
+    /// Letters with accents sort next to their base letter (`é` with `e`),
+    /// accents and then case only deciding between otherwise equal names.
+    Locale,
+}
+
+impl Collation {
+    /// Get a human-readable label for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ordinal => "Ordinal",
+            Self::Locale => "Locale",
+        }
+    }
+
+    /// Characters of `text` compared first: lowercased, and for
+    /// [`Collation::Locale`] without accents.
+    fn primary_key(self, text: &str) -> impl Iterator<Item = char> + '_ {
+        text.chars().flat_map(move |c| self.char_key(c))
+    }
+
+    /// [`Collation::primary_key`] of a single character.
+    fn char_key(self, c: char) -> impl Iterator<Item = char> {
+        c.to_lowercase().map(move |c| match self {
+            Self::Ordinal => c,
+            Self::Locale => strip_accent(c),
+        })
+    }
+
+    /// Compare two strings in full, including the tie-breaks.
+    fn compare(self, a: &str, b: &str) -> Ordering {
+        let lowercase = |text: &str| text.chars().flat_map(char::to_lowercase).collect::<String>();
+        match self {
+            Self::Ordinal => lowercase(a).cmp(&lowercase(b)),
+            Self::Locale => self
+                .primary_key(a)
+                .cmp(self.primary_key(b))
+                .then_with(|| lowercase(a).cmp(&lowercase(b)))
+                .then_with(|| a.cmp(b)),
+        }
+    }
+}
+
+/// The base letter of an accented lowercase Latin letter.
+fn strip_accent(c: char) -> char {
+    match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ß' | 'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        c => c,
+    }
+}
+
+/// Compare runs of digits by their value and other characters with
+/// `collation`, so `file2` sorts before `file10`.
+fn natural_cmp(mut a: &str, mut b: &str, collation: Collation) -> Ordering {
+    let is_digit = |c: char| c.is_ascii_digit();
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.is_empty().cmp(&b.is_empty()).reverse();
+        };
+        let ord = if is_digit(x) && is_digit(y) {
+            let (digits_a, rest_a) = a.split_at(a.find(|c| !is_digit(c)).unwrap_or(a.len()));
+            let (digits_b, rest_b) = b.split_at(b.find(|c| !is_digit(c)).unwrap_or(b.len()));
+            (a, b) = (rest_a, rest_b);
+            // Leading zeros don't change the value; longer means larger
+            let value_a = digits_a.trim_start_matches('0');
+            let value_b = digits_b.trim_start_matches('0');
+            value_a.len().cmp(&value_b.len()).then_with(|| value_a.cmp(value_b))
+        } else {
+            (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            collation.char_key(x).cmp(collation.char_key(y))
+        };
+        if ord.is_ne() {
+            return ord;
+        }
+    }
+}
+
 /// A complete sorting specification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SortSpec {
@@ -80,6 +209,27 @@ pub struct SortSpec {
     pub order: SortOrder,
     /// Whether to always show directories before files.
     pub directories_first: bool,
+    /// When sorting by size, sort directories by their computed size among
+    /// the files instead of grouping them first.
+    ///
+    /// Directories whose size hasn't been computed yet sort as if empty, so
+    /// the order shifts as sizes resolve; callers should re-sort then.
+    #[serde(default)]
+    pub dirs_by_computed_size: bool,
+    /// Group files by [`FileGroup`] before sorting by `field` within each
+    /// group. Groups keep their order whichever way the field sorts.
+    #[serde(default)]
+    pub group_by_type: bool,
+    /// Compare numbers in names by value, so `file2` sorts before `file10`.
+    #[serde(default)]
+    pub natural: bool,
+    /// How names and extensions compare.
+    #[serde(default)]
+    pub collation: Collation,
+    /// Field deciding between entries that are equal by `field`, always
+    /// ascending.
+    #[serde(default)]
+    pub then_by: Option<SortField>,
 }
 
 impl Default for SortSpec {
@@ -88,6 +238,11 @@ impl Default for SortSpec {
             field: SortField::Name,
             order: SortOrder::Ascending,
             directories_first: true,
+            dirs_by_computed_size: false,
+            group_by_type: false,
+            natural: false,
+            collation: Collation::Ordinal,
+            then_by: None,
         }
     }
 }
@@ -99,6 +254,11 @@ impl SortSpec {
             field,
             order,
             directories_first: true,
+            dirs_by_computed_size: false,
+            group_by_type: false,
+            natural: false,
+            collation: Collation::Ordinal,
+            then_by: None,
         }
     }
 
@@ -127,11 +287,57 @@ impl SortSpec {
         }
     }
 
+    /// Switch listing directories before files on or off.
+    pub fn toggle_directories_first(&mut self) {
+        self.directories_first = !self.directories_first;
+    }
+
+    /// Compare two names (or extensions) with this spec's collation and,
+    /// if enabled, natural number ordering.
+    pub fn compare_text(&self, a: &str, b: &str) -> Ordering {
+        if self.natural {
+            // Names equal by value (`file01`, `file1`) still get an order
+            natural_cmp(a, b, self.collation).then_with(|| self.collation.compare(a, b))
+        } else {
+            self.collation.compare(a, b)
+        }
+    }
+
+    /// Compare two entries by `field` alone, ascending.
+    fn compare_by(&self, field: SortField, a: &EntryMeta, b: &EntryMeta) -> Ordering {
+        match field {
+            SortField::Name => self.compare_text(&a.name, &b.name),
+            SortField::Size => self.size_key(a).cmp(&self.size_key(b)),
+            SortField::Modified => a.modified.cmp(&b.modified),
+            SortField::Created => a.created.cmp(&b.created),
+            SortField::Extension => {
+                let ext_a = a.extension.as_deref().unwrap_or("");
+                let ext_b = b.extension.as_deref().unwrap_or("");
+                self.compare_text(ext_a, ext_b)
+            }
+            SortField::Kind => a.kind.label().cmp(b.kind.label()),
+        }
+    }
+
+    /// Whether directories are interleaved with files by computed size.
+    pub fn interleaves_directories(&self) -> bool {
+        self.field == SortField::Size && self.dirs_by_computed_size
+    }
+
+    /// The size an entry sorts by.
+    fn size_key(&self, entry: &EntryMeta) -> u64 {
+        if entry.is_directory() && self.interleaves_directories() {
+            entry.computed_size.unwrap_or(0)
+        } else {
+            entry.size
+        }
+    }
+
     /// Sort a slice of entries in place according to this specification.
     pub fn sort(&self, entries: &mut [EntryMeta]) {
         entries.sort_by(|a, b| {
             // Directories first, if enabled
-            if self.directories_first {
+            if self.directories_first && !self.interleaves_directories() {
                 match (a.is_directory(), b.is_directory()) {
                     (true, false) => return std::cmp::Ordering::Less,
                     (false, true) => return std::cmp::Ordering::Greater,
@@ -139,24 +345,25 @@ impl SortSpec {
                 }
             }
 
-            // Primary sort field comparison
-            let cmp = match self.field {
-                SortField::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                SortField::Size => a.size.cmp(&b.size),
-                SortField::Modified => a.modified.cmp(&b.modified),
-                SortField::Created => a.created.cmp(&b.created),
-                SortField::Extension => {
-                    let ext_a = a.extension.as_deref().unwrap_or("");
-                    let ext_b = b.extension.as_deref().unwrap_or("");
-                    ext_a.cmp(ext_b)
+            if self.group_by_type {
+                let groups = FileGroup::of(a).cmp(&FileGroup::of(b));
+                if groups.is_ne() {
+                    return groups;
                 }
-                SortField::Kind => a.kind.label().cmp(b.kind.label()),
-            };
+            }
+
+            // Primary sort field comparison
+            let cmp = self.compare_by(self.field, a, b);
 
             // Apply order
-            match self.order {
+            let cmp = match self.order {
                 SortOrder::Ascending => cmp,
                 SortOrder::Descending => cmp.reverse(),
+            };
+
+            match self.then_by {
+                Some(field) => cmp.then_with(|| self.compare_by(field, a, b)),
+                None => cmp,
             }
         });
     }
@@ -283,6 +490,104 @@ mod tests {
         assert_eq!(entries[2].name, "zfile.txt");
     }
 
+    #[test]
+    fn test_dirs_by_computed_size() {
+        let mut big = make_dir("big");
+        big.computed_size = Some(5000);
+        let pending = make_dir("pending");
+        let mut entries = vec![
+            make_file("small.txt", 10),
+            big,
+            make_file("medium.txt", 800),
+            pending,
+        ];
+
+        let mut spec = SortSpec::by_size();
+        spec.dirs_by_computed_size = true;
+        spec.sort(&mut entries);
+
+        // Directories take their place among files; unknown sizes sort as 0
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["big", "medium.txt", "small.txt", "pending"]);
+
+        // Other fields keep directories first
+        spec.field = SortField::Name;
+        spec.sort(&mut entries);
+        assert!(entries[0].is_directory() && entries[1].is_directory());
+    }
+
+    #[test]
+    fn test_group_by_type() {
+        let mut entries = vec![
+            make_file("b.png", 10),
+            make_file("notes.txt", 20),
+            make_dir("src"),
+            make_file("a.rs", 30),
+            make_file("a.png", 40),
+        ];
+
+        let mut spec = SortSpec::new(SortField::Name, SortOrder::Descending);
+        spec.group_by_type = true;
+        spec.sort(&mut entries);
+
+        // Groups stay in order while names sort descending within them
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["src", "notes.txt", "b.png", "a.png", "a.rs"]);
+    }
+
+    #[test]
+    fn test_natural_sort() {
+        let names = ["file10.txt", "File2.txt", "file1.txt", "file01.txt", "file.txt"];
+        let mut entries: Vec<EntryMeta> = names.iter().map(|n| make_file(n, 0)).collect();
+
+        let mut spec = SortSpec::by_name();
+        spec.sort(&mut entries);
+        let sorted: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(sorted, ["file.txt", "file01.txt", "file1.txt", "file10.txt", "File2.txt"]);
+
+        spec.natural = true;
+        spec.sort(&mut entries);
+        let sorted: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(sorted, ["file.txt", "file01.txt", "file1.txt", "File2.txt", "file10.txt"]);
+    }
+
+    #[test]
+    fn test_locale_collation() {
+        let names = ["Zebra", "éclair", "eclair", "Eclair", "ecole"];
+        let mut entries: Vec<EntryMeta> = names.iter().map(|n| make_file(n, 0)).collect();
+
+        let mut spec = SortSpec::by_name();
+        spec.sort(&mut entries);
+        // Ordinal puts accented letters after every plain one
+        assert_eq!(entries.last().unwrap().name, "éclair");
+
+        spec.collation = Collation::Locale;
+        spec.sort(&mut entries);
+        let sorted: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(sorted, ["Eclair", "eclair", "éclair", "ecole", "Zebra"]);
+    }
+
+    #[test]
+    fn test_secondary_sort() {
+        let mut entries = vec![
+            make_file("b.txt", 1),
+            make_file("c.rs", 2),
+            make_file("a.txt", 3),
+            make_file("a.rs", 4),
+        ];
+
+        let mut spec = SortSpec::new(SortField::Extension, SortOrder::Descending);
+        spec.then_by = Some(SortField::Name);
+        spec.sort(&mut entries);
+
+        // The secondary field stays ascending
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["a.txt", "b.txt", "a.rs", "c.rs"]);
+
+        spec.toggle_directories_first();
+        assert!(!spec.directories_first);
+    }
+
     #[test]
     fn test_case_insensitive_sort() {
         let mut entries = vec![
@@ -0,0 +1,428 @@
+//! Small text files loaded whole for editing.
+//!
+//! [`TextFile`] keeps what it takes to write a file back the way it was
+//! read: its encoding, byte order mark and line endings. Files that would
+//! not come back byte for byte, such as ones mixing line endings, aren't
+//! loaded at all, so editing never changes more than the user typed.
+//!
+//! Converting files to another encoding or line ending is the exception:
+//! [`convert_text_files_with_progress`] rewrites each file whole, optionally
+//! keeping a backup of the original next to it.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::fs::extended_path;
+use crate::viewer::{TextEncoding, SAMPLE_SIZE};
+use crate::{write_file_atomic, CancellationToken, Progress, ZError, ZResult};
+
+/// Largest file converted, as conversions hold the whole file in memory.
+pub const MAX_CONVERT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// How lines end in a text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    /// A line feed, as on Unix
+    Lf,
+    /// A carriage return and line feed, as on Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// The characters ending a line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// Display name of the line ending.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::CrLf => "CRLF",
+        }
+    }
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::CrLf
+        } else {
+            Self::Lf
+        }
+    }
+}
+
+/// A text file split into lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFile {
+    /// The lines, without line endings. Text ending in a line break has an
+    /// empty last line.
+    pub lines: Vec<String>,
+    /// Encoding the file is written in.
+    pub encoding: TextEncoding,
+    /// Whether the file starts with a byte order mark.
+    pub bom: bool,
+    /// How lines end.
+    pub line_ending: LineEnding,
+}
+
+impl TextFile {
+    /// Load the file at `path` if it is text of at most `max_size` bytes.
+    ///
+    /// # Errors
+    /// * `ZError::InvalidOperation` - The file is too large, binary, or
+    ///   would not be written back unchanged
+    /// * `ZError::Io` - The file could not be read
+    pub fn load(path: impl AsRef<Path>, max_size: u64) -> ZResult<Self> {
+        let path = path.as_ref();
+        let refuse = |reason: String| ZError::InvalidOperation {
+            operation: "edit file".to_string(),
+            reason,
+        };
+
+        let meta = std::fs::metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+        if meta.len() > max_size {
+            return Err(refuse(format!("File is larger than {max_size} bytes")));
+        }
+        let bytes = std::fs::read(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+        debug!(path = %path.display(), len = bytes.len(), "Loading text file");
+
+        Self::parse(&bytes).ok_or_else(|| refuse("Not a plain text file".to_string()))
+    }
+
+    /// Split `bytes` into lines, or `None` if they look binary or would
+    /// not encode back to the same bytes.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let file = Self::decode(bytes)?;
+        (file.to_bytes().as_deref() == Some(bytes)).then_some(file)
+    }
+
+    /// Split `bytes` into lines however each of them ends, or `None` if
+    /// they look binary. The file takes the first line's ending.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut encoding = TextEncoding::detect(&bytes[..bytes.len().min(SAMPLE_SIZE)]);
+        let bom_len = encoding.bom_len(bytes) as usize;
+        let body = &bytes[bom_len..];
+        if matches!(encoding, TextEncoding::Utf8 | TextEncoding::Ansi) {
+            if body.contains(&0) {
+                return None;
+            }
+            // Only the start decided the encoding
+            if encoding == TextEncoding::Utf8 && std::str::from_utf8(body).is_err() {
+                encoding = TextEncoding::Ansi;
+            }
+        }
+
+        let text = encoding.decode_all(body);
+        let line_ending = match text.find('\n') {
+            Some(i) if text[..i].ends_with('\r') => LineEnding::CrLf,
+            Some(_) => LineEnding::Lf,
+            None => LineEnding::default(),
+        };
+        let lines = text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line));
+        Some(Self {
+            lines: lines.map(str::to_string).collect(),
+            encoding,
+            bom: bom_len > 0,
+            line_ending,
+        })
+    }
+
+    /// The file's contents, or `None` if the lines have characters its
+    /// encoding can't hold.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let mut bytes = if self.bom { self.encoding.encode("\u{feff}")? } else { Vec::new() };
+        bytes.extend(self.encoding.encode(&self.lines.join(self.line_ending.as_str()))?);
+        Some(bytes)
+    }
+
+    /// Write the file to `path`, replacing it atomically.
+    ///
+    /// # Errors
+    /// * `ZError::InvalidOperation` - The text has characters the encoding
+    ///   can't hold
+    /// * `ZError::PermissionDenied` - The file is read-only
+    /// * `ZError::Io` - The file could not be written
+    pub fn save(&self, path: impl AsRef<Path>) -> ZResult<()> {
+        let bytes = self.to_bytes().ok_or_else(|| ZError::InvalidOperation {
+            operation: "save file".to_string(),
+            reason: format!("The text has characters {} can't hold", self.encoding.name()),
+        })?;
+        write_file_atomic(path, &bytes)
+    }
+}
+
+/// Encoding and line ending to convert text files to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextConversion {
+    /// Encoding to write, or None to keep each file's own.
+    pub encoding: Option<TextEncoding>,
+    /// Line ending to write, or None to keep each file's own.
+    pub line_ending: Option<LineEnding>,
+    /// Whether to copy each file to a backup before rewriting it.
+    pub backup: bool,
+}
+
+impl TextConversion {
+    /// Encodings files can be converted to.
+    pub const ENCODINGS: [TextEncoding; 3] =
+        [TextEncoding::Utf8, TextEncoding::Utf16Le, TextEncoding::Ansi];
+
+    /// Convert `file`, returning whether anything changed.
+    ///
+    /// UTF-16 is written with a byte order mark. UTF-8 keeps the file's
+    /// mark when it already was UTF-8, and has none otherwise.
+    pub fn apply(&self, file: &mut TextFile) -> bool {
+        let before = (file.encoding, file.bom, file.line_ending);
+        if let Some(encoding) = self.encoding.filter(|&encoding| encoding != file.encoding) {
+            file.bom = matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be);
+            file.encoding = encoding;
+        }
+        if let Some(line_ending) = self.line_ending {
+            file.line_ending = line_ending;
+        }
+        before != (file.encoding, file.bom, file.line_ending)
+    }
+}
+
+/// What converting one file did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionOutcome {
+    /// The file was rewritten, after being copied to the backup if any.
+    Converted { backup: Option<PathBuf> },
+    /// The file already was in the encoding and line ending asked for.
+    Unchanged,
+}
+
+/// Result of converting one file in a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileConversion {
+    /// The file that was converted.
+    pub path: PathBuf,
+    /// What was done, or why the file couldn't be converted.
+    pub outcome: Result<ConversionOutcome, String>,
+}
+
+/// Convert the text file at `path`, replacing it atomically.
+///
+/// Files mixing line endings are converted too, with every line ending
+/// made the same when a line ending is asked for.
+///
+/// # Errors
+/// * `ZError::InvalidOperation` - The file is binary, larger than
+///   [`MAX_CONVERT_SIZE`], or has characters the new encoding can't hold
+/// * `ZError::PermissionDenied` - The file is read-only
+/// * `ZError::Io` - The file could not be read, backed up or written
+pub fn convert_text_file(
+    path: impl AsRef<Path>,
+    conversion: &TextConversion,
+) -> ZResult<ConversionOutcome> {
+    let path = path.as_ref();
+    let refuse = |reason: String| ZError::InvalidOperation {
+        operation: "convert file".to_string(),
+        reason,
+    };
+
+    let meta = std::fs::metadata(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    if meta.len() > MAX_CONVERT_SIZE {
+        return Err(refuse(format!("File is larger than {MAX_CONVERT_SIZE} bytes")));
+    }
+    let bytes = std::fs::read(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    let mut file = TextFile::decode(&bytes).ok_or_else(|| refuse("Not a text file".to_string()))?;
+    conversion.apply(&mut file);
+    let converted = file.to_bytes().ok_or_else(|| {
+        refuse(format!("The text has characters {} can't hold", file.encoding.name()))
+    })?;
+    if converted == bytes {
+        return Ok(ConversionOutcome::Unchanged);
+    }
+
+    let backup = if conversion.backup {
+        let backup = backup_path(path);
+        std::fs::copy(extended_path(path), extended_path(&backup))
+            .map_err(|e| ZError::from_io(&backup, e))?;
+        Some(backup)
+    } else {
+        None
+    };
+    debug!(path = %path.display(), encoding = file.encoding.name(), "Converting text file");
+    write_file_atomic(path, &converted)?;
+    Ok(ConversionOutcome::Converted { backup })
+}
+
+/// A free name for a backup of `path`: `name.bak`, or `name.bak2` and up
+/// when that is taken.
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    (1..)
+        .map(|n| match n {
+            1 => path.with_file_name(format!("{name}.bak")),
+            n => path.with_file_name(format!("{name}.bak{n}")),
+        })
+        .find(|candidate| !extended_path(candidate).exists())
+        .expect("some backup name is free")
+}
+
+/// Convert several text files, reporting bytes and files done to
+/// `on_progress`.
+///
+/// A file that can't be converted is recorded with its error and the rest
+/// are still converted; only cancellation stops the batch.
+pub fn convert_text_files_with_progress(
+    paths: &[PathBuf],
+    conversion: &TextConversion,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&Progress),
+) -> ZResult<Vec<FileConversion>> {
+    let sizes: Vec<u64> = paths
+        .iter()
+        .map(|path| std::fs::metadata(path).map_or(0, |m| m.len()))
+        .collect();
+    let mut progress = Progress::new(paths.len(), Some(sizes.iter().sum()));
+    let mut results = Vec::with_capacity(paths.len());
+
+    for (path, size) in paths.iter().zip(sizes) {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        progress.current_item = Some(path.clone());
+        on_progress(&progress);
+
+        results.push(FileConversion {
+            path: path.clone(),
+            outcome: convert_text_file(path, conversion).map_err(|e| e.to_string()),
+        });
+        progress.bytes_done += size;
+        progress.items_done += 1;
+    }
+
+    progress.current_item = None;
+    on_progress(&progress);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_keeps_the_file_format() {
+        let file = TextFile::parse(b"one\r\ntwo\r\n").unwrap();
+        assert_eq!(file.lines, ["one", "two", ""]);
+        assert_eq!(file.line_ending, LineEnding::CrLf);
+        assert_eq!(file.encoding, TextEncoding::Utf8);
+
+        let utf16: Vec<u8> = "\u{feff}héllo\nwörld"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let file = TextFile::parse(&utf16).unwrap();
+        assert_eq!(file.lines, ["héllo", "wörld"]);
+        assert!(file.bom);
+        assert_eq!(file.to_bytes().unwrap(), utf16);
+
+        // ANSI text past the part the encoding is detected from
+        let mut ansi = vec![b'a'; SAMPLE_SIZE];
+        ansi.extend(b"caf\xe9");
+        let file = TextFile::parse(&ansi).unwrap();
+        assert_eq!(file.encoding, TextEncoding::Ansi);
+        assert!(file.lines[0].ends_with("café"));
+    }
+
+    #[test]
+    fn test_parse_refuses_what_it_cant_write_back() {
+        assert_eq!(TextFile::parse(b"MZ\0\0\x90"), None);
+        assert_eq!(TextFile::parse(b"mixed\r\nline\nendings"), None);
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("notes.txt");
+        std::fs::write(&path, "first\nsecond\n").unwrap();
+
+        let result = TextFile::load(&path, 4);
+        assert!(matches!(result, Err(ZError::InvalidOperation { .. })));
+
+        let mut file = TextFile::load(&path, 1024).unwrap();
+        file.lines[1] = "2nd".to_string();
+        file.save(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\n2nd\n");
+
+        file.encoding = TextEncoding::Ansi;
+        file.lines[0] = "日本".to_string();
+        assert!(matches!(file.save(&path), Err(ZError::InvalidOperation { .. })));
+    }
+
+    #[test]
+    fn test_convert_text_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("mixed.txt");
+        std::fs::write(&path, "caf\u{e9}\r\nline\nend").unwrap();
+
+        let to_utf16 = TextConversion {
+            encoding: Some(TextEncoding::Utf16Le),
+            line_ending: Some(LineEnding::CrLf),
+            backup: true,
+        };
+        let outcome = convert_text_file(&path, &to_utf16).unwrap();
+        let backup = temp.path().join("mixed.txt.bak");
+        assert_eq!(outcome, ConversionOutcome::Converted { backup: Some(backup.clone()) });
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "caf\u{e9}\r\nline\nend");
+        let expected: Vec<u8> = "\u{feff}caf\u{e9}\r\nline\r\nend"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+
+        // Nothing left to do, so no second backup
+        assert_eq!(convert_text_file(&path, &to_utf16).unwrap(), ConversionOutcome::Unchanged);
+        assert!(!temp.path().join("mixed.txt.bak2").exists());
+
+        let to_ansi = TextConversion {
+            encoding: Some(TextEncoding::Ansi),
+            line_ending: Some(LineEnding::Lf),
+            backup: true,
+        };
+        convert_text_file(&path, &to_ansi).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"caf\xe9\nline\nend");
+        assert!(temp.path().join("mixed.txt.bak2").exists());
+    }
+
+    #[test]
+    fn test_convert_text_files_reports_each_file() {
+        let temp = TempDir::new().unwrap();
+        let text = temp.path().join("a.txt");
+        let binary = temp.path().join("b.bin");
+        std::fs::write(&text, "one\ntwo\n").unwrap();
+        std::fs::write(&binary, b"MZ\0\0").unwrap();
+        let paths = vec![text.clone(), binary, temp.path().join("missing.txt")];
+
+        let conversion = TextConversion {
+            line_ending: Some(LineEnding::CrLf),
+            ..TextConversion::default()
+        };
+        let mut last = None;
+        let results = convert_text_files_with_progress(
+            &paths,
+            &conversion,
+            &CancellationToken::new(),
+            |progress| last = Some(progress.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(results[0].outcome, Ok(ConversionOutcome::Converted { backup: None }));
+        assert!(results[1].outcome.as_ref().unwrap_err().contains("Not a text file"));
+        assert!(results[2].outcome.is_err());
+        assert_eq!(std::fs::read_to_string(&text).unwrap(), "one\r\ntwo\r\n");
+        assert_eq!(last.unwrap().items_done, 3);
+    }
+}
@@ -0,0 +1,756 @@
+//! Paging through file contents for the built-in viewer.
+//!
+//! [`FileContent`] reads a file a block at a time, so a file of any size
+//! opens at once and only the part on screen is held in memory. It detects
+//! the text encoding from the first bytes, splits text into lines (wrapped
+//! or not) and bytes into hex rows, and [`find_in_file`] searches the file
+//! forward or backward from a position. Positions are byte offsets into the
+//! file.
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CancellationToken, ZError, ZResult};
+
+/// Size of the blocks files are read in.
+const BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Blocks kept in memory while paging.
+const CACHED_BLOCKS: usize = 8;
+
+/// Bytes looked at to detect the encoding.
+pub(crate) const SAMPLE_SIZE: usize = 8 * 1024;
+
+/// Longest run of bytes shown as one line; longer lines are broken up so
+/// a file without line breaks still pages.
+pub const MAX_LINE_BYTES: u64 = 4096;
+
+/// Bytes per row in hex mode.
+pub const HEX_ROW_BYTES: u64 = 16;
+
+/// Columns a tab advances to a multiple of.
+const TAB_WIDTH: usize = 8;
+
+/// Size of the chunks read while searching.
+const SEARCH_CHUNK: u64 = 1024 * 1024;
+
+/// Characters of Windows-1252 for the bytes 0x80 to 0x9F, where it differs
+/// from Latin-1.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡',
+    'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—',
+    '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Encoding text is decoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    /// UTF-8, which includes plain ASCII
+    Utf8,
+    /// UTF-16, little-endian, as Windows writes it
+    Utf16Le,
+    /// UTF-16, big-endian
+    Utf16Be,
+    /// The Windows ANSI code page (Windows-1252)
+    Ansi,
+}
+
+impl TextEncoding {
+    /// All encodings, in the order the viewer cycles through them.
+    pub const ALL: [Self; 4] = [Self::Utf8, Self::Utf16Le, Self::Utf16Be, Self::Ansi];
+
+    /// Display name of the encoding.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16 LE",
+            Self::Utf16Be => "UTF-16 BE",
+            Self::Ansi => "ANSI",
+        }
+    }
+
+    /// The encoding after this one in [`Self::ALL`].
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&e| e == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Bytes per code unit.
+    fn unit(self) -> u64 {
+        match self {
+            Self::Utf16Le | Self::Utf16Be => 2,
+            Self::Utf8 | Self::Ansi => 1,
+        }
+    }
+
+    /// Length of this encoding's byte order mark at the start of `bytes`,
+    /// 0 if there is none.
+    pub(crate) fn bom_len(self, bytes: &[u8]) -> u64 {
+        let bom: &[u8] = match self {
+            Self::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Self::Utf16Le => &[0xFF, 0xFE],
+            Self::Utf16Be => &[0xFE, 0xFF],
+            Self::Ansi => return 0,
+        };
+        if bytes.starts_with(bom) {
+            bom.len() as u64
+        } else {
+            0
+        }
+    }
+
+    /// Guess the encoding of text starting with `sample`, which holds the
+    /// whole text if it is shorter than the 8 KiB looked at.
+    ///
+    /// A byte order mark decides; otherwise text with NULs in every other
+    /// byte is taken for UTF-16, valid UTF-8 for UTF-8 and anything else
+    /// for ANSI.
+    pub fn detect(sample: &[u8]) -> Self {
+        for encoding in [Self::Utf8, Self::Utf16Le, Self::Utf16Be] {
+            if encoding.bom_len(sample) > 0 {
+                return encoding;
+            }
+        }
+
+        let pairs = sample.len() / 2;
+        if pairs > 0 {
+            let even = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+            let odd = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+            if odd * 10 > pairs * 4 && even * 10 < pairs {
+                return Self::Utf16Le;
+            }
+            if even * 10 > pairs * 4 && odd * 10 < pairs {
+                return Self::Utf16Be;
+            }
+        }
+
+        match std::str::from_utf8(sample) {
+            Ok(_) => Self::Utf8,
+            // A character cut off by the end of the sample is fine
+            Err(e) if e.error_len().is_none() && sample.len() >= SAMPLE_SIZE => Self::Utf8,
+            Err(_) => Self::Ansi,
+        }
+    }
+
+    /// Decode the character at the start of `bytes`, returning it with its
+    /// length in bytes. Bytes that don't decode give U+FFFD.
+    fn decode(self, bytes: &[u8]) -> (char, usize) {
+        const INVALID: char = char::REPLACEMENT_CHARACTER;
+        let Some(&first) = bytes.first() else {
+            return (INVALID, 1);
+        };
+        match self {
+            Self::Ansi => match first {
+                0x80..=0x9F => (WINDOWS_1252[usize::from(first - 0x80)], 1),
+                _ => (char::from(first), 1),
+            },
+            Self::Utf8 => {
+                let width = match first {
+                    0x00..=0x7F => return (char::from(first), 1),
+                    0xC2..=0xDF => 2,
+                    0xE0..=0xEF => 3,
+                    0xF0..=0xF4 => 4,
+                    _ => return (INVALID, 1),
+                };
+                bytes
+                    .get(..width)
+                    .and_then(|b| std::str::from_utf8(b).ok())
+                    .and_then(|s| s.chars().next())
+                    .map_or((INVALID, 1), |c| (c, width))
+            }
+            Self::Utf16Le | Self::Utf16Be => {
+                let units = bytes.chunks_exact(2).take(2).map(|b| self.unit_value(b));
+                match char::decode_utf16(units).next() {
+                    Some(Ok(c)) => (c, c.len_utf16() * 2),
+                    _ => (INVALID, 2.min(bytes.len())),
+                }
+            }
+        }
+    }
+
+    /// The value of the code unit in `bytes`.
+    fn unit_value(self, bytes: &[u8]) -> u16 {
+        match self {
+            Self::Utf16Le => u16::from_le_bytes([bytes[0], bytes[1]]),
+            Self::Utf16Be => u16::from_be_bytes([bytes[0], bytes[1]]),
+            Self::Utf8 | Self::Ansi => u16::from(bytes[0]),
+        }
+    }
+
+    /// Decode all of `bytes`, which hold no byte order mark.
+    pub(crate) fn decode_all(self, bytes: &[u8]) -> String {
+        let mut text = String::with_capacity(bytes.len());
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let (c, len) = self.decode(&bytes[pos..]);
+            text.push(c);
+            pos += len;
+        }
+        text
+    }
+
+    /// Encode `text` in this encoding, or `None` if it has characters the
+    /// ANSI code page lacks.
+    pub(crate) fn encode(self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Utf8 => Some(text.as_bytes().to_vec()),
+            Self::Utf16Le => Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Self::Utf16Be => Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Self::Ansi => text
+                .chars()
+                .map(|c| match WINDOWS_1252.iter().position(|&w| w == c) {
+                    Some(i) => Some(0x80 + i as u8),
+                    None => u8::try_from(u32::from(c)).ok(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A line of text as shown by the viewer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentLine {
+    /// Offset of the line's first byte.
+    pub offset: u64,
+    /// Offset just past the line, including its line break.
+    pub end: u64,
+    /// The line with tabs expanded and control characters shown as dots.
+    pub text: String,
+    /// Offset of the byte each character of `text` came from.
+    char_offsets: Vec<u64>,
+}
+
+impl ContentLine {
+    /// The characters of `text` that came from the bytes in `range`.
+    pub fn char_range(&self, range: &Range<u64>) -> Option<Range<usize>> {
+        let start = self.char_offsets.iter().position(|&o| o >= range.start)?;
+        let len = self.char_offsets[start..].iter().take_while(|&&o| o < range.end).count();
+        (len > 0).then_some(start..start + len)
+    }
+}
+
+/// A row of bytes as shown in hex mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexRow {
+    /// Offset of the row's first byte, a multiple of [`HEX_ROW_BYTES`].
+    pub offset: u64,
+    /// Up to [`HEX_ROW_BYTES`] bytes.
+    pub bytes: Vec<u8>,
+}
+
+/// The contents of a file, read as they are paged through.
+#[derive(Debug)]
+pub struct FileContent {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    encoding: TextEncoding,
+    /// Whether the file looks like binary data rather than text.
+    binary: bool,
+    /// First bytes of the file, for detecting the byte order mark.
+    sample: Vec<u8>,
+    /// Recently read blocks by offset, most recent last.
+    blocks: RefCell<Vec<(u64, Vec<u8>)>>,
+}
+
+impl FileContent {
+    /// Open `path`, detecting its encoding.
+    pub fn open(path: impl AsRef<Path>) -> ZResult<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| ZError::from_io(path, e))?;
+        let meta = file.metadata().map_err(|e| ZError::from_io(path, e))?;
+        if meta.is_dir() {
+            return Err(ZError::NotAFile {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let mut sample = Vec::with_capacity(SAMPLE_SIZE);
+        (&mut file)
+            .take(SAMPLE_SIZE as u64)
+            .read_to_end(&mut sample)
+            .map_err(|e| ZError::from_io(path, e))?;
+        let encoding = TextEncoding::detect(&sample);
+        let binary = encoding.unit() == 1 && sample.contains(&0);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len: meta.len(),
+            encoding,
+            binary,
+            sample,
+            blocks: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Encoding text is decoded with.
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    /// Decode text with `encoding` from now on.
+    pub fn set_encoding(&mut self, encoding: TextEncoding) {
+        self.encoding = encoding;
+    }
+
+    /// Whether the file looks like binary data, best shown in hex.
+    pub fn is_binary(&self) -> bool {
+        self.binary
+    }
+
+    /// Offset of the first character, past any byte order mark.
+    pub fn text_start(&self) -> u64 {
+        self.encoding.bom_len(&self.sample)
+    }
+
+    /// Read up to `len` bytes at `offset`; fewer at the end of the file.
+    pub fn read_at(&self, offset: u64, len: u64) -> ZResult<Vec<u8>> {
+        let end = offset.saturating_add(len).min(self.len);
+        let mut data = Vec::with_capacity(end.saturating_sub(offset) as usize);
+        let mut pos = offset;
+        while pos < end {
+            let block_start = pos - pos % BLOCK_SIZE;
+            let block = self.block(block_start)?;
+            let from = (pos - block_start) as usize;
+            let to = ((end - block_start) as usize).min(block.len());
+            if from >= to {
+                break;
+            }
+            data.extend_from_slice(&block[from..to]);
+            pos = block_start + to as u64;
+        }
+        Ok(data)
+    }
+
+    /// The block at `start`, from the cache or the file.
+    fn block(&self, start: u64) -> ZResult<Vec<u8>> {
+        let mut blocks = self.blocks.borrow_mut();
+        if let Some(index) = blocks.iter().position(|(offset, _)| *offset == start) {
+            let block = blocks.remove(index);
+            let data = block.1.clone();
+            blocks.push(block);
+            return Ok(data);
+        }
+
+        let mut file = &self.file;
+        let mut data = Vec::with_capacity(BLOCK_SIZE as usize);
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.take(BLOCK_SIZE).read_to_end(&mut data))
+            .map_err(|e| ZError::from_io(&self.path, e))?;
+        if blocks.len() >= CACHED_BLOCKS {
+            blocks.remove(0);
+        }
+        blocks.push((start, data.clone()));
+        Ok(data)
+    }
+
+    /// Round `offset` down to the start of a code unit.
+    fn align(&self, offset: u64) -> u64 {
+        offset - offset % self.encoding.unit()
+    }
+
+    /// The line starting at `offset`, ending at a line break, after `wrap`
+    /// characters, or after [`MAX_LINE_BYTES`].
+    pub fn line_at(&self, offset: u64, wrap: Option<usize>) -> ZResult<ContentLine> {
+        let bytes = self.read_at(offset, MAX_LINE_BYTES + 4)?;
+        let limit = (MAX_LINE_BYTES as usize).min(bytes.len());
+        let wrap = wrap.filter(|&w| w > 0);
+
+        let mut text = String::new();
+        let mut char_offsets = Vec::new();
+        let mut pos = 0;
+        while pos < limit {
+            let (c, len) = self.encoding.decode(&bytes[pos..]);
+            let char_offset = offset + pos as u64;
+            if c == '\n' {
+                pos += len;
+                break;
+            }
+            let shown = match c {
+                '\t' => TAB_WIDTH - char_offsets.len() % TAB_WIDTH,
+                // Carriage returns end lines along with the line feed
+                '\r' => 0,
+                _ => 1,
+            };
+            if wrap.is_some_and(|w| char_offsets.len() + shown > w && !char_offsets.is_empty()) {
+                break;
+            }
+            for _ in 0..shown {
+                text.push(match c {
+                    '\t' => ' ',
+                    c if c.is_control() => '.',
+                    c => c,
+                });
+                char_offsets.push(char_offset);
+            }
+            pos += len;
+        }
+
+        Ok(ContentLine {
+            offset,
+            end: offset + pos as u64,
+            text,
+            char_offsets,
+        })
+    }
+
+    /// Up to `count` lines starting at `offset`.
+    pub fn lines(
+        &self,
+        offset: u64,
+        count: usize,
+        wrap: Option<usize>,
+    ) -> ZResult<Vec<ContentLine>> {
+        let mut lines = Vec::with_capacity(count);
+        let mut pos = offset;
+        while lines.len() < count && pos < self.len {
+            let line = self.line_at(pos, wrap)?;
+            pos = line.end;
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+
+    /// Start of the line holding the byte at `offset`.
+    pub fn line_start(&self, offset: u64, wrap: Option<usize>) -> ZResult<u64> {
+        let start = self.text_start();
+        let offset = self.align(offset.min(self.len));
+        if offset <= start {
+            return Ok(start);
+        }
+
+        // Back up to just past the previous line break; a line too long
+        // to have one in reach is taken to start a full line back, at the
+        // next character
+        let unit = self.encoding.unit();
+        let window = (offset + unit).saturating_sub(MAX_LINE_BYTES).max(start);
+        let before = self.read_at(window, offset - window)?;
+        let line_break = before
+            .chunks_exact(unit as usize)
+            .rposition(|bytes| self.encoding.unit_value(bytes) == u16::from(b'\n'));
+        let mut line = match line_break {
+            Some(index) => window + (index as u64 + 1) * unit,
+            None if self.encoding == TextEncoding::Utf8 => {
+                let continuation = before.iter().take_while(|&&b| b & 0xC0 == 0x80).count();
+                window + continuation as u64
+            }
+            None => window,
+        };
+
+        // Walk the line's wrapped rows up to the one holding the offset
+        loop {
+            let row = self.line_at(line, wrap)?;
+            if row.end > offset || row.end <= line {
+                return Ok(line);
+            }
+            line = row.end;
+        }
+    }
+
+    /// Start of the line before the one starting at `offset`.
+    pub fn prev_line(&self, offset: u64, wrap: Option<usize>) -> ZResult<u64> {
+        let start = self.text_start();
+        if offset <= start {
+            return Ok(start);
+        }
+        self.line_start(offset - self.encoding.unit(), wrap)
+    }
+
+    /// Start of the line `count` lines up from the last one, where the
+    /// view stops scrolling.
+    pub fn last_page(&self, count: usize, wrap: Option<usize>) -> ZResult<u64> {
+        if self.len == 0 {
+            return Ok(0);
+        }
+        let mut line = self.line_start(self.len - 1, wrap)?;
+        for _ in 1..count {
+            let prev = self.prev_line(line, wrap)?;
+            if prev == line {
+                break;
+            }
+            line = prev;
+        }
+        Ok(line)
+    }
+
+    /// Up to `count` hex rows starting at the row holding `offset`.
+    pub fn hex_rows(&self, offset: u64, count: usize) -> ZResult<Vec<HexRow>> {
+        let start = offset - offset % HEX_ROW_BYTES;
+        let data = self.read_at(start, count as u64 * HEX_ROW_BYTES)?;
+        Ok(data
+            .chunks(HEX_ROW_BYTES as usize)
+            .enumerate()
+            .map(|(i, bytes)| HexRow {
+                offset: start + i as u64 * HEX_ROW_BYTES,
+                bytes: bytes.to_vec(),
+            })
+            .collect())
+    }
+}
+
+/// Text to look for in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentSearch {
+    /// The text, encoded like the file before searching.
+    pub text: String,
+    /// Whether letters must match in case; otherwise ASCII letters match
+    /// either case.
+    pub match_case: bool,
+    /// Look before the starting position instead of after it.
+    pub backward: bool,
+}
+
+/// Find `search` in the file at `path`, decoded with `encoding`.
+///
+/// Searching forward finds the first match starting at or after `from`;
+/// backward, the last match starting before it. Returns the bytes of the
+/// match.
+pub fn find_in_file(
+    path: impl AsRef<Path>,
+    encoding: TextEncoding,
+    search: &ContentSearch,
+    from: u64,
+    cancel: &CancellationToken,
+) -> ZResult<Option<Range<u64>>> {
+    let path = path.as_ref();
+    let Some(needle) = encoding.encode(&search.text).filter(|n| !n.is_empty()) else {
+        return Ok(None);
+    };
+    let mut file = File::open(path).map_err(|e| ZError::from_io(path, e))?;
+    let len = file.metadata().map_err(|e| ZError::from_io(path, e))?.len();
+    let unit = encoding.unit();
+    let needle_len = needle.len() as u64;
+    let fold = |bytes: &[u8]| -> u16 {
+        let value = encoding.unit_value(bytes);
+        if search.match_case || value > 0x7F {
+            value
+        } else {
+            u16::from((value as u8).to_ascii_lowercase())
+        }
+    };
+    let needle: Vec<u16> = needle.chunks_exact(unit as usize).map(fold).collect();
+    let matches_at = |chunk: &[u8], at: usize| {
+        chunk[at..at + needle_len as usize]
+            .chunks_exact(unit as usize)
+            .zip(&needle)
+            .all(|(bytes, &n)| fold(bytes) == n)
+    };
+
+    let from = from - from % unit;
+    let mut chunk = Vec::new();
+    let mut read_chunk = |start: u64, chunk: &mut Vec<u8>| -> ZResult<()> {
+        chunk.clear();
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| (&mut file).take(SEARCH_CHUNK + needle_len).read_to_end(chunk))
+            .map_err(|e| ZError::from_io(path, e))?;
+        Ok(())
+    };
+
+    if search.backward {
+        let mut end = from.min(len);
+        while end > 0 {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            let start = end.saturating_sub(SEARCH_CHUNK);
+            let start = start - start % unit;
+            read_chunk(start, &mut chunk)?;
+            let starts = (0..(end - start) as usize).step_by(unit as usize).rev();
+            for at in starts {
+                if at + needle.len() * unit as usize <= chunk.len() && matches_at(&chunk, at) {
+                    let found = start + at as u64;
+                    return Ok(Some(found..found + needle_len));
+                }
+            }
+            end = start;
+        }
+    } else {
+        let mut start = from;
+        while start < len {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            read_chunk(start, &mut chunk)?;
+            let starts = (0..chunk.len().min(SEARCH_CHUNK as usize)).step_by(unit as usize);
+            for at in starts {
+                if at + needle.len() * unit as usize <= chunk.len() && matches_at(&chunk, at) {
+                    let found = start + at as u64;
+                    return Ok(Some(found..found + needle_len));
+                }
+            }
+            start += SEARCH_CHUNK;
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file_with(dir: &TempDir, name: &str, data: &[u8]) -> FileContent {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        FileContent::open(path).unwrap()
+    }
+
+    fn texts(lines: &[ContentLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(TextEncoding::detect(b"plain text"), TextEncoding::Utf8);
+        assert_eq!(TextEncoding::detect("naïve".as_bytes()), TextEncoding::Utf8);
+        assert_eq!(TextEncoding::detect(b"caf\xe9"), TextEncoding::Ansi);
+        assert_eq!(TextEncoding::detect(b"\xef\xbb\xbfhi"), TextEncoding::Utf8);
+        assert_eq!(TextEncoding::detect(b"\xff\xfeh\0i\0"), TextEncoding::Utf16Le);
+        assert_eq!(TextEncoding::detect(b"h\0e\0l\0l\0o\0"), TextEncoding::Utf16Le);
+        assert_eq!(TextEncoding::detect(b"\0h\0e\0l\0l\0o"), TextEncoding::Utf16Be);
+        // A character cut off at the end of the sample is still UTF-8
+        let mut sample = "é".repeat(SAMPLE_SIZE).into_bytes();
+        sample.truncate(SAMPLE_SIZE - 1);
+        sample.insert(0, b'a');
+        assert_eq!(TextEncoding::detect(&sample), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_lines_decode_each_encoding() {
+        let dir = TempDir::new().unwrap();
+
+        let utf8 = file_with(&dir, "utf8.txt", "\u{feff}première\r\nligne\tdeux\n".as_bytes());
+        assert_eq!(utf8.text_start(), 3);
+        let lines = utf8.lines(utf8.text_start(), 10, None).unwrap();
+        assert_eq!(texts(&lines), ["première", "ligne   deux"]);
+
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("one\r\ntwo".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let utf16 = file_with(&dir, "utf16.txt", &utf16);
+        assert_eq!(utf16.encoding(), TextEncoding::Utf16Le);
+        let lines = utf16.lines(utf16.text_start(), 10, None).unwrap();
+        assert_eq!(texts(&lines), ["one", "two"]);
+
+        let ansi = file_with(&dir, "ansi.txt", b"\x80 5 caf\xe9");
+        assert_eq!(ansi.encoding(), TextEncoding::Ansi);
+        assert_eq!(ansi.line_at(0, None).unwrap().text, "€ 5 café");
+    }
+
+    #[test]
+    fn test_wrapping_and_scrolling_back() {
+        let dir = TempDir::new().unwrap();
+        let content = file_with(&dir, "wrap.txt", b"abcdefghij\nkl\nmnop");
+
+        let lines = content.lines(0, 10, Some(4)).unwrap();
+        assert_eq!(texts(&lines), ["abcd", "efgh", "ij", "kl", "mnop"]);
+
+        // Each row steps back to the one above, wrapped or not
+        assert_eq!(content.prev_line(lines[3].offset, Some(4)).unwrap(), lines[2].offset);
+        assert_eq!(content.prev_line(lines[2].offset, Some(4)).unwrap(), lines[1].offset);
+        assert_eq!(content.prev_line(lines[3].offset, None).unwrap(), 0);
+        assert_eq!(content.line_start(6, Some(4)).unwrap(), 4);
+        assert_eq!(content.last_page(2, Some(4)).unwrap(), lines[3].offset);
+        assert_eq!(content.last_page(2, None).unwrap(), 11);
+    }
+
+    #[test]
+    fn test_long_lines_are_broken_up() {
+        let dir = TempDir::new().unwrap();
+        let data = vec![b'x'; MAX_LINE_BYTES as usize * 2 + 10];
+        let content = file_with(&dir, "long.txt", &data);
+
+        let lines = content.lines(0, 10, None).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].offset, MAX_LINE_BYTES);
+        assert_eq!(lines[2].text.len(), 10);
+        // Scrolling back through it goes a whole line at a time
+        assert_eq!(content.prev_line(lines[2].offset, None).unwrap(), lines[1].offset);
+    }
+
+    #[test]
+    fn test_binary_files_page_as_hex_rows() {
+        let dir = TempDir::new().unwrap();
+        let data: Vec<u8> = (0..=255).cycle().take(BLOCK_SIZE as usize + 40).collect();
+        let content = file_with(&dir, "data.bin", &data);
+        assert!(content.is_binary());
+
+        let rows = content.hex_rows(BLOCK_SIZE + 5, 4).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].offset, BLOCK_SIZE);
+        assert_eq!(rows[0].bytes, data[BLOCK_SIZE as usize..][..16]);
+        assert_eq!(rows[2].bytes.len(), 8);
+
+        // Reads across block boundaries come out whole
+        let across = content.read_at(BLOCK_SIZE - 2, 4).unwrap();
+        assert_eq!(across, data[BLOCK_SIZE as usize - 2..][..4]);
+    }
+
+    #[test]
+    fn test_find_in_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("find.txt");
+        std::fs::write(&path, "Needle, needle and NEEDLE").unwrap();
+        let cancel = CancellationToken::new();
+        let search = |text: &str, match_case, backward, from| {
+            let search = ContentSearch {
+                text: text.to_string(),
+                match_case,
+                backward,
+            };
+            find_in_file(&path, TextEncoding::Utf8, &search, from, &cancel).unwrap()
+        };
+
+        assert_eq!(search("needle", false, false, 0), Some(0..6));
+        assert_eq!(search("needle", true, false, 0), Some(8..14));
+        assert_eq!(search("needle", false, false, 1), Some(8..14));
+        assert_eq!(search("needle", false, true, 19), Some(8..14));
+        assert_eq!(search("needle", false, true, 100), Some(19..25));
+        assert_eq!(search("haystack", false, false, 0), None);
+    }
+
+    #[test]
+    fn test_find_in_utf16_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("find16.txt");
+        let data: Vec<u8> = "say Hello".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        std::fs::write(&path, data).unwrap();
+        let search = ContentSearch {
+            text: "hello".to_string(),
+            match_case: false,
+            backward: false,
+        };
+
+        let cancel = CancellationToken::new();
+        let found = find_in_file(&path, TextEncoding::Utf16Le, &search, 0, &cancel).unwrap();
+        assert_eq!(found, Some(8..18));
+
+        let content = FileContent::open(&path).unwrap();
+        let line = content.line_at(0, None).unwrap();
+        assert_eq!(line.char_range(&(8..18)), Some(4..9));
+    }
+}
@@ -0,0 +1,258 @@
+//! Directory visit history ranked by frecency.
+//!
+//! Each visited directory is kept with how often and when it was last
+//! visited. Ranking combines the two ("frecency"): recent visits weigh more,
+//! so a directory used all week outranks one opened many times months ago.
+//! The history is stored in `history.toml` next to the config file.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{Config, ZError, ZResult};
+
+/// Most directories kept; the lowest ranked are forgotten first.
+pub const MAX_VISITS: usize = 500;
+
+/// Visits to one directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Visit {
+    /// The directory.
+    pub path: PathBuf,
+    /// Number of visits.
+    pub count: u32,
+    /// When it was last visited.
+    pub last_visit: DateTime<Utc>,
+}
+
+impl Visit {
+    /// Rank of the directory at `now`: the visit count weighted by how
+    /// recently it was last visited.
+    pub fn frecency(&self, now: DateTime<Utc>) -> f64 {
+        let age = now - self.last_visit;
+        let weight = if age < Duration::hours(1) {
+            4.0
+        } else if age < Duration::days(1) {
+            2.0
+        } else if age < Duration::weeks(1) {
+            0.5
+        } else {
+            0.25
+        };
+        f64::from(self.count) * weight
+    }
+}
+
+/// Persistent history of directory visits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisitHistory {
+    visits: Vec<Visit>,
+}
+
+impl VisitHistory {
+    /// Get the default history file path, next to the config file.
+    pub fn default_path() -> ZResult<PathBuf> {
+        Ok(Config::default_path()?.with_file_name("history.toml"))
+    }
+
+    /// Load the history from the default location.
+    pub fn load() -> ZResult<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load the history from a specific path; a missing file is an empty
+    /// history.
+    pub fn load_from(path: &Path) -> ZResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        debug!(path = %path.display(), "Loading visit history");
+
+        let content = std::fs::read_to_string(path).map_err(|e| ZError::io(path, e))?;
+        toml::from_str(&content).map_err(|e| ZError::Config {
+            message: format!("Failed to parse visit history: {e}"),
+        })
+    }
+
+    /// Save the history to the default location.
+    pub fn save(&self) -> ZResult<()> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    /// Save the history to a specific path.
+    pub fn save_to(&self, path: &Path) -> ZResult<()> {
+        debug!(path = %path.display(), visits = self.visits.len(), "Saving visit history");
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ZError::io(parent, e))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| ZError::Config {
+            message: format!("Failed to serialize visit history: {e}"),
+        })?;
+        std::fs::write(path, content).map_err(|e| ZError::io(path, e))
+    }
+
+    /// Record a visit to `path` now.
+    pub fn record(&mut self, path: &Path) {
+        self.record_at(path, Utc::now());
+    }
+
+    /// Record a visit to `path` at `now`.
+    pub fn record_at(&mut self, path: &Path, now: DateTime<Utc>) {
+        if let Some(visit) = self.visits.iter_mut().find(|v| same_path(&v.path, path)) {
+            visit.count = visit.count.saturating_add(1);
+            visit.last_visit = now;
+            return;
+        }
+
+        if self.visits.len() >= MAX_VISITS {
+            let lowest = self
+                .visits
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.frecency(now).total_cmp(&b.frecency(now)))
+                .map(|(i, _)| i);
+            if let Some(i) = lowest {
+                self.visits.swap_remove(i);
+            }
+        }
+        self.visits.push(Visit {
+            path: path.to_path_buf(),
+            count: 1,
+            last_visit: now,
+        });
+    }
+
+    /// Forget `path`. Returns whether it was in the history.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let before = self.visits.len();
+        self.visits.retain(|v| !same_path(&v.path, path));
+        self.visits.len() != before
+    }
+
+    /// All visits, in no particular order.
+    pub fn visits(&self) -> &[Visit] {
+        &self.visits
+    }
+
+    /// Visits ranked by frecency, highest first.
+    pub fn ranked(&self) -> Vec<&Visit> {
+        self.ranked_at(Utc::now())
+    }
+
+    /// Visits ranked by frecency at `now`, highest first; ties go to the
+    /// most recent.
+    pub fn ranked_at(&self, now: DateTime<Utc>) -> Vec<&Visit> {
+        let mut ranked: Vec<&Visit> = self.visits.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.frecency(now)
+                .total_cmp(&a.frecency(now))
+                .then(b.last_visit.cmp(&a.last_visit))
+        });
+        ranked
+    }
+
+    /// Number of directories in the history.
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    /// Check if nothing has been visited.
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+}
+
+/// Compare paths the way the file system does: case-insensitively on
+/// Windows.
+pub(crate) fn same_path(a: &Path, b: &Path) -> bool {
+    if cfg!(windows) {
+        a.as_os_str().eq_ignore_ascii_case(b.as_os_str())
+    } else {
+        a == b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_counts_visits() {
+        let now = Utc::now();
+        let mut history = VisitHistory::default();
+
+        history.record_at(Path::new("/a"), now - Duration::days(2));
+        history.record_at(Path::new("/b"), now);
+        history.record_at(Path::new("/a"), now);
+
+        assert_eq!(history.len(), 2);
+        let a = history.visits().iter().find(|v| v.path == Path::new("/a")).unwrap();
+        assert_eq!(a.count, 2);
+        assert_eq!(a.last_visit, now);
+
+        assert!(history.remove(Path::new("/b")));
+        assert!(!history.remove(Path::new("/b")));
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_frecency_ranking() {
+        let now = Utc::now();
+        let mut history = VisitHistory::default();
+
+        // Many visits long ago
+        for _ in 0..10 {
+            history.record_at(Path::new("/old"), now - Duration::days(30));
+        }
+        // A few visits today
+        for _ in 0..3 {
+            history.record_at(Path::new("/today"), now - Duration::hours(3));
+        }
+        // One visit just now
+        history.record_at(Path::new("/now"), now);
+        history.record_at(Path::new("/now2"), now - Duration::minutes(5));
+
+        let ranked: Vec<&Path> = history.ranked_at(now).iter().map(|v| v.path.as_path()).collect();
+        let expected = ["/today", "/now", "/now2", "/old"].map(Path::new);
+        assert_eq!(ranked, expected);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let now = Utc::now();
+        let mut history = VisitHistory::default();
+        history.record_at(Path::new("/kept"), now);
+        history.record_at(Path::new("/kept"), now);
+        for i in 0..MAX_VISITS {
+            history.record_at(&PathBuf::from(format!("/dir{i}")), now);
+        }
+
+        assert_eq!(history.len(), MAX_VISITS);
+        assert!(history.visits().iter().any(|v| v.path == Path::new("/kept")));
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ZManager").join("history.toml");
+
+        assert!(VisitHistory::load_from(&path).unwrap().is_empty());
+
+        let mut history = VisitHistory::default();
+        history.record(Path::new("C:\\Projects"));
+        history.record(Path::new("C:\\Projects"));
+        history.save_to(&path).unwrap();
+
+        let loaded = VisitHistory::load_from(&path).unwrap();
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.visits()[0].count, 2);
+
+        std::fs::write(&path, "visits = 3").unwrap();
+        assert!(VisitHistory::load_from(&path).is_err());
+    }
+}
@@ -1,25 +1,31 @@
 //! File system watching with debouncing.
 //!
 //! This module provides directory watching functionality to detect file system
-//! changes and trigger UI refreshes. Events are debounced to prevent thrashing.
+//! changes and trigger UI refreshes. Events are debounced to prevent thrashing:
+//! a burst of changes is reported once it settles, or at most every
+//! [`DEFAULT_MAX_DELAY_MS`] while it keeps going.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use notify::event::{ModifyKind, RenameMode};
 use notify::{
     Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher,
 };
 use tokio::sync::broadcast;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 
 use crate::{ZError, ZResult};
 
 /// Default debounce duration (300ms).
 pub const DEFAULT_DEBOUNCE_MS: u64 = 300;
 
+/// Default longest wait before a continuing burst is reported (2s).
+pub const DEFAULT_MAX_DELAY_MS: u64 = 2000;
+
 /// File system change event.
 #[derive(Debug, Clone)]
 pub struct WatchEvent {
@@ -29,6 +35,8 @@ pub struct WatchEvent {
     pub kind: WatchEventKind,
     /// Paths that changed (if known).
     pub paths: Vec<PathBuf>,
+    /// Renamed entries as (old path, new path), when both names are known.
+    pub renames: Vec<(PathBuf, PathBuf)>,
 }
 
 /// Kind of file system change.
@@ -63,6 +71,7 @@ impl From<&EventKind> for WatchEventKind {
     fn from(kind: &EventKind) -> Self {
         match kind {
             EventKind::Create(_) => Self::Created,
+            EventKind::Modify(ModifyKind::Name(_)) => Self::Renamed,
             EventKind::Modify(_) => Self::Modified,
             EventKind::Remove(_) => Self::Deleted,
             EventKind::Any | EventKind::Access(_) | EventKind::Other => Self::Changed,
@@ -75,6 +84,8 @@ impl From<&EventKind> for WatchEventKind {
 pub struct WatcherConfig {
     /// Debounce duration in milliseconds.
     pub debounce_ms: u64,
+    /// Longest time in milliseconds a change waits for its burst to settle.
+    pub max_delay_ms: u64,
     /// Maximum directories to watch simultaneously.
     pub max_watched_dirs: usize,
     /// Whether to watch subdirectories recursively.
@@ -85,6 +96,7 @@ impl Default for WatcherConfig {
     fn default() -> Self {
         Self {
             debounce_ms: DEFAULT_DEBOUNCE_MS,
+            max_delay_ms: DEFAULT_MAX_DELAY_MS,
             max_watched_dirs: 10,
             recursive: false, // Only watch the immediate directory
         }
@@ -93,9 +105,11 @@ impl Default for WatcherConfig {
 
 /// Debouncer state for a single directory.
 struct DebouncerState {
+    first_event: Instant,
     last_event: Instant,
     pending_paths: Vec<PathBuf>,
     pending_kind: WatchEventKind,
+    pending_renames: Vec<(PathBuf, PathBuf)>,
 }
 
 /// File system watcher with debouncing.
@@ -104,8 +118,11 @@ pub struct DirectoryWatcher {
     event_tx: broadcast::Sender<WatchEvent>,
     /// Handle to the notify watcher
     watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
-    /// Currently watched directories
-    watched: Arc<Mutex<HashMap<PathBuf, ()>>>,
+    /// Currently watched directories, by canonical path, with the path each
+    /// was asked for
+    watched: Arc<Mutex<HashMap<PathBuf, PathBuf>>>,
+    /// Directories [`Self::sync`] couldn't watch, as requested
+    failed: Mutex<HashSet<PathBuf>>,
     /// Debounce state per directory
     debounce_state: Arc<Mutex<HashMap<PathBuf, DebouncerState>>>,
     /// Shutdown flag
@@ -131,6 +148,7 @@ impl DirectoryWatcher {
             event_tx,
             watcher,
             watched,
+            failed: Mutex::new(HashSet::new()),
             debounce_state,
             shutdown,
         })
@@ -166,10 +184,13 @@ impl DirectoryWatcher {
         let event_tx = self.event_tx.clone();
         let debounce_state = self.debounce_state.clone();
         let debounce_duration = Duration::from_millis(self.config.debounce_ms);
+        let max_delay = Duration::from_millis(self.config.max_delay_ms);
         let shutdown = self.shutdown.clone();
 
         tokio::spawn(async move {
             let mut debounce_interval = tokio::time::interval(Duration::from_millis(50));
+            // Old name of a rename whose new name hasn't been reported yet
+            let mut rename_from = None;
 
             loop {
                 if shutdown.load(Ordering::SeqCst) {
@@ -179,11 +200,11 @@ impl DirectoryWatcher {
 
                 // Check for raw events (non-blocking)
                 while let Ok(event) = raw_rx.try_recv() {
-                    Self::handle_raw_event(&debounce_state, &event);
+                    Self::handle_raw_event(&debounce_state, &event, &mut rename_from);
                 }
 
                 // Flush debounced events
-                Self::flush_debounced(&debounce_state, &event_tx, debounce_duration);
+                Self::flush_debounced(&debounce_state, &event_tx, debounce_duration, max_delay);
 
                 debounce_interval.tick().await;
             }
@@ -212,6 +233,7 @@ impl DirectoryWatcher {
 
     /// Watch a directory for changes.
     pub fn watch(&self, path: &Path) -> ZResult<()> {
+        let requested = path;
         let path = path.canonicalize().map_err(|e| ZError::io(path, e))?;
 
         // Check limit
@@ -245,7 +267,7 @@ impl DirectoryWatcher {
             })?;
         }
 
-        self.watched.lock().unwrap().insert(path.clone(), ());
+        self.watched.lock().unwrap().insert(path.clone(), requested.to_path_buf());
         debug!(path = %path.display(), "Now watching directory");
 
         Ok(())
@@ -253,7 +275,7 @@ impl DirectoryWatcher {
 
     /// Stop watching a directory.
     pub fn unwatch(&self, path: &Path) -> ZResult<()> {
-        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let path = self.canonical_path(path).unwrap_or_else(|| path.to_path_buf());
 
         if !self.watched.lock().unwrap().contains_key(&path) {
             return Ok(());
@@ -270,6 +292,41 @@ impl DirectoryWatcher {
         Ok(())
     }
 
+    /// Watch exactly `paths`, unwatching every other directory.
+    ///
+    /// Returns the directories that couldn't be watched, such as a network
+    /// share that doesn't support change notifications. Each is reported
+    /// once: it isn't retried until a later sync has left it out.
+    ///
+    /// A watched directory that has since been deleted stays watched, so the
+    /// events of its deletion still reach whoever shows it.
+    pub fn sync(&self, paths: &[PathBuf]) -> Vec<(PathBuf, ZError)> {
+        let wanted: HashSet<PathBuf> = paths
+            .iter()
+            .filter_map(|path| self.canonical_path(path))
+            .collect();
+        for dir in self.watched_dirs() {
+            if !wanted.contains(&dir) {
+                let _ = self.unwatch(&dir);
+            }
+        }
+
+        let mut failed = self.failed.lock().unwrap();
+        failed.retain(|path| paths.contains(path));
+        let mut errors = Vec::new();
+        for path in paths {
+            if failed.contains(path) || self.is_watching(path) {
+                continue;
+            }
+            if let Err(e) = self.watch(path) {
+                warn!(path = %path.display(), error = %e, "Cannot watch directory");
+                failed.insert(path.clone());
+                errors.push((path.clone(), e));
+            }
+        }
+        errors
+    }
+
     /// Get the list of currently watched directories.
     pub fn watched_dirs(&self) -> Vec<PathBuf> {
         self.watched.lock().unwrap().keys().cloned().collect()
@@ -277,16 +334,48 @@ impl DirectoryWatcher {
 
     /// Check if a directory is being watched.
     pub fn is_watching(&self, path: &Path) -> bool {
-        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let path = self.canonical_path(path).unwrap_or_else(|| path.to_path_buf());
         self.watched.lock().unwrap().contains_key(&path)
     }
 
+    /// The canonical form of `path`, as events report its directory.
+    ///
+    /// A watched directory that no longer exists keeps the canonical path it
+    /// had when it was watched, so events about its deletion can be matched.
+    pub fn canonical_path(&self, path: &Path) -> Option<PathBuf> {
+        path.canonicalize().ok().or_else(|| {
+            let watched = self.watched.lock().unwrap();
+            watched
+                .iter()
+                .find(|(_, requested)| requested.as_path() == path)
+                .map(|(canonical, _)| canonical.clone())
+        })
+    }
+
     /// Handle a raw notify event.
+    ///
+    /// Renames come either as one event with both paths, or as an event for
+    /// the old name followed by one for the new name; `rename_from` carries
+    /// the old name between the two.
     fn handle_raw_event(
         debounce_state: &Arc<Mutex<HashMap<PathBuf, DebouncerState>>>,
         event: &Event,
+        rename_from: &mut Option<PathBuf>,
     ) {
         let kind = WatchEventKind::from(&event.kind);
+        let rename = match (&event.kind, event.paths.as_slice()) {
+            (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) => {
+                Some((from.clone(), to.clone()))
+            }
+            (EventKind::Modify(ModifyKind::Name(RenameMode::From)), [from]) => {
+                *rename_from = Some(from.clone());
+                None
+            }
+            (EventKind::Modify(ModifyKind::Name(RenameMode::To)), [to]) => {
+                rename_from.take().map(|from| (from, to.clone()))
+            }
+            _ => None,
+        };
 
         // Group by parent directory
         for path in &event.paths {
@@ -294,9 +383,11 @@ impl DirectoryWatcher {
 
             let mut state = debounce_state.lock().unwrap();
             let entry = state.entry(dir).or_insert_with(|| DebouncerState {
+                first_event: Instant::now(),
                 last_event: Instant::now(),
                 pending_paths: Vec::new(),
                 pending_kind: kind,
+                pending_renames: Vec::new(),
             });
 
             entry.last_event = Instant::now();
@@ -309,13 +400,26 @@ impl DirectoryWatcher {
                 entry.pending_kind = kind;
             }
         }
+
+        // Reported with the directory the entry was renamed into
+        if let Some((from, to)) = rename {
+            let dir = to.parent().unwrap_or(&to).to_path_buf();
+            if let Some(entry) = debounce_state.lock().unwrap().get_mut(&dir) {
+                let rename = (from, to);
+                if !entry.pending_renames.contains(&rename) {
+                    entry.pending_renames.push(rename);
+                }
+            }
+        }
     }
 
-    /// Flush debounced events that have stabilized.
+    /// Flush debounced events that have stabilized, or that have waited
+    /// `max_delay` for a burst that keeps going.
     fn flush_debounced(
         debounce_state: &Arc<Mutex<HashMap<PathBuf, DebouncerState>>>,
         event_tx: &broadcast::Sender<WatchEvent>,
         debounce_duration: Duration,
+        max_delay: Duration,
     ) {
         let now = Instant::now();
         let mut to_flush = Vec::new();
@@ -323,11 +427,14 @@ impl DirectoryWatcher {
         {
             let state = debounce_state.lock().unwrap();
             for (dir, debouncer) in state.iter() {
-                if now.duration_since(debouncer.last_event) >= debounce_duration {
+                if now.duration_since(debouncer.last_event) >= debounce_duration
+                    || now.duration_since(debouncer.first_event) >= max_delay
+                {
                     to_flush.push((
                         dir.clone(),
                         debouncer.pending_kind,
                         debouncer.pending_paths.clone(),
+                        debouncer.pending_renames.clone(),
                     ));
                 }
             }
@@ -336,13 +443,14 @@ impl DirectoryWatcher {
         // Flush and remove
         if !to_flush.is_empty() {
             let mut state = debounce_state.lock().unwrap();
-            for (dir, kind, paths) in to_flush {
+            for (dir, kind, paths, renames) in to_flush {
                 state.remove(&dir);
 
                 let event = WatchEvent {
                     directory: dir.clone(),
                     kind,
                     paths,
+                    renames,
                 };
 
                 trace!(dir = %dir.display(), ?kind, "Flushing debounced event");
@@ -372,6 +480,7 @@ mod tests {
     fn test_watcher_config_default() {
         let config = WatcherConfig::default();
         assert_eq!(config.debounce_ms, DEFAULT_DEBOUNCE_MS);
+        assert_eq!(config.max_delay_ms, DEFAULT_MAX_DELAY_MS);
         assert_eq!(config.max_watched_dirs, 10);
         assert!(!config.recursive);
     }
@@ -386,6 +495,7 @@ mod tests {
     fn test_watcher_with_config() {
         let config = WatcherConfig {
             debounce_ms: 500,
+            max_delay_ms: 1000,
             max_watched_dirs: 5,
             recursive: true,
         };
@@ -483,6 +593,7 @@ mod tests {
             directory: PathBuf::from("/test"),
             kind: WatchEventKind::Created,
             paths: vec![PathBuf::from("/test/file.txt")],
+            renames: Vec::new(),
         };
 
         assert_eq!(event.kind, WatchEventKind::Created);
@@ -499,6 +610,102 @@ mod tests {
         watcher.stop(); // Double stop should be safe
     }
 
+    #[tokio::test]
+    async fn test_sync_follows_paths() {
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        let missing = a.path().join("missing");
+        let mut watcher = DirectoryWatcher::new().unwrap();
+        watcher.start().unwrap();
+
+        let errors = watcher.sync(&[a.path().to_path_buf(), missing.clone()]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, missing);
+        assert!(watcher.is_watching(a.path()));
+
+        // A failed directory is reported once while it stays in the set
+        let errors = watcher.sync(&[b.path().to_path_buf(), missing.clone()]);
+        assert!(errors.is_empty());
+        assert!(!watcher.is_watching(a.path()));
+        assert!(watcher.is_watching(b.path()));
+        assert_eq!(watcher.watched_dirs().len(), 1);
+
+        // ...and again once it has left the set and comes back
+        watcher.sync(&[]);
+        assert!(watcher.watched_dirs().is_empty());
+        assert_eq!(watcher.sync(std::slice::from_ref(&missing)).len(), 1);
+
+        watcher.stop();
+    }
+
+    #[tokio::test]
+    async fn test_deleted_directory_stays_watched() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+        let canonical = dir.canonicalize().unwrap();
+        let mut watcher = DirectoryWatcher::new().unwrap();
+        watcher.start().unwrap();
+        watcher.sync(std::slice::from_ref(&dir));
+
+        fs::remove_dir(&dir).unwrap();
+        assert_eq!(watcher.canonical_path(&dir), Some(canonical.clone()));
+        assert!(watcher.sync(std::slice::from_ref(&dir)).is_empty());
+        assert_eq!(watcher.watched_dirs(), vec![canonical]);
+
+        watcher.sync(&[]);
+        assert_eq!(watcher.canonical_path(&dir), None);
+
+        watcher.stop();
+    }
+
+    #[test]
+    fn test_bursts_flush_after_max_delay() {
+        let state = Arc::new(Mutex::new(HashMap::new()));
+        let (tx, mut rx) = broadcast::channel(16);
+        let event = Event::new(EventKind::Create(notify::event::CreateKind::File))
+            .add_path(PathBuf::from("/dir/file.txt"));
+        let debounce = Duration::from_secs(60);
+
+        DirectoryWatcher::handle_raw_event(&state, &event, &mut None);
+        DirectoryWatcher::flush_debounced(&state, &tx, debounce, Duration::from_secs(60));
+        assert!(rx.try_recv().is_err());
+
+        // Still busy, but waited long enough
+        DirectoryWatcher::handle_raw_event(&state, &event, &mut None);
+        DirectoryWatcher::flush_debounced(&state, &tx, debounce, Duration::ZERO);
+        let flushed = rx.try_recv().unwrap();
+        assert_eq!(flushed.directory, PathBuf::from("/dir"));
+        assert_eq!(flushed.kind, WatchEventKind::Created);
+        assert!(state.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_renames_are_paired() {
+        let state = Arc::new(Mutex::new(HashMap::new()));
+        let (tx, mut rx) = broadcast::channel(16);
+        let name = |mode| EventKind::Modify(ModifyKind::Name(mode));
+        let mut rename_from = None;
+
+        // Windows reports the old and the new name separately
+        let from = Event::new(name(RenameMode::From)).add_path(PathBuf::from("/dir/a.txt"));
+        let to = Event::new(name(RenameMode::To)).add_path(PathBuf::from("/dir/b.txt"));
+        DirectoryWatcher::handle_raw_event(&state, &from, &mut rename_from);
+        DirectoryWatcher::handle_raw_event(&state, &to, &mut rename_from);
+        // inotify also reports both names at once
+        let both = Event::new(name(RenameMode::Both))
+            .add_path(PathBuf::from("/dir/a.txt"))
+            .add_path(PathBuf::from("/dir/b.txt"));
+        DirectoryWatcher::handle_raw_event(&state, &both, &mut rename_from);
+
+        DirectoryWatcher::flush_debounced(&state, &tx, Duration::ZERO, Duration::ZERO);
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.kind, WatchEventKind::Renamed);
+        let expected = (PathBuf::from("/dir/a.txt"), PathBuf::from("/dir/b.txt"));
+        assert_eq!(event.renames, vec![expected]);
+        assert!(rename_from.is_none());
+    }
+
     #[tokio::test]
     async fn test_duplicate_watch() {
         let temp = TempDir::new().unwrap();
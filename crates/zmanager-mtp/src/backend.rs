@@ -0,0 +1,289 @@
+//! Transfers to, from and between devices.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::{debug, info, warn};
+use zmanager_core::{
+    extended_path, get_entry_meta, CancellationToken, EntryMeta, PauseToken, SpeedLimit, ZError,
+    ZResult,
+};
+use zmanager_transfer_win::{
+    copy_stream, CopyProgress, PortableBackend, ProgressCallback, TransferBackend,
+};
+
+use crate::device::DeviceUpload;
+use crate::filesystem::MtpFileSystem;
+use crate::path::{is_device_path, split_device_path};
+
+/// Runs copies and moves that involve a device.
+///
+/// Registered with a [`BackendRegistry`](zmanager_transfer_win::BackendRegistry),
+/// it takes every job with an `mtp://` path among its sources or its
+/// destination, so photos come off a phone as ordinary transfer jobs with
+/// progress. Local paths in those jobs are handled through `std::fs`.
+#[derive(Debug, Clone)]
+pub struct MtpBackend {
+    fs: Arc<MtpFileSystem>,
+}
+
+/// The open destination of a copy.
+enum Destination {
+    Local(File),
+    Device(Box<dyn DeviceUpload>),
+}
+
+impl Destination {
+    /// Complete the file once everything is written.
+    fn finish(self) -> ZResult<()> {
+        match self {
+            Self::Local(_) => Ok(()),
+            Self::Device(upload) => upload.finish(),
+        }
+    }
+}
+
+impl Write for Destination {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Local(file) => file.write(buf),
+            Self::Device(upload) => upload.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Local(file) => file.flush(),
+            Self::Device(upload) => upload.flush(),
+        }
+    }
+}
+
+impl MtpBackend {
+    /// Create a backend working on the devices of `fs`.
+    pub fn new(fs: Arc<MtpFileSystem>) -> Self {
+        Self { fs }
+    }
+
+    /// Open the source of a copy: its reader, size and modification time.
+    fn open_source(
+        &self,
+        source: &Path,
+    ) -> ZResult<(Box<dyn Read + Send>, u64, Option<std::time::SystemTime>)> {
+        if let Some(device_path) = split_device_path(source) {
+            let (reader, object) = self.fs.open(&device_path)?;
+            return Ok((reader, object.size, object.modified.map(Into::into)));
+        }
+        let os_source = extended_path(source);
+        let meta = std::fs::metadata(&os_source).map_err(|e| ZError::from_io(source, e))?;
+        if !meta.is_file() {
+            return Err(ZError::NotAFile {
+                path: source.to_path_buf(),
+            });
+        }
+        let reader = File::open(os_source).map_err(|e| ZError::from_io(source, e))?;
+        // Devices time files as they arrive, so local times aren't carried over
+        Ok((Box::new(reader), meta.len(), None))
+    }
+
+    /// Create the destination of a copy of `size` bytes, and the folders
+    /// above it.
+    fn create_destination(&self, destination: &Path, size: u64) -> ZResult<Destination> {
+        if let Some(device_path) = split_device_path(destination) {
+            if let Some(parent) = device_path.parent() {
+                self.fs.create_dir_all(&parent)?;
+            }
+            return Ok(Destination::Device(self.fs.create(&device_path, size)?));
+        }
+        if let Some(parent) = destination.parent() {
+            PortableBackend.create_dir(parent)?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(extended_path(destination))
+            .map_err(|e| ZError::from_io(destination, e))?;
+        Ok(Destination::Local(file))
+    }
+}
+
+impl TransferBackend for MtpBackend {
+    fn name(&self) -> &'static str {
+        "mtp"
+    }
+
+    fn handles(&self, sources: &[PathBuf], destination: &Path) -> bool {
+        is_device_path(destination) || sources.iter().any(|source| is_device_path(source))
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        if self.entry(destination).is_some() {
+            if !overwrite {
+                return Err(ZError::AlreadyExists {
+                    path: destination.to_path_buf(),
+                });
+            }
+            // Devices keep both files rather than replace one
+            if is_device_path(destination) {
+                self.delete(destination)?;
+            }
+        }
+        let (mut reader, total_bytes, modified) = self.open_source(source)?;
+
+        debug!(
+            source = %source.display(),
+            destination = %destination.display(),
+            overwrite,
+            "Starting device file copy"
+        );
+
+        let mut writer = self.create_destination(destination, total_bytes)?;
+        let result = copy_stream(
+            &mut reader,
+            &mut writer,
+            source,
+            destination,
+            &cancel_token,
+            &pause_token,
+            &speed_limit,
+            |bytes_copied, speed| {
+                if let Some(ref callback) = progress_callback {
+                    callback(CopyProgress {
+                        total_bytes,
+                        bytes_copied,
+                        source: source.to_path_buf(),
+                        destination: destination.to_path_buf(),
+                        speed_bps: speed,
+                        eta_seconds: (speed > 0)
+                            .then(|| total_bytes.saturating_sub(bytes_copied) / speed),
+                    });
+                }
+            },
+        )
+        .and_then(|bytes| {
+            if let (Destination::Local(file), Some(modified)) = (&writer, modified) {
+                if let Err(e) = file.set_modified(modified) {
+                    warn!(destination = %destination.display(), error = %e, "Cannot set time");
+                }
+            }
+            writer.finish().map(|()| bytes)
+        });
+
+        match result {
+            Ok(bytes) => {
+                info!(
+                    bytes,
+                    source = %source.display(),
+                    destination = %destination.display(),
+                    "Device file copy completed"
+                );
+                Ok(bytes)
+            }
+            Err(e) => {
+                if matches!(e, ZError::Cancelled) {
+                    warn!(source = %source.display(), "Device file copy cancelled");
+                }
+                // An abandoned upload leaves nothing behind on the device
+                if !is_device_path(destination) {
+                    let _ = self.delete(destination);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn move_file(&self, source: &Path, _destination: &Path) -> ZResult<()> {
+        Err(ZError::InvalidOperation {
+            operation: format!("move {}", source.display()),
+            reason: "Moving to or from a device copies".to_string(),
+        })
+    }
+
+    fn entry(&self, path: &Path) -> Option<EntryMeta> {
+        match split_device_path(path) {
+            Some(device_path) => self.fs.entry(&device_path).ok(),
+            None => get_entry_meta(path).ok(),
+        }
+    }
+
+    fn walk(&self, path: &Path) -> Option<ZResult<Vec<EntryMeta>>> {
+        split_device_path(path).map(|device_path| self.fs.walk(&device_path))
+    }
+
+    fn create_dir(&self, path: &Path) -> ZResult<()> {
+        match split_device_path(path) {
+            Some(device_path) => self.fs.create_dir_all(&device_path),
+            None => PortableBackend.create_dir(path),
+        }
+    }
+
+    fn delete(&self, path: &Path) -> ZResult<()> {
+        match split_device_path(path) {
+            Some(device_path) => self.fs.remove(&device_path),
+            None => PortableBackend.delete(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::fake::FakeDevices;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn copy(
+        backend: &MtpBackend,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+    ) -> ZResult<u64> {
+        backend.copy_file(
+            source,
+            destination,
+            overwrite,
+            CancellationToken::new(),
+            PauseToken::new(),
+            SpeedLimit::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_copies_off_and_onto_a_device() {
+        let temp = TempDir::new().unwrap();
+        let devices = FakeDevices::new();
+        let backend = MtpBackend::new(Arc::new(MtpFileSystem::new(devices.clone())));
+        let photo = Path::new("mtp://Pixel 7/Internal storage/DCIM/IMG_1.jpg");
+        let local = temp.path().join("photos").join("IMG_1.jpg");
+
+        assert!(backend.handles(&[photo.to_path_buf()], temp.path()));
+        assert!(!backend.handles(std::slice::from_ref(&local), temp.path()));
+        assert_eq!(copy(&backend, photo, &local, false).unwrap(), 10);
+        assert_eq!(fs::read(&local).unwrap(), b"jpeg bytes");
+
+        fs::write(&local, "edited").unwrap();
+        assert!(copy(&backend, &local, photo, false).is_err());
+        assert_eq!(copy(&backend, &local, photo, true).unwrap(), 6);
+        assert_eq!(devices.data("IMG_1.jpg").unwrap(), b"edited");
+        assert_eq!(backend.entry(photo).unwrap().size, 6);
+
+        let backup = Path::new("mtp://Pixel 7/Internal storage/Backup/IMG_1.jpg");
+        copy(&backend, photo, backup, false).unwrap();
+        let backed_up = backend.walk(Path::new("mtp://Pixel 7/Internal storage/Backup"));
+        assert_eq!(backed_up.unwrap().unwrap().len(), 2);
+        assert!(backend.move_file(photo, &local).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! Access to the devices connected to this computer.
+//!
+//! [`PortableDevices`] is the little the rest of the crate needs from a
+//! device API, in terms of object IDs: on Windows it is Windows Portable
+//! Devices (WPD), elsewhere there are no devices.
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use zmanager_core::{ZError, ZResult};
+
+/// ID of the object standing for a device itself, whose children are its
+/// storages.
+pub const DEVICE_OBJECT_ID: &str = "DEVICE";
+
+/// A device connected to this computer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// ID the system knows the device by.
+    pub id: String,
+    /// Name the user gave the device, or its model.
+    pub name: String,
+}
+
+/// A storage, folder or file on a device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceObject {
+    /// ID of the object on its device.
+    pub id: String,
+    /// File name of the object.
+    pub name: String,
+    /// Whether the object holds others: a storage or folder.
+    pub is_folder: bool,
+    /// Size in bytes, 0 for folders.
+    pub size: u64,
+    /// Last modification time, if the device keeps one.
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// A file being written to a device.
+pub trait DeviceUpload: Write + Send {
+    /// Complete the file once everything is written. Dropping an upload
+    /// first abandons it.
+    fn finish(self: Box<Self>) -> ZResult<()>;
+}
+
+/// The devices connected to this computer and what is on them.
+///
+/// Devices are named by the `id` of their [`DeviceInfo`], objects by their
+/// `id` on the device. All calls block on the device.
+pub trait PortableDevices: Send + Sync {
+    /// The devices connected now.
+    fn devices(&self) -> ZResult<Vec<DeviceInfo>>;
+
+    /// The objects in `parent` on `device`.
+    fn children(&self, device: &str, parent: &str) -> ZResult<Vec<DeviceObject>>;
+
+    /// Open the file `object` on `device` for reading.
+    fn open(&self, device: &str, object: &str) -> ZResult<Box<dyn Read + Send>>;
+
+    /// Create the file `name`, of `size` bytes, in `parent` on `device`.
+    /// Devices need the size up front.
+    fn create(
+        &self,
+        device: &str,
+        parent: &str,
+        name: &str,
+        size: u64,
+    ) -> ZResult<Box<dyn DeviceUpload>>;
+
+    /// Create the folder `name` in `parent` on `device`, returning its ID.
+    fn create_folder(&self, device: &str, parent: &str, name: &str) -> ZResult<String>;
+
+    /// Delete `object` on `device`, and everything in it.
+    fn delete(&self, device: &str, object: &str) -> ZResult<()>;
+}
+
+/// The devices of this system: Windows Portable Devices on Windows, none
+/// elsewhere.
+pub fn system_devices() -> Arc<dyn PortableDevices> {
+    #[cfg(windows)]
+    {
+        Arc::new(crate::wpd::WpdDevices::new())
+    }
+    #[cfg(not(windows))]
+    {
+        Arc::new(NoDevices)
+    }
+}
+
+/// The device API of systems without one: nothing is ever connected.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoDevices;
+
+impl NoDevices {
+    fn unsupported() -> ZError {
+        ZError::InvalidOperation {
+            operation: "open portable device".to_string(),
+            reason: "Portable devices are only supported on Windows".to_string(),
+        }
+    }
+}
+
+impl PortableDevices for NoDevices {
+    fn devices(&self) -> ZResult<Vec<DeviceInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn children(&self, _device: &str, _parent: &str) -> ZResult<Vec<DeviceObject>> {
+        Err(Self::unsupported())
+    }
+
+    fn open(&self, _device: &str, _object: &str) -> ZResult<Box<dyn Read + Send>> {
+        Err(Self::unsupported())
+    }
+
+    fn create(
+        &self,
+        _device: &str,
+        _parent: &str,
+        _name: &str,
+        _size: u64,
+    ) -> ZResult<Box<dyn DeviceUpload>> {
+        Err(Self::unsupported())
+    }
+
+    fn create_folder(&self, _device: &str, _parent: &str, _name: &str) -> ZResult<String> {
+        Err(Self::unsupported())
+    }
+
+    fn delete(&self, _device: &str, _object: &str) -> ZResult<()> {
+        Err(Self::unsupported())
+    }
+}
+
+/// Devices kept in memory, for tests.
+#[cfg(test)]
+pub(crate) mod fake {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// An object on a [`FakeDevices`] device.
+    #[derive(Debug, Clone)]
+    struct FakeObject {
+        device: String,
+        id: String,
+        parent: String,
+        name: String,
+        is_folder: bool,
+        data: Vec<u8>,
+    }
+
+    /// Devices whose objects live in memory: one named "Pixel 7" to begin
+    /// with, holding `Internal storage/DCIM/IMG_1.jpg`.
+    #[derive(Debug, Default)]
+    pub(crate) struct FakeDevices {
+        devices: Mutex<Vec<DeviceInfo>>,
+        objects: Arc<Mutex<Vec<FakeObject>>>,
+    }
+
+    impl FakeDevices {
+        pub(crate) fn new() -> Arc<Self> {
+            let devices = Arc::new(Self::default());
+            devices.add_device("phone-id", "Pixel 7");
+            let storage = devices.add("phone-id", DEVICE_OBJECT_ID, "Internal storage", None);
+            let dcim = devices.add("phone-id", &storage, "DCIM", None);
+            devices.add("phone-id", &dcim, "IMG_1.jpg", Some(b"jpeg bytes".to_vec()));
+            devices
+        }
+
+        pub(crate) fn add_device(&self, id: &str, name: &str) {
+            self.devices.lock().unwrap().push(DeviceInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+            });
+        }
+
+        /// Add a folder, or a file holding `data`, returning its ID.
+        fn add(&self, device: &str, parent: &str, name: &str, data: Option<Vec<u8>>) -> String {
+            add_object(&self.objects, device, parent, name, data)
+        }
+
+        /// The contents of the file called `name`, if there is one.
+        pub(crate) fn data(&self, name: &str) -> Option<Vec<u8>> {
+            let objects = self.objects.lock().unwrap();
+            let object = objects.iter().find(|object| object.name == name)?;
+            Some(object.data.clone())
+        }
+    }
+
+    fn add_object(
+        objects: &Mutex<Vec<FakeObject>>,
+        device: &str,
+        parent: &str,
+        name: &str,
+        data: Option<Vec<u8>>,
+    ) -> String {
+        let mut objects = objects.lock().unwrap();
+        let id = format!("o{}", objects.len() + 1);
+        objects.push(FakeObject {
+            device: device.to_string(),
+            id: id.clone(),
+            parent: parent.to_string(),
+            name: name.to_string(),
+            is_folder: data.is_none(),
+            data: data.unwrap_or_default(),
+        });
+        id
+    }
+
+    fn not_found(object: &str) -> ZError {
+        ZError::NotFound {
+            path: object.into(),
+        }
+    }
+
+    impl PortableDevices for FakeDevices {
+        fn devices(&self) -> ZResult<Vec<DeviceInfo>> {
+            Ok(self.devices.lock().unwrap().clone())
+        }
+
+        fn children(&self, device: &str, parent: &str) -> ZResult<Vec<DeviceObject>> {
+            let objects = self.objects.lock().unwrap();
+            Ok(objects
+                .iter()
+                .filter(|object| object.device == device && object.parent == parent)
+                .map(|object| DeviceObject {
+                    id: object.id.clone(),
+                    name: object.name.clone(),
+                    is_folder: object.is_folder,
+                    size: object.data.len() as u64,
+                    modified: None,
+                })
+                .collect())
+        }
+
+        fn open(&self, device: &str, object: &str) -> ZResult<Box<dyn Read + Send>> {
+            let objects = self.objects.lock().unwrap();
+            let found = objects
+                .iter()
+                .find(|o| o.device == device && o.id == object)
+                .ok_or_else(|| not_found(object))?;
+            Ok(Box::new(std::io::Cursor::new(found.data.clone())))
+        }
+
+        fn create(
+            &self,
+            device: &str,
+            parent: &str,
+            name: &str,
+            size: u64,
+        ) -> ZResult<Box<dyn DeviceUpload>> {
+            Ok(Box::new(FakeUpload {
+                objects: Arc::clone(&self.objects),
+                device: device.to_string(),
+                parent: parent.to_string(),
+                name: name.to_string(),
+                size,
+                data: Vec::new(),
+            }))
+        }
+
+        fn create_folder(&self, device: &str, parent: &str, name: &str) -> ZResult<String> {
+            Ok(self.add(device, parent, name, None))
+        }
+
+        fn delete(&self, device: &str, object: &str) -> ZResult<()> {
+            let mut objects = self.objects.lock().unwrap();
+            if !objects.iter().any(|o| o.device == device && o.id == object) {
+                return Err(not_found(object));
+            }
+            let mut doomed = vec![object.to_string()];
+            while let Some(id) = doomed.pop() {
+                let children = objects.iter().filter(|o| o.parent == id);
+                doomed.extend(children.map(|o| o.id.clone()));
+                objects.retain(|o| o.id != id);
+            }
+            Ok(())
+        }
+    }
+
+    /// A file being written to a [`FakeDevices`] device, which appears
+    /// once finished with the size promised.
+    struct FakeUpload {
+        objects: Arc<Mutex<Vec<FakeObject>>>,
+        device: String,
+        parent: String,
+        name: String,
+        size: u64,
+        data: Vec<u8>,
+    }
+
+    impl Write for FakeUpload {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.data.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl DeviceUpload for FakeUpload {
+        fn finish(self: Box<Self>) -> ZResult<()> {
+            if self.data.len() as u64 != self.size {
+                return Err(ZError::Internal {
+                    message: format!("{} bytes written of {}", self.data.len(), self.size),
+                });
+            }
+            let FakeUpload {
+                objects,
+                device,
+                parent,
+                name,
+                data,
+                ..
+            } = *self;
+            add_object(&objects, &device, &parent, &name, Some(data));
+            Ok(())
+        }
+    }
+}
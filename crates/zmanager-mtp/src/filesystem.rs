@@ -0,0 +1,417 @@
+//! Folders and files on devices, by their virtual paths.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tracing::{debug, warn};
+use zmanager_core::{
+    DirectoryProvider, DriveInfo, DriveType, EntryKind, EntryMeta, ZError, ZResult,
+};
+
+use crate::device::{
+    system_devices, DeviceObject, DeviceUpload, PortableDevices, DEVICE_OBJECT_ID,
+};
+use crate::path::{is_device_path, path_component, split_device_path, DevicePath};
+
+/// The devices connected to this computer, reached by their virtual
+/// `mtp://` paths.
+///
+/// Lists folders for the panes as a [`DirectoryProvider`], lists the
+/// devices themselves as drives, and does the file work for
+/// [`MtpBackend`](crate::MtpBackend). Devices only know their objects by
+/// ID, so the IDs behind the paths listed are remembered. All calls block
+/// on the device.
+pub struct MtpFileSystem {
+    devices: Arc<dyn PortableDevices>,
+    /// Device IDs by the names in paths, as of the last enumeration.
+    names: Mutex<HashMap<String, String>>,
+    /// Objects by path, as of the last listing of their folder.
+    objects: Mutex<HashMap<DevicePath, DeviceObject>>,
+}
+
+impl MtpFileSystem {
+    /// Create a file system on the devices `devices` connects to.
+    pub fn new(devices: Arc<dyn PortableDevices>) -> Self {
+        Self {
+            devices,
+            names: Mutex::new(HashMap::new()),
+            objects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The connected devices, as drives rooted at their `mtp://` paths.
+    /// Devices that share a name are told apart by a number.
+    pub fn drives(&self) -> Vec<DriveInfo> {
+        let devices = match self.devices.devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                warn!(error = %e, "Failed to list portable devices");
+                return Vec::new();
+            }
+        };
+
+        let mut names = HashMap::new();
+        let mut drives = Vec::new();
+        for device in devices {
+            let base = path_component(&device.name);
+            let mut name = base.clone();
+            for count in 2.. {
+                if !names.contains_key(&name) {
+                    break;
+                }
+                name = format!("{base} ({count})");
+            }
+            drives.push(DriveInfo {
+                path: DevicePath::device(&name).to_path_buf(),
+                label: name.clone(),
+                drive_type: DriveType::Portable,
+                file_system: None,
+                total_bytes: None,
+                free_bytes: None,
+                is_ready: true,
+                volume_serial: None,
+                mount_points: Vec::new(),
+                bitlocker: None,
+                cluster_size: None,
+            });
+            names.insert(name, device.id);
+        }
+        debug!(count = drives.len(), "Portable devices enumerated");
+        *lock(&self.names) = names;
+        drives
+    }
+
+    /// List the device, storage or folder at `path`.
+    pub fn list_dir(&self, path: &DevicePath) -> ZResult<Vec<EntryMeta>> {
+        let (device, object) = self.resolve(path)?;
+        if object.as_ref().is_some_and(|object| !object.is_folder) {
+            return Err(ZError::NotADirectory {
+                path: path.to_path_buf(),
+            });
+        }
+        let parent = object.as_ref().map_or(DEVICE_OBJECT_ID, |object| &object.id);
+        let children = self.list_children(&device, parent, path)?;
+        Ok(children
+            .iter()
+            .map(|(child, object)| entry_meta(child, object))
+            .collect())
+    }
+
+    /// Look up `path`.
+    pub fn entry(&self, path: &DevicePath) -> ZResult<EntryMeta> {
+        match self.resolve(path)? {
+            (_, Some(object)) => Ok(entry_meta(path, &object)),
+            (_, None) => Ok(EntryMeta::new(
+                path.device.clone(),
+                path.to_path_buf(),
+                EntryKind::Directory,
+            )),
+        }
+    }
+
+    /// List `path` and, for a folder, everything below it, each folder
+    /// before its contents.
+    pub fn walk(&self, path: &DevicePath) -> ZResult<Vec<EntryMeta>> {
+        let root = self.entry(path)?;
+        let is_dir = root.is_directory();
+        let mut entries = vec![root];
+        if !is_dir {
+            return Ok(entries);
+        }
+
+        let mut pending = vec![path.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in self.list_dir(&dir)? {
+                if entry.is_directory() {
+                    pending.push(dir.join(&entry.name));
+                }
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Open the file at `path` for reading.
+    pub fn open(&self, path: &DevicePath) -> ZResult<(Box<dyn Read + Send>, DeviceObject)> {
+        let (device, object) = self.resolve(path)?;
+        let object = object.filter(|object| !object.is_folder).ok_or_else(|| ZError::NotAFile {
+            path: path.to_path_buf(),
+        })?;
+        let reader = self.devices.open(&device, &object.id)?;
+        Ok((reader, object))
+    }
+
+    /// Create the file at `path`, of `size` bytes. Fails if something is
+    /// there already, since devices would keep both.
+    pub fn create(&self, path: &DevicePath, size: u64) -> ZResult<Box<dyn DeviceUpload>> {
+        let (name, parent) = match (path.name(), path.parent()) {
+            (Some(name), Some(parent)) if !parent.components.is_empty() => (name, parent),
+            _ => {
+                return Err(ZError::InvalidPath {
+                    path: path.to_path_buf(),
+                    reason: "Files go in a storage of the device".to_string(),
+                });
+            }
+        };
+        match self.resolve(path) {
+            Ok(_) => {
+                return Err(ZError::AlreadyExists {
+                    path: path.to_path_buf(),
+                });
+            }
+            Err(e) if e.is_not_found() => {}
+            Err(e) => return Err(e),
+        }
+        let (device, folder) = self.resolve(&parent)?;
+        let folder = folder.filter(|folder| folder.is_folder).ok_or_else(|| {
+            ZError::NotADirectory {
+                path: parent.to_path_buf(),
+            }
+        })?;
+        self.devices.create(&device, &folder.id, name, size)
+    }
+
+    /// Create the folder at `path` and any missing parents. Storages can't
+    /// be created.
+    pub fn create_dir_all(&self, path: &DevicePath) -> ZResult<()> {
+        match self.resolve(path) {
+            Ok((_, Some(object))) if !object.is_folder => Err(ZError::NotADirectory {
+                path: path.to_path_buf(),
+            }),
+            Ok(_) => Ok(()),
+            Err(e) if e.is_not_found() && path.components.len() > 1 => {
+                let parent = path.parent().expect("has components");
+                self.create_dir_all(&parent)?;
+                let (device, folder) = self.resolve(&parent)?;
+                let folder = folder.expect("parent below the device");
+                let name = path.name().expect("has components");
+                let id = self.devices.create_folder(&device, &folder.id, name)?;
+                lock(&self.objects).insert(
+                    path.clone(),
+                    DeviceObject {
+                        id,
+                        name: name.to_string(),
+                        is_folder: true,
+                        size: 0,
+                        modified: None,
+                    },
+                );
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete the file or folder at `path`, and everything in it.
+    pub fn remove(&self, path: &DevicePath) -> ZResult<()> {
+        let (device, object) = self.resolve(path)?;
+        let Some(object) = object else {
+            return Err(ZError::InvalidOperation {
+                operation: format!("delete {}", path.to_path_buf().display()),
+                reason: "A device can't be deleted".to_string(),
+            });
+        };
+        self.devices.delete(&device, &object.id)?;
+        self.forget(path);
+        Ok(())
+    }
+
+    /// Forget what was listed at and below `path`, once it changed.
+    pub fn forget(&self, path: &DevicePath) {
+        lock(&self.objects).retain(|known, _| {
+            known.device != path.device || !known.components.starts_with(&path.components)
+        });
+    }
+
+    /// The ID of the device of `path`, and the object at `path` or `None`
+    /// for the device itself.
+    fn resolve(&self, path: &DevicePath) -> ZResult<(String, Option<DeviceObject>)> {
+        let device = self.device_id(path)?;
+        let mut current = DevicePath::device(&path.device);
+        let mut object: Option<DeviceObject> = None;
+        for component in &path.components {
+            if object.as_ref().is_some_and(|object| !object.is_folder) {
+                return Err(ZError::NotADirectory {
+                    path: current.to_path_buf(),
+                });
+            }
+            let next = current.join(component);
+            let known = lock(&self.objects).get(&next).cloned();
+            let found = match known {
+                Some(found) => found,
+                None => {
+                    let parent = object.as_ref().map_or(DEVICE_OBJECT_ID, |object| &object.id);
+                    self.list_children(&device, parent, &current)?
+                        .into_iter()
+                        .find(|(child, _)| child == &next)
+                        .map(|(_, found)| found)
+                        .ok_or_else(|| ZError::NotFound {
+                            path: next.to_path_buf(),
+                        })?
+                }
+            };
+            object = Some(found);
+            current = next;
+        }
+        Ok((device, object))
+    }
+
+    /// List `parent` on `device`, whose path is `path`, remembering what is
+    /// in it.
+    fn list_children(
+        &self,
+        device: &str,
+        parent: &str,
+        path: &DevicePath,
+    ) -> ZResult<Vec<(DevicePath, DeviceObject)>> {
+        let children: Vec<(DevicePath, DeviceObject)> = self
+            .devices
+            .children(device, parent)?
+            .into_iter()
+            .map(|object| (path.join(&path_component(&object.name)), object))
+            .collect();
+        let mut objects = lock(&self.objects);
+        objects.retain(|known, _| known.parent().as_ref() != Some(path));
+        objects.extend(children.iter().cloned());
+        Ok(children)
+    }
+
+    /// The ID of the device `path` is on, enumerating the devices again if
+    /// it isn't known.
+    fn device_id(&self, path: &DevicePath) -> ZResult<String> {
+        if let Some(id) = lock(&self.names).get(&path.device) {
+            return Ok(id.clone());
+        }
+        self.drives();
+        lock(&self.names)
+            .get(&path.device)
+            .cloned()
+            .ok_or_else(|| ZError::NotFound {
+                path: DevicePath::device(&path.device).to_path_buf(),
+            })
+    }
+}
+
+impl Default for MtpFileSystem {
+    fn default() -> Self {
+        Self::new(system_devices())
+    }
+}
+
+impl std::fmt::Debug for MtpFileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let devices: Vec<String> = lock(&self.names).keys().cloned().collect();
+        f.debug_struct("MtpFileSystem").field("devices", &devices).finish_non_exhaustive()
+    }
+}
+
+impl DirectoryProvider for MtpFileSystem {
+    fn handles(&self, path: &Path) -> bool {
+        is_device_path(path)
+    }
+
+    fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>> {
+        let device_path = split_device_path(path).ok_or_else(|| ZError::InvalidPath {
+            path: path.to_path_buf(),
+            reason: "Not an mtp:// path".to_string(),
+        })?;
+        self.list_dir(&device_path)
+    }
+}
+
+/// The entry for `object` at `path`.
+fn entry_meta(path: &DevicePath, object: &DeviceObject) -> EntryMeta {
+    let name = path.name().unwrap_or(&path.device).to_string();
+    let kind = if object.is_folder {
+        EntryKind::Directory
+    } else {
+        EntryKind::File
+    };
+    let mut meta = EntryMeta::new(name, path.to_path_buf(), kind);
+    if !object.is_folder {
+        meta.size = object.size;
+    }
+    meta.modified = object.modified;
+    meta
+}
+
+/// Lock `mutex`, taking the data over from a thread that panicked with it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::fake::FakeDevices;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn phone() -> (Arc<FakeDevices>, MtpFileSystem) {
+        let devices = FakeDevices::new();
+        let fs = MtpFileSystem::new(devices.clone());
+        (devices, fs)
+    }
+
+    #[test]
+    fn test_drives_name_devices_apart() {
+        let (devices, fs) = phone();
+        devices.add_device("second-id", "Pixel 7");
+
+        let drives = fs.drives();
+        let labels: Vec<&str> = drives.iter().map(|d| d.label.as_str()).collect();
+        assert_eq!(labels, ["Pixel 7", "Pixel 7 (2)"]);
+        assert_eq!(drives[0].path, PathBuf::from("mtp://Pixel 7"));
+        assert_eq!(drives[1].drive_type, DriveType::Portable);
+    }
+
+    #[test]
+    fn test_list_and_walk() {
+        let (_, fs) = phone();
+        let device = DevicePath::device("Pixel 7");
+
+        let storages = fs.list(&device.to_path_buf()).unwrap();
+        assert_eq!(storages.len(), 1);
+        assert_eq!(storages[0].name, "Internal storage");
+        assert!(storages[0].is_directory());
+
+        let photo = device.join("Internal storage").join("DCIM").join("IMG_1.jpg");
+        let entry = fs.entry(&photo).unwrap();
+        assert_eq!(entry.size, 10);
+        assert_eq!(entry.path, photo.to_path_buf());
+        assert!(fs.list_dir(&photo).is_err());
+
+        let names: Vec<String> = fs.walk(&device).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["Pixel 7", "Internal storage", "DCIM", "IMG_1.jpg"]);
+
+        let missing = device.join("Internal storage").join("Music");
+        assert!(fs.entry(&missing).unwrap_err().is_not_found());
+        assert!(fs.entry(&DevicePath::device("Unplugged")).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_create_and_remove() {
+        let (devices, fs) = phone();
+        let storage = DevicePath::device("Pixel 7").join("Internal storage");
+        let dir = storage.join("Backups").join("2026");
+        fs.create_dir_all(&dir).unwrap();
+        assert!(fs.entry(&dir).unwrap().is_directory());
+
+        let file = dir.join("notes.txt");
+        let mut upload = fs.create(&file, 5).unwrap();
+        upload.write_all(b"notes").unwrap();
+        upload.finish().unwrap();
+        assert_eq!(devices.data("notes.txt").unwrap(), b"notes");
+        let again = fs.create(&file, 5).err().unwrap();
+        assert!(matches!(again, ZError::AlreadyExists { .. }));
+        // Files can't go straight onto the device
+        assert!(fs.create(&DevicePath::device("Pixel 7").join("x.txt"), 1).is_err());
+
+        fs.remove(&storage.join("Backups")).unwrap();
+        assert!(fs.entry(&file).unwrap_err().is_not_found());
+        assert!(devices.data("notes.txt").is_none());
+        assert!(fs.remove(&DevicePath::device("Pixel 7")).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+//! # ZManager MTP
+//!
+//! Phones, cameras and other portable devices for ZManager.
+//!
+//! Devices connected over MTP have no drive letter, so a pane browses them
+//! at a virtual `mtp://device/storage/path` instead:
+//! - [`MtpFileSystem`] lists the devices as drives and their folders as
+//!   ordinary [`EntryMeta`](zmanager_core::EntryMeta) listings
+//! - [`MtpBackend`] runs transfers to, from and between devices
+//!
+//! On Windows devices are reached through Windows Portable Devices (WPD);
+//! elsewhere none are ever connected.
+
+pub mod backend;
+pub mod device;
+pub mod filesystem;
+pub mod path;
+#[cfg(windows)]
+mod wpd;
+
+pub use backend::MtpBackend;
+pub use device::{
+    system_devices, DeviceInfo, DeviceObject, DeviceUpload, NoDevices, PortableDevices,
+    DEVICE_OBJECT_ID,
+};
+pub use filesystem::MtpFileSystem;
+pub use path::{is_device_path, path_component, split_device_path, DevicePath, MTP_PATH_PREFIX};
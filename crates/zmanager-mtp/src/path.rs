@@ -0,0 +1,135 @@
+//! Virtual paths of files on devices.
+//!
+//! A pane shows a folder on a phone or camera at `mtp://device/storage/path`,
+//! where `device` is the name the device goes by and `storage` one of its
+//! storages, such as "Internal shared storage" or an SD card.
+
+use std::path::{Path, PathBuf};
+
+/// Prefix of device paths.
+pub const MTP_PATH_PREFIX: &str = "mtp://";
+
+/// A device, or a storage, folder or file on one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DevicePath {
+    /// Name of the device.
+    pub device: String,
+    /// Names of the storage and folders leading to the file, empty for the
+    /// device itself.
+    pub components: Vec<String>,
+}
+
+impl DevicePath {
+    /// The path of the device named `device` itself.
+    pub fn device(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            components: Vec::new(),
+        }
+    }
+
+    /// The virtual path a pane shows this at.
+    pub fn to_path_buf(&self) -> PathBuf {
+        let mut path = format!("{MTP_PATH_PREFIX}{}", self.device);
+        for component in &self.components {
+            path.push('/');
+            path.push_str(component);
+        }
+        PathBuf::from(path)
+    }
+
+    /// The last component, or `None` for the device itself.
+    pub fn name(&self) -> Option<&str> {
+        self.components.last().map(String::as_str)
+    }
+
+    /// The path of the entry `name` in this folder.
+    pub fn join(&self, name: &str) -> Self {
+        let mut components = self.components.clone();
+        components.push(name.to_string());
+        Self {
+            device: self.device.clone(),
+            components,
+        }
+    }
+
+    /// The folder this is in, or `None` for the device itself.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parent) = self.components.split_last()?;
+        Some(Self {
+            device: self.device.clone(),
+            components: parent.to_vec(),
+        })
+    }
+}
+
+/// The device and components of a virtual device path, or `None` for any
+/// other path.
+///
+/// Both separators are accepted, since paths joined on Windows pick up
+/// backslashes, and `.` and `..` components are resolved.
+pub fn split_device_path(path: &Path) -> Option<DevicePath> {
+    let rest = path.to_str()?.strip_prefix(MTP_PATH_PREFIX)?;
+    let mut parts = rest.split(['/', '\\']).filter(|part| !part.is_empty() && *part != ".");
+    let device = parts.next().filter(|device| *device != "..")?;
+
+    let mut components: Vec<String> = Vec::new();
+    for part in parts {
+        if part == ".." {
+            components.pop();
+        } else {
+            components.push(part.to_string());
+        }
+    }
+    Some(DevicePath {
+        device: device.to_string(),
+        components,
+    })
+}
+
+/// Check if `path` is a virtual device path.
+pub fn is_device_path(path: &Path) -> bool {
+    split_device_path(path).is_some()
+}
+
+/// `name` as a path component: devices may name things with characters
+/// that would split the path.
+pub fn path_component(name: &str) -> String {
+    name.replace(['/', '\\'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_device_path() {
+        let path = split_device_path(Path::new("mtp://Pixel 7/Internal shared storage/DCIM"))
+            .unwrap();
+        assert_eq!(path.device, "Pixel 7");
+        assert_eq!(path.components, ["Internal shared storage", "DCIM"]);
+        assert_eq!(path.name(), Some("DCIM"));
+
+        // Backslashes from joins on Windows, and dots
+        let joined = Path::new("mtp://Pixel 7/Card/./Music\\..\\Photos");
+        let joined = split_device_path(joined).unwrap();
+        assert_eq!(joined.components, ["Card", "Photos"]);
+
+        let device = split_device_path(Path::new("mtp://Pixel 7/")).unwrap();
+        assert_eq!(device, DevicePath::device("Pixel 7"));
+        assert!(split_device_path(Path::new("mtp://")).is_none());
+        assert!(split_device_path(Path::new("C:\\DCIM")).is_none());
+        assert!(!is_device_path(Path::new("sftp://host/srv")));
+    }
+
+    #[test]
+    fn test_round_trip_join_and_parent() {
+        let device = DevicePath::device("EOS R6");
+        let photo = device.join("SD").join("IMG_0001.CR3");
+        assert_eq!(photo.to_path_buf(), PathBuf::from("mtp://EOS R6/SD/IMG_0001.CR3"));
+        assert_eq!(split_device_path(&photo.to_path_buf()).unwrap(), photo);
+        assert_eq!(photo.parent().unwrap().parent().unwrap(), device);
+        assert!(device.parent().is_none());
+        assert_eq!(path_component("AC/DC"), "AC_DC");
+    }
+}
@@ -0,0 +1,409 @@
+//! Windows Portable Devices (WPD), the system's API for MTP devices.
+//!
+//! Each call opens the device anew through `PortableDeviceFTM`, whose
+//! objects use the free-threaded marshaler, so streams opened on one thread
+//! can be read or written on any other.
+
+use std::io::{Read, Write};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use tracing::debug;
+use windows::core::{HSTRING, PROPVARIANT, PWSTR};
+use windows::Win32::Devices::PortableDevices::{
+    IPortableDevice, IPortableDeviceContent, IPortableDeviceKeyCollection,
+    IPortableDeviceManager, IPortableDevicePropVariantCollection, IPortableDeviceValues,
+    PortableDeviceFTM, PortableDeviceKeyCollection, PortableDeviceManager,
+    PortableDevicePropVariantCollection, PortableDeviceValues,
+    PORTABLE_DEVICE_DELETE_WITH_RECURSION, WPD_CLIENT_MAJOR_VERSION, WPD_CLIENT_MINOR_VERSION,
+    WPD_CLIENT_NAME, WPD_CLIENT_REVISION, WPD_CONTENT_TYPE_FOLDER,
+    WPD_CONTENT_TYPE_FUNCTIONAL_OBJECT, WPD_CONTENT_TYPE_GENERIC_FILE, WPD_OBJECT_CONTENT_TYPE,
+    WPD_OBJECT_DATE_MODIFIED, WPD_OBJECT_FORMAT, WPD_OBJECT_FORMAT_PROPERTIES_ONLY,
+    WPD_OBJECT_FORMAT_UNSPECIFIED, WPD_OBJECT_NAME, WPD_OBJECT_ORIGINAL_FILE_NAME,
+    WPD_OBJECT_PARENT_ID, WPD_OBJECT_SIZE, WPD_RESOURCE_DEFAULT,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, IStream, CLSCTX_INPROC_SERVER,
+    COINIT_MULTITHREADED, STGC_DEFAULT, STGM_READ,
+};
+use windows::Win32::System::Variant::{VT_DATE, VT_LPWSTR};
+use zmanager_core::{ZError, ZResult};
+
+use crate::device::{DeviceInfo, DeviceObject, DeviceUpload, PortableDevices};
+
+/// Objects listed per call to the enumerator.
+const ENUM_BATCH: usize = 64;
+
+/// The devices Windows Portable Devices knows of.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct WpdDevices;
+
+impl WpdDevices {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl PortableDevices for WpdDevices {
+    fn devices(&self) -> ZResult<Vec<DeviceInfo>> {
+        init_com();
+        unsafe {
+            let manager: IPortableDeviceManager =
+                CoCreateInstance(&PortableDeviceManager, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| wpd_error("open the device manager", e))?;
+            // Devices plugged in since the last call are only seen after this
+            let _ = manager.RefreshDeviceList();
+
+            let mut count = 0u32;
+            manager
+                .GetDevices(std::ptr::null_mut(), &mut count)
+                .map_err(|e| wpd_error("list devices", e))?;
+            let mut ids = vec![PWSTR::null(); count as usize];
+            if count > 0 {
+                manager
+                    .GetDevices(ids.as_mut_ptr(), &mut count)
+                    .map_err(|e| wpd_error("list devices", e))?;
+            }
+
+            let mut devices = Vec::new();
+            for id in ids.into_iter().take(count as usize) {
+                let id = take_string(id);
+                let name = friendly_name(&manager, &id).unwrap_or_else(|| id.clone());
+                devices.push(DeviceInfo { id, name });
+            }
+            debug!(count = devices.len(), "WPD devices listed");
+            Ok(devices)
+        }
+    }
+
+    fn children(&self, device: &str, parent: &str) -> ZResult<Vec<DeviceObject>> {
+        let content = content(device)?;
+        unsafe {
+            let ids = content
+                .EnumObjects(0, &HSTRING::from(parent), None::<&IPortableDeviceValues>)
+                .map_err(|e| wpd_error("list the folder", e))?;
+            let mut children = Vec::new();
+            loop {
+                let mut batch = [PWSTR::null(); ENUM_BATCH];
+                let mut fetched = 0u32;
+                let result = ids.Next(&mut batch, &mut fetched);
+                for id in batch.into_iter().take(fetched as usize) {
+                    children.push(take_string(id));
+                }
+                result.ok().map_err(|e| wpd_error("list the folder", e))?;
+                // S_FALSE: the enumerator ran out before the batch filled
+                if fetched < ENUM_BATCH as u32 {
+                    break;
+                }
+            }
+
+            let properties = content
+                .Properties()
+                .map_err(|e| wpd_error("read object properties", e))?;
+            let keys: IPortableDeviceKeyCollection =
+                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| wpd_error("read object properties", e))?;
+            for key in [
+                WPD_OBJECT_NAME,
+                WPD_OBJECT_ORIGINAL_FILE_NAME,
+                WPD_OBJECT_CONTENT_TYPE,
+                WPD_OBJECT_SIZE,
+                WPD_OBJECT_DATE_MODIFIED,
+            ] {
+                keys.Add(&key)
+                    .map_err(|e| wpd_error("read object properties", e))?;
+            }
+
+            let mut objects = Vec::with_capacity(children.len());
+            for id in children {
+                let values = properties
+                    .GetValues(&HSTRING::from(id.as_str()), &keys)
+                    .map_err(|e| wpd_error("read object properties", e))?;
+                let name = values
+                    .GetStringValue(&WPD_OBJECT_ORIGINAL_FILE_NAME)
+                    .or_else(|_| values.GetStringValue(&WPD_OBJECT_NAME))
+                    .map(|name| take_string(name))
+                    .unwrap_or_else(|_| id.clone());
+                // Storages are functional objects
+                let is_folder = values.GetGuidValue(&WPD_OBJECT_CONTENT_TYPE).is_ok_and(|kind| {
+                    [WPD_CONTENT_TYPE_FOLDER, WPD_CONTENT_TYPE_FUNCTIONAL_OBJECT].contains(&kind)
+                });
+                let size = values.GetUnsignedLargeIntegerValue(&WPD_OBJECT_SIZE).unwrap_or(0);
+                let modified = values
+                    .GetValue(&WPD_OBJECT_DATE_MODIFIED)
+                    .ok()
+                    .and_then(|value| ole_date(&value));
+                objects.push(DeviceObject {
+                    id,
+                    name,
+                    is_folder,
+                    size: if is_folder { 0 } else { size },
+                    modified,
+                });
+            }
+            Ok(objects)
+        }
+    }
+
+    fn open(&self, device: &str, object: &str) -> ZResult<Box<dyn Read + Send>> {
+        let content = content(device)?;
+        unsafe {
+            let resources = content
+                .Transfer()
+                .map_err(|e| wpd_error("open the file", e))?;
+            let mut buffer_size = 0u32;
+            let mut stream = None;
+            resources
+                .GetStream(
+                    &HSTRING::from(object),
+                    &WPD_RESOURCE_DEFAULT,
+                    STGM_READ.0,
+                    &mut buffer_size,
+                    &mut stream,
+                )
+                .map_err(|e| wpd_error("open the file", e))?;
+            let stream = stream.ok_or_else(|| ZError::Internal {
+                message: "The device opened no stream".to_string(),
+            })?;
+            Ok(Box::new(StreamReader { stream }))
+        }
+    }
+
+    fn create(
+        &self,
+        device: &str,
+        parent: &str,
+        name: &str,
+        size: u64,
+    ) -> ZResult<Box<dyn DeviceUpload>> {
+        let content = content(device)?;
+        unsafe {
+            let values = object_values(parent, name)
+                .and_then(|values| {
+                    values.SetUnsignedLargeIntegerValue(&WPD_OBJECT_SIZE, size)?;
+                    values.SetGuidValue(&WPD_OBJECT_CONTENT_TYPE, &WPD_CONTENT_TYPE_GENERIC_FILE)?;
+                    values.SetGuidValue(&WPD_OBJECT_FORMAT, &WPD_OBJECT_FORMAT_UNSPECIFIED)?;
+                    Ok(values)
+                })
+                .map_err(|e| wpd_error("create the file", e))?;
+            let mut stream = None;
+            let mut buffer_size = 0u32;
+            content
+                .CreateObjectWithPropertiesAndData(
+                    &values,
+                    &mut stream,
+                    &mut buffer_size,
+                    std::ptr::null_mut(),
+                )
+                .map_err(|e| wpd_error("create the file", e))?;
+            let stream = stream.ok_or_else(|| ZError::Internal {
+                message: "The device opened no stream".to_string(),
+            })?;
+            Ok(Box::new(StreamUpload {
+                stream,
+                committed: false,
+            }))
+        }
+    }
+
+    fn create_folder(&self, device: &str, parent: &str, name: &str) -> ZResult<String> {
+        let content = content(device)?;
+        unsafe {
+            let values = object_values(parent, name)
+                .and_then(|values| {
+                    values.SetGuidValue(&WPD_OBJECT_CONTENT_TYPE, &WPD_CONTENT_TYPE_FOLDER)?;
+                    values.SetGuidValue(&WPD_OBJECT_FORMAT, &WPD_OBJECT_FORMAT_PROPERTIES_ONLY)?;
+                    Ok(values)
+                })
+                .map_err(|e| wpd_error("create the folder", e))?;
+            let mut id = PWSTR::null();
+            content
+                .CreateObjectWithPropertiesOnly(&values, &mut id)
+                .map_err(|e| wpd_error("create the folder", e))?;
+            Ok(take_string(id))
+        }
+    }
+
+    fn delete(&self, device: &str, object: &str) -> ZResult<()> {
+        let content = content(device)?;
+        unsafe {
+            let ids: IPortableDevicePropVariantCollection =
+                CoCreateInstance(&PortableDevicePropVariantCollection, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| wpd_error("delete", e))?;
+            ids.Add(&PROPVARIANT::from(object))
+                .map_err(|e| wpd_error("delete", e))?;
+            // Devices want object IDs as plain strings, not BSTRs
+            ids.ChangeType(VT_LPWSTR.0)
+                .map_err(|e| wpd_error("delete", e))?;
+            content
+                .Delete(
+                    PORTABLE_DEVICE_DELETE_WITH_RECURSION.0 as u32,
+                    &ids,
+                    std::ptr::null_mut(),
+                )
+                .map_err(|e| wpd_error("delete", e))
+        }
+    }
+}
+
+/// A file on a device, read through its stream.
+struct StreamReader {
+    stream: IStream,
+}
+
+// SAFETY: streams of PortableDeviceFTM devices use the free-threaded
+// marshaler, so they may be called from any thread.
+unsafe impl Send for StreamReader {}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        let mut read = 0u32;
+        unsafe { self.stream.Read(buf.as_mut_ptr().cast(), len, Some(&mut read)) }
+            .ok()
+            .map_err(std::io::Error::other)?;
+        Ok(read as usize)
+    }
+}
+
+/// A file being written to a device. The device only keeps it once the
+/// stream is committed.
+struct StreamUpload {
+    stream: IStream,
+    committed: bool,
+}
+
+// SAFETY: as for `StreamReader`.
+unsafe impl Send for StreamUpload {}
+
+impl Write for StreamUpload {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        let mut written = 0u32;
+        unsafe { self.stream.Write(buf.as_ptr().cast(), len, Some(&mut written)) }
+            .ok()
+            .map_err(std::io::Error::other)?;
+        Ok(written as usize)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl DeviceUpload for StreamUpload {
+    fn finish(mut self: Box<Self>) -> ZResult<()> {
+        self.committed = true;
+        unsafe { self.stream.Commit(STGC_DEFAULT) }
+            .map_err(|e| wpd_error("finish the file", e))
+    }
+}
+
+impl Drop for StreamUpload {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = unsafe { self.stream.Revert() };
+        }
+    }
+}
+
+/// Join the multithreaded apartment. Threads already in another apartment
+/// stay there; the free-threaded objects work from either.
+fn init_com() {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+}
+
+/// Open `device` and its content.
+fn content(device: &str) -> ZResult<IPortableDeviceContent> {
+    init_com();
+    unsafe {
+        let client: IPortableDeviceValues =
+            CoCreateInstance(&PortableDeviceValues, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| wpd_error("open the device", e))?;
+        client
+            .SetStringValue(&WPD_CLIENT_NAME, &HSTRING::from("ZManager"))
+            .and_then(|()| client.SetUnsignedIntegerValue(&WPD_CLIENT_MAJOR_VERSION, 1))
+            .and_then(|()| client.SetUnsignedIntegerValue(&WPD_CLIENT_MINOR_VERSION, 0))
+            .and_then(|()| client.SetUnsignedIntegerValue(&WPD_CLIENT_REVISION, 0))
+            .map_err(|e| wpd_error("open the device", e))?;
+
+        let portable: IPortableDevice =
+            CoCreateInstance(&PortableDeviceFTM, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| wpd_error("open the device", e))?;
+        portable
+            .Open(&HSTRING::from(device), &client)
+            .map_err(|e| wpd_error("open the device", e))?;
+        portable
+            .Content()
+            .map_err(|e| wpd_error("open the device", e))
+    }
+}
+
+/// The properties every new object is created with: its parent and name.
+unsafe fn object_values(parent: &str, name: &str) -> windows::core::Result<IPortableDeviceValues> {
+    unsafe {
+        let values: IPortableDeviceValues =
+            CoCreateInstance(&PortableDeviceValues, None, CLSCTX_INPROC_SERVER)?;
+        values.SetStringValue(&WPD_OBJECT_PARENT_ID, &HSTRING::from(parent))?;
+        values.SetStringValue(&WPD_OBJECT_NAME, &HSTRING::from(name))?;
+        values.SetStringValue(&WPD_OBJECT_ORIGINAL_FILE_NAME, &HSTRING::from(name))?;
+        Ok(values)
+    }
+}
+
+/// The name the user gave `device`, if it has one.
+unsafe fn friendly_name(manager: &IPortableDeviceManager, device: &str) -> Option<String> {
+    let device = HSTRING::from(device);
+    let mut len = 0u32;
+    unsafe {
+        manager
+            .GetDeviceFriendlyName(&device, PWSTR::null(), &mut len)
+            .ok()?;
+        let mut buffer = vec![0u16; len as usize];
+        manager
+            .GetDeviceFriendlyName(&device, PWSTR(buffer.as_mut_ptr()), &mut len)
+            .ok()?;
+        let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        let name = String::from_utf16_lossy(&buffer[..end]);
+        (!name.trim().is_empty()).then_some(name)
+    }
+}
+
+/// Take over a string the API allocated, freeing it.
+unsafe fn take_string(string: PWSTR) -> String {
+    if string.is_null() {
+        return String::new();
+    }
+    unsafe {
+        let text = string.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(string.0.cast_const().cast()));
+        text
+    }
+}
+
+/// The time in an OLE date: days since the end of 1899, local time.
+fn ole_date(value: &PROPVARIANT) -> Option<DateTime<Utc>> {
+    let raw = value.as_raw();
+    // SAFETY: the variant type says which member is set
+    let days = unsafe {
+        if raw.Anonymous.Anonymous.vt != VT_DATE.0 {
+            return None;
+        }
+        raw.Anonymous.Anonymous.Anonymous.date
+    };
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let local = epoch.checked_add_signed(Duration::milliseconds((days * 86_400_000.0) as i64))?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// A ZError for a failed WPD call.
+fn wpd_error(action: &str, error: windows::core::Error) -> ZError {
+    ZError::Windows {
+        code: error.code().0 as u32,
+        message: format!("Failed to {action} on the device: {}", error.message()),
+    }
+}
@@ -0,0 +1,349 @@
+//! Transfers to, from and between servers.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use tracing::{debug, info, warn};
+use zmanager_core::{
+    extended_path, get_entry_meta, CancellationToken, EntryMeta, PauseToken, SpeedLimit, ZError,
+    ZResult,
+};
+use zmanager_transfer_win::{
+    copy_stream, CopyProgress, PortableBackend, ProgressCallback, TransferBackend,
+};
+
+use crate::remote::{is_remote_path, Location, RemoteFileSystems};
+use crate::sftp_error;
+use crate::webdav::DavUpload;
+
+/// Runs copies and moves that involve a server.
+///
+/// Registered with a [`BackendRegistry`](zmanager_transfer_win::BackendRegistry),
+/// it takes every job with an `sftp://`, `dav://` or `davs://` path among
+/// its sources or its destination, so uploads, downloads and copies between
+/// servers run as ordinary transfer jobs with progress. Local paths in those
+/// jobs are handled through `std::fs`.
+#[derive(Debug, Clone)]
+pub struct RemoteBackend {
+    fs: Arc<RemoteFileSystems>,
+}
+
+/// The open destination of a copy.
+trait Destination: Write {
+    /// Complete the file at `path`, reporting what the server only reports
+    /// once it has all of it.
+    fn finish(self: Box<Self>, path: &Path) -> ZResult<()>;
+}
+
+impl Destination for File {
+    fn finish(self: Box<Self>, _path: &Path) -> ZResult<()> {
+        Ok(())
+    }
+}
+
+impl Destination for ssh2::File {
+    fn finish(mut self: Box<Self>, path: &Path) -> ZResult<()> {
+        // Some servers only commit a file once its handle is closed
+        self.close().map_err(|e| sftp_error(path, e))
+    }
+}
+
+impl Destination for DavUpload {
+    fn finish(self: Box<Self>, _path: &Path) -> ZResult<()> {
+        DavUpload::finish(*self)
+    }
+}
+
+impl RemoteBackend {
+    /// Create a backend working through the connections of `fs`.
+    pub fn new(fs: Arc<RemoteFileSystems>) -> Self {
+        Self { fs }
+    }
+
+    /// Open the source of a copy: its reader, size and modification time
+    /// in seconds since the Unix epoch.
+    fn open_source(&self, source: &Path) -> ZResult<(Box<dyn Read>, u64, Option<u64>)> {
+        let not_a_file = || ZError::NotAFile {
+            path: source.to_path_buf(),
+        };
+        match Location::of(source) {
+            Location::Sftp(remote) => {
+                let stat = self.fs.sftp().metadata(&remote)?;
+                if stat.is_dir() {
+                    return Err(not_a_file());
+                }
+                let reader = self.fs.sftp().open(&remote)?;
+                Ok((Box::new(reader), stat.size.unwrap_or(0), stat.mtime))
+            }
+            Location::WebDav(remote) => {
+                let entry = self.fs.webdav().entry(&remote)?;
+                if entry.is_directory() {
+                    return Err(not_a_file());
+                }
+                let mtime = entry
+                    .modified
+                    .and_then(|time| u64::try_from(time.timestamp()).ok());
+                let reader = self.fs.webdav().open(&remote)?;
+                Ok((reader, entry.size, mtime))
+            }
+            Location::Local => {
+                let os_source = extended_path(source);
+                let meta =
+                    std::fs::metadata(&os_source).map_err(|e| ZError::from_io(source, e))?;
+                if !meta.is_file() {
+                    return Err(not_a_file());
+                }
+                let mtime = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs());
+                let reader = File::open(os_source).map_err(|e| ZError::from_io(source, e))?;
+                Ok((Box::new(reader), meta.len(), mtime))
+            }
+        }
+    }
+
+    /// Create the destination of a copy, and the directories above it.
+    fn create_destination(&self, destination: &Path) -> ZResult<Box<dyn Destination>> {
+        match Location::of(destination) {
+            Location::Sftp(remote) => {
+                if let Some(parent) = remote.parent() {
+                    self.fs.sftp().create_dir_all(&parent)?;
+                }
+                Ok(Box::new(self.fs.sftp().create(&remote)?))
+            }
+            Location::WebDav(remote) => {
+                if let Some(parent) = remote.parent() {
+                    self.fs.webdav().create_dir_all(&parent)?;
+                }
+                Ok(Box::new(self.fs.webdav().create(&remote)?))
+            }
+            Location::Local => {
+                if let Some(parent) = destination.parent() {
+                    PortableBackend.create_dir(parent)?;
+                }
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(extended_path(destination))
+                    .map_err(|e| ZError::from_io(destination, e))?;
+                Ok(Box::new(file))
+            }
+        }
+    }
+
+    /// Carry the modification time over to a finished copy. WebDAV servers
+    /// time uploads themselves, so copies there keep the upload time.
+    fn set_modified(&self, destination: &Path, mtime: u64) -> ZResult<()> {
+        match Location::of(destination) {
+            Location::Sftp(remote) => self.fs.sftp().set_modified(&remote, mtime),
+            Location::WebDav(_) => Ok(()),
+            Location::Local => {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .open(extended_path(destination))
+                    .map_err(|e| ZError::from_io(destination, e))?;
+                file.set_modified(UNIX_EPOCH + Duration::from_secs(mtime))
+                    .map_err(|e| ZError::io(destination, e))
+            }
+        }
+    }
+}
+
+impl TransferBackend for RemoteBackend {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+
+    fn handles(&self, sources: &[PathBuf], destination: &Path) -> bool {
+        is_remote_path(destination) || sources.iter().any(|source| is_remote_path(source))
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        if !overwrite && self.entry(destination).is_some() {
+            return Err(ZError::AlreadyExists {
+                path: destination.to_path_buf(),
+            });
+        }
+        let (mut reader, total_bytes, mtime) = self.open_source(source)?;
+
+        debug!(
+            source = %source.display(),
+            destination = %destination.display(),
+            overwrite,
+            "Starting remote file copy"
+        );
+
+        let mut writer = self.create_destination(destination)?;
+        let result = copy_stream(
+            &mut reader,
+            &mut writer,
+            source,
+            destination,
+            &cancel_token,
+            &pause_token,
+            &speed_limit,
+            |bytes_copied, speed| {
+                if let Some(ref callback) = progress_callback {
+                    callback(CopyProgress {
+                        total_bytes,
+                        bytes_copied,
+                        source: source.to_path_buf(),
+                        destination: destination.to_path_buf(),
+                        speed_bps: speed,
+                        eta_seconds: (speed > 0)
+                            .then(|| total_bytes.saturating_sub(bytes_copied) / speed),
+                    });
+                }
+            },
+        )
+        .and_then(|bytes| writer.finish(destination).map(|()| bytes));
+
+        match result {
+            Ok(bytes) => {
+                if let Some(mtime) = mtime {
+                    if let Err(e) = self.set_modified(destination, mtime) {
+                        warn!(destination = %destination.display(), error = %e, "Cannot set time");
+                    }
+                }
+                info!(
+                    bytes,
+                    source = %source.display(),
+                    destination = %destination.display(),
+                    "Remote file copy completed"
+                );
+                Ok(bytes)
+            }
+            Err(e) => {
+                if matches!(e, ZError::Cancelled) {
+                    warn!(source = %source.display(), "Remote file copy cancelled");
+                }
+                let _ = self.delete(destination);
+                Err(e)
+            }
+        }
+    }
+
+    fn move_file(&self, source: &Path, destination: &Path) -> ZResult<()> {
+        match (Location::of(source), Location::of(destination)) {
+            (Location::Sftp(from), Location::Sftp(to)) => self.fs.sftp().rename(&from, &to),
+            (Location::WebDav(from), Location::WebDav(to)) => {
+                self.fs.webdav().rename(&from, &to)
+            }
+            (Location::Local, Location::Local) => PortableBackend.move_file(source, destination),
+            _ => Err(ZError::InvalidOperation {
+                operation: format!("move {}", source.display()),
+                reason: "Moving between a server and this computer copies".to_string(),
+            }),
+        }
+    }
+
+    fn entry(&self, path: &Path) -> Option<EntryMeta> {
+        match Location::of(path) {
+            Location::Sftp(remote) => self.fs.sftp().entry(&remote).ok(),
+            Location::WebDav(remote) => self.fs.webdav().entry(&remote).ok(),
+            Location::Local => get_entry_meta(path).ok(),
+        }
+    }
+
+    fn walk(&self, path: &Path) -> Option<ZResult<Vec<EntryMeta>>> {
+        match Location::of(path) {
+            Location::Sftp(remote) => Some(self.fs.sftp().walk(&remote)),
+            Location::WebDav(remote) => Some(self.fs.webdav().walk(&remote)),
+            Location::Local => None,
+        }
+    }
+
+    fn create_dir(&self, path: &Path) -> ZResult<()> {
+        match Location::of(path) {
+            Location::Sftp(remote) => self.fs.sftp().create_dir_all(&remote),
+            Location::WebDav(remote) => self.fs.webdav().create_dir_all(&remote),
+            Location::Local => PortableBackend.create_dir(path),
+        }
+    }
+
+    fn delete(&self, path: &Path) -> ZResult<()> {
+        match Location::of(path) {
+            Location::Sftp(remote) => self.fs.sftp().remove(&remote),
+            Location::WebDav(remote) => self.fs.webdav().remove(&remote),
+            Location::Local => PortableBackend.delete(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_handles_jobs_touching_a_server() {
+        let backend = RemoteBackend::new(Arc::new(RemoteFileSystems::new()));
+        let local = vec![PathBuf::from("/home/me/notes.txt")];
+        let remote = vec![PathBuf::from("sftp://example.com/srv/notes.txt")];
+
+        assert!(backend.handles(&local, Path::new("sftp://example.com/srv")));
+        assert!(backend.handles(&remote, Path::new("/home/me")));
+        assert!(backend.handles(&local, Path::new("davs://cloud.example.com/Files")));
+        assert!(!backend.handles(&local, Path::new("/tmp")));
+        assert!(backend.walk(Path::new("/home/me")).is_none());
+    }
+
+    #[test]
+    fn test_local_paths_use_std_fs() {
+        let temp = TempDir::new().unwrap();
+        let backend = RemoteBackend::new(Arc::new(RemoteFileSystems::new()));
+        let source = temp.path().join("source.txt");
+        let dest = temp.path().join("copies").join("dest.txt");
+        fs::write(&source, "local bytes").unwrap();
+
+        let bytes = backend
+            .copy_file(
+                &source,
+                &dest,
+                false,
+                CancellationToken::new(),
+                PauseToken::new(),
+                SpeedLimit::new(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(bytes, 11);
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "local bytes");
+        // SFTP keeps times to the second, and so does the backend
+        let secs = |path: &Path| {
+            let modified = fs::metadata(path).unwrap().modified().unwrap();
+            modified.duration_since(UNIX_EPOCH).unwrap().as_secs()
+        };
+        assert_eq!(secs(&dest), secs(&source));
+
+        backend.delete(&dest).unwrap();
+        assert!(backend.entry(&dest).is_none());
+    }
+
+    #[test]
+    fn test_unreachable_server_fails() {
+        let backend = RemoteBackend::new(Arc::new(RemoteFileSystems::new()));
+        // Nothing listens on port 1
+        for remote in ["sftp://127.0.0.1:1/tmp", "dav://127.0.0.1:1/tmp"] {
+            let remote = Path::new(remote);
+            assert!(backend.entry(remote).is_none());
+            assert!(backend.walk(remote).unwrap().is_err());
+        }
+    }
+}
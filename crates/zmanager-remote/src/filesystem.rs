@@ -0,0 +1,277 @@
+//! Files and directories on servers, over SFTP.
+
+use std::path::Path;
+
+use chrono::DateTime;
+use ssh2::{FileStat, OpenFlags, OpenType, RenameFlags};
+use tracing::debug;
+use zmanager_core::{DirectoryProvider, EntryKind, EntryMeta, ZError, ZResult};
+
+use crate::path::{split_remote_path, RemotePath};
+use crate::session::SftpSessions;
+
+/// Permissions of files created on a server, before its umask.
+const FILE_MODE: i32 = 0o644;
+
+/// Permissions of directories created on a server, before its umask.
+const DIR_MODE: i32 = 0o755;
+
+/// Write permission bits for the owner, group and others.
+const WRITE_BITS: u32 = 0o222;
+
+/// Directories and files on any number of servers, reached by their
+/// virtual `sftp://` paths.
+///
+/// Lists directories for the panes as a [`DirectoryProvider`], and does the
+/// file work for [`RemoteBackend`](crate::RemoteBackend). All calls block on
+/// the network.
+#[derive(Debug, Default)]
+pub struct SftpFileSystem {
+    sessions: SftpSessions,
+}
+
+impl SftpFileSystem {
+    /// Create a file system with no servers connected yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The connections to servers, which log in on first use.
+    pub fn sessions(&self) -> &SftpSessions {
+        &self.sessions
+    }
+
+    /// List the directory at `remote`.
+    ///
+    /// Links are listed as what they point to, with the link's target; a
+    /// link whose target is missing is listed as a broken link.
+    pub fn list_dir(&self, remote: &RemotePath) -> ZResult<Vec<EntryMeta>> {
+        let path = remote.to_path_buf();
+        debug!(path = %path.display(), "Listing remote directory");
+        self.sessions.with(&remote.host, &path, |sftp| {
+            let mut entries = Vec::new();
+            for (child, stat) in sftp.readdir(remote.server_path())? {
+                let Some(name) = child.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                let child = remote.join(name);
+                let entry = if stat.file_type().is_symlink() {
+                    let target = sftp.readlink(child.server_path()).ok();
+                    let resolved = sftp.stat(child.server_path()).ok();
+                    link_meta(&child, &stat, resolved.as_ref(), target)
+                } else {
+                    entry_meta(&child, &stat)
+                };
+                entries.push(entry);
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Look up `remote`, without following a link there.
+    pub fn entry(&self, remote: &RemotePath) -> ZResult<EntryMeta> {
+        let path = remote.to_path_buf();
+        self.sessions.with(&remote.host, &path, |sftp| {
+            let stat = sftp.lstat(remote.server_path())?;
+            let mut meta = entry_meta(remote, &stat);
+            if stat.file_type().is_symlink() {
+                meta.kind = EntryKind::Symlink;
+                meta.link_target = sftp.readlink(remote.server_path()).ok();
+            }
+            Ok(meta)
+        })
+    }
+
+    /// The attributes of `remote`, following a link there.
+    pub fn metadata(&self, remote: &RemotePath) -> ZResult<FileStat> {
+        let path = remote.to_path_buf();
+        self.sessions.with(&remote.host, &path, |sftp| sftp.stat(remote.server_path()))
+    }
+
+    /// List `remote` and, for a directory, everything below it, each
+    /// directory before its contents.
+    ///
+    /// Links are followed to files, but not into directories, so a link
+    /// cycle can't trap the walk; a linked directory comes out empty.
+    pub fn walk(&self, remote: &RemotePath) -> ZResult<Vec<EntryMeta>> {
+        let root = self.metadata(remote)?;
+        let mut entries = vec![entry_meta(remote, &root)];
+        if !root.is_dir() {
+            return Ok(entries);
+        }
+
+        let mut pending = vec![remote.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in self.list_dir(&dir)? {
+                if entry.is_directory() && entry.link_target.is_none() {
+                    let name = entry.name.clone();
+                    pending.push(dir.join(&name));
+                }
+                if !entry.is_broken_link {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Create the directory at `remote` and any missing parents.
+    pub fn create_dir_all(&self, remote: &RemotePath) -> ZResult<()> {
+        let mut missing = Vec::new();
+        let mut dir = Some(remote.clone());
+        while let Some(current) = dir {
+            match self.entry(&current) {
+                Ok(entry) if entry.is_directory() => break,
+                Ok(_) => {
+                    return Err(ZError::NotADirectory {
+                        path: current.to_path_buf(),
+                    });
+                }
+                Err(e) if e.is_not_found() => {
+                    dir = current.parent();
+                    missing.push(current);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        for dir in missing.iter().rev() {
+            let path = dir.to_path_buf();
+            self.sessions.with(&dir.host, &path, |sftp| {
+                sftp.mkdir(dir.server_path(), DIR_MODE)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Delete the file, link or empty directory at `remote`.
+    pub fn remove(&self, remote: &RemotePath) -> ZResult<()> {
+        let path = remote.to_path_buf();
+        self.sessions.with(&remote.host, &path, |sftp| {
+            let stat = sftp.lstat(remote.server_path())?;
+            if stat.is_dir() {
+                sftp.rmdir(remote.server_path())
+            } else {
+                sftp.unlink(remote.server_path())
+            }
+        })
+    }
+
+    /// Rename `from` to `to` on the same server. Fails if `to` exists.
+    pub fn rename(&self, from: &RemotePath, to: &RemotePath) -> ZResult<()> {
+        let path = from.to_path_buf();
+        if from.host != to.host {
+            return Err(ZError::InvalidOperation {
+                operation: format!("rename {}", path.display()),
+                reason: "Files can only be renamed on the same server".to_string(),
+            });
+        }
+        if self.entry(to).is_ok() {
+            return Err(ZError::AlreadyExists {
+                path: to.to_path_buf(),
+            });
+        }
+        self.sessions.with(&from.host, &path, |sftp| {
+            sftp.rename(from.server_path(), to.server_path(), Some(RenameFlags::ATOMIC))
+        })
+    }
+
+    /// Open the file at `remote` for reading.
+    pub fn open(&self, remote: &RemotePath) -> ZResult<ssh2::File> {
+        let path = remote.to_path_buf();
+        self.sessions.with(&remote.host, &path, |sftp| sftp.open(remote.server_path()))
+    }
+
+    /// Create or truncate the file at `remote` for writing.
+    pub fn create(&self, remote: &RemotePath) -> ZResult<ssh2::File> {
+        let path = remote.to_path_buf();
+        self.sessions.with(&remote.host, &path, |sftp| {
+            sftp.open_mode(
+                remote.server_path(),
+                OpenFlags::WRITE | OpenFlags::TRUNCATE,
+                FILE_MODE,
+                OpenType::File,
+            )
+        })
+    }
+
+    /// Set the modification time of the file at `remote`, in seconds since
+    /// the Unix epoch.
+    pub fn set_modified(&self, remote: &RemotePath, mtime: u64) -> ZResult<()> {
+        let path = remote.to_path_buf();
+        let stat = FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: None,
+            atime: Some(mtime),
+            mtime: Some(mtime),
+        };
+        self.sessions.with(&remote.host, &path, |sftp| {
+            sftp.setstat(remote.server_path(), stat)
+        })
+    }
+}
+
+impl DirectoryProvider for SftpFileSystem {
+    fn handles(&self, path: &Path) -> bool {
+        split_remote_path(path).is_some()
+    }
+
+    fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>> {
+        let remote = split_remote_path(path).ok_or_else(|| ZError::InvalidPath {
+            path: path.to_path_buf(),
+            reason: "Not an sftp:// path".to_string(),
+        })?;
+        self.list_dir(&remote)
+    }
+}
+
+/// The entry for `remote`, from its attributes.
+fn entry_meta(remote: &RemotePath, stat: &FileStat) -> EntryMeta {
+    let path = remote.to_path_buf();
+    let name = remote
+        .path
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .map_or_else(|| remote.host.to_string(), str::to_string);
+    let kind = if stat.is_dir() {
+        EntryKind::Directory
+    } else {
+        EntryKind::File
+    };
+
+    let mut meta = EntryMeta::new(name, path, kind);
+    if kind.is_file() {
+        meta.size = stat.size.unwrap_or(0);
+    }
+    meta.modified = stat
+        .mtime
+        .and_then(|secs| DateTime::from_timestamp(i64::try_from(secs).ok()?, 0));
+    meta.accessed = stat
+        .atime
+        .and_then(|secs| DateTime::from_timestamp(i64::try_from(secs).ok()?, 0));
+    meta.attributes.readonly = stat.perm.is_some_and(|perm| perm & WRITE_BITS == 0);
+    meta
+}
+
+/// The entry for the link at `remote`: what it resolves to, if anything.
+fn link_meta(
+    remote: &RemotePath,
+    stat: &FileStat,
+    resolved: Option<&FileStat>,
+    target: Option<std::path::PathBuf>,
+) -> EntryMeta {
+    let mut meta = match resolved {
+        Some(resolved) => entry_meta(remote, resolved),
+        None => {
+            let mut meta = entry_meta(remote, stat);
+            meta.kind = EntryKind::Symlink;
+            meta.is_broken_link = true;
+            meta
+        }
+    };
+    meta.link_target = Some(target.unwrap_or_default());
+    meta
+}
@@ -0,0 +1,34 @@
+//! # ZManager Remote
+//!
+//! Remote file systems for ZManager, reached over SFTP and WebDAV.
+//!
+//! A pane browses a server at a virtual `sftp://user@host:port/path`, or
+//! `dav://...` and `davs://...` for WebDAV, and copies and moves between
+//! the local disk and servers run as ordinary transfer jobs with progress:
+//! - [`RemoteFileSystems`] lists remote directories as a
+//!   [`DirectoryProvider`](zmanager_core::DirectoryProvider)
+//! - [`RemoteBackend`] runs transfers involving a server as a
+//!   [`TransferBackend`](zmanager_transfer_win::TransferBackend)
+//! - [`SftpSessions`] logs in to each SFTP server once, with the SSH agent
+//!   or a key file, and checks its host key against `known_hosts`
+//! - [`WebDavFileSystem`] logs in to WebDAV servers with the passwords in
+//!   `~/.netrc`
+
+pub mod backend;
+pub mod filesystem;
+mod netrc;
+pub mod path;
+pub mod remote;
+pub mod session;
+pub mod webdav;
+
+pub use backend::RemoteBackend;
+pub use filesystem::SftpFileSystem;
+pub use path::{
+    remote_path, split_dav_path, split_remote_path, DavPath, DavServer, RemoteHost, RemotePath,
+    DAVS_PATH_PREFIX, DAV_PATH_PREFIX, DEFAULT_SSH_PORT, SFTP_PATH_PREFIX,
+};
+pub use remote::{canonical_remote_path, is_remote_path, RemoteFileSystems};
+pub(crate) use session::sftp_error;
+pub use session::SftpSessions;
+pub use webdav::{DavUpload, WebDavFileSystem};
@@ -0,0 +1,117 @@
+//! Passwords from `~/.netrc`.
+//!
+//! WebDAV servers are logged in to with the password recorded for their host
+//! in the user's `.netrc` (`_netrc` on Windows), the file `curl` and `ftp`
+//! read, so passwords never show in a pane's path.
+
+use std::path::PathBuf;
+
+/// A login recorded in `.netrc`.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct NetrcLogin {
+    /// Host the login is for, or `None` for the `default` entry.
+    pub machine: Option<String>,
+    /// User name.
+    pub login: String,
+    /// Password.
+    pub password: String,
+}
+
+impl std::fmt::Debug for NetrcLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NetrcLogin")
+            .field("machine", &self.machine)
+            .field("login", &self.login)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The logins in the user's `.netrc`, or none if there is no such file.
+pub(crate) fn read_netrc() -> Vec<NetrcLogin> {
+    netrc_file()
+        .and_then(|file| std::fs::read_to_string(file).ok())
+        .map(|text| parse_netrc(&text))
+        .unwrap_or_default()
+}
+
+/// The login for `user` on `host`: the entry for the host, else the
+/// `default` entry. With no `user`, the first entry that matches is used.
+pub(crate) fn find_login<'a>(
+    logins: &'a [NetrcLogin],
+    host: &str,
+    user: Option<&str>,
+) -> Option<&'a NetrcLogin> {
+    let usable = |login: &&NetrcLogin| user.is_none_or(|user| login.login == user);
+    logins
+        .iter()
+        .filter(usable)
+        .find(|login| login.machine.as_deref() == Some(host))
+        .or_else(|| logins.iter().filter(usable).find(|login| login.machine.is_none()))
+}
+
+/// Parse the entries of a `.netrc` file. Macro definitions end the parse,
+/// since only logins are of use.
+fn parse_netrc(text: &str) -> Vec<NetrcLogin> {
+    let mut logins = Vec::new();
+    let mut current: Option<NetrcLogin> = None;
+    let mut tokens = text.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" | "default" => {
+                logins.extend(current.take());
+                let machine = if token == "machine" {
+                    tokens.next().map(str::to_string)
+                } else {
+                    None
+                };
+                current = Some(NetrcLogin {
+                    machine,
+                    login: String::new(),
+                    password: String::new(),
+                });
+            }
+            "login" | "password" => {
+                let value = tokens.next().unwrap_or_default().to_string();
+                if let Some(entry) = current.as_mut() {
+                    if token == "login" {
+                        entry.login = value;
+                    } else {
+                        entry.password = value;
+                    }
+                }
+            }
+            "macdef" => break,
+            _ => {}
+        }
+    }
+    logins.extend(current);
+    logins
+}
+
+/// Where the user's `.netrc` is.
+fn netrc_file() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "_netrc" } else { ".netrc" };
+    dirs::home_dir().map(|home| home.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_find_logins() {
+        let logins = parse_netrc(
+            "machine nas login alice password s3cret\n\
+             machine nas\n  login bob\n  password hunter2\n\
+             default login guest password guest\n\
+             macdef init\ncd /tmp\n",
+        );
+        assert_eq!(logins.len(), 3);
+
+        let login = |host, user| find_login(&logins, host, user).map(|l| l.password.as_str());
+        assert_eq!(login("nas", None), Some("s3cret"));
+        assert_eq!(login("nas", Some("bob")), Some("hunter2"));
+        assert_eq!(login("other", None), Some("guest"));
+        assert_eq!(login("other", Some("carol")), None);
+    }
+}
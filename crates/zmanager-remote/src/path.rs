@@ -0,0 +1,455 @@
+//! Virtual paths of remote files.
+//!
+//! A pane shows a directory on a server at `sftp://user@host:port/path`,
+//! the way it shows folders inside archives at their virtual paths. The
+//! user and port are optional. Paths are absolute on the server, except
+//! that a first component of `~` stands for the login directory.
+//!
+//! WebDAV folders are shown the same way at `dav://user@host:port/path`,
+//! or at `davs://...` for servers reached over HTTPS.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// Prefix of remote paths.
+pub const SFTP_PATH_PREFIX: &str = "sftp://";
+
+/// Prefix of paths on WebDAV servers reached over HTTP.
+pub const DAV_PATH_PREFIX: &str = "dav://";
+
+/// Prefix of paths on WebDAV servers reached over HTTPS.
+pub const DAVS_PATH_PREFIX: &str = "davs://";
+
+/// Characters escaped in each component of a WebDAV URL.
+const URL_COMPONENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Port SSH servers listen on unless told otherwise.
+pub const DEFAULT_SSH_PORT: u16 = 22;
+
+/// A server to log in to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemoteHost {
+    /// User to log in as, or `None` for the local user's name.
+    pub user: Option<String>,
+    /// Host name or address.
+    pub host: String,
+    /// SSH port.
+    pub port: u16,
+}
+
+impl RemoteHost {
+    /// The user to log in as: the one in the path, or else the local user.
+    pub fn login(&self) -> String {
+        self.user
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for RemoteHost {
+    /// Formats as `user@host:port`, leaving out what the path left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let port = Some(self.port).filter(|&port| port != DEFAULT_SSH_PORT);
+        write_authority(f, self.user.as_deref(), &self.host, port)
+    }
+}
+
+/// A file or directory on a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemotePath {
+    /// The server.
+    pub host: RemoteHost,
+    /// Path on the server, `/`-separated: absolute, or relative to the login
+    /// directory. Never empty; `.` is the login directory itself.
+    pub path: String,
+}
+
+impl RemotePath {
+    /// The path on the server, as the SFTP calls take it.
+    pub fn server_path(&self) -> &Path {
+        Path::new(&self.path)
+    }
+
+    /// The virtual path a pane shows this at.
+    pub fn to_path_buf(&self) -> PathBuf {
+        let path = match self.path.as_str() {
+            "/" => "",
+            "." => "/~",
+            path if path.starts_with('/') => path,
+            path => return PathBuf::from(format!("{SFTP_PATH_PREFIX}{}/~/{path}", self.host)),
+        };
+        PathBuf::from(format!("{SFTP_PATH_PREFIX}{}{path}", self.host))
+    }
+
+    /// The path of the entry `name` in this directory.
+    pub fn join(&self, name: &str) -> Self {
+        let path = match self.path.as_str() {
+            "." => name.to_string(),
+            parent => format!("{}/{name}", parent.trim_end_matches('/')),
+        };
+        Self {
+            host: self.host.clone(),
+            path,
+        }
+    }
+
+    /// The directory this is in, or `None` for `/` and the login directory.
+    pub fn parent(&self) -> Option<Self> {
+        let path = match self.path.rsplit_once('/') {
+            Some(("", _)) if self.path != "/" => "/",
+            Some((parent, _)) if !parent.is_empty() => parent,
+            None if self.path != "." => ".",
+            _ => return None,
+        };
+        Some(Self {
+            host: self.host.clone(),
+            path: path.to_string(),
+        })
+    }
+}
+
+/// A WebDAV server to connect to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DavServer {
+    /// Whether the server is reached over HTTPS.
+    pub secure: bool,
+    /// User to log in as, or `None` to log in as the `~/.netrc` entry for
+    /// the host says, or not at all.
+    pub user: Option<String>,
+    /// Host name or address.
+    pub host: String,
+    /// Port, or `None` for the one of the protocol.
+    pub port: Option<u16>,
+}
+
+impl DavServer {
+    /// The URL of the server, without a path.
+    pub fn url(&self) -> String {
+        let scheme = if self.secure { "https" } else { "http" };
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match self.port {
+            Some(port) => format!("{scheme}://{host}:{port}"),
+            None => format!("{scheme}://{host}"),
+        }
+    }
+}
+
+impl fmt::Display for DavServer {
+    /// Formats as `user@host:port`, leaving out what the path left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_authority(f, self.user.as_deref(), &self.host, self.port)
+    }
+}
+
+/// A file or folder on a WebDAV server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DavPath {
+    /// The server.
+    pub server: DavServer,
+    /// Absolute path on the server, `/`-separated and unescaped.
+    pub path: String,
+}
+
+impl DavPath {
+    /// The virtual path a pane shows this at.
+    pub fn to_path_buf(&self) -> PathBuf {
+        let prefix = if self.server.secure {
+            DAVS_PATH_PREFIX
+        } else {
+            DAV_PATH_PREFIX
+        };
+        let path = if self.path == "/" { "" } else { &self.path };
+        PathBuf::from(format!("{prefix}{}{path}", self.server))
+    }
+
+    /// The URL of this on the server.
+    pub fn url(&self) -> String {
+        let path: Vec<String> = self
+            .path
+            .split('/')
+            .map(|part| utf8_percent_encode(part, URL_COMPONENT).to_string())
+            .collect();
+        format!("{}{}", self.server.url(), path.join("/"))
+    }
+
+    /// The last component of the path, or `None` for `/`.
+    pub fn name(&self) -> Option<&str> {
+        self.path.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// The path of the entry `name` in this folder.
+    pub fn join(&self, name: &str) -> Self {
+        Self {
+            server: self.server.clone(),
+            path: format!("{}/{name}", self.path.trim_end_matches('/')),
+        }
+    }
+
+    /// The folder this is in, or `None` for `/`.
+    pub fn parent(&self) -> Option<Self> {
+        let (parent, _) = self.path.rsplit_once('/').filter(|_| self.path != "/")?;
+        Some(Self {
+            server: self.server.clone(),
+            path: if parent.is_empty() { "/" } else { parent }.to_string(),
+        })
+    }
+}
+
+/// The virtual path of `path` on `host`.
+pub fn remote_path(host: &RemoteHost, path: &str) -> PathBuf {
+    RemotePath {
+        host: host.clone(),
+        path: normalize(path),
+    }
+    .to_path_buf()
+}
+
+/// The server and remote path of a virtual remote path, or `None` for any
+/// other path.
+///
+/// Both separators are accepted, since paths joined on Windows pick up
+/// backslashes, and `.` and `..` components are resolved.
+pub fn split_remote_path(path: &Path) -> Option<RemotePath> {
+    let rest = path.to_str()?.strip_prefix(SFTP_PATH_PREFIX)?;
+    let (authority, path) = rest.split_at(rest.find(['/', '\\']).unwrap_or(rest.len()));
+    Some(RemotePath {
+        host: parse_host(authority)?,
+        path: normalize(path),
+    })
+}
+
+/// The server and path of a virtual WebDAV path, or `None` for any other
+/// path.
+///
+/// As with [`split_remote_path`], both separators are accepted and `.` and
+/// `..` components are resolved.
+pub fn split_dav_path(path: &Path) -> Option<DavPath> {
+    let path = path.to_str()?;
+    let (secure, rest) = match path.strip_prefix(DAVS_PATH_PREFIX) {
+        Some(rest) => (true, rest),
+        None => (false, path.strip_prefix(DAV_PATH_PREFIX)?),
+    };
+    let (authority, path) = rest.split_at(rest.find(['/', '\\']).unwrap_or(rest.len()));
+    let (user, host, port) = parse_authority(authority)?;
+    Some(DavPath {
+        server: DavServer {
+            secure,
+            user,
+            host,
+            port,
+        },
+        path: format!("/{}", resolve_components(path).join("/")),
+    })
+}
+
+/// Parse `user@host:port` for an SFTP server.
+fn parse_host(authority: &str) -> Option<RemoteHost> {
+    let (user, host, port) = parse_authority(authority)?;
+    Some(RemoteHost {
+        user,
+        host,
+        port: port.unwrap_or(DEFAULT_SSH_PORT),
+    })
+}
+
+/// Parse `user@host:port` into its parts, where the user and port are
+/// optional and an IPv6 address is bracketed.
+fn parse_authority(authority: &str) -> Option<(Option<String>, String, Option<u16>)> {
+    let (user, host_port) = match authority.rsplit_once('@') {
+        Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+        Some(_) => return None,
+        None => (None, authority),
+    };
+    let (host, port) = match host_port.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after) = bracketed.split_once(']')?;
+            (host, after.strip_prefix(':'))
+        }
+        None => match host_port.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        },
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => Some(port.parse().ok()?),
+        None => None,
+    };
+    Some((user, host.to_string(), port))
+}
+
+/// Write `user@host:port`, bracketing an IPv6 address.
+fn write_authority(
+    f: &mut fmt::Formatter<'_>,
+    user: Option<&str>,
+    host: &str,
+    port: Option<u16>,
+) -> fmt::Result {
+    if let Some(user) = user {
+        write!(f, "{user}@")?;
+    }
+    if host.contains(':') {
+        write!(f, "[{host}]")?;
+    } else {
+        write!(f, "{host}")?;
+    }
+    if let Some(port) = port {
+        write!(f, ":{port}")?;
+    }
+    Ok(())
+}
+
+/// Resolve the components of a path after the host: `/`-separated, with `.`
+/// and `..` applied, absolute unless it starts at `~`.
+fn normalize(path: &str) -> String {
+    let (home, rest) = match path.trim_start_matches(['/', '\\']).strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => (true, rest),
+        _ => (false, path),
+    };
+    let resolved = resolve_components(rest);
+
+    match (home, resolved.is_empty()) {
+        (true, true) => ".".to_string(),
+        (true, false) => resolved.join("/"),
+        (false, _) => format!("/{}", resolved.join("/")),
+    }
+}
+
+/// The components of `path`, split at either separator, with empty and `.`
+/// components dropped and `..` applied.
+pub(crate) fn resolve_components(path: &str) -> Vec<&str> {
+    let mut resolved: Vec<&str> = Vec::new();
+    for part in path.split(['/', '\\']).filter(|part| !part.is_empty() && *part != ".") {
+        if part == ".." {
+            resolved.pop();
+        } else {
+            resolved.push(part);
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(user: Option<&str>, host: &str, port: u16) -> RemoteHost {
+        RemoteHost {
+            user: user.map(str::to_string),
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn test_split_remote_path() {
+        let path = Path::new("sftp://alice@example.com:2222/srv/data");
+        let remote = split_remote_path(path).unwrap();
+        assert_eq!(remote.host, host(Some("alice"), "example.com", 2222));
+        assert_eq!(remote.path, "/srv/data");
+
+        let remote = split_remote_path(Path::new("sftp://example.com")).unwrap();
+        assert_eq!(remote.host, host(None, "example.com", DEFAULT_SSH_PORT));
+        assert_eq!(remote.path, "/");
+
+        let remote = split_remote_path(Path::new("sftp://[::1]:22/tmp")).unwrap();
+        assert_eq!(remote.host, host(None, "::1", 22));
+
+        // Backslashes from joins on Windows, dots and the login directory
+        let remote = split_remote_path(Path::new("sftp://h/~/a/./b/../c\\d.txt")).unwrap();
+        assert_eq!(remote.path, "a/c/d.txt");
+        assert_eq!(split_remote_path(Path::new("sftp://h/~")).unwrap().path, ".");
+
+        assert!(split_remote_path(Path::new("/srv/data")).is_none());
+        assert!(split_remote_path(Path::new("sftp://")).is_none());
+        assert!(split_remote_path(Path::new("sftp://h:port/")).is_none());
+        assert!(split_remote_path(Path::new("sftp://@h/")).is_none());
+    }
+
+    #[test]
+    fn test_remote_path_round_trip() {
+        for path in [
+            "sftp://alice@example.com:2222/srv/data",
+            "sftp://example.com",
+            "sftp://[::1]:2200/tmp",
+            "sftp://h/~",
+            "sftp://h/~/notes.txt",
+        ] {
+            let remote = split_remote_path(Path::new(path)).unwrap();
+            assert_eq!(remote.to_path_buf(), PathBuf::from(path));
+        }
+        assert_eq!(
+            remote_path(&host(None, "h", 22), "/var//log/"),
+            PathBuf::from("sftp://h/var/log")
+        );
+    }
+
+    #[test]
+    fn test_join_and_parent() {
+        let root = split_remote_path(Path::new("sftp://h")).unwrap();
+        let file = root.join("etc").join("hosts");
+        assert_eq!(file.path, "/etc/hosts");
+        assert_eq!(file.parent().unwrap().path, "/etc");
+        assert_eq!(file.parent().unwrap().parent().unwrap(), root);
+        assert!(root.parent().is_none());
+
+        let home = split_remote_path(Path::new("sftp://h/~")).unwrap();
+        let notes = home.join("notes.txt");
+        assert_eq!(notes.path, "notes.txt");
+        assert_eq!(notes.parent().unwrap(), home);
+        assert!(home.parent().is_none());
+    }
+
+    #[test]
+    fn test_split_dav_path() {
+        let path = Path::new("davs://bob@cloud.example.com:8443/Files/./a\\..");
+        let dav = split_dav_path(path).unwrap();
+        assert!(dav.server.secure);
+        assert_eq!(dav.server.user.as_deref(), Some("bob"));
+        assert_eq!(dav.server.port, Some(8443));
+        assert_eq!(dav.path, "/Files");
+        assert_eq!(dav.to_path_buf(), PathBuf::from("davs://bob@cloud.example.com:8443/Files"));
+
+        let root = split_dav_path(Path::new("dav://nas")).unwrap();
+        assert!(!root.server.secure);
+        assert_eq!(root.path, "/");
+        assert_eq!(root.to_path_buf(), PathBuf::from("dav://nas"));
+        assert!(root.parent().is_none());
+
+        // `~` means nothing special to WebDAV
+        assert_eq!(split_dav_path(Path::new("dav://nas/~")).unwrap().path, "/~");
+        assert!(split_dav_path(Path::new("sftp://nas/srv")).is_none());
+        assert!(split_remote_path(Path::new("dav://nas/srv")).is_none());
+    }
+
+    #[test]
+    fn test_dav_urls() {
+        let dir = split_dav_path(Path::new("dav://[::1]:8080/My Files")).unwrap();
+        let file = dir.join("50% off #1.txt");
+        assert_eq!(file.url(), "http://[::1]:8080/My%20Files/50%25%20off%20%231.txt");
+        assert_eq!(file.name(), Some("50% off #1.txt"));
+        assert_eq!(file.parent().unwrap(), dir);
+        assert_eq!(dir.parent().unwrap().url(), "http://[::1]:8080/");
+    }
+}
@@ -0,0 +1,108 @@
+//! Every kind of server a pane can browse.
+
+use std::path::{Path, PathBuf};
+
+use zmanager_core::{DirectoryProvider, EntryMeta, ZError, ZResult};
+
+use crate::filesystem::SftpFileSystem;
+use crate::path::{split_dav_path, split_remote_path, DavPath, RemotePath};
+use crate::webdav::WebDavFileSystem;
+
+/// Where a path leads: to a server, and over which protocol, or to this
+/// computer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Location {
+    Sftp(RemotePath),
+    WebDav(DavPath),
+    Local,
+}
+
+impl Location {
+    /// Where `path` leads.
+    pub(crate) fn of(path: &Path) -> Self {
+        if let Some(remote) = split_remote_path(path) {
+            Self::Sftp(remote)
+        } else if let Some(remote) = split_dav_path(path) {
+            Self::WebDav(remote)
+        } else {
+            Self::Local
+        }
+    }
+}
+
+/// The SFTP and WebDAV servers panes browse, as one [`DirectoryProvider`]
+/// for every virtual remote path.
+#[derive(Debug, Default)]
+pub struct RemoteFileSystems {
+    sftp: SftpFileSystem,
+    webdav: WebDavFileSystem,
+}
+
+impl RemoteFileSystems {
+    /// Create file systems with no servers connected yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Servers at `sftp://` paths.
+    pub fn sftp(&self) -> &SftpFileSystem {
+        &self.sftp
+    }
+
+    /// Servers at `dav://` and `davs://` paths.
+    pub fn webdav(&self) -> &WebDavFileSystem {
+        &self.webdav
+    }
+}
+
+impl DirectoryProvider for RemoteFileSystems {
+    fn handles(&self, path: &Path) -> bool {
+        is_remote_path(path)
+    }
+
+    fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>> {
+        match Location::of(path) {
+            Location::Sftp(remote) => self.sftp.list_dir(&remote),
+            Location::WebDav(remote) => self.webdav.list_dir(&remote),
+            Location::Local => Err(ZError::InvalidPath {
+                path: path.to_path_buf(),
+                reason: "Not a path on a server".to_string(),
+            }),
+        }
+    }
+}
+
+/// Check if `path` is a virtual path on a server, of any protocol.
+pub fn is_remote_path(path: &Path) -> bool {
+    Location::of(path) != Location::Local
+}
+
+/// `path` in the form panes show it, with `.` and `..` resolved, or `None`
+/// if it isn't on a server.
+pub fn canonical_remote_path(path: &Path) -> Option<PathBuf> {
+    match Location::of(path) {
+        Location::Sftp(remote) => Some(remote.to_path_buf()),
+        Location::WebDav(remote) => Some(remote.to_path_buf()),
+        Location::Local => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_paths() {
+        for (path, canonical) in [
+            ("sftp://h/srv/../data/", "sftp://h/data"),
+            ("dav://nas:8080/a/./b", "dav://nas:8080/a/b"),
+            ("davs://cloud/", "davs://cloud"),
+        ] {
+            assert!(is_remote_path(Path::new(path)));
+            assert_eq!(canonical_remote_path(Path::new(path)), Some(PathBuf::from(canonical)));
+        }
+        assert!(!is_remote_path(Path::new("/srv/data")));
+        assert!(canonical_remote_path(Path::new("C:\\data")).is_none());
+        assert!(RemoteFileSystems::new().list(Path::new("/srv")).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! SSH connections to servers.
+//!
+//! [`SftpSessions`] logs in to each server once and keeps the SFTP channel
+//! open for the listings and transfers that follow. Logging in tries the
+//! SSH agent first, then the usual key files in `~/.ssh` that have no
+//! passphrase. Host keys are checked against `~/.ssh/known_hosts`: a key
+//! that differs from the one recorded is refused, while a server that isn't
+//! recorded yet is let through with a warning.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ssh2::{CheckResult, ErrorCode, KnownHostFileKind, Session, Sftp};
+use tracing::{debug, info, warn};
+use zmanager_core::{ZError, ZResult};
+
+use crate::path::RemoteHost;
+
+/// Milliseconds a blocking SSH call may take before failing.
+const TIMEOUT_MS: u32 = 30_000;
+
+/// Private key files tried after the agent, in `~/.ssh`.
+const KEY_FILES: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Open SFTP channels, one per server.
+#[derive(Default)]
+pub struct SftpSessions {
+    open: Mutex<HashMap<RemoteHost, Arc<Sftp>>>,
+}
+
+impl SftpSessions {
+    /// Create an empty set of sessions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` on the SFTP channel to `host`, logging in first if there is
+    /// none. `path` names what `f` works on, in errors.
+    ///
+    /// A channel whose connection failed is dropped, so the next call logs
+    /// in again.
+    pub fn with<T>(
+        &self,
+        host: &RemoteHost,
+        path: &Path,
+        f: impl FnOnce(&Sftp) -> Result<T, ssh2::Error>,
+    ) -> ZResult<T> {
+        let sftp = self.channel(host, path)?;
+        f(&sftp).map_err(|e| {
+            if !matches!(e.code(), ErrorCode::SFTP(_)) {
+                warn!(host = %host, error = %e, "SSH connection failed, dropping it");
+                self.close(host);
+            }
+            sftp_error(path, e)
+        })
+    }
+
+    /// Close the channel to `host`, if one is open.
+    pub fn close(&self, host: &RemoteHost) {
+        if let Ok(mut open) = self.open.lock() {
+            open.remove(host);
+        }
+    }
+
+    /// The open channel to `host`, or a new one.
+    fn channel(&self, host: &RemoteHost, path: &Path) -> ZResult<Arc<Sftp>> {
+        let mut open = self.open.lock().map_err(|_| ZError::Internal {
+            message: "SFTP session lock poisoned".to_string(),
+        })?;
+        if let Some(sftp) = open.get(host) {
+            return Ok(Arc::clone(sftp));
+        }
+        let sftp = Arc::new(connect(host, path)?);
+        open.insert(host.clone(), Arc::clone(&sftp));
+        Ok(sftp)
+    }
+}
+
+impl std::fmt::Debug for SftpSessions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hosts: Vec<String> = self
+            .open
+            .lock()
+            .map(|open| open.keys().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        f.debug_struct("SftpSessions").field("open", &hosts).finish()
+    }
+}
+
+/// Connect to `host`, check its key, log in and open an SFTP channel.
+fn connect(host: &RemoteHost, path: &Path) -> ZResult<Sftp> {
+    debug!(host = %host, "Connecting over SSH");
+    let tcp = TcpStream::connect((host.host.as_str(), host.port))
+        .map_err(|e| ZError::io(path, e))?;
+    let mut session = Session::new().map_err(|e| sftp_error(path, e))?;
+    session.set_timeout(TIMEOUT_MS);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| sftp_error(path, e))?;
+
+    check_host_key(&session, host, path)?;
+
+    let user = host.login();
+    authenticate(&session, &user);
+    if !session.authenticated() {
+        return Err(ZError::io(
+            path,
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("no SSH agent identity or key file in ~/.ssh logs in as {user}"),
+            ),
+        ));
+    }
+
+    let sftp = session.sftp().map_err(|e| sftp_error(path, e))?;
+    info!(host = %host, user, "Opened SFTP session");
+    Ok(sftp)
+}
+
+/// Refuse a server whose key differs from the one in `~/.ssh/known_hosts`.
+fn check_host_key(session: &Session, host: &RemoteHost, path: &Path) -> ZResult<()> {
+    let Some((key, _)) = session.host_key() else {
+        return Err(ZError::io(path, io::Error::other("the server sent no host key")));
+    };
+    let mut known = session.known_hosts().map_err(|e| sftp_error(path, e))?;
+    let file = ssh_dir().map(|dir| dir.join("known_hosts"));
+    if let Some(file) = file.filter(|file| file.is_file()) {
+        if let Err(e) = known.read_file(&file, KnownHostFileKind::OpenSSH) {
+            warn!(file = %file.display(), error = %e, "Cannot read known hosts");
+        }
+    }
+
+    match known.check_port(&host.host, host.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(ZError::io(
+            path,
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("the host key of {host} differs from the one in known_hosts"),
+            ),
+        )),
+        CheckResult::NotFound | CheckResult::Failure => {
+            warn!(host = %host, "Host key not in known_hosts, connecting anyway");
+            Ok(())
+        }
+    }
+}
+
+/// Try the SSH agent, then each key file without a passphrase, until one
+/// logs in.
+fn authenticate(session: &Session, user: &str) {
+    if session.userauth_agent(user).is_ok() {
+        return;
+    }
+    let Some(dir) = ssh_dir() else {
+        return;
+    };
+    for name in KEY_FILES {
+        let key = dir.join(name);
+        if key.is_file() && session.userauth_pubkey_file(user, None, &key, None).is_ok() {
+            debug!(key = %key.display(), "Logged in with key file");
+            return;
+        }
+    }
+}
+
+/// The user's `~/.ssh` directory.
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh"))
+}
+
+/// Turn an SSH or SFTP error about `path` into a `ZError`.
+pub(crate) fn sftp_error(path: &Path, e: ssh2::Error) -> ZError {
+    ZError::from_io(path, e.into())
+}
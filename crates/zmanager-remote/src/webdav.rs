@@ -0,0 +1,587 @@
+//! Files and folders on WebDAV servers.
+//!
+//! Folders are listed with `PROPFIND` one level at a time, since many
+//! servers refuse listings of any depth. Files are read with `GET` and
+//! written with `PUT`, streaming in both directions.
+
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::OnceLock;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use tracing::debug;
+use zmanager_core::{DirectoryProvider, EntryKind, EntryMeta, ZError, ZResult};
+
+use crate::netrc::{find_login, read_netrc, NetrcLogin};
+use crate::path::{resolve_components, split_dav_path, DavPath, DavServer};
+
+/// Time a request may wait on the server before failing.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Chunks of an upload buffered ahead of the network.
+const UPLOAD_CHUNKS: usize = 4;
+
+/// The namespace of WebDAV elements.
+const DAV_NS: &str = "DAV:";
+
+/// Body of `PROPFIND` requests, asking for what listings show.
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:propfind xmlns:D="DAV:">
+  <D:prop><D:resourcetype/><D:getcontentlength/><D:getlastmodified/></D:prop>
+</D:propfind>"#;
+
+/// Folders and files on any number of WebDAV servers, reached by their
+/// virtual `dav://` and `davs://` paths.
+///
+/// Lists folders for the panes as a [`DirectoryProvider`], and does the
+/// file work for [`RemoteBackend`](crate::RemoteBackend). All calls block
+/// on the network.
+pub struct WebDavFileSystem {
+    agent: ureq::Agent,
+    logins: OnceLock<Vec<NetrcLogin>>,
+}
+
+impl WebDavFileSystem {
+    /// Create a file system, reading `~/.netrc` on first use.
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(TIMEOUT)
+                .timeout_read(TIMEOUT)
+                .timeout_write(TIMEOUT)
+                .build(),
+            logins: OnceLock::new(),
+        }
+    }
+
+    /// List the folder at `remote`.
+    pub fn list_dir(&self, remote: &DavPath) -> ZResult<Vec<EntryMeta>> {
+        let path = remote.to_path_buf();
+        debug!(path = %path.display(), "Listing WebDAV folder");
+        let mut entries = self.propfind(remote, "1")?;
+        // The folder answers for itself too
+        entries.retain(|entry| entry.path != path);
+        Ok(entries)
+    }
+
+    /// Look up `remote`.
+    pub fn entry(&self, remote: &DavPath) -> ZResult<EntryMeta> {
+        self.propfind(remote, "0")?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ZError::NotFound {
+                path: remote.to_path_buf(),
+            })
+    }
+
+    /// List `remote` and, for a folder, everything below it, each folder
+    /// before its contents.
+    pub fn walk(&self, remote: &DavPath) -> ZResult<Vec<EntryMeta>> {
+        let root = self.entry(remote)?;
+        let is_dir = root.is_directory();
+        let mut entries = vec![root];
+        if !is_dir {
+            return Ok(entries);
+        }
+
+        let mut pending = vec![remote.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in self.list_dir(&dir)? {
+                if entry.is_directory() {
+                    pending.push(dir.join(&entry.name));
+                }
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Create the folder at `remote` and any missing parents.
+    pub fn create_dir_all(&self, remote: &DavPath) -> ZResult<()> {
+        let mut missing = Vec::new();
+        let mut dir = Some(remote.clone());
+        while let Some(current) = dir {
+            match self.entry(&current) {
+                Ok(entry) if entry.is_directory() => break,
+                Ok(_) => {
+                    return Err(ZError::NotADirectory {
+                        path: current.to_path_buf(),
+                    });
+                }
+                Err(e) if e.is_not_found() => {
+                    dir = current.parent();
+                    missing.push(current);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        for dir in missing.iter().rev() {
+            self.request("MKCOL", dir)
+                .call()
+                .map_err(|e| http_error(&dir.to_path_buf(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Delete the file or folder at `remote`. Servers delete folders with
+    /// everything in them.
+    pub fn remove(&self, remote: &DavPath) -> ZResult<()> {
+        self.request("DELETE", remote)
+            .call()
+            .map_err(|e| http_error(&remote.to_path_buf(), e))?;
+        Ok(())
+    }
+
+    /// Rename `from` to `to` on the same server. Fails if `to` exists.
+    pub fn rename(&self, from: &DavPath, to: &DavPath) -> ZResult<()> {
+        let path = from.to_path_buf();
+        if from.server != to.server {
+            return Err(ZError::InvalidOperation {
+                operation: format!("rename {}", path.display()),
+                reason: "Files can only be renamed on the same server".to_string(),
+            });
+        }
+        let result = self
+            .request("MOVE", from)
+            .set("Destination", &to.url())
+            .set("Overwrite", "F")
+            .call();
+        match result {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(412, _)) => Err(ZError::AlreadyExists {
+                path: to.to_path_buf(),
+            }),
+            Err(e) => Err(http_error(&path, e)),
+        }
+    }
+
+    /// Open the file at `remote` for reading.
+    pub fn open(&self, remote: &DavPath) -> ZResult<Box<dyn Read + Send>> {
+        let response = self
+            .request("GET", remote)
+            .call()
+            .map_err(|e| http_error(&remote.to_path_buf(), e))?;
+        Ok(Box::new(response.into_reader()))
+    }
+
+    /// Create or replace the file at `remote`, uploading what is written to
+    /// it as it is written.
+    pub fn create(&self, remote: &DavPath) -> ZResult<DavUpload> {
+        let path = remote.to_path_buf();
+        let request = self.request("PUT", remote);
+        let (sender, chunks) = mpsc::sync_channel(UPLOAD_CHUNKS);
+        let reader = ChannelReader {
+            chunks,
+            chunk: Vec::new(),
+            position: 0,
+            ended: false,
+        };
+        let request_path = path.clone();
+        let request = std::thread::Builder::new()
+            .name("webdav-upload".to_string())
+            .spawn(move || {
+                request
+                    .send(reader)
+                    .map(|_| ())
+                    .map_err(|e| http_error(&request_path, e))
+            })
+            .map_err(|e| ZError::io(&path, e))?;
+        Ok(DavUpload {
+            path,
+            chunks: Some(sender),
+            request: Some(request),
+        })
+    }
+
+    /// `PROPFIND` `remote` to `depth`.
+    fn propfind(&self, remote: &DavPath, depth: &str) -> ZResult<Vec<EntryMeta>> {
+        let path = remote.to_path_buf();
+        let response = self
+            .request("PROPFIND", remote)
+            .set("Depth", depth)
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)
+            .map_err(|e| http_error(&path, e))?;
+        let body = response.into_string().map_err(|e| ZError::io(&path, e))?;
+        parse_multistatus(&body, &remote.server, &path)
+    }
+
+    /// A request for `remote`, logged in if `~/.netrc` has a password for
+    /// its server or the path names a user.
+    fn request(&self, method: &str, remote: &DavPath) -> ureq::Request {
+        let request = self.agent.request(method, &remote.url());
+        match self.credentials(&remote.server) {
+            Some((user, password)) => {
+                let token = STANDARD.encode(format!("{user}:{password}"));
+                request.set("Authorization", &format!("Basic {token}"))
+            }
+            None => request,
+        }
+    }
+
+    /// The user and password to log in to `server` with.
+    fn credentials(&self, server: &DavServer) -> Option<(String, String)> {
+        let logins = self.logins.get_or_init(read_netrc);
+        find_login(logins, &server.host, server.user.as_deref())
+            .map(|login| (login.login.clone(), login.password.clone()))
+            .or_else(|| server.user.clone().map(|user| (user, String::new())))
+    }
+}
+
+impl Default for WebDavFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for WebDavFileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebDavFileSystem").finish_non_exhaustive()
+    }
+}
+
+impl DirectoryProvider for WebDavFileSystem {
+    fn handles(&self, path: &Path) -> bool {
+        split_dav_path(path).is_some()
+    }
+
+    fn list(&self, path: &Path) -> ZResult<Vec<EntryMeta>> {
+        let remote = split_dav_path(path).ok_or_else(|| ZError::InvalidPath {
+            path: path.to_path_buf(),
+            reason: "Not a dav:// path".to_string(),
+        })?;
+        self.list_dir(&remote)
+    }
+}
+
+/// A file being uploaded to a WebDAV server.
+///
+/// What is written goes to the server as it is written. The upload only
+/// completes with [`finish`](Self::finish); dropping it first abandons the
+/// upload.
+pub struct DavUpload {
+    path: PathBuf,
+    chunks: Option<SyncSender<Vec<u8>>>,
+    request: Option<JoinHandle<ZResult<()>>>,
+}
+
+impl DavUpload {
+    /// End the upload and wait for the server to take the file.
+    pub fn finish(mut self) -> ZResult<()> {
+        if let Some(chunks) = self.chunks.take() {
+            // An empty chunk marks the end, as opposed to a dropped upload
+            let _ = chunks.send(Vec::new());
+        }
+        match self.request.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(ZError::Internal {
+                message: format!("Upload of '{}' panicked", self.path.display()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for DavUpload {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let sent = self
+            .chunks
+            .as_ref()
+            .is_some_and(|chunks| chunks.send(buf.to_vec()).is_ok());
+        if !sent {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the server stopped taking the upload",
+            ));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for DavUpload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DavUpload").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+/// The body of an upload, read from the chunks written to its [`DavUpload`].
+struct ChannelReader {
+    chunks: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+    ended: bool,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            if self.ended {
+                return Ok(0);
+            }
+            match self.chunks.recv() {
+                Ok(chunk) if chunk.is_empty() => self.ended = true,
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                Err(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "the upload was abandoned",
+                    ));
+                }
+            }
+        }
+        let count = buf.len().min(self.chunk.len() - self.position);
+        buf[..count].copy_from_slice(&self.chunk[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// The entries of a `PROPFIND` answer from `server`. `path` names what was
+/// asked for, in errors.
+fn parse_multistatus(xml: &str, server: &DavServer, path: &Path) -> ZResult<Vec<EntryMeta>> {
+    let document = roxmltree::Document::parse(xml).map_err(|e| ZError::InvalidPath {
+        path: path.to_path_buf(),
+        reason: format!("The server's listing can't be read: {e}"),
+    })?;
+
+    let mut entries = Vec::new();
+    for response in document
+        .descendants()
+        .filter(|node| node.has_tag_name((DAV_NS, "response")))
+    {
+        let Some(href) = child_text(response, "href").and_then(href_path) else {
+            continue;
+        };
+        let remote = DavPath {
+            server: server.clone(),
+            path: href,
+        };
+        let name = remote
+            .name()
+            .map_or_else(|| server.to_string(), str::to_string);
+
+        let found = response.children().find(|propstat| {
+            propstat.has_tag_name((DAV_NS, "propstat"))
+                && child_text(*propstat, "status").is_some_and(|status| status.contains(" 200 "))
+        });
+        let prop = found.and_then(|propstat| {
+            propstat
+                .children()
+                .find(|node| node.has_tag_name((DAV_NS, "prop")))
+        });
+        let is_collection = prop.is_some_and(|prop| {
+            prop.descendants()
+                .any(|node| node.has_tag_name((DAV_NS, "collection")))
+        });
+        let kind = if is_collection {
+            EntryKind::Directory
+        } else {
+            EntryKind::File
+        };
+
+        let mut meta = EntryMeta::new(name, remote.to_path_buf(), kind);
+        if let Some(prop) = prop {
+            if kind.is_file() {
+                meta.size = child_text(prop, "getcontentlength")
+                    .and_then(|size| size.trim().parse().ok())
+                    .unwrap_or(0);
+            }
+            meta.modified = child_text(prop, "getlastmodified")
+                .and_then(|time| DateTime::parse_from_rfc2822(time.trim()).ok())
+                .map(|time| time.with_timezone(&Utc));
+        }
+        entries.push(meta);
+    }
+    Ok(entries)
+}
+
+/// The text of the WebDAV element `name` under `node`.
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name((DAV_NS, name)))
+        .and_then(|child| child.text())
+}
+
+/// The unescaped path of an `href`, which servers give as a path or as a
+/// whole URL.
+fn href_path(href: &str) -> Option<String> {
+    let href = href.trim();
+    let path = match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |start| &rest[start..]),
+        None => href,
+    };
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    Some(format!("/{}", resolve_components(&path).join("/")))
+}
+
+/// Turn a failed WebDAV request about `path` into a `ZError`.
+fn http_error(path: &Path, e: ureq::Error) -> ZError {
+    let path = path.to_path_buf();
+    match e {
+        ureq::Error::Status(404 | 410, _) => ZError::NotFound { path },
+        ureq::Error::Status(401 | 403, _) => ZError::PermissionDenied { path },
+        ureq::Error::Status(code, response) => {
+            let message = format!("the server answered {code} {}", response.status_text());
+            ZError::io(path, io::Error::other(message))
+        }
+        ureq::Error::Transport(transport) => {
+            ZError::io(path, io::Error::other(transport.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const LISTING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/remote.php/dav/files/me/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://cloud.example.com/remote.php/dav/files/me/My%20Photos/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getlastmodified>Tue, 03 Mar 2026 10:15:00 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getcontentlength/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/me/notes.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>1234</d:getcontentlength>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let dir = split_dav_path(Path::new("davs://cloud.example.com/remote.php/dav/files/me"))
+            .unwrap();
+        let entries = parse_multistatus(LISTING, &dir.server, Path::new("davs://x")).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, dir.to_path_buf());
+
+        let photos = &entries[1];
+        assert_eq!(photos.name, "My Photos");
+        assert!(photos.is_directory());
+        assert_eq!(photos.path, dir.join("My Photos").to_path_buf());
+        assert_eq!(photos.modified.unwrap().timestamp(), 1_772_532_900);
+
+        let notes = &entries[2];
+        assert_eq!(notes.name, "notes.txt");
+        assert!(!notes.is_directory());
+        assert_eq!(notes.size, 1234);
+        assert!(notes.modified.is_none());
+
+        assert!(parse_multistatus("<not xml", &dir.server, Path::new("davs://x")).is_err());
+    }
+
+    #[test]
+    fn test_upload_chunks_reach_the_reader() {
+        let (sender, chunks) = mpsc::sync_channel(UPLOAD_CHUNKS);
+        let mut reader = ChannelReader {
+            chunks,
+            chunk: Vec::new(),
+            position: 0,
+            ended: false,
+        };
+        let writer = std::thread::spawn(move || {
+            for chunk in ["hello, ", "", "server"] {
+                sender.send(chunk.as_bytes().to_vec()).unwrap();
+            }
+        });
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        writer.join().unwrap();
+        // The empty chunk ends the body
+        assert_eq!(body, "hello, ");
+
+        let (sender, chunks) = mpsc::sync_channel(UPLOAD_CHUNKS);
+        let mut reader = ChannelReader {
+            chunks,
+            chunk: Vec::new(),
+            position: 0,
+            ended: false,
+        };
+        sender.send(b"partial".to_vec()).unwrap();
+        drop(sender);
+        let mut body = Vec::new();
+        assert!(reader.read_to_end(&mut body).is_err());
+    }
+
+    #[test]
+    fn test_upload_streams_to_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            // The chunked body ends with an empty chunk
+            while !request.ends_with(b"0\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let fs = WebDavFileSystem::new();
+        let path = format!("dav://127.0.0.1:{port}/My Files/notes.txt");
+        let mut upload = fs.create(&split_dav_path(Path::new(&path)).unwrap()).unwrap();
+        upload.write_all(b"first line\n").unwrap();
+        upload.write_all(b"second line\n").unwrap();
+        upload.finish().unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("PUT /My%20Files/notes.txt HTTP/1.1"));
+        assert!(request.contains("first line\n"));
+        assert!(request.contains("second line\n"));
+    }
+
+    #[test]
+    fn test_unreachable_server_fails() {
+        let fs = WebDavFileSystem::new();
+        // Nothing listens on port 1
+        let remote = split_dav_path(Path::new("dav://127.0.0.1:1/files")).unwrap();
+        assert!(fs.entry(&remote).is_err());
+        assert!(fs.list(Path::new("dav://127.0.0.1:1/files")).is_err());
+        assert!(fs.list(Path::new("/tmp")).is_err());
+    }
+}
@@ -17,7 +17,8 @@ import {
   ToastProvider,
   useToast,
 } from "./components";
-import { useFileWatcher, useKeyboardShortcuts } from "./hooks";
+import { useFileWatcher, useKeyboardShortcuts, useSession } from "./hooks";
+import { dropFiles } from "./lib/tauri";
 import { type PaneId, useClipboardStore, useFileSystemStore, useUIStore } from "./stores";
 import type { EntryMeta } from "./types";
 
@@ -29,6 +30,9 @@ function AppShell() {
   // Auto-refresh on focus/visibility change
   useFileWatcher();
 
+  // Restore the last session's directories and sort before the panes load
+  const sessionRestored = useSession();
+
   const { cutPaths, paste } = useClipboardStore();
   const { refresh } = useFileSystemStore();
   const { paneMode } = useUIStore();
@@ -66,18 +70,19 @@ function AppShell() {
     async (files: string[], _targetPane: PaneId, targetPath: string) => {
       if (files.length === 0) return;
 
-      // Copy external files to target
-      const { copyPaths, paste: pasteFiles } = useClipboardStore.getState();
+      // Copy or move external files to target, as Explorer would
       const { refresh: refreshPane } = useFileSystemStore.getState();
 
-      const ok = await copyPaths(files);
-      if (ok) {
-        const count = await pasteFiles(targetPath);
-        if (count > 0) {
-          toast.success(`Copied ${count} file${count > 1 ? "s" : ""} from Explorer`);
+      try {
+        const { effect, succeeded } = await dropFiles(files, targetPath);
+        if (succeeded > 0) {
+          const verb = effect === "move" ? "Moved" : "Copied";
+          toast.success(`${verb} ${succeeded} item${succeeded > 1 ? "s" : ""} from Explorer`);
           refreshPane("left");
           refreshPane("right");
         }
+      } catch (err) {
+        toast.error("Drop failed", err instanceof Error ? err.message : "Unknown error");
       }
     },
     [toast]
@@ -94,16 +99,17 @@ function AppShell() {
           {/* Sidebar */}
           <Sidebar />
 
-          {/* File pane view - single or dual */}
+          {/* File pane view - single or dual, once the last session is restored */}
           <main className="flex flex-1 overflow-hidden">
-            {paneMode === "single" ? (
-              <FilePane paneId="left" />
-            ) : (
-              <ResizablePanes
-                leftPane={<FilePane paneId="left" />}
-                rightPane={<FilePane paneId="right" />}
-              />
-            )}
+            {sessionRestored &&
+              (paneMode === "single" ? (
+                <FilePane paneId="left" />
+              ) : (
+                <ResizablePanes
+                  leftPane={<FilePane paneId="left" />}
+                  rightPane={<FilePane paneId="right" />}
+                />
+              ))}
           </main>
         </div>
 
@@ -24,6 +24,7 @@ import {
 } from "@dnd-kit/core";
 import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { createContext, type ReactNode, useCallback, useContext, useEffect, useState } from "react";
+import { dragOut } from "../lib/tauri";
 import type { PaneId } from "../stores";
 import type { EntryMeta } from "../types";
 import { getIconForEntry } from "../utils/iconMappings";
@@ -170,20 +171,8 @@ export function DndProvider({
   // Start external drag (to Explorer)
   const startExternalDrag = useCallback(async (entries: EntryMeta[]) => {
     try {
-      const { startDrag } = await import("@crabnebula/tauri-plugin-drag");
-      const paths = entries.map((e) => e.path);
-      // Use first file's icon as preview (or a generic icon)
-      const iconPath = entries[0]?.path ?? "";
-
-      await startDrag(
-        {
-          item: paths,
-          icon: iconPath,
-        },
-        (_result) => {
-          // Drag completed - result is "Dropped" or "Cancelled"
-        }
-      );
+      // Resolves once the files are dropped (copy or move) or the drag is cancelled
+      await dragOut(entries.map((e) => e.path));
     } catch (_err) {
       // External drag failed - silently ignore
     }
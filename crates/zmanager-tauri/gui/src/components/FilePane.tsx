@@ -15,7 +15,14 @@
 import { useDroppable } from "@dnd-kit/core";
 import clsx from "clsx";
 import { useCallback, useEffect, useState } from "react";
-import { createFile, createFolder, deleteEntries, openFile, renameEntry } from "../lib/tauri";
+import {
+  createFile,
+  createFolder,
+  deleteEntries,
+  openFile,
+  renameEntry,
+  showShellContextMenu,
+} from "../lib/tauri";
 import { type PaneId, useClipboardStore, useFavoritesStore, useFileSystemStore } from "../stores";
 import type { EntryMeta, SortField } from "../types";
 import { AddressBar } from "./AddressBar";
@@ -317,27 +324,33 @@ export function FilePane({ paneId }: { paneId: PaneId }) {
     }
   }, [getSelectedEntries, dialog, refresh, paneId, toast]);
 
+  // Ask for a new name and rename an entry
+  const promptRename = useCallback(
+    async (entry: EntryMeta) => {
+      const newName = await dialog.showRename({
+        currentName: entry.name,
+        isDirectory: entry.kind === "directory",
+      });
+
+      if (!newName) return;
+
+      try {
+        await renameEntry(entry.path, newName);
+        toast.success(`Renamed to "${newName}"`);
+        refresh(paneId);
+      } catch (err) {
+        toast.error("Rename failed", err instanceof Error ? err.message : "Unknown error");
+      }
+    },
+    [dialog, refresh, paneId, toast]
+  );
+
   // Rename entry
   const handleRename = useCallback(async () => {
     const selected = getSelectedEntries();
     if (selected.length !== 1) return;
-
-    const entry = selected[0];
-    const newName = await dialog.showRename({
-      currentName: entry.name,
-      isDirectory: entry.kind === "directory",
-    });
-
-    if (!newName) return;
-
-    try {
-      await renameEntry(entry.path, newName);
-      toast.success(`Renamed to "${newName}"`);
-      refresh(paneId);
-    } catch (err) {
-      toast.error("Rename failed", err instanceof Error ? err.message : "Unknown error");
-    }
-  }, [getSelectedEntries, dialog, refresh, paneId, toast]);
+    await promptRename(selected[0]);
+  }, [getSelectedEntries, promptRename]);
 
   // Create new folder
   const handleNewFolder = useCallback(async () => {
@@ -354,6 +367,23 @@ export function FilePane({ paneId }: { paneId: PaneId }) {
     }
   }, [dialog, path, refresh, paneId, toast]);
 
+  // Show the Windows shell context menu for entries
+  const handleShellMenu = useCallback(
+    async (entries: EntryMeta[], x: number, y: number, extended: boolean) => {
+      try {
+        const result = await showShellContextMenu(entries.map((e) => e.path), x, y, extended);
+        if (result.kind === "invoked") {
+          refresh(paneId);
+        } else if (result.kind === "rename" && entries.length === 1) {
+          await promptRename(entries[0]);
+        }
+      } catch (err) {
+        toast.error("Context menu failed", err instanceof Error ? err.message : "Unknown error");
+      }
+    },
+    [refresh, paneId, promptRename, toast]
+  );
+
   // Show properties panel
   const handleShowProperties = useCallback(() => {
     const selected = getSelectedEntries();
@@ -469,6 +499,12 @@ export function FilePane({ paneId }: { paneId: PaneId }) {
         singleSelection && (selected[0].kind === "directory" || selected[0].kind === "junction");
       const canPaste = hasClipboardContent();
 
+      // Shift+right-click goes straight to the shell's extended menu, as in Explorer
+      if (hasSelection && e.shiftKey) {
+        handleShellMenu(selected, e.clientX, e.clientY, true);
+        return;
+      }
+
       const menuItems: MenuEntry[] = [];
 
       if (hasSelection) {
@@ -545,6 +581,14 @@ export function FilePane({ paneId }: { paneId: PaneId }) {
 
         menuItems.push({ separator: true });
 
+        menuItems.push({
+          id: "shell-menu",
+          label: "Show more options",
+          icon: "ic_more_horizontal",
+          shortcut: "Shift+Right-click",
+          onClick: () => handleShellMenu(selected, e.clientX, e.clientY, false),
+        });
+
         menuItems.push({
           id: "properties",
           label: "Properties",
@@ -611,6 +655,7 @@ export function FilePane({ paneId }: { paneId: PaneId }) {
       handleRename,
       handleDelete,
       handleShowProperties,
+      handleShellMenu,
       handleNewFolder,
       handleNewFile,
       refresh,
@@ -7,6 +7,7 @@
 
 import clsx from "clsx";
 import { useEffect, useState } from "react";
+import { calculateFolderSize, cancelFolderSize, onFolderSize } from "../lib/tauri";
 import type { EntryMeta } from "../types";
 import { getIconForEntry } from "../utils/iconMappings";
 import { SvgIcon } from "./SvgIcon";
@@ -56,6 +57,10 @@ function formatDate(dateStr: string | null): string {
   }
 }
 
+function formatCounts(stats: FolderStats): string {
+  return `${stats.fileCount.toLocaleString()} files, ${stats.folderCount.toLocaleString()} folders`;
+}
+
 function getFileType(entry: EntryMeta): string {
   if (entry.kind === "directory") return "Folder";
   if (entry.kind === "junction") return "Junction (Symbolic Link)";
@@ -95,19 +100,53 @@ function PropertyRow({
 
 function SingleEntryProperties({ entry }: { entry: EntryMeta }) {
   const iconInfo = getIconForEntry(entry);
-  const [_folderStats, setFolderStats] = useState<FolderStats | null>(null);
+  const [folderStats, setFolderStats] = useState<FolderStats | null>(null);
 
-  // TODO: Calculate folder stats via IPC when needed
+  // Calculate folder stats in the background, showing the totals as they grow
   useEffect(() => {
-    if (entry.kind === "directory") {
+    if (entry.kind !== "directory") {
+      setFolderStats(null);
+      return;
+    }
+
+    setFolderStats({
+      totalSize: 0,
+      fileCount: 0,
+      folderCount: 0,
+      isCalculating: true,
+    });
+
+    let jobId: string | null = null;
+    let finished = false;
+    // Totals can arrive before the job id, so they're matched by path
+    const unlisten = onFolderSize((event) => {
+      if (event.path !== entry.path) return;
+      finished = event.state !== "running";
       setFolderStats({
-        totalSize: 0,
-        fileCount: 0,
-        folderCount: 0,
-        isCalculating: true,
+        totalSize: event.totalSize,
+        fileCount: event.fileCount,
+        folderCount: event.folderCount,
+        isCalculating: !finished,
       });
-      // Would call: zmanager_calculate_folder_stats(entry.path)
-    }
+    });
+    const started = unlisten
+      .then(() => calculateFolderSize(entry.path))
+      .then((id) => {
+        jobId = id;
+      })
+      .catch((error) => {
+        console.error("Failed to calculate folder size:", error);
+        setFolderStats(null);
+      });
+
+    return () => {
+      unlisten.then((stop) => stop());
+      started.then(() => {
+        if (jobId && !finished) {
+          cancelFolderSize(jobId).catch(() => {});
+        }
+      });
+    };
   }, [entry]);
 
   return (
@@ -130,8 +169,22 @@ function SingleEntryProperties({ entry }: { entry: EntryMeta }) {
 
         {entry.kind !== "directory" && <PropertyRow label="Size" value={formatBytes(entry.size)} />}
 
-        {entry.kind === "directory" && (
-          <PropertyRow label="Size" value={<span className="text-zinc-500">Calculating...</span>} />
+        {entry.kind === "directory" && folderStats && (
+          <>
+            <PropertyRow
+              label="Size"
+              value={
+                <span className={clsx(folderStats.isCalculating && "text-zinc-500")}>
+                  {formatBytes(folderStats.totalSize)}
+                  {folderStats.isCalculating && " (calculating...)"}
+                </span>
+              }
+            />
+            <PropertyRow
+              label="Contains"
+              value={formatCounts(folderStats)}
+            />
+          </>
         )}
 
         <PropertyRow label="Created" value={formatDate(entry.created)} />
@@ -8,7 +8,8 @@
 import { useDraggable } from "@dnd-kit/core";
 import { useVirtualizer } from "@tanstack/react-virtual";
 import clsx from "clsx";
-import { useCallback, useMemo, useRef } from "react";
+import { useCallback, useEffect, useMemo, useRef, useState } from "react";
+import { getShellIcon } from "../lib/tauri";
 import type { PaneId } from "../stores";
 import type { EntryMeta, SortField, SortSpec } from "../types";
 import { getIconForEntry } from "../utils/iconMappings";
@@ -121,6 +122,28 @@ interface FileRowProps {
   onContextMenu?: (event: React.MouseEvent, entry: EntryMeta) => void;
 }
 
+/** Shell icon of an entry; the bundled icon shows until it loads or if it can't */
+function FileIcon({ entry, fallback }: { entry: EntryMeta; fallback: string }) {
+  const [src, setSrc] = useState<string | null>(null);
+  const isDirectory = entry.kind === "directory" || entry.kind === "junction";
+
+  useEffect(() => {
+    let active = true;
+    setSrc(null);
+    getShellIcon(entry.path, isDirectory).then((url) => {
+      if (active) setSrc(url);
+    });
+    return () => {
+      active = false;
+    };
+  }, [entry.path, isDirectory]);
+
+  if (!src) {
+    return <SvgIcon name={fallback} size={16} />;
+  }
+  return <img src={src} width={16} height={16} alt="" draggable={false} />;
+}
+
 function FileRow({
   entry,
   index,
@@ -174,7 +197,7 @@ function FileRow({
       tabIndex={0}
     >
       <span className="flex w-5 shrink-0 items-center justify-center">
-        <SvgIcon name={iconInfo.symbolName} size={16} />
+        <FileIcon entry={entry} fallback={iconInfo.symbolName} />
       </span>
       <span className="min-w-0 flex-1 truncate">{entry.name}</span>
       <span className="w-20 shrink-0 text-right text-zinc-400">
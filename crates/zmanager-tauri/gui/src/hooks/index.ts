@@ -4,3 +4,4 @@
 
 export { useFileWatcher } from "./useFileWatcher";
 export { useKeyboardShortcuts } from "./useKeyboardShortcuts";
+export { useSession } from "./useSession";
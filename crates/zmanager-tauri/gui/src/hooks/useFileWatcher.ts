@@ -2,27 +2,81 @@
  * File Watcher Hook
  *
  * Implements directory refresh when:
+ * - A directory shown in a pane changes (watched by the backend)
  * - Window regains focus
  * - Tab becomes visible after being hidden
  *
- * This is a lightweight alternative to full file watching that
- * catches most real-world scenarios where files change.
+ * Focus and visibility refreshes catch changes in directories the backend
+ * can't watch, such as some network shares.
  */
 
 import { useEffect, useRef } from "react";
-import { useFileSystemStore } from "../stores";
+import { onDirChanged, unwatchDir, watchDir } from "../lib/tauri";
+import { type PaneId, useFileSystemStore } from "../stores";
+
+/** Compare two Windows paths, ignoring case and trailing separators */
+function samePath(a: string, b: string): boolean {
+  const normalize = (path: string) => path.replace(/[\\/]+$/, "").toLowerCase();
+  return normalize(a) === normalize(b);
+}
 
 /**
- * Auto-refresh directories when window regains focus or visibility.
- *
- * This is useful when the user switches to Explorer, makes changes,
- * then switches back to ZManager.
+ * Watch the directory shown in a pane while it's shown.
+ */
+function usePaneWatch(path: string) {
+  useEffect(() => {
+    if (!path) return;
+
+    let watching = false;
+    let cancelled = false;
+    watchDir(path)
+      .then(() => {
+        watching = true;
+        if (cancelled) {
+          unwatchDir(path).catch(() => {});
+        }
+      })
+      .catch((error) => {
+        console.warn(`Changes in ${path} won't show until refreshed:`, error);
+      });
+
+    return () => {
+      cancelled = true;
+      if (watching) {
+        unwatchDir(path).catch(() => {});
+      }
+    };
+  }, [path]);
+}
+
+/**
+ * Auto-refresh directories when they change on disk, and when the window
+ * regains focus or visibility.
  */
 export function useFileWatcher() {
   const { left, right, refresh } = useFileSystemStore();
   const lastRefreshRef = useRef<number>(0);
   const MIN_REFRESH_INTERVAL = 2000; // Don't refresh more than once per 2 seconds
 
+  usePaneWatch(left.path);
+  usePaneWatch(right.path);
+
+  // Refresh the panes showing a directory that changed
+  useEffect(() => {
+    const unlisten = onDirChanged((event) => {
+      const state = useFileSystemStore.getState();
+      for (const pane of ["left", "right"] as PaneId[]) {
+        if (state[pane].listing && samePath(state[pane].path, event.path)) {
+          state.refresh(pane);
+        }
+      }
+    });
+
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   useEffect(() => {
     const doRefresh = () => {
       const now = Date.now();
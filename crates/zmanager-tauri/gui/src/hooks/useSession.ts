@@ -0,0 +1,60 @@
+/**
+ * Session Hook
+ *
+ * Restores the pane directories and sort saved when ZManager last closed,
+ * and saves them again when the window closes. The window's own position
+ * and size are saved and restored by the backend.
+ */
+
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useEffect, useState } from "react";
+import { loadSession, saveSession } from "../lib/tauri";
+import { useFileSystemStore } from "../stores";
+
+/**
+ * Restore the last session and save it on close.
+ *
+ * @returns Whether the session has been restored, so the panes can load
+ */
+export function useSession(): boolean {
+  const [restored, setRestored] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    loadSession()
+      .then((session) => {
+        if (!cancelled) {
+          useFileSystemStore.getState().restoreSession(session);
+        }
+      })
+      .catch((error) => {
+        console.error("Failed to load the session:", error);
+      })
+      .finally(() => {
+        if (!cancelled) {
+          setRestored(true);
+        }
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  useEffect(() => {
+    // The window closes once the handler has finished
+    const unlisten = getCurrentWindow().onCloseRequested(async () => {
+      try {
+        await saveSession(useFileSystemStore.getState().sessionState());
+      } catch (error) {
+        console.error("Failed to save the session:", error);
+      }
+    });
+
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  return restored;
+}
@@ -4,7 +4,8 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { DirListing, DriveInfo, FilterSpec, IpcResponse, SortSpec } from "../types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { DirListing, DriveInfo, EntryMeta, FilterSpec, IpcResponse, SortSpec } from "../types";
 
 // ============================================================================
 // IPC Error Handling
@@ -98,6 +99,89 @@ export async function getDrives(): Promise<DriveInfo[]> {
   return unwrap(response);
 }
 
+// ============================================================================
+// Settings
+// ============================================================================
+
+/** The configuration file as JSON; keys are snake_case like `config.toml` */
+export interface AppConfig {
+  general: Record<string, unknown>;
+  appearance: Record<string, unknown>;
+  operations: Record<string, unknown>;
+  tools: { terminal: string; editor: string };
+  notifications: { enabled: boolean; min_duration_secs: number; bell: boolean };
+  favorites: unknown[];
+  keybindings?: Record<string, string[]>;
+  session?: Record<string, unknown>;
+}
+
+/** Changes to the configuration; `null` resets a setting to its default */
+export type ConfigPatch = Record<string, unknown>;
+
+/** Get the whole configuration */
+export async function getConfig(): Promise<AppConfig> {
+  const response = await invoke<IpcResponse<AppConfig>>("zmanager_get_config");
+  return unwrap(response);
+}
+
+/**
+ * Change settings and save them, e.g. `setConfig({ general: { show_hidden: true } })`.
+ *
+ * Rejects without saving anything when a setting is unknown or invalid.
+ *
+ * @param patch - JSON merge patch of the settings to change
+ * @returns The configuration as saved
+ */
+export async function setConfig(patch: ConfigPatch): Promise<AppConfig> {
+  const response = await invoke<IpcResponse<AppConfig>>("zmanager_set_config", { patch });
+  return unwrap(response);
+}
+
+// ============================================================================
+// Session
+// ============================================================================
+
+/** Sort as kept in the session; `field` is a `SortField` name */
+export interface SessionSort {
+  field: string;
+  ascending: boolean;
+}
+
+/** Window geometry in physical pixels */
+export interface WindowGeometry {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  maximized: boolean;
+}
+
+/** State restored on launch */
+export interface SessionState {
+  leftDir: string | null;
+  rightDir: string | null;
+  sort: SessionSort | null;
+  rightPaneActive: boolean;
+  /** Restored by the backend; ignored when saving */
+  window?: WindowGeometry | null;
+}
+
+/** Get the session saved when ZManager last closed (empty when restoring is turned off) */
+export async function loadSession(): Promise<SessionState> {
+  const response = await invoke<IpcResponse<SessionState>>("zmanager_load_session");
+  return unwrap(response);
+}
+
+/**
+ * Save the session; the window's geometry is captured by the backend.
+ *
+ * @returns Whether it was saved (false when restoring is turned off)
+ */
+export async function saveSession(session: SessionState): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_save_session", { session });
+  return unwrap(response);
+}
+
 // ============================================================================
 // File Operations
 // ============================================================================
@@ -166,6 +250,16 @@ export async function openFile(path: string): Promise<void> {
   unwrap(response);
 }
 
+/**
+ * Open Explorer on the containing folder with the item selected.
+ *
+ * @param path - Absolute path to reveal
+ */
+export async function revealInExplorer(path: string): Promise<void> {
+  const response = await invoke<IpcResponse<null>>("zmanager_reveal_in_explorer", { path });
+  unwrap(response);
+}
+
 /** File properties from backend */
 export interface FileProperties {
   path: string;
@@ -191,6 +285,448 @@ export async function getProperties(path: string): Promise<FileProperties> {
   return unwrap(response);
 }
 
+/** Attribute and timestamp changes; omitted fields are left unchanged */
+export interface PropertyChanges {
+  readonly?: boolean;
+  hidden?: boolean;
+  system?: boolean;
+  /** RFC 3339 time (Windows only) */
+  created?: string;
+  /** RFC 3339 time */
+  modified?: string;
+}
+
+/**
+ * Change attributes and timestamps of a file or folder.
+ *
+ * @param path - Absolute path
+ * @param changes - Fields to change
+ * @returns File properties after the change
+ */
+export async function setProperties(
+  path: string,
+  changes: PropertyChanges
+): Promise<FileProperties> {
+  const response = await invoke<IpcResponse<FileProperties>>("zmanager_set_properties", {
+    path,
+    changes,
+  });
+  return unwrap(response);
+}
+
+/** Payload of the `zmanager://folder-size` event */
+export interface FolderSizeEvent {
+  jobId: string;
+  /** The folder, as passed to `calculateFolderSize` */
+  path: string;
+  state: "running" | "completed" | "failed" | "canceled";
+  totalSize: number;
+  fileCount: number;
+  folderCount: number;
+  error?: string;
+}
+
+/**
+ * Start calculating the size of a folder in the background.
+ *
+ * Running totals and the result come through `onFolderSize`.
+ *
+ * @param path - Absolute path of the folder
+ * @returns Id of the calculation
+ */
+export async function calculateFolderSize(path: string): Promise<string> {
+  const response = await invoke<IpcResponse<string>>("zmanager_calculate_folder_size", { path });
+  return unwrap(response);
+}
+
+/** Cancel a running folder size calculation */
+export async function cancelFolderSize(jobId: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_cancel_folder_size", { jobId });
+  return unwrap(response);
+}
+
+/** Listen to folder size totals; call the returned function to stop */
+export function onFolderSize(handler: (event: FolderSizeEvent) => void): Promise<UnlistenFn> {
+  return listen<FolderSizeEvent>("zmanager://folder-size", (event) => handler(event.payload));
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+
+/** What a search looks for - matches Rust SearchQuery from zmanager-core */
+export interface SearchQuery {
+  /** Entries to match by name, extension and size */
+  filter: FilterSpec;
+  /** Text the contents of matching files must contain */
+  contents?: string | null;
+  /** Whether letters of `contents` must match in case */
+  match_case?: boolean;
+  /** Whether folders can match as well as files */
+  folders?: boolean;
+}
+
+/** State of a search */
+export type SearchState = "running" | "completed" | "failed" | "canceled";
+
+/** Payload of the `zmanager://search-results` event */
+export interface SearchResultsEvent {
+  jobId: string;
+  /** The folder searched, as passed to `startSearch` */
+  root: string;
+  state: SearchState;
+  /** Matches found since the previous event */
+  entries: EntryMeta[];
+  /** Matches found so far */
+  matchCount: number;
+  error?: string;
+}
+
+/** Matches of a search, as `searchResults` returns them */
+export interface SearchResults {
+  state: SearchState;
+  entries: EntryMeta[];
+  error?: string;
+}
+
+/**
+ * Start searching a folder and everything below it in the background.
+ *
+ * Matches and the outcome come through `onSearchResults`.
+ *
+ * @param root - Absolute path of the folder to search
+ * @param query - What to look for
+ * @returns Id of the search
+ */
+export async function startSearch(root: string, query: SearchQuery): Promise<string> {
+  const response = await invoke<IpcResponse<string>>("zmanager_search_start", { root, query });
+  return unwrap(response);
+}
+
+/** Get the matches a search has found so far */
+export async function searchResults(jobId: string): Promise<SearchResults> {
+  const response = await invoke<IpcResponse<SearchResults>>("zmanager_search_results", { jobId });
+  return unwrap(response);
+}
+
+/** Cancel a search and discard its matches */
+export async function cancelSearch(jobId: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_search_cancel", { jobId });
+  return unwrap(response);
+}
+
+/** Listen to search matches; call the returned function to stop */
+export function onSearchResults(handler: (event: SearchResultsEvent) => void): Promise<UnlistenFn> {
+  return listen<SearchResultsEvent>("zmanager://search-results", (event) =>
+    handler(event.payload)
+  );
+}
+
+// ============================================================================
+// Drag and Drop
+// ============================================================================
+
+/** Whether dropped files are copied or moved */
+export type DropEffect = "copy" | "move";
+
+/** Result of dropping files onto a pane */
+export interface DropResult {
+  effect: DropEffect;
+  succeeded: number;
+  failed: number;
+  skipped: number;
+}
+
+/**
+ * Copy or move files dropped from the shell into a directory.
+ *
+ * @param paths - Absolute paths of the dropped files
+ * @param destination - Directory to drop into
+ * @param effect - Copy or move; defaults to move within a volume, copy across volumes
+ * @returns Counts of transferred, failed and skipped items
+ */
+export async function dropFiles(
+  paths: string[],
+  destination: string,
+  effect?: DropEffect
+): Promise<DropResult> {
+  const response = await invoke<IpcResponse<DropResult>>("zmanager_drop_files", {
+    paths,
+    destination,
+    effect,
+  });
+  return unwrap(response);
+}
+
+/**
+ * Drag files out of the GUI to the shell.
+ *
+ * @param paths - Absolute paths to drag
+ * @param effect - Effect offered to the drop target (default copy)
+ * @returns The effect the target performed, or null if the drag was cancelled
+ */
+export async function dragOut(paths: string[], effect?: DropEffect): Promise<DropEffect | null> {
+  const response = await invoke<IpcResponse<DropEffect | null>>("zmanager_drag_out", {
+    paths,
+    effect,
+  });
+  return unwrap(response);
+}
+
+// ============================================================================
+// Shell Integration
+// ============================================================================
+
+/** What the user did with the shell context menu */
+export type ShellMenuResult =
+  | { kind: "dismissed" }
+  | { kind: "invoked"; verb: string | null }
+  | { kind: "rename" };
+
+/**
+ * Show the Windows shell context menu for items in one folder.
+ *
+ * @param paths - Absolute paths of the items
+ * @param x - Client X coordinate of the menu, as in a mouse event
+ * @param y - Client Y coordinate of the menu
+ * @param extended - Include the verbs shown on Shift+right-click
+ * @returns What the user chose; rename is left to the caller
+ */
+export async function showShellContextMenu(
+  paths: string[],
+  x: number,
+  y: number,
+  extended?: boolean
+): Promise<ShellMenuResult> {
+  const response = await invoke<IpcResponse<ShellMenuResult>>("zmanager_shell_context_menu", {
+    paths,
+    x,
+    y,
+    extended,
+  });
+  return unwrap(response);
+}
+
+/** Extensions whose files have an icon of their own, as in the backend's cache */
+const PER_FILE_ICON_EXTENSIONS = new Set(["exe", "ico", "lnk", "url", "cur", "ani", "scr", "msc"]);
+
+/** Object URLs of the shell icons asked for, by what decides the icon */
+const shellIconUrls = new Map<string, Promise<string | null>>();
+
+/**
+ * Get the Windows shell icon of a file or folder as an object URL.
+ *
+ * Files sharing their type's icon are only fetched once per extension.
+ *
+ * @param path - Absolute path of the file or folder
+ * @param isDirectory - Whether the path is a folder
+ * @param size - Icon width and height in pixels (default 16)
+ * @returns URL of the PNG image, or null if the shell has no icon for it
+ */
+export function getShellIcon(
+  path: string,
+  isDirectory: boolean,
+  size = 16
+): Promise<string | null> {
+  const name = path.split(/[\\/]/).pop() ?? "";
+  const dot = name.lastIndexOf(".");
+  const ext = dot > 0 ? name.slice(dot + 1).toLowerCase() : "";
+  const key =
+    isDirectory || PER_FILE_ICON_EXTENSIONS.has(ext) ? `${size}:${path}` : `${size}:.${ext}`;
+
+  let url = shellIconUrls.get(key);
+  if (!url) {
+    url = invoke<IpcResponse<number[]>>("zmanager_get_icon", { path, size })
+      .then(unwrap)
+      .then((bytes) =>
+        URL.createObjectURL(new Blob([new Uint8Array(bytes)], { type: "image/png" }))
+      )
+      .catch(() => null);
+    shellIconUrls.set(key, url);
+  }
+  return url;
+}
+
+/**
+ * Get a thumbnail of an image or video as an object URL.
+ *
+ * The caller owns the URL and should revoke it once the image is gone.
+ *
+ * @param path - Absolute path of the file
+ * @param size - Box the thumbnail fits in, in pixels (default 256)
+ * @returns URL of the PNG image
+ */
+export async function getThumbnail(path: string, size?: number): Promise<string> {
+  const response = await invoke<IpcResponse<number[]>>("zmanager_get_thumbnail", {
+    path,
+    size,
+  });
+  const bytes = unwrap(response);
+  return URL.createObjectURL(new Blob([new Uint8Array(bytes)], { type: "image/png" }));
+}
+
+// ============================================================================
+// Transfer Jobs
+// ============================================================================
+
+/** How a transfer resolves files that already exist at the destination */
+export type ConflictPolicy = "overwrite" | "skip" | "rename" | "keep_newer" | "keep_larger";
+
+/** State of a job on the scheduler */
+export type JobState = "queued" | "running" | "paused" | "completed" | "failed" | "canceled";
+
+/** Counts of a finished transfer's items */
+export interface JobReport {
+  total: number;
+  succeeded: number;
+  failed: number;
+  skipped: number;
+}
+
+/** A job as listed by `listJobs` */
+export interface JobInfo {
+  jobId: string;
+  kind: "copy" | "move" | "delete" | "other";
+  description: string;
+  state: JobState;
+  bytesDone: number;
+  bytesTotal: number | null;
+  itemsDone: number;
+  itemsTotal: number;
+  currentPath: string | null;
+  throughputBytesPerSec: number | null;
+  etaSecs: number | null;
+  error: string | null;
+}
+
+/** Payload of the `zmanager://job-progress` event */
+export interface JobProgressEvent {
+  jobId: string;
+  bytesDone: number;
+  bytesTotal: number | null;
+  itemsDone: number;
+  itemsTotal: number;
+  currentPath: string | null;
+  throughputBytesPerSec: number | null;
+}
+
+/** Payload of the `zmanager://job-state` event */
+export interface JobStateEvent {
+  jobId: string;
+  state: JobState;
+  error?: string;
+  report?: JobReport;
+}
+
+/**
+ * Queue a copy of files and folders into a directory.
+ *
+ * @param sources - Absolute paths to copy
+ * @param destination - Directory to copy into
+ * @param conflict - How existing files are handled (default "rename")
+ * @returns Id of the queued job
+ */
+export async function startCopy(
+  sources: string[],
+  destination: string,
+  conflict?: ConflictPolicy
+): Promise<string> {
+  const response = await invoke<IpcResponse<string>>("zmanager_start_copy", {
+    sources,
+    destination,
+    conflict,
+  });
+  return unwrap(response);
+}
+
+/**
+ * Queue a move of files and folders into a directory.
+ *
+ * @param sources - Absolute paths to move
+ * @param destination - Directory to move into
+ * @param conflict - How existing files are handled (default "rename")
+ * @returns Id of the queued job
+ */
+export async function startMove(
+  sources: string[],
+  destination: string,
+  conflict?: ConflictPolicy
+): Promise<string> {
+  const response = await invoke<IpcResponse<string>>("zmanager_start_move", {
+    sources,
+    destination,
+    conflict,
+  });
+  return unwrap(response);
+}
+
+/** List queued, running and finished jobs, oldest first */
+export async function listJobs(): Promise<JobInfo[]> {
+  const response = await invoke<IpcResponse<JobInfo[]>>("zmanager_job_list");
+  return unwrap(response);
+}
+
+/** Pause a running job between files */
+export async function pauseJob(jobId: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_job_pause", { jobId });
+  return unwrap(response);
+}
+
+/** Resume a paused job */
+export async function resumeJob(jobId: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_job_resume", { jobId });
+  return unwrap(response);
+}
+
+/** Cancel a queued, running or paused job */
+export async function cancelJob(jobId: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_job_cancel", { jobId });
+  return unwrap(response);
+}
+
+/** Listen to job progress; call the returned function to stop */
+export function onJobProgress(handler: (event: JobProgressEvent) => void): Promise<UnlistenFn> {
+  return listen<JobProgressEvent>("zmanager://job-progress", (event) => handler(event.payload));
+}
+
+/** Listen to jobs being queued, started, paused and finished; call the returned function to stop */
+export function onJobState(handler: (event: JobStateEvent) => void): Promise<UnlistenFn> {
+  return listen<JobStateEvent>("zmanager://job-state", (event) => handler(event.payload));
+}
+
+// ============================================================================
+// Directory Watching
+// ============================================================================
+
+/** Payload of the `zmanager://dir-changed` event */
+export interface DirChangedEvent {
+  /** The watched directory, as passed to `watchDir` */
+  path: string;
+  kind: "create" | "modify" | "delete" | "rename" | "change";
+  affectedPaths: string[];
+}
+
+/**
+ * Watch a directory for changes, reported through `onDirChanged`.
+ *
+ * Each call must be matched by an `unwatchDir` call for the same path.
+ */
+export async function watchDir(path: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_watch_dir", { path });
+  return unwrap(response);
+}
+
+/** Stop watching a directory */
+export async function unwatchDir(path: string): Promise<boolean> {
+  const response = await invoke<IpcResponse<boolean>>("zmanager_unwatch_dir", { path });
+  return unwrap(response);
+}
+
+/** Listen to changes in watched directories; call the returned function to stop */
+export function onDirChanged(handler: (event: DirChangedEvent) => void): Promise<UnlistenFn> {
+  return listen<DirChangedEvent>("zmanager://dir-changed", (event) => handler(event.payload));
+}
+
 // ============================================================================
 // Re-exports for convenience
 // ============================================================================
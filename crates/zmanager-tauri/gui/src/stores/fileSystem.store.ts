@@ -6,8 +6,8 @@
  */
 
 import { create } from "zustand";
-import { getDrives, navigate } from "../lib/tauri";
-import type { DirListing, DriveInfo, FilterSpec, SortSpec } from "../types";
+import { getDrives, navigate, type SessionState } from "../lib/tauri";
+import type { DirListing, DriveInfo, FilterSpec, SortField, SortSpec } from "../types";
 import { DEFAULT_FILTER, DEFAULT_SORT } from "../types";
 
 /** Which pane is being referenced */
@@ -75,8 +75,14 @@ interface FileSystemState {
   setCursor: (pane: PaneId, index: number) => void;
   /** Clear selection */
   clearSelection: (pane: PaneId) => void;
+  /** Restore pane directories and sort; call before the panes first load */
+  restoreSession: (session: SessionState) => void;
+  /** Capture pane directories and sort for the next launch */
+  sessionState: () => SessionState;
 }
 
+const SORT_FIELDS: SortField[] = ["name", "size", "modified", "created", "extension", "kind"];
+
 /** Create initial pane state */
 function createInitialPaneState(defaultPath: string): PaneState {
   return {
@@ -352,4 +358,37 @@ export const useFileSystemStore = create<FileSystemState>((set, get) => ({
       [pane]: { ...paneState, selectedIndices: new Set() },
     });
   },
+
+  restoreSession: (session) => {
+    const { left, right } = get();
+    const field = SORT_FIELDS.find((f) => f === session.sort?.field.toLowerCase());
+    const restore = (paneState: PaneState, dir: string | null): PaneState => ({
+      ...paneState,
+      path: dir ?? paneState.path,
+      sort:
+        session.sort && field
+          ? {
+              ...paneState.sort,
+              field,
+              order: session.sort.ascending ? "ascending" : "descending",
+            }
+          : paneState.sort,
+    });
+    set({
+      left: restore(left, session.leftDir),
+      right: restore(right, session.rightDir),
+      activePane: session.rightPaneActive ? "right" : "left",
+    });
+  },
+
+  sessionState: () => {
+    const { left, right, activePane } = get();
+    const sort = get()[activePane].sort;
+    return {
+      leftDir: left.path,
+      rightDir: right.path,
+      sort: { field: sort.field, ascending: sort.order === "ascending" },
+      rightPaneActive: activePane === "right",
+    };
+  },
 }));
@@ -117,7 +117,8 @@ export type DriveType =
   | "Fixed"
   | "Network"
   | "CdRom"
-  | "RamDisk";
+  | "RamDisk"
+  | "Portable";
 
 /** Drive information */
 export interface DriveInfo {
@@ -5,10 +5,17 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
 use zmanager_core::{
     list_directory, list_drives as core_list_drives, DirListing, DriveInfo as CoreDriveInfo,
     DriveType, FilterSpec, SortSpec, Config, Favorite,
 };
+use zmanager_transfer_win::{
+    default_drop_effect, drag_files_out, drop_job, show_shell_context_menu, ConflictPolicy,
+    ConflictResolver, ConflictSettings, DropEffect, FolderTransferExecutor, IconCache,
+    ShellMenuResult, ThumbnailCache,
+};
 
 /// Response wrapper for IPC commands.
 /// Follows { ok: bool, data?, error? } pattern per IPC_Contract.md.
@@ -79,6 +86,7 @@ impl From<CoreDriveInfo> for DriveInfoDto {
             DriveType::Network => "Network",
             DriveType::CdRom => "CdRom",
             DriveType::RamDisk => "RamDisk",
+            DriveType::Portable => "Portable",
             DriveType::Unknown => "Unknown",
             DriveType::NoRootDir => "NoRootDir",
         };
@@ -297,6 +305,20 @@ pub async fn zmanager_open_file(path: String) -> IpcResponse<()> {
     }
 }
 
+/// Open Explorer on the folder containing a file or folder, with it selected.
+#[tauri::command]
+pub async fn zmanager_reveal_in_explorer(path: String) -> IpcResponse<()> {
+    tracing::debug!("reveal_in_explorer: {}", path);
+
+    match zmanager_core::reveal_in_explorer(&path) {
+        Ok(()) => IpcResponse::success(()),
+        Err(e) => {
+            tracing::error!("Failed to reveal {}: {}", path, e);
+            IpcResponse::failure(e.to_string())
+        }
+    }
+}
+
 /// File properties response
 #[derive(Debug, Clone, Serialize)]
 pub struct FileProperties {
@@ -385,6 +407,57 @@ pub async fn zmanager_get_properties(path: String) -> IpcResponse<FileProperties
     })
 }
 
+/// Attribute and timestamp changes from the frontend.
+///
+/// Omitted fields are left unchanged; times are RFC 3339 strings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PropertyChangesDto {
+    pub readonly: Option<bool>,
+    pub hidden: Option<bool>,
+    pub system: Option<bool>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+/// Change attributes and timestamps of a file or folder.
+///
+/// Returns the properties after the change.
+#[tauri::command]
+pub async fn zmanager_set_properties(
+    path: String,
+    changes: PropertyChangesDto,
+) -> IpcResponse<FileProperties> {
+    tracing::debug!("set_properties: {} {:?}", path, changes);
+
+    let parse_time = |time: Option<String>| -> Result<Option<std::time::SystemTime>, String> {
+        time.map(|t| {
+            chrono::DateTime::parse_from_rfc3339(&t)
+                .map(std::time::SystemTime::from)
+                .map_err(|e| format!("Invalid time '{}': {}", t, e))
+        })
+        .transpose()
+    };
+    let (created, modified) = match (parse_time(changes.created), parse_time(changes.modified)) {
+        (Ok(created), Ok(modified)) => (created, modified),
+        (Err(e), _) | (_, Err(e)) => return IpcResponse::failure(e),
+    };
+
+    let changes = zmanager_core::PropertyChanges {
+        readonly: changes.readonly,
+        hidden: changes.hidden,
+        system: changes.system,
+        created,
+        modified,
+    };
+    if let Err(e) = zmanager_core::set_properties(&path, &changes) {
+        tracing::error!("Failed to set properties of {}: {}", path, e);
+        return IpcResponse::failure(e.to_string());
+    }
+
+    zmanager_get_properties(path).await
+}
+
 // ============================================================================
 // Favorites Management - Sprint 16
 // ============================================================================
@@ -655,6 +728,202 @@ pub fn zmanager_clipboard_clear(
     Ok(())
 }
 
+// ============================================================================
+// Drag and Drop
+// ============================================================================
+
+/// Result of files dropped onto a pane
+#[derive(Debug, Clone, Serialize)]
+pub struct DropResult {
+    pub effect: DropEffect,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Copy or move files dropped from the shell into a directory.
+///
+/// Without an explicit effect, files move within a volume and copy across
+/// volumes, as they would in Explorer. Conflicting files are kept as renamed
+/// copies.
+#[tauri::command]
+pub async fn zmanager_drop_files(
+    paths: Vec<String>,
+    destination: String,
+    effect: Option<DropEffect>,
+) -> IpcResponse<DropResult> {
+    tracing::debug!("drop_files: {} items to {}", paths.len(), destination);
+
+    let sources: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    let dest_path = PathBuf::from(&destination);
+    if !dest_path.is_dir() {
+        return IpcResponse::failure(format!("Destination is not a directory: {}", destination));
+    }
+
+    let effect = effect.unwrap_or_else(|| default_drop_effect(&sources, &dest_path));
+    let job = drop_job(sources, dest_path, effect);
+    let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::with_settings(
+        ConflictSettings {
+            file_policy: ConflictPolicy::Rename,
+            ..Default::default()
+        },
+    )));
+
+    match FolderTransferExecutor::new().execute_job(&job, resolver).await {
+        Ok(report) => {
+            tracing::info!("Dropped {} items ({:?})", report.succeeded, effect);
+            IpcResponse::success(DropResult {
+                effect,
+                succeeded: report.succeeded,
+                failed: report.failed,
+                skipped: report.skipped,
+            })
+        }
+        Err(e) => {
+            tracing::error!("Failed to drop files into {}: {}", destination, e);
+            IpcResponse::failure(e.to_string())
+        }
+    }
+}
+
+/// Drag files out of the GUI to the shell.
+///
+/// Returns the effect the drop target performed, or `None` if the drag was
+/// cancelled.
+#[tauri::command]
+pub async fn zmanager_drag_out(
+    window: tauri::Window,
+    paths: Vec<String>,
+    effect: Option<DropEffect>,
+) -> IpcResponse<Option<DropEffect>> {
+    tracing::debug!("drag_out: {} items", paths.len());
+
+    let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    let effect = effect.unwrap_or(DropEffect::Copy);
+
+    // The OLE drag loop has to run on the UI thread
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    if let Err(e) = window.run_on_main_thread(move || {
+        let _ = tx.send(drag_files_out(&paths, effect));
+    }) {
+        return IpcResponse::failure(e.to_string());
+    }
+
+    match rx.await {
+        Ok(Ok(performed)) => IpcResponse::success(performed),
+        Ok(Err(e)) => {
+            tracing::error!("Drag out failed: {}", e);
+            IpcResponse::failure(e.to_string())
+        }
+        Err(_) => IpcResponse::failure("Drag was interrupted"),
+    }
+}
+
+/// Show the Windows shell context menu for items at a point in the window.
+///
+/// `x` and `y` are client coordinates in CSS pixels, as in a mouse event.
+/// The chosen command runs through the shell, except rename, which is
+/// returned for the GUI to start inline.
+#[tauri::command]
+pub async fn zmanager_shell_context_menu(
+    window: tauri::Window,
+    paths: Vec<String>,
+    x: f64,
+    y: f64,
+    extended: Option<bool>,
+) -> IpcResponse<ShellMenuResult> {
+    tracing::debug!("shell_context_menu: {} items at ({}, {})", paths.len(), x, y);
+
+    let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    let extended = extended.unwrap_or(false);
+
+    // The menu is placed in screen pixels
+    let (hwnd, origin, scale) =
+        match (window.hwnd(), window.inner_position(), window.scale_factor()) {
+            (Ok(hwnd), Ok(origin), Ok(scale)) => (hwnd.0 as isize, origin, scale),
+            _ => return IpcResponse::failure("Failed to locate the window"),
+        };
+    let x = origin.x + (x * scale).round() as i32;
+    let y = origin.y + (y * scale).round() as i32;
+
+    // The menu loop has to run on the UI thread
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    if let Err(e) = window.run_on_main_thread(move || {
+        let _ = tx.send(show_shell_context_menu(hwnd, &paths, x, y, extended));
+    }) {
+        return IpcResponse::failure(e.to_string());
+    }
+
+    match rx.await {
+        Ok(Ok(result)) => IpcResponse::success(result),
+        Ok(Err(e)) => {
+            tracing::error!("Shell context menu failed: {}", e);
+            IpcResponse::failure(e.to_string())
+        }
+        Err(_) => IpcResponse::failure("Context menu was interrupted"),
+    }
+}
+
+/// Get the Windows shell icon of a file or folder as PNG bytes.
+///
+/// `size` is the icon's width and height in pixels (default 16). Icons are
+/// cached per file type, so listing a folder of similar files asks the shell
+/// once.
+#[tauri::command]
+pub async fn zmanager_get_icon(
+    path: String,
+    size: Option<u32>,
+    app: AppHandle,
+) -> IpcResponse<Vec<u8>> {
+    let cache = Arc::clone(app.state::<Arc<IconCache>>().inner());
+    let size = size.unwrap_or(16);
+
+    // The shell draws icons synchronously through COM
+    let result = tokio::task::spawn_blocking(move || {
+        cache.get(std::path::Path::new(&path), size).map_err(|e| {
+            tracing::debug!("No shell icon for {}: {}", path, e);
+            e.to_string()
+        })
+    })
+    .await;
+
+    match result {
+        Ok(Ok(png)) => IpcResponse::success(png.to_vec()),
+        Ok(Err(e)) => IpcResponse::failure(e),
+        Err(e) => IpcResponse::failure(e.to_string()),
+    }
+}
+
+/// Get a thumbnail of an image or video as PNG bytes.
+///
+/// The thumbnail fits in `size` pixels square (default 256), keeping the
+/// aspect ratio. Thumbnails are cached on disk, so a grid view can ask again
+/// cheaply.
+#[tauri::command]
+pub async fn zmanager_get_thumbnail(
+    path: String,
+    size: Option<u32>,
+    app: AppHandle,
+) -> IpcResponse<Vec<u8>> {
+    let cache = Arc::clone(app.state::<Arc<ThumbnailCache>>().inner());
+    let size = size.unwrap_or(256);
+
+    // The shell draws thumbnails synchronously through COM
+    let result = tokio::task::spawn_blocking(move || {
+        cache.get(std::path::Path::new(&path), size).map_err(|e| {
+            tracing::debug!("No thumbnail for {}: {}", path, e);
+            e.to_string()
+        })
+    })
+    .await;
+
+    match result {
+        Ok(Ok(png)) => IpcResponse::success(png),
+        Ok(Err(e)) => IpcResponse::failure(e),
+        Err(e) => IpcResponse::failure(e.to_string()),
+    }
+}
+
 /// Create a new empty file.
 #[tauri::command]
 pub async fn zmanager_create_file(parent: String, name: String) -> IpcResponse<String> {
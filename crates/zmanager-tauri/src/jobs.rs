@@ -0,0 +1,438 @@
+//! Copy and move jobs for the GUI.
+//!
+//! Transfers are queued on the core `Scheduler` and run by the
+//! `FolderTransferExecutor` as the scheduler hands out slots. Job changes
+//! reach the frontend as `zmanager://job-state` events and progress as
+//! throttled `zmanager://job-progress` events.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use zmanager_core::{
+    Job, JobId, JobInfo, JobKind, JobState, Progress, SchedulerEvent, SchedulerHandle, ZError,
+};
+use zmanager_transfer_win::{
+    ConflictPolicy, ConflictResolver, ConflictSettings, FolderTransferEvent,
+    FolderTransferExecutor,
+};
+
+use crate::commands::IpcResponse;
+
+/// Event carrying a job's progress.
+pub const JOB_PROGRESS_EVENT: &str = "zmanager://job-progress";
+
+/// Event carrying a job's state changes.
+pub const JOB_STATE_EVENT: &str = "zmanager://job-state";
+
+/// Least time between two progress events of a job.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Transfer jobs run for the GUI, kept in app state.
+pub struct JobsState {
+    scheduler: SchedulerHandle,
+    /// Conflict policy of each queued job, taken when it starts.
+    policies: Mutex<HashMap<JobId, ConflictPolicy>>,
+    /// Outcome of each finished transfer, for its state event.
+    reports: Mutex<HashMap<JobId, JobReportDto>>,
+}
+
+impl JobsState {
+    /// Run the GUI's jobs on `scheduler`.
+    pub fn new(scheduler: SchedulerHandle) -> Self {
+        Self {
+            scheduler,
+            policies: Mutex::new(HashMap::new()),
+            reports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a copy or move, remembering how its conflicts are resolved.
+    async fn submit(&self, kind: JobKind, conflict: ConflictPolicy) -> JobId {
+        // Holding the lock keeps the job from starting before its policy is in
+        let mut policies = self.policies.lock().await;
+        let id = self.scheduler.submit(kind).await;
+        policies.insert(id, conflict);
+        id
+    }
+}
+
+/// Job state as the frontend knows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStateDto {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl From<JobState> for JobStateDto {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Pending => Self::Queued,
+            JobState::Running => Self::Running,
+            JobState::Paused => Self::Paused,
+            JobState::Completed => Self::Completed,
+            JobState::Failed => Self::Failed,
+            JobState::Cancelled => Self::Canceled,
+        }
+    }
+}
+
+/// Counts of a finished transfer's items.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobReportDto {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// A job in the transfers panel.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDto {
+    pub job_id: String,
+    /// "copy", "move", "delete" or "other"
+    pub kind: &'static str,
+    pub description: String,
+    pub state: JobStateDto,
+    pub bytes_done: u64,
+    pub bytes_total: Option<u64>,
+    pub items_done: usize,
+    pub items_total: usize,
+    pub current_path: Option<String>,
+    pub throughput_bytes_per_sec: Option<u64>,
+    pub eta_secs: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl JobDto {
+    fn new(info: JobInfo, kind: &'static str) -> Self {
+        Self {
+            job_id: info.id.0.to_string(),
+            kind,
+            description: info.description,
+            state: info.state.into(),
+            bytes_done: info.bytes_done,
+            bytes_total: info.total_bytes,
+            items_done: info.items_done,
+            items_total: info.total_items,
+            current_path: info.current_item.map(|p| p.to_string_lossy().to_string()),
+            throughput_bytes_per_sec: info.speed_bytes_per_sec,
+            eta_secs: info.eta_secs,
+            error: info.error,
+        }
+    }
+}
+
+/// Payload of [`JOB_PROGRESS_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgressPayload {
+    pub job_id: String,
+    pub bytes_done: u64,
+    pub bytes_total: Option<u64>,
+    pub items_done: usize,
+    pub items_total: usize,
+    pub current_path: Option<String>,
+    pub throughput_bytes_per_sec: Option<u64>,
+}
+
+/// Payload of [`JOB_STATE_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatePayload {
+    pub job_id: String,
+    pub state: JobStateDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<JobReportDto>,
+}
+
+/// Frontend name of a job's kind.
+fn kind_name(kind: &JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy { .. } => "copy",
+        JobKind::Move { .. } => "move",
+        JobKind::Delete { .. } | JobKind::DeletePermanent { .. } => "delete",
+        _ => "other",
+    }
+}
+
+/// Parse a job id sent by the frontend.
+pub(crate) fn parse_job_id(job_id: &str) -> Option<JobId> {
+    job_id.parse().ok().map(JobId)
+}
+
+/// Start transfers as the scheduler hands out slots and forward job
+/// changes to the frontend. Runs for the lifetime of the app.
+pub async fn bridge_jobs(app: AppHandle, jobs: Arc<JobsState>) {
+    let mut events = jobs.scheduler.subscribe();
+    let mut last_progress: HashMap<JobId, Instant> = HashMap::new();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+
+        let (id, state, error) = match event {
+            SchedulerEvent::JobProgress(id, progress) => {
+                if last_progress.get(&id).is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+                    continue;
+                }
+                last_progress.insert(id, Instant::now());
+                let _ = app.emit(JOB_PROGRESS_EVENT, progress_payload(id, &progress));
+                continue;
+            }
+            SchedulerEvent::JobAdded(id) => (id, JobStateDto::Queued, None),
+            SchedulerEvent::JobStarted(id) => {
+                if let Some(job) = jobs.scheduler.job(id).await {
+                    if matches!(job.kind, JobKind::Copy { .. } | JobKind::Move { .. }) {
+                        tokio::spawn(run_transfer(job, Arc::clone(&jobs)));
+                    }
+                }
+                (id, JobStateDto::Running, None)
+            }
+            SchedulerEvent::JobPaused(id) => (id, JobStateDto::Paused, None),
+            SchedulerEvent::JobResumed(id) => (id, JobStateDto::Running, None),
+            SchedulerEvent::JobCompleted(id) => (id, JobStateDto::Completed, None),
+            SchedulerEvent::JobFailed(id, error) => (id, JobStateDto::Failed, Some(error)),
+            SchedulerEvent::JobCancelled(id) => (id, JobStateDto::Canceled, None),
+            SchedulerEvent::JobPriorityChanged(..) | SchedulerEvent::JobSpeedLimitChanged(..) => {
+                continue;
+            }
+        };
+
+        let report = match state {
+            JobStateDto::Queued | JobStateDto::Running | JobStateDto::Paused => None,
+            _ => {
+                // A job cancelled while queued never took its policy
+                jobs.policies.lock().await.remove(&id);
+                last_progress.remove(&id);
+                jobs.reports.lock().await.remove(&id)
+            }
+        };
+        let payload = JobStatePayload {
+            job_id: id.0.to_string(),
+            state,
+            error,
+            report,
+        };
+        let _ = app.emit(JOB_STATE_EVENT, payload);
+    }
+}
+
+/// Describe a progress update for the frontend.
+fn progress_payload(id: JobId, progress: &Progress) -> JobProgressPayload {
+    JobProgressPayload {
+        job_id: id.0.to_string(),
+        bytes_done: progress.bytes_done,
+        bytes_total: progress.total_bytes,
+        items_done: progress.items_done,
+        items_total: progress.total_items,
+        current_path: progress
+            .current_item
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        throughput_bytes_per_sec: progress.speed_bytes_per_sec,
+    }
+}
+
+/// Run a copy or move job, reporting its progress and outcome to the
+/// scheduler.
+async fn run_transfer(job: Job, jobs: Arc<JobsState>) {
+    let policy = jobs
+        .policies
+        .lock()
+        .await
+        .remove(&job.id)
+        .unwrap_or(ConflictPolicy::Rename);
+    let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::with_settings(
+        ConflictSettings {
+            file_policy: policy,
+            ..Default::default()
+        },
+    )));
+
+    let executor = FolderTransferExecutor::new();
+    let mut events = executor.subscribe();
+    let scheduler = jobs.scheduler.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(FolderTransferEvent::Progress { job_id, progress }) => {
+                    scheduler.report_progress(job_id, progress).await;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let result = executor.execute_job(&job, resolver).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+
+    let scheduler = &jobs.scheduler;
+    match result {
+        Ok(report) => {
+            let failed = report.failed;
+            let total = report.items.len();
+            jobs.reports.lock().await.insert(
+                job.id,
+                JobReportDto {
+                    total,
+                    succeeded: report.succeeded,
+                    failed,
+                    skipped: report.skipped,
+                },
+            );
+            if failed == 0 {
+                scheduler.complete(job.id).await;
+            } else {
+                scheduler.fail(job.id, format!("{failed} of {total} item(s) failed")).await;
+            }
+        }
+        // Cancelled through zmanager_job_cancel, the scheduler already knows
+        Err(ZError::Cancelled) if job.cancellation.is_cancelled() => {}
+        Err(e) => scheduler.fail(job.id, e.to_string()).await,
+    }
+}
+
+/// Queue a copy or move of `sources` into `destination`.
+async fn start_transfer(
+    jobs: &JobsState,
+    kind: JobKind,
+    destination: &Path,
+    conflict: Option<ConflictPolicy>,
+) -> IpcResponse<String> {
+    if !destination.is_dir() {
+        return IpcResponse::failure(format!(
+            "Destination is not a directory: {}",
+            destination.display()
+        ));
+    }
+    let conflict = conflict.unwrap_or(ConflictPolicy::Rename);
+    if conflict == ConflictPolicy::Ask {
+        return IpcResponse::failure("Asking about conflicts is not supported yet");
+    }
+
+    let id = jobs.submit(kind, conflict).await;
+    tracing::info!("Queued transfer job {}", id.0);
+    IpcResponse::success(id.0.to_string())
+}
+
+/// Queue a copy of files and folders into a directory.
+///
+/// Conflicting files are resolved by `conflict` (default "rename"). Returns
+/// the id of the job.
+#[tauri::command]
+pub async fn zmanager_start_copy(
+    sources: Vec<String>,
+    destination: String,
+    conflict: Option<ConflictPolicy>,
+    app: AppHandle,
+) -> IpcResponse<String> {
+    let jobs = app.state::<Arc<JobsState>>();
+    tracing::debug!("start_copy: {} items to {}", sources.len(), destination);
+
+    let destination = PathBuf::from(destination);
+    let kind = JobKind::Copy {
+        sources: sources.iter().map(PathBuf::from).collect(),
+        destination: destination.clone(),
+    };
+    start_transfer(&jobs, kind, &destination, conflict).await
+}
+
+/// Queue a move of files and folders into a directory.
+///
+/// Conflicting files are resolved by `conflict` (default "rename"). Returns
+/// the id of the job.
+#[tauri::command]
+pub async fn zmanager_start_move(
+    sources: Vec<String>,
+    destination: String,
+    conflict: Option<ConflictPolicy>,
+    app: AppHandle,
+) -> IpcResponse<String> {
+    let jobs = app.state::<Arc<JobsState>>();
+    tracing::debug!("start_move: {} items to {}", sources.len(), destination);
+
+    let destination = PathBuf::from(destination);
+    let kind = JobKind::Move {
+        sources: sources.iter().map(PathBuf::from).collect(),
+        destination: destination.clone(),
+    };
+    start_transfer(&jobs, kind, &destination, conflict).await
+}
+
+/// List queued, running and finished jobs, oldest first.
+#[tauri::command]
+pub async fn zmanager_job_list(app: AppHandle) -> IpcResponse<Vec<JobDto>> {
+    let jobs = app.state::<Arc<JobsState>>();
+    let mut infos = jobs.scheduler.list_jobs().await;
+    infos.sort_by_key(|info| info.id.0);
+
+    let mut list = Vec::with_capacity(infos.len());
+    for info in infos {
+        let kind = jobs
+            .scheduler
+            .job(info.id)
+            .await
+            .map_or("other", |job| kind_name(&job.kind));
+        list.push(JobDto::new(info, kind));
+    }
+    IpcResponse::success(list)
+}
+
+/// Pause a running job between files.
+#[tauri::command]
+pub async fn zmanager_job_pause(
+    job_id: String,
+    app: AppHandle,
+) -> IpcResponse<bool> {
+    let jobs = app.state::<Arc<JobsState>>();
+    match parse_job_id(&job_id) {
+        Some(id) => IpcResponse::success(jobs.scheduler.pause(id).await),
+        None => IpcResponse::failure(format!("Invalid job id: {job_id}")),
+    }
+}
+
+/// Resume a paused job.
+#[tauri::command]
+pub async fn zmanager_job_resume(
+    job_id: String,
+    app: AppHandle,
+) -> IpcResponse<bool> {
+    let jobs = app.state::<Arc<JobsState>>();
+    match parse_job_id(&job_id) {
+        Some(id) => IpcResponse::success(jobs.scheduler.resume(id).await),
+        None => IpcResponse::failure(format!("Invalid job id: {job_id}")),
+    }
+}
+
+/// Cancel a queued, running or paused job.
+#[tauri::command]
+pub async fn zmanager_job_cancel(
+    job_id: String,
+    app: AppHandle,
+) -> IpcResponse<bool> {
+    let jobs = app.state::<Arc<JobsState>>();
+    match parse_job_id(&job_id) {
+        Some(id) => IpcResponse::success(jobs.scheduler.cancel(id).await),
+        None => IpcResponse::failure(format!("Invalid job id: {job_id}")),
+    }
+}
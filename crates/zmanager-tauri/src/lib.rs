@@ -3,8 +3,20 @@
 //! Backend for the ZManager GUI built with Tauri v2.
 
 mod commands;
+mod jobs;
+mod notify;
+mod search;
+mod session;
+mod settings;
+mod size;
+mod taskbar;
+mod watch;
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+
+use tauri::Manager;
+use zmanager_core::{Config, Scheduler, SchedulerConfig};
+use zmanager_transfer_win::{IconCache, ThumbnailCache, DEFAULT_THUMBNAIL_CACHE_BYTES};
 
 /// Configure Tauri with ZManager commands.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -12,7 +24,12 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_drag::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(Mutex::new(commands::ClipboardState::default()))
+        .manage(Arc::new(IconCache::new()))
+        .manage(Arc::new(ThumbnailCache::default()))
+        .manage(Arc::new(size::SizeJobs::default()))
+        .manage(Arc::new(search::SearchJobs::default()))
         .invoke_handler(tauri::generate_handler![
             // Directory operations
             commands::zmanager_list_dir,
@@ -24,21 +41,92 @@ pub fn run() {
             commands::zmanager_create_folder,
             commands::zmanager_create_file,
             commands::zmanager_open_file,
+            commands::zmanager_reveal_in_explorer,
             commands::zmanager_get_properties,
+            commands::zmanager_set_properties,
+            size::zmanager_calculate_folder_size,
+            size::zmanager_cancel_folder_size,
+            // Recursive search
+            search::zmanager_search_start,
+            search::zmanager_search_results,
+            search::zmanager_search_cancel,
             // Favorites (Sprint 16)
             commands::zmanager_get_favorites,
             commands::zmanager_add_favorite,
             commands::zmanager_remove_favorite,
             commands::zmanager_reorder_favorites,
+            // Settings
+            settings::zmanager_get_config,
+            settings::zmanager_set_config,
+            // Session (window geometry, pane directories, sort)
+            session::zmanager_load_session,
+            session::zmanager_save_session,
             // Clipboard (Sprint 16)
             commands::zmanager_clipboard_copy,
             commands::zmanager_clipboard_cut,
             commands::zmanager_clipboard_get,
             commands::zmanager_clipboard_paste,
             commands::zmanager_clipboard_clear,
+            // Drag and drop
+            commands::zmanager_drop_files,
+            commands::zmanager_drag_out,
+            // Shell integration
+            commands::zmanager_shell_context_menu,
+            commands::zmanager_get_icon,
+            commands::zmanager_get_thumbnail,
+            // Transfer jobs
+            jobs::zmanager_start_copy,
+            jobs::zmanager_start_move,
+            jobs::zmanager_job_list,
+            jobs::zmanager_job_pause,
+            jobs::zmanager_job_resume,
+            jobs::zmanager_job_cancel,
+            // Directory watching
+            watch::zmanager_watch_dir,
+            watch::zmanager_unwatch_dir,
         ])
-        .setup(|_app| {
+        .setup(|app| {
             tracing::info!("ZManager GUI starting...");
+
+            if let Err(e) = session::restore_window(app.handle()) {
+                tracing::warn!("Failed to restore the window: {}", e);
+            }
+
+            // Copy and move jobs run on the core scheduler
+            let config = Config::load().unwrap_or_default();
+            let (scheduler, handle) = Scheduler::new(SchedulerConfig {
+                max_concurrent_jobs: config.operations.concurrency_for(false),
+                ..Default::default()
+            });
+            tauri::async_runtime::spawn(scheduler.run());
+            // Overall transfer progress shows on the taskbar button, and long
+            // transfers finishing in the background raise a toast
+            let taskbar = taskbar::bridge_taskbar(app.handle().clone(), handle.clone());
+            tauri::async_runtime::spawn(taskbar);
+            let notify = notify::bridge_notifications(
+                app.handle().clone(),
+                handle.clone(),
+                config.notifications,
+            );
+            tauri::async_runtime::spawn(notify);
+            let jobs = Arc::new(jobs::JobsState::new(handle));
+            let bridge = jobs::bridge_jobs(app.handle().clone(), Arc::clone(&jobs));
+            tauri::async_runtime::spawn(bridge);
+            app.manage(jobs);
+
+            // Panes refresh when the directories they show change
+            let watch = tauri::async_runtime::block_on(async { watch::WatchState::start() })?;
+            let watch = Arc::new(watch);
+            let bridge = watch::bridge_watcher(app.handle().clone(), Arc::clone(&watch));
+            tauri::async_runtime::spawn(bridge);
+            app.manage(watch);
+
+            // Keep the thumbnail cache from growing without bound
+            std::thread::spawn(|| {
+                if let Err(e) = ThumbnailCache::default().cleanup(DEFAULT_THUMBNAIL_CACHE_BYTES) {
+                    tracing::warn!("Failed to trim the thumbnail cache: {}", e);
+                }
+            });
             Ok(())
         })
         .run(tauri::generate_context!())
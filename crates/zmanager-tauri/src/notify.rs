@@ -0,0 +1,74 @@
+//! Toast notifications for finished jobs.
+//!
+//! A job that ran for a while and then finished or failed while the window
+//! wasn't focused gets a toast, so it isn't missed. The `[notifications]`
+//! config section turns them off or sets how long a job has to run first;
+//! it is read when the app starts.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::broadcast::error::RecvError;
+use zmanager_core::{JobKind, NotificationsConfig, SchedulerEvent, SchedulerHandle};
+
+use crate::session::MAIN_WINDOW;
+
+/// Check if the main window has focus.
+fn is_focused(app: &AppHandle) -> bool {
+    app.get_webview_window(MAIN_WINDOW)
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// Get the title of the toast for a job of `kind` that finished or failed.
+fn title(kind: &JobKind, failed: bool) -> String {
+    let name = match kind {
+        JobKind::Copy { .. } => "Copy",
+        JobKind::Move { .. } => "Move",
+        JobKind::Delete { .. } | JobKind::DeletePermanent { .. } => "Delete",
+        JobKind::CalculateSize { .. } => "Size calculation",
+        JobKind::Compress { .. } => "Compression",
+        JobKind::Extract { .. } => "Extraction",
+        JobKind::Checksum { .. } => "Checksum",
+        JobKind::Compare { .. } => "Comparison",
+        JobKind::ConvertText { .. } => "Conversion",
+    };
+    let outcome = if failed { "failed" } else { "finished" };
+    format!("{name} {outcome}")
+}
+
+/// Show a toast for each long job that finishes while the window is in the
+/// background. Runs for the lifetime of the app.
+pub async fn bridge_notifications(
+    app: AppHandle,
+    scheduler: SchedulerHandle,
+    notifications: NotificationsConfig,
+) {
+    let mut events = scheduler.subscribe();
+
+    loop {
+        let (id, error) = match events.recv().await {
+            Ok(SchedulerEvent::JobCompleted(id)) => (id, None),
+            Ok(SchedulerEvent::JobFailed(id, error)) => (id, Some(error)),
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        if is_focused(&app) {
+            continue;
+        }
+        let Some(job) = scheduler.job(id).await else {
+            continue;
+        };
+        if !job.running_time().is_some_and(|time| notifications.should_notify(time)) {
+            continue;
+        }
+
+        let title = title(&job.kind, error.is_some());
+        let body = match error {
+            None => job.kind.description(),
+            Some(error) => format!("{}: {}", job.kind.description(), error),
+        };
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            tracing::warn!("Failed to show a notification: {}", e);
+        }
+    }
+}
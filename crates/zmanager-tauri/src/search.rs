@@ -0,0 +1,220 @@
+//! Recursive search for the GUI.
+//!
+//! A search walks a folder tree with the core `search` on a blocking thread.
+//! Its matches reach the frontend in batches as `zmanager://search-results`
+//! events while the walk goes on, followed by one event carrying the outcome.
+//! The matches are also kept, so a view opened after the search started can
+//! fetch them with `zmanager_search_results`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use zmanager_core::{
+    search, CancellationToken, EntryMeta, JobId, SearchQuery, ZError, LISTING_BATCH_SIZE,
+};
+
+use crate::commands::IpcResponse;
+use crate::jobs::parse_job_id;
+
+/// Event carrying the matches of a search, and its outcome.
+pub const SEARCH_RESULTS_EVENT: &str = "zmanager://search-results";
+
+/// State of a search as the frontend knows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchStateDto {
+    Running,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+/// A search started for the frontend.
+struct SearchRun {
+    cancel: CancellationToken,
+    state: SearchStateDto,
+    /// Matches found so far.
+    matches: Vec<EntryMeta>,
+    error: Option<String>,
+}
+
+/// Searches run for the GUI, kept in app state.
+///
+/// A finished search keeps its matches until it is cancelled or another
+/// search starts.
+#[derive(Default)]
+pub struct SearchJobs {
+    searches: Mutex<HashMap<JobId, SearchRun>>,
+}
+
+impl SearchJobs {
+    /// Keep new matches of search `id`. Returns how many it has found, or
+    /// `None` once the search was cancelled.
+    fn record(&self, id: JobId, entries: &[EntryMeta]) -> Option<usize> {
+        let mut searches = self.searches.lock().unwrap();
+        let run = searches.get_mut(&id)?;
+        run.matches.extend_from_slice(entries);
+        Some(run.matches.len())
+    }
+
+    /// Note how search `id` ended. Returns how many matches it found.
+    fn finish(&self, id: JobId, state: SearchStateDto, error: Option<String>) -> usize {
+        let mut searches = self.searches.lock().unwrap();
+        let Some(run) = searches.get_mut(&id) else {
+            return 0;
+        };
+        run.state = state;
+        run.error = error;
+        run.matches.len()
+    }
+}
+
+/// Payload of [`SEARCH_RESULTS_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultsPayload {
+    pub job_id: String,
+    pub root: String,
+    pub state: SearchStateDto,
+    /// Matches found since the previous event.
+    pub entries: Vec<EntryMeta>,
+    /// Matches found so far.
+    pub match_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Matches of a search, as `zmanager_search_results` returns them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultsDto {
+    pub state: SearchStateDto,
+    pub entries: Vec<EntryMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run a search, emitting its matches as they are found and its outcome.
+async fn run_search(
+    app: AppHandle,
+    id: JobId,
+    root: String,
+    query: SearchQuery,
+    cancel: CancellationToken,
+    jobs: Arc<SearchJobs>,
+) {
+    let batch_app = app.clone();
+    let batch_root = root.clone();
+    let batch_jobs = Arc::clone(&jobs);
+    let result = tokio::task::spawn_blocking(move || {
+        search(&batch_root, &query, LISTING_BATCH_SIZE, &cancel, |entries| {
+            let Some(match_count) = batch_jobs.record(id, &entries) else {
+                return;
+            };
+            let payload = SearchResultsPayload {
+                job_id: id.0.to_string(),
+                root: batch_root.clone(),
+                state: SearchStateDto::Running,
+                entries,
+                match_count,
+                error: None,
+            };
+            let _ = batch_app.emit(SEARCH_RESULTS_EVENT, payload);
+        })
+    })
+    .await;
+
+    let (state, error) = match result {
+        Ok(Ok(_)) => (SearchStateDto::Completed, None),
+        Ok(Err(ZError::Cancelled)) => (SearchStateDto::Canceled, None),
+        Ok(Err(e)) => (SearchStateDto::Failed, Some(e.to_string())),
+        Err(e) => (SearchStateDto::Failed, Some(e.to_string())),
+    };
+    let match_count = jobs.finish(id, state, error.clone());
+    let payload = SearchResultsPayload {
+        job_id: id.0.to_string(),
+        root,
+        state,
+        entries: Vec::new(),
+        match_count,
+        error,
+    };
+    let _ = app.emit(SEARCH_RESULTS_EVENT, payload);
+}
+
+/// Start searching a folder and everything below it in the background.
+///
+/// Matches and the outcome come as `zmanager://search-results` events.
+/// Returns the id of the search.
+#[tauri::command]
+pub fn zmanager_search_start(
+    root: String,
+    query: SearchQuery,
+    app: AppHandle,
+    jobs: tauri::State<'_, Arc<SearchJobs>>,
+) -> IpcResponse<String> {
+    tracing::debug!("search_start: {}", root);
+
+    if !PathBuf::from(&root).is_dir() {
+        return IpcResponse::failure(format!("Not a directory: {}", root));
+    }
+
+    let id = JobId::new();
+    let cancel = CancellationToken::new();
+    {
+        let mut searches = jobs.searches.lock().unwrap();
+        searches.retain(|_, run| run.state == SearchStateDto::Running);
+        searches.insert(
+            id,
+            SearchRun {
+                cancel: cancel.clone(),
+                state: SearchStateDto::Running,
+                matches: Vec::new(),
+                error: None,
+            },
+        );
+    }
+    let jobs = Arc::clone(jobs.inner());
+    tauri::async_runtime::spawn(run_search(app, id, root, query, cancel, jobs));
+    IpcResponse::success(id.0.to_string())
+}
+
+/// The matches a search has found so far, and its state.
+#[tauri::command]
+pub fn zmanager_search_results(
+    job_id: String,
+    jobs: tauri::State<'_, Arc<SearchJobs>>,
+) -> IpcResponse<SearchResultsDto> {
+    let Some(id) = parse_job_id(&job_id) else {
+        return IpcResponse::failure(format!("Invalid job id: {job_id}"));
+    };
+    match jobs.searches.lock().unwrap().get(&id) {
+        Some(run) => IpcResponse::success(SearchResultsDto {
+            state: run.state,
+            entries: run.matches.clone(),
+            error: run.error.clone(),
+        }),
+        None => IpcResponse::failure(format!("No search with id {job_id}")),
+    }
+}
+
+/// Cancel a search and discard its matches. Returns whether it was running.
+#[tauri::command]
+pub fn zmanager_search_cancel(
+    job_id: String,
+    jobs: tauri::State<'_, Arc<SearchJobs>>,
+) -> IpcResponse<bool> {
+    let Some(id) = parse_job_id(&job_id) else {
+        return IpcResponse::failure(format!("Invalid job id: {job_id}"));
+    };
+    match jobs.searches.lock().unwrap().remove(&id) {
+        Some(run) => {
+            run.cancel.cancel();
+            IpcResponse::success(run.state == SearchStateDto::Running)
+        }
+        None => IpcResponse::success(false),
+    }
+}
@@ -0,0 +1,150 @@
+//! Session state for the GUI.
+//!
+//! The pane directories, sort and window geometry are kept in the config's
+//! `SessionState`, the same one the TUI saves, so either frontend picks up
+//! where the other left off. The frontend saves them when the window closes
+//! and loads them before the panes first list their directories; the window
+//! geometry is restored here as the app starts.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window};
+use zmanager_core::{Config, SessionState, SortSettings, WindowState, ZResult};
+
+use crate::commands::IpcResponse;
+
+/// Label of the main window.
+pub(crate) const MAIN_WINDOW: &str = "main";
+
+/// Session state as the frontend knows it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionDto {
+    pub left_dir: Option<String>,
+    pub right_dir: Option<String>,
+    pub sort: Option<SortSettings>,
+    pub right_pane_active: bool,
+    /// Window geometry; filled in by the backend when saving.
+    pub window: Option<WindowState>,
+}
+
+impl From<SessionState> for SessionDto {
+    fn from(session: SessionState) -> Self {
+        let dir = |dir: Option<PathBuf>| dir.map(|dir| dir.to_string_lossy().to_string());
+        Self {
+            left_dir: dir(session.last_left_dir),
+            right_dir: dir(session.last_right_dir),
+            sort: session.last_sort,
+            right_pane_active: session.right_pane_active,
+            window: session.window_state,
+        }
+    }
+}
+
+/// Geometry of `window` to restore on the next start.
+///
+/// A maximized window keeps the position and size it had before it was
+/// maximized, so un-maximizing after a restart brings it back.
+fn window_state<R: Runtime>(
+    window: &Window<R>,
+    previous: Option<WindowState>,
+) -> Option<WindowState> {
+    if window.is_maximized().ok()? {
+        let mut state = previous?;
+        state.maximized = true;
+        return Some(state);
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+    })
+}
+
+/// Check if the top-left corner of `state` lies on one of the monitors, so
+/// a window saved on a since-unplugged monitor isn't restored off screen.
+fn is_on_screen<R: Runtime>(window: &WebviewWindow<R>, state: &WindowState) -> bool {
+    window.available_monitors().is_ok_and(|monitors| {
+        monitors.iter().any(|monitor| {
+            let (origin, size) = (monitor.position(), monitor.size());
+            (origin.x..origin.x + size.width as i32).contains(&state.x)
+                && (origin.y..origin.y + size.height as i32).contains(&state.y)
+        })
+    })
+}
+
+/// Move and size the main window as it was when the last session ended.
+pub fn restore_window<R: Runtime, M: Manager<R>>(manager: &M) -> ZResult<()> {
+    let config = Config::load()?;
+    if !config.general.restore_session {
+        return Ok(());
+    }
+    let Some(state) = config.session.and_then(|session| session.window_state) else {
+        return Ok(());
+    };
+    let Some(window) = manager.get_webview_window(MAIN_WINDOW) else {
+        return Ok(());
+    };
+
+    if state.width > 0 && state.height > 0 && is_on_screen(&window, &state) {
+        let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+        let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+    Ok(())
+}
+
+/// Get the session state saved when the GUI or TUI last closed.
+///
+/// Empty when restoring sessions is turned off.
+#[tauri::command]
+pub async fn zmanager_load_session() -> IpcResponse<SessionDto> {
+    tracing::debug!("load_session called");
+
+    match Config::load() {
+        Ok(config) if !config.general.restore_session => {
+            IpcResponse::success(SessionDto::default())
+        }
+        Ok(config) => IpcResponse::success(config.session.unwrap_or_default().into()),
+        Err(e) => IpcResponse::failure(e.to_string()),
+    }
+}
+
+/// Save the pane directories and sort, along with the window's geometry.
+///
+/// Does nothing when restoring sessions is turned off. Returns whether the
+/// session was saved.
+#[tauri::command]
+pub async fn zmanager_save_session(session: SessionDto, window: Window) -> IpcResponse<bool> {
+    tracing::debug!("save_session called");
+
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => return IpcResponse::failure(e.to_string()),
+    };
+    if !config.general.restore_session {
+        return IpcResponse::success(false);
+    }
+
+    let previous = config.session.take().unwrap_or_default();
+    let geometry = window_state(&window, previous.window_state.clone());
+    config.session = Some(SessionState {
+        last_left_dir: session.left_dir.map(PathBuf::from),
+        last_right_dir: session.right_dir.map(PathBuf::from),
+        last_sort: session.sort,
+        right_pane_active: session.right_pane_active,
+        window_state: geometry.or(previous.window_state),
+        ..previous
+    });
+    match config.save() {
+        Ok(()) => IpcResponse::success(true),
+        Err(e) => IpcResponse::failure(format!("Failed to save config: {}", e)),
+    }
+}
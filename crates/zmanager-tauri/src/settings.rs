@@ -0,0 +1,123 @@
+//! Configuration commands for the GUI settings page.
+//!
+//! The frontend reads the whole `Config` and changes it with JSON merge
+//! patches (RFC 7396): objects are merged section by section, any other
+//! value replaces the setting, and `null` resets it to its default. A patch
+//! holding the whole config replaces it. Patched configs are checked the
+//! way a config file is before they're saved, so a bad value comes back as
+//! an error instead of ending up in `config.toml`.
+
+use serde_json::{Map, Value};
+use zmanager_core::Config;
+
+use crate::commands::IpcResponse;
+
+/// Apply a JSON merge patch to `target`.
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Check if a patch sets anything rather than only resetting settings.
+fn sets_value(patch: &Value) -> bool {
+    match patch {
+        Value::Null => false,
+        Value::Object(patch) => patch.values().any(sets_value),
+        _ => true,
+    }
+}
+
+/// Find a setting in `patch` that didn't survive into `config`, which is
+/// what happens to misspelled or unknown keys.
+fn unknown_setting(patch: &Value, config: &Value, prefix: &str) -> Option<String> {
+    let Value::Object(patch) = patch else {
+        return None;
+    };
+    for (key, value) in patch {
+        if !sets_value(value) {
+            continue;
+        }
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match config.get(key) {
+            Some(kept) => {
+                if let Some(unknown) = unknown_setting(value, kept, &name) {
+                    return Some(unknown);
+                }
+            }
+            None => return Some(name),
+        }
+    }
+    None
+}
+
+/// Apply `patch` to `config`, checking the result.
+fn patch_config(config: &Config, patch: Value) -> Result<Config, String> {
+    let mut value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    merge_patch(&mut value, patch.clone());
+
+    let patched: Config =
+        serde_json::from_value(value).map_err(|e| format!("Invalid setting: {e}"))?;
+    let kept = serde_json::to_value(&patched).map_err(|e| e.to_string())?;
+    if let Some(name) = unknown_setting(&patch, &kept, "") {
+        return Err(format!("Unknown setting: {name}"));
+    }
+    patched.validate().map_err(|e| e.to_string())?;
+    Ok(patched)
+}
+
+/// Get the whole configuration.
+#[tauri::command]
+pub async fn zmanager_get_config() -> IpcResponse<Config> {
+    tracing::debug!("get_config called");
+
+    match Config::load() {
+        Ok(config) => IpcResponse::success(config),
+        Err(e) => {
+            tracing::error!("Failed to load config: {}", e);
+            IpcResponse::failure(e.to_string())
+        }
+    }
+}
+
+/// Change the configuration with a JSON merge patch and save it.
+///
+/// Nothing is saved if the patched configuration is invalid. Returns the
+/// configuration as saved.
+#[tauri::command]
+pub async fn zmanager_set_config(patch: Value) -> IpcResponse<Config> {
+    tracing::debug!("set_config: {}", patch);
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => return IpcResponse::failure(e.to_string()),
+    };
+    let patched = match patch_config(&config, patch) {
+        Ok(patched) => patched,
+        Err(e) => {
+            tracing::warn!("Rejected config change: {}", e);
+            return IpcResponse::failure(e);
+        }
+    };
+    match patched.save() {
+        Ok(()) => IpcResponse::success(patched),
+        Err(e) => IpcResponse::failure(format!("Failed to save config: {}", e)),
+    }
+}
@@ -0,0 +1,153 @@
+//! Folder size calculation for the GUI.
+//!
+//! Sizes are calculated by the `SizeExecutor` in the background, outside the
+//! transfer queue, and reach the frontend as `zmanager://folder-size` events
+//! carrying the running totals, so the properties panel fills in while the
+//! walk is still going.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use zmanager_core::{CancellationToken, FolderStats, Job, JobId, JobKind, ZError};
+use zmanager_transfer_win::{SizeEvent, SizeExecutor};
+
+use crate::commands::IpcResponse;
+use crate::jobs::parse_job_id;
+
+/// Event carrying a size calculation's running totals.
+pub const FOLDER_SIZE_EVENT: &str = "zmanager://folder-size";
+
+/// Size calculations running for the GUI, kept in app state.
+#[derive(Default)]
+pub struct SizeJobs {
+    /// Cancellation of each running calculation.
+    running: Mutex<HashMap<JobId, CancellationToken>>,
+}
+
+/// State of a size calculation as the frontend knows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeStateDto {
+    Running,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+/// Payload of [`FOLDER_SIZE_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSizePayload {
+    pub job_id: String,
+    pub path: String,
+    pub state: SizeStateDto,
+    pub total_size: u64,
+    pub file_count: usize,
+    pub folder_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FolderSizePayload {
+    fn new(job_id: JobId, path: &str, state: SizeStateDto, stats: &FolderStats) -> Self {
+        Self {
+            job_id: job_id.0.to_string(),
+            path: path.to_string(),
+            state,
+            total_size: stats.total_size,
+            file_count: stats.file_count,
+            folder_count: stats.folder_count,
+            error: None,
+        }
+    }
+}
+
+/// Run a size job, emitting its running totals and outcome.
+async fn run_size_job(app: AppHandle, job: Job, path: String, jobs: Arc<SizeJobs>) {
+    let executor = SizeExecutor::new();
+    let mut events = executor.subscribe();
+    let progress_app = app.clone();
+    let progress_path = path.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(SizeEvent::Progress { job_id, stats, .. }) => {
+                    let payload = FolderSizePayload::new(
+                        job_id,
+                        &progress_path,
+                        SizeStateDto::Running,
+                        &stats,
+                    );
+                    let _ = progress_app.emit(FOLDER_SIZE_EVENT, payload);
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let result = executor.execute_job(&job, job.cancellation.clone()).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+    jobs.running.lock().unwrap().remove(&job.id);
+
+    let payload = match result {
+        Ok(stats) => FolderSizePayload::new(job.id, &path, SizeStateDto::Completed, &stats),
+        Err(ZError::Cancelled) => {
+            FolderSizePayload::new(job.id, &path, SizeStateDto::Canceled, &FolderStats::default())
+        }
+        Err(e) => FolderSizePayload {
+            error: Some(e.to_string()),
+            ..FolderSizePayload::new(job.id, &path, SizeStateDto::Failed, &FolderStats::default())
+        },
+    };
+    let _ = app.emit(FOLDER_SIZE_EVENT, payload);
+}
+
+/// Start calculating the size of a folder in the background.
+///
+/// Running totals and the result come as `zmanager://folder-size` events.
+/// Returns the id of the calculation.
+#[tauri::command]
+pub fn zmanager_calculate_folder_size(
+    path: String,
+    app: AppHandle,
+    jobs: tauri::State<'_, Arc<SizeJobs>>,
+) -> IpcResponse<String> {
+    tracing::debug!("calculate_folder_size: {}", path);
+
+    let folder = PathBuf::from(&path);
+    if !folder.is_dir() {
+        return IpcResponse::failure(format!("Not a directory: {}", path));
+    }
+
+    let job = Job::new(JobKind::CalculateSize { path: folder });
+    let id = job.id;
+    jobs.running.lock().unwrap().insert(id, job.cancellation.clone());
+    let jobs = Arc::clone(jobs.inner());
+    tauri::async_runtime::spawn(run_size_job(app, job, path, jobs));
+    IpcResponse::success(id.0.to_string())
+}
+
+/// Cancel a running size calculation. Returns whether it was running.
+#[tauri::command]
+pub fn zmanager_cancel_folder_size(
+    job_id: String,
+    jobs: tauri::State<'_, Arc<SizeJobs>>,
+) -> IpcResponse<bool> {
+    let Some(id) = parse_job_id(&job_id) else {
+        return IpcResponse::failure(format!("Invalid job id: {job_id}"));
+    };
+    match jobs.running.lock().unwrap().get(&id) {
+        Some(token) => {
+            token.cancel();
+            IpcResponse::success(true)
+        }
+        None => IpcResponse::success(false),
+    }
+}
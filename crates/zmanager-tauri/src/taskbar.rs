@@ -0,0 +1,117 @@
+//! Transfer progress on the taskbar button.
+//!
+//! Overall progress of the transfer queue shows on the main window's
+//! taskbar button, which Tauri draws through `ITaskbarList3` on Windows. The
+//! bar is green while transfers run, yellow once every unfinished one is
+//! paused and red after one fails; a failure stays up until the next
+//! transfer is queued, so it isn't missed when it ends the queue.
+
+use std::time::{Duration, Instant};
+
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use zmanager_core::{JobInfo, JobState, SchedulerEvent, SchedulerHandle};
+
+use crate::session::MAIN_WINDOW;
+
+/// Least time between two progress updates of the taskbar button.
+const UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What the taskbar button shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskbarProgress {
+    /// No bar.
+    Idle,
+    /// Transfers running, with their completion when it's known.
+    Running(Option<u64>),
+    /// Every unfinished transfer is paused.
+    Paused(Option<u64>),
+    /// A transfer failed.
+    Error(Option<u64>),
+}
+
+impl TaskbarProgress {
+    fn state(self) -> ProgressBarState {
+        let (status, progress) = match self {
+            Self::Idle => (ProgressBarStatus::None, None),
+            Self::Running(None) => (ProgressBarStatus::Indeterminate, None),
+            Self::Running(progress) => (ProgressBarStatus::Normal, progress),
+            Self::Paused(progress) => (ProgressBarStatus::Paused, progress.or(Some(0))),
+            Self::Error(progress) => (ProgressBarStatus::Error, progress.or(Some(100))),
+        };
+        ProgressBarState {
+            status: Some(status),
+            progress,
+        }
+    }
+}
+
+/// Overall completion of the unfinished jobs, weighted by bytes across the
+/// ones that know their size.
+fn completion(jobs: &[&JobInfo]) -> Option<u64> {
+    let (done, total) = jobs
+        .iter()
+        .filter_map(|job| job.total_bytes.map(|total| (job.bytes_done.min(total), total)))
+        .fold((0u64, 0u64), |(done, total), (d, t)| (done + d, total + t));
+    (total > 0).then(|| done * 100 / total)
+}
+
+/// What the taskbar button shows for `jobs`.
+fn taskbar_progress(jobs: &[JobInfo], failed: bool) -> TaskbarProgress {
+    let unfinished: Vec<&JobInfo> = jobs.iter().filter(|job| !job.state.is_terminal()).collect();
+    let progress = completion(&unfinished);
+    if failed {
+        TaskbarProgress::Error(progress)
+    } else if unfinished.is_empty() {
+        TaskbarProgress::Idle
+    } else if unfinished.iter().all(|job| job.state == JobState::Paused) {
+        TaskbarProgress::Paused(progress)
+    } else {
+        TaskbarProgress::Running(progress)
+    }
+}
+
+/// Keep the taskbar button's progress in step with the scheduler's jobs.
+/// Runs for the lifetime of the app.
+pub async fn bridge_taskbar(app: AppHandle, scheduler: SchedulerHandle) {
+    let mut events = scheduler.subscribe();
+    let mut failed = false;
+    let mut shown = TaskbarProgress::Idle;
+    let mut last_update = Instant::now();
+
+    loop {
+        match events.recv().await {
+            Ok(SchedulerEvent::JobProgress(..)) => {
+                if last_update.elapsed() < UPDATE_INTERVAL {
+                    continue;
+                }
+            }
+            Ok(SchedulerEvent::JobAdded(_)) => {
+                // A new transfer clears the failure from the last queue
+                if matches!(shown, TaskbarProgress::Error(_))
+                    && scheduler.list_jobs().await.iter().all(|job| {
+                        job.state.is_terminal() || job.state == JobState::Pending
+                    })
+                {
+                    failed = false;
+                }
+            }
+            Ok(SchedulerEvent::JobFailed(..)) => failed = true,
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => break,
+        }
+
+        last_update = Instant::now();
+        let progress = taskbar_progress(&scheduler.list_jobs().await, failed);
+        if progress == shown {
+            continue;
+        }
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+            if let Err(e) = window.set_progress_bar(progress.state()) {
+                tracing::debug!("Failed to set the taskbar progress: {}", e);
+            }
+        }
+        shown = progress;
+    }
+}
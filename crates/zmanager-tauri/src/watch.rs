@@ -0,0 +1,169 @@
+//! Directory watching for the GUI.
+//!
+//! The panes ask for the directories they show to be watched, and the core
+//! `DirectoryWatcher` reports their debounced changes to the frontend as
+//! `zmanager://dir-changed` events, so listings refresh on their own like
+//! they do in the TUI.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use zmanager_core::{DirectoryWatcher, WatchEventKind, ZResult};
+
+use crate::commands::IpcResponse;
+
+/// Event carrying a change in a watched directory.
+pub const DIR_CHANGED_EVENT: &str = "zmanager://dir-changed";
+
+/// A directory being watched for the frontend.
+struct WatchedDir {
+    /// Path the directory was first asked for by, as the frontend wrote it.
+    path: String,
+    /// Number of panes watching the directory.
+    count: usize,
+}
+
+/// Directories watched for the GUI, kept in app state.
+pub struct WatchState {
+    watcher: DirectoryWatcher,
+    /// Watched directories by canonical path, as the watcher reports them.
+    watched: Mutex<HashMap<PathBuf, WatchedDir>>,
+}
+
+impl WatchState {
+    /// Start watching for the GUI. Must be called within the async runtime.
+    pub fn start() -> ZResult<Self> {
+        let mut watcher = DirectoryWatcher::new()?;
+        watcher.start()?;
+        Ok(Self {
+            watcher,
+            watched: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Watch `path` for one more pane.
+    fn watch(&self, path: &str) -> ZResult<()> {
+        let dir = Path::new(path);
+        let key = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let mut watched = self.watched.lock().unwrap();
+        if let Some(entry) = watched.get_mut(&key) {
+            entry.count += 1;
+            return Ok(());
+        }
+        self.watcher.watch(dir)?;
+        watched.insert(
+            key,
+            WatchedDir {
+                path: path.to_string(),
+                count: 1,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stop watching `path` for one pane; the directory stays watched while
+    /// other panes still show it. Returns whether it was being watched.
+    fn unwatch(&self, path: &str) -> ZResult<bool> {
+        let dir = Path::new(path);
+        let key = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let mut watched = self.watched.lock().unwrap();
+        let Some(entry) = watched.get_mut(&key) else {
+            return Ok(false);
+        };
+        entry.count -= 1;
+        if entry.count == 0 {
+            watched.remove(&key);
+            self.watcher.unwatch(&key)?;
+        }
+        Ok(true)
+    }
+}
+
+/// Payload of [`DIR_CHANGED_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirChangedPayload {
+    /// The watched directory, as the frontend asked for it.
+    pub path: String,
+    /// "create", "modify", "delete", "rename" or "change"
+    pub kind: &'static str,
+    /// Paths that changed, when known.
+    pub affected_paths: Vec<String>,
+}
+
+/// Frontend name of a change's kind.
+fn kind_name(kind: WatchEventKind) -> &'static str {
+    match kind {
+        WatchEventKind::Created => "create",
+        WatchEventKind::Modified => "modify",
+        WatchEventKind::Deleted => "delete",
+        WatchEventKind::Renamed => "rename",
+        WatchEventKind::Changed => "change",
+    }
+}
+
+/// Forward changes in watched directories to the frontend. Runs for the
+/// lifetime of the app.
+pub async fn bridge_watcher(app: AppHandle, state: Arc<WatchState>) {
+    let mut events = state.watcher.subscribe();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+
+        let path = match state.watched.lock().unwrap().get(&event.directory) {
+            Some(entry) => entry.path.clone(),
+            // Unwatched while the change was being debounced
+            None => continue,
+        };
+        let payload = DirChangedPayload {
+            path,
+            kind: kind_name(event.kind),
+            affected_paths: event
+                .paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        };
+        let _ = app.emit(DIR_CHANGED_EVENT, payload);
+    }
+}
+
+/// Watch a directory, emitting `zmanager://dir-changed` when its entries
+/// change. Each call must be matched by a call to `zmanager_unwatch_dir`.
+#[tauri::command]
+pub fn zmanager_watch_dir(
+    path: String,
+    watch: tauri::State<'_, Arc<WatchState>>,
+) -> IpcResponse<bool> {
+    tracing::debug!("watch_dir: {}", path);
+
+    match watch.watch(&path) {
+        Ok(()) => IpcResponse::success(true),
+        Err(e) => {
+            tracing::warn!("Cannot watch {}: {}", path, e);
+            IpcResponse::failure(e.to_string())
+        }
+    }
+}
+
+/// Stop watching a directory. Returns whether it was being watched.
+#[tauri::command]
+pub fn zmanager_unwatch_dir(
+    path: String,
+    watch: tauri::State<'_, Arc<WatchState>>,
+) -> IpcResponse<bool> {
+    tracing::debug!("unwatch_dir: {}", path);
+
+    match watch.unwatch(&path) {
+        Ok(was_watched) => IpcResponse::success(was_watched),
+        Err(e) => IpcResponse::failure(e.to_string()),
+    }
+}
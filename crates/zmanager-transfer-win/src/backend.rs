@@ -0,0 +1,415 @@
+//! Transfer engines behind a common interface.
+//!
+//! A [`TransferBackend`] copies one file with progress, pausing, throttling
+//! and cancellation, and moves, deletes and looks up files. The executors
+//! work through one, so the engine can be swapped without touching them:
+//!
+//! - [`NativeBackend`] uses `CopyFileExW` and only exists on Windows
+//! - [`PortableBackend`] copies in chunks through `std::fs` on any platform
+//! - [`ResumableBackend`] and [`UnbufferedBackend`] wrap the engines for
+//!   large and huge files
+//!
+//! [`default_backend`] picks the native engine where there is one, which is
+//! what lets the transfer engine, and the TUI on top of it, run on Linux and
+//! macOS.
+//!
+//! Other backends (say, for a remote server) go in a [`BackendRegistry`],
+//! which picks one per job from the paths the job involves. Jobs no
+//! registered backend takes run on the default engine. A backend whose
+//! paths aren't on the local file system also lists their trees and creates
+//! their directories, so folder transfers can be planned and run on it.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use zmanager_core::{
+    extended_path, get_entry_meta, remove_link, CancellationToken, EntryMeta, PauseToken,
+    SpeedLimit, ZError, ZResult,
+};
+
+use crate::copy::ProgressCallback;
+use crate::portable::copy_file_portable;
+use crate::resume::copy_file_resume;
+use crate::unbuffered::copy_file_unbuffered;
+
+/// An engine that copies, moves and deletes single files.
+///
+/// Only copying has to be implemented; everything else defaults to
+/// `std::fs`.
+pub trait TransferBackend: fmt::Debug + Send + Sync {
+    /// Short name of the engine, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Check if the backend should run a job copying or moving `sources`
+    /// into `destination`. Only matters for registered backends.
+    fn handles(&self, _sources: &[PathBuf], _destination: &Path) -> bool {
+        true
+    }
+
+    /// Copy `source` to `destination`, returning the bytes copied.
+    ///
+    /// Fails with `AlreadyExists` if the destination exists and `overwrite`
+    /// is false, and with `Cancelled` once `cancel_token` is cancelled.
+    #[allow(clippy::too_many_arguments)]
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64>;
+
+    /// Move a file or directory in one step, without copying it. Fails
+    /// when that isn't possible, e.g. across volumes, leaving the caller to
+    /// copy and delete instead.
+    fn move_file(&self, source: &Path, destination: &Path) -> ZResult<()> {
+        if extended_path(destination).exists() {
+            return Err(ZError::AlreadyExists {
+                path: destination.to_path_buf(),
+            });
+        }
+        std::fs::rename(extended_path(source), extended_path(destination))
+            .map_err(|e| ZError::io(source, e))
+    }
+
+    /// Look up what's at `path`, without following a link there. `None` if
+    /// there's nothing.
+    fn entry(&self, path: &Path) -> Option<EntryMeta> {
+        get_entry_meta(path).ok()
+    }
+
+    /// List `path` and, for a directory, everything below it, each directory
+    /// before its contents. `None` for a path on the local file system, which
+    /// the transfer plan walks itself.
+    fn walk(&self, _path: &Path) -> Option<ZResult<Vec<EntryMeta>>> {
+        None
+    }
+
+    /// Create a directory and any missing parents.
+    fn create_dir(&self, path: &Path) -> ZResult<()> {
+        std::fs::create_dir_all(extended_path(path)).map_err(|e| ZError::io(path, e))
+    }
+
+    /// Delete a file, a link (not its target) or an empty directory.
+    fn delete(&self, path: &Path) -> ZResult<()> {
+        let os_path = extended_path(path);
+        let meta = std::fs::symlink_metadata(&os_path).map_err(|e| ZError::from_io(path, e))?;
+        if meta.is_symlink() {
+            return remove_link(path);
+        }
+        let result = if meta.is_dir() {
+            std::fs::remove_dir(&os_path)
+        } else {
+            std::fs::remove_file(&os_path)
+        };
+        result.map_err(|e| ZError::from_io(path, e))
+    }
+}
+
+/// Copies through `CopyFileExW`.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeBackend;
+
+#[cfg(windows)]
+impl TransferBackend for NativeBackend {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        crate::copy::copy_file_ex(
+            source,
+            destination,
+            overwrite,
+            cancel_token,
+            pause_token,
+            speed_limit,
+            progress_callback,
+        )
+    }
+}
+
+/// Copies in chunks through `std::fs`; see [`copy_file_portable`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PortableBackend;
+
+impl TransferBackend for PortableBackend {
+    fn name(&self) -> &'static str {
+        "portable"
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        copy_file_portable(
+            source,
+            destination,
+            overwrite,
+            cancel_token,
+            pause_token,
+            speed_limit,
+            progress_callback,
+        )
+    }
+}
+
+/// Copies resumably; see [`copy_file_resume`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResumableBackend;
+
+impl TransferBackend for ResumableBackend {
+    fn name(&self) -> &'static str {
+        "resumable"
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        copy_file_resume(
+            source,
+            destination,
+            overwrite,
+            cancel_token,
+            pause_token,
+            speed_limit,
+            progress_callback,
+        )
+    }
+}
+
+/// Copies without the system file cache; see [`copy_file_unbuffered`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnbufferedBackend;
+
+impl TransferBackend for UnbufferedBackend {
+    fn name(&self) -> &'static str {
+        "unbuffered"
+    }
+
+    fn copy_file(
+        &self,
+        source: &Path,
+        destination: &Path,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ZResult<u64> {
+        copy_file_unbuffered(
+            source,
+            destination,
+            overwrite,
+            cancel_token,
+            pause_token,
+            speed_limit,
+            progress_callback,
+        )
+    }
+}
+
+/// The engine for ordinary copies on this platform: `CopyFileExW` on
+/// Windows, chunked `std::fs` copies elsewhere.
+pub fn default_backend() -> Arc<dyn TransferBackend> {
+    #[cfg(windows)]
+    {
+        Arc::new(NativeBackend)
+    }
+    #[cfg(not(windows))]
+    {
+        Arc::new(PortableBackend)
+    }
+}
+
+/// The backends transfers can run on, and which one a job gets.
+#[derive(Debug, Clone)]
+pub struct BackendRegistry {
+    /// Engine for jobs no registered backend takes.
+    default: Arc<dyn TransferBackend>,
+    /// Registered backends, in the order they're tried.
+    backends: Vec<Arc<dyn TransferBackend>>,
+}
+
+impl BackendRegistry {
+    /// Create a registry falling back to `default`.
+    pub fn new(default: Arc<dyn TransferBackend>) -> Self {
+        Self {
+            default,
+            backends: Vec::new(),
+        }
+    }
+
+    /// Add a backend, tried after the ones registered before it.
+    pub fn register(mut self, backend: Arc<dyn TransferBackend>) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    /// The engine for jobs no registered backend takes.
+    pub fn default_backend(&self) -> &Arc<dyn TransferBackend> {
+        &self.default
+    }
+
+    /// The first registered backend that handles a job on `sources` and
+    /// `destination`, or `None` if the job belongs on the default engine.
+    pub fn select(
+        &self,
+        sources: &[PathBuf],
+        destination: &Path,
+    ) -> Option<Arc<dyn TransferBackend>> {
+        self.backends
+            .iter()
+            .find(|backend| backend.handles(sources, destination))
+            .cloned()
+    }
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        Self::new(default_backend())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backends_copy_alike() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.txt");
+        fs::write(&source, b"the same bytes every way").unwrap();
+
+        let backends: [Arc<dyn TransferBackend>; 4] = [
+            default_backend(),
+            Arc::new(PortableBackend),
+            Arc::new(ResumableBackend),
+            Arc::new(UnbufferedBackend),
+        ];
+        for (i, backend) in backends.iter().enumerate() {
+            let dest = temp.path().join(i.to_string()).join("dest.txt");
+            let copy = |overwrite| {
+                backend.copy_file(
+                    &source,
+                    &dest,
+                    overwrite,
+                    CancellationToken::new(),
+                    PauseToken::new(),
+                    SpeedLimit::new(),
+                    None,
+                )
+            };
+
+            assert_eq!(copy(false).unwrap(), 24, "{}", backend.name());
+            assert_eq!(fs::read(&dest).unwrap(), fs::read(&source).unwrap());
+            assert!(matches!(copy(false), Err(ZError::AlreadyExists { .. })));
+            assert_eq!(copy(true).unwrap(), 24);
+        }
+    }
+
+    /// Handles paths under one directory, as a remote backend would.
+    #[derive(Debug)]
+    struct UnderRoot(PathBuf);
+
+    impl TransferBackend for UnderRoot {
+        fn name(&self) -> &'static str {
+            "under-root"
+        }
+
+        fn handles(&self, sources: &[PathBuf], destination: &Path) -> bool {
+            destination.starts_with(&self.0) && sources.iter().all(|s| s.starts_with(&self.0))
+        }
+
+        fn copy_file(
+            &self,
+            source: &Path,
+            destination: &Path,
+            overwrite: bool,
+            cancel_token: CancellationToken,
+            pause_token: PauseToken,
+            speed_limit: SpeedLimit,
+            progress_callback: Option<ProgressCallback>,
+        ) -> ZResult<u64> {
+            PortableBackend.copy_file(
+                source,
+                destination,
+                overwrite,
+                cancel_token,
+                pause_token,
+                speed_limit,
+                progress_callback,
+            )
+        }
+    }
+
+    #[test]
+    fn test_registry_selects_by_paths() {
+        let remote = PathBuf::from("/mnt/remote");
+        let registry = BackendRegistry::default().register(Arc::new(UnderRoot(remote.clone())));
+
+        let inside = [remote.join("a.txt"), remote.join("b")];
+        let selected = registry.select(&inside, &remote.join("copies"));
+        assert_eq!(selected.map(|backend| backend.name()), Some("under-root"));
+
+        // Every path has to be handled, the destination included
+        let mixed = [remote.join("a.txt"), PathBuf::from("/home/a.txt")];
+        assert!(registry.select(&mixed, &remote).is_none());
+        assert!(registry.select(&inside, Path::new("/home")).is_none());
+    }
+
+    #[test]
+    fn test_default_move_and_delete() {
+        let temp = TempDir::new().unwrap();
+        let backend = default_backend();
+        let dir = temp.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("file.txt"), "content").unwrap();
+
+        let moved = temp.path().join("moved");
+        backend.move_file(&dir, &moved).unwrap();
+        assert!(backend.entry(&dir).is_none());
+        assert!(backend.entry(&moved).unwrap().is_directory());
+        assert!(backend.walk(&moved).is_none());
+        assert!(!dir.exists());
+        assert!(matches!(
+            backend.move_file(&moved, temp.path()),
+            Err(ZError::AlreadyExists { .. })
+        ));
+
+        // A directory only goes once it's empty
+        assert!(backend.delete(&moved).is_err());
+        backend.delete(&moved.join("file.txt")).unwrap();
+        backend.delete(&moved).unwrap();
+        assert!(!moved.exists());
+    }
+}
@@ -0,0 +1,38 @@
+//! Helper that carries out an elevation manifest with administrator rights.
+//!
+//! ZManager starts it through UAC to retry operations that failed because
+//! access was denied; see `zmanager_transfer_win::elevate`. It reads the
+//! manifest, checks it against the SHA-256 it was started with, carries out
+//! every operation and writes the results next to it, in a new file it
+//! creates itself.
+//!
+//! Usage: `zmanager_elevate <manifest.json> <sha256>`
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use zmanager_transfer_win::elevate::{write_results, ElevationManifest};
+
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1);
+    let (Some(manifest_path), Some(sha256)) = (args.next().map(PathBuf::from), args.next()) else {
+        eprintln!("Usage: zmanager_elevate <manifest.json> <sha256>");
+        return ExitCode::from(2);
+    };
+
+    let manifest = match ElevationManifest::load(&manifest_path, &sha256.to_string_lossy()) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let results = manifest.run();
+    match write_results(&manifest_path, &results) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Failed to write results: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
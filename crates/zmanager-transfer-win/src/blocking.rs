@@ -0,0 +1,102 @@
+//! Shared plumbing of the executors that run a job on a blocking thread.
+//!
+//! Checksum, compare, compression, conversion, delete, extraction and size
+//! jobs each hand their work to a blocking thread, turn the progress it
+//! reports into throttled events and end with an event saying how the job
+//! went.
+//! [`run_blocking`] does that for all of them; an executor only supplies the
+//! work and its [`JobEvent`] type.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::{error, warn};
+use zmanager_core::{JobId, ZError, ZResult};
+
+/// Events an executor sends while it runs a job.
+pub(crate) trait JobEvent: Send + 'static {
+    /// What the work reports as it goes.
+    type Update;
+    /// What the work produces.
+    type Output: Send + 'static;
+
+    /// The job started.
+    fn started(job_id: JobId) -> Self;
+    /// The work reported `update`.
+    fn progress(job_id: JobId, update: &Self::Update) -> Self;
+    /// The work finished with `output`.
+    fn completed(job_id: JobId, output: &Self::Output) -> Self;
+    /// The work failed.
+    fn failed(job_id: JobId, error: String) -> Self;
+    /// The job was cancelled.
+    fn cancelled(job_id: JobId) -> Self;
+}
+
+/// Sends the progress events of a job, at most one per interval.
+pub(crate) struct ProgressReporter<E> {
+    job_id: JobId,
+    event_tx: broadcast::Sender<E>,
+    interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+impl<E: JobEvent> ProgressReporter<E> {
+    /// Send an event for `update`, unless one went out less than the
+    /// interval ago. The `last` update is always sent.
+    pub(crate) fn report(&mut self, update: &E::Update, last: bool) {
+        if !last && self.last_sent.is_some_and(|t| t.elapsed() < self.interval) {
+            return;
+        }
+        self.last_sent = Some(Instant::now());
+        let _ = self.event_tx.send(E::progress(self.job_id, update));
+    }
+}
+
+/// Run `work` for job `job_id` on a blocking thread, sending its events to
+/// `event_tx`.
+///
+/// `work` reports its progress to the [`ProgressReporter`] it is given,
+/// which sends at most one progress event per `interval`. A cancelled or
+/// failed job is logged, naming it `what`, like "Checksum".
+pub(crate) async fn run_blocking<E, F>(
+    job_id: JobId,
+    what: &'static str,
+    event_tx: &broadcast::Sender<E>,
+    interval: Duration,
+    work: F,
+) -> ZResult<E::Output>
+where
+    E: JobEvent,
+    F: FnOnce(&mut ProgressReporter<E>) -> ZResult<E::Output> + Send + 'static,
+{
+    let _ = event_tx.send(E::started(job_id));
+    let mut reporter = ProgressReporter {
+        job_id,
+        event_tx: event_tx.clone(),
+        interval,
+        last_sent: None,
+    };
+
+    let result = tokio::task::spawn_blocking(move || work(&mut reporter))
+        .await
+        .unwrap_or_else(|e| {
+            Err(ZError::Internal {
+                message: format!("Task join error: {e}"),
+            })
+        });
+
+    match &result {
+        Ok(output) => {
+            let _ = event_tx.send(E::completed(job_id, output));
+        }
+        Err(ZError::Cancelled) => {
+            warn!(job_id = %job_id, "{what} cancelled");
+            let _ = event_tx.send(E::cancelled(job_id));
+        }
+        Err(e) => {
+            error!(job_id = %job_id, error = %e, "{what} failed");
+            let _ = event_tx.send(E::failed(job_id, e.to_string()));
+        }
+    }
+    result
+}
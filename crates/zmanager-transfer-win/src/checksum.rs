@@ -0,0 +1,234 @@
+//! Job executor for file checksums.
+//!
+//! Runs [`JobKind::Checksum`] jobs on a blocking thread with
+//! [`zmanager_core::hash_files_with_progress`] and reports bytes hashed as
+//! throttled events. Files that can't be read get an error in their
+//! [`FileHash`] instead of failing the whole job.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    hash_files_with_progress, CancellationToken, FileHash, Job, JobId, JobKind, Progress, ZError,
+    ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+
+/// Events emitted while hashing.
+#[derive(Debug, Clone)]
+pub enum ChecksumEvent {
+    /// Hashing started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Hashing completed, possibly with unreadable files.
+    Completed {
+        job_id: JobId,
+        hashes: Vec<FileHash>,
+    },
+    /// Hashing failed.
+    Failed { job_id: JobId, error: String },
+    /// Hashing was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the checksum executor.
+#[derive(Debug, Clone)]
+pub struct ChecksumConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for ChecksumConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for checksum jobs.
+pub struct ChecksumExecutor {
+    config: ChecksumConfig,
+    event_tx: broadcast::Sender<ChecksumEvent>,
+}
+
+impl ChecksumExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(ChecksumConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: ChecksumConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to checksum events.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChecksumEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a checksum job from the job system.
+    ///
+    /// Returns one [`FileHash`] per path, in job order. Any other job kind is
+    /// rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<Vec<FileHash>> {
+        let JobKind::Checksum { paths, algorithm } = &job.kind else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for checksum executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(job_id = %job_id, files = paths.len(), %algorithm, "Starting checksum");
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let paths = paths.clone();
+        let algorithm = *algorithm;
+        let start_time = Instant::now();
+
+        let hashes = run_blocking(job_id, "Checksum", &self.event_tx, interval, move |reporter| {
+            hash_files_with_progress(&paths, algorithm, &cancel_token, |progress| {
+                // The final update (no current item) is always sent
+                reporter.report(progress, progress.current_item.is_none());
+            })
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            files = hashes.len(),
+            failed = hashes.iter().filter(|h| h.hash.is_err()).count(),
+            duration_ms = start_time.elapsed().as_millis(),
+            "Checksum completed"
+        );
+        Ok(hashes)
+    }
+}
+
+impl Default for ChecksumExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for ChecksumEvent {
+    type Update = Progress;
+    type Output = Vec<FileHash>;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    fn completed(job_id: JobId, hashes: &Vec<FileHash>) -> Self {
+        let hashes = hashes.clone();
+        Self::Completed { job_id, hashes }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+    use zmanager_core::HashAlgorithm;
+
+    fn checksum_job(paths: Vec<PathBuf>) -> Job {
+        Job::new(JobKind::Checksum {
+            paths,
+            algorithm: HashAlgorithm::Sha256,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_checksum_job() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("abc.txt");
+        fs::write(&file, b"abc").unwrap();
+        let job = checksum_job(vec![file.clone(), temp.path().join("missing.txt")]);
+
+        let executor = ChecksumExecutor::new();
+        let mut events = executor.subscribe();
+        let hashes = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(hashes.len(), 2);
+        assert_eq!(hashes[0].path, file);
+        assert_eq!(
+            hashes[0].hash.as_deref(),
+            Ok("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert!(hashes[1].hash.is_err());
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                ChecksumEvent::Progress { progress, .. } => last_progress = Some(progress),
+                ChecksumEvent::Completed { job_id, .. } => got_completed = job_id == job.id,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.bytes_done, 3);
+        assert_eq!(last_progress.items_done, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_checksum_job() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.txt");
+        fs::write(&file, b"data").unwrap();
+        let job = checksum_job(vec![file]);
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = ChecksumExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor.execute_job(&job, token).await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, ChecksumEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::Delete {
+            paths: vec![PathBuf::from("a")],
+        });
+
+        let result = ChecksumExecutor::new()
+            .execute_job(&job, CancellationToken::new())
+            .await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
@@ -0,0 +1,214 @@
+//! Job executor for file comparisons.
+//!
+//! Runs [`JobKind::Compare`] jobs on a blocking thread with
+//! [`zmanager_core::compare_files_with_progress`] and reports bytes read as
+//! throttled events.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    compare_files_with_progress, CancellationToken, FileComparison, Job, JobId, JobKind, Progress,
+    ZError, ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+
+/// Events emitted while comparing.
+#[derive(Debug, Clone)]
+pub enum CompareEvent {
+    /// Comparison started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Comparison completed.
+    Completed { job_id: JobId, identical: bool },
+    /// Comparison failed.
+    Failed { job_id: JobId, error: String },
+    /// Comparison was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the compare executor.
+#[derive(Debug, Clone)]
+pub struct CompareConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for compare jobs.
+pub struct CompareExecutor {
+    config: CompareConfig,
+    event_tx: broadcast::Sender<CompareEvent>,
+}
+
+impl CompareExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(CompareConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: CompareConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to compare events.
+    pub fn subscribe(&self) -> broadcast::Receiver<CompareEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a compare job from the job system.
+    ///
+    /// Any other job kind is rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<FileComparison> {
+        let JobKind::Compare { left, right, mode } = &job.kind else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for compare executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(
+            job_id = %job_id,
+            left = %left.display(),
+            right = %right.display(),
+            %mode,
+            "Starting comparison"
+        );
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let (left, right, mode) = (left.clone(), right.clone(), *mode);
+        let start_time = Instant::now();
+
+        let what = "Comparison";
+        let comparison = run_blocking(job_id, what, &self.event_tx, interval, move |reporter| {
+            compare_files_with_progress(&left, &right, mode, &cancel_token, |progress| {
+                // The final update (no current item) is always sent
+                reporter.report(progress, progress.current_item.is_none());
+            })
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            identical = comparison.is_identical(),
+            duration_ms = start_time.elapsed().as_millis(),
+            "Comparison completed"
+        );
+        Ok(comparison)
+    }
+}
+
+impl Default for CompareExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for CompareEvent {
+    type Update = Progress;
+    type Output = FileComparison;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    fn completed(job_id: JobId, comparison: &FileComparison) -> Self {
+        let identical = comparison.is_identical();
+        Self::Completed { job_id, identical }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+    use zmanager_core::CompareMode;
+
+    fn compare_job(left: PathBuf, right: PathBuf, mode: CompareMode) -> Job {
+        Job::new(JobKind::Compare { left, right, mode })
+    }
+
+    #[tokio::test]
+    async fn test_execute_compare_job() {
+        let temp = TempDir::new().unwrap();
+        let (left, right) = (temp.path().join("a.txt"), temp.path().join("b.txt"));
+        fs::write(&left, "one\ntwo\n").unwrap();
+        fs::write(&right, "one\n2\n").unwrap();
+        let job = compare_job(left, right, CompareMode::Lines);
+
+        let executor = CompareExecutor::new();
+        let mut events = executor.subscribe();
+        let comparison = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(comparison.first_difference, Some(4));
+        let stats = comparison.text.unwrap().stats();
+        assert_eq!((stats.added, stats.removed, stats.unchanged), (1, 1, 1));
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                CompareEvent::Progress { progress, .. } => last_progress = Some(progress),
+                CompareEvent::Completed { identical, .. } => got_completed = !identical,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        assert_eq!(last_progress.unwrap().bytes_done, 14);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_compare_job() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.txt");
+        fs::write(&file, b"data").unwrap();
+        let job = compare_job(file.clone(), file, CompareMode::Bytes);
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = CompareExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor.execute_job(&job, token).await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, CompareEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+}
@@ -0,0 +1,226 @@
+//! Job executor for archive creation.
+//!
+//! Runs [`JobKind::Compress`] jobs on a blocking thread with
+//! [`zmanager_core::create_archive`] and turns its progress callbacks into
+//! throttled events, so compression shows up like any other transfer.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    create_archive, CancellationToken, CompressReport, Job, JobId, JobKind, Progress, ZError,
+    ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+
+/// Events emitted during compression.
+#[derive(Debug, Clone)]
+pub enum CompressEvent {
+    /// Compression started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Compression completed.
+    Completed { job_id: JobId, report: CompressReport },
+    /// Compression failed.
+    Failed { job_id: JobId, error: String },
+    /// Compression was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the compress executor.
+#[derive(Debug, Clone)]
+pub struct CompressConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for CompressConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for archive creation jobs.
+pub struct CompressExecutor {
+    config: CompressConfig,
+    event_tx: broadcast::Sender<CompressEvent>,
+}
+
+impl CompressExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(CompressConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: CompressConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to compression events.
+    pub fn subscribe(&self) -> broadcast::Receiver<CompressEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a compress job from the job system.
+    ///
+    /// An existing archive is never overwritten. Any other job kind is
+    /// rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<CompressReport> {
+        let JobKind::Compress { sources, destination } = &job.kind else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for compress executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(
+            job_id = %job_id,
+            destination = %destination.display(),
+            sources = sources.len(),
+            "Starting compression"
+        );
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let (sources, destination) = (sources.clone(), destination.clone());
+        let start_time = Instant::now();
+
+        let report = run_blocking(job_id, "Compression", &self.event_tx, interval, move |reporter| {
+            create_archive(&sources, &destination, &cancel_token, |progress| {
+                // The final update (no current item) is always sent
+                reporter.report(progress, progress.current_item.is_none());
+            })
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            files = report.files,
+            skipped = report.skipped.len(),
+            bytes_in = report.bytes_in,
+            bytes_out = report.bytes_out,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Compression completed"
+        );
+        Ok(report)
+    }
+}
+
+impl Default for CompressExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for CompressEvent {
+    type Update = Progress;
+    type Output = CompressReport;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    fn completed(job_id: JobId, report: &CompressReport) -> Self {
+        let report = report.clone();
+        Self::Completed { job_id, report }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn compress_job(sources: Vec<PathBuf>, destination: PathBuf) -> Job {
+        Job::new(JobKind::Compress { sources, destination })
+    }
+
+    #[tokio::test]
+    async fn test_execute_compress_job() {
+        let temp = TempDir::new().unwrap();
+        let docs = temp.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        fs::write(docs.join("a.txt"), [b'A'; 4096]).unwrap();
+        fs::write(temp.path().join("b.txt"), b"bee").unwrap();
+        let archive = temp.path().join("bundle.zip");
+        let job = compress_job(vec![docs, temp.path().join("b.txt")], archive.clone());
+
+        let executor = CompressExecutor::new();
+        let mut events = executor.subscribe();
+        let report = executor.execute_job(&job, CancellationToken::new()).await.unwrap();
+
+        assert_eq!(report.files, 2);
+        assert_eq!(report.bytes_in, 4099);
+        assert!(report.skipped.is_empty());
+        assert!(archive.is_file());
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                CompressEvent::Progress { progress, .. } => last_progress = Some(progress),
+                CompressEvent::Completed { job_id, .. } => got_completed = job_id == job.id,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        assert_eq!(last_progress.unwrap().percentage_int(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_compress_job() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.txt"), b"a").unwrap();
+        let archive = temp.path().join("bundle.zip");
+        let job = compress_job(vec![temp.path().join("a.txt")], archive.clone());
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = CompressExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor.execute_job(&job, token).await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        assert!(!archive.exists());
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, CompressEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::Delete {
+            paths: vec![PathBuf::from("a")],
+        });
+
+        let result = CompressExecutor::new().execute_job(&job, CancellationToken::new()).await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, trace};
+use zmanager_core::EntryMeta;
 
 /// A conflict detected during transfer planning or execution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +52,19 @@ impl Conflict {
         })
     }
 
+    /// Create a conflict from looked up source and destination entries.
+    pub fn from_entries(source: &EntryMeta, destination: &EntryMeta) -> Self {
+        Self {
+            source: source.path.clone(),
+            destination: destination.path.clone(),
+            source_size: source.size,
+            dest_size: destination.size,
+            source_modified: source.modified.map(Into::into),
+            dest_modified: destination.modified.map(Into::into),
+            is_dir: source.is_directory(),
+        }
+    }
+
     /// Check if source is newer than destination.
     pub fn source_is_newer(&self) -> Option<bool> {
         match (self.source_modified, self.dest_modified) {
@@ -133,6 +147,19 @@ pub enum ConflictResolution {
     Cancel,
 }
 
+/// The user's answer to a conflict the resolver left to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictAnswer {
+    /// Settle the conflict by `policy`; with `apply_to_all`, settle the
+    /// remaining file conflicts of the transfer the same way.
+    Resolve {
+        policy: ConflictPolicy,
+        apply_to_all: bool,
+    },
+    /// Cancel the entire operation.
+    Cancel,
+}
+
 /// Settings for conflict handling.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictSettings {
@@ -270,6 +297,36 @@ impl ConflictResolver {
         resolution
     }
 
+    /// Settle a conflict the user was asked about.
+    ///
+    /// A policy that still can't decide (`Ask`, or `KeepNewer` without
+    /// timestamps) skips the file.
+    pub fn apply_answer(
+        &mut self,
+        conflict: &Conflict,
+        answer: ConflictAnswer,
+    ) -> ConflictResolution {
+        let ConflictAnswer::Resolve {
+            policy,
+            apply_to_all,
+        } = answer
+        else {
+            return ConflictResolution::Cancel;
+        };
+
+        if apply_to_all {
+            self.settings.file_policy = policy;
+        }
+
+        Self::with_settings(ConflictSettings {
+            file_policy: policy,
+            dir_policy: policy,
+            apply_to_all: false,
+        })
+        .resolve(conflict)
+        .unwrap_or(ConflictResolution::Skip)
+    }
+
     /// Generate a unique renamed path for a file.
     pub fn generate_rename_path(path: &Path) -> PathBuf {
         let parent = path.parent().unwrap_or(Path::new(""));
@@ -468,6 +525,42 @@ mod tests {
         assert_eq!(resolver.resolve(&conflict2), Some(ConflictResolution::Skip));
     }
 
+    #[test]
+    fn test_apply_answer() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.txt");
+        let dest = temp.path().join("dest.txt");
+
+        fs::write(&source, "larger content here").unwrap();
+        fs::write(&dest, "small").unwrap();
+
+        let conflict = Conflict::new(&source, &dest).unwrap();
+        let mut resolver = ConflictResolver::new();
+
+        // A one-off answer leaves the policy alone
+        let answer = ConflictAnswer::Resolve {
+            policy: ConflictPolicy::KeepLarger,
+            apply_to_all: false,
+        };
+        assert_eq!(
+            resolver.apply_answer(&conflict, answer),
+            ConflictResolution::Overwrite
+        );
+        assert_eq!(resolver.resolve(&conflict), None);
+
+        let answer = ConflictAnswer::Resolve {
+            policy: ConflictPolicy::Skip,
+            apply_to_all: true,
+        };
+        assert_eq!(resolver.apply_answer(&conflict, answer), ConflictResolution::Skip);
+        assert_eq!(resolver.resolve(&conflict), Some(ConflictResolution::Skip));
+
+        assert_eq!(
+            resolver.apply_answer(&conflict, ConflictAnswer::Cancel),
+            ConflictResolution::Cancel
+        );
+    }
+
     #[test]
     fn test_conflict_same_size() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,208 @@
+//! Job executor for text file conversions.
+//!
+//! Runs [`JobKind::ConvertText`] jobs on a blocking thread with
+//! [`zmanager_core::convert_text_files_with_progress`] and reports files
+//! converted as throttled events. Files that can't be converted get an error
+//! in their [`FileConversion`] instead of failing the whole job.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    convert_text_files_with_progress, CancellationToken, FileConversion, Job, JobId, JobKind,
+    Progress, ZError, ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+
+/// Events emitted while converting.
+#[derive(Debug, Clone)]
+pub enum ConvertEvent {
+    /// Conversion started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Conversion completed, possibly with files left as they were.
+    Completed {
+        job_id: JobId,
+        results: Vec<FileConversion>,
+    },
+    /// Conversion failed.
+    Failed { job_id: JobId, error: String },
+    /// Conversion was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the convert executor.
+#[derive(Debug, Clone)]
+pub struct ConvertConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for ConvertConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for text conversion jobs.
+pub struct ConvertExecutor {
+    config: ConvertConfig,
+    event_tx: broadcast::Sender<ConvertEvent>,
+}
+
+impl ConvertExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(ConvertConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: ConvertConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to conversion events.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConvertEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a text conversion job from the job system.
+    ///
+    /// Returns one [`FileConversion`] per path, in job order. Any other job
+    /// kind is rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<Vec<FileConversion>> {
+        let JobKind::ConvertText { paths, conversion } = &job.kind else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for convert executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(job_id = %job_id, files = paths.len(), ?conversion, "Starting conversion");
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let paths = paths.clone();
+        let conversion = *conversion;
+        let start_time = Instant::now();
+
+        let what = "Conversion";
+        let results = run_blocking(job_id, what, &self.event_tx, interval, move |reporter| {
+            convert_text_files_with_progress(&paths, &conversion, &cancel_token, |progress| {
+                // The final update (no current item) is always sent
+                reporter.report(progress, progress.current_item.is_none());
+            })
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            files = results.len(),
+            failed = results.iter().filter(|r| r.outcome.is_err()).count(),
+            duration_ms = start_time.elapsed().as_millis(),
+            "Conversion completed"
+        );
+        Ok(results)
+    }
+}
+
+impl Default for ConvertExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for ConvertEvent {
+    type Update = Progress;
+    type Output = Vec<FileConversion>;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    fn completed(job_id: JobId, results: &Vec<FileConversion>) -> Self {
+        let results = results.clone();
+        Self::Completed { job_id, results }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+    use zmanager_core::{ConversionOutcome, LineEnding, TextConversion};
+
+    fn convert_job(paths: Vec<PathBuf>) -> Job {
+        Job::new(JobKind::ConvertText {
+            paths,
+            conversion: TextConversion {
+                line_ending: Some(LineEnding::Lf),
+                ..TextConversion::default()
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_convert_job() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.txt");
+        fs::write(&file, "one\r\ntwo").unwrap();
+        let job = convert_job(vec![file.clone(), temp.path().join("missing.txt")]);
+
+        let executor = ConvertExecutor::new();
+        let mut events = executor.subscribe();
+        let results = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].outcome, Ok(ConversionOutcome::Converted { backup: None }));
+        assert!(results[1].outcome.is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "one\ntwo");
+
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            if let ConvertEvent::Completed { job_id, .. } = event {
+                got_completed = job_id == job.id;
+            }
+        }
+        assert!(got_completed);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::Delete {
+            paths: vec![PathBuf::from("a")],
+        });
+
+        let result = ConvertExecutor::new()
+            .execute_job(&job, CancellationToken::new())
+            .await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
@@ -3,23 +3,42 @@
 //! This module provides efficient file copying with:
 //! - Real-time progress callbacks
 //! - Cancellation support via CancellationToken
+//! - Pausing between chunks via PauseToken
+//! - Bandwidth limiting via SpeedLimit
 //! - Windows native performance
+//!
+//! Outside Windows the same functions copy through the portable engine in
+//! [`crate::portable`].
 
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(windows)]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(windows)]
+use std::sync::Mutex;
+#[cfg(windows)]
 use std::time::Instant;
 
+#[cfg(windows)]
 use tracing::{debug, error, info, trace, warn};
+#[cfg(windows)]
 use windows::core::PCWSTR;
+#[cfg(windows)]
 use windows::Win32::Foundation::HANDLE;
+#[cfg(windows)]
 use windows::Win32::Storage::FileSystem::{CopyFileExW, LPPROGRESS_ROUTINE_CALLBACK_REASON};
-use zmanager_core::{CancellationToken, ZError, ZResult};
+#[cfg(windows)]
+use zmanager_core::{extended_path, SpeedEstimator};
+use zmanager_core::{CancellationToken, Job, PauseToken, SpeedLimit, ZError, ZResult};
 
 // CopyFileExW progress callback return values
+#[cfg(windows)]
 const PROGRESS_CONTINUE: u32 = 0;
+#[cfg(windows)]
 const PROGRESS_CANCEL: u32 = 1;
+#[cfg(windows)]
 #[allow(dead_code)]
 const PROGRESS_STOP: u32 = 2;
+#[cfg(windows)]
 #[allow(dead_code)]
 const PROGRESS_QUIET: u32 = 3;
 
@@ -60,6 +79,7 @@ impl CopyProgress {
 }
 
 /// Shared state passed to the CopyFileExW callback.
+#[cfg(windows)]
 struct CallbackState {
     /// Source file path for progress reporting.
     source: std::path::PathBuf,
@@ -67,56 +87,58 @@ struct CallbackState {
     destination: std::path::PathBuf,
     /// Cancellation token to check for cancel requests.
     cancel_token: CancellationToken,
-    /// Pause flag - when true, operation should pause.
-    paused: AtomicBool,
+    /// Pause token - the callback parks while it is set.
+    pause_token: PauseToken,
+    /// Bandwidth limit - the callback sleeps to stay under it.
+    speed_limit: SpeedLimit,
+    /// Bytes already accounted against the speed limit.
+    throttled_bytes: AtomicU64,
     /// Bytes transferred (updated by callback).
     bytes_transferred: AtomicU64,
     /// Total bytes (updated by callback).
     total_bytes: AtomicU64,
-    /// Start time for speed calculation.
-    start_time: Instant,
+    /// Speed over the last few seconds.
+    speed: Mutex<SpeedEstimator>,
     /// User progress callback (if any).
     progress_callback: Option<ProgressCallback>,
 }
 
+#[cfg(windows)]
 impl CallbackState {
     fn new(
         source: impl AsRef<Path>,
         destination: impl AsRef<Path>,
         cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
         progress_callback: Option<ProgressCallback>,
     ) -> Self {
         Self {
             source: source.as_ref().to_path_buf(),
             destination: destination.as_ref().to_path_buf(),
             cancel_token,
-            paused: AtomicBool::new(false),
+            pause_token,
+            speed_limit,
+            throttled_bytes: AtomicU64::new(0),
             bytes_transferred: AtomicU64::new(0),
             total_bytes: AtomicU64::new(0),
-            start_time: Instant::now(),
+            speed: Mutex::new(SpeedEstimator::new()),
             progress_callback,
         }
     }
 
-    fn calculate_speed(&self, bytes_transferred: u64) -> u64 {
-        let elapsed = self.start_time.elapsed();
-        if elapsed.as_secs() > 0 {
-            bytes_transferred / elapsed.as_secs()
-        } else if elapsed.as_millis() > 100 {
-            (bytes_transferred * 1000) / elapsed.as_millis() as u64
-        } else {
-            0
-        }
-    }
-
-    fn calculate_eta(&self, bytes_transferred: u64, total_bytes: u64) -> Option<u64> {
-        let speed = self.calculate_speed(bytes_transferred);
-        if speed > 0 && bytes_transferred < total_bytes {
-            let remaining = total_bytes - bytes_transferred;
-            Some(remaining / speed)
-        } else {
-            None
-        }
+    /// Record the bytes transferred, returning the speed and the seconds
+    /// left at that speed.
+    fn rate(&self, bytes_transferred: u64, total_bytes: u64) -> (u64, Option<u64>) {
+        let speed = self
+            .speed
+            .lock()
+            .ok()
+            .and_then(|mut speed| speed.record(bytes_transferred, Instant::now()))
+            .unwrap_or(0);
+        let eta = (speed > 0 && bytes_transferred < total_bytes)
+            .then(|| (total_bytes - bytes_transferred) / speed);
+        (speed, eta)
     }
 }
 
@@ -129,6 +151,7 @@ impl CallbackState {
 ///
 /// This function is called from Windows and must be `unsafe extern "system"`.
 /// The `lpdata` parameter must point to a valid `CallbackState` box.
+#[cfg(windows)]
 #[allow(clippy::too_many_arguments)]
 unsafe extern "system" fn copy_progress_callback(
     total_file_size: i64,
@@ -150,12 +173,27 @@ unsafe extern "system" fn copy_progress_callback(
         return PROGRESS_CANCEL;
     }
 
-    // Check for pause
-    if state.paused.load(Ordering::Acquire) {
+    // Park between chunks while paused; cancellation still wins
+    if state.pause_token.is_paused() {
         trace!("Copy paused, waiting...");
-        // In a real pause scenario, we'd spin here. For now, just continue.
-        // The job system handles actual pausing at a higher level.
-        return PROGRESS_QUIET;
+        let waited = state.pause_token.wait_blocking(&state.cancel_token);
+        if waited.is_err() {
+            trace!("Copy cancelled while paused");
+            return PROGRESS_CANCEL;
+        }
+    }
+
+    // Hold the chunk back until the limit allows it
+    let bytes_done = total_bytes_transferred as u64;
+    let throttled = state.throttled_bytes.swap(bytes_done, Ordering::AcqRel);
+    if bytes_done > throttled
+        && state
+            .speed_limit
+            .throttle_blocking(bytes_done - throttled, &state.cancel_token)
+            .is_err()
+    {
+        trace!("Copy cancelled while throttled");
+        return PROGRESS_CANCEL;
     }
 
     // Update progress tracking
@@ -172,8 +210,7 @@ unsafe extern "system" fn copy_progress_callback(
         if let Some(ref callback) = state.progress_callback {
             let bytes_copied = total_bytes_transferred as u64;
             let total_bytes = total_file_size as u64;
-            let speed = state.calculate_speed(bytes_copied);
-            let eta = state.calculate_eta(bytes_copied, total_bytes);
+            let (speed, eta) = state.rate(bytes_copied, total_bytes);
 
             let progress = CopyProgress {
                 total_bytes,
@@ -234,8 +271,81 @@ pub fn copy_file_with_progress(
     cancel_token: CancellationToken,
     progress_callback: Option<ProgressCallback>,
 ) -> ZResult<u64> {
-    let source = source.as_ref();
-    let destination = destination.as_ref();
+    copy_file_with_pause(
+        source,
+        destination,
+        overwrite,
+        cancel_token,
+        PauseToken::new(),
+        progress_callback,
+    )
+}
+
+/// Copy a file with progress reporting and pause support.
+///
+/// Same as [`copy_file_with_progress`], but the copy parks between chunks
+/// while `pause_token` is set. Time spent parked doesn't count toward the
+/// reported speed.
+pub fn copy_file_with_pause(
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+    overwrite: bool,
+    cancel_token: CancellationToken,
+    pause_token: PauseToken,
+    progress_callback: Option<ProgressCallback>,
+) -> ZResult<u64> {
+    copy_file_throttled(
+        source,
+        destination,
+        overwrite,
+        cancel_token,
+        pause_token,
+        SpeedLimit::new(),
+        progress_callback,
+    )
+}
+
+/// Copy a file with progress reporting, pause support and a bandwidth limit.
+///
+/// Same as [`copy_file_with_pause`], but each chunk is held back as long as
+/// `speed_limit` requires. The limit is read per chunk, so changing it takes
+/// effect during the copy.
+pub fn copy_file_throttled(
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+    overwrite: bool,
+    cancel_token: CancellationToken,
+    pause_token: PauseToken,
+    speed_limit: SpeedLimit,
+    progress_callback: Option<ProgressCallback>,
+) -> ZResult<u64> {
+    #[cfg(windows)]
+    let copy = copy_file_ex;
+    #[cfg(not(windows))]
+    let copy = crate::portable::copy_file_portable;
+
+    copy(
+        source.as_ref(),
+        destination.as_ref(),
+        overwrite,
+        cancel_token,
+        pause_token,
+        speed_limit,
+        progress_callback,
+    )
+}
+
+/// Copy a file through `CopyFileExW`.
+#[cfg(windows)]
+pub(crate) fn copy_file_ex(
+    source: &Path,
+    destination: &Path,
+    overwrite: bool,
+    cancel_token: CancellationToken,
+    pause_token: PauseToken,
+    speed_limit: SpeedLimit,
+    progress_callback: Option<ProgressCallback>,
+) -> ZResult<u64> {
 
     debug!(
         source = %source.display(),
@@ -259,7 +369,7 @@ pub fn copy_file_with_progress(
     }
 
     // Check if destination exists and we shouldn't overwrite
-    if !overwrite && destination.exists() {
+    if !overwrite && extended_path(destination).exists() {
         return Err(ZError::AlreadyExists {
             path: destination.to_path_buf(),
         });
@@ -267,8 +377,9 @@ pub fn copy_file_with_progress(
 
     // Ensure parent directory exists
     if let Some(parent) = destination.parent() {
-        if !parent.exists() {
-            std::fs::create_dir_all(parent).map_err(|e| ZError::io(parent, e))?;
+        let os_parent = extended_path(parent);
+        if !os_parent.exists() {
+            std::fs::create_dir_all(&os_parent).map_err(|e| ZError::io(parent, e))?;
         }
     }
 
@@ -277,6 +388,8 @@ pub fn copy_file_with_progress(
         source,
         destination,
         cancel_token.clone(),
+        pause_token,
+        speed_limit,
         progress_callback,
     ));
     let state_ptr = Box::into_raw(state);
@@ -352,6 +465,18 @@ pub fn copy_file_with_progress(
     }
 }
 
+/// Get the bandwidth limit an executor copies under.
+///
+/// A job without a limit of its own takes `default`; the job keeps the token,
+/// so the limit can still be changed through the scheduler while it runs.
+pub(crate) fn speed_limit(job: Option<&Job>, default: Option<u64>) -> SpeedLimit {
+    let limit = job.map_or_else(SpeedLimit::new, |job| job.speed_limit.clone());
+    if limit.get().is_none() {
+        limit.set(default);
+    }
+    limit
+}
+
 /// Copy a file asynchronously with progress reporting.
 ///
 /// This wraps `copy_file_with_progress` in a tokio blocking task.
@@ -375,17 +500,13 @@ pub async fn copy_file_async(
 }
 
 /// Convert a path to a null-terminated wide string for Windows API.
+#[cfg(windows)]
 fn path_to_wide(path: &Path) -> ZResult<Vec<u16>> {
     use std::os::windows::ffi::OsStrExt;
 
     // Use \\?\ prefix for long path support
-    let path_str = if path.to_string_lossy().len() >= 240 {
-        format!("\\\\?\\{}", path.display())
-    } else {
-        path.to_string_lossy().to_string()
-    };
-
-    let wide: Vec<u16> = std::ffi::OsStr::new(&path_str)
+    let wide: Vec<u16> = extended_path(path)
+        .as_os_str()
         .encode_wide()
         .chain(std::iter::once(0))
         .collect();
@@ -406,12 +527,16 @@ pub struct CopyResult {
     pub duration: std::time::Duration,
     /// Average speed in bytes per second.
     pub average_speed_bps: u64,
+    /// Number of retries needed after transient errors.
+    pub retries: u32,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
     use tempfile::TempDir;
 
     fn create_test_file(dir: &TempDir, name: &str, size: usize) -> std::path::PathBuf {
@@ -516,6 +641,73 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_copy_parks_while_paused() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_file(&temp, "source.txt", 1024);
+        let dest = temp.path().join("dest.txt");
+
+        let cancel = CancellationToken::new();
+        let pause = PauseToken::new();
+        pause.pause();
+
+        let copy = {
+            let (source, dest) = (source.clone(), dest.clone());
+            let pause = pause.clone();
+            std::thread::spawn(move || {
+                copy_file_with_pause(&source, &dest, false, cancel, pause, None)
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!copy.is_finished());
+
+        pause.resume();
+        assert!(copy.join().unwrap().is_ok());
+        assert_eq!(fs::read(&source).unwrap(), fs::read(&dest).unwrap());
+    }
+
+    #[test]
+    fn test_cancel_while_paused() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_file(&temp, "source.txt", 1024);
+        let dest = temp.path().join("dest.txt");
+
+        let cancel = CancellationToken::new();
+        let pause = PauseToken::new();
+        pause.pause();
+
+        let copy = {
+            let (source, dest) = (source.clone(), dest.clone());
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                copy_file_with_pause(&source, &dest, false, cancel, pause, None)
+            })
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        cancel.cancel();
+
+        assert!(matches!(copy.join().unwrap(), Err(ZError::Cancelled)));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_copy_throttled() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_file(&temp, "source.bin", 256 * 1024);
+        let dest = temp.path().join("dest.bin");
+
+        // 256 KiB at 1 MiB/s takes about a quarter of a second
+        let limit = SpeedLimit::new();
+        limit.set(Some(1024 * 1024));
+        let start = Instant::now();
+        let token = CancellationToken::new();
+        copy_file_throttled(&source, &dest, false, token, PauseToken::new(), limit, None)
+            .unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(fs::read(&source).unwrap(), fs::read(&dest).unwrap());
+    }
+
     #[test]
     fn test_copy_creates_parent_dirs() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,355 @@
+//! Job executor for deletion.
+//!
+//! Runs [`JobKind::Delete`] (to the Recycle Bin) and
+//! [`JobKind::DeletePermanent`] jobs on a blocking thread, one item at a
+//! time. Progress is reported per item, cancellation is checked between
+//! items, and every item's outcome ends up in a [`DetailedTransferReport`],
+//! so one locked file doesn't stop the rest of a batch. Permanent deletes
+//! can overwrite file contents first ([`DeleteConfig::wipe`]), reporting the
+//! bytes overwritten as they go.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+use zmanager_core::{
+    delete_path, move_to_recycle_bin, wipe_path, CancellationToken, Job, JobId, JobKind,
+    LinkHandling, Progress, ZError, ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+use crate::report::{DetailedTransferReport, ReportBuilder, TransferItemResult, TransferOperation};
+
+/// Events emitted during deletion.
+#[derive(Debug, Clone)]
+pub enum DeleteEvent {
+    /// Deletion started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Deletion completed, possibly with failed items.
+    Completed {
+        job_id: JobId,
+        report: DetailedTransferReport,
+    },
+    /// Deletion failed.
+    Failed { job_id: JobId, error: String },
+    /// Deletion was cancelled; items before the cancellation were deleted.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the delete executor.
+#[derive(Debug, Clone)]
+pub struct DeleteConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+    /// How permanent deletes treat symlinks and junctions.
+    pub link_handling: LinkHandling,
+    /// Overwrite file contents with zeros before deleting permanently.
+    /// Recycle Bin deletes are unaffected.
+    pub wipe: bool,
+}
+
+impl Default for DeleteConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+            link_handling: LinkHandling::default(),
+            wipe: false,
+        }
+    }
+}
+
+/// Executor for delete jobs.
+pub struct DeleteExecutor {
+    config: DeleteConfig,
+    event_tx: broadcast::Sender<DeleteEvent>,
+}
+
+impl DeleteExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(DeleteConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: DeleteConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to deletion events.
+    pub fn subscribe(&self) -> broadcast::Receiver<DeleteEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a delete job from the job system.
+    ///
+    /// Items that can't be deleted are recorded as failed in the report and
+    /// the rest are still deleted. A cancelled job returns the report of the
+    /// items handled so far, marked as cancelled. Any other job kind is
+    /// rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<DetailedTransferReport> {
+        let (paths, permanent) = match &job.kind {
+            JobKind::Delete { paths } => (paths.clone(), false),
+            JobKind::DeletePermanent { paths } => (paths.clone(), true),
+            _ => {
+                return Err(ZError::Internal {
+                    message: format!("Unsupported job kind for delete executor: {:?}", job.kind),
+                });
+            }
+        };
+
+        let job_id = job.id;
+        let wipe = permanent && self.config.wipe;
+        info!(job_id = %job_id, items = paths.len(), permanent, wipe, "Starting delete");
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let links = self.config.link_handling;
+        let start_time = Instant::now();
+
+        let report = run_blocking(job_id, "Delete", &self.event_tx, interval, move |reporter| {
+            let mut report = ReportBuilder::new(job_id, TransferOperation::Delete);
+            let mut progress = Progress::new(paths.len(), None);
+
+            for path in paths {
+                if cancel_token.is_cancelled() {
+                    report.set_cancelled(true);
+                    break;
+                }
+                progress.current_item = Some(path.clone());
+                reporter.report(&progress, false);
+
+                let item = if wipe {
+                    // Wiping a large file takes a while; report the bytes
+                    // overwritten as they go
+                    let bytes_before = progress.bytes_done;
+                    delete_item(path, |path| {
+                        wipe_path(path, links, |wiped| {
+                            progress.bytes_done = bytes_before + wiped;
+                            reporter.report(&progress, false);
+                        })
+                    })
+                } else if permanent {
+                    delete_item(path, |path| delete_path(path, links))
+                } else {
+                    delete_item(path, |path| move_to_recycle_bin(path))
+                };
+                report.add_item(item);
+                progress.items_done += 1;
+            }
+
+            // The final update (no current item) is always sent
+            progress.current_item = None;
+            reporter.report(&progress, true);
+            Ok(report.build())
+        })
+        .await?;
+
+        if report.was_cancelled {
+            warn!(job_id = %job_id, deleted = report.summary.succeeded, "Delete cancelled");
+        } else {
+            info!(
+                job_id = %job_id,
+                deleted = report.summary.succeeded,
+                failed = report.summary.failed,
+                duration_ms = start_time.elapsed().as_millis(),
+                "Delete completed"
+            );
+        }
+        Ok(report)
+    }
+}
+
+impl Default for DeleteExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for DeleteEvent {
+    type Update = Progress;
+    type Output = DetailedTransferReport;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    /// A cancelled delete still has a report, of the items handled before.
+    fn completed(job_id: JobId, report: &DetailedTransferReport) -> Self {
+        if report.was_cancelled {
+            return Self::Cancelled { job_id };
+        }
+        let report = report.clone();
+        Self::Completed { job_id, report }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+/// Delete one item with `delete`, recording its outcome.
+///
+/// Deleted items have no destination; the report leaves it empty.
+fn delete_item(path: PathBuf, delete: impl FnOnce(&Path) -> ZResult<()>) -> TransferItemResult {
+    let started = Instant::now();
+    let metadata = std::fs::symlink_metadata(&path).ok();
+    let result = delete(&path);
+
+    let item = match (result, metadata) {
+        (Ok(()), Some(metadata)) if metadata.is_dir() => {
+            TransferItemResult::success_dir(path, PathBuf::new())
+        }
+        (Ok(()), metadata) => {
+            let size = metadata.map_or(0, |m| m.len());
+            TransferItemResult::success(path, PathBuf::new(), size)
+        }
+        (Err(e), _) => {
+            debug!(path = %path.display(), error = %e, "Could not delete item");
+            TransferItemResult::failed(path, PathBuf::new(), e.to_string())
+        }
+    };
+    item.with_duration(started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Check whether `path` is still there, without following links.
+    fn exists(path: &Path) -> bool {
+        fs::symlink_metadata(path).is_ok()
+    }
+
+    fn create_items(dir: &Path) -> Vec<PathBuf> {
+        let file = dir.join("a.txt");
+        fs::write(&file, [b'A'; 100]).unwrap();
+        let folder = dir.join("folder");
+        fs::create_dir_all(folder.join("nested")).unwrap();
+        fs::write(folder.join("nested").join("b.txt"), b"bee").unwrap();
+        vec![file, folder]
+    }
+
+    #[tokio::test]
+    async fn test_execute_permanent_delete_job() {
+        let temp = TempDir::new().unwrap();
+        let mut paths = create_items(temp.path());
+        paths.push(temp.path().join("missing.txt"));
+        let job = Job::new(JobKind::DeletePermanent {
+            paths: paths.clone(),
+        });
+
+        let executor = DeleteExecutor::new();
+        let mut events = executor.subscribe();
+        let report = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(!exists(&paths[0]) && !exists(&paths[1]));
+        assert_eq!(report.operation, TransferOperation::Delete);
+        assert_eq!(report.summary.succeeded, 2);
+        assert_eq!(report.summary.failed, 1);
+        assert_eq!(report.summary.bytes_transferred, 100);
+        assert_eq!(report.failed_items().next().unwrap().source, paths[2]);
+        assert!(!report.was_cancelled);
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                DeleteEvent::Progress { progress, .. } => last_progress = Some(progress),
+                DeleteEvent::Completed { job_id, .. } => got_completed = job_id == job.id,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        assert_eq!(last_progress.unwrap().percentage_int(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_wipe_delete_job_reports_bytes() {
+        let temp = TempDir::new().unwrap();
+        let paths = create_items(temp.path());
+        let job = Job::new(JobKind::DeletePermanent {
+            paths: paths.clone(),
+        });
+
+        let executor = DeleteExecutor::with_config(DeleteConfig {
+            progress_interval_ms: 0,
+            wipe: true,
+            ..Default::default()
+        });
+        let mut events = executor.subscribe();
+        let report = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(!exists(&paths[0]) && !exists(&paths[1]));
+        assert_eq!(report.summary.succeeded, 2);
+        let mut bytes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DeleteEvent::Progress { progress, .. } = event {
+                bytes.push(progress.bytes_done);
+            }
+        }
+        // Both files' bytes are reported, the folder's as it is overwritten
+        assert!(bytes.contains(&100));
+        assert_eq!(bytes.last(), Some(&103));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_delete_job() {
+        let temp = TempDir::new().unwrap();
+        let paths = create_items(temp.path());
+        let job = Job::new(JobKind::DeletePermanent {
+            paths: paths.clone(),
+        });
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = DeleteExecutor::new();
+        let mut events = executor.subscribe();
+        let report = executor.execute_job(&job, token).await.unwrap();
+
+        assert!(report.was_cancelled);
+        assert!(report.items.is_empty());
+        assert!(paths.iter().all(|path| exists(path)));
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, DeleteEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::CalculateSize {
+            path: PathBuf::from("a"),
+        });
+
+        let result = DeleteExecutor::new()
+            .execute_job(&job, CancellationToken::new())
+            .await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
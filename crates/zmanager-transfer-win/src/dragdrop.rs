@@ -0,0 +1,178 @@
+//! OLE drag-and-drop of files to and from the Windows shell.
+//!
+//! Dragging out hands the drop target a data object carrying CF_HDROP and
+//! the "Preferred DropEffect", the same formats used by clipboard cut/copy.
+//! Files dropped in are turned into copy or move jobs with [`drop_job`].
+
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use tracing::debug;
+use windows::core::{implement, HRESULT};
+use windows::Win32::Foundation::{
+    BOOL, DRAGDROP_S_CANCEL, DRAGDROP_S_DROP, DRAGDROP_S_USEDEFAULTCURSORS, HGLOBAL, S_OK,
+};
+use windows::Win32::System::Com::{
+    IDataObject, DVASPECT_CONTENT, FORMATETC, STGMEDIUM, STGMEDIUM_0, TYMED_HGLOBAL,
+};
+use windows::Win32::System::Ole::{
+    DoDragDrop, IDropSource, IDropSource_Impl, OleInitialize, CF_HDROP, DROPEFFECT,
+    DROPEFFECT_COPY, DROPEFFECT_MOVE, DROPEFFECT_NONE,
+};
+use windows::Win32::System::SystemServices::{MK_LBUTTON, MODIFIERKEYS_FLAGS};
+use windows::Win32::UI::Shell::SHCreateDataObject;
+use zmanager_core::{same_volume, Job, JobKind, ZError, ZResult};
+
+use crate::clipboard::{build_drop_effect, build_hdrop, get_drop_effect_format, DropEffect};
+
+/// Pick the effect the shell would use for a plain drop: move within a
+/// volume, copy across volumes.
+pub fn default_drop_effect(sources: &[PathBuf], destination: &Path) -> DropEffect {
+    if !sources.is_empty() && sources.iter().all(|source| same_volume(source, destination)) {
+        DropEffect::Move
+    } else {
+        DropEffect::Copy
+    }
+}
+
+/// Build the transfer job for files dropped onto `destination`.
+pub fn drop_job(sources: Vec<PathBuf>, destination: PathBuf, effect: DropEffect) -> Job {
+    let kind = match effect {
+        DropEffect::Copy => JobKind::Copy {
+            sources,
+            destination,
+        },
+        DropEffect::Move => JobKind::Move {
+            sources,
+            destination,
+        },
+    };
+    Job::new(kind)
+}
+
+/// Drop source that follows the left mouse button and Escape.
+#[implement(IDropSource)]
+struct DropSource;
+
+impl IDropSource_Impl for DropSource_Impl {
+    fn QueryContinueDrag(&self, escape_pressed: BOOL, key_state: MODIFIERKEYS_FLAGS) -> HRESULT {
+        if escape_pressed.as_bool() {
+            DRAGDROP_S_CANCEL
+        } else if key_state.0 & MK_LBUTTON.0 == 0 {
+            DRAGDROP_S_DROP
+        } else {
+            S_OK
+        }
+    }
+
+    fn GiveFeedback(&self, _effect: DROPEFFECT) -> HRESULT {
+        DRAGDROP_S_USEDEFAULTCURSORS
+    }
+}
+
+/// Hand an HGLOBAL to the data object, which takes ownership of it.
+fn set_hglobal(data: &IDataObject, format: u16, hglobal: HGLOBAL) -> ZResult<()> {
+    let format = FORMATETC {
+        cfFormat: format,
+        ptd: ptr::null_mut(),
+        dwAspect: DVASPECT_CONTENT.0,
+        lindex: -1,
+        tymed: TYMED_HGLOBAL.0 as u32,
+    };
+    let medium = STGMEDIUM {
+        tymed: TYMED_HGLOBAL.0 as u32,
+        u: STGMEDIUM_0 { hGlobal: hglobal },
+        pUnkForRelease: ManuallyDrop::new(None),
+    };
+
+    unsafe { data.SetData(&format, &medium, true) }.map_err(|e| ZError::Internal {
+        message: format!("Failed to set drag data: {e}"),
+    })
+}
+
+/// Drag files out to the shell with a modal OLE drag loop.
+///
+/// Must be called on the UI thread, which has to keep pumping messages.
+/// `effect` is offered to the drop target as the preferred effect; both
+/// copy and move are allowed.
+///
+/// Returns the effect the target performed, or `None` if the drag was
+/// cancelled. A move the shell carried out itself reports no effect and is
+/// returned as [`DropEffect::Move`].
+pub fn drag_files_out(paths: &[PathBuf], effect: DropEffect) -> ZResult<Option<DropEffect>> {
+    if paths.is_empty() {
+        return Err(ZError::InvalidOperation {
+            operation: "drag and drop".to_string(),
+            reason: "No files to drag".to_string(),
+        });
+    }
+
+    debug!(count = paths.len(), effect = ?effect, "Starting drag out");
+
+    unsafe {
+        // Fails harmlessly if OLE is already initialized on this thread
+        let _ = OleInitialize(None);
+
+        let data: IDataObject =
+            SHCreateDataObject(None, None, None).map_err(|e| ZError::Internal {
+                message: format!("Failed to create drag data: {e}"),
+            })?;
+        set_hglobal(&data, CF_HDROP.0, build_hdrop(paths)?)?;
+        set_hglobal(
+            &data,
+            get_drop_effect_format() as u16,
+            build_drop_effect(effect)?,
+        )?;
+
+        let source: IDropSource = DropSource.into();
+        let mut performed = DROPEFFECT_NONE;
+        let result = DoDragDrop(&data, &source, DROPEFFECT_COPY | DROPEFFECT_MOVE, &mut performed);
+
+        if result == DRAGDROP_S_DROP {
+            let performed = DropEffect::from_value(performed.0).unwrap_or(DropEffect::Move);
+            debug!(effect = ?performed, "Drag out dropped");
+            Ok(Some(performed))
+        } else if result == DRAGDROP_S_CANCEL {
+            debug!("Drag out cancelled");
+            Ok(None)
+        } else {
+            Err(ZError::Internal {
+                message: format!("Drag and drop failed: {}", result.message()),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_drop_effect() {
+        let temp = TempDir::new().unwrap();
+        let sources = vec![temp.path().join("a.txt"), temp.path().join("b.txt")];
+        let destination = temp.path().join("dest");
+
+        assert_eq!(default_drop_effect(&sources, &destination), DropEffect::Move);
+        assert_eq!(default_drop_effect(&[], &destination), DropEffect::Copy);
+    }
+
+    #[test]
+    fn test_drop_job() {
+        let sources = vec![PathBuf::from("a.txt")];
+        let destination = PathBuf::from("dest");
+
+        let job = drop_job(sources.clone(), destination.clone(), DropEffect::Copy);
+        assert!(matches!(job.kind, JobKind::Copy { .. }));
+
+        let job = drop_job(sources, destination, DropEffect::Move);
+        assert!(matches!(job.kind, JobKind::Move { .. }));
+    }
+
+    #[test]
+    fn test_drag_out_empty_fails() {
+        assert!(drag_files_out(&[], DropEffect::Copy).is_err());
+    }
+}
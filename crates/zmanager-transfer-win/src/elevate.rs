@@ -0,0 +1,657 @@
+//! Retrying access-denied operations with administrator rights.
+//!
+//! The items of a copy, move, delete or rename that failed because access
+//! was denied go into an [`ElevationManifest`], as do items to take
+//! ownership of. [`run_elevated`] writes it to
+//! a JSON file and starts the `zmanager_elevate` helper through UAC (the
+//! `runas` verb), which carries out each operation and writes an
+//! [`ElevatedResult`] per operation next to the manifest. The results are
+//! then folded back into the job's report with
+//! [`merge_elevated_results`].
+//!
+//! The manifest goes in the user's temporary folder, which any of the user's
+//! unelevated processes can write to. So the helper is also handed the
+//! SHA-256 of the manifest on its command line, and refuses a manifest that
+//! was changed after it was written. For the same reason the helper only
+//! writes its results to a file it creates itself, and neither side uses
+//! the folder if it has been swapped for a symbolic link or junction.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use zmanager_core::{
+    delete_path, extended_path, hash_bytes, move_to_recycle_bin, rename, take_ownership,
+    HashAlgorithm, LinkHandling, ZError, ZResult,
+};
+
+use crate::report::{DetailedTransferReport, TransferOperation, TransferStatus};
+
+/// File name of the helper binary, without the platform's executable suffix.
+pub const HELPER_NAME: &str = "zmanager_elevate";
+
+/// One operation for the helper to carry out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ElevatedOperation {
+    /// Copy a file, or create a folder, at `destination`.
+    Copy {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// Move a file to `destination`. For a folder, create it at
+    /// `destination`; its contents move with their own operations, and the
+    /// manifest removes the source folder once it is empty.
+    Move {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// Delete a file or folder, to the Recycle Bin unless `permanent`.
+    Delete { path: PathBuf, permanent: bool },
+    /// Rename `from` to the full path `to`.
+    Rename { from: PathBuf, to: PathBuf },
+    /// Make the user the owner of `path` and grant them full control,
+    /// through everything inside it too if `recursive`.
+    TakeOwnership { path: PathBuf, recursive: bool },
+}
+
+impl ElevatedOperation {
+    /// The path the operation works on.
+    pub fn source(&self) -> &Path {
+        match self {
+            Self::Copy { source, .. } | Self::Move { source, .. } => source,
+            Self::Delete { path, .. } | Self::TakeOwnership { path, .. } => path,
+            Self::Rename { from, .. } => from,
+        }
+    }
+
+    /// Carry out the operation in this process.
+    pub fn run(&self) -> ZResult<()> {
+        self.run_with(&|from, to| rename(from, to))
+    }
+
+    /// Carry out the operation, moving files with `rename` when it can.
+    fn run_with(&self, rename: &dyn Fn(&Path, &Path) -> ZResult<()>) -> ZResult<()> {
+        match self {
+            Self::Copy {
+                source,
+                destination,
+            } => copy_item(source, destination),
+            // Moving the whole folder at once would delete files still
+            // waiting for their own operations when it crosses volumes
+            Self::Move {
+                source,
+                destination,
+            } if source.is_dir() => copy_item(source, destination),
+            Self::Move {
+                source,
+                destination,
+            } => {
+                create_parent(destination)?;
+                if rename(source, destination).is_ok() {
+                    return Ok(());
+                }
+                // Across volumes a move is a copy followed by a delete
+                copy_item(source, destination)?;
+                delete_path(source, LinkHandling::default())
+            }
+            Self::Delete { path, permanent } if *permanent => {
+                delete_path(path, LinkHandling::default())
+            }
+            Self::Delete { path, .. } => move_to_recycle_bin(path),
+            Self::Rename { from, to } => rename(from, to),
+            Self::TakeOwnership { path, recursive } => take_ownership(path, *recursive),
+        }
+    }
+}
+
+/// Outcome of one elevated operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElevatedResult {
+    /// The operation carried out.
+    pub operation: ElevatedOperation,
+    /// Why it failed, if it did.
+    pub error: Option<String>,
+}
+
+impl ElevatedResult {
+    /// Check if the operation succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The operations handed to the elevated helper.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElevationManifest {
+    /// Operations, carried out in order.
+    pub operations: Vec<ElevatedOperation>,
+}
+
+impl ElevationManifest {
+    /// Create a manifest of `operations`.
+    pub fn new(operations: Vec<ElevatedOperation>) -> Self {
+        Self { operations }
+    }
+
+    /// The items of `report` that failed because access was denied.
+    ///
+    /// Deleted items go to the Recycle Bin unless `permanent`. Folders come
+    /// before the files copied into them, as they do in the report.
+    pub fn from_report(report: &DetailedTransferReport, permanent: bool) -> Self {
+        let operations = report
+            .items
+            .iter()
+            .filter(|item| item.status == TransferStatus::Failed)
+            .filter(|item| item.reason.as_deref().is_some_and(is_access_denied))
+            .map(|item| {
+                let source = item.source.clone();
+                let destination = item.destination.clone();
+                match report.operation {
+                    TransferOperation::Copy => ElevatedOperation::Copy {
+                        source,
+                        destination,
+                    },
+                    TransferOperation::Move => ElevatedOperation::Move {
+                        source,
+                        destination,
+                    },
+                    TransferOperation::Delete => ElevatedOperation::Delete {
+                        path: source,
+                        permanent,
+                    },
+                }
+            })
+            .collect();
+        Self { operations }
+    }
+
+    /// Number of operations.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Check if there is nothing to do.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Write the manifest to `path` as JSON, returning the SHA-256 of what
+    /// was written.
+    pub fn save(&self, path: &Path) -> ZResult<String> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| ZError::Internal {
+            message: format!("Failed to serialize elevation manifest: {e}"),
+        })?;
+        fs::write(path, &json).map_err(|e| ZError::io(path, e))?;
+        Ok(hash_bytes(&json, HashAlgorithm::Sha256))
+    }
+
+    /// Read a manifest written by [`save`](Self::save), checking that its
+    /// SHA-256 is still `sha256`.
+    ///
+    /// # Errors
+    /// * `ZError::InvalidOperation` - The manifest was changed since it was
+    ///   written
+    pub fn load(path: &Path, sha256: &str) -> ZResult<Self> {
+        let json = fs::read(path).map_err(|e| ZError::from_io(path, e))?;
+        if hash_bytes(&json, HashAlgorithm::Sha256) != sha256.to_ascii_lowercase() {
+            return Err(ZError::InvalidOperation {
+                operation: "retry as administrator".to_string(),
+                reason: format!("{} was changed after it was written", path.display()),
+            });
+        }
+        serde_json::from_slice(&json).map_err(|e| ZError::Internal {
+            message: format!("Failed to read {}: {e}", path.display()),
+        })
+    }
+
+    /// Carry out every operation in this process, going on past failures.
+    pub fn run(&self) -> Vec<ElevatedResult> {
+        self.run_with(&|from, to| rename(from, to))
+    }
+
+    /// Carry out every operation, moving files with `rename` when it can.
+    fn run_with(&self, rename: &dyn Fn(&Path, &Path) -> ZResult<()>) -> Vec<ElevatedResult> {
+        let mut results: Vec<_> = self
+            .operations
+            .iter()
+            .map(|operation| ElevatedResult {
+                operation: operation.clone(),
+                error: operation.run_with(rename).err().map(|e| e.to_string()),
+            })
+            .collect();
+
+        // Folders come before their contents, so going backwards removes
+        // the folders being moved once whatever of them could move has
+        for result in results.iter_mut().rev() {
+            if let (ElevatedOperation::Move { source, .. }, None) =
+                (&result.operation, &result.error)
+            {
+                if source.is_dir() {
+                    result.error = remove_moved_folder(source).err().map(|e| e.to_string());
+                }
+            }
+        }
+        for result in &results {
+            if let Some(error) = &result.error {
+                let path = result.operation.source().display();
+                warn!(path = %path, %error, "Elevated operation failed");
+            }
+        }
+        results
+    }
+}
+
+/// Check if a failure reason from a report means access was denied.
+pub fn is_access_denied(reason: &str) -> bool {
+    let reason = reason.to_ascii_lowercase();
+    reason.starts_with("permission denied")
+        || reason.contains("access is denied")
+        || reason.contains("os error 5)")
+}
+
+/// Where the helper writes the results of the manifest at `manifest`.
+pub fn results_path(manifest: &Path) -> PathBuf {
+    manifest.with_extension("results.json")
+}
+
+/// Write the helper's `results` for the manifest at `manifest`.
+///
+/// The results file must not exist yet, so a link planted under its
+/// predictable name is never followed, and the folders holding it must not
+/// be symbolic links or junctions.
+///
+/// # Errors
+/// * `ZError::AlreadyExists` - Something is already at the results path
+/// * `ZError::InvalidOperation` - The manifest's folder is a link
+pub fn write_results(manifest: &Path, results: &[ElevatedResult]) -> ZResult<()> {
+    check_manifest_folder(manifest)?;
+    let json = serde_json::to_vec_pretty(results).map_err(|e| ZError::Internal {
+        message: format!("Failed to serialize elevation results: {e}"),
+    })?;
+    let path = results_path(manifest);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| ZError::from_io(&path, e))?;
+    file.write_all(&json).map_err(|e| ZError::io(&path, e))
+}
+
+/// Carry out `manifest` in the elevated helper and return its results.
+///
+/// Blocks until the helper exits. Returns [`ZError::Cancelled`] if the user
+/// declines the UAC prompt.
+pub fn run_elevated(manifest: &ElevationManifest) -> ZResult<Vec<ElevatedResult>> {
+    let dir = std::env::temp_dir().join("ZManager").join("elevation");
+    fs::create_dir_all(&dir).map_err(|e| ZError::io(&dir, e))?;
+    check_manifest_folder(&dir.join("manifest.json"))?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = dir.join(format!("{}-{stamp}.json", std::process::id()));
+    let sha256 = manifest.save(&path)?;
+
+    info!(operations = manifest.len(), "Retrying operations elevated");
+    let result = spawn_helper(&path, &sha256).and_then(|()| read_results(&results_path(&path)));
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(results_path(&path));
+    result
+}
+
+/// Fold elevated `results` into `report`: items that now succeeded are
+/// marked as such, and items that failed again get the new reason.
+pub fn merge_elevated_results(report: &mut DetailedTransferReport, results: &[ElevatedResult]) {
+    for result in results {
+        let Some(item) = report
+            .items
+            .iter_mut()
+            .find(|item| item.is_failed() && item.source == result.operation.source())
+        else {
+            continue;
+        };
+        match &result.error {
+            None => {
+                item.status = TransferStatus::Success;
+                item.reason = None;
+            }
+            Some(error) => item.reason = Some(format!("As administrator: {error}")),
+        }
+    }
+    report.refresh_summary();
+}
+
+/// Path of the helper binary, next to the running executable.
+pub fn helper_path() -> ZResult<PathBuf> {
+    let exe = std::env::current_exe().map_err(|e| ZError::Internal {
+        message: format!("Failed to find the running executable: {e}"),
+    })?;
+    let dir = exe.parent().unwrap_or(Path::new("."));
+    Ok(dir.join(format!("{HELPER_NAME}{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// Start the helper on `manifest`, whose SHA-256 is `sha256`, through UAC
+/// and wait for it to exit.
+#[cfg(windows)]
+fn spawn_helper(manifest: &Path, sha256: &str) -> ZResult<()> {
+    use std::ffi::OsStr;
+    use std::mem::size_of;
+    use std::os::windows::ffi::OsStrExt;
+
+    use tracing::debug;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_CANCELLED};
+    use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+    };
+
+    let helper = helper_path()?;
+    if !helper.exists() {
+        return Err(ZError::NotFound { path: helper });
+    }
+    debug!(helper = %helper.display(), manifest = %manifest.display(), "Starting elevated helper");
+
+    let wide = |text: &OsStr| -> Vec<u16> { text.encode_wide().chain(Some(0)).collect() };
+    let verb = wide(OsStr::new("runas"));
+    let file = wide(helper.as_os_str());
+    let params = wide(OsStr::new(&format!("\"{}\" {sha256}", manifest.display())));
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC,
+        lpVerb: PCWSTR(verb.as_ptr()),
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: PCWSTR(params.as_ptr()),
+        nShow: 0, // SW_HIDE
+        ..Default::default()
+    };
+    unsafe { ShellExecuteExW(&mut info) }.map_err(|e| {
+        if e.code() == ERROR_CANCELLED.to_hresult() {
+            ZError::Cancelled
+        } else {
+            ZError::from_io(&helper, e.into())
+        }
+    })?;
+
+    let mut code = 0u32;
+    unsafe {
+        WaitForSingleObject(info.hProcess, INFINITE);
+        let _ = GetExitCodeProcess(info.hProcess, &mut code);
+        let _ = CloseHandle(info.hProcess);
+    }
+    if code != 0 {
+        return Err(ZError::Internal {
+            message: format!("Elevated helper exited with code {code}"),
+        });
+    }
+    Ok(())
+}
+
+/// Start the helper on `manifest` (non-Windows fallback).
+#[cfg(not(windows))]
+fn spawn_helper(_manifest: &Path, _sha256: &str) -> ZResult<()> {
+    Err(ZError::InvalidOperation {
+        operation: "retry as administrator".to_string(),
+        reason: "Elevation is only available on Windows".to_string(),
+    })
+}
+
+/// Copy a file to `destination`, or create it as a folder when `source` is
+/// one, creating missing parent folders.
+fn copy_item(source: &Path, destination: &Path) -> ZResult<()> {
+    if source.is_dir() {
+        return fs::create_dir_all(extended_path(destination))
+            .map_err(|e| ZError::from_io(destination, e));
+    }
+    create_parent(destination)?;
+    fs::copy(extended_path(source), extended_path(destination))
+        .map(|_| ())
+        .map_err(|e| ZError::from_io(source, e))
+}
+
+/// Remove a folder whose contents have been moved, unless something was
+/// left in it.
+fn remove_moved_folder(path: &Path) -> ZResult<()> {
+    let mut entries = fs::read_dir(extended_path(path)).map_err(|e| ZError::from_io(path, e))?;
+    if entries.next().is_some() {
+        return Err(ZError::DirectoryNotEmpty {
+            path: path.to_path_buf(),
+        });
+    }
+    fs::remove_dir(extended_path(path)).map_err(|e| ZError::from_io(path, e))
+}
+
+/// Create the folder `path` goes in, if it is missing.
+fn create_parent(path: &Path) -> ZResult<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            fs::create_dir_all(extended_path(parent)).map_err(|e| ZError::from_io(parent, e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Refuse to use the folder holding `manifest`, or the `ZManager` folder
+/// above it, if either has been replaced by a symbolic link or junction.
+fn check_manifest_folder(manifest: &Path) -> ZResult<()> {
+    for dir in manifest.ancestors().skip(1).take(2) {
+        if dir.as_os_str().is_empty() {
+            break;
+        }
+        if is_reparse_point(dir) {
+            return Err(ZError::InvalidOperation {
+                operation: "retry as administrator".to_string(),
+                reason: format!("{} is a link", dir.display()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check if `path` is a reparse point, such as a symbolic link or junction.
+#[cfg(windows)]
+fn is_reparse_point(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    fs::symlink_metadata(path)
+        .is_ok_and(|meta| meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0)
+}
+
+/// Check if `path` is a symbolic link (non-Windows fallback).
+#[cfg(not(windows))]
+fn is_reparse_point(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink())
+}
+
+fn read_results(path: &Path) -> ZResult<Vec<ElevatedResult>> {
+    let json = fs::read(path).map_err(|e| ZError::from_io(path, e))?;
+    serde_json::from_slice(&json).map_err(|e| ZError::Internal {
+        message: format!("Failed to read {}: {e}", path.display()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ReportBuilder, TransferItemResult};
+    use tempfile::TempDir;
+    use zmanager_core::JobId;
+
+    #[test]
+    fn test_manifest_from_report_keeps_access_denied_items() {
+        let mut builder = ReportBuilder::new(JobId(1), TransferOperation::Copy);
+        builder.add_item(TransferItemResult::success("a".into(), "d/a".into(), 1));
+        builder.add_item(TransferItemResult::failed(
+            "b".into(),
+            "d/b".into(),
+            "Permission denied: 'd/b'",
+        ));
+        builder.add_item(TransferItemResult::failed(
+            "c".into(),
+            "d/c".into(),
+            "Disk full",
+        ));
+        let manifest = ElevationManifest::from_report(&builder.build(), false);
+
+        assert_eq!(
+            manifest.operations,
+            vec![ElevatedOperation::Copy {
+                source: "b".into(),
+                destination: "d/b".into(),
+            }]
+        );
+        assert!(is_access_denied(
+            "I/O error at 'x': Access is denied. (os error 5)"
+        ));
+    }
+
+    #[test]
+    fn test_run_manifest_and_merge_results() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("a.txt");
+        let destination = temp.path().join("out").join("a.txt");
+        fs::write(&source, b"data").unwrap();
+
+        let manifest = ElevationManifest::new(vec![
+            ElevatedOperation::Copy {
+                source: source.clone(),
+                destination: destination.clone(),
+            },
+            ElevatedOperation::Delete {
+                path: temp.path().join("missing"),
+                permanent: true,
+            },
+        ]);
+        let path = temp.path().join("manifest.json");
+        let sha256 = manifest.save(&path).unwrap();
+        let results = ElevationManifest::load(&path, &sha256).unwrap().run();
+
+        assert!(results[0].is_success());
+        assert!(!results[1].is_success());
+        assert_eq!(fs::read(&destination).unwrap(), b"data");
+
+        let mut builder = ReportBuilder::new(JobId(1), TransferOperation::Copy);
+        builder.add_item(TransferItemResult::failed(
+            source,
+            destination,
+            "Permission denied",
+        ));
+        let mut report = builder.build();
+        merge_elevated_results(&mut report, &results);
+        assert_eq!((report.summary.succeeded, report.summary.failed), (1, 0));
+        assert_eq!(report.items[0].reason, None);
+    }
+
+    #[test]
+    fn test_changed_manifest_is_refused() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("manifest.json");
+        let manifest = ElevationManifest::new(vec![ElevatedOperation::Delete {
+            path: temp.path().join("a.txt"),
+            permanent: true,
+        }]);
+        let sha256 = manifest.save(&path).unwrap();
+
+        ElevationManifest::new(vec![ElevatedOperation::Delete {
+            path: temp.path().join("b.txt"),
+            permanent: true,
+        }])
+        .save(&path)
+        .unwrap();
+        assert!(matches!(
+            ElevationManifest::load(&path, &sha256),
+            Err(ZError::InvalidOperation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_results_are_never_written_through_an_existing_file() {
+        let temp = TempDir::new().unwrap();
+        let manifest = temp.path().join("elevation").join("manifest.json");
+        fs::create_dir(temp.path().join("elevation")).unwrap();
+        let results = vec![ElevatedResult {
+            operation: ElevatedOperation::Delete {
+                path: temp.path().join("a.txt"),
+                permanent: true,
+            },
+            error: None,
+        }];
+
+        fs::write(results_path(&manifest), b"planted").unwrap();
+        assert!(matches!(
+            write_results(&manifest, &results),
+            Err(ZError::AlreadyExists { .. })
+        ));
+        assert_eq!(fs::read(results_path(&manifest)).unwrap(), b"planted");
+
+        fs::remove_file(results_path(&manifest)).unwrap();
+        write_results(&manifest, &results).unwrap();
+        assert_eq!(read_results(&results_path(&manifest)).unwrap(), results);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_results_are_refused_in_a_linked_folder() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("target");
+        fs::create_dir(&target).unwrap();
+        std::os::unix::fs::symlink(&target, temp.path().join("elevation")).unwrap();
+
+        let manifest = temp.path().join("elevation").join("manifest.json");
+        assert!(matches!(
+            write_results(&manifest, &[]),
+            Err(ZError::InvalidOperation { .. })
+        ));
+        assert!(!results_path(&target.join("manifest.json")).exists());
+    }
+
+    #[test]
+    fn test_move_folder_across_volumes_keeps_files_until_moved() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("src");
+        let destination = temp.path().join("dst");
+        fs::create_dir_all(source.join("sub")).unwrap();
+        fs::write(source.join("a.txt"), b"a").unwrap();
+        fs::write(source.join("sub").join("b.txt"), b"b").unwrap();
+        fs::create_dir(temp.path().join("kept")).unwrap();
+        fs::write(temp.path().join("kept").join("c.txt"), b"c").unwrap();
+
+        let moved = |path: &str| ElevatedOperation::Move {
+            source: temp.path().join("src").join(path),
+            destination: destination.join(path),
+        };
+        // `kept/c.txt` failed for another reason, so it isn't retried
+        let manifest = ElevationManifest::new(vec![
+            ElevatedOperation::Move {
+                source: source.clone(),
+                destination: destination.clone(),
+            },
+            moved("a.txt"),
+            moved("sub"),
+            moved("sub/b.txt"),
+            ElevatedOperation::Move {
+                source: temp.path().join("kept"),
+                destination: temp.path().join("out").join("kept"),
+            },
+        ]);
+        let across_volumes = |_: &Path, _: &Path| -> ZResult<()> {
+            Err(ZError::InvalidOperation {
+                operation: "rename".to_string(),
+                reason: "not the same device".to_string(),
+            })
+        };
+        let results = manifest.run_with(&across_volumes);
+
+        assert!(results[..4].iter().all(ElevatedResult::is_success));
+        assert_eq!(fs::read(destination.join("a.txt")).unwrap(), b"a");
+        assert_eq!(fs::read(destination.join("sub").join("b.txt")).unwrap(), b"b");
+        assert!(!source.exists());
+        assert!(!results[4].is_success());
+        assert_eq!(fs::read(temp.path().join("kept").join("c.txt")).unwrap(), b"c");
+    }
+}
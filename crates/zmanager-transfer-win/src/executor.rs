@@ -3,16 +3,21 @@
 //! This module provides the execution logic that connects the copy primitives
 //! to the job system from zmanager-core.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 #[allow(unused_imports)]
-use zmanager_core::{CancellationToken, Job, JobId, JobKind, JobState, Progress, ZError, ZResult};
+use zmanager_core::{
+    CancellationToken, Job, JobId, JobKind, JobState, PauseToken, Progress, SpeedLimit, ZError,
+    ZResult,
+};
 
-use crate::copy::{copy_file_with_progress, CopyProgress, CopyResult, ProgressCallback};
+use crate::backend::BackendRegistry;
+use crate::copy::{speed_limit, CopyProgress, CopyResult, ProgressCallback};
+use crate::retry::RetryPolicy;
 
 /// Events emitted during job execution.
 #[derive(Debug, Clone)]
@@ -36,6 +41,13 @@ pub struct ExecutorConfig {
     pub progress_interval_bytes: u64,
     /// Minimum interval between progress updates (in milliseconds).
     pub progress_interval_ms: u64,
+    /// How transient I/O errors (sharing violations, dropped shares) are retried.
+    pub retry: RetryPolicy,
+    /// Default bandwidth limit in bytes per second (None for unlimited).
+    /// A job that has its own limit keeps it.
+    pub speed_limit: Option<u64>,
+    /// Backends the copies run on, picked per job from its paths.
+    pub backends: BackendRegistry,
 }
 
 impl Default for ExecutorConfig {
@@ -43,6 +55,9 @@ impl Default for ExecutorConfig {
         Self {
             progress_interval_bytes: 1024 * 1024, // 1MB
             progress_interval_ms: 100,            // 100ms
+            retry: RetryPolicy::default(),
+            speed_limit: None,
+            backends: BackendRegistry::default(),
         }
     }
 }
@@ -93,17 +108,47 @@ impl CopyExecutor {
         overwrite: bool,
         cancel_token: CancellationToken,
     ) -> ZResult<CopyResult> {
-        let source = source.as_ref().to_path_buf();
-        let destination = destination.as_ref().to_path_buf();
+        self.copy_one(
+            job_id,
+            source.as_ref().to_path_buf(),
+            destination.as_ref().to_path_buf(),
+            overwrite,
+            cancel_token,
+            PauseToken::new(),
+            speed_limit(None, self.config.speed_limit),
+        )
+        .await
+    }
+
+    /// Copy one file, parking between chunks while `pause_token` is set and
+    /// holding chunks back to stay under `speed_limit`.
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_one(
+        &self,
+        job_id: JobId,
+        source: PathBuf,
+        destination: PathBuf,
+        overwrite: bool,
+        cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+    ) -> ZResult<CopyResult> {
         let start_time = Instant::now();
 
         // Emit job started event
         let _ = self.event_tx.send(ExecutorEvent::JobStarted { job_id });
 
+        let backend = self
+            .config
+            .backends
+            .select(std::slice::from_ref(&source), &destination)
+            .unwrap_or_else(|| Arc::clone(self.config.backends.default_backend()));
+
         debug!(
             job_id = %job_id,
             source = %source.display(),
             destination = %destination.display(),
+            backend = backend.name(),
             "Starting single file copy"
         );
 
@@ -119,7 +164,7 @@ impl CopyExecutor {
         let last_progress_time = Arc::new(std::sync::Mutex::new(Instant::now()));
         let source_clone = source.clone();
 
-        let callback: ProgressCallback = Box::new(move |copy_progress: CopyProgress| {
+        let on_progress = Arc::new(move |copy_progress: CopyProgress| {
             let last_bytes = last_progress_bytes.load(std::sync::atomic::Ordering::Relaxed);
             let bytes_since_last = copy_progress.bytes_copied.saturating_sub(last_bytes);
             
@@ -149,28 +194,44 @@ impl CopyExecutor {
             }
         });
 
-        // Execute the copy in a blocking task
-        let source_for_copy = source.clone();
-        let dest_for_copy = destination.clone();
-
-        let result = tokio::task::spawn_blocking(move || {
-            copy_file_with_progress(
-                &source_for_copy,
-                &dest_for_copy,
-                overwrite,
-                cancel_token,
-                Some(callback),
-            )
-        })
-        .await
-        .map_err(|e| ZError::Internal {
-            message: format!("Task join error: {e}"),
-        })?;
+        // Execute the copy in a blocking task, retrying transient failures
+        let result = self
+            .config
+            .retry
+            .run(&cancel_token, || {
+                let backend = Arc::clone(&backend);
+                let source_for_copy = source.clone();
+                let dest_for_copy = destination.clone();
+                let token = cancel_token.clone();
+                let pause = pause_token.clone();
+                let limit = speed_limit.clone();
+                let on_progress = on_progress.clone();
+                let callback: ProgressCallback = Box::new(move |p| on_progress(p));
+
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        backend.copy_file(
+                            &source_for_copy,
+                            &dest_for_copy,
+                            overwrite,
+                            token,
+                            pause,
+                            limit,
+                            Some(callback),
+                        )
+                    })
+                    .await
+                    .map_err(|e| ZError::Internal {
+                        message: format!("Task join error: {e}"),
+                    })?
+                }
+            })
+            .await;
 
         let duration = start_time.elapsed();
 
         match result {
-            Ok(bytes_copied) => {
+            Ok((bytes_copied, retries)) => {
                 let average_speed = if duration.as_secs() > 0 {
                     bytes_copied / duration.as_secs()
                 } else if duration.as_millis() > 0 {
@@ -185,11 +246,13 @@ impl CopyExecutor {
                     bytes_copied,
                     duration,
                     average_speed_bps: average_speed,
+                    retries,
                 };
 
                 info!(
                     job_id = %job_id,
                     bytes = bytes_copied,
+                    retries,
                     duration_ms = duration.as_millis(),
                     speed_mbps = average_speed as f64 / 1_000_000.0,
                     "Copy completed"
@@ -217,13 +280,13 @@ impl CopyExecutor {
 
                 Ok(copy_result)
             }
-            Err(ZError::Cancelled) => {
+            Err((ZError::Cancelled, _)) => {
                 warn!(job_id = %job_id, "Copy cancelled");
                 let _ = self.event_tx.send(ExecutorEvent::JobCancelled { job_id });
                 Err(ZError::Cancelled)
             }
-            Err(e) => {
-                error!(job_id = %job_id, error = %e, "Copy failed");
+            Err((e, retries)) => {
+                error!(job_id = %job_id, error = %e, retries, "Copy failed");
                 let _ = self.event_tx.send(ExecutorEvent::JobFailed {
                     job_id,
                     error: e.to_string(),
@@ -241,11 +304,15 @@ impl CopyExecutor {
         job: &Job,
         cancel_token: CancellationToken,
     ) -> ZResult<Vec<CopyResult>> {
+        let limit = speed_limit(Some(job), self.config.speed_limit);
         match &job.kind {
             JobKind::Copy { sources, destination } => {
                 let mut results = Vec::with_capacity(sources.len());
                 
                 for source in sources {
+                    // Park between files while the job is paused
+                    job.pause.wait(&cancel_token).await?;
+
                     // Determine destination path
                     let dest_path = if destination.is_dir() || sources.len() > 1 {
                         // Copy into directory
@@ -260,12 +327,14 @@ impl CopyExecutor {
                     };
 
                     let result = self
-                        .execute_single_copy(
+                        .copy_one(
                             job.id,
                             source.clone(),
                             dest_path,
                             false, // Don't overwrite by default
                             cancel_token.clone(),
+                            job.pause.clone(),
+                            limit.clone(),
                         )
                         .await?;
 
@@ -285,6 +354,8 @@ impl CopyExecutor {
                 let mut results = Vec::with_capacity(sources.len());
                 
                 for source in sources {
+                    job.pause.wait(&cancel_token).await?;
+
                     let dest_path = if destination.is_dir() || sources.len() > 1 {
                         let file_name = source.file_name().ok_or_else(|| ZError::InvalidPath {
                             path: source.clone(),
@@ -297,12 +368,14 @@ impl CopyExecutor {
 
                     // Copy first
                     let result = self
-                        .execute_single_copy(
+                        .copy_one(
                             job.id,
                             source.clone(),
                             dest_path,
                             false,
                             cancel_token.clone(),
+                            job.pause.clone(),
+                            limit.clone(),
                         )
                         .await?;
 
@@ -336,6 +409,7 @@ mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
+    use zmanager_core::Priority;
 
     fn create_test_file(dir: &TempDir, name: &str, size: usize) -> std::path::PathBuf {
         let path = dir.path().join(name);
@@ -394,12 +468,18 @@ mod tests {
                 destination: dest_dir.clone(),
             },
             state: JobState::Pending,
+            priority: Priority::default(),
             progress: Progress::new(1, Some(512)),
             cancellation: CancellationToken::new(),
             error: None,
             created_at: Instant::now(),
             started_at: None,
             finished_at: None,
+            pause: PauseToken::new(),
+            speed_limit: SpeedLimit::new(),
+            paused_at: None,
+            paused_time: std::time::Duration::ZERO,
+            retry: Vec::new(),
         };
 
         let executor = CopyExecutor::new();
@@ -425,12 +505,18 @@ mod tests {
                 destination: dest.clone(),
             },
             state: JobState::Pending,
+            priority: Priority::default(),
             progress: Progress::new(1, Some(256)),
             cancellation: CancellationToken::new(),
             error: None,
             created_at: Instant::now(),
             started_at: None,
             finished_at: None,
+            pause: PauseToken::new(),
+            speed_limit: SpeedLimit::new(),
+            paused_at: None,
+            paused_time: std::time::Duration::ZERO,
+            retry: Vec::new(),
         };
 
         let executor = CopyExecutor::new();
@@ -442,6 +528,34 @@ mod tests {
         assert!(!source.exists()); // Source should be deleted after move
     }
 
+    #[tokio::test]
+    async fn test_paused_job_waits_for_resume() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_file(&temp, "source.txt", 512);
+        let dest_dir = temp.path().join("dest_dir");
+        fs::create_dir(&dest_dir).unwrap();
+
+        let mut job = Job::new(JobKind::Copy {
+            sources: vec![source],
+            destination: dest_dir.clone(),
+        });
+        job.start();
+        job.pause();
+
+        let pause = job.pause.clone();
+        let token = job.cancellation.clone();
+        let copy = tokio::spawn(async move { CopyExecutor::new().execute_job(&job, token).await });
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!copy.is_finished());
+        assert!(!dest_dir.join("source.txt").exists());
+
+        pause.resume();
+        let results = copy.await.unwrap().unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(dest_dir.join("source.txt").exists());
+    }
+
     #[tokio::test]
     async fn test_execute_with_cancellation() {
         let temp = TempDir::new().unwrap();
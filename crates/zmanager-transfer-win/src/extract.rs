@@ -0,0 +1,325 @@
+//! Job executor for archive extraction.
+//!
+//! Runs [`JobKind::Extract`] jobs on a blocking thread with
+//! [`zmanager_core::extract_archive`] and turns its progress callbacks into
+//! throttled events, so extraction shows up like any other transfer.
+//! Files that already exist at the destination are settled by a
+//! [`ConflictResolver`], as in a copy.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    extract_archive, CancellationToken, EntryMeta, ExtractResult, Job, JobId, JobKind, Progress,
+    ZError, ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+use crate::conflict::{Conflict, ConflictResolution, ConflictResolver};
+
+/// Events emitted during extraction.
+#[derive(Debug, Clone)]
+pub enum ExtractEvent {
+    /// Extraction started.
+    Started { job_id: JobId },
+    /// Progress update.
+    Progress { job_id: JobId, progress: Progress },
+    /// Extraction completed.
+    Completed { job_id: JobId, result: ExtractResult },
+    /// Extraction failed.
+    Failed { job_id: JobId, error: String },
+    /// Extraction was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the extract executor.
+#[derive(Debug, Clone)]
+pub struct ExtractConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for ExtractConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for archive extraction jobs.
+pub struct ExtractExecutor {
+    config: ExtractConfig,
+    event_tx: broadcast::Sender<ExtractEvent>,
+}
+
+impl ExtractExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(ExtractConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: ExtractConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to extraction events.
+    pub fn subscribe(&self) -> broadcast::Receiver<ExtractEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute an extract job from the job system.
+    ///
+    /// Existing files are settled by `resolver`. There is no one to ask
+    /// during an extraction, so conflicts it leaves to the user are skipped.
+    /// Any other job kind is rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        resolver: ConflictResolver,
+        cancel_token: CancellationToken,
+    ) -> ZResult<ExtractResult> {
+        let JobKind::Extract {
+            archive,
+            entries,
+            destination,
+        } = &job.kind
+        else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for extract executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(
+            job_id = %job_id,
+            archive = %archive.display(),
+            destination = %destination.display(),
+            entries = entries.len(),
+            "Starting extraction"
+        );
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let (archive, entries, destination) =
+            (archive.clone(), entries.clone(), destination.clone());
+        let start_time = Instant::now();
+
+        let result = run_blocking(job_id, "Extraction", &self.event_tx, interval, move |reporter| {
+            let on_conflict =
+                |source: &EntryMeta, target: &Path| resolve(&resolver, source, target);
+            let on_progress = |progress: &Progress| {
+                // The final update (no current item) is always sent
+                let progress = with_speed(progress, start_time.elapsed());
+                reporter.report(&progress, progress.current_item.is_none());
+            };
+            extract_archive(&archive, &entries, &destination, &cancel_token, on_conflict, on_progress)
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            files = result.files,
+            skipped = result.skipped,
+            bytes = result.bytes,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Extraction completed"
+        );
+        Ok(result)
+    }
+}
+
+impl Default for ExtractExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for ExtractEvent {
+    type Update = Progress;
+    type Output = ExtractResult;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, progress: &Progress) -> Self {
+        let progress = progress.clone();
+        Self::Progress { job_id, progress }
+    }
+
+    fn completed(job_id: JobId, result: &ExtractResult) -> Self {
+        let result = result.clone();
+        Self::Completed { job_id, result }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+/// Where to write the archive file `source` whose `target` already exists,
+/// or `None` to skip it.
+fn resolve(
+    resolver: &ConflictResolver,
+    source: &EntryMeta,
+    target: &Path,
+) -> ZResult<Option<PathBuf>> {
+    let existing = std::fs::metadata(target).map_err(|e| ZError::from_io(target, e))?;
+    let conflict = Conflict {
+        source: source.path.clone(),
+        destination: target.to_path_buf(),
+        source_size: source.size,
+        dest_size: existing.len(),
+        source_modified: source.modified.map(Into::into),
+        dest_modified: existing.modified().ok(),
+        is_dir: false,
+    };
+    match resolver.resolve(&conflict).unwrap_or(ConflictResolution::Skip) {
+        ConflictResolution::Overwrite => Ok(Some(target.to_path_buf())),
+        ConflictResolution::Skip => Ok(None),
+        ConflictResolution::Rename => Ok(Some(ConflictResolver::generate_rename_path(target))),
+        ConflictResolution::Cancel => Err(ZError::Cancelled),
+    }
+}
+
+/// Fill in speed and ETA from the bytes written so far.
+fn with_speed(progress: &Progress, elapsed: Duration) -> Progress {
+    let mut progress = progress.clone();
+    let millis = elapsed.as_millis() as u64;
+    if let Some(speed) = progress.bytes_done.saturating_mul(1000).checked_div(millis) {
+        progress.speed_bytes_per_sec = Some(speed);
+        if speed > 0 {
+            let remaining = progress.total_bytes.map(|t| t.saturating_sub(progress.bytes_done));
+            progress.eta = remaining.map(|bytes| Duration::from_secs(bytes / speed));
+        }
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflict::{ConflictPolicy, ConflictSettings};
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn create_zip(dir: &Path) -> PathBuf {
+        let path = dir.join("bundle.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        writer.start_file("docs/a.txt", SimpleFileOptions::default()).unwrap();
+        writer.write_all(&[b'A'; 4096]).unwrap();
+        writer.start_file("b.txt", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"bee").unwrap();
+        writer.finish().unwrap();
+        path
+    }
+
+    fn extract_job(archive: PathBuf, destination: PathBuf) -> Job {
+        Job::new(JobKind::Extract {
+            archive,
+            entries: vec![],
+            destination,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_extract_job() {
+        let temp = TempDir::new().unwrap();
+        let dest = temp.path().join("out");
+        let job = extract_job(create_zip(temp.path()), dest.clone());
+
+        let executor = ExtractExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor
+            .execute_job(&job, ConflictResolver::new(), CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(result.files, 2);
+        assert_eq!(result.bytes, 4099);
+        assert_eq!(fs::read(dest.join("b.txt")).unwrap(), b"bee");
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                ExtractEvent::Progress { progress, .. } => last_progress = Some(progress),
+                ExtractEvent::Completed { job_id, .. } => got_completed = job_id == job.id,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        assert_eq!(last_progress.unwrap().percentage_int(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_extract_job_resolves_existing_files() {
+        let temp = TempDir::new().unwrap();
+        let dest = temp.path().join("out");
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("b.txt"), b"mine").unwrap();
+        let job = extract_job(create_zip(temp.path()), dest.clone());
+        let executor = ExtractExecutor::new();
+
+        // Asking isn't possible, so the file is skipped
+        let result = executor
+            .execute_job(&job, ConflictResolver::new(), CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!((result.files, result.skipped), (1, 1));
+        assert_eq!(fs::read(dest.join("b.txt")).unwrap(), b"mine");
+
+        let rename = ConflictResolver::with_settings(ConflictSettings {
+            file_policy: ConflictPolicy::Rename,
+            ..ConflictSettings::default()
+        });
+        executor
+            .execute_job(&job, rename, CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!(fs::read(dest.join("b (1).txt")).unwrap(), b"bee");
+        assert_eq!(fs::read(dest.join("docs").join("a (1).txt")).unwrap(), [b'A'; 4096]);
+        assert_eq!(fs::read(dest.join("b.txt")).unwrap(), b"mine");
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_extract_job() {
+        let temp = TempDir::new().unwrap();
+        let job = extract_job(create_zip(temp.path()), temp.path().join("out"));
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = ExtractExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor.execute_job(&job, ConflictResolver::new(), token).await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, ExtractEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::Delete {
+            paths: vec![PathBuf::from("a")],
+        });
+
+        let result = ExtractExecutor::new()
+            .execute_job(&job, ConflictResolver::new(), CancellationToken::new())
+            .await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
@@ -5,16 +5,27 @@
 
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tracing::{debug, info, trace, warn};
-use zmanager_core::{CancellationToken, JobId, Progress, ZError, ZResult};
-
-use crate::conflict::{Conflict, ConflictResolution, ConflictResolver};
-use crate::copy::{copy_file_with_progress, CopyProgress, ProgressCallback};
+use zmanager_core::{
+    copy_link, extended_path, remove_link, CancellationToken, ChecksumAlgorithm, Job, JobId,
+    JobKind, LinkHandling, PauseToken, Progress, SpeedEstimator, SpeedLimit, ZError, ZResult,
+};
+
+use crate::conflict::{Conflict, ConflictAnswer, ConflictResolution, ConflictResolver};
+use crate::backend::{BackendRegistry, ResumableBackend, TransferBackend, UnbufferedBackend};
+use crate::copy::{speed_limit, CopyProgress, ProgressCallback};
+use crate::journal::TransferJournal;
 use crate::plan::{same_volume, TransferItem, TransferPlan, TransferPlanBuilder, TransferStats};
+use crate::report::TransferItemResult;
+use crate::resume::PartialCopy;
+use crate::retry::RetryPolicy;
+use crate::verify::verify_copy;
 
 /// Result for a single item transfer.
 #[derive(Debug, Clone)]
@@ -24,6 +35,16 @@ pub enum ItemResult {
         source: PathBuf,
         destination: PathBuf,
         bytes: u64,
+        /// Retries needed after transient errors.
+        retries: u32,
+    },
+    /// Item transferred and its checksum matched the source.
+    Verified {
+        source: PathBuf,
+        destination: PathBuf,
+        bytes: u64,
+        /// Retries needed after transient errors.
+        retries: u32,
     },
     /// Item was skipped (e.g., conflict policy).
     Skipped {
@@ -36,26 +57,87 @@ pub enum ItemResult {
         source: PathBuf,
         destination: PathBuf,
         error: String,
+        /// Retries attempted before giving up.
+        retries: u32,
+    },
+    /// Item was copied, but the copy couldn't be verified against the source.
+    VerifyFailed {
+        source: PathBuf,
+        destination: PathBuf,
+        bytes: u64,
+        error: String,
+        /// Retries needed after transient errors.
+        retries: u32,
     },
 }
 
 impl ItemResult {
     /// Check if this result is a success.
     pub fn is_success(&self) -> bool {
-        matches!(self, Self::Success { .. })
+        matches!(self, Self::Success { .. } | Self::Verified { .. })
     }
 
     /// Check if this result is a failure.
     pub fn is_failed(&self) -> bool {
-        matches!(self, Self::Failed { .. })
+        matches!(self, Self::Failed { .. } | Self::VerifyFailed { .. })
+    }
+
+    /// Number of retries spent on this item.
+    pub fn retries(&self) -> u32 {
+        match self {
+            Self::Success { retries, .. }
+            | Self::Verified { retries, .. }
+            | Self::Failed { retries, .. }
+            | Self::VerifyFailed { retries, .. } => *retries,
+            Self::Skipped { .. } => 0,
+        }
     }
 
     /// Get the source path.
     pub fn source(&self) -> &Path {
         match self {
             Self::Success { source, .. }
+            | Self::Verified { source, .. }
             | Self::Skipped { source, .. }
-            | Self::Failed { source, .. } => source,
+            | Self::Failed { source, .. }
+            | Self::VerifyFailed { source, .. } => source,
+        }
+    }
+}
+
+impl From<&ItemResult> for TransferItemResult {
+    fn from(result: &ItemResult) -> Self {
+        match result.clone() {
+            ItemResult::Success {
+                source,
+                destination,
+                bytes,
+                ..
+            } => Self::success(source, destination, bytes),
+            ItemResult::Verified {
+                source,
+                destination,
+                bytes,
+                ..
+            } => Self::verified(source, destination, bytes),
+            ItemResult::Skipped {
+                source,
+                destination,
+                reason,
+            } => Self::skipped(source, destination, reason),
+            ItemResult::Failed {
+                source,
+                destination,
+                error,
+                ..
+            } => Self::failed(source, destination, error),
+            ItemResult::VerifyFailed {
+                source,
+                destination,
+                bytes,
+                error,
+                ..
+            } => Self::verify_failed(source, destination, bytes, error),
         }
     }
 }
@@ -73,6 +155,8 @@ pub struct TransferReport {
     pub skipped: usize,
     /// Number of failed items.
     pub failed: usize,
+    /// Number of files and folders left out by include and exclude patterns.
+    pub excluded: usize,
     /// Total duration.
     pub duration: std::time::Duration,
 }
@@ -122,10 +206,12 @@ pub enum FolderTransferEvent {
 /// Request for conflict resolution from the UI.
 #[derive(Debug)]
 pub struct ConflictQuery {
+    /// The job whose transfer is waiting on the answer.
+    pub job_id: JobId,
     /// The conflict that needs resolution.
     pub conflict: Conflict,
-    /// Channel to send the resolution.
-    pub response: oneshot::Sender<ConflictResolution>,
+    /// Channel to send the answer.
+    pub response: oneshot::Sender<ConflictAnswer>,
 }
 
 /// Configuration for folder transfers.
@@ -133,21 +219,71 @@ pub struct ConflictQuery {
 pub struct FolderTransferConfig {
     /// Maximum concurrent file copies.
     pub concurrency: usize,
+    /// Files at least this large are copied on their own, with no other
+    /// file alongside, so they don't compete for the disk.
+    pub exclusive_min_size: u64,
     /// Whether to continue on individual file errors.
     pub continue_on_error: bool,
     /// Whether to delete source after successful move.
     pub delete_source_on_move: bool,
     /// Progress update interval in bytes.
     pub progress_interval_bytes: u64,
+    /// Retry policy for transient file copy errors.
+    pub retry: RetryPolicy,
+    /// Default bandwidth limit in bytes per second (None for unlimited).
+    /// A job that has its own limit keeps it.
+    pub speed_limit: Option<u64>,
+    /// Checksum used to verify each copied file (None to skip verification).
+    pub verify: Option<ChecksumAlgorithm>,
+    /// Files at least this large are copied resumably, keeping a partial copy
+    /// when interrupted (None to always copy in one go, the default).
+    /// Resumable copies keep the modification time, but not the attributes,
+    /// creation time, alternate data streams or permissions.
+    pub resumable_min_size: Option<u64>,
+    /// Backends the transfers run on. A job goes to a registered backend
+    /// that handles all its paths; other jobs copy on the default engine,
+    /// resumably or unbuffered by file size. Jobs on a registered backend
+    /// follow links, and skip verification and the free space check.
+    pub backends: BackendRegistry,
+    /// Files at least this large are copied without the system file cache
+    /// (None to always copy through it). Takes precedence over
+    /// `resumable_min_size`; unbuffered copies aren't resumable.
+    pub unbuffered_min_size: Option<u64>,
+    /// Whether symlinks and junctions are recreated or followed.
+    pub link_handling: LinkHandling,
+    /// Whether to fail before copying anything when the destination volume
+    /// has less free space than the transfer needs.
+    pub check_free_space: bool,
+    /// Directory for the journals that let a transfer cut short by a crash
+    /// be resumed (None to keep no journal).
+    pub journal_dir: Option<PathBuf>,
+    /// When not empty, only files matching one of these wildcard patterns
+    /// are transferred.
+    pub include: Vec<String>,
+    /// Files and folders matching any of these wildcard patterns are left
+    /// out, like `node_modules` or `*.tmp`.
+    pub exclude: Vec<String>,
 }
 
 impl Default for FolderTransferConfig {
     fn default() -> Self {
         Self {
             concurrency: 2,
+            exclusive_min_size: 64 * 1024 * 1024, // 64MB
             continue_on_error: true,
             delete_source_on_move: true,
             progress_interval_bytes: 1024 * 1024, // 1MB
+            retry: RetryPolicy::default(),
+            speed_limit: None,
+            verify: None,
+            resumable_min_size: None,
+            backends: BackendRegistry::default(),
+            unbuffered_min_size: None,
+            link_handling: LinkHandling::default(),
+            check_free_space: true,
+            journal_dir: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
         }
     }
 }
@@ -156,7 +292,6 @@ impl Default for FolderTransferConfig {
 pub struct FolderTransferExecutor {
     config: FolderTransferConfig,
     event_tx: broadcast::Sender<FolderTransferEvent>,
-    #[allow(dead_code)] // Reserved for Ask mode protocol
     conflict_tx: mpsc::Sender<ConflictQuery>,
     conflict_rx: Option<mpsc::Receiver<ConflictQuery>>,
 }
@@ -187,6 +322,8 @@ impl FolderTransferExecutor {
     /// Take the conflict receiver for handling conflicts.
     ///
     /// This should be called once by the UI layer to receive conflict queries.
+    /// Until it is taken, conflicts the resolver leaves to the user are
+    /// skipped instead of asked about.
     pub fn take_conflict_receiver(&mut self) -> Option<mpsc::Receiver<ConflictQuery>> {
         self.conflict_rx.take()
     }
@@ -200,8 +337,17 @@ impl FolderTransferExecutor {
         resolver: Arc<std::sync::Mutex<ConflictResolver>>,
         cancel_token: CancellationToken,
     ) -> ZResult<TransferReport> {
-        self.execute_transfer(job_id, sources, destination, false, resolver, cancel_token)
-            .await
+        self.execute_transfer(
+            job_id,
+            sources,
+            destination,
+            false,
+            resolver,
+            cancel_token,
+            PauseToken::new(),
+            speed_limit(None, self.config.speed_limit),
+        )
+        .await
     }
 
     /// Execute a folder move operation.
@@ -213,10 +359,58 @@ impl FolderTransferExecutor {
         resolver: Arc<std::sync::Mutex<ConflictResolver>>,
         cancel_token: CancellationToken,
     ) -> ZResult<TransferReport> {
-        self.execute_transfer(job_id, sources, destination, true, resolver, cancel_token)
-            .await
+        self.execute_transfer(
+            job_id,
+            sources,
+            destination,
+            true,
+            resolver,
+            cancel_token,
+            PauseToken::new(),
+            speed_limit(None, self.config.speed_limit),
+        )
+        .await
+    }
+
+    /// Execute a copy or move job from the job system.
+    ///
+    /// The job's cancellation and pause tokens and its speed limit are
+    /// honored between items and between chunks of each file.
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        resolver: Arc<std::sync::Mutex<ConflictResolver>>,
+    ) -> ZResult<TransferReport> {
+        let (sources, destination, is_move) = match &job.kind {
+            JobKind::Copy {
+                sources,
+                destination,
+            } => (sources.clone(), destination.clone(), false),
+            JobKind::Move {
+                sources,
+                destination,
+            } => (sources.clone(), destination.clone(), true),
+            other => {
+                return Err(ZError::Internal {
+                    message: format!("Unsupported job kind for folder executor: {other:?}"),
+                });
+            }
+        };
+
+        self.execute_transfer(
+            job.id,
+            sources,
+            destination,
+            is_move,
+            resolver,
+            job.cancellation.clone(),
+            job.pause.clone(),
+            speed_limit(Some(job), self.config.speed_limit),
+        )
+        .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn execute_transfer(
         &self,
         job_id: JobId,
@@ -225,23 +419,58 @@ impl FolderTransferExecutor {
         is_move: bool,
         resolver: Arc<std::sync::Mutex<ConflictResolver>>,
         cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
     ) -> ZResult<TransferReport> {
         let start_time = Instant::now();
 
+        let job_backend = self.config.backends.select(&sources, &destination);
+        let backend = job_backend
+            .clone()
+            .unwrap_or_else(|| Arc::clone(self.config.backends.default_backend()));
+
         info!(
             job_id = %job_id,
             sources = sources.len(),
             destination = %destination.display(),
             is_move,
+            backend = backend.name(),
             "Starting folder transfer"
         );
 
-        // Build transfer plan
-        let mut builder = TransferPlanBuilder::new(&destination).is_move(is_move);
-        for source in &sources {
-            builder = builder.add_source(source);
-        }
-        let plan = builder.build()?;
+        // Pick up where an interrupted run of the same transfer stopped, or
+        // build a new transfer plan
+        let journal_dir = self.config.journal_dir.as_deref();
+        let mut journal =
+            journal_dir.and_then(|dir| TransferJournal::open(dir, &sources, &destination, is_move));
+        let resumed = journal.is_some();
+        let plan = match &journal {
+            Some(journal) => {
+                info!(
+                    job_id = %job_id,
+                    done = journal.completed_count(),
+                    "Resuming interrupted transfer"
+                );
+                journal.plan.clone()
+            }
+            None => {
+                let mut builder = TransferPlanBuilder::new(&destination)
+                    .is_move(is_move)
+                    .include(self.config.include.iter().cloned())
+                    .exclude(self.config.exclude.iter().cloned());
+                builder = match &job_backend {
+                    // Links can only be recreated on the local file system
+                    Some(backend) => builder
+                        .backend(Arc::clone(backend))
+                        .link_handling(LinkHandling::Follow),
+                    None => builder.link_handling(self.config.link_handling),
+                };
+                for source in &sources {
+                    builder = builder.add_source(source);
+                }
+                builder.build()?
+            }
+        };
 
         // Emit started event
         let _ = self.event_tx.send(FolderTransferEvent::Started {
@@ -250,33 +479,73 @@ impl FolderTransferExecutor {
         });
 
         // Check for same-volume move optimization
+        // Renaming the folder would take the excluded items along
         if is_move
+            && plan.stats.excluded == 0
             && sources.len() == 1
             && sources[0].is_dir()
             && same_volume(&sources[0], &destination)
         {
             debug!("Attempting same-volume atomic move");
             if let Ok(report) = self
-                .try_atomic_move(job_id, &sources[0], &destination, &cancel_token)
+                .try_atomic_move(backend.as_ref(), &sources[0], &destination, &cancel_token)
                 .await
             {
+                if let Some(journal) = journal {
+                    let _ = journal.discard();
+                }
                 return Ok(report);
             }
             debug!("Atomic move failed, falling back to copy+delete");
         }
 
+        // Fail early rather than part way through a transfer that can't fit
+        if self.config.check_free_space && job_backend.is_none() {
+            plan.check_free_space()?;
+        }
+
+        if journal.is_none() {
+            if let Some(dir) = journal_dir {
+                match TransferJournal::create(dir, &plan) {
+                    Ok(created) => journal = Some(created),
+                    Err(e) => {
+                        warn!(error = %e, "Failed to create transfer journal, continuing without")
+                    }
+                }
+            }
+        }
+
         // Execute the transfer
-        let report = self
-            .execute_plan(job_id, &plan, resolver, cancel_token.clone())
-            .await?;
+        let result = self
+            .execute_plan(
+                job_id,
+                &plan,
+                resolver,
+                cancel_token.clone(),
+                pause_token,
+                speed_limit,
+                job_backend.as_ref(),
+                &mut journal,
+                resumed,
+            )
+            .await;
+
+        // However the transfer ended, there is nothing left to recover
+        if let Some(journal) = journal {
+            if let Err(e) = journal.discard() {
+                warn!(error = %e, "Failed to remove transfer journal");
+            }
+        }
+        let report = result?;
 
         // For move operations, delete sources after successful copy
         if is_move && self.config.delete_source_on_move && report.is_complete_success() {
-            self.delete_sources(&plan).await;
+            self.delete_sources(&plan, backend.as_ref()).await;
         }
 
         let duration = start_time.elapsed();
         let final_report = TransferReport {
+            excluded: plan.stats.excluded,
             duration,
             ..report
         };
@@ -301,7 +570,7 @@ impl FolderTransferExecutor {
 
     async fn try_atomic_move(
         &self,
-        _job_id: JobId, // Reserved for event emission
+        backend: &dyn TransferBackend,
         source: &Path,
         destination: &Path,
         cancel_token: &CancellationToken,
@@ -310,22 +579,18 @@ impl FolderTransferExecutor {
             return Err(ZError::Cancelled);
         }
 
-        let dest_path = destination.join(
-            source
-                .file_name()
-                .ok_or_else(|| ZError::InvalidPath {
-                    path: source.to_path_buf(),
-                    reason: "No directory name".to_string(),
-                })?,
-        );
-
-        // Check for conflicts
-        if dest_path.exists() {
-            return Err(ZError::AlreadyExists { path: dest_path });
-        }
+        // A folder moved to a path that isn't a folder takes that name
+        let dest_path = if destination.is_dir() {
+            destination.join(source.file_name().ok_or_else(|| ZError::InvalidPath {
+                path: source.to_path_buf(),
+                reason: "No directory name".to_string(),
+            })?)
+        } else {
+            destination.to_path_buf()
+        };
 
-        // Attempt atomic rename
-        std::fs::rename(source, &dest_path).map_err(|e| ZError::io(source, e))?;
+        // Attempt atomic rename; fails on conflicts
+        backend.move_file(source, &dest_path)?;
 
         let bytes = calculate_dir_size(&dest_path);
 
@@ -334,44 +599,58 @@ impl FolderTransferExecutor {
                 source: source.to_path_buf(),
                 destination: dest_path,
                 bytes,
+                retries: 0,
             }],
             bytes_transferred: bytes,
             succeeded: 1,
             skipped: 0,
             failed: 0,
+            excluded: 0,
             duration: std::time::Duration::ZERO,
         };
 
         Ok(report)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn execute_plan(
         &self,
         job_id: JobId,
         plan: &TransferPlan,
         resolver: Arc<std::sync::Mutex<ConflictResolver>>,
         cancel_token: CancellationToken,
+        pause_token: PauseToken,
+        speed_limit: SpeedLimit,
+        backend: Option<&Arc<dyn TransferBackend>>,
+        journal: &mut Option<TransferJournal>,
+        resumed: bool,
     ) -> ZResult<TransferReport> {
         let mut report = TransferReport::default();
 
         // Progress tracking
-        let bytes_done = Arc::new(AtomicU64::new(0));
-        let items_done = Arc::new(AtomicUsize::new(0));
-        let total_bytes = plan.stats.total_bytes;
-        let total_items = plan.stats.total_items();
+        let tracker = ProgressTracker {
+            job_id,
+            event_tx: self.event_tx.clone(),
+            bytes_done: Arc::new(AtomicU64::new(0)),
+            items_done: Arc::new(AtomicUsize::new(0)),
+            total_bytes: plan.stats.total_bytes,
+            total_items: plan.stats.total_items(),
+            speed: Arc::new(Mutex::new(SpeedEstimator::new())),
+        };
 
         // Phase 1: Create directories
         debug!("Creating {} directories", plan.stats.total_dirs);
         for item in plan.directories() {
-            if cancel_token.is_cancelled() {
+            // Park while paused; waiting fails once the job is cancelled
+            if pause_token.wait(&cancel_token).await.is_err() {
                 let _ = self.event_tx.send(FolderTransferEvent::Cancelled { job_id });
                 return Err(ZError::Cancelled);
             }
 
-            match self.create_directory(item, &resolver).await {
+            match self.create_directory(item, &resolver, backend).await {
                 Ok(result) => {
-                    items_done.fetch_add(1, Ordering::Relaxed);
-                    self.emit_progress(job_id, &items_done, &bytes_done, total_items, total_bytes);
+                    tracker.items_done.fetch_add(1, Ordering::Relaxed);
+                    tracker.emit(None);
 
                     if result.is_success() {
                         report.succeeded += 1;
@@ -392,6 +671,7 @@ impl FolderTransferExecutor {
                             source: item.source.clone(),
                             destination: item.destination.clone(),
                             error: e.to_string(),
+                            retries: 0,
                         });
                     } else {
                         return Err(e);
@@ -400,78 +680,148 @@ impl FolderTransferExecutor {
             }
         }
 
-        // Phase 2: Copy files
+        // Phase 2: Copy files. Small files are copied several at a time; a
+        // large file, or one that may have to wait on the user's answer to a
+        // conflict, is copied on its own.
         debug!("Copying {} files", plan.stats.total_files);
-        for item in plan.files() {
-            if cancel_token.is_cancelled() {
-                let _ = self.event_tx.send(FolderTransferEvent::Cancelled { job_id });
-                return Err(ZError::Cancelled);
+        let concurrency = self.config.concurrency.max(1);
+        let mut copying = FuturesUnordered::new();
+        let mut alone_copying = false;
+        let mut outcome = Ok(());
+        'files: for item in plan.files() {
+            if pause_token.wait(&cancel_token).await.is_err() {
+                outcome = Err(ZError::Cancelled);
+                break;
             }
 
-            let bytes_done_clone = bytes_done.clone();
-            let items_done_clone = items_done.clone();
-            let event_tx = self.event_tx.clone();
+            if journal.as_ref().is_some_and(|journal| journal.is_done(&item.source)) {
+                tracker.bytes_done.fetch_add(item.size, Ordering::Relaxed);
+                tracker.items_done.fetch_add(1, Ordering::Relaxed);
+                tracker.emit(None);
+                report.skipped += 1;
+                report.items.push(ItemResult::Skipped {
+                    source: item.source.clone(),
+                    destination: item.destination.clone(),
+                    reason: "Finished before the interruption".to_string(),
+                });
+                continue;
+            }
 
-            match self
-                .copy_file(job_id, item, &resolver, &cancel_token, bytes_done_clone)
-                .await
-            {
-                Ok(result) => {
-                    items_done_clone.fetch_add(1, Ordering::Relaxed);
-                    self.emit_progress(
-                        job_id,
-                        &items_done_clone,
-                        &bytes_done,
-                        total_items,
-                        total_bytes,
-                    );
-
-                    let _ = event_tx.send(FolderTransferEvent::ItemCompleted {
-                        job_id,
-                        result: result.clone(),
-                    });
+            // Wait for a free slot, or for every slot when either this file
+            // or the one copying must be alone
+            let alone = item.has_conflict || item.size >= self.config.exclusive_min_size;
+            let slots = if alone || alone_copying { 1 } else { concurrency };
+            while copying.len() >= slots {
+                let Some((item, result)) = copying.next().await else {
+                    break;
+                };
+                outcome = self.finish_file(job_id, item, result, &tracker, journal, &mut report);
+                if outcome.is_err() {
+                    break 'files;
+                }
+            }
 
-                    match &result {
-                        ItemResult::Success { bytes, .. } => {
-                            report.succeeded += 1;
-                            report.bytes_transferred += bytes;
-                        }
-                        ItemResult::Skipped { .. } => {
-                            report.skipped += 1;
-                        }
-                        ItemResult::Failed { .. } => {
-                            report.failed += 1;
-                        }
+            alone_copying = alone;
+            let copy = self.copy_file(
+                job_id,
+                item,
+                &resolver,
+                &cancel_token,
+                &pause_token,
+                &speed_limit,
+                backend,
+                resumed,
+                tracker.clone(),
+            );
+            copying.push(copy.map(move |result| (item, result)));
+        }
+
+        // Let the copies under way finish, so a cancelled one cleans up after
+        // itself and finished ones still reach the journal
+        while let Some((item, result)) = copying.next().await {
+            let finished = self.finish_file(job_id, item, result, &tracker, journal, &mut report);
+            if outcome.is_ok() {
+                outcome = finished;
+            }
+        }
+        if let Err(ZError::Cancelled) = outcome {
+            let _ = self.event_tx.send(FolderTransferEvent::Cancelled { job_id });
+        }
+        outcome?;
+
+        Ok(report)
+    }
+
+    /// Count a copied file into the report, the journal and the events.
+    /// Fails when the transfer has to stop: it was cancelled, or the file
+    /// failed and errors don't let it go on.
+    fn finish_file(
+        &self,
+        job_id: JobId,
+        item: &TransferItem,
+        result: ZResult<ItemResult>,
+        tracker: &ProgressTracker,
+        journal: &mut Option<TransferJournal>,
+        report: &mut TransferReport,
+    ) -> ZResult<()> {
+        match result {
+            Ok(result) => {
+                tracker.items_done.fetch_add(1, Ordering::Relaxed);
+                tracker.emit(None);
+
+                let finished =
+                    !matches!(result, ItemResult::Failed { .. } | ItemResult::VerifyFailed { .. });
+                if let Some(journal) = journal.as_mut().filter(|_| finished) {
+                    if let Err(e) = journal.record(&item.source) {
+                        warn!(error = %e, "Failed to record finished file in transfer journal");
                     }
-                    report.items.push(result);
                 }
-                Err(e) => {
-                    if self.config.continue_on_error {
-                        warn!(
-                            file = %item.source.display(),
-                            error = %e,
-                            "Failed to copy file, continuing"
-                        );
+
+                let _ = self.event_tx.send(FolderTransferEvent::ItemCompleted {
+                    job_id,
+                    result: result.clone(),
+                });
+
+                match &result {
+                    ItemResult::Success { bytes, .. } | ItemResult::Verified { bytes, .. } => {
+                        report.succeeded += 1;
+                        report.bytes_transferred += bytes;
+                    }
+                    ItemResult::Skipped { .. } => {
+                        report.skipped += 1;
+                    }
+                    ItemResult::Failed { .. } | ItemResult::VerifyFailed { .. } => {
                         report.failed += 1;
-                        report.items.push(ItemResult::Failed {
-                            source: item.source.clone(),
-                            destination: item.destination.clone(),
-                            error: e.to_string(),
-                        });
-                    } else {
-                        return Err(e);
                     }
                 }
+                report.items.push(result);
+                Ok(())
             }
+            Err(ZError::Cancelled) => Err(ZError::Cancelled),
+            Err(e) if self.config.continue_on_error => {
+                warn!(
+                    file = %item.source.display(),
+                    error = %e,
+                    "Failed to copy file, continuing"
+                );
+                report.failed += 1;
+                report.items.push(ItemResult::Failed {
+                    source: item.source.clone(),
+                    destination: item.destination.clone(),
+                    error: e.to_string(),
+                    retries: 0,
+                });
+                Ok(())
+            }
+            Err(e) => Err(e),
         }
-
-        Ok(report)
     }
 
     async fn create_directory(
         &self,
         item: &TransferItem,
         resolver: &Arc<std::sync::Mutex<ConflictResolver>>,
+        backend: Option<&Arc<dyn TransferBackend>>,
     ) -> ZResult<ItemResult> {
         trace!(
             source = %item.source.display(),
@@ -479,8 +829,17 @@ impl FolderTransferExecutor {
             "Creating directory"
         );
 
-        if item.destination.exists() {
-            if item.destination.is_dir() {
+        let existing = match backend {
+            Some(backend) => backend
+                .entry(&item.destination)
+                .map(|entry| entry.is_directory()),
+            None => item
+                .destination
+                .exists()
+                .then(|| item.destination.is_dir()),
+        };
+        if let Some(is_dir) = existing {
+            if is_dir {
                 // Directory already exists, that's fine for merging
                 return Ok(ItemResult::Skipped {
                     source: item.source.clone(),
@@ -490,7 +849,7 @@ impl FolderTransferExecutor {
             }
 
             // Conflict: file exists where we want a directory
-            let conflict = Conflict::new(&item.source, &item.destination);
+            let conflict = find_conflict(backend, &item.source, &item.destination);
             if let Some(conflict) = conflict {
                 let resolution = resolver
                     .lock()
@@ -509,8 +868,11 @@ impl FolderTransferExecutor {
                     }
                     Some(ConflictResolution::Overwrite) => {
                         // Remove the file and create directory
-                        std::fs::remove_file(&item.destination)
-                            .map_err(|e| ZError::io(&item.destination, e))?;
+                        match backend {
+                            Some(backend) => backend.delete(&item.destination)?,
+                            None => std::fs::remove_file(extended_path(&item.destination))
+                                .map_err(|e| ZError::io(&item.destination, e))?,
+                        }
                     }
                     _ => {
                         return Err(ZError::AlreadyExists {
@@ -521,23 +883,32 @@ impl FolderTransferExecutor {
             }
         }
 
-        std::fs::create_dir_all(&item.destination)
-            .map_err(|e| ZError::io(&item.destination, e))?;
+        match backend {
+            Some(backend) => backend.create_dir(&item.destination)?,
+            None => std::fs::create_dir_all(extended_path(&item.destination))
+                .map_err(|e| ZError::io(&item.destination, e))?,
+        }
 
         Ok(ItemResult::Success {
             source: item.source.clone(),
             destination: item.destination.clone(),
             bytes: 0,
+            retries: 0,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn copy_file(
         &self,
         job_id: JobId,
         item: &TransferItem,
         resolver: &Arc<std::sync::Mutex<ConflictResolver>>,
         cancel_token: &CancellationToken,
-        bytes_done: Arc<AtomicU64>,
+        pause_token: &PauseToken,
+        speed_limit: &SpeedLimit,
+        backend: Option<&Arc<dyn TransferBackend>>,
+        resumed: bool,
+        tracker: ProgressTracker,
     ) -> ZResult<ItemResult> {
         trace!(
             source = %item.source.display(),
@@ -546,11 +917,31 @@ impl FolderTransferExecutor {
         );
 
         let mut destination = item.destination.clone();
-        let mut overwrite = false;
+        // When resuming an interrupted run, a destination that wasn't there
+        // when the transfer was planned is what that run left part way
+        let mut overwrite = resumed && !item.has_conflict;
+
+        // Size picks the engine unless the job has a backend of its own
+        let unbuffered = backend.is_none()
+            && self
+                .config
+                .unbuffered_min_size
+                .is_some_and(|min| item.size >= min);
+
+        // A partial copy left by an earlier run is picked up, not a conflict
+        let resumable = backend.is_none()
+            && !unbuffered
+            && self
+                .config
+                .resumable_min_size
+                .is_some_and(|min| item.size >= min);
+        let resuming = resumable
+            && PartialCopy::load(&item.destination)
+                .is_some_and(|partial| partial.source == item.source);
 
         // Handle conflicts
-        if item.has_conflict {
-            let conflict = Conflict::new(&item.source, &item.destination);
+        if item.has_conflict && !resuming {
+            let conflict = find_conflict(backend, &item.source, &item.destination);
             if let Some(conflict) = conflict {
                 let resolution = resolver
                     .lock()
@@ -559,88 +950,253 @@ impl FolderTransferExecutor {
                     })?
                     .resolve(&conflict);
 
+                // Left to the user, the item waits here until they answer
+                let resolution = match resolution {
+                    Some(resolution) => resolution,
+                    None => {
+                        self.ask_user(job_id, &conflict, resolver, cancel_token)
+                            .await?
+                    }
+                };
+
                 match resolution {
-                    Some(ConflictResolution::Skip) => {
+                    ConflictResolution::Skip => {
                         return Ok(ItemResult::Skipped {
                             source: item.source.clone(),
                             destination: item.destination.clone(),
                             reason: "Skipped due to conflict".to_string(),
                         });
                     }
-                    Some(ConflictResolution::Overwrite) => {
+                    ConflictResolution::Overwrite => {
                         overwrite = true;
                     }
-                    Some(ConflictResolution::Rename) => {
+                    ConflictResolution::Rename => {
                         destination = ConflictResolver::generate_rename_path(&item.destination);
                     }
-                    Some(ConflictResolution::Cancel) => {
+                    ConflictResolution::Cancel => {
                         return Err(ZError::Cancelled);
                     }
-                    None => {
-                        // Need to ask user - emit conflict event
-                        let _ = self.event_tx.send(FolderTransferEvent::ConflictDetected {
-                            job_id,
-                            conflict: conflict.clone(),
-                        });
-
-                        // For now, default to skip when Ask policy is used
-                        // In a real implementation, we'd wait for user response
-                        return Ok(ItemResult::Skipped {
-                            source: item.source.clone(),
-                            destination: item.destination.clone(),
-                            reason: "Awaiting user resolution".to_string(),
-                        });
-                    }
                 }
             }
         }
 
+        if item.is_link() {
+            return Ok(match copy_link_item(&item.source, &destination, overwrite) {
+                Ok(()) => ItemResult::Success {
+                    source: item.source.clone(),
+                    destination,
+                    bytes: 0,
+                    retries: 0,
+                },
+                Err(e) => ItemResult::Failed {
+                    source: item.source.clone(),
+                    destination,
+                    error: e.to_string(),
+                    retries: 0,
+                },
+            });
+        }
+
         // Create progress callback
-        let _event_tx = self.event_tx.clone(); // Reserved for per-file progress events
-        let _source_clone = item.source.clone(); // Reserved for per-file progress events
         let config_interval = self.config.progress_interval_bytes;
         let last_reported = Arc::new(AtomicU64::new(0));
 
-        let callback: ProgressCallback = Box::new(move |p: CopyProgress| {
-            let last = last_reported.load(Ordering::Relaxed);
-            if p.bytes_copied - last >= config_interval {
-                last_reported.store(p.bytes_copied, Ordering::Relaxed);
-                bytes_done.fetch_add(p.bytes_copied - last, Ordering::Relaxed);
-            }
-        });
-
-        // Execute the copy
-        let result = tokio::task::spawn_blocking({
+        // Only bytes past the high-water mark count, so a retried attempt
+        // doesn't report the same data twice.
+        let on_progress = {
+            let tracker = tracker.clone();
+            let last_reported = last_reported.clone();
             let source = item.source.clone();
-            let destination = destination.clone();
-            let token = cancel_token.clone();
-            move || copy_file_with_progress(&source, &destination, overwrite, token, Some(callback))
-        })
-        .await
-        .map_err(|e| ZError::Internal {
-            message: format!("Task join error: {e}"),
-        })?;
+            Arc::new(move |p: CopyProgress| {
+                let last = last_reported.load(Ordering::Relaxed);
+                let delta = p.bytes_copied.saturating_sub(last);
+                if delta > 0 && delta >= config_interval {
+                    last_reported.store(p.bytes_copied, Ordering::Relaxed);
+                    tracker.bytes_done.fetch_add(delta, Ordering::Relaxed);
+                    tracker.emit(Some(&source));
+                }
+            })
+        };
+
+        // Hashing works on local files only
+        let verify = self.config.verify.filter(|_| backend.is_none());
+        let backend: Arc<dyn TransferBackend> = match backend {
+            Some(backend) => Arc::clone(backend),
+            None if unbuffered => Arc::new(UnbufferedBackend),
+            None if resumable => Arc::new(ResumableBackend),
+            None => Arc::clone(self.config.backends.default_backend()),
+        };
+
+        // Execute the copy, retrying transient failures
+        let result = self
+            .config
+            .retry
+            .run(cancel_token, || {
+                let backend = Arc::clone(&backend);
+                let source = item.source.clone();
+                let destination = destination.clone();
+                let token = cancel_token.clone();
+                let pause = pause_token.clone();
+                let limit = speed_limit.clone();
+                let on_progress = on_progress.clone();
+                let callback: ProgressCallback = Box::new(move |p| on_progress(p));
+
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        backend.copy_file(
+                            &source,
+                            &destination,
+                            overwrite,
+                            token,
+                            pause,
+                            limit,
+                            Some(callback),
+                        )
+                    })
+                    .await
+                    .map_err(|e| ZError::Internal {
+                        message: format!("Task join error: {e}"),
+                    })?
+                }
+            })
+            .await;
 
         match result {
-            Ok(bytes) => Ok(ItemResult::Success {
-                source: item.source.clone(),
-                destination,
-                bytes,
-            }),
-            Err(ZError::Cancelled) => {
-                // Clean up partial file
-                let _ = std::fs::remove_file(&destination);
+            Ok((bytes, retries)) => {
+                // The tail of the file was below the reporting interval
+                let reported = last_reported.load(Ordering::Relaxed);
+                tracker
+                    .bytes_done
+                    .fetch_add(bytes.saturating_sub(reported), Ordering::Relaxed);
+
+                let Some(algorithm) = verify else {
+                    return Ok(ItemResult::Success {
+                        source: item.source.clone(),
+                        destination,
+                        bytes,
+                        retries,
+                    });
+                };
+                self.verify_file(item, destination, bytes, retries, algorithm, cancel_token)
+                    .await
+            }
+            Err((ZError::Cancelled, _)) => {
+                // Clean up partial file, unless it can be resumed
+                if !resumable {
+                    let _ = std::fs::remove_file(extended_path(&destination));
+                }
                 Err(ZError::Cancelled)
             }
-            Err(e) => Ok(ItemResult::Failed {
+            Err((e, retries)) => Ok(ItemResult::Failed {
                 source: item.source.clone(),
                 destination,
                 error: e.to_string(),
+                retries,
             }),
         }
     }
 
-    async fn delete_sources(&self, plan: &TransferPlan) {
+    /// Hash a copied file and its source and report whether they match.
+    async fn verify_file(
+        &self,
+        item: &TransferItem,
+        destination: PathBuf,
+        bytes: u64,
+        retries: u32,
+        algorithm: ChecksumAlgorithm,
+        cancel_token: &CancellationToken,
+    ) -> ZResult<ItemResult> {
+        let source = item.source.clone();
+        let copy = destination.clone();
+        let token = cancel_token.clone();
+        let result =
+            tokio::task::spawn_blocking(move || verify_copy(&source, &copy, algorithm, &token))
+                .await
+                .map_err(|e| ZError::Internal {
+                    message: format!("Task join error: {e}"),
+                })?;
+
+        let error = match result {
+            Ok(checksums) if checksums.matches() => {
+                return Ok(ItemResult::Verified {
+                    source: item.source.clone(),
+                    destination,
+                    bytes,
+                    retries,
+                });
+            }
+            Ok(checksums) => format!(
+                "Checksum mismatch: source {}, destination {}",
+                checksums.source_checksum, checksums.destination_checksum
+            ),
+            Err(ZError::Cancelled) => return Err(ZError::Cancelled),
+            Err(e) => format!("Verification failed: {e}"),
+        };
+
+        warn!(
+            source = %item.source.display(),
+            dest = %destination.display(),
+            error = %error,
+            "Copy verification failed"
+        );
+        Ok(ItemResult::VerifyFailed {
+            source: item.source.clone(),
+            destination,
+            bytes,
+            error,
+            retries,
+        })
+    }
+
+    /// Ask the UI to settle a conflict and wait for the answer.
+    ///
+    /// Skips the item when no UI is listening or the query is dropped
+    /// unanswered.
+    async fn ask_user(
+        &self,
+        job_id: JobId,
+        conflict: &Conflict,
+        resolver: &Arc<std::sync::Mutex<ConflictResolver>>,
+        cancel_token: &CancellationToken,
+    ) -> ZResult<ConflictResolution> {
+        let _ = self.event_tx.send(FolderTransferEvent::ConflictDetected {
+            job_id,
+            conflict: conflict.clone(),
+        });
+
+        if self.conflict_rx.is_some() {
+            debug!("No conflict handler attached, skipping");
+            return Ok(ConflictResolution::Skip);
+        }
+
+        let (response, answer) = oneshot::channel();
+        let query = ConflictQuery {
+            job_id,
+            conflict: conflict.clone(),
+            response,
+        };
+        if self.conflict_tx.send(query).await.is_err() {
+            return Ok(ConflictResolution::Skip);
+        }
+
+        let answer = tokio::select! {
+            answer = answer => answer,
+            _ = wait_for_cancel(cancel_token) => return Err(ZError::Cancelled),
+        };
+        let Ok(answer) = answer else {
+            return Ok(ConflictResolution::Skip);
+        };
+
+        let resolution = resolver
+            .lock()
+            .map_err(|_| ZError::Internal {
+                message: "Resolver lock poisoned".to_string(),
+            })?
+            .apply_answer(conflict, answer);
+        Ok(resolution)
+    }
+
+    async fn delete_sources(&self, plan: &TransferPlan, backend: &dyn TransferBackend) {
         // Delete in reverse order (files first, then directories deepest first)
         let mut items: Vec<_> = plan.items.iter().collect();
         items.sort_by(|a, b| {
@@ -653,44 +1209,48 @@ impl FolderTransferExecutor {
         });
 
         for item in items {
-            if item.is_dir {
-                if let Err(e) = std::fs::remove_dir(&item.source) {
-                    warn!(
-                        path = %item.source.display(),
-                        error = %e,
-                        "Failed to delete source directory"
-                    );
-                }
-            } else if let Err(e) = std::fs::remove_file(&item.source) {
+            if let Err(e) = backend.delete(&item.source) {
                 warn!(
                     path = %item.source.display(),
                     error = %e,
-                    "Failed to delete source file"
+                    "Failed to delete source"
                 );
             }
         }
     }
+}
 
-    fn emit_progress(
-        &self,
-        job_id: JobId,
-        items_done: &AtomicUsize,
-        bytes_done: &AtomicU64,
-        total_items: usize,
-        total_bytes: u64,
-    ) {
-        let progress = Progress {
-            total_bytes: Some(total_bytes),
-            bytes_done: bytes_done.load(Ordering::Relaxed),
-            total_items,
-            items_done: items_done.load(Ordering::Relaxed),
-            current_item: None,
-            eta: None,
-            speed_bytes_per_sec: None,
-        };
+/// The conflict between `source` and what's at `destination`, looked up
+/// through the job's backend when it has one.
+fn find_conflict(
+    backend: Option<&Arc<dyn TransferBackend>>,
+    source: &Path,
+    destination: &Path,
+) -> Option<Conflict> {
+    match backend {
+        Some(backend) => Some(Conflict::from_entries(
+            &backend.entry(source)?,
+            &backend.entry(destination)?,
+        )),
+        None => Conflict::new(source, destination),
+    }
+}
 
-        let _ = self.event_tx.send(FolderTransferEvent::Progress { job_id, progress });
+/// Recreate a link at `destination`, replacing an existing file or link when
+/// `overwrite` is set. A real directory is never replaced by a link.
+fn copy_link_item(source: &Path, destination: &Path, overwrite: bool) -> ZResult<()> {
+    if overwrite {
+        match std::fs::symlink_metadata(extended_path(destination)) {
+            Ok(meta) if meta.is_dir() => {
+                return Err(ZError::AlreadyExists {
+                    path: destination.to_path_buf(),
+                });
+            }
+            Ok(_) => remove_link(destination)?,
+            Err(_) => {}
+        }
     }
+    copy_link(source, destination)
 }
 
 impl Default for FolderTransferExecutor {
@@ -699,6 +1259,58 @@ impl Default for FolderTransferExecutor {
     }
 }
 
+/// Running totals of a transfer, shared with the per-file copy callbacks so
+/// large files report progress while they are being copied.
+#[derive(Clone)]
+struct ProgressTracker {
+    job_id: JobId,
+    event_tx: broadcast::Sender<FolderTransferEvent>,
+    bytes_done: Arc<AtomicU64>,
+    items_done: Arc<AtomicUsize>,
+    total_bytes: u64,
+    total_items: usize,
+    /// Speed of the whole transfer over the last few seconds.
+    speed: Arc<Mutex<SpeedEstimator>>,
+}
+
+impl ProgressTracker {
+    fn emit(&self, current_item: Option<&Path>) {
+        // Read the bytes under the lock so samples never go backwards
+        let mut speed = self.speed.lock().ok();
+        let bytes_done = self.bytes_done.load(Ordering::Relaxed);
+        let bytes_per_sec = speed
+            .as_mut()
+            .and_then(|speed| speed.record(bytes_done, Instant::now()));
+        drop(speed);
+
+        let mut progress = Progress {
+            total_bytes: Some(self.total_bytes),
+            bytes_done,
+            total_items: self.total_items,
+            items_done: self.items_done.load(Ordering::Relaxed),
+            current_item: current_item.map(Path::to_path_buf),
+            eta: None,
+            speed_bytes_per_sec: None,
+        };
+        progress.set_speed(bytes_per_sec);
+
+        let _ = self.event_tx.send(FolderTransferEvent::Progress {
+            job_id: self.job_id,
+            progress,
+        });
+    }
+}
+
+/// How often a pending conflict query checks for cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Resolve once `cancel_token` is cancelled.
+async fn wait_for_cancel(cancel_token: &CancellationToken) {
+    while !cancel_token.is_cancelled() {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
+
 /// Calculate directory size (for atomic move reporting).
 fn calculate_dir_size(path: &Path) -> u64 {
     walkdir::WalkDir::new(path)
@@ -713,6 +1325,9 @@ fn calculate_dir_size(path: &Path) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::journal::find_unfinished_transfers;
+    use crate::backend::PortableBackend;
+    use crate::resume::copy_file_resume;
     use std::fs;
     use tempfile::TempDir;
 
@@ -737,6 +1352,7 @@ mod tests {
 
         let executor = FolderTransferExecutor::new();
         let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let mut events = executor.subscribe();
         let token = CancellationToken::new();
 
         let report = executor
@@ -746,10 +1362,63 @@ mod tests {
 
         assert!(report.is_complete_success());
         assert!(report.succeeded >= 2); // At least 2 files
+
+        // Small files are below the reporting interval but still counted
+        let mut last_progress = None;
+        while let Ok(event) = events.try_recv() {
+            if let FolderTransferEvent::Progress { progress, .. } = event {
+                last_progress = Some(progress);
+            }
+        }
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.bytes_done, 300);
+        assert_eq!(last_progress.items_done, last_progress.total_items);
         assert!(dest.join("source").join("file1.txt").exists());
         assert!(dest.join("source").join("subdir").join("file2.txt").exists());
     }
 
+    #[tokio::test]
+    async fn test_copy_folder_concurrently() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source");
+        let dest = temp.path().join("dest");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir(&dest).unwrap();
+        for i in 0..20 {
+            fs::write(source.join(format!("small{i}.txt")), vec![b'x'; 100]).unwrap();
+        }
+        fs::write(source.join("large.bin"), vec![b'y'; 1000]).unwrap();
+
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            concurrency: 4,
+            exclusive_min_size: 1000,
+            ..Default::default()
+        });
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let mut events = executor.subscribe();
+        let token = CancellationToken::new();
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, token)
+            .await
+            .unwrap();
+
+        assert!(report.is_complete_success());
+        assert_eq!(report.bytes_transferred, 3000);
+        assert_eq!(fs::read(dest.join("source").join("large.bin")).unwrap().len(), 1000);
+        assert_eq!(fs::read(dest.join("source").join("small19.txt")).unwrap().len(), 100);
+
+        // Copies finishing out of order still add up
+        let mut last_progress = None;
+        while let Ok(event) = events.try_recv() {
+            if let FolderTransferEvent::Progress { progress, .. } = event {
+                last_progress = Some(progress);
+            }
+        }
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.bytes_done, 3000);
+        assert_eq!(last_progress.items_done, last_progress.total_items);
+    }
+
     #[tokio::test]
     async fn test_copy_folder_with_conflicts() {
         let temp = TempDir::new().unwrap();
@@ -775,6 +1444,97 @@ mod tests {
         assert_eq!(fs::read_to_string(&existing).unwrap(), "old content");
     }
 
+    #[tokio::test]
+    async fn test_ask_waits_for_answer() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source");
+        let dest = temp.path().join("dest");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(dest.join("source")).unwrap();
+        for name in ["a.txt", "b.txt"] {
+            fs::write(source.join(name), "new content").unwrap();
+            fs::write(dest.join("source").join(name), "old").unwrap();
+        }
+
+        let mut executor = FolderTransferExecutor::new();
+        let mut queries = executor.take_conflict_receiver().unwrap();
+        let answered = tokio::spawn(async move {
+            // One answer covers both files
+            let query = queries.recv().await.unwrap();
+            let _ = query.response.send(ConflictAnswer::Resolve {
+                policy: crate::conflict::ConflictPolicy::Overwrite,
+                apply_to_all: true,
+            });
+            let mut more = 0;
+            while queries.recv().await.is_some() {
+                more += 1;
+            }
+            more
+        });
+
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let token = CancellationToken::new();
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, token)
+            .await
+            .unwrap();
+        drop(executor);
+
+        assert_eq!(answered.await.unwrap(), 0);
+        assert!(report.is_complete_success());
+        for name in ["a.txt", "b.txt"] {
+            let content = fs::read_to_string(dest.join("source").join(name)).unwrap();
+            assert_eq!(content, "new content");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ask_cancel_stops_transfer() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.txt");
+        let dest = temp.path().join("dest");
+        fs::write(&source, "new content").unwrap();
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("source.txt"), "old").unwrap();
+
+        let mut executor = FolderTransferExecutor::new();
+        let mut queries = executor.take_conflict_receiver().unwrap();
+        tokio::spawn(async move {
+            let query = queries.recv().await.unwrap();
+            let _ = query.response.send(ConflictAnswer::Cancel);
+        });
+
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let token = CancellationToken::new();
+        let result = executor
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, token)
+            .await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        assert_eq!(fs::read_to_string(dest.join("source.txt")).unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn test_ask_without_handler_skips() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.txt");
+        let dest = temp.path().join("dest");
+        fs::write(&source, "new content").unwrap();
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("source.txt"), "old").unwrap();
+
+        let executor = FolderTransferExecutor::new();
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let token = CancellationToken::new();
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, token)
+            .await
+            .unwrap();
+
+        assert_eq!(report.skipped, 1);
+        assert_eq!(fs::read_to_string(dest.join("source.txt")).unwrap(), "old");
+    }
+
     #[tokio::test]
     async fn test_copy_folder_overwrite() {
         let temp = TempDir::new().unwrap();
@@ -799,6 +1559,260 @@ mod tests {
         assert_eq!(fs::read_to_string(&existing).unwrap(), "new content");
     }
 
+    #[tokio::test]
+    async fn test_paused_job_resumes() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let mut job = Job::new(JobKind::Copy {
+            sources: vec![source],
+            destination: dest.clone(),
+        });
+        job.start();
+        job.pause();
+
+        let pause = job.pause.clone();
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let transfer = tokio::spawn(async move {
+            FolderTransferExecutor::new()
+                .execute_job(&job, resolver)
+                .await
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!transfer.is_finished());
+        assert!(!dest.join("source").join("file1.txt").exists());
+
+        pause.resume();
+        let report = transfer.await.unwrap().unwrap();
+        assert!(report.is_complete_success());
+        assert!(dest.join("source").join("subdir").join("file2.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_job_takes_configured_speed_limit() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let job = Job::new(JobKind::Copy {
+            sources: vec![source],
+            destination: dest.clone(),
+        });
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            speed_limit: Some(1000),
+            ..Default::default()
+        });
+
+        // 300 bytes at 1000 bytes/s
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let start = Instant::now();
+        let report = executor.execute_job(&job, resolver).await.unwrap();
+
+        assert!(report.is_complete_success());
+        assert!(start.elapsed() >= Duration::from_millis(250));
+        // The limit lands on the job, where the scheduler can change it
+        assert_eq!(job.speed_limit.get(), Some(1000));
+    }
+
+    #[tokio::test]
+    async fn test_copy_folder_resumes_partial_copy() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("large.bin");
+        let dest = temp.path().join("dest");
+        let content: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        fs::write(&source, &content).unwrap();
+        fs::create_dir(&dest).unwrap();
+
+        // Leave a partial copy behind, as an interrupted transfer would
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let callback: ProgressCallback = Box::new(move |_| cancel.cancel());
+        let partial = dest.join("large.bin");
+        let result = copy_file_resume(
+            &source,
+            &partial,
+            false,
+            token,
+            PauseToken::new(),
+            SpeedLimit::new(),
+            Some(callback),
+        );
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        assert!(PartialCopy::load(&partial).is_some());
+
+        // No conflict handler: a real conflict would be skipped
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            resumable_min_size: Some(0),
+            ..Default::default()
+        });
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest, resolver, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(fs::read(&partial).unwrap(), content);
+        assert!(PartialCopy::load(&partial).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_copy_folder_resumes_from_journal() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        let journals = temp.path().join("journals");
+        fs::create_dir(&dest).unwrap();
+
+        // A crash after the first file finished, with the second cut short
+        let plan = TransferPlanBuilder::new(&dest)
+            .add_source(&source)
+            .build()
+            .unwrap();
+        let mut journal = TransferJournal::create(&journals, &plan).unwrap();
+        journal.record(&source.join("file1.txt")).unwrap();
+        drop(journal);
+        fs::create_dir_all(dest.join("source").join("subdir")).unwrap();
+        fs::write(dest.join("source").join("subdir").join("file2.txt"), b"BB").unwrap();
+
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            journal_dir: Some(journals.clone()),
+            ..Default::default()
+        });
+        // The cut short file is not a conflict to skip
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::skip_all()));
+        let token = CancellationToken::new();
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, token)
+            .await
+            .unwrap();
+
+        // The finished file and the folders the first run created
+        assert_eq!(report.skipped, 3);
+        assert_eq!(report.failed, 0);
+        assert!(!dest.join("source").join("file1.txt").exists());
+        assert_eq!(
+            fs::read(dest.join("source").join("subdir").join("file2.txt")).unwrap(),
+            vec![b'B'; 200]
+        );
+        assert!(find_unfinished_transfers(&journals).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_copy_folder_verifies_files() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            verify: Some(ChecksumAlgorithm::Sha256),
+            ..Default::default()
+        });
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let report = executor
+            .copy_folder(JobId::new(), vec![source], dest, resolver, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(report.is_complete_success());
+        assert_eq!(report.bytes_transferred, 300);
+        let verified = report
+            .items
+            .iter()
+            .filter(|item| matches!(item, ItemResult::Verified { .. }))
+            .count();
+        assert_eq!(verified, 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_folder_recreates_links() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        std::os::unix::fs::symlink("subdir", source.join("link")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", source.join("dangling")).unwrap();
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::new()));
+        let cancel = CancellationToken::new();
+        let report = FolderTransferExecutor::new()
+            .copy_folder(JobId::new(), vec![source], dest.clone(), resolver, cancel)
+            .await
+            .unwrap();
+
+        assert!(report.is_complete_success());
+        assert_eq!(report.bytes_transferred, 300);
+        let copied = dest.join("source");
+        assert_eq!(fs::read_link(copied.join("link")).unwrap(), PathBuf::from("subdir"));
+        assert_eq!(
+            fs::read_link(copied.join("dangling")).unwrap(),
+            PathBuf::from("missing.txt")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_mismatch() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.txt");
+        let copy = temp.path().join("copy.txt");
+        fs::write(&source, "original").unwrap();
+        fs::write(&copy, "corrupted").unwrap();
+        let item = TransferItem {
+            source,
+            destination: copy.clone(),
+            is_dir: false,
+            size: 8,
+            depth: 0,
+            has_conflict: false,
+            link_target: None,
+        };
+
+        let result = FolderTransferExecutor::new()
+            .verify_file(&item, copy, 8, 0, ChecksumAlgorithm::XxHash, &CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(result.is_failed());
+        let ItemResult::VerifyFailed { error, .. } = result else {
+            panic!("expected a verification failure");
+        };
+        assert!(error.starts_with("Checksum mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_paused_job() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let mut job = Job::new(JobKind::Copy {
+            sources: vec![source],
+            destination: dest,
+        });
+        job.start();
+        job.pause();
+
+        let token = job.cancellation.clone();
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let transfer = tokio::spawn(async move {
+            FolderTransferExecutor::new()
+                .execute_job(&job, resolver)
+                .await
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        token.cancel();
+        let result = transfer.await.unwrap();
+        assert!(matches!(result, Err(ZError::Cancelled)));
+    }
+
     #[tokio::test]
     async fn test_move_folder() {
         let temp = TempDir::new().unwrap();
@@ -821,6 +1835,97 @@ mod tests {
         // Note: In the current implementation, source dirs may remain if not empty
     }
 
+    /// Counts what it's asked to do, and can't move in one step, like a
+    /// backend for remote storage.
+    #[derive(Debug, Default)]
+    struct CountingBackend {
+        copies: AtomicUsize,
+        deletes: AtomicUsize,
+    }
+
+    impl TransferBackend for CountingBackend {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn copy_file(
+            &self,
+            source: &Path,
+            destination: &Path,
+            overwrite: bool,
+            cancel_token: CancellationToken,
+            pause_token: PauseToken,
+            speed_limit: SpeedLimit,
+            progress_callback: Option<ProgressCallback>,
+        ) -> ZResult<u64> {
+            self.copies.fetch_add(1, Ordering::Relaxed);
+            PortableBackend.copy_file(
+                source,
+                destination,
+                overwrite,
+                cancel_token,
+                pause_token,
+                speed_limit,
+                progress_callback,
+            )
+        }
+
+        fn move_file(&self, source: &Path, _destination: &Path) -> ZResult<()> {
+            Err(ZError::InvalidOperation {
+                operation: format!("move {}", source.display()),
+                reason: "No renames here".to_string(),
+            })
+        }
+
+        fn delete(&self, path: &Path) -> ZResult<()> {
+            self.deletes.fetch_add(1, Ordering::Relaxed);
+            PortableBackend.delete(path)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_move_folder_on_registered_backend() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+
+        let backend = Arc::new(CountingBackend::default());
+        let executor = FolderTransferExecutor::with_config(FolderTransferConfig {
+            backends: BackendRegistry::default().register(backend.clone()),
+            // Would take both files off the job's backend if it applied
+            resumable_min_size: Some(0),
+            ..Default::default()
+        });
+        let resolver = Arc::new(std::sync::Mutex::new(ConflictResolver::overwrite_all()));
+        let token = CancellationToken::new();
+        let report = executor
+            .move_folder(JobId::new(), vec![source.clone()], dest.clone(), resolver, token)
+            .await
+            .unwrap();
+
+        // Copied and deleted item by item, since it couldn't rename
+        assert!(report.is_complete_success());
+        assert_eq!(backend.copies.load(Ordering::Relaxed), 2);
+        assert_eq!(backend.deletes.load(Ordering::Relaxed), 4);
+        assert!(dest.join("source").join("subdir").join("file2.txt").exists());
+        assert!(!source.exists());
+    }
+
+    #[test]
+    fn test_item_result_to_report_item() {
+        let item = TransferItemResult::from(&ItemResult::VerifyFailed {
+            source: PathBuf::from("a"),
+            destination: PathBuf::from("b"),
+            bytes: 10,
+            error: "Checksum mismatch".to_string(),
+            retries: 0,
+        });
+        assert_eq!(item.status, crate::report::TransferStatus::VerifyFailed);
+        assert_eq!(item.size_bytes, 10);
+        assert_eq!(item.reason.as_deref(), Some("Checksum mismatch"));
+    }
+
     #[tokio::test]
     async fn test_transfer_report() {
         let report = TransferReport {
@@ -829,6 +1934,7 @@ mod tests {
             succeeded: 5,
             skipped: 2,
             failed: 1,
+            excluded: 0,
             duration: std::time::Duration::from_secs(2),
         };
 
@@ -843,10 +1949,22 @@ mod tests {
             source: PathBuf::from("src"),
             destination: PathBuf::from("dst"),
             bytes: 100,
+            retries: 1,
         };
 
         assert!(success.is_success());
         assert!(!success.is_failed());
+
+        let verify_failed = ItemResult::VerifyFailed {
+            source: PathBuf::from("/src"),
+            destination: PathBuf::from("/dst"),
+            bytes: 100,
+            error: "Checksum mismatch".to_string(),
+            retries: 1,
+        };
+        assert!(verify_failed.is_failed());
+        assert_eq!(verify_failed.retries(), 1);
         assert_eq!(success.source(), Path::new("src"));
+        assert_eq!(success.retries(), 1);
     }
 }
@@ -0,0 +1,327 @@
+//! Shell icons for files and folders, as PNG images.
+//!
+//! Icons come from the shell (`IShellItemImageFactory`), so they are the
+//! ones Explorer shows, including per-file icons of programs and shortcuts.
+//! Most files share the icon of their type, so [`IconCache`] keeps one image
+//! per extension and only asks the shell again for files whose icon is their
+//! own.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::io::Write;
+use std::mem::size_of;
+use std::os::windows::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, SIZE};
+use windows::Win32::Graphics::Gdi::{
+    DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
+    BI_RGB, DIB_RGB_COLORS, HGDIOBJ,
+};
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_APARTMENTTHREADED};
+use windows::Win32::UI::Shell::{
+    IShellItemImageFactory, SHCreateItemFromParsingName, SIIGBF, SIIGBF_ICONONLY,
+};
+use zmanager_core::{extended_path, ZError, ZResult};
+
+/// Smallest icon size handed out, in pixels.
+pub const MIN_ICON_SIZE: u32 = 16;
+
+/// Largest icon size handed out, in pixels; the shell's jumbo icons.
+pub const MAX_ICON_SIZE: u32 = 256;
+
+/// Icons kept before the cache starts over.
+const MAX_CACHED_ICONS: usize = 2048;
+
+/// Extensions whose files carry their own icon rather than their type's.
+const PER_FILE_EXTENSIONS: &[&str] = &["exe", "ico", "lnk", "url", "cur", "ani", "scr", "msc"];
+
+/// What decides the icon the shell shows for a path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum IconKey {
+    /// Files sharing their type's icon, by lowercase extension.
+    Extension(String),
+    /// Folders and files with an icon of their own.
+    Path(PathBuf),
+}
+
+impl IconKey {
+    fn for_path(path: &Path, is_dir: bool) -> Self {
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if is_dir || PER_FILE_EXTENSIONS.contains(&ext.as_str()) {
+            Self::Path(path.to_path_buf())
+        } else {
+            Self::Extension(ext)
+        }
+    }
+}
+
+/// Cached PNG images by icon and size.
+type IconMap = HashMap<(IconKey, u32), Arc<[u8]>>;
+
+/// Shell icons already fetched, as PNG images.
+///
+/// Safe to share between threads; the Tauri backend keeps one for the
+/// lifetime of the app.
+#[derive(Debug, Default)]
+pub struct IconCache {
+    icons: Mutex<IconMap>,
+}
+
+impl IconCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shell icon of `path` as a PNG, `size` pixels square.
+    ///
+    /// `size` is clamped to [`MIN_ICON_SIZE`]..=[`MAX_ICON_SIZE`].
+    ///
+    /// # Errors
+    /// * `ZError::NotFound` - The shell cannot find the path
+    /// * `ZError::Internal` - The shell failed to draw the icon
+    pub fn get(&self, path: &Path, size: u32) -> ZResult<Arc<[u8]>> {
+        let size = size.clamp(MIN_ICON_SIZE, MAX_ICON_SIZE);
+        let key = (IconKey::for_path(path, extended_path(path).is_dir()), size);
+        if let Some(png) = self.lock().get(&key) {
+            return Ok(Arc::clone(png));
+        }
+
+        let png: Arc<[u8]> = shell_icon_png(path, size)?.into();
+        let mut icons = self.lock();
+        if icons.len() >= MAX_CACHED_ICONS {
+            icons.clear();
+        }
+        icons.insert(key, Arc::clone(&png));
+        Ok(png)
+    }
+
+    /// Number of icons cached.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no icons are cached.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Forget every cached icon, e.g. after file associations changed.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, IconMap> {
+        // A panic while holding the lock can't leave a half-written entry
+        self.icons.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Draw the shell icon of `path`, `size` pixels square, as a PNG.
+///
+/// Uncached; use [`IconCache::get`] when listing folders.
+///
+/// # Errors
+/// * `ZError::NotFound` - The shell cannot find the path
+/// * `ZError::Internal` - The shell failed to draw the icon
+pub fn shell_icon_png(path: &Path, size: u32) -> ZResult<Vec<u8>> {
+    shell_image_png(path, size, SIIGBF_ICONONLY).map_err(|e| match e {
+        ZError::Internal { message } => ZError::Internal {
+            message: format!("Failed to draw icon for {}: {message}", path.display()),
+        },
+        e => e,
+    })
+}
+
+/// Have the shell draw an image of `path` fitting in `size` pixels square,
+/// as a PNG; `flags` pick an icon, a thumbnail or either.
+///
+/// # Errors
+/// * `ZError::NotFound` - The shell cannot find the path
+/// * `ZError::Internal` - The shell has no such image or failed to draw it
+pub(crate) fn shell_image_png(path: &Path, size: u32, flags: SIIGBF) -> ZResult<Vec<u8>> {
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    let bitmap = unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let factory: IShellItemImageFactory =
+            SHCreateItemFromParsingName(PCWSTR(wide.as_ptr()), None)
+                .map_err(|_| ZError::NotFound { path: path.to_path_buf() })?;
+        let size = SIZE {
+            cx: size as i32,
+            cy: size as i32,
+        };
+        factory
+            .GetImage(size, flags)
+            .map_err(|e| ZError::Internal { message: e.to_string() })?
+    };
+    let object = HGDIOBJ(bitmap.0);
+
+    let pixels = unsafe {
+        let mut info = BITMAP::default();
+        let read = GetObjectW(
+            object,
+            size_of::<BITMAP>() as i32,
+            Some(&mut info as *mut BITMAP as *mut c_void),
+        );
+        let (width, height) = (info.bmWidth, info.bmHeight.abs());
+        let mut header = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative for rows top to bottom, as PNG stores them
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width.max(0) as usize * height as usize * 4];
+        let dc = GetDC(HWND::default());
+        let copied = if read == 0 || pixels.is_empty() {
+            0
+        } else {
+            GetDIBits(
+                dc,
+                bitmap,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr() as *mut c_void),
+                &mut header,
+                DIB_RGB_COLORS,
+            )
+        };
+        ReleaseDC(HWND::default(), dc);
+        let _ = DeleteObject(object);
+        if copied == 0 {
+            return Err(ZError::Internal {
+                message: "Failed to read the bitmap".to_string(),
+            });
+        }
+        (width as u32, height as u32, pixels)
+    };
+
+    let (width, height, mut pixels) = pixels;
+    bgra_to_rgba(&mut pixels);
+    Ok(encode_png(width, height, &pixels))
+}
+
+/// Turn the shell's premultiplied BGRA pixels into straight RGBA in place.
+///
+/// Icons without an alpha channel come back fully transparent, so they are
+/// made opaque instead.
+fn bgra_to_rgba(pixels: &mut [u8]) {
+    let has_alpha = pixels.chunks_exact(4).any(|px| px[3] != 0);
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        if !has_alpha {
+            px[3] = 255;
+        } else if px[3] != 0 && px[3] != 255 {
+            let alpha = u32::from(px[3]);
+            for channel in &mut px[..3] {
+                *channel = (u32::from(*channel) * 255 / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+/// Encode 8-bit RGBA pixels, rows top to bottom, as a PNG.
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let row_len = width as usize * 4;
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in rgba.chunks_exact(row_len.max(1)).take(height as usize) {
+        // Filter type 0: the row as is
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec can't fail
+    let _ = encoder.write_all(&raw);
+    let data = encoder.finish().unwrap_or_default();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, default compression, filtering and no interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &data);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Append a PNG chunk: length, type, data and the CRC of type and data.
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_icon_key_shares_type_icons() {
+        let doc = IconKey::for_path(Path::new(r"C:\a\Report.DOCX"), false);
+        assert_eq!(doc, IconKey::for_path(Path::new(r"C:\b\other.docx"), false));
+        assert_eq!(doc, IconKey::Extension("docx".to_string()));
+
+        let app = Path::new(r"C:\tools\app.exe");
+        assert_eq!(IconKey::for_path(app, false), IconKey::Path(app.to_path_buf()));
+        let folder = Path::new(r"C:\photos.2024");
+        assert_eq!(IconKey::for_path(folder, true), IconKey::Path(folder.to_path_buf()));
+    }
+
+    #[test]
+    fn test_bgra_to_rgba() {
+        // Half transparent blue, premultiplied
+        let mut pixels = vec![64, 0, 0, 128, 0, 0, 0, 0];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![0, 0, 127, 128, 0, 0, 0, 0]);
+
+        // No alpha channel at all
+        let mut pixels = vec![1, 2, 3, 0];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![3, 2, 1, 255]);
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let rgba: Vec<u8> = (0..2 * 3 * 4).map(|i| i as u8).collect();
+        let png = encode_png(2, 3, &rgba);
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 3]);
+        assert_eq!(&png[png.len() - 12..png.len() - 4], b"\0\0\0\0IEND");
+
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut raw = Vec::new();
+        ZlibDecoder::new(&png[41..41 + idat_len])
+            .read_to_end(&mut raw)
+            .unwrap();
+        assert_eq!(raw.len(), 3 * (1 + 2 * 4));
+        assert_eq!(raw[0], 0);
+        assert_eq!(&raw[1..9], &rgba[..8]);
+    }
+}
@@ -0,0 +1,510 @@
+//! Journals of transfers in progress, for recovery after a crash.
+//!
+//! While a copy or move runs, a `.zmjournal` file records its plan and then
+//! each file as it finishes, one JSON line at a time. A transfer that ends in
+//! any way, even failed or cancelled, removes its journal, so one found on
+//! the next launch belongs to a transfer that was cut short.
+//! [`find_unfinished_transfers`] lists them, and running the same transfer
+//! again picks up the journal and skips the files it already finished.
+//!
+//! Finished files are flushed to disk in batches rather than one by one, as
+//! syncing after every small file would cost more than copying it. A crash
+//! loses at most the last batch, and those files are simply copied again.
+//!
+//! Each run gets a journal of its own, so two identical transfers running at
+//! once don't overwrite each other's. A journal is in use while its
+//! [`TransferJournal`] is alive, and a rerun only picks up one that isn't.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{debug, warn};
+use xxhash_rust::xxh3::xxh3_64;
+use zmanager_core::{JobKind, ZError, ZResult};
+
+use crate::plan::TransferPlan;
+use crate::resume::PartialCopy;
+
+/// Extension of transfer journal files.
+pub const JOURNAL_EXTENSION: &str = "zmjournal";
+
+/// Finished files recorded between syncs of the journal to disk, at most.
+const SYNC_EVERY_RECORDS: usize = 256;
+
+/// Time between syncs of the journal to disk, at most, while files finish.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Journals in use by transfers running in this process.
+static IN_USE: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// The plan of a transfer and the files it has finished so far.
+#[derive(Debug)]
+pub struct TransferJournal {
+    /// Journal file.
+    path: PathBuf,
+    /// The transfer being recorded.
+    pub plan: TransferPlan,
+    /// Sources of the files already finished.
+    completed: HashSet<PathBuf>,
+    /// Journal file opened for appending, once something is recorded.
+    file: Option<File>,
+    /// Files recorded since the journal was last synced to disk.
+    unsynced: usize,
+    /// When the journal was last synced to disk.
+    last_sync: Instant,
+    /// Whether a running transfer records into this journal.
+    in_use: bool,
+}
+
+impl TransferJournal {
+    /// Get the default journal directory.
+    pub fn default_dir() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("ZManager")
+            .join("journals")
+    }
+
+    /// Start a journal in `dir` for a run of `plan`.
+    pub fn create(dir: &Path, plan: &TransferPlan) -> ZResult<Self> {
+        std::fs::create_dir_all(dir).map_err(|e| ZError::io(dir, e))?;
+        let key = transfer_key(&plan.source_roots, &plan.destination_root, plan.is_move);
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let name = format!("{key}-{}-{stamp}.{JOURNAL_EXTENSION}", std::process::id());
+        let path = dir.join(name);
+
+        let mut header = serde_json::to_vec(plan).map_err(|e| ZError::Internal {
+            message: format!("Failed to serialize transfer plan: {e}"),
+        })?;
+        header.push(b'\n');
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| ZError::io(&path, e))?;
+        file.write_all(&header)
+            .and_then(|()| file.sync_data())
+            .map_err(|e| ZError::io(&path, e))?;
+        claim(&path);
+
+        debug!(path = %path.display(), "Transfer journal created");
+        Ok(Self {
+            path,
+            plan: plan.clone(),
+            completed: HashSet::new(),
+            file: Some(file),
+            unsynced: 0,
+            last_sync: Instant::now(),
+            in_use: true,
+        })
+    }
+
+    /// Load a journal left in `dir` by an interrupted run of the same
+    /// transfer, if there is one that no running transfer is using.
+    pub fn open(
+        dir: &Path,
+        sources: &[PathBuf],
+        destination: &Path,
+        is_move: bool,
+    ) -> Option<Self> {
+        let key = transfer_key(sources, destination, is_move);
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == JOURNAL_EXTENSION))
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.starts_with(&key)
+            })
+            .collect();
+        paths.sort();
+
+        paths.into_iter().filter(|path| claim(path)).find_map(|path| match Self::load(&path) {
+            Ok(mut journal) => {
+                journal.in_use = true;
+                Some(journal)
+            }
+            Err(e) => {
+                release(&path);
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable transfer journal");
+                None
+            }
+        })
+    }
+
+    /// Load a journal file.
+    ///
+    /// A last line cut short by a crash is ignored; that file is copied again.
+    pub fn load(path: &Path) -> ZResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| ZError::io(path, e))?;
+        let mut lines = content.lines();
+        let plan = lines
+            .next()
+            .and_then(|header| serde_json::from_str(header).ok())
+            .ok_or_else(|| ZError::Internal {
+                message: format!("Transfer journal '{}' has no plan", path.display()),
+            })?;
+        let completed = lines
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            plan,
+            completed,
+            file: None,
+            unsynced: 0,
+            last_sync: Instant::now(),
+            in_use: false,
+        })
+    }
+
+    /// The journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What running this transfer again submits, to resume it.
+    pub fn job_kind(&self) -> JobKind {
+        let sources = self.plan.source_roots.clone();
+        let destination = self.plan.destination_root.clone();
+        if self.plan.is_move {
+            JobKind::Move {
+                sources,
+                destination,
+            }
+        } else {
+            JobKind::Copy {
+                sources,
+                destination,
+            }
+        }
+    }
+
+    /// Check if the file copied from `source` already finished.
+    pub fn is_done(&self, source: &Path) -> bool {
+        self.completed.contains(source)
+    }
+
+    /// Number of files already finished.
+    pub fn completed_count(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Files of the transfer left partly copied, by destination, with the
+    /// state saved for each. Running the transfer again resumes them.
+    pub fn partial_copies(&self) -> Vec<(PathBuf, PartialCopy)> {
+        self.plan
+            .files()
+            .filter(|item| !self.is_done(&item.source))
+            .filter_map(|item| {
+                let partial = PartialCopy::load(&item.destination)?;
+                (partial.source == item.source).then(|| (item.destination.clone(), partial))
+            })
+            .collect()
+    }
+
+    /// Destinations the transfer may have left part written: those of files
+    /// it didn't finish that weren't there when it was planned but are now,
+    /// and its partial copies.
+    pub fn unfinished_destinations(&self) -> Vec<PathBuf> {
+        self.plan
+            .files()
+            .filter(|item| !self.is_done(&item.source))
+            .filter(|item| {
+                let written = !item.has_conflict && item.destination.symlink_metadata().is_ok();
+                written
+                    || PartialCopy::load(&item.destination)
+                        .is_some_and(|partial| partial.source == item.source)
+            })
+            .map(|item| item.destination.clone())
+            .collect()
+    }
+
+    /// Record that the file copied from `source` finished.
+    ///
+    /// The journal is synced to disk every [`SYNC_EVERY_RECORDS`] files or
+    /// [`SYNC_INTERVAL`], whichever comes first, and when it is dropped.
+    pub fn record(&mut self, source: &Path) -> ZResult<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new()
+                    .append(true)
+                    .open(&self.path)
+                    .map_err(|e| ZError::io(&self.path, e))?;
+                self.file.insert(file)
+            }
+        };
+
+        let mut line = serde_json::to_vec(source).map_err(|e| ZError::Internal {
+            message: format!("Failed to serialize journal entry: {e}"),
+        })?;
+        line.push(b'\n');
+        file.write_all(&line).map_err(|e| ZError::io(&self.path, e))?;
+        self.completed.insert(source.to_path_buf());
+
+        self.unsynced += 1;
+        if self.unsynced >= SYNC_EVERY_RECORDS || self.last_sync.elapsed() >= SYNC_INTERVAL {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Flush the files recorded so far to disk.
+    pub fn sync(&mut self) -> ZResult<()> {
+        if let Some(file) = &self.file {
+            if self.unsynced > 0 {
+                file.sync_data().map_err(|e| ZError::io(&self.path, e))?;
+            }
+        }
+        self.unsynced = 0;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// Delete the journal, once the transfer has ended or is abandoned.
+    pub fn discard(mut self) -> ZResult<()> {
+        drop(self.file.take());
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(ZError::io(&self.path, e)),
+        }
+    }
+}
+
+impl Drop for TransferJournal {
+    fn drop(&mut self) {
+        if let Err(e) = self.sync() {
+            warn!(error = %e, "Failed to sync transfer journal");
+        }
+        if self.in_use {
+            release(&self.path);
+        }
+    }
+}
+
+/// List the journals of transfers cut short, found in `dir`.
+///
+/// Unreadable journals are skipped.
+pub fn find_unfinished_transfers(dir: &Path) -> Vec<TransferJournal> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut journals: Vec<TransferJournal> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == JOURNAL_EXTENSION))
+        .filter_map(|path| match TransferJournal::load(&path) {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Skipping unreadable transfer journal");
+                None
+            }
+        })
+        .collect();
+    journals.sort_by(|a, b| a.path.cmp(&b.path));
+    journals
+}
+
+/// What the journal files of a transfer are named after: what it transfers
+/// where, so a rerun of the same transfer finds them.
+fn transfer_key(sources: &[PathBuf], destination: &Path, is_move: bool) -> String {
+    let key = serde_json::to_vec(&(sources, destination, is_move)).unwrap_or_default();
+    format!("{:016x}", xxh3_64(&key))
+}
+
+/// Mark the journal at `path` as in use, unless it already is.
+fn claim(path: &Path) -> bool {
+    IN_USE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_path_buf())
+}
+
+/// Mark the journal at `path` as no longer in use.
+fn release(path: &Path) {
+    IN_USE.lock().unwrap_or_else(|e| e.into_inner()).remove(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::TransferPlanBuilder;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_plan(temp: &TempDir) -> TransferPlan {
+        let source = temp.path().join("source");
+        fs::create_dir(&source).unwrap();
+        fs::write(source.join("a.txt"), "a").unwrap();
+        fs::write(source.join("b.txt"), "b").unwrap();
+        let dest = temp.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+        TransferPlanBuilder::new(&dest)
+            .add_source(&source)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_record_and_load() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+
+        let mut journal = TransferJournal::create(&dir, &plan).unwrap();
+        let mut files = plan.files().map(|item| item.source.clone());
+        let first = files.next().unwrap();
+        journal.record(&first).unwrap();
+        journal.record(&files.next().unwrap()).unwrap();
+        drop(journal);
+
+        // A line cut short by a crash
+        let path = find_unfinished_transfers(&dir).remove(0).path().to_path_buf();
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"\"C:\\\\cut")
+            .unwrap();
+
+        let journal =
+            TransferJournal::open(&dir, &plan.source_roots, &plan.destination_root, false)
+                .unwrap();
+        assert_eq!(journal.plan.items.len(), plan.items.len());
+        assert_eq!(journal.completed_count(), 2);
+        assert!(journal.is_done(&first));
+        assert!(matches!(journal.job_kind(), JobKind::Copy { .. }));
+
+        // A move of the same files is another transfer
+        assert!(
+            TransferJournal::open(&dir, &plan.source_roots, &plan.destination_root, true)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_record_syncs_in_batches() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+
+        let mut journal = TransferJournal::create(&dir, &plan).unwrap();
+        for i in 0..SYNC_EVERY_RECORDS - 1 {
+            journal.record(&temp.path().join(i.to_string())).unwrap();
+        }
+        assert_eq!(journal.unsynced, SYNC_EVERY_RECORDS - 1);
+        journal.record(&temp.path().join("last")).unwrap();
+        assert_eq!(journal.unsynced, 0);
+
+        // A record long enough after the last sync syncs on its own
+        journal.record(&temp.path().join("after")).unwrap();
+        if let Some(earlier) = Instant::now().checked_sub(SYNC_INTERVAL) {
+            journal.last_sync = earlier;
+            journal.record(&temp.path().join("late")).unwrap();
+            assert_eq!(journal.unsynced, 0);
+        } else {
+            journal.record(&temp.path().join("late")).unwrap();
+        }
+
+        let path = journal.path().to_path_buf();
+        drop(journal);
+        assert_eq!(
+            TransferJournal::load(&path).unwrap().completed_count(),
+            SYNC_EVERY_RECORDS + 2
+        );
+    }
+
+    #[test]
+    fn test_discard() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+
+        TransferJournal::create(&dir, &plan).unwrap();
+        let journals = find_unfinished_transfers(&dir);
+        assert_eq!(journals.len(), 1);
+
+        for journal in journals {
+            journal.discard().unwrap();
+        }
+        assert!(find_unfinished_transfers(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_identical_transfers_keep_their_own_journals() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+        let (sources, destination) = (&plan.source_roots, &plan.destination_root);
+        let open = || TransferJournal::open(&dir, sources, destination, false);
+
+        let first = TransferJournal::create(&dir, &plan).unwrap();
+        let second = TransferJournal::create(&dir, &plan).unwrap();
+        assert_ne!(first.path(), second.path());
+        // Journals of running transfers are not picked up by a rerun
+        assert!(open().is_none());
+
+        first.discard().unwrap();
+        assert_eq!(find_unfinished_transfers(&dir).len(), 1);
+        let path = second.path().to_path_buf();
+        drop(second);
+        assert_eq!(open().unwrap().path(), path);
+    }
+
+    #[test]
+    fn test_partial_copies() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+        let mut journal = TransferJournal::create(&dir, &plan).unwrap();
+
+        let files: Vec<_> = plan.files().cloned().collect();
+        for item in &files {
+            fs::create_dir_all(item.destination.parent().unwrap()).unwrap();
+            fs::write(&item.destination, "").unwrap();
+            let partial = PartialCopy {
+                source: item.source.clone(),
+                source_size: 1,
+                source_modified: None,
+                offset: 0,
+                prefix_hash: String::new(),
+            };
+            partial.save(&item.destination).unwrap();
+        }
+        journal.record(&files[0].source).unwrap();
+
+        // A finished file's sidecar is not a partial copy of this transfer
+        let partial = journal.partial_copies();
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial[0].0, files[1].destination);
+        assert_eq!(partial[0].1.source, files[1].source);
+    }
+
+    #[test]
+    fn test_unfinished_destinations() {
+        let temp = TempDir::new().unwrap();
+        let plan = setup_plan(&temp);
+        let dir = temp.path().join("journals");
+        let mut journal = TransferJournal::create(&dir, &plan).unwrap();
+        assert!(journal.unfinished_destinations().is_empty());
+
+        // Both files were being written when the transfer was cut short,
+        // and the first one finished
+        let files: Vec<_> = plan.files().cloned().collect();
+        for item in &files {
+            fs::create_dir_all(item.destination.parent().unwrap()).unwrap();
+            fs::write(&item.destination, "").unwrap();
+        }
+        journal.record(&files[0].source).unwrap();
+        assert_eq!(journal.unfinished_destinations(), [files[1].destination.clone()]);
+    }
+}
@@ -3,41 +3,132 @@
 //! Windows-specific file transfer engine using CopyFileEx for efficient
 //! file copying with progress callbacks.
 //!
+//! The transfer engine itself also builds on Linux and macOS, copying
+//! through a portable `std::fs` engine there; the shell integration
+//! (drag-and-drop, context menus, icons and thumbnails) is Windows-only,
+//! and the clipboard and "Open with" report themselves unavailable.
+//!
 //! This crate provides:
 //! - Single file copy with progress via `CopyFileExW`
+//! - Interchangeable transfer backends, picked per job, with a portable one
+//!   for other platforms
+//! - Resumable copies of large files
+//! - Unbuffered copies of huge files that bypass the file cache
+//! - Journals for resuming transfers cut short by a crash
 //! - Folder copy/move operations with conflict resolution
+//! - Archive creation and extraction jobs
+//! - Cancellable delete jobs with per-item results
+//! - Retrying access-denied items with administrator rights (UAC)
+//! - Folder size calculation jobs
+//! - File checksum and comparison jobs
+//! - Text encoding and line ending conversion jobs
 //! - Transfer planning and enumeration
 //! - Transfer reporting with JSON/text export
-//! - Windows clipboard integration (CF_HDROP)
+//! - Windows clipboard integration (CF_HDROP and text)
+//! - OLE drag-and-drop to and from the shell
+//! - "Open with" handlers registered for a file type
+//! - The shell's native context menu for files and folders
+//! - Shell icons of files and folders as PNG, cached per file type
+//! - Thumbnails of images and videos, cached on disk
 //! - Job scheduling and management
 //! - Cancellation and pause support
+//! - Bounded retries for transient I/O errors
+//! - Checksum verification of copied files
 
+pub mod backend;
+mod blocking;
+pub mod checksum;
 pub mod clipboard;
+pub mod compare;
+pub mod compress;
 pub mod conflict;
+pub mod convert;
 pub mod copy;
+pub mod delete;
+#[cfg(windows)]
+pub mod dragdrop;
+pub mod elevate;
 pub mod executor;
+pub mod extract;
 pub mod folder;
+#[cfg(windows)]
+pub mod icon;
 pub mod job;
+pub mod journal;
+pub mod open_with;
 pub mod plan;
+pub mod portable;
 pub mod report;
+pub mod resume;
+pub mod retry;
+#[cfg(windows)]
+pub mod shell_menu;
+pub mod size;
+#[cfg(windows)]
+pub mod thumbnail;
+pub mod unbuffered;
+pub mod verify;
 
 // Re-export main types
+pub use backend::{
+    default_backend, BackendRegistry, PortableBackend, ResumableBackend, TransferBackend,
+    UnbufferedBackend,
+};
+#[cfg(windows)]
+pub use backend::NativeBackend;
+pub use checksum::{ChecksumConfig, ChecksumEvent, ChecksumExecutor};
+pub use compare::{CompareConfig, CompareEvent, CompareExecutor};
+pub use compress::{CompressConfig, CompressEvent, CompressExecutor};
 pub use clipboard::{
-    clear_clipboard, clipboard_has_files, read_files_from_clipboard, write_files_to_clipboard,
-    Clipboard, ClipboardContent, DropEffect,
+    clear_clipboard, clipboard_has_files, clipboard_has_text, read_files_from_clipboard,
+    read_text_from_clipboard, write_files_to_clipboard, write_text_to_clipboard, Clipboard,
+    ClipboardContent, DropEffect,
+};
+pub use conflict::{
+    Conflict, ConflictAnswer, ConflictPolicy, ConflictResolution, ConflictResolver,
+    ConflictSettings,
+};
+pub use convert::{ConvertConfig, ConvertEvent, ConvertExecutor};
+pub use copy::{
+    copy_file_async, copy_file_throttled, copy_file_with_pause, copy_file_with_progress,
+    CopyProgress, CopyResult, ProgressCallback,
+};
+pub use delete::{DeleteConfig, DeleteEvent, DeleteExecutor};
+#[cfg(windows)]
+pub use dragdrop::{default_drop_effect, drag_files_out, drop_job};
+pub use elevate::{
+    is_access_denied, merge_elevated_results, run_elevated, ElevatedOperation, ElevatedResult,
+    ElevationManifest,
 };
-pub use conflict::{Conflict, ConflictPolicy, ConflictResolution, ConflictResolver};
-pub use copy::{copy_file_async, copy_file_with_progress, CopyProgress, CopyResult};
 pub use executor::{CopyExecutor, ExecutorConfig, ExecutorEvent};
+pub use extract::{ExtractConfig, ExtractEvent, ExtractExecutor};
 pub use folder::{
-    FolderTransferConfig, FolderTransferEvent, FolderTransferExecutor, ItemResult, TransferReport,
+    ConflictQuery, FolderTransferConfig, FolderTransferEvent, FolderTransferExecutor, ItemResult,
+    TransferReport,
 };
+#[cfg(windows)]
+pub use icon::{shell_icon_png, IconCache, MAX_ICON_SIZE, MIN_ICON_SIZE};
 pub use job::{JobId, JobKind, JobState, Progress};
+pub use journal::{find_unfinished_transfers, TransferJournal, JOURNAL_EXTENSION};
+pub use open_with::{open_with, open_with_dialog, open_with_handlers, OpenWithHandler};
 pub use plan::{same_volume, TransferItem, TransferPlan, TransferPlanBuilder, TransferStats};
+pub use portable::{copy_file_portable, copy_stream};
 pub use report::{
     DetailedTransferReport, ReportBuilder, ReportStorage, TransferItemResult, TransferOperation,
     TransferStatus, TransferSummary,
 };
+pub use resume::{copy_file_resume, discard_partial_copy, PartialCopy, PART_EXTENSION};
+pub use retry::{is_transient, RetryPolicy};
+#[cfg(windows)]
+pub use shell_menu::{show_shell_context_menu, ShellMenuResult};
+pub use size::{SizeConfig, SizeEvent, SizeExecutor};
+#[cfg(windows)]
+pub use thumbnail::{
+    has_thumbnail, ThumbnailCache, DEFAULT_THUMBNAIL_CACHE_BYTES, MAX_THUMBNAIL_SIZE,
+    MIN_THUMBNAIL_SIZE,
+};
+pub use unbuffered::copy_file_unbuffered;
+pub use verify::{file_checksum, verify_copy, VerifyResult};
 
 /// Initialize the transfer engine.
 ///
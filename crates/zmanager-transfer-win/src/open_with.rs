@@ -0,0 +1,412 @@
+//! "Open with" support: the applications registered for a file type.
+//!
+//! Handlers are gathered from the registry the way Explorer's "Open with"
+//! menu does: the default program, the user's recently chosen programs, then
+//! the ProgIDs and applications registered under the extension. Names come
+//! from `AssocQueryStringW`, which also weeds out stale registrations.
+//! A handler is launched with `ShellExecuteExW` using its registry class;
+//! [`open_with_dialog`] shows the shell's own "Open with" dialog instead.
+
+#[cfg(windows)]
+use std::ffi::OsStr;
+#[cfg(windows)]
+use std::mem::size_of;
+#[cfg(windows)]
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+#[cfg(windows)]
+use tracing::debug;
+#[cfg(windows)]
+use windows::core::{PCWSTR, PWSTR};
+#[cfg(windows)]
+use windows::Win32::Foundation::{ERROR_CANCELLED, ERROR_SUCCESS, HWND};
+#[cfg(windows)]
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_APARTMENTTHREADED};
+#[cfg(windows)]
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegEnumKeyExW, RegEnumValueW, RegGetValueW, RegOpenKeyExW, HKEY,
+    HKEY_CLASSES_ROOT, HKEY_CURRENT_USER, KEY_READ, RRF_RT_REG_SZ,
+};
+#[cfg(windows)]
+use windows::Win32::UI::Shell::{
+    AssocQueryStringW, SHOpenWithDialog, ShellExecuteExW, ASSOCF, ASSOCF_INIT_IGNOREUNKNOWN,
+    ASSOCF_NOTRUNCATE, ASSOCF_OPEN_BYEXENAME, ASSOCSTR_FRIENDLYAPPNAME, OAIF_ALLOW_REGISTRATION,
+    OAIF_EXEC, OPENASINFO, SEE_MASK_CLASSNAME, SEE_MASK_FLAG_NO_UI, SEE_MASK_NOASYNC,
+    SHELLEXECUTEINFOW,
+};
+use zmanager_core::{ZError, ZResult};
+
+/// Per-user file type choices, under `HKEY_CURRENT_USER`.
+#[cfg(windows)]
+const FILE_EXTS: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts";
+
+/// Prefix of the registry class of an application registered by executable.
+#[cfg(windows)]
+const APPLICATIONS: &str = r"Applications\";
+
+/// An application registered to open a file type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenWithHandler {
+    /// Name to show, e.g. "Notepad".
+    pub name: String,
+    /// Registry class the application is launched through: a ProgID such as
+    /// `txtfile`, or `Applications\notepad.exe` for one registered by
+    /// executable name.
+    pub class: String,
+    /// Whether files of this type open with it by default.
+    pub is_default: bool,
+}
+
+/// List the applications registered to open `path`, default first.
+///
+/// Files without an extension have no registrations, and registrations
+/// whose application can no longer be found are left out, so the list may
+/// be empty; [`open_with_dialog`] still lets the user pick a program then.
+#[cfg(windows)]
+pub fn open_with_handlers(path: &Path) -> Vec<OpenWithHandler> {
+    let Some(ext) = path.extension().map(|e| format!(".{}", e.to_string_lossy())) else {
+        return Vec::new();
+    };
+
+    let user = RegKey::open(HKEY_CURRENT_USER, &format!(r"{FILE_EXTS}\{ext}"));
+    let class = RegKey::open(HKEY_CLASSES_ROOT, &ext);
+
+    let mut classes = Vec::new();
+    // The user's choice overrides the type's registered default
+    let default = user
+        .as_ref()
+        .and_then(|key| key.subkey("UserChoice"))
+        .and_then(|key| key.string(Some("ProgId")))
+        .or_else(|| class.as_ref().and_then(|key| key.string(None)));
+    classes.extend(default.clone());
+    if let Some(user) = &user {
+        if let Some(list) = user.subkey("OpenWithList") {
+            let mru = list.string(Some("MRUList")).unwrap_or_default();
+            let apps: Vec<(String, String)> = list
+                .value_names()
+                .into_iter()
+                .filter_map(|name| list.string(Some(&name)).map(|exe| (name, exe)))
+                .collect();
+            classes.extend(mru_order(&mru, apps).iter().map(|exe| app_class(exe)));
+        }
+        if let Some(progids) = user.subkey("OpenWithProgids") {
+            classes.extend(progids.value_names());
+        }
+    }
+    if let Some(class) = &class {
+        if let Some(progids) = class.subkey("OpenWithProgids") {
+            classes.extend(progids.value_names());
+        }
+        if let Some(list) = class.subkey("OpenWithList") {
+            classes.extend(list.subkey_names().iter().map(|exe| app_class(exe)));
+        }
+    }
+
+    let mut handlers: Vec<OpenWithHandler> = Vec::new();
+    for class in dedup_ignore_case(classes) {
+        let Some(name) = friendly_app_name(&class) else {
+            continue;
+        };
+        // Several registrations often lead to the same application
+        if handlers.iter().any(|h| h.name.eq_ignore_ascii_case(&name)) {
+            continue;
+        }
+        let is_default = default.as_deref().is_some_and(|d| d.eq_ignore_ascii_case(&class));
+        handlers.push(OpenWithHandler {
+            name,
+            class,
+            is_default,
+        });
+    }
+    debug!(path = %path.display(), count = handlers.len(), "Found open-with handlers");
+    handlers
+}
+
+/// Open `path` with `handler`.
+#[cfg(windows)]
+pub fn open_with(path: &Path, handler: &OpenWithHandler) -> ZResult<()> {
+    debug!(path = %path.display(), class = %handler.class, "Opening with handler");
+
+    let file = to_wide(path.as_os_str());
+    let class = to_wide(OsStr::new(&handler.class));
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_CLASSNAME | SEE_MASK_NOASYNC | SEE_MASK_FLAG_NO_UI,
+        lpFile: PCWSTR(file.as_ptr()),
+        lpClass: PCWSTR(class.as_ptr()),
+        nShow: 1, // SW_SHOWNORMAL
+        ..Default::default()
+    };
+    unsafe { ShellExecuteExW(&mut info) }.map_err(|e| ZError::from_io(path, e.into()))
+}
+
+/// Show the shell's "Open with" dialog for `path`, which launches the
+/// program the user picks and can make it the default.
+///
+/// Blocks until the dialog is closed; returns [`ZError::Cancelled`] if it
+/// was dismissed.
+#[cfg(windows)]
+pub fn open_with_dialog(path: &Path) -> ZResult<()> {
+    debug!(path = %path.display(), "Showing open-with dialog");
+
+    let file = to_wide(path.as_os_str());
+    let info = OPENASINFO {
+        pcszFile: PCWSTR(file.as_ptr()),
+        pcszClass: PCWSTR::null(),
+        oaifInFlags: OAIF_ALLOW_REGISTRATION | OAIF_EXEC,
+    };
+    unsafe {
+        // Fails harmlessly if COM is already initialized on this thread
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        SHOpenWithDialog(HWND::default(), &info)
+    }
+    .map_err(|e| {
+        if e.code() == ERROR_CANCELLED.to_hresult() {
+            ZError::Cancelled
+        } else {
+            ZError::from_io(path, e.into())
+        }
+    })
+}
+
+/// List the applications registered to open `path` (non-Windows fallback).
+///
+/// Nothing registers handlers this way outside Windows, so this is always
+/// empty.
+#[cfg(not(windows))]
+pub fn open_with_handlers(_path: &Path) -> Vec<OpenWithHandler> {
+    Vec::new()
+}
+
+/// Open `path` with `handler` (non-Windows fallback).
+#[cfg(not(windows))]
+pub fn open_with(path: &Path, _handler: &OpenWithHandler) -> ZResult<()> {
+    Err(ZError::InvalidOperation {
+        operation: format!("open {} with another program", path.display()),
+        reason: "\"Open with\" is only available on Windows".to_string(),
+    })
+}
+
+/// Show the shell's "Open with" dialog for `path` (non-Windows fallback).
+#[cfg(not(windows))]
+pub fn open_with_dialog(path: &Path) -> ZResult<()> {
+    Err(ZError::InvalidOperation {
+        operation: format!("choose a program to open {}", path.display()),
+        reason: "\"Open with\" is only available on Windows".to_string(),
+    })
+}
+
+/// Order the values of an `OpenWithList` key by its `MRUList`, which names
+/// the values (single letters) most recent first. Values missing from the
+/// MRU list go last.
+#[cfg(windows)]
+fn mru_order(mru: &str, mut apps: Vec<(String, String)>) -> Vec<String> {
+    apps.retain(|(name, _)| name != "MRUList");
+    apps.sort_by_key(|(name, _)| {
+        let letter = name.chars().next().filter(|_| name.chars().count() == 1);
+        letter.and_then(|c| mru.find(c)).unwrap_or(usize::MAX)
+    });
+    apps.into_iter().map(|(_, exe)| exe).collect()
+}
+
+/// Registry class of an application registered by executable name.
+#[cfg(windows)]
+fn app_class(exe: &str) -> String {
+    format!("{APPLICATIONS}{exe}")
+}
+
+/// Drop empty and repeated classes, ignoring case as the registry does.
+#[cfg(windows)]
+fn dedup_ignore_case(classes: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for class in classes {
+        if !class.is_empty() && !unique.iter().any(|c| c.eq_ignore_ascii_case(&class)) {
+            unique.push(class);
+        }
+    }
+    unique
+}
+
+/// Display name of the application behind a registry class, or `None` if
+/// it is no longer installed.
+#[cfg(windows)]
+fn friendly_app_name(class: &str) -> Option<String> {
+    let (flags, assoc) = match class.strip_prefix(APPLICATIONS) {
+        Some(exe) => (ASSOCF_OPEN_BYEXENAME, exe),
+        None => (ASSOCF::default(), class),
+    };
+    let assoc = to_wide(OsStr::new(assoc));
+    let mut buf = [0u16; 512];
+    let mut len = buf.len() as u32;
+    unsafe {
+        AssocQueryStringW(
+            flags | ASSOCF_INIT_IGNOREUNKNOWN | ASSOCF_NOTRUNCATE,
+            ASSOCSTR_FRIENDLYAPPNAME,
+            PCWSTR(assoc.as_ptr()),
+            PCWSTR::null(),
+            PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        )
+    }
+    .ok()
+    .ok()?;
+    let name = from_wide(&buf);
+    (!name.trim().is_empty()).then_some(name)
+}
+
+/// Null-terminated UTF-16 copy of `text`.
+#[cfg(windows)]
+fn to_wide(text: &OsStr) -> Vec<u16> {
+    text.encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// The text before the first null of a UTF-16 buffer.
+#[cfg(windows)]
+fn from_wide(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+/// An open registry key, closed when dropped.
+#[cfg(windows)]
+struct RegKey(HKEY);
+
+#[cfg(windows)]
+impl RegKey {
+    /// Open `subkey` of `root` for reading.
+    fn open(root: HKEY, subkey: &str) -> Option<Self> {
+        let subkey = to_wide(OsStr::new(subkey));
+        let mut key = HKEY::default();
+        let result = unsafe { RegOpenKeyExW(root, PCWSTR(subkey.as_ptr()), 0, KEY_READ, &mut key) };
+        (result == ERROR_SUCCESS).then_some(Self(key))
+    }
+
+    /// Open a subkey of this key for reading.
+    fn subkey(&self, name: &str) -> Option<Self> {
+        Self::open(self.0, name)
+    }
+
+    /// Read a string value, or the key's default value for `None`.
+    fn string(&self, name: Option<&str>) -> Option<String> {
+        let name = name.map(|n| to_wide(OsStr::new(n)));
+        let name = name.as_ref().map_or(PCWSTR::null(), |n| PCWSTR(n.as_ptr()));
+        let mut size = 0u32;
+        let result = unsafe {
+            RegGetValueW(self.0, PCWSTR::null(), name, RRF_RT_REG_SZ, None, None, Some(&mut size))
+        };
+        if result != ERROR_SUCCESS {
+            return None;
+        }
+        let mut buf = vec![0u16; size as usize / 2 + 1];
+        let mut size = (buf.len() * 2) as u32;
+        let result = unsafe {
+            RegGetValueW(
+                self.0,
+                PCWSTR::null(),
+                name,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buf.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        (result == ERROR_SUCCESS).then(|| from_wide(&buf))
+    }
+
+    /// Names of the key's values.
+    fn value_names(&self) -> Vec<String> {
+        // Value names are at most 16,383 characters
+        let mut buf = vec![0u16; 16_384];
+        let mut names = Vec::new();
+        for index in 0.. {
+            let mut len = buf.len() as u32;
+            let name = PWSTR(buf.as_mut_ptr());
+            let result =
+                unsafe { RegEnumValueW(self.0, index, name, &mut len, None, None, None, None) };
+            if result != ERROR_SUCCESS {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buf[..len as usize]));
+        }
+        names
+    }
+
+    /// Names of the key's subkeys.
+    fn subkey_names(&self) -> Vec<String> {
+        // Key names are at most 255 characters
+        let mut buf = [0u16; 256];
+        let mut names = Vec::new();
+        for index in 0.. {
+            let mut len = buf.len() as u32;
+            let result = unsafe {
+                RegEnumKeyExW(
+                    self.0,
+                    index,
+                    PWSTR(buf.as_mut_ptr()),
+                    &mut len,
+                    None,
+                    PWSTR::null(),
+                    None,
+                    None,
+                )
+            };
+            if result != ERROR_SUCCESS {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buf[..len as usize]));
+        }
+        names
+    }
+}
+
+#[cfg(windows)]
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RegCloseKey(self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(windows)]
+    fn apps(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries.iter().map(|(n, e)| (n.to_string(), e.to_string())).collect()
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_mru_order() {
+        let list = apps(&[
+            ("a", "notepad.exe"),
+            ("b", "code.exe"),
+            ("c", "wordpad.exe"),
+            ("MRUList", "bca"),
+            ("x", "unlisted.exe"),
+        ]);
+        let ordered = mru_order("bca", list);
+        assert_eq!(ordered, ["code.exe", "wordpad.exe", "notepad.exe", "unlisted.exe"]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_dedup_ignore_case() {
+        let classes = vec![
+            "txtfile".to_string(),
+            app_class("notepad.exe"),
+            "TXTFILE".to_string(),
+            String::new(),
+            r"applications\NOTEPAD.EXE".to_string(),
+        ];
+        assert_eq!(dedup_ignore_case(classes), ["txtfile", r"Applications\notepad.exe"]);
+    }
+
+    #[test]
+    fn test_no_extension_has_no_handlers() {
+        assert!(open_with_handlers(Path::new("README")).is_empty());
+    }
+}
@@ -4,11 +4,19 @@
 //! and generating destination paths with conflict detection.
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, trace};
 use walkdir::WalkDir;
-use zmanager_core::{ZError, ZResult};
+use zmanager_core::fs::to_long_path;
+use zmanager_core::{
+    available_space, extended_path, wildcard_match, EntryMeta, LinkHandling, ZError, ZResult,
+};
+
+use crate::backend::TransferBackend;
+
+pub use zmanager_core::same_volume;
 
 /// An individual item in a transfer plan.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +33,9 @@ pub struct TransferItem {
     pub depth: usize,
     /// Whether a conflict exists at the destination.
     pub has_conflict: bool,
+    /// Target of a symlink or junction that is recreated rather than followed.
+    #[serde(default)]
+    pub link_target: Option<PathBuf>,
 }
 
 impl TransferItem {
@@ -36,7 +47,7 @@ impl TransferItem {
         size: u64,
         depth: usize,
     ) -> Self {
-        let has_conflict = destination.exists();
+        let has_conflict = extended_path(&destination).symlink_metadata().is_ok();
         Self {
             source,
             destination,
@@ -44,8 +55,24 @@ impl TransferItem {
             size,
             depth,
             has_conflict,
+            link_target: None,
         }
     }
+
+    /// Create an item that recreates the link at `source` instead of copying
+    /// what it points to.
+    pub fn link(source: PathBuf, destination: PathBuf, depth: usize) -> Self {
+        let target = std::fs::read_link(extended_path(&source)).unwrap_or_default();
+        Self {
+            link_target: Some(target),
+            ..Self::new(source, destination, false, 0, depth)
+        }
+    }
+
+    /// Check if this item is a link to recreate at the destination.
+    pub fn is_link(&self) -> bool {
+        self.link_target.is_some()
+    }
 }
 
 /// Statistics for a transfer plan.
@@ -61,6 +88,10 @@ pub struct TransferStats {
     pub conflicts: usize,
     /// Number of items that would be skipped.
     pub skipped: usize,
+    /// Number of files and folders left out by include and exclude
+    /// patterns. An excluded folder counts once, whatever it holds.
+    #[serde(default)]
+    pub excluded: usize,
 }
 
 impl TransferStats {
@@ -71,7 +102,7 @@ impl TransferStats {
 }
 
 /// A complete transfer plan for a folder operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferPlan {
     /// All items to transfer, in order (directories first, then files).
     pub items: Vec<TransferItem>,
@@ -105,16 +136,44 @@ impl TransferPlan {
     pub fn has_conflicts(&self) -> bool {
         self.stats.conflicts > 0
     }
+
+    /// Check that the destination volume has room for all files of the plan.
+    ///
+    /// Fails with [`ZError::InsufficientSpace`] if it doesn't. A volume whose
+    /// free space can't be read is assumed to have room.
+    pub fn check_free_space(&self) -> ZResult<()> {
+        let available = available_space(&self.destination_root);
+        check_space(&self.destination_root, self.stats.total_bytes, available)
+    }
+}
+
+/// Fail if `available` bytes on the volume of `path` can't hold `required`.
+fn check_space(path: &Path, required: u64, available: Option<u64>) -> ZResult<()> {
+    match available {
+        Some(available) if available < required => Err(ZError::InsufficientSpace {
+            path: path.to_path_buf(),
+            required,
+            available,
+        }),
+        _ => Ok(()),
+    }
 }
 
 /// Builder for creating transfer plans.
+///
+/// Sources are transferred into the destination folder under their own
+/// names. A single source whose destination isn't a folder is transferred
+/// as the destination instead, so a file or folder can be renamed on the way.
 #[derive(Debug)]
 pub struct TransferPlanBuilder {
     sources: Vec<PathBuf>,
     destination: PathBuf,
     is_move: bool,
-    follow_symlinks: bool,
+    links: LinkHandling,
     max_depth: Option<usize>,
+    backend: Option<Arc<dyn TransferBackend>>,
+    include: Vec<String>,
+    exclude: Vec<String>,
 }
 
 impl TransferPlanBuilder {
@@ -124,8 +183,11 @@ impl TransferPlanBuilder {
             sources: Vec::new(),
             destination: destination.as_ref().to_path_buf(),
             is_move: false,
-            follow_symlinks: false,
+            links: LinkHandling::default(),
             max_depth: None,
+            backend: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
         }
     }
 
@@ -149,9 +211,9 @@ impl TransferPlanBuilder {
         self
     }
 
-    /// Set whether to follow symbolic links.
-    pub fn follow_symlinks(mut self, follow: bool) -> Self {
-        self.follow_symlinks = follow;
+    /// Set how symlinks and junctions are transferred.
+    pub fn link_handling(mut self, links: LinkHandling) -> Self {
+        self.links = links;
         self
     }
 
@@ -161,6 +223,55 @@ impl TransferPlanBuilder {
         self
     }
 
+    /// Set the backend the transfer runs on. Sources it can walk are listed
+    /// through it, and conflicts are looked up through it.
+    pub fn backend(mut self, backend: Arc<dyn TransferBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Only transfer files matching one of `patterns`. Folders are still
+    /// walked, so matching files inside them are found.
+    ///
+    /// Patterns are matched like [`exclude`](Self::exclude) patterns.
+    pub fn include(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Leave out files and folders matching any of `patterns`, along with
+    /// everything inside those folders.
+    ///
+    /// Patterns are wildcards (`*` and `?`, ignoring case) matched against
+    /// names, like `node_modules` or `*.tmp`. A pattern with a path
+    /// separator is matched against the path below the source folder
+    /// instead, like `build/*.o`.
+    pub fn exclude(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.exclude.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether the include and exclude patterns leave out the entry at
+    /// `relative`, its path below the parent of its source.
+    fn is_excluded(&self, relative: &Path, is_dir: bool) -> bool {
+        let name = relative.file_name().unwrap_or_default().to_string_lossy();
+        let below_source: Vec<_> = relative
+            .components()
+            .skip(1)
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect();
+        let path = below_source.join("/");
+        let matches = |pattern: &String| {
+            if pattern.contains(['/', '\\']) {
+                wildcard_match(&pattern.replace('\\', "/"), &path)
+            } else {
+                wildcard_match(pattern, &name)
+            }
+        };
+        self.exclude.iter().any(matches)
+            || (!is_dir && !self.include.is_empty() && !self.include.iter().any(matches))
+    }
+
     /// Build the transfer plan.
     pub fn build(self) -> ZResult<TransferPlan> {
         if self.sources.is_empty() {
@@ -180,19 +291,65 @@ impl TransferPlanBuilder {
         let mut stats = TransferStats::default();
 
         // Ensure destination directory exists or will be created
-        let dest_is_dir = self.destination.is_dir()
-            || self.sources.len() > 1
-            || self.sources.first().map(|s| s.is_dir()).unwrap_or(false);
+        let is_dir = |path: &Path| {
+            path.is_dir()
+                || self
+                    .backend
+                    .as_ref()
+                    .and_then(|backend| backend.entry(path))
+                    .is_some_and(|entry| entry.is_directory())
+        };
+        let dest_is_dir = is_dir(&self.destination) || self.sources.len() > 1;
 
         for source in &self.sources {
-            if !source.exists() {
+            if let Some(walked) = self.backend.as_ref().and_then(|backend| backend.walk(source)) {
+                self.add_walked(source, walked?, dest_is_dir, &mut items, &mut stats)?;
+                continue;
+            }
+
+            let os_source = extended_path(source);
+            let is_link = os_source
+                .symlink_metadata()
+                .is_ok_and(|meta| meta.file_type().is_symlink());
+
+            if is_link && self.links == LinkHandling::CopyLink {
+                // Recreate the link itself, even if its target is missing
+                let dest_path = if dest_is_dir {
+                    let file_name = source.file_name().ok_or_else(|| ZError::InvalidPath {
+                        path: source.clone(),
+                        reason: "No file name".to_string(),
+                    })?;
+                    self.destination.join(file_name)
+                } else {
+                    self.destination.clone()
+                };
+
+                let item = TransferItem::link(source.clone(), dest_path, 0);
+
+                if item.has_conflict {
+                    stats.conflicts += 1;
+                }
+                stats.total_files += 1;
+
+                items.push(item);
+                continue;
+            }
+
+            if !os_source.exists() {
                 return Err(ZError::NotFound {
                     path: source.clone(),
                 });
             }
 
-            if source.is_file() {
+            if os_source.is_file() {
                 // Single file transfer
+                let name = Path::new(source.file_name().unwrap_or_default());
+                if self.is_excluded(name, false) {
+                    trace!(source = %source.display(), "Excluded item");
+                    stats.excluded += 1;
+                    continue;
+                }
+
                 let dest_path = if dest_is_dir {
                     let file_name = source.file_name().ok_or_else(|| ZError::InvalidPath {
                         path: source.clone(),
@@ -203,7 +360,7 @@ impl TransferPlanBuilder {
                     self.destination.clone()
                 };
 
-                let size = std::fs::metadata(source)
+                let size = std::fs::metadata(&os_source)
                     .map(|m| m.len())
                     .unwrap_or(0);
 
@@ -216,10 +373,18 @@ impl TransferPlanBuilder {
                 stats.total_bytes += size;
 
                 items.push(item);
-            } else if source.is_dir() {
+            } else if os_source.is_dir() {
                 // Directory transfer - enumerate contents
-                self.enumerate_directory(source, &self.destination, &mut items, &mut stats)?;
+                self.enumerate_directory(source, dest_is_dir, &mut items, &mut stats)?;
+            }
+        }
+
+        // What's at the destination is only known to the backend
+        if let Some(backend) = &self.backend {
+            for item in &mut items {
+                item.has_conflict = backend.entry(&item.destination).is_some();
             }
+            stats.conflicts = items.iter().filter(|item| item.has_conflict).count();
         }
 
         // Sort items: directories first (by depth), then files
@@ -245,41 +410,106 @@ impl TransferPlanBuilder {
             dirs = plan.stats.total_dirs,
             bytes = plan.stats.total_bytes,
             conflicts = plan.stats.conflicts,
+            excluded = plan.stats.excluded,
             "Transfer plan built"
         );
 
         Ok(plan)
     }
 
-    fn enumerate_directory(
+    /// Add the entries a backend walked at and below `source`.
+    fn add_walked(
         &self,
-        source_root: &Path,
-        dest_root: &Path,
+        source: &Path,
+        entries: Vec<EntryMeta>,
+        dest_is_dir: bool,
         items: &mut Vec<TransferItem>,
         stats: &mut TransferStats,
     ) -> ZResult<()> {
-        let source_parent = source_root.parent().unwrap_or(source_root);
-        let source_name = source_root
-            .file_name()
-            .ok_or_else(|| ZError::InvalidPath {
-                path: source_root.to_path_buf(),
-                reason: "No directory name".to_string(),
+        let source_parent = source.parent().unwrap_or(source);
+        // Walked entries are flat, so what's inside an excluded folder is
+        // left out by path
+        let mut excluded_dirs: Vec<PathBuf> = Vec::new();
+        for entry in entries {
+            if excluded_dirs.iter().any(|dir| entry.path.starts_with(dir)) {
+                continue;
+            }
+            let relative_path = entry.path.strip_prefix(source_parent).map_err(|_| {
+                ZError::InvalidPath {
+                    path: entry.path.clone(),
+                    reason: "Failed to compute relative path".to_string(),
+                }
             })?;
+            let depth = relative_path.components().count().saturating_sub(1);
+            if self.max_depth.is_some_and(|max| depth > max) {
+                continue;
+            }
+            let is_dir = entry.is_directory();
+            if self.is_excluded(relative_path, is_dir) {
+                trace!(source = %entry.path.display(), "Excluded item");
+                stats.excluded += 1;
+                if is_dir {
+                    excluded_dirs.push(entry.path.clone());
+                }
+                continue;
+            }
 
-        // The destination for this source directory
-        let _dest_for_source = dest_root.join(source_name);
+            let dest_path = self.destination_for(relative_path, dest_is_dir);
+            let size = if is_dir { 0 } else { entry.size };
+            trace!(source = %entry.path.display(), is_dir, size, depth, "Walked item");
 
-        let mut walker = WalkDir::new(source_root);
+            if is_dir {
+                stats.total_dirs += 1;
+            } else {
+                stats.total_files += 1;
+                stats.total_bytes += size;
+            }
+            items.push(TransferItem::new(entry.path, dest_path, is_dir, size, depth));
+        }
+        Ok(())
+    }
 
-        if !self.follow_symlinks {
-            walker = walker.follow_links(false);
+    /// Where the entry at `relative`, its path below the parent of its
+    /// source, goes: under the source's name in the destination folder, or
+    /// in place of the source when it is transferred as the destination.
+    fn destination_for(&self, relative: &Path, dest_is_dir: bool) -> PathBuf {
+        if dest_is_dir {
+            return self.destination.join(relative);
         }
+        let below_source: PathBuf = relative.components().skip(1).collect();
+        if below_source.as_os_str().is_empty() {
+            self.destination.clone()
+        } else {
+            self.destination.join(below_source)
+        }
+    }
+
+    fn enumerate_directory(
+        &self,
+        source_root: &Path,
+        dest_is_dir: bool,
+        items: &mut Vec<TransferItem>,
+        stats: &mut TransferStats,
+    ) -> ZResult<()> {
+        let source_parent = source_root.parent().unwrap_or(source_root);
+        source_root.file_name().ok_or_else(|| ZError::InvalidPath {
+            path: source_root.to_path_buf(),
+            reason: "No directory name".to_string(),
+        })?;
+
+        // Walk the prefixed root so entries at any depth get long path
+        // support, and map them back to plain paths below
+        let walk_root = to_long_path(source_root);
+        let walk_parent = walk_root.parent().unwrap_or(&walk_root).to_path_buf();
+        let mut walker =
+            WalkDir::new(&walk_root).follow_links(self.links == LinkHandling::Follow);
 
         if let Some(depth) = self.max_depth {
             walker = walker.max_depth(depth);
         }
 
-        for entry in walker {
+        let mut entries = walker.into_iter();
+        while let Some(entry) = entries.next() {
             let entry = entry.map_err(|e| {
                 let path = e.path().map(|p| p.to_path_buf()).unwrap_or_default();
                 ZError::Io {
@@ -291,18 +521,38 @@ impl TransferPlanBuilder {
                 }
             })?;
 
-            let source_path = entry.path();
-            let relative_path = source_path.strip_prefix(source_parent).map_err(|_| {
+            let relative_path = entry.path().strip_prefix(&walk_parent).map_err(|_| {
                 ZError::InvalidPath {
-                    path: source_path.to_path_buf(),
+                    path: entry.path().to_path_buf(),
                     reason: "Failed to compute relative path".to_string(),
                 }
             })?;
+            let source_path = source_parent.join(relative_path);
 
-            let dest_path = dest_root.join(relative_path);
+            let dest_path = self.destination_for(relative_path, dest_is_dir);
             let depth = entry.depth();
             let is_dir = entry.file_type().is_dir();
 
+            if self.is_excluded(relative_path, is_dir) {
+                trace!(source = %source_path.display(), "Excluded item");
+                stats.excluded += 1;
+                if is_dir {
+                    entries.skip_current_dir();
+                }
+                continue;
+            }
+
+            if entry.path_is_symlink() && self.links == LinkHandling::CopyLink {
+                trace!(source = %source_path.display(), "Enumerated link");
+                let item = TransferItem::link(source_path.clone(), dest_path, depth);
+                if item.has_conflict {
+                    stats.conflicts += 1;
+                }
+                stats.total_files += 1;
+                items.push(item);
+                continue;
+            }
+
             let size = if is_dir {
                 0
             } else {
@@ -318,7 +568,7 @@ impl TransferPlanBuilder {
                 "Enumerated item"
             );
 
-            let item = TransferItem::new(source_path.to_path_buf(), dest_path, is_dir, size, depth);
+            let item = TransferItem::new(source_path, dest_path, is_dir, size, depth);
 
             if item.has_conflict {
                 stats.conflicts += 1;
@@ -338,61 +588,13 @@ impl TransferPlanBuilder {
     }
 }
 
-/// Check if two paths are on the same volume (for move optimization).
-#[cfg(windows)]
-pub fn same_volume(path1: &Path, path2: &Path) -> bool {
-    // Get the root of each path
-    let root1 = get_volume_root(path1);
-    let root2 = get_volume_root(path2);
-
-    match (root1, root2) {
-        (Some(r1), Some(r2)) => r1.eq_ignore_ascii_case(&r2),
-        _ => false,
-    }
-}
-
-#[cfg(windows)]
-fn get_volume_root(path: &Path) -> Option<String> {
-    let path_str = path.to_string_lossy();
-
-    // Handle UNC paths: \\server\share
-    if path_str.starts_with("\\\\") {
-        let parts: Vec<&str> = path_str.trim_start_matches("\\\\").splitn(3, '\\').collect();
-        if parts.len() >= 2 {
-            return Some(format!("\\\\{}\\{}", parts[0], parts[1]));
-        }
-    }
-
-    // Handle drive letters: C:\
-    if path_str.len() >= 2 {
-        let chars: Vec<char> = path_str.chars().take(2).collect();
-        if chars[1] == ':' {
-            return Some(format!("{}:", chars[0].to_ascii_uppercase()));
-        }
-    }
-
-    None
-}
-
-#[cfg(not(windows))]
-pub fn same_volume(path1: &Path, path2: &Path) -> bool {
-    // On non-Windows, compare mount points using stat
-    use std::os::unix::fs::MetadataExt;
-    
-    let meta1 = std::fs::metadata(path1).ok();
-    let meta2 = std::fs::metadata(path2).ok();
-    
-    match (meta1, meta2) {
-        (Some(m1), Some(m2)) => m1.dev() == m2.dev(),
-        _ => false,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::copy::ProgressCallback;
     use std::fs;
     use tempfile::TempDir;
+    use zmanager_core::{CancellationToken, EntryKind, PauseToken, SpeedLimit};
 
     fn create_test_tree(dir: &TempDir) -> PathBuf {
         let root = dir.path().join("source");
@@ -463,6 +665,33 @@ mod tests {
         assert!(first_item.is_dir);
     }
 
+    #[test]
+    fn test_build_plan_renames_single_source() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        let renamed = temp.path().join("dest").join("renamed");
+
+        let plan = TransferPlanBuilder::new(&renamed)
+            .add_source(&source)
+            .build()
+            .unwrap();
+
+        assert_eq!(plan.stats.total_items(), 6);
+        let root = plan.directories().find(|item| item.depth == 0).unwrap();
+        assert_eq!(root.destination, renamed);
+        let nested = plan.files().find(|item| item.size == 300).unwrap();
+        assert_eq!(nested.destination, renamed.join("subdir/nested/file3.txt"));
+
+        // Into an existing folder, the source keeps its name
+        fs::create_dir_all(&renamed).unwrap();
+        let plan = TransferPlanBuilder::new(&renamed)
+            .add_source(&source)
+            .build()
+            .unwrap();
+        let root = plan.directories().find(|item| item.depth == 0).unwrap();
+        assert_eq!(root.destination, renamed.join("source"));
+    }
+
     #[test]
     fn test_build_plan_with_conflicts() {
         let temp = TempDir::new().unwrap();
@@ -504,6 +733,92 @@ mod tests {
         assert_eq!(plan.items.len(), 2);
     }
 
+    /// Serves a small tree under `remote:`, with `remote:/dst/tree/a.txt`
+    /// already at the destination.
+    #[derive(Debug)]
+    struct ListedBackend;
+
+    impl ListedBackend {
+        fn meta(path: &str, kind: EntryKind, size: u64) -> EntryMeta {
+            let path = PathBuf::from(path);
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let mut meta = EntryMeta::new(name, path, kind);
+            meta.size = size;
+            meta
+        }
+    }
+
+    impl TransferBackend for ListedBackend {
+        fn name(&self) -> &'static str {
+            "listed"
+        }
+
+        fn entry(&self, path: &Path) -> Option<EntryMeta> {
+            let kind = match path.to_str()? {
+                "remote:/dst" => EntryKind::Directory,
+                "remote:/dst/tree/a.txt" => EntryKind::File,
+                _ => return None,
+            };
+            Some(Self::meta(path.to_str()?, kind, 1))
+        }
+
+        fn walk(&self, path: &Path) -> Option<ZResult<Vec<EntryMeta>>> {
+            path.starts_with("remote:").then(|| {
+                Ok(vec![
+                    Self::meta("remote:/src/tree", EntryKind::Directory, 0),
+                    Self::meta("remote:/src/tree/a.txt", EntryKind::File, 10),
+                    Self::meta("remote:/src/tree/sub", EntryKind::Directory, 0),
+                    Self::meta("remote:/src/tree/sub/b.txt", EntryKind::File, 20),
+                ])
+            })
+        }
+
+        fn copy_file(
+            &self,
+            _source: &Path,
+            _destination: &Path,
+            _overwrite: bool,
+            _cancel_token: CancellationToken,
+            _pause_token: PauseToken,
+            _speed_limit: SpeedLimit,
+            _progress_callback: Option<ProgressCallback>,
+        ) -> ZResult<u64> {
+            unreachable!("planning doesn't copy")
+        }
+    }
+
+    #[test]
+    fn test_build_plan_through_backend() {
+        let plan = TransferPlanBuilder::new("remote:/dst")
+            .add_source("remote:/src/tree")
+            .backend(Arc::new(ListedBackend))
+            .build()
+            .unwrap();
+
+        assert_eq!(plan.stats.total_dirs, 2);
+        assert_eq!(plan.stats.total_files, 2);
+        assert_eq!(plan.stats.total_bytes, 30);
+        assert_eq!(plan.stats.conflicts, 1);
+        let file = plan.files().find(|item| item.size == 20).unwrap();
+        assert_eq!(file.destination, Path::new("remote:/dst/tree/sub/b.txt"));
+        assert_eq!(file.depth, 2);
+        assert!(plan.files().any(|item| item.has_conflict && item.size == 10));
+    }
+
+    #[test]
+    fn test_build_plan_excludes_through_backend() {
+        let plan = TransferPlanBuilder::new("remote:/dst")
+            .add_source("remote:/src/tree")
+            .backend(Arc::new(ListedBackend))
+            .exclude(["SUB"])
+            .build()
+            .unwrap();
+
+        assert_eq!((plan.stats.total_dirs, plan.stats.total_files), (1, 1));
+        assert_eq!(plan.stats.excluded, 1);
+        assert!(plan.items.iter().all(|item| !item.source.starts_with("remote:/src/tree/sub")));
+    }
+
     #[test]
     fn test_build_plan_source_not_found() {
         let temp = TempDir::new().unwrap();
@@ -548,6 +863,145 @@ mod tests {
         assert!(same_volume(&path1, &path2));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_build_plan_link_handling() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        std::os::unix::fs::symlink("subdir", source.join("link")).unwrap();
+        let dest_dir = temp.path().join("dest");
+        fs::create_dir(&dest_dir).unwrap();
+
+        let plan = TransferPlanBuilder::new(&dest_dir)
+            .add_source(&source)
+            .build()
+            .unwrap();
+        let links: Vec<_> = plan.items.iter().filter(|item| item.is_link()).collect();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].link_target, Some(PathBuf::from("subdir")));
+        assert_eq!(links[0].destination, dest_dir.join("source").join("link"));
+        assert_eq!(plan.stats.total_files, 4);
+        assert_eq!(plan.stats.total_bytes, 600);
+
+        let plan = TransferPlanBuilder::new(&dest_dir)
+            .add_source(&source)
+            .link_handling(LinkHandling::Follow)
+            .build()
+            .unwrap();
+        assert!(plan.items.iter().all(|item| !item.is_link()));
+        assert_eq!(plan.stats.total_files, 5); // subdir's files appear twice
+        assert_eq!(plan.stats.total_dirs, 5);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_plan_broken_link_source() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("dangling");
+        std::os::unix::fs::symlink("missing.txt", &source).unwrap();
+        let dest_dir = temp.path().join("dest");
+        fs::create_dir(&dest_dir).unwrap();
+
+        let plan = TransferPlanBuilder::new(&dest_dir)
+            .add_source(&source)
+            .build()
+            .unwrap();
+        assert_eq!(plan.items.len(), 1);
+        assert!(plan.items[0].is_link());
+
+        let result = TransferPlanBuilder::new(&dest_dir)
+            .add_source(&source)
+            .link_handling(LinkHandling::Follow)
+            .build();
+        assert!(matches!(result, Err(ZError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_build_plan_deep_tree() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source");
+        let mut deep = source.clone();
+        while deep.as_os_str().len() <= 300 {
+            deep.push("a_rather_long_folder_name_to_get_past_max_path");
+        }
+        fs::create_dir_all(extended_path(&deep)).unwrap();
+        fs::write(extended_path(deep.join("file.txt")), "hello").unwrap();
+        let dest_dir = temp.path().join("dest");
+        fs::create_dir(&dest_dir).unwrap();
+
+        let plan = TransferPlanBuilder::new(&dest_dir)
+            .add_source(&source)
+            .build()
+            .unwrap();
+
+        assert_eq!(plan.stats.total_files, 1);
+        assert_eq!(plan.stats.total_bytes, 5);
+        let file = plan.files().next().unwrap();
+        assert_eq!(file.source, deep.join("file.txt"));
+        let relative = deep.join("file.txt");
+        let relative = relative.strip_prefix(temp.path()).unwrap();
+        assert_eq!(file.destination, dest_dir.join(relative));
+    }
+
+    #[test]
+    fn test_build_plan_include_exclude() {
+        let temp = TempDir::new().unwrap();
+        let source = create_test_tree(&temp);
+        fs::write(source.join("scratch.tmp"), "x").unwrap();
+        let dest_dir = temp.path().join("dest");
+        let plan = |include: &[&str], exclude: &[&str]| {
+            TransferPlanBuilder::new(&dest_dir)
+                .add_source(&source)
+                .include(include.iter().copied())
+                .exclude(exclude.iter().copied())
+                .build()
+                .unwrap()
+        };
+
+        // A folder is left out with everything in it, and counted once
+        let excluded = plan(&[], &["nested", "*.tmp"]);
+        assert_eq!(excluded.stats.total_dirs, 2);
+        assert_eq!(excluded.stats.total_files, 2);
+        assert_eq!(excluded.stats.total_bytes, 300);
+        assert_eq!(excluded.stats.excluded, 2);
+
+        // Path patterns match below the source folder
+        let by_path = plan(&[], &["subdir/*/file3.txt"]);
+        assert_eq!((by_path.stats.total_files, by_path.stats.excluded), (3, 1));
+
+        // Includes pick files but keep walking folders
+        let included = plan(&["file3.*"], &[]);
+        assert_eq!(included.stats.total_dirs, 3);
+        assert_eq!(included.stats.total_files, 1);
+        assert_eq!(included.stats.excluded, 3);
+
+        let single = TransferPlanBuilder::new(&dest_dir)
+            .add_source(source.join("scratch.tmp"))
+            .exclude(["*.TMP"])
+            .build()
+            .unwrap();
+        assert!(single.items.is_empty());
+        assert_eq!(single.stats.excluded, 1);
+    }
+
+    #[test]
+    fn test_check_space() {
+        let dest = Path::new("E:\\Backup");
+        assert!(check_space(dest, 600, Some(600)).is_ok());
+        assert!(check_space(dest, 600, None).is_ok());
+
+        let err = check_space(dest, 600, Some(100)).unwrap_err();
+        assert!(matches!(
+            err,
+            ZError::InsufficientSpace {
+                required: 600,
+                available: 100,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("600 bytes needed, 100 bytes available"));
+    }
+
     #[test]
     fn test_transfer_stats() {
         let stats = TransferStats {
@@ -556,6 +1010,7 @@ mod tests {
             total_bytes: 1000,
             conflicts: 2,
             skipped: 0,
+            excluded: 0,
         };
 
         assert_eq!(stats.total_items(), 15);
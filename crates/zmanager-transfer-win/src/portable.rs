@@ -0,0 +1,232 @@
+//! Portable file copies through `std::fs`.
+//!
+//! [`copy_file_portable`] copies in plain chunks and checks for pausing,
+//! throttling and cancellation between them, the way `CopyFileExW`'s
+//! progress routine does on Windows. It works on every platform, and is
+//! what [`copy_file_throttled`](crate::copy_file_throttled) uses outside
+//! Windows. [`copy_stream`] runs the same loop between any reader and
+//! writer, for backends whose files aren't local.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use tracing::{debug, info, warn};
+use zmanager_core::{
+    extended_path, CancellationToken, PauseToken, SpeedEstimator, SpeedLimit, ZError, ZResult,
+};
+
+use crate::copy::{CopyProgress, ProgressCallback};
+
+/// Read and write size of portable copies.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Copy a file in chunks through `std::fs`.
+///
+/// Reports progress after each chunk, parks while paused, keeps to the speed
+/// limit and removes the partial destination when cancelled or failed. The
+/// modification time is carried over.
+pub fn copy_file_portable(
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+    overwrite: bool,
+    cancel_token: CancellationToken,
+    pause_token: PauseToken,
+    speed_limit: SpeedLimit,
+    progress_callback: Option<ProgressCallback>,
+) -> ZResult<u64> {
+    let source = source.as_ref();
+    let destination = destination.as_ref();
+
+    let meta = std::fs::metadata(extended_path(source)).map_err(|e| ZError::from_io(source, e))?;
+    if !meta.is_file() {
+        return Err(ZError::NotAFile {
+            path: source.to_path_buf(),
+        });
+    }
+    let total_bytes = meta.len();
+    if !overwrite && extended_path(destination).exists() {
+        return Err(ZError::AlreadyExists {
+            path: destination.to_path_buf(),
+        });
+    }
+    if let Some(parent) = destination.parent() {
+        let os_parent = extended_path(parent);
+        if !os_parent.exists() {
+            std::fs::create_dir_all(&os_parent).map_err(|e| ZError::io(parent, e))?;
+        }
+    }
+
+    debug!(
+        source = %source.display(),
+        destination = %destination.display(),
+        overwrite,
+        "Starting portable file copy"
+    );
+
+    let mut reader = File::open(extended_path(source)).map_err(|e| ZError::from_io(source, e))?;
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(extended_path(destination))
+        .map_err(|e| ZError::from_io(destination, e))?;
+
+    let result = copy_stream(
+        &mut reader,
+        &mut writer,
+        source,
+        destination,
+        &cancel_token,
+        &pause_token,
+        &speed_limit,
+        |bytes_copied, speed| {
+            if let Some(ref callback) = progress_callback {
+                callback(CopyProgress {
+                    total_bytes,
+                    bytes_copied,
+                    source: source.to_path_buf(),
+                    destination: destination.to_path_buf(),
+                    speed_bps: speed,
+                    eta_seconds: (speed > 0)
+                        .then(|| total_bytes.saturating_sub(bytes_copied) / speed),
+                });
+            }
+        },
+    );
+
+    match result {
+        Ok(bytes) => {
+            if let Ok(modified) = meta.modified() {
+                let _ = writer.set_modified(modified);
+            }
+            info!(
+                bytes,
+                source = %source.display(),
+                destination = %destination.display(),
+                "File copy completed"
+            );
+            Ok(bytes)
+        }
+        Err(e) => {
+            drop(writer);
+            if matches!(e, ZError::Cancelled) {
+                warn!(source = %source.display(), "File copy cancelled");
+            }
+            let _ = std::fs::remove_file(extended_path(destination));
+            Err(e)
+        }
+    }
+}
+
+/// Copy `reader` to `writer` chunk by chunk. Returns the bytes copied.
+///
+/// Checks for cancellation and pausing before each chunk and keeps to
+/// `speed_limit`. `on_progress` gets the bytes copied so far and the speed
+/// after each chunk; `source` and `destination` name the ends in errors.
+#[allow(clippy::too_many_arguments)]
+pub fn copy_stream(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    source: &Path,
+    destination: &Path,
+    cancel: &CancellationToken,
+    pause: &PauseToken,
+    speed_limit: &SpeedLimit,
+    mut on_progress: impl FnMut(u64, u64),
+) -> ZResult<u64> {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut copied = 0u64;
+    let mut speed = SpeedEstimator::new();
+
+    loop {
+        if cancel.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        if pause.is_paused() {
+            pause.wait_blocking(cancel)?;
+        }
+
+        let n = reader.read(&mut buffer).map_err(|e| ZError::io(source, e))?;
+        if n == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..n])
+            .map_err(|e| ZError::io(destination, e))?;
+        copied += n as u64;
+
+        speed_limit.throttle_blocking(n as u64, cancel)?;
+        let bytes_per_sec = speed.record(copied, Instant::now());
+        on_progress(copied, bytes_per_sec.unwrap_or(0));
+    }
+
+    writer.flush().map_err(|e| ZError::io(destination, e))?;
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_file_portable() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.bin");
+        let dest = temp.path().join("nested").join("dest.bin");
+        let content: Vec<u8> = (0..CHUNK_SIZE * 2 + 77).map(|i| (i % 253) as u8).collect();
+        fs::write(&source, &content).unwrap();
+
+        let reported = Arc::new(AtomicU64::new(0));
+        let seen = Arc::clone(&reported);
+        let callback: ProgressCallback = Box::new(move |p: CopyProgress| {
+            seen.store(p.bytes_copied, Ordering::Relaxed);
+        });
+        let bytes = copy_file_portable(
+            &source,
+            &dest,
+            false,
+            CancellationToken::new(),
+            PauseToken::new(),
+            SpeedLimit::new(),
+            Some(callback),
+        )
+        .unwrap();
+
+        assert_eq!(bytes, content.len() as u64);
+        assert_eq!(reported.load(Ordering::Relaxed), bytes);
+        assert_eq!(fs::read(&dest).unwrap(), content);
+        assert_eq!(
+            fs::metadata(&dest).unwrap().modified().unwrap(),
+            fs::metadata(&source).unwrap().modified().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cancelled_portable_copy_removes_destination() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.bin");
+        let dest = temp.path().join("dest.bin");
+        fs::write(&source, vec![1u8; CHUNK_SIZE]).unwrap();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = copy_file_portable(
+            &source,
+            &dest,
+            false,
+            token,
+            PauseToken::new(),
+            SpeedLimit::new(),
+            None,
+        );
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        assert!(!dest.exists());
+    }
+}
@@ -9,7 +9,9 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
-use zmanager_core::{JobId, ZError, ZResult};
+use zmanager_core::{extended_path, JobId, JobKind, ZError, ZResult};
+
+use crate::plan::{TransferItem, TransferPlan, TransferStats};
 
 /// Status of an individual transfer item.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,10 +19,14 @@ use zmanager_core::{JobId, ZError, ZResult};
 pub enum TransferStatus {
     /// Item transferred successfully.
     Success,
+    /// Item transferred and its checksum matched the source.
+    Verified,
     /// Item was skipped (e.g., conflict policy).
     Skipped,
     /// Item transfer failed.
     Failed,
+    /// Item was copied, but its checksum didn't match the source.
+    VerifyFailed,
 }
 
 impl TransferStatus {
@@ -28,8 +34,10 @@ impl TransferStatus {
     pub fn label(&self) -> &'static str {
         match self {
             Self::Success => "Success",
+            Self::Verified => "Verified",
             Self::Skipped => "Skipped",
             Self::Failed => "Failed",
+            Self::VerifyFailed => "Verify failed",
         }
     }
 
@@ -37,10 +45,22 @@ impl TransferStatus {
     pub fn symbol(&self) -> &'static str {
         match self {
             Self::Success => "✓",
+            Self::Verified => "✓",
             Self::Skipped => "○",
             Self::Failed => "✗",
+            Self::VerifyFailed => "✗",
         }
     }
+
+    /// Check if the status counts as a success.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success | Self::Verified)
+    }
+
+    /// Check if the status counts as a failure.
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed | Self::VerifyFailed)
+    }
 }
 
 /// Detailed result for a single transferred item.
@@ -91,6 +111,28 @@ impl TransferItemResult {
         }
     }
 
+    /// Create a result for a file whose checksum matched the source.
+    pub fn verified(source: PathBuf, destination: PathBuf, size_bytes: u64) -> Self {
+        Self {
+            status: TransferStatus::Verified,
+            ..Self::success(source, destination, size_bytes)
+        }
+    }
+
+    /// Create a result for a file whose checksum didn't match the source.
+    pub fn verify_failed(
+        source: PathBuf,
+        destination: PathBuf,
+        size_bytes: u64,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: TransferStatus::VerifyFailed,
+            reason: Some(error.into()),
+            ..Self::success(source, destination, size_bytes)
+        }
+    }
+
     /// Create a skipped result.
     pub fn skipped(source: PathBuf, destination: PathBuf, reason: impl Into<String>) -> Self {
         Self {
@@ -125,12 +167,12 @@ impl TransferItemResult {
 
     /// Check if the transfer succeeded.
     pub fn is_success(&self) -> bool {
-        self.status == TransferStatus::Success
+        self.status.is_success()
     }
 
     /// Check if the transfer failed.
     pub fn is_failed(&self) -> bool {
-        self.status == TransferStatus::Failed
+        self.status.is_failed()
     }
 }
 
@@ -145,6 +187,13 @@ pub struct TransferSummary {
     pub skipped: usize,
     /// Number of items that failed.
     pub failed: usize,
+    /// Number of files whose checksum matched the source.
+    #[serde(default)]
+    pub verified: usize,
+    /// Number of files and folders left out by include and exclude
+    /// patterns. Not counted in `total_items`.
+    #[serde(default)]
+    pub excluded: usize,
     /// Total bytes transferred.
     pub bytes_transferred: u64,
     /// Total duration in milliseconds.
@@ -201,7 +250,7 @@ impl TransferSummary {
 pub struct DetailedTransferReport {
     /// Unique identifier for the job.
     pub job_id: JobId,
-    /// Type of operation (copy/move).
+    /// Type of operation (copy/move/delete).
     pub operation: TransferOperation,
     /// When the transfer started.
     #[serde(with = "system_time_serde")]
@@ -223,6 +272,9 @@ pub struct DetailedTransferReport {
 pub enum TransferOperation {
     Copy,
     Move,
+    /// Items deleted, to the Recycle Bin or permanently; they have no
+    /// destination.
+    Delete,
 }
 
 impl TransferOperation {
@@ -231,6 +283,7 @@ impl TransferOperation {
         match self {
             Self::Copy => "Copy",
             Self::Move => "Move",
+            Self::Delete => "Delete",
         }
     }
 }
@@ -243,12 +296,12 @@ impl DetailedTransferReport {
 
     /// Get all failed items.
     pub fn failed_items(&self) -> impl Iterator<Item = &TransferItemResult> {
-        self.items.iter().filter(|i| i.status == TransferStatus::Failed)
+        self.items.iter().filter(|i| i.is_failed())
     }
 
     /// Get all successful items.
     pub fn successful_items(&self) -> impl Iterator<Item = &TransferItemResult> {
-        self.items.iter().filter(|i| i.status == TransferStatus::Success)
+        self.items.iter().filter(|i| i.is_success())
     }
 
     /// Get all skipped items.
@@ -256,6 +309,125 @@ impl DetailedTransferReport {
         self.items.iter().filter(|i| i.status == TransferStatus::Skipped)
     }
 
+    /// Count the items again after their statuses changed, keeping the
+    /// duration and the number of excluded items.
+    pub fn refresh_summary(&mut self) {
+        let mut summary = TransferSummary {
+            total_items: self.items.len(),
+            excluded: self.summary.excluded,
+            duration_ms: self.summary.duration_ms,
+            ..Default::default()
+        };
+
+        for item in &self.items {
+            match item.status {
+                TransferStatus::Success | TransferStatus::Verified => {
+                    summary.succeeded += 1;
+                    if item.status == TransferStatus::Verified {
+                        summary.verified += 1;
+                    }
+                    summary.bytes_transferred += item.size_bytes;
+                    if item.is_directory {
+                        summary.directories_created += 1;
+                    } else {
+                        summary.files_copied += 1;
+                    }
+                }
+                TransferStatus::Skipped => summary.skipped += 1,
+                TransferStatus::Failed | TransferStatus::VerifyFailed => summary.failed += 1,
+            }
+        }
+        self.summary = summary;
+    }
+
+    /// Rebuild the plan of a copy or move for just its failed items.
+    ///
+    /// Items keep their destinations, and file sizes are read from the
+    /// sources again. None for deletes and when nothing failed.
+    pub fn retry_plan(&self) -> Option<TransferPlan> {
+        if self.operation == TransferOperation::Delete {
+            return None;
+        }
+        let mut items: Vec<TransferItem> = self
+            .failed_items()
+            .map(|item| {
+                let size = match extended_path(&item.source).metadata() {
+                    Ok(meta) if !item.is_directory => meta.len(),
+                    _ => item.size_bytes,
+                };
+                let source = item.source.clone();
+                let destination = item.destination.clone();
+                TransferItem::new(source, destination, item.is_directory, size, 0)
+            })
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+        // Directories are created before files are copied into them
+        items.sort_by_key(|item| !item.is_dir);
+
+        let stats = TransferStats {
+            total_files: items.iter().filter(|item| !item.is_dir).count(),
+            total_dirs: items.iter().filter(|item| item.is_dir).count(),
+            total_bytes: items.iter().map(|item| item.size).sum(),
+            conflicts: items.iter().filter(|item| item.has_conflict).count(),
+            skipped: 0,
+            excluded: 0,
+        };
+        let destination_root = common_ancestor(
+            items
+                .iter()
+                .map(|item| item.destination.parent().unwrap_or(&item.destination)),
+        );
+        Some(TransferPlan {
+            source_roots: items.iter().map(|item| item.source.clone()).collect(),
+            destination_root,
+            is_move: self.operation == TransferOperation::Move,
+            items,
+            stats,
+        })
+    }
+
+    /// Jobs that redo just the failed items.
+    ///
+    /// The items of the [`retry_plan`](Self::retry_plan) need one job per
+    /// destination folder; failed deletes are retried as a single Recycle
+    /// Bin delete.
+    pub fn retry_jobs(&self) -> Vec<JobKind> {
+        if self.operation == TransferOperation::Delete {
+            let paths: Vec<PathBuf> = self.failed_items().map(|item| item.source.clone()).collect();
+            if paths.is_empty() {
+                return Vec::new();
+            }
+            return vec![JobKind::Delete { paths }];
+        }
+        let Some(plan) = self.retry_plan() else {
+            return Vec::new();
+        };
+
+        let mut groups: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+        for item in &plan.items {
+            let folder = item.destination.parent().unwrap_or(&item.destination);
+            match groups.iter_mut().find(|(destination, _)| destination == folder) {
+                Some((_, sources)) => sources.push(item.source.clone()),
+                None => groups.push((folder.to_path_buf(), vec![item.source.clone()])),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(destination, sources)| match self.operation {
+                TransferOperation::Move => JobKind::Move {
+                    sources,
+                    destination,
+                },
+                _ => JobKind::Copy {
+                    sources,
+                    destination,
+                },
+            })
+            .collect()
+    }
+
     /// Export the report to JSON.
     pub fn to_json(&self) -> ZResult<String> {
         serde_json::to_string_pretty(self).map_err(|e| ZError::Internal {
@@ -298,6 +470,12 @@ impl DetailedTransferReport {
         ));
         out.push_str(&format!("Skipped: {}\n", self.summary.skipped));
         out.push_str(&format!("Failed: {}\n", self.summary.failed));
+        if self.summary.verified > 0 {
+            out.push_str(&format!("Verified: {}\n", self.summary.verified));
+        }
+        if self.summary.excluded > 0 {
+            out.push_str(&format!("Excluded: {}\n", self.summary.excluded));
+        }
         out.push_str(&format!(
             "Bytes transferred: {}\n",
             format_bytes(self.summary.bytes_transferred)
@@ -317,9 +495,8 @@ impl DetailedTransferReport {
             out.push_str("\n--- Failed Items ---\n");
             for item in failed {
                 out.push_str(&format!(
-                    "✗ {} → {}\n  Error: {}\n",
-                    item.source.display(),
-                    item.destination.display(),
+                    "✗ {}\n  Error: {}\n",
+                    self.item_paths(item),
                     item.reason.as_deref().unwrap_or("Unknown error")
                 ));
             }
@@ -331,9 +508,8 @@ impl DetailedTransferReport {
             out.push_str("\n--- Skipped Items ---\n");
             for item in skipped {
                 out.push_str(&format!(
-                    "○ {} → {}\n  Reason: {}\n",
-                    item.source.display(),
-                    item.destination.display(),
+                    "○ {}\n  Reason: {}\n",
+                    self.item_paths(item),
                     item.reason.as_deref().unwrap_or("Unknown reason")
                 ));
             }
@@ -342,6 +518,15 @@ impl DetailedTransferReport {
         out
     }
 
+    /// Describe an item's paths for the text export; deleted items have
+    /// only a source.
+    fn item_paths(&self, item: &TransferItemResult) -> String {
+        match self.operation {
+            TransferOperation::Delete => item.source.display().to_string(),
+            _ => format!("{} → {}", item.source.display(), item.destination.display()),
+        }
+    }
+
     /// Export the report to a text file.
     pub fn save_text(&self, path: &Path) -> ZResult<()> {
         let mut file = File::create(path).map_err(|e| ZError::io(path, e))?;
@@ -350,12 +535,23 @@ impl DetailedTransferReport {
     }
 }
 
+/// Deepest folder containing all of `paths`.
+fn common_ancestor<'a>(mut paths: impl Iterator<Item = &'a Path>) -> PathBuf {
+    let mut common = paths.next().map(Path::to_path_buf).unwrap_or_default();
+    for path in paths {
+        while !path.starts_with(&common) && common.pop() {}
+    }
+    common
+}
+
 /// Builder for constructing transfer reports.
+#[derive(Debug, Clone)]
 pub struct ReportBuilder {
     job_id: JobId,
     operation: TransferOperation,
     started_at: SystemTime,
     items: Vec<TransferItemResult>,
+    excluded: usize,
     was_cancelled: bool,
 }
 
@@ -367,6 +563,7 @@ impl ReportBuilder {
             operation,
             started_at: SystemTime::now(),
             items: Vec::new(),
+            excluded: 0,
             was_cancelled: false,
         }
     }
@@ -376,49 +573,53 @@ impl ReportBuilder {
         self.items.push(item);
     }
 
+    /// Set how many files and folders the transfer's patterns left out.
+    pub fn set_excluded(&mut self, excluded: usize) {
+        self.excluded = excluded;
+    }
+
     /// Mark the operation as cancelled.
     pub fn set_cancelled(&mut self, cancelled: bool) {
         self.was_cancelled = cancelled;
     }
 
+    /// Get the operation being reported.
+    pub fn operation(&self) -> TransferOperation {
+        self.operation
+    }
+
+    /// Get the item results added so far.
+    pub fn items(&self) -> &[TransferItemResult] {
+        &self.items
+    }
+
     /// Build the final report.
     pub fn build(self) -> DetailedTransferReport {
-        let completed_at = SystemTime::now();
+        self.build_at(SystemTime::now())
+    }
+
+    /// Build the final report of an operation that completed at
+    /// `completed_at`.
+    pub fn build_at(self, completed_at: SystemTime) -> DetailedTransferReport {
         let duration = completed_at
             .duration_since(self.started_at)
             .unwrap_or_default();
 
-        let mut summary = TransferSummary {
-            total_items: self.items.len(),
-            duration_ms: duration.as_millis() as u64,
-            ..Default::default()
-        };
-
-        for item in &self.items {
-            match item.status {
-                TransferStatus::Success => {
-                    summary.succeeded += 1;
-                    summary.bytes_transferred += item.size_bytes;
-                    if item.is_directory {
-                        summary.directories_created += 1;
-                    } else {
-                        summary.files_copied += 1;
-                    }
-                }
-                TransferStatus::Skipped => summary.skipped += 1,
-                TransferStatus::Failed => summary.failed += 1,
-            }
-        }
-
-        DetailedTransferReport {
+        let mut report = DetailedTransferReport {
             job_id: self.job_id,
             operation: self.operation,
             started_at: self.started_at,
             completed_at,
-            summary,
+            summary: TransferSummary {
+                excluded: self.excluded,
+                duration_ms: duration.as_millis() as u64,
+                ..Default::default()
+            },
             items: self.items,
             was_cancelled: self.was_cancelled,
-        }
+        };
+        report.refresh_summary();
+        report
     }
 }
 
@@ -578,6 +779,8 @@ mod tests {
         assert_eq!(TransferStatus::Success.label(), "Success");
         assert_eq!(TransferStatus::Skipped.label(), "Skipped");
         assert_eq!(TransferStatus::Failed.label(), "Failed");
+        assert_eq!(TransferStatus::Verified.label(), "Verified");
+        assert_eq!(TransferStatus::VerifyFailed.label(), "Verify failed");
     }
 
     #[test]
@@ -625,6 +828,70 @@ mod tests {
         assert_eq!(result.reason.as_deref(), Some("Access denied"));
     }
 
+    #[test]
+    fn test_retry_jobs_group_failed_items() {
+        let mut builder = ReportBuilder::new(JobId::new(), TransferOperation::Move);
+        let item = |src: &str, dst: &str| (PathBuf::from(src), PathBuf::from(dst));
+        let (src, dst) = item("a/1.txt", "out/1.txt");
+        builder.add_item(TransferItemResult::failed(src, dst, "Locked"));
+        let (src, dst) = item("a/2.txt", "out/2.txt");
+        builder.add_item(TransferItemResult::success(src, dst, 1));
+        let (src, dst) = item("a/sub/3.txt", "out/sub/3.txt");
+        builder.add_item(TransferItemResult::failed(src, dst, "Locked"));
+        let (src, dst) = item("a/4.txt", "out/4.txt");
+        builder.add_item(TransferItemResult::failed(src, dst, "Locked"));
+
+        let jobs = builder.build().retry_jobs();
+        assert_eq!(jobs.len(), 2);
+        match &jobs[0] {
+            JobKind::Move {
+                sources,
+                destination,
+            } => {
+                assert_eq!(sources, &[PathBuf::from("a/1.txt"), PathBuf::from("a/4.txt")]);
+                assert_eq!(destination, &PathBuf::from("out"));
+            }
+            other => panic!("unexpected job {other:?}"),
+        }
+
+        let report = ReportBuilder::new(JobId::new(), TransferOperation::Copy).build();
+        assert!(report.retry_jobs().is_empty());
+        assert!(report.retry_plan().is_none());
+    }
+
+    #[test]
+    fn test_retry_plan_keeps_failed_items() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let source = temp.path().join("src");
+        std::fs::create_dir_all(source.join("sub")).unwrap();
+        std::fs::write(source.join("big.bin"), vec![0u8; 64]).unwrap();
+        let dest = temp.path().join("dest");
+
+        let mut builder = ReportBuilder::new(JobId::new(), TransferOperation::Copy);
+        builder.add_item(TransferItemResult::success(
+            source.join("ok.txt"),
+            dest.join("ok.txt"),
+            3,
+        ));
+        builder.add_item(TransferItemResult::failed(
+            source.join("big.bin"),
+            dest.join("big.bin"),
+            "Disk full",
+        ));
+        let mut failed_dir =
+            TransferItemResult::failed(source.join("sub"), dest.join("sub"), "Access denied");
+        failed_dir.is_directory = true;
+        builder.add_item(failed_dir);
+
+        let plan = builder.build().retry_plan().unwrap();
+        assert!(!plan.is_move);
+        assert_eq!(plan.items.len(), 2);
+        assert!(plan.items[0].is_dir);
+        assert_eq!(plan.items[1].size, 64);
+        assert_eq!(plan.stats.total_bytes, 64);
+        assert_eq!(plan.destination_root, dest);
+    }
+
     #[test]
     fn test_summary_percentage() {
         let summary = TransferSummary {
@@ -705,6 +972,31 @@ mod tests {
         assert_eq!(report.summary.bytes_transferred, 100);
     }
 
+    #[test]
+    fn test_report_builder_verification() {
+        let mut builder = ReportBuilder::new(JobId::new(), TransferOperation::Copy);
+        builder.add_item(TransferItemResult::verified(
+            PathBuf::from("a.txt"),
+            PathBuf::from("b.txt"),
+            100,
+        ));
+        builder.add_item(TransferItemResult::verify_failed(
+            PathBuf::from("c.txt"),
+            PathBuf::from("d.txt"),
+            50,
+            "Checksum mismatch",
+        ));
+
+        let report = builder.build();
+
+        assert_eq!(report.summary.succeeded, 1);
+        assert_eq!(report.summary.verified, 1);
+        assert_eq!(report.summary.failed, 1);
+        assert_eq!(report.summary.bytes_transferred, 100);
+        assert_eq!(report.failed_items().count(), 1);
+        assert!(report.to_text().contains("Verified: 1"));
+    }
+
     #[test]
     fn test_report_json_export() {
         let mut builder = ReportBuilder::new(JobId::new(), TransferOperation::Move);
@@ -733,13 +1025,19 @@ mod tests {
             PathBuf::from("fail"),
             "Permission denied",
         ));
-        let report = builder.build();
+        let report = builder.clone().build();
 
         let text = report.to_text();
         assert!(text.contains("=== Copy Report ==="));
         assert!(text.contains("Succeeded: 1"));
         assert!(text.contains("Failed: 1"));
         assert!(text.contains("Permission denied"));
+        assert!(!text.contains("Excluded"));
+
+        builder.set_excluded(3);
+        let report = builder.build();
+        assert_eq!(report.summary.total_items, 2);
+        assert!(report.to_text().contains("Excluded: 3"));
     }
 
     #[test]
@@ -0,0 +1,487 @@
+//! Resumable file copies.
+//!
+//! [`copy_file_resume`] copies in plain chunks instead of through
+//! `CopyFileExW`, so an interrupted copy can continue where it stopped. While
+//! it runs, a `.zmpart` sidecar next to the destination records how much of
+//! the file is in place and a hash of that prefix. A cancelled or failed copy
+//! keeps the partial file and its sidecar, and a crash leaves the last
+//! checkpoint behind. The next copy of the same source checks the prefix
+//! against the hash and picks up from the recorded offset.
+//!
+//! The copies a crash left behind are found through the transfer journal,
+//! with [`TransferJournal::partial_copies`](crate::TransferJournal::partial_copies),
+//! and resumed by running the transfer again or removed with
+//! [`discard_partial_copy`], along with the other files the transfer left
+//! part written.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use xxhash_rust::xxh3::Xxh3;
+use zmanager_core::{
+    extended_path, CancellationToken, PauseToken, SpeedEstimator, SpeedLimit, ZError, ZResult,
+};
+
+use crate::copy::{CopyProgress, ProgressCallback};
+
+/// Extension of the sidecar written next to a partial copy.
+pub const PART_EXTENSION: &str = "zmpart";
+
+/// Read and write size for resumable copies.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Bytes copied between sidecar checkpoints.
+const CHECKPOINT_INTERVAL: u64 = 16 * 1024 * 1024;
+
+/// State of an interrupted copy, as stored in its `.zmpart` sidecar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialCopy {
+    /// File being copied.
+    pub source: PathBuf,
+    /// Size of the source when the copy started.
+    pub source_size: u64,
+    /// Modification time of the source when the copy started.
+    pub source_modified: Option<SystemTime>,
+    /// Bytes at the start of the destination known to be in place.
+    pub offset: u64,
+    /// XXH3 hash of the first `offset` bytes, as lowercase hex.
+    pub prefix_hash: String,
+}
+
+impl PartialCopy {
+    /// Get the sidecar path for a destination file.
+    pub fn sidecar_path(destination: impl AsRef<Path>) -> PathBuf {
+        let mut path = destination.as_ref().as_os_str().to_owned();
+        path.push(".");
+        path.push(PART_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Load the sidecar for a destination file, if there is one.
+    ///
+    /// An unreadable sidecar is treated as missing, so the copy starts over.
+    pub fn load(destination: impl AsRef<Path>) -> Option<Self> {
+        let path = Self::sidecar_path(destination);
+        let content = std::fs::read(&path).ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(partial) => Some(partial),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable copy sidecar");
+                None
+            }
+        }
+    }
+
+    /// Write the sidecar for a destination file.
+    pub fn save(&self, destination: impl AsRef<Path>) -> ZResult<()> {
+        let path = Self::sidecar_path(destination);
+        let content = serde_json::to_vec_pretty(self).map_err(|e| ZError::Internal {
+            message: format!("Failed to serialize copy state: {e}"),
+        })?;
+        std::fs::write(&path, content).map_err(|e| ZError::io(&path, e))
+    }
+
+    /// Check if this state belongs to a copy of `source` as it is now.
+    pub fn matches_source(&self, source: impl AsRef<Path>) -> bool {
+        let source = source.as_ref();
+        let Ok(meta) = std::fs::metadata(source) else {
+            return false;
+        };
+        self.source == source
+            && self.source_size == meta.len()
+            && self.source_modified == meta.modified().ok()
+            && self.offset <= self.source_size
+    }
+}
+
+/// Remove an interrupted copy: the partial destination file and its sidecar.
+pub fn discard_partial_copy(destination: impl AsRef<Path>) -> ZResult<()> {
+    let destination = destination.as_ref();
+    for path in [destination.to_path_buf(), PartialCopy::sidecar_path(destination)] {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(ZError::io(&path, e)),
+        }
+    }
+    Ok(())
+}
+
+/// Copy a file so that an interrupted copy can be resumed.
+///
+/// If `destination` has a sidecar from an earlier copy of the same,
+/// unchanged `source` and the partial file still matches its hash, copying
+/// continues from the saved offset; otherwise it starts over. A partial copy
+/// of `source` doesn't count as an existing destination for `overwrite`.
+///
+/// On cancellation or an I/O error the partial file and its sidecar are kept.
+/// On success the sidecar is removed and the source's modification time is
+/// copied over.
+///
+/// Progress reports count the resumed prefix as already copied.
+pub fn copy_file_resume(
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+    overwrite: bool,
+    cancel_token: CancellationToken,
+    pause_token: PauseToken,
+    speed_limit: SpeedLimit,
+    progress_callback: Option<ProgressCallback>,
+) -> ZResult<u64> {
+    let source = source.as_ref();
+    let destination = destination.as_ref();
+
+    let meta = std::fs::metadata(extended_path(source)).map_err(|e| ZError::from_io(source, e))?;
+    if !meta.is_file() {
+        return Err(ZError::NotAFile {
+            path: source.to_path_buf(),
+        });
+    }
+    let total_bytes = meta.len();
+
+    let partial = PartialCopy::load(destination).filter(|partial| partial.source == source);
+    if partial.is_none() && !overwrite && destination.exists() {
+        return Err(ZError::AlreadyExists {
+            path: destination.to_path_buf(),
+        });
+    }
+
+    if let Some(parent) = destination.parent() {
+        let os_parent = extended_path(parent);
+        if !os_parent.exists() {
+            std::fs::create_dir_all(&os_parent).map_err(|e| ZError::io(parent, e))?;
+        }
+    }
+
+    let (offset, hasher) = partial
+        .filter(|partial| partial.matches_source(source))
+        .and_then(|partial| verify_prefix(destination, &partial))
+        .unwrap_or_default();
+    if offset > 0 {
+        info!(
+            offset,
+            source = %source.display(),
+            destination = %destination.display(),
+            "Resuming file copy"
+        );
+    } else {
+        debug!(
+            source = %source.display(),
+            destination = %destination.display(),
+            "Starting resumable file copy"
+        );
+    }
+
+    let mut copy = ResumableCopy {
+        source,
+        destination,
+        state: PartialCopy {
+            source: source.to_path_buf(),
+            source_size: total_bytes,
+            source_modified: meta.modified().ok(),
+            offset,
+            prefix_hash: String::new(),
+        },
+        hasher,
+    };
+
+    let result = copy.run(&cancel_token, &pause_token, &speed_limit, |bytes_copied, speed| {
+        if let Some(ref callback) = progress_callback {
+            callback(CopyProgress {
+                total_bytes,
+                bytes_copied,
+                source: source.to_path_buf(),
+                destination: destination.to_path_buf(),
+                speed_bps: speed,
+                eta_seconds: (speed > 0).then(|| total_bytes.saturating_sub(bytes_copied) / speed),
+            });
+        }
+    });
+
+    match result {
+        Ok(()) => {
+            let _ = std::fs::remove_file(PartialCopy::sidecar_path(destination));
+            info!(
+                bytes = total_bytes,
+                source = %source.display(),
+                destination = %destination.display(),
+                "File copy completed"
+            );
+            Ok(total_bytes)
+        }
+        Err(e) => {
+            // Whatever made it to disk can be picked up next time
+            if destination.exists() {
+                if let Err(save_error) = copy.checkpoint() {
+                    warn!(error = %save_error, "Failed to save copy state");
+                }
+            }
+            if matches!(e, ZError::Cancelled) {
+                warn!(
+                    source = %source.display(),
+                    offset = copy.state.offset,
+                    "File copy cancelled, partial copy kept"
+                );
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Check a partial copy against its saved hash.
+///
+/// Returns the offset to resume from and the hasher state over the prefix,
+/// or `None` if the prefix is missing or doesn't match.
+fn verify_prefix(destination: &Path, partial: &PartialCopy) -> Option<(u64, Xxh3)> {
+    let mut file = File::open(extended_path(destination)).ok()?;
+    if file.metadata().ok()?.len() < partial.offset {
+        return None;
+    }
+
+    let mut hasher = Xxh3::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut remaining = partial.offset;
+    while remaining > 0 {
+        let want = remaining.min(CHUNK_SIZE as u64) as usize;
+        file.read_exact(&mut buf[..want]).ok()?;
+        hasher.update(&buf[..want]);
+        remaining -= want as u64;
+    }
+
+    if format!("{:016x}", hasher.digest()) != partial.prefix_hash {
+        warn!(
+            destination = %destination.display(),
+            "Partial copy doesn't match its saved state, starting over"
+        );
+        return None;
+    }
+    Some((partial.offset, hasher))
+}
+
+/// A resumable copy in progress.
+struct ResumableCopy<'a> {
+    source: &'a Path,
+    destination: &'a Path,
+    /// State as of the last byte written.
+    state: PartialCopy,
+    /// Hash of everything written so far.
+    hasher: Xxh3,
+}
+
+impl ResumableCopy<'_> {
+    /// Copy from the current offset to the end of the source.
+    fn run(
+        &mut self,
+        cancel: &CancellationToken,
+        pause: &PauseToken,
+        speed_limit: &SpeedLimit,
+        mut on_progress: impl FnMut(u64, u64),
+    ) -> ZResult<()> {
+        let (source, destination) = (self.source, self.destination);
+        let mut reader =
+            File::open(extended_path(source)).map_err(|e| ZError::from_io(source, e))?;
+        reader
+            .seek(SeekFrom::Start(self.state.offset))
+            .map_err(|e| ZError::io(source, e))?;
+
+        let mut writer = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(extended_path(destination))
+            .map_err(|e| ZError::from_io(destination, e))?;
+        // Drop anything past the verified prefix
+        writer
+            .set_len(self.state.offset)
+            .and_then(|()| writer.seek(SeekFrom::Start(self.state.offset)))
+            .map_err(|e| ZError::io(destination, e))?;
+        self.checkpoint()?;
+
+        let mut speed = SpeedEstimator::new();
+        let mut last_checkpoint = self.state.offset;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+
+        loop {
+            if cancel.is_cancelled() {
+                return Err(ZError::Cancelled);
+            }
+            if pause.is_paused() {
+                pause.wait_blocking(cancel)?;
+            }
+
+            let n = reader.read(&mut buf).map_err(|e| ZError::io(source, e))?;
+            if n == 0 {
+                break;
+            }
+            writer
+                .write_all(&buf[..n])
+                .map_err(|e| ZError::io(destination, e))?;
+            self.hasher.update(&buf[..n]);
+            self.state.offset += n as u64;
+
+            speed_limit.throttle_blocking(n as u64, cancel)?;
+
+            if self.state.offset - last_checkpoint >= CHECKPOINT_INTERVAL {
+                // The sidecar must never claim data that isn't on disk yet
+                writer.sync_data().map_err(|e| ZError::io(destination, e))?;
+                self.checkpoint()?;
+                last_checkpoint = self.state.offset;
+            }
+
+            let bytes_per_sec = speed.record(self.state.offset, Instant::now());
+            on_progress(self.state.offset, bytes_per_sec.unwrap_or(0));
+        }
+
+        writer.flush().map_err(|e| ZError::io(destination, e))?;
+        if let Some(modified) = self.state.source_modified {
+            let _ = writer.set_modified(modified);
+        }
+        Ok(())
+    }
+
+    /// Save the current offset and prefix hash to the sidecar.
+    fn checkpoint(&mut self) -> ZResult<()> {
+        self.state.prefix_hash = format!("{:016x}", self.hasher.digest());
+        self.state.save(self.destination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A source spanning a few chunks, with varied content.
+    fn create_source(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("source.bin");
+        let content: Vec<u8> = (0..3 * CHUNK_SIZE + 123).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Copy until the first chunk is written, then cancel.
+    fn interrupt_copy(source: &Path, dest: &Path) {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let callback: ProgressCallback = Box::new(move |_| cancel.cancel());
+        let result = copy_file_resume(
+            source,
+            dest,
+            false,
+            token,
+            PauseToken::new(),
+            SpeedLimit::new(),
+            Some(callback),
+        );
+        assert!(matches!(result, Err(ZError::Cancelled)));
+    }
+
+    fn resume(source: &Path, dest: &Path, progress: Option<ProgressCallback>) -> ZResult<u64> {
+        copy_file_resume(
+            source,
+            dest,
+            false,
+            CancellationToken::new(),
+            PauseToken::new(),
+            SpeedLimit::new(),
+            progress,
+        )
+    }
+
+    #[test]
+    fn test_cancelled_copy_keeps_sidecar() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+
+        interrupt_copy(&source, &dest);
+
+        let partial = PartialCopy::load(&dest).unwrap();
+        assert_eq!(partial.offset, CHUNK_SIZE as u64);
+        assert_eq!(partial.source, source);
+        assert!(partial.matches_source(&source));
+        assert_eq!(fs::metadata(&dest).unwrap().len(), CHUNK_SIZE as u64);
+    }
+
+    #[test]
+    fn test_resume_continues_from_offset() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+        interrupt_copy(&source, &dest);
+
+        let first = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let seen = first.clone();
+        let callback: ProgressCallback = Box::new(move |p| {
+            seen.lock().unwrap().get_or_insert(p.bytes_copied);
+        });
+        let bytes = resume(&source, &dest, Some(callback)).unwrap();
+
+        assert_eq!(bytes, fs::metadata(&source).unwrap().len());
+        assert_eq!(fs::read(&source).unwrap(), fs::read(&dest).unwrap());
+        assert!(!PartialCopy::sidecar_path(&dest).exists());
+        // The first chunk wasn't copied again
+        assert_eq!(*first.lock().unwrap(), Some(2 * CHUNK_SIZE as u64));
+    }
+
+    #[test]
+    fn test_corrupt_prefix_starts_over() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+        interrupt_copy(&source, &dest);
+
+        let mut partial = fs::read(&dest).unwrap();
+        partial[10] ^= 0xff;
+        fs::write(&dest, partial).unwrap();
+
+        resume(&source, &dest, None).unwrap();
+        assert_eq!(fs::read(&source).unwrap(), fs::read(&dest).unwrap());
+    }
+
+    #[test]
+    fn test_changed_source_starts_over() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+        interrupt_copy(&source, &dest);
+
+        fs::write(&source, b"replaced").unwrap();
+        assert!(!PartialCopy::load(&dest).unwrap().matches_source(&source));
+
+        assert_eq!(resume(&source, &dest, None).unwrap(), 8);
+        assert_eq!(fs::read(&dest).unwrap(), b"replaced");
+    }
+
+    #[test]
+    fn test_other_file_is_a_conflict() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+        fs::write(&dest, b"unrelated").unwrap();
+
+        let result = resume(&source, &dest, None);
+        assert!(matches!(result, Err(ZError::AlreadyExists { .. })));
+        assert_eq!(fs::read(&dest).unwrap(), b"unrelated");
+    }
+
+    #[test]
+    fn test_discard_partial_copy() {
+        let temp = TempDir::new().unwrap();
+        let source = create_source(&temp);
+        let dest = temp.path().join("dest.bin");
+        interrupt_copy(&source, &dest);
+
+        discard_partial_copy(&dest).unwrap();
+        assert!(!dest.exists());
+        assert!(!PartialCopy::sidecar_path(&dest).exists());
+
+        // Nothing left to discard is fine
+        discard_partial_copy(&dest).unwrap();
+    }
+}
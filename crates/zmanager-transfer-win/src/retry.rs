@@ -0,0 +1,284 @@
+//! Retry policy for transient I/O errors.
+//!
+//! Network shares and files held open by other processes (antivirus scanners,
+//! indexers) regularly produce errors that go away on their own after a short
+//! wait. This module classifies those errors and drives a bounded, cancellable
+//! exponential backoff around a copy attempt.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tracing::warn;
+use zmanager_core::{CancellationToken, ZError, ZResult};
+
+/// `ERROR_SHARING_VIOLATION` as an HRESULT.
+const HRESULT_SHARING_VIOLATION: u32 = 0x80070020;
+/// `ERROR_LOCK_VIOLATION` as an HRESULT.
+const HRESULT_LOCK_VIOLATION: u32 = 0x80070021;
+/// `ERROR_UNEXP_NET_ERR` as an HRESULT.
+const HRESULT_UNEXPECTED_NETWORK_ERROR: u32 = 0x8007003B;
+/// `ERROR_NETNAME_DELETED` as an HRESULT.
+const HRESULT_NETNAME_DELETED: u32 = 0x80070040;
+/// `ERROR_SEM_TIMEOUT` as an HRESULT.
+const HRESULT_SEM_TIMEOUT: u32 = 0x80070079;
+/// `ERROR_NETWORK_UNREACHABLE` as an HRESULT.
+const HRESULT_NETWORK_UNREACHABLE: u32 = 0x800704CF;
+
+/// How often a backoff wait checks the cancellation token.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Policy controlling how transient copy failures are retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay to wait after the given failed attempt (1-based), doubling each time.
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Run `operation` until it succeeds, fails permanently or runs out of attempts.
+    ///
+    /// On success, returns the value together with the number of retries that
+    /// were needed. On failure, returns the last error and the retry count.
+    /// Cancellation during a backoff wait returns [`ZError::Cancelled`] immediately.
+    pub async fn run<T, F, Fut>(
+        &self,
+        cancel_token: &CancellationToken,
+        mut operation: F,
+    ) -> Result<(T, u32), (ZError, u32)>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ZResult<T>>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Ok(value) => return Ok((value, attempt - 1)),
+                Err(e) if attempt < max_attempts && is_transient(&e) => {
+                    let delay = self.backoff_for(attempt);
+                    warn!(
+                        error = %e,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        "Transient error, retrying"
+                    );
+                    if let Err(e) = wait_or_cancel(delay, cancel_token).await {
+                        return Err((e, attempt - 1));
+                    }
+                    attempt += 1;
+                }
+                Err(e) => return Err((e, attempt - 1)),
+            }
+        }
+    }
+}
+
+/// Check whether an error is likely to clear up on its own.
+///
+/// Sharing/lock violations and dropped network connections are transient;
+/// missing files, access denied, existing destinations and cancellation are not.
+pub fn is_transient(error: &ZError) -> bool {
+    match error {
+        ZError::Windows { code, .. } => matches!(
+            *code,
+            HRESULT_SHARING_VIOLATION
+                | HRESULT_LOCK_VIOLATION
+                | HRESULT_UNEXPECTED_NETWORK_ERROR
+                | HRESULT_NETNAME_DELETED
+                | HRESULT_SEM_TIMEOUT
+                | HRESULT_NETWORK_UNREACHABLE
+        ),
+        ZError::Io { source, .. } => {
+            matches!(source.raw_os_error(), Some(32 | 33 | 59 | 64 | 121 | 1231))
+                || matches!(
+                    source.kind(),
+                    std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Sleep for `delay`, returning early with [`ZError::Cancelled`] if the token fires.
+async fn wait_or_cancel(delay: Duration, cancel_token: &CancellationToken) -> ZResult<()> {
+    let deadline = tokio::time::Instant::now() + delay;
+    loop {
+        if cancel_token.is_cancelled() {
+            return Err(ZError::Cancelled);
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Ok(());
+        }
+        tokio::time::sleep((deadline - now).min(CANCEL_POLL_INTERVAL)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Instant;
+
+    fn sharing_violation() -> ZError {
+        ZError::Windows {
+            code: HRESULT_SHARING_VIOLATION,
+            message: "The file is in use".to_string(),
+        }
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff_for(40), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(is_transient(&sharing_violation()));
+        assert!(is_transient(&ZError::Windows {
+            code: HRESULT_NETNAME_DELETED,
+            message: String::new(),
+        }));
+        assert!(!is_transient(&ZError::NotFound {
+            path: PathBuf::from("a"),
+        }));
+        assert!(!is_transient(&ZError::PermissionDenied {
+            path: PathBuf::from("a"),
+        }));
+        assert!(!is_transient(&ZError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_succeeds_on_retry() {
+        let calls = AtomicU32::new(0);
+        let token = CancellationToken::new();
+
+        let result = fast_policy(3)
+            .run(&token, || {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call == 0 {
+                        Err(sharing_violation())
+                    } else {
+                        Ok(42u64)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), (42, 1));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failure_is_not_retried() {
+        let calls = AtomicU32::new(0);
+        let token = CancellationToken::new();
+
+        let result: Result<((), u32), _> = fast_policy(5)
+            .run(&token, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Err(ZError::NotFound {
+                        path: PathBuf::from("missing"),
+                    })
+                }
+            })
+            .await;
+
+        let (error, retries) = result.unwrap_err();
+        assert!(matches!(error, ZError::NotFound { .. }));
+        assert_eq!(retries, 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let token = CancellationToken::new();
+
+        let result: Result<((), u32), _> = fast_policy(3)
+            .run(&token, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(sharing_violation()) }
+            })
+            .await;
+
+        let (error, retries) = result.unwrap_err();
+        assert!(matches!(error, ZError::Windows { .. }));
+        assert_eq!(retries, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_interrupts_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(30),
+        };
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let start = Instant::now();
+        let result: Result<((), u32), _> = policy
+            .run(&token, || async { Err(sharing_violation()) })
+            .await;
+
+        assert!(matches!(result, Err((ZError::Cancelled, 0))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
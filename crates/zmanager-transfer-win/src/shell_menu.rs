@@ -0,0 +1,285 @@
+//! The Windows shell's context menu for files and folders.
+//!
+//! Shows the menu Explorer shows for a set of items, with "Send to", "Open
+//! with", shell extensions and third-party entries, and runs the chosen
+//! command through the shell. The menu comes from the items' parent folder
+//! (`IShellFolder::GetUIObjectOf`), so all items must share one folder, as
+//! they do in an Explorer window.
+
+use std::cell::RefCell;
+use std::ffi::{c_void, OsStr};
+use std::mem::size_of;
+use std::os::windows::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::ptr;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use windows::core::{Interface, PCSTR, PCWSTR, PSTR};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::Ole::OleInitialize;
+use windows::Win32::UI::Shell::Common::ITEMIDLIST;
+use windows::Win32::UI::Shell::{
+    DefSubclassProc, ILFree, IContextMenu, IContextMenu2, IContextMenu3, IShellFolder,
+    RemoveWindowSubclass, SHBindToParent, SHParseDisplayName, SetWindowSubclass, CMF_CANRENAME,
+    CMF_EXTENDEDVERBS, CMF_NORMAL, CMIC_MASK_PTINVOKE, CMINVOKECOMMANDINFO, CMINVOKECOMMANDINFOEX,
+    GCS_VERBW, SEE_MASK_UNICODE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreatePopupMenu, DestroyMenu, TrackPopupMenuEx, HMENU, SW_SHOWNORMAL, TPM_RETURNCMD,
+    TPM_RIGHTBUTTON, WM_DRAWITEM, WM_INITMENUPOPUP, WM_MEASUREITEM, WM_MENUCHAR,
+};
+use zmanager_core::{ZError, ZResult};
+
+/// First command id handed to the shell's menu handlers.
+const FIRST_COMMAND: u32 = 1;
+
+/// Last command id handed to the shell's menu handlers.
+const LAST_COMMAND: u32 = 0x7FFF;
+
+/// Id of the window subclass that forwards menu messages to the handlers.
+const SUBCLASS_ID: usize = 0x5A4D;
+
+thread_local! {
+    /// Menu being shown on this thread, for the window subclass.
+    static ACTIVE_MENU: RefCell<Option<IContextMenu2>> = const { RefCell::new(None) };
+}
+
+/// What the user did with the shell context menu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShellMenuResult {
+    /// The menu was closed without choosing anything.
+    Dismissed,
+    /// The shell ran the chosen command, e.g. "delete" or a "Send to"
+    /// target; `verb` is its canonical name if it has one.
+    Invoked { verb: Option<String> },
+    /// Rename was chosen. The shell can only rename inside its own views, so
+    /// the caller starts its own rename, as Explorer does.
+    Rename,
+}
+
+/// Show the shell context menu for `paths` at screen position `x`, `y` and
+/// run the command the user picks.
+///
+/// `hwnd` is the raw handle of the window that owns the menu, and this must
+/// be called on that window's thread, which has to be pumping messages.
+/// `extended` adds the verbs Explorer shows on Shift+right-click.
+///
+/// # Errors
+/// * `ZError::InvalidOperation` - No paths, or paths in different folders
+/// * `ZError::NotFound` - A path the shell cannot find
+/// * `ZError::Internal` - The shell failed to build or run the menu
+pub fn show_shell_context_menu(
+    hwnd: isize,
+    paths: &[PathBuf],
+    x: i32,
+    y: i32,
+    extended: bool,
+) -> ZResult<ShellMenuResult> {
+    let invalid = |reason: &str| ZError::InvalidOperation {
+        operation: "shell context menu".to_string(),
+        reason: reason.to_string(),
+    };
+    let Some(first) = paths.first() else {
+        return Err(invalid("No items selected"));
+    };
+    if paths.iter().any(|p| p.parent() != first.parent()) {
+        return Err(invalid("Items must be in the same folder"));
+    }
+
+    debug!(count = paths.len(), x, y, extended, "Showing shell context menu");
+
+    let hwnd = HWND(hwnd as *mut c_void);
+    unsafe {
+        // Fails harmlessly if OLE is already initialized on this thread
+        let _ = OleInitialize(None);
+
+        let pidls = Pidls::parse(paths)?;
+        let menu = pidls.context_menu(hwnd)?;
+        let hmenu = CreatePopupMenu().map_err(|e| internal("create the menu", e))?;
+        let result = track_menu(hwnd, &menu, hmenu, x, y, extended);
+        let _ = DestroyMenu(hmenu);
+        result
+    }
+}
+
+/// Fill `hmenu` from the shell, show it and run the chosen command.
+unsafe fn track_menu(
+    hwnd: HWND,
+    menu: &IContextMenu,
+    hmenu: HMENU,
+    x: i32,
+    y: i32,
+    extended: bool,
+) -> ZResult<ShellMenuResult> {
+    let mut flags = CMF_NORMAL | CMF_CANRENAME;
+    if extended {
+        flags |= CMF_EXTENDEDVERBS;
+    }
+    unsafe {
+        menu.QueryContextMenu(hmenu, 0, FIRST_COMMAND, LAST_COMMAND, flags)
+            .map_err(|e| internal("build the menu", e))?;
+
+        // Handlers fill submenus such as "Send to", and draw some entries,
+        // through messages sent to the owner window while the menu is open
+        let forwarding = menu.cast::<IContextMenu2>().ok();
+        let subclassed = forwarding.is_some()
+            && SetWindowSubclass(hwnd, Some(forward_menu_messages), SUBCLASS_ID, 0).as_bool();
+        ACTIVE_MENU.set(forwarding);
+        let track = TPM_RETURNCMD | TPM_RIGHTBUTTON;
+        let command = TrackPopupMenuEx(hmenu, track.0, x, y, hwnd, None);
+        ACTIVE_MENU.set(None);
+        if subclassed {
+            let _ = RemoveWindowSubclass(hwnd, Some(forward_menu_messages), SUBCLASS_ID);
+        }
+
+        let Some(offset) = (command.0 as u32).checked_sub(FIRST_COMMAND) else {
+            debug!("Shell context menu dismissed");
+            return Ok(ShellMenuResult::Dismissed);
+        };
+        let verb = command_verb(menu, offset);
+        debug!(verb = ?verb, "Shell context menu command chosen");
+        if verb.as_deref() == Some("rename") {
+            return Ok(ShellMenuResult::Rename);
+        }
+
+        // The command is passed by offset rather than by verb, which not
+        // every entry has
+        let info = CMINVOKECOMMANDINFOEX {
+            cbSize: size_of::<CMINVOKECOMMANDINFOEX>() as u32,
+            fMask: SEE_MASK_UNICODE | CMIC_MASK_PTINVOKE,
+            hwnd,
+            lpVerb: PCSTR(offset as usize as *const u8),
+            lpVerbW: PCWSTR(offset as usize as *const u16),
+            nShow: SW_SHOWNORMAL.0,
+            ptInvoke: POINT { x, y },
+            ..Default::default()
+        };
+        let info = ptr::from_ref(&info).cast::<CMINVOKECOMMANDINFO>();
+        menu.InvokeCommand(info).map_err(|e| internal("run the command", e))?;
+        Ok(ShellMenuResult::Invoked { verb })
+    }
+}
+
+/// Canonical verb of the command at `offset`, such as "delete" or
+/// "properties".
+unsafe fn command_verb(menu: &IContextMenu, offset: u32) -> Option<String> {
+    let mut buf = [0u16; 256];
+    let name = PSTR(buf.as_mut_ptr().cast());
+    unsafe { menu.GetCommandString(offset as usize, GCS_VERBW, None, name, buf.len() as u32) }
+        .ok()?;
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    let verb = String::from_utf16_lossy(&buf[..len]);
+    (!verb.is_empty()).then_some(verb)
+}
+
+/// Window subclass passing menu messages to the open menu's handlers.
+unsafe extern "system" fn forward_menu_messages(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    if matches!(msg, WM_INITMENUPOPUP | WM_DRAWITEM | WM_MEASUREITEM | WM_MENUCHAR) {
+        let handled = ACTIVE_MENU.with_borrow(|menu| {
+            let menu = menu.as_ref()?;
+            unsafe {
+                if let Ok(menu) = menu.cast::<IContextMenu3>() {
+                    let mut result = LRESULT(0);
+                    menu.HandleMenuMsg2(msg, wparam, lparam, Some(&mut result)).ok()?;
+                    return Some(result);
+                }
+                if msg == WM_MENUCHAR {
+                    return None;
+                }
+                menu.HandleMenuMsg(msg, wparam, lparam).ok()?;
+            }
+            // Drawing and measuring messages report that they were handled
+            Some(LRESULT((msg != WM_INITMENUPOPUP) as isize))
+        });
+        if let Some(result) = handled {
+            return result;
+        }
+    }
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+/// Absolute item ids of the paths, freed when dropped.
+struct Pidls(Vec<*mut ITEMIDLIST>);
+
+impl Pidls {
+    unsafe fn parse(paths: &[PathBuf]) -> ZResult<Self> {
+        let mut pidls = Self(Vec::with_capacity(paths.len()));
+        for path in paths {
+            let wide: Vec<u16> = OsStr::new(path).encode_wide().chain(Some(0)).collect();
+            let mut pidl = ptr::null_mut();
+            unsafe { SHParseDisplayName(PCWSTR(wide.as_ptr()), None, &mut pidl, 0, None) }
+                .map_err(|e| ZError::from_io(path, e.into()))?;
+            pidls.0.push(pidl);
+        }
+        Ok(pidls)
+    }
+
+    /// The context menu of the items, from their parent folder.
+    unsafe fn context_menu(&self, hwnd: HWND) -> ZResult<IContextMenu> {
+        let mut folder: Option<IShellFolder> = None;
+        let mut children = Vec::with_capacity(self.0.len());
+        for &pidl in &self.0 {
+            let mut child = ptr::null_mut();
+            let parent: IShellFolder = unsafe { SHBindToParent(pidl, Some(&mut child)) }
+                .map_err(|e| internal("find the parent folder", e))?;
+            folder.get_or_insert(parent);
+            children.push(child.cast_const());
+        }
+        let folder = folder.ok_or_else(|| ZError::Internal {
+            message: "No items for the context menu".to_string(),
+        })?;
+        unsafe { folder.GetUIObjectOf(hwnd, &children, None) }
+            .map_err(|e| internal("get the context menu", e))
+    }
+}
+
+impl Drop for Pidls {
+    fn drop(&mut self) {
+        for &pidl in &self.0 {
+            unsafe { ILFree(Some(pidl.cast_const())) };
+        }
+    }
+}
+
+fn internal(action: &str, error: windows::core::Error) -> ZError {
+    ZError::Internal {
+        message: format!("Failed to {action}: {}", error.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_items_in_one_folder() {
+        let result = show_shell_context_menu(0, &[], 0, 0, false);
+        assert!(matches!(result, Err(ZError::InvalidOperation { .. })));
+
+        let paths = [PathBuf::from(r"C:\a\one.txt"), PathBuf::from(r"C:\b\two.txt")];
+        let result = show_shell_context_menu(0, &paths, 0, 0, false);
+        assert!(matches!(result, Err(ZError::InvalidOperation { .. })));
+    }
+
+    #[test]
+    fn test_result_serialization() {
+        let json = |result| serde_json::to_string(&result).unwrap();
+        assert_eq!(json(ShellMenuResult::Dismissed), r#"{"kind":"dismissed"}"#);
+        assert_eq!(json(ShellMenuResult::Rename), r#"{"kind":"rename"}"#);
+        assert_eq!(
+            json(ShellMenuResult::Invoked {
+                verb: Some("delete".to_string())
+            }),
+            r#"{"kind":"invoked","verb":"delete"}"#
+        );
+    }
+}
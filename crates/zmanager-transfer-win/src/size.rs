@@ -0,0 +1,239 @@
+//! Job executor for folder size calculation.
+//!
+//! Runs [`JobKind::CalculateSize`] jobs on a blocking thread with
+//! [`zmanager_core::calculate_folder_stats_with_progress`] and reports the
+//! running totals as throttled events, so a frontend can show the size while
+//! the walk is still going.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+use tracing::info;
+use zmanager_core::{
+    calculate_folder_stats_with_progress, CancellationToken, FolderStats, Job, JobId, JobKind,
+    Progress, ZError, ZResult,
+};
+
+use crate::blocking::{run_blocking, JobEvent};
+
+/// Events emitted during size calculation.
+#[derive(Debug, Clone)]
+pub enum SizeEvent {
+    /// Calculation started.
+    Started { job_id: JobId },
+    /// Running totals so far.
+    Progress {
+        job_id: JobId,
+        progress: Progress,
+        stats: FolderStats,
+    },
+    /// Calculation completed.
+    Completed { job_id: JobId, stats: FolderStats },
+    /// Calculation failed.
+    Failed { job_id: JobId, error: String },
+    /// Calculation was cancelled.
+    Cancelled { job_id: JobId },
+}
+
+/// Configuration for the size executor.
+#[derive(Debug, Clone)]
+pub struct SizeConfig {
+    /// Minimum interval between progress updates (in milliseconds).
+    pub progress_interval_ms: u64,
+}
+
+impl Default for SizeConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval_ms: 100,
+        }
+    }
+}
+
+/// Executor for folder size calculation jobs.
+pub struct SizeExecutor {
+    config: SizeConfig,
+    event_tx: broadcast::Sender<SizeEvent>,
+}
+
+impl SizeExecutor {
+    /// Create a new executor with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(SizeConfig::default())
+    }
+
+    /// Create a new executor with custom configuration.
+    pub fn with_config(config: SizeConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1024);
+        Self { config, event_tx }
+    }
+
+    /// Subscribe to size calculation events.
+    pub fn subscribe(&self) -> broadcast::Receiver<SizeEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Execute a size calculation job from the job system.
+    ///
+    /// Any other job kind is rejected with [`ZError::Internal`].
+    pub async fn execute_job(
+        &self,
+        job: &Job,
+        cancel_token: CancellationToken,
+    ) -> ZResult<FolderStats> {
+        let JobKind::CalculateSize { path } = &job.kind else {
+            return Err(ZError::Internal {
+                message: format!("Unsupported job kind for size executor: {:?}", job.kind),
+            });
+        };
+
+        let job_id = job.id;
+        info!(job_id = %job_id, path = %path.display(), "Starting size calculation");
+
+        let interval = Duration::from_millis(self.config.progress_interval_ms);
+        let path = path.clone();
+        let start_time = Instant::now();
+
+        let what = "Size calculation";
+        let stats = run_blocking(job_id, what, &self.event_tx, interval, move |reporter| {
+            let stats = calculate_folder_stats_with_progress(&path, &cancel_token, |stats| {
+                reporter.report(stats, false);
+            })?;
+            // The last update may have been throttled away
+            reporter.report(&stats, true);
+            Ok(stats)
+        })
+        .await?;
+
+        info!(
+            job_id = %job_id,
+            size = stats.total_size,
+            files = stats.file_count,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Size calculation completed"
+        );
+        Ok(stats)
+    }
+}
+
+impl Default for SizeExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobEvent for SizeEvent {
+    type Update = FolderStats;
+    type Output = FolderStats;
+
+    fn started(job_id: JobId) -> Self {
+        Self::Started { job_id }
+    }
+
+    fn progress(job_id: JobId, stats: &FolderStats) -> Self {
+        Self::Progress {
+            job_id,
+            progress: stats_progress(stats),
+            stats: stats.clone(),
+        }
+    }
+
+    fn completed(job_id: JobId, stats: &FolderStats) -> Self {
+        let stats = stats.clone();
+        Self::Completed { job_id, stats }
+    }
+
+    fn failed(job_id: JobId, error: String) -> Self {
+        Self::Failed { job_id, error }
+    }
+
+    fn cancelled(job_id: JobId) -> Self {
+        Self::Cancelled { job_id }
+    }
+}
+
+/// Express running totals as job progress. The total isn't known until the
+/// walk ends, so only the done counts are filled in.
+fn stats_progress(stats: &FolderStats) -> Progress {
+    let mut progress = Progress::new(0, None);
+    progress.bytes_done = stats.total_size;
+    progress.items_done = stats.file_count + stats.folder_count;
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn size_job(path: PathBuf) -> Job {
+        Job::new(JobKind::CalculateSize { path })
+    }
+
+    #[tokio::test]
+    async fn test_execute_size_job() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        fs::write(temp.path().join("a.bin"), [0u8; 100]).unwrap();
+        fs::write(temp.path().join("sub").join("b.bin"), [0u8; 50]).unwrap();
+        let job = size_job(temp.path().to_path_buf());
+
+        let executor = SizeExecutor::new();
+        let mut events = executor.subscribe();
+        let stats = executor
+            .execute_job(&job, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(stats.total_size, 150);
+        assert_eq!(stats.file_count, 2);
+        assert_eq!(stats.folder_count, 1);
+
+        let mut last_progress = None;
+        let mut got_completed = false;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                SizeEvent::Progress { progress, .. } => last_progress = Some(progress),
+                SizeEvent::Completed { job_id, .. } => got_completed = job_id == job.id,
+                _ => {}
+            }
+        }
+        assert!(got_completed);
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.bytes_done, 150);
+        assert_eq!(last_progress.items_done, 3);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_size_job() {
+        let temp = TempDir::new().unwrap();
+        let job = size_job(temp.path().to_path_buf());
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = SizeExecutor::new();
+        let mut events = executor.subscribe();
+        let result = executor.execute_job(&job, token).await;
+
+        assert!(matches!(result, Err(ZError::Cancelled)));
+        let mut got_cancelled = false;
+        while let Ok(event) = events.try_recv() {
+            got_cancelled |= matches!(event, SizeEvent::Cancelled { .. });
+        }
+        assert!(got_cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_job_kinds() {
+        let job = Job::new(JobKind::Delete {
+            paths: vec![PathBuf::from("a")],
+        });
+
+        let result = SizeExecutor::new()
+            .execute_job(&job, CancellationToken::new())
+            .await;
+        assert!(matches!(result, Err(ZError::Internal { .. })));
+    }
+}
@@ -0,0 +1,244 @@
+//! Thumbnails of images and videos, cached on disk.
+//!
+//! Thumbnails come from the shell (`IShellItemImageFactory` asked for
+//! thumbnails only), which reads the Windows thumbnail cache or has the
+//! installed thumbnail handlers draw them, so videos get a frame and any
+//! format with a handler works. [`ThumbnailCache`] keeps them as PNGs under
+//! `%LOCALAPPDATA%\ZManager\thumbnails`, named after the file's path, size
+//! and modification time, so an edited file gets a fresh thumbnail.
+
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use tracing::{debug, warn};
+use windows::Win32::UI::Shell::SIIGBF_THUMBNAILONLY;
+use xxhash_rust::xxh3::xxh3_64;
+use zmanager_core::{extended_path, ZError, ZResult};
+
+use crate::icon::shell_image_png;
+
+/// Smallest thumbnail size handed out, in pixels.
+pub const MIN_THUMBNAIL_SIZE: u32 = 32;
+
+/// Largest thumbnail size handed out, in pixels.
+pub const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Disk space the thumbnail cache is trimmed to by default (256 MiB).
+pub const DEFAULT_THUMBNAIL_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Extensions of images the shell usually has a thumbnail handler for.
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "jpe", "jfif", "png", "gif", "bmp", "dib", "tif", "tiff", "ico", "webp",
+    "heic", "heif", "avif", "jxr", "wdp", "dng", "cr2", "nef", "arw",
+];
+
+/// Extensions of videos the shell usually has a thumbnail handler for.
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "mov", "avi", "wmv", "mkv", "webm", "mpg", "mpeg", "m2ts", "mts", "3gp",
+    "flv",
+];
+
+/// Check if `path` is an image or video a thumbnail can be made for.
+///
+/// Goes by extension only; the shell may still lack a handler for a file.
+pub fn has_thumbnail(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| {
+            IMAGE_EXTENSIONS.contains(&ext.as_str()) || VIDEO_EXTENSIONS.contains(&ext.as_str())
+        })
+}
+
+/// Thumbnails of images and videos, kept as PNG files in a directory.
+#[derive(Debug, Clone)]
+pub struct ThumbnailCache {
+    /// Directory the thumbnails are kept in.
+    dir: PathBuf,
+}
+
+impl ThumbnailCache {
+    /// Create a cache keeping its thumbnails in `dir`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get the default thumbnail directory.
+    pub fn default_dir() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("ZManager")
+            .join("thumbnails")
+    }
+
+    /// Directory the thumbnails are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The thumbnail of `path` as a PNG fitting in `size` pixels square,
+    /// keeping the image's aspect ratio.
+    ///
+    /// `size` is clamped to [`MIN_THUMBNAIL_SIZE`]..=[`MAX_THUMBNAIL_SIZE`].
+    /// A thumbnail that can't be saved is still returned.
+    ///
+    /// # Errors
+    /// * `ZError::NotFound` - The file doesn't exist
+    /// * `ZError::InvalidOperation` - The shell has no thumbnail for the file
+    pub fn get(&self, path: &Path, size: u32) -> ZResult<Vec<u8>> {
+        let size = size.clamp(MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE);
+        let meta = extended_path(path)
+            .metadata()
+            .map_err(|e| ZError::from_io(path, e))?;
+        let cached = self.entry_path(path, &meta, size);
+        if let Ok(png) = fs::read(&cached) {
+            return Ok(png);
+        }
+
+        let png = shell_image_png(path, size, SIIGBF_THUMBNAILONLY).map_err(|e| match e {
+            ZError::Internal { message } => ZError::InvalidOperation {
+                operation: "thumbnail".to_string(),
+                reason: format!("No thumbnail for {}: {message}", path.display()),
+            },
+            e => e,
+        })?;
+        let saved = fs::create_dir_all(&self.dir).and_then(|_| fs::write(&cached, &png));
+        if let Err(e) = saved {
+            warn!("Failed to cache thumbnail of {}: {}", path.display(), e);
+        }
+        Ok(png)
+    }
+
+    /// Delete the least recently made thumbnails until the cache takes at
+    /// most `max_bytes`. Returns the number of thumbnails deleted.
+    pub fn cleanup(&self, max_bytes: u64) -> ZResult<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(ZError::io(&self.dir, e)),
+        };
+        let mut thumbnails: Vec<_> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let meta = entry.metadata().ok()?;
+                let made = meta.modified().unwrap_or(UNIX_EPOCH);
+                meta.is_file().then(|| (made, meta.len(), entry.path()))
+            })
+            .collect();
+
+        let mut total: u64 = thumbnails.iter().map(|(_, len, _)| len).sum();
+        thumbnails.sort_by_key(|(made, _, _)| *made);
+        let mut deleted = 0;
+        for (_, len, path) in thumbnails {
+            if total <= max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= len;
+                deleted += 1;
+            }
+        }
+        debug!("Deleted {} cached thumbnail(s)", deleted);
+        Ok(deleted)
+    }
+
+    /// Delete every cached thumbnail.
+    pub fn clear(&self) -> ZResult<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(ZError::io(&self.dir, e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// File the thumbnail of `path` at `size` is kept in.
+    fn entry_path(&self, path: &Path, meta: &Metadata, size: u32) -> PathBuf {
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos());
+        // Paths are case-insensitive on Windows
+        let key = format!(
+            "{}|{}|{}|{}",
+            path.to_string_lossy().to_lowercase(),
+            meta.len(),
+            modified,
+            size
+        );
+        self.dir.join(format!("{:016x}.png", xxh3_64(key.as_bytes())))
+    }
+}
+
+impl Default for ThumbnailCache {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_has_thumbnail() {
+        assert!(has_thumbnail(Path::new(r"C:\photos\IMG_0001.JPG")));
+        assert!(has_thumbnail(Path::new("clip.mkv")));
+        assert!(!has_thumbnail(Path::new("notes.txt")));
+        assert!(!has_thumbnail(Path::new("README")));
+    }
+
+    #[test]
+    fn test_get_reads_cached_thumbnail() {
+        let temp = TempDir::new().unwrap();
+        let image = temp.path().join("photo.png");
+        fs::write(&image, b"pixels").unwrap();
+        let cache = ThumbnailCache::new(temp.path().join("thumbnails"));
+
+        let meta = fs::metadata(&image).unwrap();
+        let entry = cache.entry_path(&image, &meta, 256);
+        assert_ne!(entry, cache.entry_path(&image, &meta, 128));
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(&entry, b"cached png").unwrap();
+
+        assert_eq!(cache.get(&image, 256).unwrap(), b"cached png");
+
+        // Editing the file makes the old thumbnail stale
+        fs::write(&image, b"new pixels").unwrap();
+        let meta = fs::metadata(&image).unwrap();
+        assert_ne!(entry, cache.entry_path(&image, &meta, 256));
+    }
+
+    #[test]
+    fn test_get_missing_file() {
+        let temp = TempDir::new().unwrap();
+        let cache = ThumbnailCache::new(temp.path().join("thumbnails"));
+
+        let result = cache.get(&temp.path().join("gone.jpg"), 256);
+        assert!(matches!(result, Err(ZError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_cleanup_deletes_oldest() {
+        let temp = TempDir::new().unwrap();
+        let cache = ThumbnailCache::new(temp.path().join("thumbnails"));
+        fs::create_dir_all(cache.dir()).unwrap();
+        let now = SystemTime::now();
+        for (i, name) in ["old.png", "mid.png", "new.png"].iter().enumerate() {
+            let path = cache.dir().join(name);
+            fs::write(&path, [0u8; 100]).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(300 - i as u64 * 100))
+                .unwrap();
+        }
+
+        assert_eq!(cache.cleanup(250).unwrap(), 1);
+        assert!(!cache.dir().join("old.png").exists());
+        assert!(cache.dir().join("new.png").exists());
+
+        cache.clear().unwrap();
+        assert!(!cache.dir().exists());
+        assert_eq!(cache.cleanup(0).unwrap(), 0);
+    }
+}
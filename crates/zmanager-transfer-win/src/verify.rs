@@ -0,0 +1,100 @@
+//! Post-copy verification by checksum.
+//!
+//! After a file is copied, the source and destination are hashed with the
+//! configured [`ChecksumAlgorithm`] and compared, catching corruption that the
+//! copy itself didn't report.
+
+use std::path::Path;
+
+use zmanager_core::{hash_file, CancellationToken, ChecksumAlgorithm, ZResult};
+
+/// Checksums of a copied file and its source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyResult {
+    /// Algorithm both checksums were computed with.
+    pub algorithm: ChecksumAlgorithm,
+    /// Checksum of the source, as lowercase hex.
+    pub source_checksum: String,
+    /// Checksum of the destination, as lowercase hex.
+    pub destination_checksum: String,
+}
+
+impl VerifyResult {
+    /// Check if the copy matches its source.
+    pub fn matches(&self) -> bool {
+        self.source_checksum == self.destination_checksum
+    }
+}
+
+/// Hash the contents of a file, returning the checksum as lowercase hex.
+pub fn file_checksum(
+    path: impl AsRef<Path>,
+    algorithm: ChecksumAlgorithm,
+    cancel: &CancellationToken,
+) -> ZResult<String> {
+    hash_file(path, algorithm.into(), cancel)
+}
+
+/// Hash a copied file and its source so they can be compared.
+pub fn verify_copy(
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+    algorithm: ChecksumAlgorithm,
+    cancel: &CancellationToken,
+) -> ZResult<VerifyResult> {
+    Ok(VerifyResult {
+        algorithm,
+        source_checksum: file_checksum(source, algorithm, cancel)?,
+        destination_checksum: file_checksum(destination, algorithm, cancel)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    use zmanager_core::ZError;
+
+    /// Read size while hashing, so test files span several chunks.
+    const READ_CHUNK: usize = 1024 * 1024;
+
+    #[test]
+    fn test_known_checksums() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("abc.txt");
+        fs::write(&path, b"abc").unwrap();
+        let cancel = CancellationToken::new();
+
+        assert_eq!(
+            file_checksum(&path, ChecksumAlgorithm::Sha256, &cancel).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            file_checksum(&path, ChecksumAlgorithm::XxHash, &cancel).unwrap(),
+            format!("{:016x}", xxhash_rust::xxh3::xxh3_64(b"abc"))
+        );
+    }
+
+    #[test]
+    fn test_verify_copy() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source.bin");
+        let copy = temp.path().join("copy.bin");
+        fs::write(&source, vec![7u8; 3 * READ_CHUNK + 1]).unwrap();
+        fs::copy(&source, &copy).unwrap();
+        let cancel = CancellationToken::new();
+
+        for algorithm in [ChecksumAlgorithm::XxHash, ChecksumAlgorithm::Sha256] {
+            assert!(verify_copy(&source, &copy, algorithm, &cancel).unwrap().matches());
+        }
+
+        fs::write(&copy, vec![7u8; 3 * READ_CHUNK]).unwrap();
+        let result = verify_copy(&source, &copy, ChecksumAlgorithm::XxHash, &cancel).unwrap();
+        assert!(!result.matches());
+
+        cancel.cancel();
+        let result = verify_copy(&source, &copy, ChecksumAlgorithm::XxHash, &cancel);
+        assert!(matches!(result, Err(ZError::Cancelled)));
+    }
+}
@@ -1,33 +1,144 @@
 //! Application state management.
 
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use ratatui::widgets::ListState;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc, Semaphore};
+use tokio::task::AbortHandle;
+use tracing::{debug, warn};
 use zmanager_core::{
-    Config, DriveInfo, EntryMeta, Favorite, FilterSpec, JobInfo, NavigationState, Properties,
-    Selection, SortField as CoreSortField, SortSpec, ZResult,
+    basket_path, bulk_rename, calculate_multi_properties_with_progress,
+    copy_text, descend_single_children, drive_for_path, entry::format_size,
+    expand_env_vars, find_in_file, get_security, is_browsable_archive, is_network_path, is_unc_path,
+    launch_tool, open_default, remember_path, reveal_in_explorer, set_properties,
+    split_archive_path, split_basket_path, unc_server, validate_filename,
+    would_lose_alternate_streams, Baskets, CancellationToken, Collation, CompareMode,
+    Config, ContentSearch, CopyFormat, DirectoryProvider, DirectoryWatcher,
+    DriveChange, DriveInfo, DuplicateIndex, DuplicateReport, EntryMeta, ExecutableOpenBehavior,
+    Favorite, FileComparison, FileConversion, FileHash, FilterSpec, FolderStats, HashAlgorithm, Job,
+    JobId, JobInfo, JobKind, JobState, MultiProperties, NavigationState, OpenDirectoryBehavior,
+    Plugins, Priority, Progress, Properties, PropertyChanges, RenamePattern, RenamePlan,
+    SchedulerEvent, SchedulerHandle, SecurityInfo, Selection, SessionState,
+    SortField as CoreSortField, SortOrder, SortSettings, SortSpec, TextConversion, TextFile,
+    VisitHistory, WatchEvent, ZError, ZResult,
 };
 
+use zmanager_transfer_win::{
+    discard_partial_copy, find_unfinished_transfers, open_with, open_with_dialog,
+    open_with_handlers, ChecksumEvent, ChecksumExecutor, CompareEvent, CompareExecutor,
+    CompressEvent, CompressExecutor, Conflict,
+    ConflictAnswer, ConflictPolicy,
+    ConflictQuery, ConflictResolver, ConvertEvent, ConvertExecutor,
+    DeleteConfig, DeleteEvent, DeleteExecutor, DetailedTransferReport, ExtractEvent,
+    ExtractExecutor, FolderTransferConfig,
+    BackendRegistry, ElevatedOperation, ElevatedResult, ElevationManifest, FolderTransferEvent,
+    FolderTransferExecutor, OpenWithHandler, ReportBuilder, ReportStorage, run_elevated,
+    SizeEvent, SizeExecutor, TransferItemResult, TransferJournal, TransferOperation,
+};
+
+use zmanager_mtp::{is_device_path, split_device_path, MtpBackend, MtpFileSystem};
+use zmanager_remote::{canonical_remote_path, is_remote_path, RemoteBackend, RemoteFileSystems};
+
 use crate::{
     event::Event,
-    input::Action,
-    ui::{layout::Pane, ConflictModal, Dialog, SidebarState, SortField},
+    input::{Action, Keymap},
+    status::{StatusLevel, StatusMessage, StatusQueue},
+    ui::{
+        file_list::find_match, layout::Pane, Applies, ChecksumView, CompareView, ConvertView,
+        ConflictInfo, ConflictModal, ContextAction, ContextMenu, ContextTargets, CopyMenu,
+        ConflictResolution, Dialog, DriveMenu, FileViewer, FilterBuilder, HistoryView,
+        InlineRename, JobDetails,
+        LocationBar, OpenWithMenu, PropertiesEditor, ReportHistory, SidebarState, SortField,
+        SortOption, Styles,
+        TextEditor, Theme,
+    },
 };
 
+/// Upper bound for a typed count prefix.
+const MAX_COUNT_PREFIX: usize = 999;
+
+/// Renames listed in the bulk rename preview before the rest are summarised.
+const BULK_RENAME_PREVIEW_LINES: usize = 8;
+
+/// Minimum time between transfers view refreshes caused by job progress.
+const JOB_PROGRESS_REFRESH: Duration = Duration::from_millis(100);
+
+/// Reports of finished jobs kept for the history tab; older ones are removed.
+const STORED_REPORTS: usize = 100;
+
+/// Speed limits the transfers view steps through, in bytes per second.
+const SPEED_LIMIT_PRESETS: [Option<u64>; 4] =
+    [None, Some(50 << 20), Some(10 << 20), Some(1 << 20)];
+
+/// Folder size calculations for size-interleaved sorting that run at once.
+const DIRECTORY_SIZE_JOBS: usize = 2;
+
 /// Pending operation after dialog confirmation.
 #[derive(Debug, Clone)]
 pub enum PendingOperation {
-    /// Delete the specified files.
+    /// Move the specified files to the Recycle Bin.
     Delete(Vec<PathBuf>),
-    /// Rename a file (from, to).
-    Rename(PathBuf),
+    /// Delete the specified files permanently, wiping them first if the
+    /// config says so.
+    DeletePermanent(Vec<PathBuf>),
+    /// Rename a file to a name taken from the clipboard (from, new name).
+    RenameFromClipboard(PathBuf, String),
+    /// Rename several files with the pattern entered in the dialog.
+    BulkRename(Vec<PathBuf>),
+    /// Carry out a previewed bulk rename.
+    ConfirmBulkRename(RenamePlan),
     /// Create a new directory.
     MakeDir,
-    /// Copy files to the other pane.
+    /// Create a new file, from a template if one matches its extension.
+    NewFile,
+    /// Select the entries matching the pattern entered in the dialog.
+    SelectPattern,
+    /// Deselect the entries matching the pattern entered in the dialog.
+    DeselectPattern,
+    /// Copy files to the destination entered in the dialog, which starts at
+    /// the given folder.
     Copy(Vec<PathBuf>, PathBuf),
-    /// Move files to the other pane.
+    /// Move files to the destination entered in the dialog, which starts at
+    /// the given folder.
     Move(Vec<PathBuf>, PathBuf),
+    /// Open files with their default applications, then optionally navigate.
+    /// The count is how many executables were left out of the selection.
+    OpenSelected(Vec<PathBuf>, Option<PathBuf>, usize),
+    /// Run an executable after confirmation.
+    RunExecutable(PathBuf),
+    /// Pack files into a new archive named in the dialog.
+    Compress(Vec<PathBuf>),
+    /// Add files to the basket named in the dialog.
+    AddToBasket(Vec<PathBuf>),
+    /// Show the basket named in the dialog.
+    ShowBasket,
+    /// Resume the first of the transfers a crash cut short (Esc abandons it).
+    ResumeTransfer,
+    /// Retry operations that were denied access as administrator (the job
+    /// they failed in, or `None` for a rename).
+    RetryElevated(Option<u64>, ElevationManifest),
+    /// Take ownership of files and grant the user full control, as
+    /// administrator.
+    TakeOwnership(Vec<PathBuf>),
+}
+
+/// A request from the transfers view for a queued or running job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobControl {
+    /// Park the job between files.
+    Pause,
+    /// Let a paused job continue.
+    Resume,
+    /// Stop the job.
+    Cancel,
+    /// Cap the job's bandwidth in bytes per second, or lift the cap.
+    SpeedLimit(Option<u64>),
+    /// Queue the items a failed job didn't finish again.
+    RetryFailed,
 }
 
 /// View mode for the application.
@@ -78,11 +189,62 @@ pub struct App {
     /// Selected job index in transfers view.
     pub jobs_list_state: ListState,
 
+    /// Details of the jobs that started, by job id.
+    pub job_details: HashMap<u64, JobDetails>,
+
+    /// Whether the transfers view shows the selected job's details.
+    pub show_job_details: bool,
+
+    /// Directory job reports are stored and exported to.
+    reports_dir: PathBuf,
+
+    /// History tab of the transfers view, when shown.
+    pub report_history: Option<ReportHistory>,
+
     /// Active conflict modal (if any).
     pub conflict_modal: Option<ConflictModal>,
 
-    /// Status message to display (with optional timeout).
-    pub status_message: Option<(String, bool)>, // (message, is_error)
+    /// Open location bar (if any).
+    pub location_bar: Option<LocationBar>,
+
+    /// Paths gone to from the location bar, most recent first.
+    pub path_history: Vec<String>,
+
+    /// Directories visited, kept across runs.
+    pub visit_history: VisitHistory,
+
+    /// Directory last recorded in the visit history, so refreshes don't count.
+    last_visited: Option<PathBuf>,
+
+    /// Named sets of files collected from any directories, kept across runs.
+    pub baskets: Baskets,
+
+    /// Basket last added to or shown, offered first in the basket dialogs.
+    last_basket: String,
+
+    /// Servers browsed at `sftp://`, `dav://` and `davs://` paths and
+    /// transferred to and from, logged in to on first use.
+    pub remote: Arc<RemoteFileSystems>,
+
+    /// Phones and cameras connected over MTP, browsed at `mtp://` paths
+    /// and listed among the drives.
+    pub devices: Arc<MtpFileSystem>,
+
+    /// Open directory history overlay (if any).
+    pub history_view: Option<HistoryView>,
+
+    /// Transfer conflicts waiting for an answer; the front one is shown.
+    conflict_queue: VecDeque<ConflictQuery>,
+
+    /// Status bar messages, shown one after another until they expire.
+    status: StatusQueue,
+
+    /// Whether the terminal has focus, as far as it reports focus changes.
+    terminal_focused: bool,
+
+    /// Whether the terminal bell should ring for a job that finished while
+    /// the terminal was unfocused.
+    bell_pending: bool,
 
     /// Whether the sidebar is visible.
     pub sidebar_visible: bool,
@@ -96,21 +258,263 @@ pub struct App {
     /// Available drives.
     pub drives: Vec<DriveInfo>,
 
+    /// Drive menu switching a pane's drive (if open).
+    pub drive_menu: Option<DriveMenu>,
+
+    /// Drive whose volume details are showing (if open).
+    pub drive_details: Option<DriveInfo>,
+
     /// Whether help screen is visible.
     pub show_help: bool,
 
     /// Properties to display (if showing properties panel).
     pub properties: Option<Properties>,
 
+    /// Owner and permissions of the item in the properties panel, read when
+    /// its Security tab is shown (the panel shows that tab while set).
+    pub security: Option<Result<SecurityInfo, String>>,
+
+    /// Combined properties of several selected items (if showing properties
+    /// panel).
+    pub multi_properties: Option<MultiProperties>,
+
+    /// Attribute and timestamp editor (if open).
+    pub properties_editor: Option<PropertiesEditor>,
+
+    /// Size, date and attribute filter builder (if open).
+    pub filter_builder: Option<FilterBuilder>,
+
+    /// Transfer whose progress dialog blocks browsing until it finishes.
+    pub foreground_job: Option<u64>,
+
+    /// Checksum dialog (if open).
+    pub checksums: Option<ChecksumView>,
+
+    /// Compare dialog (if open).
+    pub compare: Option<CompareView>,
+
+    /// Convert dialog (if open).
+    pub convert: Option<ConvertView>,
+
+    /// Open-with menu (if open).
+    pub open_with: Option<OpenWithMenu>,
+
+    /// Copy-as-text menu (if open).
+    pub copy_menu: Option<CopyMenu>,
+
+    /// Context menu of operations on the selected entries (if open).
+    pub context_menu: Option<ContextMenu>,
+
+    /// File viewer filling the screen (if open).
+    pub viewer: Option<FileViewer>,
+
+    /// Text editor filling the screen (if open).
+    pub text_editor: Option<TextEditor>,
+
     /// Application config.
     pub config: Config,
 
+    /// Key chords bound to actions, from the built-in bindings and config.
+    pub keymap: Keymap,
+
+    /// Custom commands added by plugins.
+    pub plugins: Plugins,
+
+    /// Count prefix typed before a motion (e.g. `3` then Backspace).
+    pending_count: Option<usize>,
+
+    /// Folder sizes computed in the background, for size-interleaved sorting.
+    dir_sizes: HashMap<PathBuf, u64>,
+
+    /// Slots for the size calculations of folders the panes list, so only a
+    /// few walk their trees at once.
+    dir_size_slots: Arc<Semaphore>,
+
+    /// Index of the other pane's tree from the last duplicate scan, reused
+    /// while that pane stays in the same directory.
+    duplicate_index: Arc<Mutex<Option<DuplicateIndex>>>,
+
+    /// Cancellation for the duplicate scan in flight.
+    duplicate_scan: Option<CancellationToken>,
+
+    /// Folder size jobs in flight, by folder.
+    size_jobs: HashMap<PathBuf, CancellationToken>,
+
+    /// Id and cancellation of the multi-selection properties calculation.
+    multi_properties_job: Option<(u64, CancellationToken)>,
+
+    /// Id for the next multi-selection properties calculation.
+    next_multi_properties: u64,
+
+    /// Id and cancellation of the search running in the file viewer.
+    viewer_search: Option<(u64, CancellationToken)>,
+
+    /// Id for the next search in the file viewer.
+    next_viewer_search: u64,
+
+    /// Job and cancellation of the checksums shown in the dialog.
+    checksum_job: Option<(JobId, CancellationToken)>,
+
+    /// Job and cancellation of the comparison shown in the dialog.
+    compare_job: Option<(JobId, CancellationToken)>,
+
+    /// Job and cancellation of the conversion shown in the dialog.
+    convert_job: Option<(JobId, CancellationToken)>,
+
+    /// Scheduler that runs copy and move transfers.
+    scheduler: Option<SchedulerHandle>,
+
+    /// Transfers a crash cut short, still to be resumed or abandoned.
+    unfinished_transfers: Vec<TransferJournal>,
+
+    /// Id for the next streamed directory listing.
+    next_listing: u64,
+
     /// Event sender for async operations.
     event_tx: mpsc::UnboundedSender<Event>,
 }
 
-/// State for a single pane.
+/// State for a single pane: its tabs and which one is shown.
+///
+/// The pane dereferences to its active tab, so `pane.nav` or `pane.entries`
+/// always refer to what is on screen.
 pub struct PaneState {
+    /// Open tabs, in display order. Never empty.
+    tabs: Vec<TabState>,
+
+    /// Index of the tab on screen.
+    active_tab: usize,
+
+    /// Directory listing still streaming into the active tab.
+    loading: Option<Loading>,
+
+    /// Size calculations for the folders of the directory on screen.
+    dir_sizes: Option<DirectorySizes>,
+}
+
+/// Size calculations for the folders a pane lists, for size-interleaved
+/// sorting.
+struct DirectorySizes {
+    /// Directory the folders were listed in.
+    directory: PathBuf,
+    /// Cancels the calculations once the pane leaves the directory.
+    cancel: CancellationToken,
+    /// Folders with a size calculation in flight.
+    pending: HashSet<PathBuf>,
+}
+
+/// A directory listing streaming into a pane.
+struct Loading {
+    /// Id carried by the listing's batches.
+    id: u64,
+    /// Entries received so far.
+    received: usize,
+    /// Task forwarding the batches; aborting it stops the listing.
+    task: Option<AbortHandle>,
+    /// Entry the cursor was on before a refresh, until it turns up again.
+    cursor: Option<PathBuf>,
+}
+
+impl PaneState {
+    /// Create a new pane with a single tab at the given path.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            tabs: vec![TabState::new(path)],
+            active_tab: 0,
+            loading: None,
+            dir_sizes: None,
+        }
+    }
+
+    /// Size calculations for the folders of `directory`, cancelling those
+    /// of the directory the pane showed before.
+    fn directory_sizes(&mut self, directory: &Path) -> &mut DirectorySizes {
+        if self.dir_sizes.as_ref().is_some_and(|sizes| sizes.directory != directory) {
+            self.cancel_directory_sizes();
+        }
+        self.dir_sizes.get_or_insert_with(|| DirectorySizes {
+            directory: directory.to_path_buf(),
+            cancel: CancellationToken::new(),
+            pending: HashSet::new(),
+        })
+    }
+
+    /// Stop the size calculations for the folders the pane lists.
+    fn cancel_directory_sizes(&mut self) {
+        if let Some(sizes) = self.dir_sizes.take() {
+            sizes.cancel.cancel();
+        }
+    }
+
+    /// Number of entries received so far if a listing is still streaming in.
+    pub fn loading_count(&self) -> Option<usize> {
+        self.loading.as_ref().map(|loading| loading.received)
+    }
+
+    /// All tabs, in display order.
+    pub fn tabs(&self) -> &[TabState] {
+        &self.tabs
+    }
+
+    /// Index of the tab on screen.
+    pub fn active_tab(&self) -> usize {
+        self.active_tab
+    }
+
+    /// Open a new tab right after the current one, showing the same directory,
+    /// and switch to it.
+    pub fn open_tab(&mut self) {
+        let current = &self.tabs[self.active_tab];
+        let mut tab = TabState::new(current.nav.current_path().to_path_buf());
+        tab.filter_inverted = current.filter_inverted;
+        tab.flat_view = current.flat_view.clone();
+        tab.set_entries(current.quick_filter.as_ref().map_or_else(
+            || current.entries.clone(),
+            |filter| filter.all_entries.clone(),
+        ));
+        self.active_tab += 1;
+        self.tabs.insert(self.active_tab, tab);
+    }
+
+    /// Close the current tab and show its neighbour.
+    ///
+    /// Returns false if this is the last tab, which stays open.
+    pub fn close_tab(&mut self) -> bool {
+        if self.tabs.len() <= 1 {
+            return false;
+        }
+        self.tabs.remove(self.active_tab);
+        self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        true
+    }
+
+    /// Switch to the next tab, wrapping around.
+    pub fn next_tab(&mut self) {
+        self.active_tab = (self.active_tab + 1) % self.tabs.len();
+    }
+
+    /// Switch to the previous tab, wrapping around.
+    pub fn prev_tab(&mut self) {
+        self.active_tab = (self.active_tab + self.tabs.len() - 1) % self.tabs.len();
+    }
+}
+
+impl std::ops::Deref for PaneState {
+    type Target = TabState;
+
+    fn deref(&self) -> &TabState {
+        &self.tabs[self.active_tab]
+    }
+}
+
+impl std::ops::DerefMut for PaneState {
+    fn deref_mut(&mut self) -> &mut TabState {
+        &mut self.tabs[self.active_tab]
+    }
+}
+
+/// State for one tab of a pane: its location, listing and selection.
+pub struct TabState {
     /// Navigation state (current path, history).
     pub nav: NavigationState,
 
@@ -122,19 +526,65 @@ pub struct PaneState {
 
     /// List widget state (for scrolling).
     pub list_state: ListState,
+
+    /// Whether this pane shows the complement of the active filter.
+    pub filter_inverted: bool,
+
+    /// Filter typed into the pane to narrow the listing by name.
+    pub quick_filter: Option<QuickFilter>,
+
+    /// Name being edited in place of an entry's name.
+    pub inline_rename: Option<InlineRename>,
+
+    /// Directory listed flat, with the files of all its subdirectories.
+    /// Leaving the directory ends the flat view.
+    pub flat_view: Option<PathBuf>,
 }
 
-impl PaneState {
-    /// Create a new pane state at the given path.
+/// Live name filter narrowing a pane's listing as the user types.
+#[derive(Debug, Clone)]
+pub struct QuickFilter {
+    /// Text typed so far, matched case-insensitively anywhere in the name.
+    pub text: String,
+    /// Whether keys go to the filter rather than to commands.
+    pub editing: bool,
+    /// Directory the filter was typed in; the filter is dropped on leaving it.
+    directory: PathBuf,
+    /// The full listing being narrowed.
+    all_entries: Vec<EntryMeta>,
+}
+
+impl QuickFilter {
+    /// Entries of the full listing whose name contains the filter text.
+    fn matching(&self) -> Vec<EntryMeta> {
+        self.all_entries
+            .iter()
+            .filter(|e| find_match(&e.name, &self.text).is_some() || self.text.is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
+impl TabState {
+    /// Create a new tab at the given path.
     pub fn new(path: PathBuf) -> Self {
         Self {
             nav: NavigationState::new(path),
             entries: Vec::new(),
             selection: Selection::new(),
             list_state: ListState::default(),
+            filter_inverted: false,
+            quick_filter: None,
+            inline_rename: None,
+            flat_view: None,
         }
     }
 
+    /// The directory listed flat, if the pane still shows it.
+    pub fn flat_base(&self) -> Option<&Path> {
+        self.flat_view.as_deref().filter(|dir| *dir == self.nav.current_path())
+    }
+
     /// Get the current cursor position.
     pub fn cursor(&self) -> usize {
         self.selection.cursor()
@@ -182,6 +632,42 @@ impl PaneState {
         self.sync_list_state();
     }
 
+    /// Move the cursor to the next entry with the current entry's extension.
+    ///
+    /// Wraps around the end of the list. Returns false if no other entry
+    /// shares the extension.
+    pub fn next_same_ext(&mut self) -> bool {
+        self.jump_to_same_ext(true)
+    }
+
+    /// Move the cursor to the previous entry with the current entry's extension.
+    ///
+    /// Wraps around the start of the list. Returns false if no other entry
+    /// shares the extension.
+    pub fn prev_same_ext(&mut self) -> bool {
+        self.jump_to_same_ext(false)
+    }
+
+    fn jump_to_same_ext(&mut self, forward: bool) -> bool {
+        let len = self.entries.len();
+        let cursor = self.cursor();
+        let Some(group) = self.entries.get(cursor).map(extension_group) else {
+            return false;
+        };
+
+        let found = (1..len)
+            .map(|step| if forward { (cursor + step) % len } else { (cursor + len - step) % len })
+            .find(|&i| extension_group(&self.entries[i]) == group);
+
+        match found {
+            Some(index) => {
+                self.set_cursor(index);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Sync list_state with selection cursor.
     fn sync_list_state(&mut self) {
         self.list_state.select(Some(self.selection.cursor()));
@@ -231,8 +717,128 @@ impl PaneState {
         self.selection.clear();
     }
 
+    /// Select entries matching the wildcard `patterns`; see
+    /// [`Selection::select_matching`].
+    pub fn select_matching(&mut self, patterns: &str) -> usize {
+        self.selection.select_matching(&self.entries, patterns)
+    }
+
+    /// Deselect entries matching the wildcard `patterns`.
+    pub fn deselect_matching(&mut self, patterns: &str) -> usize {
+        self.selection.deselect_matching(&self.entries, patterns)
+    }
+
+    /// Select files with the same extension as the current one.
+    pub fn select_same_extension(&mut self) -> usize {
+        self.selection.select_same_extension(&self.entries)
+    }
+
+    /// Select files newer than the selected ones (or the current one).
+    pub fn select_newer(&mut self) -> usize {
+        self.selection.select_newer(&self.entries)
+    }
+
     /// Update entries and sync selection.
+    ///
+    /// A quick filter typed in this directory keeps narrowing the new entries;
+    /// one typed elsewhere is dropped. So is an inline rename of an entry
+    /// that is no longer listed.
     pub fn set_entries(&mut self, entries: Vec<EntryMeta>) {
+        let renamed_gone = self
+            .inline_rename
+            .as_ref()
+            .is_some_and(|editor| !entries.iter().any(|e| e.path == editor.path()));
+        if renamed_gone {
+            self.inline_rename = None;
+        }
+        match self.quick_filter.as_mut() {
+            Some(filter) if filter.directory == self.nav.current_path() => {
+                filter.all_entries = entries;
+                let matching = filter.matching();
+                self.show_entries(matching);
+            }
+            _ => {
+                self.quick_filter = None;
+                self.show_entries(entries);
+            }
+        }
+    }
+
+    /// Take the tab's full listing, ignoring any quick filter, leaving the
+    /// tab empty.
+    fn take_entries(&mut self) -> Vec<EntryMeta> {
+        match self.quick_filter.as_mut() {
+            Some(filter) if filter.directory == self.nav.current_path() => {
+                std::mem::take(&mut filter.all_entries)
+            }
+            _ => std::mem::take(&mut self.entries),
+        }
+    }
+
+    /// Start typing a quick filter, or resume editing the current one.
+    pub fn start_quick_filter(&mut self) {
+        match self.quick_filter.as_mut() {
+            Some(filter) => filter.editing = true,
+            None => {
+                self.quick_filter = Some(QuickFilter {
+                    text: String::new(),
+                    editing: true,
+                    directory: self.nav.current_path().to_path_buf(),
+                    all_entries: self.entries.clone(),
+                });
+            }
+        }
+    }
+
+    /// Replace the quick filter text and narrow the listing to match.
+    ///
+    /// The cursor moves to the first match.
+    pub fn set_quick_filter_text(&mut self, text: String) {
+        let Some(filter) = self.quick_filter.as_mut() else {
+            return;
+        };
+        filter.text = text;
+        let matching = filter.matching();
+        self.show_entries(matching);
+        self.set_cursor(0);
+    }
+
+    /// Drop the quick filter and show the full listing again, keeping the
+    /// cursor on the same entry.
+    pub fn clear_quick_filter(&mut self) {
+        let Some(filter) = self.quick_filter.take() else {
+            return;
+        };
+        let current = self.current_entry().map(|e| e.path.clone());
+        self.show_entries(filter.all_entries);
+        let index = current.and_then(|c| self.entries.iter().position(|e| e.path == c));
+        self.set_cursor(index.unwrap_or(0));
+    }
+
+    /// Drop selections of entries missing from the full listing, including
+    /// entries hidden by a quick filter.
+    fn retain_existing_selection(&mut self) {
+        let entries = match self.quick_filter.as_ref() {
+            Some(filter) if filter.directory == self.nav.current_path() => &filter.all_entries,
+            _ => &self.entries,
+        };
+        self.selection.retain_existing(entries);
+    }
+
+    /// Show an entry renamed outside the app under its new path, keeping it
+    /// selected.
+    fn rename_entry(&mut self, from: &Path, to: &Path) {
+        let filtered = self.quick_filter.as_mut().map(|f| &mut f.all_entries);
+        for entries in [Some(&mut self.entries), filtered].into_iter().flatten() {
+            if let Some(entry) = entries.iter_mut().find(|e| e.path == from) {
+                entry.path = to.to_path_buf();
+                entry.name = to.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            }
+        }
+        self.selection.rename(from, to);
+    }
+
+    fn show_entries(&mut self, entries: Vec<EntryMeta>) {
         self.entries = entries;
         self.selection.set_entry_count(self.entries.len());
         // Ensure cursor is within bounds
@@ -248,37 +854,191 @@ impl App {
     pub fn new(left_path: PathBuf, right_path: PathBuf, event_tx: mpsc::UnboundedSender<Event>) -> Self {
         // Load config or use defaults
         let config = Config::load().unwrap_or_default();
+        let visit_history = VisitHistory::load().unwrap_or_else(|e| {
+            warn!("Failed to load visit history: {}", e);
+            VisitHistory::default()
+        });
+        let baskets = Baskets::load().unwrap_or_else(|e| {
+            warn!("Failed to load baskets: {}", e);
+            Baskets::default()
+        });
+        let last_basket = baskets.iter().last().map_or("Basket", |b| b.name.as_str()).to_string();
         let favorites = config.favorites.clone();
+        let general = &config.general;
+        let sort = SortSpec {
+            field: CoreSortField::from_key(&general.default_sort_field).unwrap_or_default(),
+            order: if general.default_sort_ascending {
+                SortOrder::Ascending
+            } else {
+                SortOrder::Descending
+            },
+            directories_first: general.sort_directories_first,
+            dirs_by_computed_size: general.sort_dirs_by_computed_size,
+            natural: general.natural_sort,
+            collation: general.sort_collation,
+            ..SortSpec::default()
+        };
 
         // Load drives
-        let drives = zmanager_core::list_drives().unwrap_or_default();
+        let devices = Arc::new(MtpFileSystem::default());
+        let drives = drives_with_devices(&devices);
+
+        let (mut keymap, problems) = Keymap::from_config(&config.keybindings);
+        let mut dialog = (!problems.is_empty())
+            .then(|| Dialog::error("Invalid keybindings", problems.join("; ")));
+
+        let (plugins, mut problems) = Plugins::load();
+        for (index, command) in plugins.commands().iter().enumerate() {
+            if let Some(key) = &command.key {
+                let chords = std::slice::from_ref(key);
+                problems.extend(keymap.bind(Action::Plugin(index), &command.name, chords));
+            }
+        }
+        if !problems.is_empty() {
+            dialog.get_or_insert_with(|| Dialog::error("Invalid plugins", problems.join("; ")));
+        }
+
+        match Theme::load(&config.appearance.theme) {
+            Ok(theme) => Styles::set_theme(theme),
+            Err(e) => {
+                warn!("Failed to load theme: {}", e);
+                dialog.get_or_insert_with(|| Dialog::error("Invalid theme", e.to_string()));
+            }
+        }
 
         Self {
             should_quit: false,
             left: PaneState::new(left_path),
             right: PaneState::new(right_path),
             active_pane: Pane::default(),
-            sort: SortSpec::default(),
-            filter: FilterSpec::default(),
+            sort,
+            filter: FilterSpec::new().with_system(config.general.show_system),
             show_hidden: false,
-            dialog: None,
+            dialog,
             pending_operation: None,
             view_mode: ViewMode::default(),
             jobs: Vec::new(),
             jobs_list_state: ListState::default(),
+            job_details: HashMap::new(),
+            show_job_details: false,
+            reports_dir: ReportStorage::default_dir(),
+            report_history: None,
             conflict_modal: None,
-            status_message: None,
+            location_bar: None,
+            path_history: Vec::new(),
+            visit_history,
+            last_visited: None,
+            baskets,
+            last_basket,
+            remote: Arc::new(RemoteFileSystems::new()),
+            devices,
+            history_view: None,
+            conflict_queue: VecDeque::new(),
+            status: StatusQueue::new(),
+            terminal_focused: true,
+            bell_pending: false,
             sidebar_visible: false,
             sidebar_state: SidebarState::new(),
             favorites,
             drives,
+            drive_menu: None,
+            drive_details: None,
             show_help: false,
             properties: None,
+            security: None,
+            multi_properties: None,
+            properties_editor: None,
+            filter_builder: None,
+            foreground_job: None,
+            checksums: None,
+            compare: None,
+            convert: None,
+            open_with: None,
+            copy_menu: None,
+            context_menu: None,
+            viewer: None,
+            text_editor: None,
             config,
+            keymap,
+            plugins,
+            pending_count: None,
+            dir_sizes: HashMap::new(),
+            dir_size_slots: Arc::new(Semaphore::new(DIRECTORY_SIZE_JOBS)),
+            duplicate_index: Arc::new(Mutex::new(None)),
+            duplicate_scan: None,
+            size_jobs: HashMap::new(),
+            multi_properties_job: None,
+            next_multi_properties: 0,
+            viewer_search: None,
+            next_viewer_search: 0,
+            checksum_job: None,
+            compare_job: None,
+            convert_job: None,
+            scheduler: None,
+            unfinished_transfers: Vec::new(),
+            next_listing: 0,
             event_tx,
         }
     }
 
+    /// Restore the directories, sort, hidden-file setting and active pane
+    /// saved by the last run, unless disabled in the config.
+    ///
+    /// Saved directories that no longer exist are skipped. Call before the
+    /// panes are first loaded.
+    pub fn restore_session(&mut self) {
+        if !self.config.general.restore_session {
+            return;
+        }
+        let Some(session) = self.config.session.clone() else {
+            return;
+        };
+
+        for (dir, pane) in [
+            (session.last_left_dir, &mut self.left),
+            (session.last_right_dir, &mut self.right),
+        ] {
+            if let Some(dir) = dir.filter(|dir| dir.is_dir()) {
+                *pane = PaneState::new(dir);
+            }
+        }
+        if let Some(sort) = &session.last_sort {
+            sort.apply_to(&mut self.sort);
+        }
+        if let Some(show_hidden) = session.show_hidden {
+            self.show_hidden = show_hidden;
+        }
+        if session.right_pane_active {
+            self.active_pane = Pane::Right;
+        }
+        self.path_history = session.path_history;
+        debug!("Session restored");
+    }
+
+    /// The session state to save for the next run.
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            last_left_dir: Some(self.left.nav.current_path().to_path_buf()),
+            last_right_dir: Some(self.right.nav.current_path().to_path_buf()),
+            last_sort: Some(SortSettings::from_spec(&self.sort)),
+            show_hidden: Some(self.show_hidden),
+            right_pane_active: self.active_pane == Pane::Right,
+            path_history: self.path_history.clone(),
+            ..self.config.session.clone().unwrap_or_default()
+        }
+    }
+
+    /// Save the session state to the config file, unless disabled.
+    pub fn save_session(&mut self) {
+        if !self.config.general.restore_session {
+            return;
+        }
+        self.config.session = Some(self.session_state());
+        if let Err(e) = self.config.save() {
+            warn!("Failed to save session: {}", e);
+        }
+    }
+
     /// Get the active pane state.
     pub fn active(&self) -> &PaneState {
         match self.active_pane {
@@ -295,8 +1055,23 @@ impl App {
         }
     }
 
+    /// Get a pane's state.
+    fn pane(&self, pane: Pane) -> &PaneState {
+        match pane {
+            Pane::Left => &self.left,
+            Pane::Right => &self.right,
+        }
+    }
+
+    /// Get a pane's state mutably.
+    fn pane_mut(&mut self, pane: Pane) -> &mut PaneState {
+        match pane {
+            Pane::Left => &mut self.left,
+            Pane::Right => &mut self.right,
+        }
+    }
+
     /// Get the inactive pane state.
-    #[allow(dead_code)]
     pub fn inactive(&self) -> &PaneState {
         match self.active_pane {
             Pane::Left => &self.right,
@@ -311,6 +1086,12 @@ impl App {
 
     /// Handle an action.
     pub fn handle_action(&mut self, action: Action) -> ZResult<()> {
+        // A count prefix only applies to the action typed right after it
+        let count = self.pending_count.take();
+        if count.is_some() && !matches!(action, Action::QuickJump(_)) {
+            self.clear_status();
+        }
+
         match action {
             Action::Quit => {
                 self.should_quit = true;
@@ -333,11 +1114,21 @@ impl App {
             Action::GoLast => {
                 self.active_mut().go_last();
             }
+            Action::NextSameExt => {
+                if !self.active_mut().next_same_ext() {
+                    self.set_status("No other entries of this type", false);
+                }
+            }
+            Action::PrevSameExt => {
+                if !self.active_mut().prev_same_ext() {
+                    self.set_status("No other entries of this type", false);
+                }
+            }
             Action::Enter | Action::Right => {
                 self.enter_directory()?;
             }
             Action::GoParent | Action::Left => {
-                self.go_parent()?;
+                self.go_up_levels(count.unwrap_or(1))?;
             }
             Action::GoBack => {
                 self.go_back()?;
@@ -345,9 +1136,28 @@ impl App {
             Action::GoForward => {
                 self.go_forward()?;
             }
+            Action::GoToPath => {
+                self.open_location_bar();
+            }
+            Action::DirectoryHistory => {
+                self.open_history_view();
+            }
             Action::SwitchPane => {
                 self.switch_pane();
             }
+            Action::NewTab => {
+                self.active_mut().open_tab();
+                self.refresh_active()?;
+            }
+            Action::CloseTab => {
+                self.close_tab()?;
+            }
+            Action::NextTab => {
+                self.switch_tab(true)?;
+            }
+            Action::PrevTab => {
+                self.switch_tab(false)?;
+            }
             Action::ToggleSelect => {
                 self.active_mut().toggle_select();
                 self.active_mut().move_down();
@@ -359,35 +1169,138 @@ impl App {
                 self.active_mut().invert_selection();
             }
             Action::ClearSelection => {
-                self.active_mut().clear_selection();
+                // Esc drops a quick filter before it clears the selection
+                let pane = self.active_mut();
+                if pane.quick_filter.is_some() {
+                    pane.clear_quick_filter();
+                } else {
+                    pane.clear_selection();
+                }
+            }
+            Action::SelectPattern => {
+                self.pending_operation = Some(PendingOperation::SelectPattern);
+                self.dialog = Some(Dialog::input("Select", "Pattern (e.g. *.jpg; *.png):", "*"));
+            }
+            Action::DeselectPattern => {
+                self.pending_operation = Some(PendingOperation::DeselectPattern);
+                self.dialog = Some(Dialog::input("Deselect", "Pattern (e.g. *.jpg; *.png):", "*"));
+            }
+            Action::SelectSameExt => {
+                let matched = self.active_mut().select_same_extension();
+                self.report_selected(matched);
+            }
+            Action::SelectNewer => {
+                let matched = self.active_mut().select_newer();
+                self.report_selected(matched);
             }
             Action::Refresh => {
+                // Sizes and content hashes may be stale after an explicit refresh.
+                // A scan in flight holds the index; it is rebuilt next time anyway.
+                self.dir_sizes.clear();
+                if let Ok(mut index) = self.duplicate_index.try_lock() {
+                    *index = None;
+                }
                 self.refresh_active()?;
             }
             Action::Delete => {
-                self.initiate_delete();
+                self.initiate_delete(false);
+            }
+            Action::DeletePermanent => {
+                self.initiate_delete(true);
             }
             Action::Rename => {
                 self.initiate_rename();
             }
+            Action::RenameFromClipboard => {
+                self.initiate_rename_from_clipboard();
+            }
             Action::MakeDir => {
                 self.initiate_mkdir();
             }
+            Action::NewFile => {
+                self.initiate_new_file();
+            }
             Action::Copy => {
                 self.initiate_copy();
             }
             Action::Move => {
                 self.initiate_move();
             }
+            Action::CopyToPrevious => {
+                self.initiate_copy_to_previous();
+            }
+            Action::MoveToPrevious => {
+                self.initiate_move_to_previous();
+            }
             Action::ToggleHidden => {
                 self.toggle_hidden();
             }
+            Action::InvertFilter => {
+                self.toggle_filter_inverted()?;
+            }
+            Action::FilterBuilder => {
+                self.show_filter_builder();
+            }
+            Action::ToggleFlatView => {
+                self.toggle_flat_view()?;
+            }
+            Action::FindDuplicates => {
+                self.find_duplicates();
+            }
             Action::SortMenu => {
                 self.show_sort_menu();
             }
             Action::Open => {
                 self.open_current()?;
             }
+            Action::OpenWith => {
+                self.show_open_with();
+            }
+            Action::OpenSelected => {
+                self.initiate_open_selected()?;
+            }
+            Action::Run => {
+                self.run_current();
+            }
+            Action::OpenTerminal => {
+                self.open_terminal();
+            }
+            Action::RevealInExplorer => {
+                self.reveal_current();
+            }
+            Action::CopyPath => {
+                self.copy_targets_as(CopyFormat::Paths);
+            }
+            Action::CopyName => {
+                self.copy_targets_as(CopyFormat::Names);
+            }
+            Action::CopyMenu => {
+                self.show_copy_menu();
+            }
+            Action::ContextMenu => {
+                self.show_context_menu();
+            }
+            Action::Edit => {
+                self.edit_current();
+            }
+            Action::View => {
+                self.view_current();
+            }
+            Action::Compress => {
+                self.initiate_compress();
+            }
+            Action::Extract => {
+                self.initiate_extract();
+            }
+            Action::AddToBasket => {
+                self.initiate_add_to_basket();
+            }
+            Action::ShowBasket => {
+                self.initiate_show_basket();
+            }
+            Action::RemoveFromBasket => {
+                self.remove_from_basket();
+            }
             Action::ToggleTransfers => {
                 self.toggle_transfers_view();
             }
@@ -400,56 +1313,118 @@ impl App {
             Action::CancelJob => {
                 self.cancel_selected_job();
             }
+            Action::CycleSpeedLimit => {
+                self.cycle_selected_speed_limit();
+            }
+            Action::ExportReport => {
+                self.export_selected_job_report();
+            }
+            Action::RetryFailed => {
+                self.retry_selected_job();
+            }
             Action::ToggleSidebar => {
                 self.toggle_sidebar();
             }
-            Action::AddFavorite => {
+            Action::DriveMenuLeft => {
+                self.show_drive_menu(Pane::Left);
+            }
+            Action::DriveMenuRight => {
+                self.show_drive_menu(Pane::Right);
+            }
+            Action::AddFavorite => {
                 self.add_current_to_favorites();
             }
             Action::QuickJump(num) => {
-                self.quick_jump_to_favorite(num);
+                if self.sidebar_visible {
+                    self.quick_jump_to_favorite(num);
+                } else {
+                    self.push_count_digit(count, num);
+                }
             }
             Action::Properties => {
                 self.show_properties();
             }
+            Action::TakeOwnership => {
+                self.confirm_take_ownership();
+            }
+            Action::CalculateSize => {
+                self.calculate_sizes();
+            }
+            Action::Checksum => {
+                self.show_checksums();
+            }
+            Action::Compare => {
+                self.show_compare();
+            }
+            Action::ConvertText => {
+                self.show_convert();
+            }
             Action::Help => {
                 self.show_help = true;
             }
-            // Not implemented yet
-            Action::FilterMenu
-            | Action::None => {}
+            Action::FilterMenu => {
+                self.active_mut().start_quick_filter();
+            }
+            Action::Plugin(index) => {
+                self.run_plugin_command(index);
+            }
+            Action::None => {}
         }
         Ok(())
     }
 
-    /// Enter the directory at cursor.
+    /// Enter the directory (or browsable archive) at cursor.
+    ///
+    /// With `auto_descend_single_child` enabled, chains of single-subdirectory
+    /// folders are skipped in one step, recorded as a single history entry.
     fn enter_directory(&mut self) -> ZResult<()> {
+        let auto_descend = self.config.general.auto_descend_single_child;
         let pane = self.active_mut();
         if let Some(entry) = pane.current_entry().cloned() {
-            if entry.kind.is_directory() {
-                pane.nav.navigate_to(&entry.path);
+            let is_archive = entry.is_file() && is_browsable_archive(&entry.path);
+            if entry.kind.is_directory() || is_archive {
+                let target = if auto_descend {
+                    descend_single_children(&entry.path)
+                } else {
+                    entry.path
+                };
+                pane.nav.navigate_to(&target);
                 pane.selection.clear();
                 pane.set_cursor(0);
                 // Request directory refresh
-                let _ = self.event_tx.send(Event::DirectoryChanged(entry.path));
+                let _ = self.event_tx.send(Event::DirectoryChanged(target));
             }
         }
         Ok(())
     }
 
-    /// Go to parent directory.
-    fn go_parent(&mut self) -> ZResult<()> {
+    /// Go up `levels` parent directories, stopping at the drive root.
+    ///
+    /// A basket has no parent, so going up from one goes back instead.
+    fn go_up_levels(&mut self, levels: usize) -> ZResult<()> {
+        if split_basket_path(self.active().nav.current_path()).is_some() {
+            return self.go_back();
+        }
         let pane = self.active_mut();
-        if let Some(parent) = pane.nav.current_path().parent() {
-            let parent = parent.to_path_buf();
-            pane.nav.navigate_to(&parent);
+        if let Some(target) = pane.nav.go_up_levels(levels).map(|p| p.to_path_buf()) {
             pane.selection.clear();
             pane.set_cursor(0);
-            let _ = self.event_tx.send(Event::DirectoryChanged(parent));
+            let _ = self.event_tx.send(Event::DirectoryChanged(target));
         }
         Ok(())
     }
 
+    /// Append a digit to the count prefix.
+    fn push_count_digit(&mut self, count: Option<usize>, digit: u8) {
+        // A leading zero is not a count
+        if count.is_none() && digit == 0 {
+            return;
+        }
+        let count = (count.unwrap_or(0) * 10 + digit as usize).min(MAX_COUNT_PREFIX);
+        self.pending_count = Some(count);
+        self.set_status(format!("Count: {}", count), false);
+    }
+
     /// Go back in history.
     fn go_back(&mut self) -> ZResult<()> {
         let pane = self.active_mut();
@@ -476,6 +1451,31 @@ impl App {
         Ok(())
     }
 
+    /// Close the active pane's current tab; the last tab stays open.
+    fn close_tab(&mut self) -> ZResult<()> {
+        if self.active_mut().close_tab() {
+            self.refresh_active()
+        } else {
+            self.set_status("Cannot close the last tab", true);
+            Ok(())
+        }
+    }
+
+    /// Show the next (or previous) tab of the active pane, reloading its
+    /// listing since it may have changed while hidden.
+    fn switch_tab(&mut self, forward: bool) -> ZResult<()> {
+        let pane = self.active_mut();
+        if pane.tabs().len() < 2 {
+            return Ok(());
+        }
+        if forward {
+            pane.next_tab();
+        } else {
+            pane.prev_tab();
+        }
+        self.refresh_active()
+    }
+
     /// Refresh the active pane.
     fn refresh_active(&mut self) -> ZResult<()> {
         let path = self.active().nav.current_path().to_path_buf();
@@ -483,514 +1483,6336 @@ impl App {
         Ok(())
     }
 
-    /// Update entries for a pane.
-    pub fn update_entries(&mut self, pane: Pane, entries: Vec<EntryMeta>) {
-        let pane_state = match pane {
-            Pane::Left => &mut self.left,
-            Pane::Right => &mut self.right,
-        };
-        pane_state.set_entries(entries);
+    /// The provider listing `path` when it isn't on a local disk: a
+    /// server or a device.
+    pub fn directory_provider(&self, path: &Path) -> Option<Arc<dyn DirectoryProvider>> {
+        if self.remote.handles(path) {
+            Some(self.remote.clone())
+        } else if self.devices.handles(path) {
+            Some(self.devices.clone())
+        } else {
+            None
+        }
     }
 
-    // ========== File Operations ==========
-
-    /// Initiate delete operation (shows confirmation dialog).
-    fn initiate_delete(&mut self) {
-        let files = self.get_operation_targets();
-        if files.is_empty() {
-            return;
+    /// Directories to watch for changes: the ones the panes show.
+    ///
+    /// Hidden tabs reload when shown, so they aren't watched. Folders inside
+    /// archives, baskets and folders on servers or devices can't be watched
+    /// and are left out.
+    pub fn watch_locations(&self) -> Vec<PathBuf> {
+        let mut locations: Vec<PathBuf> = Vec::new();
+        for pane in [&self.left, &self.right] {
+            let path = pane.nav.current_path();
+            let is_virtual = split_archive_path(path).is_some()
+                || split_basket_path(path).is_some()
+                || is_remote_path(path)
+                || is_device_path(path);
+            if !is_virtual && !locations.iter().any(|p| p == path) {
+                locations.push(path.to_path_buf());
+            }
         }
+        locations
+    }
 
-        let count = files.len();
-        let message = if count == 1 {
-            format!("Delete '{}'?", files[0].file_name().unwrap_or_default().to_string_lossy())
-        } else {
-            format!("Delete {} items?", count)
+    /// Follow entries of `pane` renamed within its directory, so the cursor
+    /// and selection stay on them when the listing is refreshed.
+    ///
+    /// `directory` and `renames` come from the watcher, which reports
+    /// canonical paths; they are mapped onto the pane's own path by name.
+    pub fn follow_renames(&mut self, pane: Pane, directory: &Path, renames: &[(PathBuf, PathBuf)]) {
+        let state = self.pane_mut(pane);
+        let current = state.nav.current_path().to_path_buf();
+        let local = |path: &Path| match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if parent == directory => Some(current.join(name)),
+            _ => None,
         };
-
-        self.pending_operation = Some(PendingOperation::Delete(files));
-        self.dialog = Some(Dialog::confirm("Confirm Delete", message));
+        for (from, to) in renames {
+            // Entries moved elsewhere are dropped from the selection on refresh
+            if let (Some(from), Some(to)) = (local(from), local(to)) {
+                state.rename_entry(&from, &to);
+            }
+        }
     }
 
-    /// Initiate rename operation (shows input dialog).
-    fn initiate_rename(&mut self) {
-        let pane = self.active();
-        if let Some(entry) = pane.current_entry() {
-            let current_name = entry.path.file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-
-            self.pending_operation = Some(PendingOperation::Rename(entry.path.clone()));
-            self.dialog = Some(Dialog::input("Rename", "New name:", current_name));
+    /// Take a watcher event about the directory one or both panes show.
+    ///
+    /// Renames are followed, and a pane whose directory was deleted moves to
+    /// its nearest existing ancestor. The watcher matches the panes' paths to
+    /// the canonical path of the event even once the directory is gone.
+    /// Returns the panes whose directory needs reloading.
+    pub fn apply_watch_event(
+        &mut self,
+        watcher: &DirectoryWatcher,
+        event: &WatchEvent,
+    ) -> Vec<Pane> {
+        let mut changed = Vec::new();
+        for pane in [Pane::Left, Pane::Right] {
+            let current = self.pane(pane).nav.current_path();
+            if watcher.canonical_path(current).as_ref() != Some(&event.directory) {
+                continue;
+            }
+            self.follow_renames(pane, &event.directory, &event.renames);
+            self.recover_missing_directory(pane);
+            changed.push(pane);
         }
+        changed
     }
 
-    /// Initiate mkdir operation (shows input dialog).
-    fn initiate_mkdir(&mut self) {
-        self.pending_operation = Some(PendingOperation::MakeDir);
-        self.dialog = Some(Dialog::input("New Folder", "Folder name:", ""));
+    /// Move `pane` to its nearest existing ancestor if its directory is gone.
+    ///
+    /// Stale entries are dropped so nothing operates on vanished paths, and the
+    /// missing directory leaves the pane's history. Returns the directory the
+    /// pane moved to, or `None` if the current directory still exists (or
+    /// nothing above it does).
+    pub fn recover_missing_directory(&mut self, pane: Pane) -> Option<PathBuf> {
+        let state = self.pane_mut(pane);
+        let missing = state.nav.current_path().to_path_buf();
+        if missing.is_dir() {
+            return None;
+        }
+        let ancestor = missing.ancestors().skip(1).find(|p| p.is_dir())?.to_path_buf();
+
+        state.nav.replace_missing(&ancestor);
+        state.selection.clear();
+        state.set_entries(Vec::new());
+        state.set_cursor(0);
+        self.set_status(
+            format!("{} no longer exists; moved to {}", missing.display(), ancestor.display()),
+            true,
+        );
+        Some(ancestor)
     }
 
-    /// Initiate copy operation.
-    fn initiate_copy(&mut self) {
-        let files = self.get_operation_targets();
-        if files.is_empty() {
-            return;
+    /// Update entries for a pane.
+    pub fn update_entries(&mut self, pane: Pane, mut entries: Vec<EntryMeta>) {
+        for entry in entries.iter_mut().filter(|e| e.is_directory()) {
+            entry.computed_size = self.dir_sizes.get(&entry.path).copied();
+        }
+        if self.sort.interleaves_directories() {
+            self.sort.sort(&mut entries);
+            self.request_directory_sizes(pane, &entries);
         }
 
-        let destination = self.inactive().nav.current_path().to_path_buf();
-        let count = files.len();
-        let message = if count == 1 {
-            format!("Copy '{}' to other pane?", files[0].file_name().unwrap_or_default().to_string_lossy())
-        } else {
-            format!("Copy {} items to other pane?", count)
+        self.pane_mut(pane).set_entries(entries);
+    }
+
+    /// Show a listing from [`zmanager_core::list_directory_streamed`] of
+    /// `path` in `pane`, replacing any listing still streaming in.
+    ///
+    /// Entries of another directory are cleared right away; on a refresh the
+    /// old entries stay until the first batch arrives, and the cursor and
+    /// selection stay on the same entries where they still exist. Batches come
+    /// back as [`Event::ListingBatch`] and the end as
+    /// [`Event::ListingFinished`], or [`Event::ListingFailed`] if the directory
+    /// could not be read. Does nothing outside a Tokio runtime.
+    pub fn stream_listing(
+        &mut self,
+        pane: Pane,
+        path: &Path,
+        mut batches: mpsc::Receiver<ZResult<Vec<EntryMeta>>>,
+    ) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
         };
 
-        self.pending_operation = Some(PendingOperation::Copy(files, destination));
-        self.dialog = Some(Dialog::confirm("Confirm Copy", message));
+        let id = self.next_listing;
+        self.next_listing += 1;
+        let tx = self.event_tx.clone();
+        let task = runtime.spawn(async move {
+            while let Some(batch) = batches.recv().await {
+                let event = match batch {
+                    Ok(entries) => Event::ListingBatch(pane, id, entries),
+                    Err(e) => {
+                        let _ = tx.send(Event::ListingFailed(pane, id, e.to_string()));
+                        return;
+                    }
+                };
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(Event::ListingFinished(pane, id));
+        });
+
+        let state = self.pane_mut(pane);
+        if let Some(task) = state.loading.take().and_then(|loading| loading.task) {
+            task.abort();
+        }
+        // Leaving a directory listed flat ends its flat view, and the sizes
+        // of its folders are no longer needed
+        state.flat_view.take_if(|dir| dir != path);
+        if state.dir_sizes.as_ref().is_some_and(|sizes| sizes.directory != path) {
+            state.cancel_directory_sizes();
+        }
+        let flat = state.flat_view.is_some();
+        let stale = state.entries.first().is_some_and(|e| {
+            if flat {
+                !e.path.starts_with(path)
+            } else {
+                e.path.parent() != Some(path)
+            }
+        });
+        if stale {
+            state.set_entries(Vec::new());
+        }
+        let cursor = state.current_entry().map(|e| e.path.clone());
+        state.loading = Some(Loading {
+            id,
+            received: 0,
+            task: Some(task.abort_handle()),
+            cursor,
+        });
     }
 
-    /// Initiate move operation.
-    fn initiate_move(&mut self) {
-        let files = self.get_operation_targets();
-        if files.is_empty() {
+    /// Add a batch of a streamed listing to `pane`, keeping it sorted.
+    ///
+    /// Batches of a listing that has since been replaced are dropped.
+    pub fn apply_listing_batch(&mut self, pane: Pane, id: u64, mut batch: Vec<EntryMeta>) {
+        if self.pane_mut(pane).loading.as_ref().is_none_or(|l| l.id != id) {
             return;
         }
 
-        let destination = self.inactive().nav.current_path().to_path_buf();
-        let count = files.len();
-        let message = if count == 1 {
-            format!("Move '{}' to other pane?", files[0].file_name().unwrap_or_default().to_string_lossy())
-        } else {
-            format!("Move {} items to other pane?", count)
-        };
+        for entry in batch.iter_mut().filter(|e| e.is_directory()) {
+            entry.computed_size = self.dir_sizes.get(&entry.path).copied();
+        }
+        if self.sort.interleaves_directories() {
+            self.request_directory_sizes(pane, &batch);
+        }
 
-        self.pending_operation = Some(PendingOperation::Move(files, destination));
-        self.dialog = Some(Dialog::confirm("Confirm Move", message));
+        let sort = self.sort;
+        let state = self.pane_mut(pane);
+        let Some(loading) = state.loading.as_mut() else {
+            return;
+        };
+        // The first batch replaces what the pane showed before
+        let first = loading.received == 0;
+        loading.received += batch.len();
+        // Keep the cursor on its entry as batches are sorted in
+        let cursor = match loading.cursor.clone() {
+            Some(path) => Some(path),
+            None if !first => state.current_entry().map(|e| e.path.clone()),
+            None => None,
+        };
+        let mut entries = if first { Vec::new() } else { state.take_entries() };
+        entries.extend(batch);
+        // Already sorted apart from the new batch, which sorts quickly
+        sort.sort(&mut entries);
+        state.set_entries(entries);
+
+        let index = cursor.and_then(|c| state.entries.iter().position(|e| e.path == c));
+        if let Some(index) = index {
+            state.set_cursor(index);
+            if let Some(loading) = state.loading.as_mut() {
+                loading.cursor = None;
+            }
+        }
     }
 
-    /// Get the files to operate on (selection or current).
-    fn get_operation_targets(&self) -> Vec<PathBuf> {
-        let pane = self.active();
-        let selected: Vec<PathBuf> = pane.entries
-            .iter()
-            .filter(|e| pane.selection.is_selected(&e.path))
-            .map(|e| e.path.clone())
-            .collect();
-
-        if selected.is_empty() {
-            // Use the current entry if no selection
-            pane.current_entry()
-                .map(|e| vec![e.path.clone()])
-                .unwrap_or_default()
-        } else {
-            selected
+    /// Mark the streamed listing `id` of `pane` as complete.
+    pub fn finish_listing(&mut self, pane: Pane, id: u64) {
+        let state = self.pane_mut(pane);
+        if let Some(loading) = state.loading.take_if(|l| l.id == id) {
+            // An empty directory sends no batches
+            if loading.received == 0 {
+                state.set_entries(Vec::new());
+            }
+            state.retain_existing_selection();
         }
     }
 
-    /// Toggle hidden files visibility.
-    fn toggle_hidden(&mut self) {
-        self.show_hidden = !self.show_hidden;
-        // Refresh both panes to apply the filter
-        let left_path = self.left.nav.current_path().to_path_buf();
-        let right_path = self.right.nav.current_path().to_path_buf();
-        let _ = self.event_tx.send(Event::DirectoryChanged(left_path));
-        let _ = self.event_tx.send(Event::DirectoryChanged(right_path));
+    /// Give up on the streamed listing `id` of `pane`, which could not be read.
+    ///
+    /// The pane goes back to where it came from if it can. Returns the
+    /// directory to list instead, if any.
+    pub fn fail_listing(&mut self, pane: Pane, id: u64, message: String) -> Option<PathBuf> {
+        let state = self.pane_mut(pane);
+        state.loading.take_if(|l| l.id == id)?;
+
+        let failed = state.nav.current_path().to_path_buf();
+        state.selection.clear();
+        state.set_entries(Vec::new());
+        state.set_cursor(0);
+        let back = state.nav.go_back().map(Path::to_path_buf);
+        self.set_status(format!("Cannot open {}: {}", failed.display(), message), true);
+        back
     }
 
-    /// Show the sort menu.
-    fn show_sort_menu(&mut self) {
-        let current = match self.sort.field {
-            CoreSortField::Name => SortField::Name,
-            CoreSortField::Size => SortField::Size,
-            CoreSortField::Modified => SortField::Modified,
-            CoreSortField::Extension => SortField::Extension,
-            CoreSortField::Kind => SortField::Kind,
-            _ => SortField::Name,
+    /// Start background size calculations for folders listed in `pane`
+    /// without a known size.
+    ///
+    /// Only [`DIRECTORY_SIZE_JOBS`] run at once, and they are cancelled when
+    /// the pane leaves the directory. Results come back as
+    /// [`Event::DirectorySizeComputed`]. Does nothing outside a Tokio runtime.
+    fn request_directory_sizes(&mut self, pane: Pane, entries: &[EntryMeta]) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
         };
-        self.dialog = Some(Dialog::sort_menu(current));
-    }
 
-    /// Open the current file/directory.
-    fn open_current(&mut self) -> ZResult<()> {
-        let pane = self.active();
-        if let Some(entry) = pane.current_entry() {
-            if entry.kind.is_directory() {
-                // Navigate into directory
-                self.enter_directory()?;
-            } else {
-                // Open file with default application
-                #[cfg(windows)]
-                {
-                    use std::process::Command;
-                    let _ = Command::new("cmd")
-                        .args(["/C", "start", "", entry.path.to_string_lossy().as_ref()])
-                        .spawn();
-                }
+        let slots = Arc::clone(&self.dir_size_slots);
+        let tx = self.event_tx.clone();
+        let state = self.pane_mut(pane);
+        let directory = state.nav.current_path().to_path_buf();
+        let sizes = state.directory_sizes(&directory);
+        for entry in entries.iter().filter(|e| e.is_directory() && e.computed_size.is_none()) {
+            if !sizes.pending.insert(entry.path.clone()) {
+                continue;
             }
+            let job = Job::new(JobKind::CalculateSize {
+                path: entry.path.clone(),
+            });
+            let cancel = sizes.cancel.clone();
+            runtime.spawn(run_directory_size_job(job, cancel, Arc::clone(&slots), tx.clone()));
         }
-        Ok(())
     }
 
-    /// Apply the sort field selection from the menu.
-    pub fn apply_sort(&mut self, field: SortField) {
-        self.sort.field = match field {
-            SortField::Name => CoreSortField::Name,
-            SortField::Size => CoreSortField::Size,
-            SortField::Modified => CoreSortField::Modified,
-            SortField::Extension => CoreSortField::Extension,
-            SortField::Kind => CoreSortField::Kind,
-        };
-        // Refresh to re-sort
-        let left_path = self.left.nav.current_path().to_path_buf();
-        let right_path = self.right.nav.current_path().to_path_buf();
-        let _ = self.event_tx.send(Event::DirectoryChanged(left_path));
-        let _ = self.event_tx.send(Event::DirectoryChanged(right_path));
-    }
+    /// Calculate the size of the selected folders (or the one under the
+    /// cursor). Pressing again cancels the calculations in flight.
+    fn calculate_sizes(&mut self) {
+        if !self.size_jobs.is_empty() {
+            for (_, token) in self.size_jobs.drain() {
+                token.cancel();
+            }
+            self.set_status("Size calculation cancelled", false);
+            return;
+        }
 
-    /// Execute pending delete operation.
-    pub fn execute_delete(&mut self, files: Vec<PathBuf>) {
-        // Send delete event to be handled asynchronously
-        let _ = self.event_tx.send(Event::ExecuteDelete(files));
+        let folders: Vec<PathBuf> =
+            self.get_operation_targets().into_iter().filter(|p| p.is_dir()).collect();
+        if folders.is_empty() {
+            self.set_status("No folders to calculate", false);
+            return;
+        }
+        let count = folders.len();
+        for folder in folders {
+            self.start_size_job(folder);
+        }
+        self.set_status(
+            format!("Calculating size of {} folder(s) (S to cancel)...", count),
+            false,
+        );
     }
 
-    /// Execute pending rename operation.
-    pub fn execute_rename(&mut self, old_path: PathBuf, new_name: String) {
-        let new_path = old_path.parent()
-            .map(|p| p.join(&new_name))
-            .unwrap_or_else(|| PathBuf::from(&new_name));
-        let _ = self.event_tx.send(Event::ExecuteRename(old_path, new_path));
-    }
+    /// Start a size job for `path` unless one is already running.
+    ///
+    /// Running totals come back as [`Event::FolderSizeProgress`] and the
+    /// result as [`Event::FolderSizeFinished`]. Does nothing outside a Tokio
+    /// runtime.
+    fn start_size_job(&mut self, path: PathBuf) {
+        if self.size_jobs.contains_key(&path) {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
 
-    /// Execute pending mkdir operation.
-    pub fn execute_mkdir(&mut self, name: String) {
-        let parent = self.active().nav.current_path().to_path_buf();
-        let new_path = parent.join(&name);
-        let _ = self.event_tx.send(Event::ExecuteMkdir(new_path));
+        let job = Job::new(JobKind::CalculateSize { path: path.clone() });
+        self.size_jobs.insert(path, job.cancellation.clone());
+        runtime.spawn(run_size_job(job, self.event_tx.clone()));
     }
 
-    /// Execute pending copy operation.
-    pub fn execute_copy(&mut self, sources: Vec<PathBuf>, destination: PathBuf) {
-        let _ = self.event_tx.send(Event::ExecuteCopy(sources, destination));
+    /// Whether a size job is running for `path`.
+    pub fn is_calculating_size(&self, path: &Path) -> bool {
+        self.size_jobs.contains_key(path)
     }
 
-    /// Execute pending move operation.
-    pub fn execute_move(&mut self, sources: Vec<PathBuf>, destination: PathBuf) {
-        let _ = self.event_tx.send(Event::ExecuteMove(sources, destination));
+    /// Show the running totals of a size job in the properties panel.
+    pub fn apply_folder_size_progress(&mut self, path: PathBuf, stats: FolderStats) {
+        if let Some(props) = self.properties.as_mut().filter(|p| p.path == path) {
+            props.size = Some(stats.total_size);
+            props.file_count = Some(stats.file_count);
+            props.folder_count = Some(stats.folder_count);
+        }
     }
 
-    /// Show an error message dialog.
-    pub fn show_error(&mut self, title: impl Into<String>, message: impl Into<String>) {
-        self.dialog = Some(Dialog::error(title, message));
+    /// Record the result of a size job in the file lists and properties panel.
+    pub fn apply_folder_size_result(&mut self, path: PathBuf, result: Result<FolderStats, String>) {
+        self.size_jobs.remove(&path);
+        match result {
+            Ok(stats) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                self.set_status(format!("{}: {}", name, stats.summary()), false);
+                self.apply_folder_size_progress(path.clone(), stats.clone());
+                self.apply_directory_size(path, stats.total_size);
+            }
+            Err(message) => {
+                self.set_status(format!("Size calculation failed: {}", message), true);
+            }
+        }
     }
 
-    /// Show an info message dialog.
-    pub fn show_message(&mut self, title: impl Into<String>, message: impl Into<String>) {
-        self.dialog = Some(Dialog::message(title, message));
-    }
+    /// Start marking files in the active pane that are byte-identical to a file
+    /// anywhere under the other pane's directory. Pressing again cancels.
+    fn find_duplicates(&mut self) {
+        if let Some(token) = self.duplicate_scan.take() {
+            token.cancel();
+            self.set_status("Duplicate scan cancelled", false);
+            return;
+        }
 
-    /// Check if there's an active dialog.
-    pub fn has_dialog(&self) -> bool {
-        self.dialog.is_some()
-    }
+        let files: Vec<EntryMeta> =
+            self.active().entries.iter().filter(|e| e.is_file()).cloned().collect();
+        if files.is_empty() {
+            self.set_status("No files to check for duplicates", false);
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
 
-    /// Close the current dialog.
-    pub fn close_dialog(&mut self) {
-        self.dialog = None;
-        self.pending_operation = None;
+        let directory = self.active().nav.current_path().to_path_buf();
+        let other_root = self.inactive().nav.current_path().to_path_buf();
+        let token = CancellationToken::new();
+        self.duplicate_scan = Some(token.clone());
+        self.set_status(
+            format!("Scanning {} for duplicates (u to cancel)...", other_root.display()),
+            false,
+        );
+
+        let index = self.duplicate_index.clone();
+        let tx = self.event_tx.clone();
+        runtime.spawn_blocking(move || {
+            let result = match scan_for_duplicates(&index, &other_root, &files, &token) {
+                Ok(report) => Ok((
+                    report.matches.into_iter().map(|m| m.path).collect(),
+                    report.reclaimable_bytes,
+                )),
+                // The status was already updated when the scan was cancelled
+                Err(ZError::Cancelled) => return,
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(Event::DuplicateScanFinished(directory, result));
+        });
     }
 
-    // ========== Transfers View ==========
+    /// Select the duplicates found by a scan and report what they add up to.
+    pub fn apply_duplicate_scan(
+        &mut self,
+        directory: PathBuf,
+        result: Result<(Vec<PathBuf>, u64), String>,
+    ) {
+        self.duplicate_scan = None;
+        let (paths, reclaimable) = match result {
+            Ok(found) => found,
+            Err(message) => {
+                self.set_status(format!("Duplicate scan failed: {message}"), true);
+                return;
+            }
+        };
 
-    /// Toggle transfers view.
-    pub fn toggle_transfers_view(&mut self) {
-        self.view_mode = match self.view_mode {
-            ViewMode::Browser => ViewMode::Transfers,
-            ViewMode::Transfers => ViewMode::Browser,
+        let pane = if self.active().nav.current_path() == directory {
+            self.active_mut()
+        } else if self.inactive().nav.current_path() == directory {
+            match self.active_pane {
+                Pane::Left => &mut self.right,
+                Pane::Right => &mut self.left,
+            }
+        } else {
+            // The pane moved on while the scan was running
+            return;
         };
-        // Reset list selection when entering transfers view
-        if self.view_mode == ViewMode::Transfers {
-            self.jobs_list_state.select(Some(0));
+
+        pane.selection.clear();
+        for path in &paths {
+            pane.selection.add(path);
         }
-    }
 
-    /// Get the currently selected job ID (in transfers view).
-    pub fn selected_job(&self) -> Option<&JobInfo> {
-        self.jobs_list_state.selected()
-            .and_then(|i| self.jobs.get(i))
+        let message = if paths.is_empty() {
+            "No duplicates found".to_string()
+        } else {
+            format!(
+                "{} duplicate(s) found, {} reclaimable",
+                paths.len(),
+                format_size(reclaimable)
+            )
+        };
+        self.set_status(message, false);
     }
 
-    /// Pause the selected job.
-    pub fn pause_selected_job(&mut self) {
-        if self.view_mode != ViewMode::Transfers {
-            return;
+    /// Record a computed folder size and re-sort the panes showing it.
+    pub fn apply_directory_size(&mut self, path: PathBuf, size: u64) {
+        for pane in [&mut self.left, &mut self.right] {
+            if let Some(sizes) = pane.dir_sizes.as_mut() {
+                sizes.pending.remove(&path);
+            }
         }
-        if let Some(job) = self.selected_job() {
-            let _ = self.event_tx.send(Event::PauseJob(job.id.0));
+        self.dir_sizes.insert(path.clone(), size);
+
+        let sort = self.sort;
+        for pane in [&mut self.left, &mut self.right] {
+            // Entries hidden by a quick filter come back when it is cleared
+            if let Some(filter) = pane.quick_filter.as_mut() {
+                if let Some(entry) = filter.all_entries.iter_mut().find(|e| e.path == path) {
+                    entry.computed_size = Some(size);
+                    if sort.interleaves_directories() {
+                        sort.sort(&mut filter.all_entries);
+                    }
+                }
+            }
+
+            let Some(entry) = pane.entries.iter_mut().find(|e| e.path == path) else {
+                continue;
+            };
+            entry.computed_size = Some(size);
+
+            if sort.interleaves_directories() {
+                // Keep the cursor on the same entry while the order shifts
+                let current = pane.current_entry().map(|e| e.path.clone());
+                sort.sort(&mut pane.entries);
+                let index = current.and_then(|c| pane.entries.iter().position(|e| e.path == c));
+                if let Some(index) = index {
+                    pane.set_cursor(index);
+                }
+            }
         }
     }
 
-    /// Resume the selected job.
-    pub fn resume_selected_job(&mut self) {
-        if self.view_mode != ViewMode::Transfers {
+    // ========== File Operations ==========
+
+    /// Initiate a delete to the Recycle Bin, or a permanent one.
+    ///
+    /// Asks for confirmation first unless `confirm_delete` or
+    /// `confirm_permanent_delete` turns that off.
+    fn initiate_delete(&mut self, permanent: bool) {
+        let files = self.get_operation_targets();
+        if files.is_empty() {
             return;
         }
-        if let Some(job) = self.selected_job() {
-            let _ = self.event_tx.send(Event::ResumeJob(job.id.0));
-        }
-    }
 
-    /// Cancel the selected job.
-    pub fn cancel_selected_job(&mut self) {
-        if self.view_mode != ViewMode::Transfers {
+        let general = &self.config.general;
+        let confirm = if permanent {
+            general.confirm_permanent_delete
+        } else {
+            general.confirm_delete
+        };
+        if !confirm {
+            self.execute_delete(files, permanent);
             return;
         }
-        if let Some(job) = self.selected_job() {
-            let _ = self.event_tx.send(Event::CancelJob(job.id.0));
+
+        let target = match files.as_slice() {
+            [file] => format!("'{}'", file.file_name().unwrap_or_default().to_string_lossy()),
+            files => format!("{} items", files.len()),
+        };
+        let (title, message, operation) = if !permanent {
+            let message = format!("Move {target} to the Recycle Bin?");
+            ("Confirm Delete", message, PendingOperation::Delete(files))
+        } else if self.config.operations.wipe_permanent_deletes {
+            let message = format!("Wipe and permanently delete {target}? This cannot be undone.");
+            ("Confirm Wipe", message, PendingOperation::DeletePermanent(files))
+        } else {
+            let message = format!("Permanently delete {target}? This cannot be undone.");
+            ("Confirm Permanent Delete", message, PendingOperation::DeletePermanent(files))
+        };
+
+        self.pending_operation = Some(operation);
+        self.dialog = Some(Dialog::confirm(title, message));
+    }
+
+    /// Initiate rename operation (edits the name in place in the list).
+    ///
+    /// With more than one item selected this asks for a bulk rename pattern
+    /// instead.
+    fn initiate_rename(&mut self) {
+        let targets = self.get_operation_targets();
+        if targets.len() > 1 {
+            self.pending_operation = Some(PendingOperation::BulkRename(targets));
+            self.dialog = Some(Dialog::input(
+                "Bulk Rename",
+                "Pattern ([N] name, [E] ext, [C:3] counter):",
+                "[N].[E]",
+            ));
+            return;
+        }
+
+        let pane = self.active_mut();
+        if let Some(entry) = pane.current_entry() {
+            let is_dir = entry.is_directory();
+            pane.inline_rename = Some(InlineRename::new(entry.path.clone(), is_dir));
+        }
+    }
+
+    /// Select or deselect the entries matching the wildcard `patterns`.
+    pub fn execute_select_pattern(&mut self, patterns: String, select: bool) {
+        let pane = self.active_mut();
+        let matched = if select {
+            pane.select_matching(&patterns)
+        } else {
+            pane.deselect_matching(&patterns)
+        };
+        if select {
+            self.report_selected(matched);
+        } else {
+            let noun = if matched == 1 { "item" } else { "items" };
+            self.set_status(format!("Deselected {matched} {noun}"), false);
+        }
+    }
+
+    /// Say how many entries a selection command matched.
+    fn report_selected(&mut self, matched: usize) {
+        if matched == 0 {
+            self.set_status("No matching items", false);
+        } else {
+            let noun = if matched == 1 { "item" } else { "items" };
+            self.set_status(format!("Selected {matched} {noun}"), false);
+        }
+    }
+
+    /// Initiate rename to the clipboard text (shows confirmation preview).
+    fn initiate_rename_from_clipboard(&mut self) {
+        let Some(entry) = self.active().current_entry().cloned() else {
+            return;
+        };
+
+        let text = match zmanager_transfer_win::Clipboard::paste_text() {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                self.set_status("Clipboard does not contain text", true);
+                return;
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to read clipboard: {}", e), true);
+                return;
+            }
+        };
+
+        let new_name = match clipboard_text_to_name(&text) {
+            Ok(name) => name,
+            Err(ZError::InvalidPath { reason, .. }) => {
+                self.set_status(format!("Cannot rename from clipboard: {}", reason), true);
+                return;
+            }
+            Err(e) => {
+                self.set_status(format!("Cannot rename from clipboard: {}", e), true);
+                return;
+            }
+        };
+
+        let current_name = entry.name.clone();
+        if new_name == current_name {
+            self.set_status(format!("'{}' already has that name", current_name), false);
+            return;
+        }
+
+        let target = entry.path.with_file_name(&new_name);
+        // Allow case-only renames, which resolve to the same file on Windows
+        if target.exists() && !new_name.eq_ignore_ascii_case(&current_name) {
+            self.set_status(format!("'{}' already exists", new_name), true);
+            return;
+        }
+
+        let message = format!("Rename '{}' to '{}'?", current_name, new_name);
+        self.pending_operation = Some(PendingOperation::RenameFromClipboard(entry.path, new_name));
+        self.dialog = Some(Dialog::confirm("Rename from Clipboard", message));
+    }
+
+    /// Initiate mkdir operation (shows input dialog).
+    fn initiate_mkdir(&mut self) {
+        if self.is_showing_basket("create folders") {
+            return;
+        }
+        self.pending_operation = Some(PendingOperation::MakeDir);
+        self.dialog = Some(Dialog::input("New Folder", "Folder name:", ""));
+    }
+
+    /// Initiate new file creation (shows input dialog).
+    fn initiate_new_file(&mut self) {
+        if self.is_showing_basket("create files") {
+            return;
+        }
+        self.pending_operation = Some(PendingOperation::NewFile);
+        self.dialog = Some(Dialog::input("New File", "File name:", ""));
+    }
+
+    /// Initiate archive creation (shows input dialog for the archive name).
+    fn initiate_compress(&mut self) {
+        let directory = self.active().nav.current_path().to_path_buf();
+        if split_archive_path(&directory).is_some() {
+            self.set_status("Cannot create an archive inside an archive", true);
+            return;
+        }
+        let files = self.get_operation_targets();
+        if files.is_empty() {
+            return;
+        }
+
+        let default_name = default_archive_name(&files, &directory);
+        self.pending_operation = Some(PendingOperation::Compress(files));
+        self.dialog = Some(Dialog::input("Create Archive", "Archive name:", default_name));
+    }
+
+    /// Extract into the other pane's directory as a background job.
+    ///
+    /// Inside an archive the selected entries are extracted; elsewhere each
+    /// selected archive is extracted whole.
+    fn initiate_extract(&mut self) {
+        let destination = self.inactive().nav.current_path().to_path_buf();
+        if split_archive_path(&destination).is_some() || split_basket_path(&destination).is_some() {
+            self.set_status("Cannot extract into an archive or basket", true);
+            return;
+        }
+
+        let targets = self.get_operation_targets();
+        if targets.is_empty() {
+            return;
+        }
+        let directory = self.active().nav.current_path().to_path_buf();
+        let jobs: Vec<JobKind> = match split_archive_path(&directory) {
+            Some((archive, _)) => vec![JobKind::Extract {
+                archive,
+                entries: targets,
+                destination,
+            }],
+            None => targets
+                .into_iter()
+                .filter(|path| is_browsable_archive(path) && path.is_file())
+                .map(|archive| JobKind::Extract {
+                    archive,
+                    entries: Vec::new(),
+                    destination: destination.clone(),
+                })
+                .collect(),
+        };
+        if jobs.is_empty() {
+            self.set_status("Nothing to extract: select an archive", true);
+            return;
+        }
+        for kind in jobs {
+            self.submit_transfer(kind, true);
+        }
+    }
+
+    /// Initiate copy operation.
+    ///
+    /// Copying to a pane that shows a basket adds the items to the basket.
+    fn initiate_copy(&mut self) {
+        let destination = self.inactive().nav.current_path().to_path_buf();
+        if let Some(name) = split_basket_path(&destination) {
+            let files = self.get_operation_targets();
+            if !files.is_empty() {
+                self.execute_add_to_basket(files, name.to_string());
+            }
+            return;
+        }
+        self.initiate_copy_to(destination);
+    }
+
+    /// Initiate copy operation to the previous directory in history.
+    fn initiate_copy_to_previous(&mut self) {
+        if let Some(destination) = self.previous_directory() {
+            self.initiate_copy_to(destination);
+        }
+    }
+
+    /// Initiate copy operation to `destination` (shows the destination dialog).
+    fn initiate_copy_to(&mut self, destination: PathBuf) {
+        let files = self.get_operation_targets();
+        if files.is_empty() {
+            return;
+        }
+        let dialog = self.transfer_dialog("Copy", &files, &destination);
+        self.pending_operation = Some(PendingOperation::Copy(files, destination));
+        self.dialog = Some(dialog);
+    }
+
+    /// Initiate move operation.
+    fn initiate_move(&mut self) {
+        let destination = self.inactive().nav.current_path().to_path_buf();
+        if split_basket_path(&destination).is_some() {
+            self.set_status("Items can only be copied into a basket", true);
+            return;
+        }
+        self.initiate_move_to(destination);
+    }
+
+    /// Initiate move operation to the previous directory in history.
+    fn initiate_move_to_previous(&mut self) {
+        if let Some(destination) = self.previous_directory() {
+            self.initiate_move_to(destination);
+        }
+    }
+
+    /// Initiate move operation to `destination` (shows the destination dialog).
+    fn initiate_move_to(&mut self, destination: PathBuf) {
+        let files = self.get_operation_targets();
+        if files.is_empty() {
+            return;
+        }
+        let dialog = self.transfer_dialog("Move", &files, &destination);
+        self.pending_operation = Some(PendingOperation::Move(files, destination));
+        self.dialog = Some(dialog);
+    }
+
+    /// Dialog for copying or moving `files` (`verb`), with an editable
+    /// destination line starting at `destination`, or at the file's new
+    /// path when there is just one.
+    fn transfer_dialog(&self, verb: &str, files: &[PathBuf], destination: &Path) -> Dialog {
+        let (prompt, initial) = match files {
+            [file] => {
+                let name = file.file_name().unwrap_or_default();
+                let prompt = format!("{} '{}' to:", verb, name.to_string_lossy());
+                (prompt, destination.join(name).display().to_string())
+            }
+            _ => {
+                let mut initial = destination.display().to_string();
+                if !initial.ends_with(std::path::is_separator) {
+                    initial.push(std::path::MAIN_SEPARATOR);
+                }
+                (format!("{} {} items to:", verb, files.len()), initial)
+            }
+        };
+        let mut notes = Vec::new();
+        if would_lose_alternate_streams(files, destination) {
+            notes.push("alternate data streams will be lost");
+        }
+        let title = if self.involves_network(files, destination) {
+            notes.push("network drive, may be slow");
+            "Network Transfer".to_string()
+        } else {
+            verb.to_string()
+        };
+        let prompt = if notes.is_empty() {
+            prompt
+        } else {
+            format!("({})\n{}", notes.join(", "), prompt)
+        };
+        let background = self.config.operations.background_transfers;
+        Dialog::input(title, prompt, initial).with_toggle("Run in background", background)
+    }
+
+    /// Where to copy or move `sources` given the destination `value` typed
+    /// in the dialog that started at `default`.
+    ///
+    /// Relative paths are taken from the active pane's directory. A single
+    /// item goes into a typed folder, or is renamed to any other typed path.
+    /// Several items, or a path ending in a separator, need a folder, which
+    /// is created if it doesn't exist. Returns None, with an error shown,
+    /// when it can't be.
+    pub fn transfer_destination(
+        &mut self,
+        sources: &[PathBuf],
+        default: PathBuf,
+        value: &str,
+    ) -> Option<PathBuf> {
+        let value = expand_env_vars(value.trim());
+        if value.is_empty() {
+            return Some(default);
+        }
+        let path = self.active().nav.current_path().join(&value);
+        // The suggested path for a single item means its folder, so an item
+        // of the same name there is a conflict rather than a folder to use
+        if let [source] = sources {
+            if source.file_name().is_some_and(|name| default.join(name) == path) {
+                return Some(default);
+            }
+        }
+
+        let needs_folder = sources.len() > 1 || value.ends_with(std::path::is_separator);
+        if needs_folder && !path.is_dir() && self.directory_provider(&path).is_none() {
+            if let Err(e) = std::fs::create_dir_all(&path) {
+                self.show_error("Create Folder Failed", format!("{}: {}", path.display(), e));
+                return None;
+            }
+        }
+        Some(path)
+    }
+
+    /// The directory the active pane was in before the current one.
+    ///
+    /// Sets a status message and returns None if there is no history or the
+    /// directory no longer exists.
+    fn previous_directory(&mut self) -> Option<PathBuf> {
+        let Some(previous) = self.active().nav.back_history().last().cloned() else {
+            self.set_status("No previous directory in history", true);
+            return None;
+        };
+
+        if !previous.is_dir() {
+            let message = format!("Previous directory no longer exists: {}", previous.display());
+            self.set_status(message, true);
+            return None;
+        }
+        Some(previous)
+    }
+
+    /// Whether a transfer should carry the network-drive notice.
+    ///
+    /// Always false unless `confirm_network_transfers` is enabled.
+    fn involves_network(&self, sources: &[PathBuf], destination: &Path) -> bool {
+        self.config.operations.confirm_network_transfers
+            && touches_network(sources, destination, &self.drives)
+    }
+
+    /// Whether the active pane shows a basket rather than a directory, in
+    /// which case the status bar says `action` is not possible there.
+    fn is_showing_basket(&mut self, action: &str) -> bool {
+        let in_basket = split_basket_path(self.active().nav.current_path()).is_some();
+        if in_basket {
+            self.set_status(format!("Cannot {action} in a basket"), true);
+        }
+        in_basket
+    }
+
+    /// Initiate adding the files to operate on to a basket (asks for its
+    /// name).
+    fn initiate_add_to_basket(&mut self) {
+        let files = self.get_operation_targets();
+        if files.is_empty() {
+            return;
+        }
+        self.pending_operation = Some(PendingOperation::AddToBasket(files));
+        let name = self.last_basket.clone();
+        self.dialog = Some(Dialog::input("Add to Basket", "Basket name:", name));
+    }
+
+    /// Initiate showing a basket in the active pane (asks for its name).
+    fn initiate_show_basket(&mut self) {
+        if self.baskets.is_empty() {
+            self.set_status("No baskets yet", false);
+            return;
+        }
+        let names: Vec<&str> = self.baskets.iter().map(|b| b.name.as_str()).collect();
+        let prompt = format!("Basket ({}):", names.join(", "));
+        self.pending_operation = Some(PendingOperation::ShowBasket);
+        self.dialog = Some(Dialog::input("Show Basket", prompt, self.last_basket.clone()));
+    }
+
+    /// Add `files` to the basket called `name`, creating it if needed.
+    pub fn execute_add_to_basket(&mut self, files: Vec<PathBuf>, name: String) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let added = self.baskets.get_or_create(name).add(files);
+        self.last_basket = name.to_string();
+        let noun = if added == 1 { "item" } else { "items" };
+        self.set_status(format!("Added {added} {noun} to basket '{name}'"), false);
+        self.refresh_basket_views(name);
+    }
+
+    /// Show the basket called `name` in the active pane.
+    pub fn execute_show_basket(&mut self, name: String) {
+        let name = name.trim();
+        if self.baskets.get(name).is_none() {
+            self.set_status(format!("No basket named '{name}'"), true);
+            return;
+        }
+        self.last_basket = name.to_string();
+        self.navigate_to_path(basket_path(name));
+    }
+
+    /// Take the files to operate on out of the basket the active pane
+    /// shows. A basket left empty is deleted.
+    fn remove_from_basket(&mut self) {
+        let path = self.active().nav.current_path().to_path_buf();
+        let Some(name) = split_basket_path(&path) else {
+            self.set_status("Show a basket to remove items from it", false);
+            return;
+        };
+        let files = self.get_operation_targets();
+        let Some(basket) = self.baskets.get_mut(name) else {
+            return;
+        };
+        let removed = files.iter().filter(|file| basket.remove(file)).count();
+        if basket.is_empty() {
+            self.baskets.remove(name);
+        }
+        self.active_mut().selection.clear();
+        let noun = if removed == 1 { "item" } else { "items" };
+        self.set_status(format!("Removed {removed} {noun} from basket '{name}'"), false);
+        self.refresh_basket_views(name);
+    }
+
+    /// List the basket called `name` in `pane`, forgetting items that no
+    /// longer exist; an unknown basket is listed empty.
+    pub fn list_basket(&mut self, pane: Pane, name: &str) {
+        let mut entries = match self.baskets.get_mut(name) {
+            Some(basket) => {
+                basket.prune_missing();
+                basket.entries()
+            }
+            None => Vec::new(),
+        };
+        self.sort.sort(&mut entries);
+
+        // A directory listing may still be streaming into the pane
+        let state = self.pane_mut(pane);
+        if let Some(task) = state.loading.take().and_then(|loading| loading.task) {
+            task.abort();
+        }
+        self.update_entries(pane, entries);
+    }
+
+    /// Relist the panes showing the basket called `name`.
+    fn refresh_basket_views(&mut self, name: &str) {
+        let path = basket_path(name);
+        for pane in [Pane::Left, Pane::Right] {
+            if self.pane_mut(pane).nav.current_path() == path {
+                self.list_basket(pane, name);
+            }
+        }
+    }
+
+    /// Save the baskets for the next run.
+    pub fn save_baskets(&self) {
+        if let Err(e) = self.baskets.save() {
+            warn!("Failed to save baskets: {}", e);
+        }
+    }
+
+    /// Get the files to operate on (selection or current).
+    fn get_operation_targets(&self) -> Vec<PathBuf> {
+        let pane = self.active();
+        let selected: Vec<PathBuf> = pane.entries
+            .iter()
+            .filter(|e| pane.selection.is_selected(&e.path))
+            .map(|e| e.path.clone())
+            .collect();
+
+        if selected.is_empty() {
+            // Use the current entry if no selection
+            pane.current_entry()
+                .map(|e| vec![e.path.clone()])
+                .unwrap_or_default()
+        } else {
+            selected
+        }
+    }
+
+    /// Whether keys currently go to an inline rename in the active pane.
+    pub fn is_renaming_inline(&self) -> bool {
+        self.active().inline_rename.is_some()
+    }
+
+    /// Finish the active pane's inline rename, renaming the entry to `name`
+    /// unless it is unchanged.
+    pub fn finish_inline_rename(&mut self, name: String) {
+        let Some(editor) = self.active_mut().inline_rename.take() else {
+            return;
+        };
+        if name != editor.original() {
+            self.execute_rename(editor.path().to_path_buf(), name);
+        }
+    }
+
+    /// Abandon the active pane's inline rename.
+    pub fn cancel_inline_rename(&mut self) {
+        self.active_mut().inline_rename = None;
+    }
+
+    /// Whether keys currently go to the active pane's quick filter.
+    pub fn is_typing_filter(&self) -> bool {
+        self.active().quick_filter.as_ref().is_some_and(|f| f.editing)
+    }
+
+    /// Append a typed character to the active pane's quick filter.
+    pub fn push_filter_char(&mut self, c: char) {
+        let pane = self.active_mut();
+        if let Some(mut text) = pane.quick_filter.as_ref().map(|f| f.text.clone()) {
+            text.push(c);
+            pane.set_quick_filter_text(text);
+        }
+    }
+
+    /// Remove the last character of the quick filter; on an empty filter,
+    /// leave filter mode altogether.
+    pub fn pop_filter_char(&mut self) {
+        let pane = self.active_mut();
+        let Some(mut text) = pane.quick_filter.as_ref().map(|f| f.text.clone()) else {
+            return;
+        };
+        if text.pop().is_some() {
+            pane.set_quick_filter_text(text);
+        } else {
+            pane.clear_quick_filter();
+        }
+    }
+
+    /// Stop typing but keep the filter applied, so the narrowed list can be
+    /// navigated and operated on. An empty filter is simply dropped.
+    pub fn finish_filter_input(&mut self) {
+        let pane = self.active_mut();
+        match pane.quick_filter.as_mut() {
+            Some(filter) if filter.text.is_empty() => pane.clear_quick_filter(),
+            Some(filter) => filter.editing = false,
+            None => {}
         }
     }
 
-    /// Update the jobs list.
-    pub fn update_jobs(&mut self, jobs: Vec<JobInfo>) {
-        self.jobs = jobs;
-        // Ensure selection is valid
-        if let Some(selected) = self.jobs_list_state.selected() {
-            if selected >= self.jobs.len() && !self.jobs.is_empty() {
-                self.jobs_list_state.select(Some(self.jobs.len() - 1));
-            }
-        }
+    /// Clear the active pane's quick filter.
+    pub fn clear_quick_filter(&mut self) {
+        self.active_mut().clear_quick_filter();
+    }
+
+    /// Toggle hidden files visibility.
+    fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        // Refresh both panes to apply the filter
+        let left_path = self.left.nav.current_path().to_path_buf();
+        let right_path = self.right.nav.current_path().to_path_buf();
+        let _ = self.event_tx.send(Event::DirectoryChanged(left_path));
+        let _ = self.event_tx.send(Event::DirectoryChanged(right_path));
+    }
+
+    /// Toggle the active pane between the filter and its complement.
+    fn toggle_filter_inverted(&mut self) -> ZResult<()> {
+        let pane = self.active_mut();
+        pane.filter_inverted = !pane.filter_inverted;
+        let message = if pane.filter_inverted {
+            "Filter inverted: showing hidden-by-filter files"
+        } else {
+            "Filter restored"
+        };
+        self.set_status(message, false);
+        self.refresh_active()
+    }
+
+    /// Toggle listing the files of all subdirectories of the active pane's
+    /// directory in one flat list.
+    fn toggle_flat_view(&mut self) -> ZResult<()> {
+        let pane = self.active_mut();
+        let path = pane.nav.current_path().to_path_buf();
+        let message = if pane.flat_base().is_some() {
+            pane.flat_view = None;
+            "Flat view off"
+        } else if split_archive_path(&path).is_some()
+            || split_basket_path(&path).is_some()
+            || unc_server(&path).is_some()
+        {
+            self.set_status("Only folders can be listed flat", true);
+            return Ok(());
+        } else {
+            pane.flat_view = Some(path);
+            "Flat view: files of all subfolders"
+        };
+        self.set_status(message, false);
+        self.refresh_active()
+    }
+
+    /// Show the sort menu.
+    fn show_sort_menu(&mut self) {
+        let current = match self.sort.field {
+            CoreSortField::Name => SortField::Name,
+            CoreSortField::Size => SortField::Size,
+            CoreSortField::Modified => SortField::Modified,
+            CoreSortField::Extension => SortField::Extension,
+            CoreSortField::Kind => SortField::Kind,
+            _ => SortField::Name,
+        };
+        self.dialog = Some(Dialog::sort_menu(current, self.sort));
+    }
+
+    /// Open the current file/directory.
+    ///
+    /// Executables follow the `executable_open` setting instead of launching
+    /// unconditionally.
+    fn open_current(&mut self) -> ZResult<()> {
+        if let Some(entry) = self.active().current_entry().cloned() {
+            if entry.kind.is_directory() {
+                // Navigate into directory
+                self.enter_directory()?;
+            } else if entry.is_executable() {
+                match self.config.general.executable_open {
+                    ExecutableOpenBehavior::Run => self.execute_run(entry.path),
+                    ExecutableOpenBehavior::Prompt => {
+                        let message = format!("Run {}?", entry.path.display());
+                        self.pending_operation = Some(PendingOperation::RunExecutable(entry.path));
+                        self.dialog = Some(Dialog::confirm("Run Program", message));
+                    }
+                    ExecutableOpenBehavior::Nothing => {
+                        self.set_status("Executables are not opened; press x to run", false);
+                    }
+                }
+            } else {
+                // Open file with default application
+                #[cfg(windows)]
+                {
+                    use std::process::Command;
+                    let _ = Command::new("cmd")
+                        .args(["/C", "start", "", entry.path.to_string_lossy().as_ref()])
+                        .spawn();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Offer the applications registered for the file at the cursor.
+    ///
+    /// Files no application is registered for go straight to the system's
+    /// "Open with" dialog.
+    pub fn show_open_with(&mut self) {
+        let Some(entry) = self.active().current_entry() else {
+            return;
+        };
+        if entry.is_directory() {
+            self.set_status("Open with works on files only", false);
+            return;
+        }
+        if split_archive_path(&entry.path).is_some() {
+            self.set_status("Extract the file to open it with another app", true);
+            return;
+        }
+        let path = entry.path.clone();
+        let handlers = open_with_handlers(&path);
+        if handlers.is_empty() {
+            self.show_open_with_dialog(path);
+        } else {
+            self.open_with = Some(OpenWithMenu::new(path, handlers));
+        }
+    }
+
+    /// Check if the open-with menu is open.
+    pub fn has_open_with(&self) -> bool {
+        self.open_with.is_some()
+    }
+
+    /// Close the open-with menu.
+    pub fn close_open_with(&mut self) {
+        self.open_with = None;
+    }
+
+    /// Open the menu's file with `handler` and close the menu.
+    pub fn launch_open_with(&mut self, handler: OpenWithHandler) {
+        let Some(menu) = self.open_with.take() else {
+            return;
+        };
+        let name = menu.path().file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let app = &handler.name;
+        match open_with(menu.path(), &handler) {
+            Ok(()) => self.set_status(format!("Opened {name} with {app}"), false),
+            Err(e) => self.set_status(format!("Cannot open {name} with {app}: {e}"), true),
+        }
+    }
+
+    /// Close the menu and let the user pick a program in the system's
+    /// "Open with" dialog instead.
+    pub fn choose_other_app(&mut self) {
+        if let Some(menu) = self.open_with.take() {
+            self.show_open_with_dialog(menu.path().to_path_buf());
+        }
+    }
+
+    /// Show the system's "Open with" dialog for `path` on a blocking thread,
+    /// as it stays up until the user picks a program. Failures come back as
+    /// [`Event::Error`]. Does nothing outside a Tokio runtime.
+    fn show_open_with_dialog(&mut self, path: PathBuf) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let tx = self.event_tx.clone();
+        runtime.spawn_blocking(move || match open_with_dialog(&path) {
+            Ok(()) | Err(ZError::Cancelled) => {}
+            Err(e) => {
+                let _ = tx.send(Event::Error(format!("Cannot open {}: {e}", path.display())));
+            }
+        });
+    }
+
+    /// Run the executable at the cursor without consulting `executable_open`.
+    fn run_current(&mut self) {
+        match self.active().current_entry() {
+            Some(entry) if entry.is_executable() => {
+                let path = entry.path.clone();
+                self.execute_run(path);
+            }
+            Some(entry) => {
+                let message = format!("{} is not an executable", entry.name);
+                self.set_status(message, true);
+            }
+            None => {}
+        }
+    }
+
+    /// Launch an executable and report the outcome.
+    pub fn execute_run(&mut self, path: PathBuf) {
+        match open_default(&path) {
+            Ok(()) => self.set_status(format!("Started {}", path.display()), false),
+            Err(e) => self.set_status(format!("Failed to run: {}", e), true),
+        }
+    }
+
+    /// Open the configured terminal in the active pane's directory.
+    fn open_terminal(&mut self) {
+        let template = self.config.tools.terminal.clone();
+        self.launch_external_tool("terminal", &template);
+    }
+
+    /// Show the item at the cursor selected in Windows Explorer. Items inside
+    /// an archive reveal the archive itself.
+    fn reveal_current(&mut self) {
+        let Some(entry) = self.active().current_entry() else {
+            return;
+        };
+        let path = entry.path.clone();
+        if self.directory_provider(&path).is_some() {
+            self.set_status("Explorer can only show local items", true);
+            return;
+        }
+        let path = split_archive_path(&path).map_or(path, |(archive, _)| archive);
+        match reveal_in_explorer(&path) {
+            Ok(()) => self.set_status(format!("Showing {} in Explorer", path.display()), false),
+            Err(e) => self.set_status(format!("Failed to open Explorer: {}", e), true),
+        }
+    }
+
+    /// Show the operations that apply to the selected entries, or to the
+    /// entry at the cursor.
+    fn show_context_menu(&mut self) {
+        let pane = self.active();
+        let selected: Vec<&EntryMeta> = pane
+            .entries
+            .iter()
+            .filter(|e| pane.selection.is_selected(&e.path))
+            .collect();
+        let entries = if selected.is_empty() {
+            pane.current_entry().into_iter().collect()
+        } else {
+            selected
+        };
+        let title = match entries.as_slice() {
+            [] => return,
+            [entry] => entry.name.clone(),
+            entries => format!("{} items", entries.len()),
+        };
+        let folders = entries.iter().filter(|e| e.is_directory()).count();
+        let targets = ContextTargets {
+            files: entries.len() - folders,
+            folders,
+        };
+        let plugin_actions: Vec<ContextAction> = self
+            .plugins
+            .commands()
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| {
+                entries.iter().any(|e| command.applies_to(&e.path, e.is_directory()))
+            })
+            .map(|(index, command)| ContextAction {
+                action: Action::Plugin(index),
+                label: command.name.clone().into(),
+                applies: Applies::Any,
+            })
+            .collect();
+        let menu = ContextMenu::new(targets, title).with_actions(plugin_actions);
+        self.context_menu = Some(menu);
+    }
+
+    /// Run the plugin command at `index` on each selected item it applies to.
+    fn run_plugin_command(&mut self, index: usize) {
+        let Some(command) = self.plugins.get(index).cloned() else {
+            return;
+        };
+        let dir = self.active().nav.current_path().to_path_buf();
+        let targets: Vec<PathBuf> = self
+            .get_operation_targets()
+            .into_iter()
+            .filter(|path| self.directory_provider(path).is_none())
+            .filter(|path| split_archive_path(path).is_none())
+            .filter(|path| command.applies_to(path, path.is_dir()))
+            .collect();
+        if targets.is_empty() {
+            let message = format!("{} doesn't apply to the selected items", command.name);
+            self.set_status(message, true);
+            return;
+        }
+
+        let failures: Vec<String> = targets
+            .iter()
+            .filter_map(|path| command.run(path, &dir).err().map(|e| e.to_string()))
+            .collect();
+        match failures.first() {
+            None => {
+                let message = format!("Ran {} on {} item(s)", command.name, targets.len());
+                self.set_status(message, false);
+            }
+            Some(e) => self.set_status(format!("{} failed: {}", command.name, e), true),
+        }
+    }
+
+    /// Check if the context menu is open.
+    pub fn has_context_menu(&self) -> bool {
+        self.context_menu.is_some()
+    }
+
+    /// Close the context menu.
+    pub fn close_context_menu(&mut self) {
+        self.context_menu = None;
+    }
+
+    /// Close the context menu and run the chosen action.
+    pub fn run_context_action(&mut self, action: Action) -> ZResult<()> {
+        self.context_menu = None;
+        self.handle_action(action)
+    }
+
+    /// Offer the ways of copying the selected items to the clipboard as text.
+    fn show_copy_menu(&mut self) {
+        let targets = self.get_operation_targets();
+        if !targets.is_empty() {
+            self.copy_menu = Some(CopyMenu::new(targets));
+        }
+    }
+
+    /// Check if the copy menu is open.
+    pub fn has_copy_menu(&self) -> bool {
+        self.copy_menu.is_some()
+    }
+
+    /// Close the copy menu.
+    pub fn close_copy_menu(&mut self) {
+        self.copy_menu = None;
+    }
+
+    /// Copy the menu's items as `format` and close the menu.
+    pub fn copy_menu_items(&mut self, format: CopyFormat) {
+        if let Some(menu) = self.copy_menu.take() {
+            self.copy_as_text(menu.paths(), format);
+        }
+    }
+
+    /// Copy the selected items to the clipboard as `format`.
+    fn copy_targets_as(&mut self, format: CopyFormat) {
+        let targets = self.get_operation_targets();
+        if !targets.is_empty() {
+            self.copy_as_text(&targets, format);
+        }
+    }
+
+    /// Put `paths` on the clipboard as text in `format`.
+    fn copy_as_text(&mut self, paths: &[PathBuf], format: CopyFormat) {
+        let text = match copy_text(paths, format) {
+            Ok(text) => text,
+            Err(e) => {
+                self.set_status(format!("Cannot copy: {}", e), true);
+                return;
+            }
+        };
+        match zmanager_transfer_win::Clipboard::copy_text(&text) {
+            Ok(()) => {
+                let message = match (format, paths) {
+                    (CopyFormat::Contents, _) => {
+                        format!("Copied contents ({})", format_size(text.len() as u64))
+                    }
+                    (_, [_]) => format!("Copied {text}"),
+                    _ => format!("Copied {} items as {}", paths.len(), format.label()),
+                };
+                self.set_status(message, false);
+            }
+            Err(e) => self.set_status(format!("Failed to copy to clipboard: {}", e), true),
+        }
+    }
+
+    /// Edit the file at the cursor in the built-in editor when it is small
+    /// enough and plain text. Anything else, or the directory when the pane
+    /// is empty, opens in the configured editor.
+    fn edit_current(&mut self) {
+        let max_size = self.config.tools.quick_edit_max_kb * 1024;
+        let quick_edit = self.active().current_entry().filter(|entry| {
+            max_size > 0
+                && !entry.is_directory()
+                && entry.size <= max_size
+                && split_archive_path(&entry.path).is_none()
+                && self.directory_provider(&entry.path).is_none()
+        });
+        if let Some(entry) = quick_edit {
+            let path = entry.path.clone();
+            match TextFile::load(&path, max_size) {
+                Ok(file) => {
+                    self.text_editor = Some(TextEditor::new(file, path));
+                    return;
+                }
+                // Binary, or it wouldn't be written back unchanged
+                Err(ZError::InvalidOperation { .. }) => {}
+                Err(e) => {
+                    self.set_status(format!("Cannot edit file: {e}"), true);
+                    return;
+                }
+            }
+        }
+        let template = self.config.tools.editor.clone();
+        self.launch_external_tool("editor", &template);
+    }
+
+    /// Show the file at the cursor in the built-in viewer.
+    fn view_current(&mut self) {
+        let Some(entry) = self.active().current_entry() else {
+            return;
+        };
+        if entry.is_directory() {
+            self.set_status("The viewer shows files only", false);
+            return;
+        }
+        let path = entry.path.clone();
+        if split_archive_path(&path).is_some() {
+            self.set_status("Extract the file to view it", true);
+            return;
+        }
+        if self.directory_provider(&path).is_some() {
+            self.set_status("Copy the file to a local folder to view it", true);
+            return;
+        }
+        match FileViewer::open(&path) {
+            Ok(viewer) => self.viewer = Some(viewer),
+            Err(e) => self.set_status(format!("Cannot view file: {e}"), true),
+        }
+    }
+
+    /// Check if the text editor is open.
+    pub fn has_text_editor(&self) -> bool {
+        self.text_editor.is_some()
+    }
+
+    /// Close the text editor, dropping unsaved changes.
+    pub fn close_text_editor(&mut self) {
+        self.text_editor = None;
+    }
+
+    /// Save the file in the text editor and reload the pane listing it.
+    pub fn save_text_editor(&mut self) {
+        let Some(editor) = self.text_editor.as_mut() else {
+            return;
+        };
+        if editor.save().is_ok() {
+            let path = self.active().nav.current_path().to_path_buf();
+            let _ = self.event_tx.send(Event::DirectoryChanged(path));
+        }
+    }
+
+    /// Check if the file viewer is open.
+    pub fn has_viewer(&self) -> bool {
+        self.viewer.is_some()
+    }
+
+    /// Close the file viewer, stopping its search.
+    pub fn close_viewer(&mut self) {
+        self.viewer = None;
+        if let Some((_, token)) = self.viewer_search.take() {
+            token.cancel();
+        }
+    }
+
+    /// Search the viewer's file for `search` from `from` in the background.
+    ///
+    /// The match comes back as [`Event::ViewerSearchFinished`]. Does nothing
+    /// outside a Tokio runtime.
+    pub fn start_viewer_search(&mut self, search: ContentSearch, from: u64) {
+        let Some(viewer) = self.viewer.as_ref() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let id = self.next_viewer_search;
+        self.next_viewer_search += 1;
+        let token = CancellationToken::new();
+        self.viewer_search = Some((id, token.clone()));
+
+        let path = viewer.path().to_path_buf();
+        let encoding = viewer.encoding();
+        let tx = self.event_tx.clone();
+        runtime.spawn_blocking(move || {
+            let result = match find_in_file(&path, encoding, &search, from, &token) {
+                Ok(found) => Ok(found),
+                // The viewer was closed
+                Err(ZError::Cancelled) => return,
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(Event::ViewerSearchFinished(id, result));
+        });
+    }
+
+    /// Show the outcome of a search in the file viewer.
+    pub fn apply_viewer_search(
+        &mut self,
+        id: u64,
+        result: Result<Option<std::ops::Range<u64>>, String>,
+    ) {
+        if self.viewer_search.take_if(|(search, _)| *search == id).is_none() {
+            return;
+        }
+        if let Some(viewer) = self.viewer.as_mut() {
+            viewer.search_finished(result);
+        }
+    }
+
+    /// Launch a `[tools]` command for the active pane and report the outcome.
+    fn launch_external_tool(&mut self, tool: &str, template: &str) {
+        let pane = self.active();
+        let directory = pane.nav.current_path().to_path_buf();
+        if split_archive_path(&directory).is_some() {
+            self.set_status(format!("Cannot open the {tool} inside an archive"), true);
+            return;
+        }
+        let path = pane.current_entry().map_or_else(|| directory.clone(), |e| e.path.clone());
+        match launch_tool(template, &path, &directory) {
+            Ok(()) => self.set_status(format!("Opened {tool}"), false),
+            Err(e) => self.set_status(format!("Failed to open {tool}: {e}"), true),
+        }
+    }
+
+    /// Open every selected file with its default application.
+    ///
+    /// Falls back to [`Self::open_current`] when nothing is selected. Selections
+    /// larger than `open_selected_max` are refused outright, and anything above
+    /// `open_selected_confirm_threshold` asks first. Selected executables follow
+    /// the `executable_open` setting, like [`Self::open_current`].
+    fn initiate_open_selected(&mut self) -> ZResult<()> {
+        let pane = self.active();
+        let (dirs, files): (Vec<&EntryMeta>, Vec<&EntryMeta>) = pane
+            .entries
+            .iter()
+            .filter(|e| pane.selection.is_selected(&e.path))
+            .partition(|e| e.kind.is_directory());
+
+        if dirs.is_empty() && files.is_empty() {
+            return self.open_current();
+        }
+
+        let general = &self.config.general;
+        let navigate_to = match general.open_selected_directories {
+            OpenDirectoryBehavior::Skip => None,
+            OpenDirectoryBehavior::Navigate => dirs.first().map(|e| e.path.clone()),
+        };
+        let (executables, files): (Vec<&EntryMeta>, Vec<&EntryMeta>) =
+            files.into_iter().partition(|e| e.is_executable());
+        let executables: Vec<PathBuf> = executables.iter().map(|e| e.path.clone()).collect();
+        let mut files: Vec<PathBuf> = files.iter().map(|e| e.path.clone()).collect();
+        let (max, threshold) = (general.open_selected_max, general.open_selected_confirm_threshold);
+
+        let (to_run, skipped) = match general.executable_open {
+            ExecutableOpenBehavior::Run => {
+                files.extend(executables);
+                (Vec::new(), 0)
+            }
+            ExecutableOpenBehavior::Prompt => (executables, 0),
+            ExecutableOpenBehavior::Nothing => (Vec::new(), executables.len()),
+        };
+
+        if files.is_empty() && to_run.is_empty() {
+            match navigate_to {
+                Some(dir) => self.navigate_to_path(dir),
+                None if skipped > 0 => self.set_status(
+                    format!("Skipped {} executable(s); press x to run", skipped),
+                    true,
+                ),
+                None => self.set_status("Selection contains no files to open", true),
+            }
+            return Ok(());
+        }
+
+        let total = files.len() + to_run.len();
+        if total > max {
+            self.set_status(format!("Refusing to open {} files (limit is {})", total, max), true);
+            return Ok(());
+        }
+
+        if !to_run.is_empty() {
+            let message = run_selected_message(&to_run, files.len());
+            files.extend(to_run);
+            self.pending_operation = Some(PendingOperation::OpenSelected(files, navigate_to, 0));
+            self.dialog = Some(Dialog::confirm("Run Program", message));
+            return Ok(());
+        }
+
+        if total > threshold {
+            let message = format!("Open {} files with their default applications?", total);
+            self.pending_operation =
+                Some(PendingOperation::OpenSelected(files, navigate_to, skipped));
+            self.dialog = Some(Dialog::confirm("Open Files", message));
+            return Ok(());
+        }
+
+        self.execute_open_selected(files, navigate_to, skipped);
+        Ok(())
+    }
+
+    /// Apply the sort field selection from the menu.
+    ///
+    /// Picking the field already sorted by reverses the order; another field
+    /// starts ascending.
+    pub fn apply_sort(&mut self, field: SortField) {
+        self.sort.toggle_or_set(match field {
+            SortField::Name => CoreSortField::Name,
+            SortField::Size => CoreSortField::Size,
+            SortField::Modified => CoreSortField::Modified,
+            SortField::Extension => CoreSortField::Extension,
+            SortField::Kind => CoreSortField::Kind,
+        });
+        // Refresh to re-sort
+        let left_path = self.left.nav.current_path().to_path_buf();
+        let right_path = self.right.nav.current_path().to_path_buf();
+        let _ = self.event_tx.send(Event::DirectoryChanged(left_path));
+        let _ = self.event_tx.send(Event::DirectoryChanged(right_path));
+    }
+
+    /// Switch a sort option from the menu, or cycle the secondary field.
+    pub fn toggle_sort_option(&mut self, option: SortOption) {
+        let sort = &mut self.sort;
+        match option {
+            SortOption::GroupByType => sort.group_by_type = !sort.group_by_type,
+            SortOption::DirectoriesFirst => sort.toggle_directories_first(),
+            SortOption::Natural => sort.natural = !sort.natural,
+            SortOption::LocaleCollation => {
+                sort.collation = match sort.collation {
+                    Collation::Ordinal => Collation::Locale,
+                    Collation::Locale => Collation::Ordinal,
+                };
+            }
+            SortOption::ThenBy => {
+                let fields = [
+                    CoreSortField::Name,
+                    CoreSortField::Size,
+                    CoreSortField::Modified,
+                    CoreSortField::Extension,
+                    CoreSortField::Kind,
+                ];
+                sort.then_by = match sort.then_by {
+                    None => Some(fields[0]),
+                    Some(field) => {
+                        let next = fields.iter().position(|f| *f == field).map_or(0, |i| i + 1);
+                        fields.get(next).copied()
+                    }
+                };
+            }
+        }
+        self.set_status(option.label(&self.sort), false);
+        let left_path = self.left.nav.current_path().to_path_buf();
+        let right_path = self.right.nav.current_path().to_path_buf();
+        let _ = self.event_tx.send(Event::DirectoryChanged(left_path));
+        let _ = self.event_tx.send(Event::DirectoryChanged(right_path));
+    }
+
+    /// Execute pending delete operation.
+    ///
+    /// The files are deleted by a job, so a large batch can be followed and
+    /// cancelled in the transfers view; the panes refresh when it finishes.
+    pub fn execute_delete(&mut self, files: Vec<PathBuf>, permanent: bool) {
+        let kind = if permanent {
+            JobKind::DeletePermanent { paths: files }
+        } else {
+            JobKind::Delete { paths: files }
+        };
+        self.submit_transfer(kind, true);
+    }
+
+    /// Execute pending rename operation.
+    pub fn execute_rename(&mut self, old_path: PathBuf, new_name: String) {
+        let new_path = old_path.parent()
+            .map(|p| p.join(&new_name))
+            .unwrap_or_else(|| PathBuf::from(&new_name));
+        let _ = self.event_tx.send(Event::ExecuteRename(old_path, new_path));
+    }
+
+    /// Preview a bulk rename of `paths` with `pattern`, asking for
+    /// confirmation if every new name can be used.
+    pub fn preview_bulk_rename(&mut self, paths: Vec<PathBuf>, pattern: String) {
+        let pattern = match RenamePattern::parse(&pattern) {
+            Ok(pattern) => pattern,
+            Err(ZError::InvalidOperation { reason, .. }) => {
+                self.show_error("Bulk Rename", reason);
+                return;
+            }
+            Err(e) => {
+                self.show_error("Bulk Rename", e.to_string());
+                return;
+            }
+        };
+
+        let plan = RenamePlan::new(&paths, &pattern);
+        if let Some(item) = plan.problems().next() {
+            let name = item.from.file_name().unwrap_or_default().to_string_lossy();
+            let problem = item.problem.as_deref().unwrap_or_default();
+            self.show_error("Bulk Rename", format!("{}: {}", name, problem));
+            return;
+        }
+        if plan.changed_count() == 0 {
+            self.set_status("Pattern leaves every name unchanged", false);
+            return;
+        }
+
+        let message = bulk_rename_preview(&plan);
+        self.pending_operation = Some(PendingOperation::ConfirmBulkRename(plan));
+        self.dialog = Some(Dialog::confirm("Bulk Rename Preview", message));
+    }
+
+    /// Execute a confirmed bulk rename; nothing is renamed if any item fails.
+    pub fn execute_bulk_rename(&mut self, plan: RenamePlan) {
+        match bulk_rename(&plan) {
+            Ok(count) => {
+                self.active_mut().clear_selection();
+                self.set_status(format!("Renamed {} item(s)", count), false);
+                let _ = self.event_tx.send(Event::RefreshAll);
+            }
+            Err(e) => {
+                self.show_error("Bulk Rename Failed", format!("No items were renamed: {}", e));
+                let _ = self.event_tx.send(Event::RefreshAll);
+            }
+        }
+    }
+
+    /// Execute pending mkdir operation.
+    pub fn execute_mkdir(&mut self, name: String) {
+        let parent = self.active().nav.current_path().to_path_buf();
+        let new_path = parent.join(&name);
+        let _ = self.event_tx.send(Event::ExecuteMkdir(new_path));
+    }
+
+    /// Execute pending new file operation.
+    pub fn execute_new_file(&mut self, name: String) {
+        let name = name.trim();
+        if let Err(e) = validate_filename(name) {
+            self.set_status(e.to_string(), true);
+            return;
+        }
+        let new_path = self.active().nav.current_path().join(name);
+        let _ = self.event_tx.send(Event::ExecuteNewFile(new_path));
+    }
+
+    /// Go to a path typed into the location bar.
+    ///
+    /// `%VARIABLE%` references are expanded and relative paths are taken from
+    /// the current directory. A UNC path is opened without checking it first,
+    /// since an unreachable server can take a long time to answer; `\\server`
+    /// on its own lists the server's shares. An `sftp://`, `dav://` or
+    /// `davs://` path is opened on the server, logging in if needed, and an
+    /// `mtp://` path on the device. Paths gone to are remembered in the
+    /// location bar history.
+    pub fn execute_go_to_path(&mut self, input: String) {
+        let input = expand_env_vars(input.trim());
+        if input.is_empty() {
+            return;
+        }
+        let path = canonical_remote_path(Path::new(&input))
+            .or_else(|| split_device_path(Path::new(&input)).map(|path| path.to_path_buf()))
+            .unwrap_or_else(|| self.active().nav.current_path().join(input));
+        let is_remote = is_remote_path(&path) || is_device_path(&path);
+        if !is_unc_path(&path) && !is_remote && !path.is_dir() {
+            self.set_status(format!("Not a directory: {}", path.display()), true);
+            return;
+        }
+        remember_path(&mut self.path_history, &path.display().to_string());
+        self.navigate_to_path(path);
+    }
+
+    /// Execute pending copy operation, in the background or behind a
+    /// progress dialog.
+    pub fn execute_copy(&mut self, sources: Vec<PathBuf>, destination: PathBuf, background: bool) {
+        let kind = JobKind::Copy {
+            sources,
+            destination,
+        };
+        self.submit_transfer(kind, background);
+    }
+
+    /// Execute pending move operation, in the background or behind a
+    /// progress dialog.
+    pub fn execute_move(&mut self, sources: Vec<PathBuf>, destination: PathBuf, background: bool) {
+        let kind = JobKind::Move {
+            sources,
+            destination,
+        };
+        self.submit_transfer(kind, background);
+    }
+
+    /// Run copy, move and delete transfers as jobs on `scheduler`.
+    ///
+    /// Job list changes come back as [`Event::JobsUpdated`] and finished jobs
+    /// as [`Event::JobCompleted`]. Does nothing outside a Tokio runtime.
+    pub fn attach_scheduler(&mut self, scheduler: SchedulerHandle) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let transfer_config = FolderTransferConfig {
+            verify: self.config.operations.verification(),
+            resumable_min_size: self.config.operations.resumable_threshold(),
+            unbuffered_min_size: self.config.operations.unbuffered_threshold(),
+            link_handling: self.config.operations.link_handling,
+            check_free_space: self.config.operations.check_free_space,
+            journal_dir: Some(TransferJournal::default_dir()),
+            include: self.config.operations.include_patterns.clone(),
+            exclude: self.config.operations.exclude_patterns.clone(),
+            concurrency: self.config.operations.concurrency_for(false),
+            backends: BackendRegistry::default()
+                .register(Arc::new(RemoteBackend::new(Arc::clone(&self.remote))))
+                .register(Arc::new(MtpBackend::new(Arc::clone(&self.devices)))),
+            ..Default::default()
+        };
+        let delete_config = DeleteConfig {
+            link_handling: self.config.operations.link_handling,
+            wipe: self.config.operations.wipe_permanent_deletes,
+            ..Default::default()
+        };
+        runtime.spawn(watch_scheduler(
+            scheduler.clone(),
+            transfer_config,
+            self.config.operations.concurrency_for(true),
+            delete_config,
+            self.drives.clone(),
+            self.event_tx.clone(),
+        ));
+        self.scheduler = Some(scheduler);
+    }
+
+    /// Offer to resume the transfers a crash cut short, whose journals are in
+    /// `dir`, one confirmation at a time.
+    pub fn check_unfinished_transfers(&mut self, dir: &Path) {
+        self.unfinished_transfers = find_unfinished_transfers(dir);
+        if !self.unfinished_transfers.is_empty() {
+            warn!("{} interrupted transfer(s) found", self.unfinished_transfers.len());
+        }
+        self.offer_unfinished_transfer();
+    }
+
+    /// Ask about the first unfinished transfer, if any.
+    fn offer_unfinished_transfer(&mut self) {
+        let Some(journal) = self.unfinished_transfers.first() else {
+            return;
+        };
+        let plan = &journal.plan;
+        let what = match plan.source_roots.as_slice() {
+            [source] => format!("'{}'", source.file_name().unwrap_or_default().to_string_lossy()),
+            sources => format!("{} items", sources.len()),
+        };
+        let verb = if plan.is_move { "Moving" } else { "Copying" };
+        let partial = match journal.unfinished_destinations().len() {
+            0 => String::new(),
+            n => format!(" and {n} partly copied"),
+        };
+        let message = format!(
+            "{verb} {what} to '{}' was interrupted with {} of {} files done{partial}. \
+             Enter resumes it, Esc abandons it.",
+            plan.destination_root.display(),
+            journal.completed_count(),
+            plan.stats.total_files,
+        );
+        self.pending_operation = Some(PendingOperation::ResumeTransfer);
+        self.dialog = Some(Dialog::confirm("Resume Transfer", message));
+    }
+
+    /// Queue the offered unfinished transfer again; files it already
+    /// finished are skipped.
+    pub fn resume_unfinished_transfer(&mut self) {
+        if self.unfinished_transfers.is_empty() {
+            return;
+        }
+        let journal = self.unfinished_transfers.remove(0);
+        self.submit_transfer(journal.job_kind(), true);
+        self.offer_unfinished_transfer();
+    }
+
+    /// Drop the offered unfinished transfer along with its journal and the
+    /// files it left partly copied, with or without a saved resume state.
+    pub fn abandon_unfinished_transfer(&mut self) {
+        self.close_dialog();
+        if self.unfinished_transfers.is_empty() {
+            return;
+        }
+        let journal = self.unfinished_transfers.remove(0);
+        for destination in journal.unfinished_destinations() {
+            if let Err(e) = discard_partial_copy(&destination) {
+                warn!("Failed to remove partial copy: {}", e);
+            }
+        }
+        if let Err(e) = journal.discard() {
+            warn!("Failed to remove transfer journal: {}", e);
+        }
+        self.offer_unfinished_transfer();
+    }
+
+    /// Queue a transfer job; progress shows in the transfers view.
+    ///
+    /// A foreground transfer starts ahead of queued jobs and shows its
+    /// progress in a dialog, which comes back as
+    /// [`Event::ForegroundJobStarted`] once the job has an id.
+    fn submit_transfer(&mut self, kind: JobKind, background: bool) {
+        let (Some(scheduler), Ok(runtime)) =
+            (self.scheduler.clone(), tokio::runtime::Handle::try_current())
+        else {
+            self.show_error("Transfer Failed", "The transfer scheduler is not running");
+            return;
+        };
+
+        if background {
+            self.set_status(format!("{} (t to view transfers)", kind.description()), false);
+            runtime.spawn(async move {
+                scheduler.submit(kind).await;
+            });
+        } else {
+            let tx = self.event_tx.clone();
+            runtime.spawn(async move {
+                let id = scheduler.submit_with_priority(kind, Priority::High).await;
+                let _ = tx.send(Event::ForegroundJobStarted(id.0));
+            });
+        }
+    }
+
+    /// Show the progress of a foreground transfer until it finishes.
+    pub fn apply_foreground_job(&mut self, job_id: u64) {
+        // A job that already finished has nothing left to show
+        let finished = self.jobs.iter().any(|job| job.id.0 == job_id && job.state.is_terminal());
+        if !finished {
+            self.foreground_job = Some(job_id);
+        }
+    }
+
+    /// Check if a foreground transfer's progress dialog is open.
+    pub fn has_foreground_job(&self) -> bool {
+        self.foreground_job.is_some()
+    }
+
+    /// The foreground transfer, once the scheduler has listed it.
+    pub fn foreground_job_info(&self) -> Option<&JobInfo> {
+        let id = self.foreground_job?;
+        self.jobs.iter().find(|job| job.id.0 == id)
+    }
+
+    /// Pause the foreground transfer, or resume it when paused.
+    pub fn toggle_foreground_pause(&mut self) {
+        let Some(job) = self.foreground_job_info() else {
+            return;
+        };
+        let (id, control) = match job.state {
+            JobState::Paused => (job.id.0, JobControl::Resume),
+            _ => (job.id.0, JobControl::Pause),
+        };
+        self.control_job(id, control);
+    }
+
+    /// Cancel the foreground transfer; its dialog closes once it stops.
+    pub fn cancel_foreground_job(&mut self) {
+        if let Some(id) = self.foreground_job {
+            self.control_job(id, JobControl::Cancel);
+        }
+    }
+
+    /// Close the progress dialog and let the foreground transfer carry on in
+    /// the background.
+    pub fn background_foreground_job(&mut self) {
+        if self.foreground_job.take().is_some() {
+            self.set_status("Transfer continues in the background (t to view transfers)", false);
+        }
+    }
+
+    /// Send a pause, resume or cancel request for a job to the scheduler.
+    pub fn control_job(&mut self, job_id: u64, control: JobControl) {
+        let (Some(scheduler), Ok(runtime)) =
+            (self.scheduler.clone(), tokio::runtime::Handle::try_current())
+        else {
+            return;
+        };
+
+        let id = JobId(job_id);
+        let tx = self.event_tx.clone();
+        runtime.spawn(async move {
+            match control {
+                JobControl::Pause => scheduler.pause(id).await,
+                JobControl::Resume => scheduler.resume(id).await,
+                JobControl::Cancel => scheduler.cancel(id).await,
+                JobControl::SpeedLimit(limit) => scheduler.set_speed_limit(id, limit).await,
+                JobControl::RetryFailed => {
+                    let retried = !scheduler.resubmit_failed(id).await.is_empty();
+                    if !retried {
+                        let _ = tx.send(Event::Error("Nothing to retry for this job".into()));
+                    }
+                    retried
+                }
+            };
+        });
+    }
+
+    /// Report a finished transfer and refresh the panes to show its results.
+    pub fn apply_job_finished(&mut self, job_id: u64, success: bool) {
+        let _ = self.event_tx.send(Event::RefreshAll);
+        if self.foreground_job == Some(job_id) {
+            self.foreground_job = None;
+        }
+        self.store_job_report(job_id);
+
+        // A cancelled transfer no longer waits on its conflict
+        let asked = self.conflict_queue.len();
+        self.conflict_queue.retain(|query| query.job_id.0 != job_id);
+        if self.conflict_queue.len() != asked {
+            self.show_next_conflict();
+        }
+
+        let Some(job) = self.jobs.iter().find(|job| job.id.0 == job_id) else {
+            return;
+        };
+        let message = match job.state {
+            _ if success => format!("Done: {}", job.description),
+            JobState::Cancelled => format!("Cancelled: {}", job.description),
+            _ => format!(
+                "Failed: {}: {}",
+                job.description,
+                job.error.as_deref().unwrap_or("unknown error")
+            ),
+        };
+        let cancelled = job.state == JobState::Cancelled;
+        self.set_status(message, !success && !cancelled);
+
+        // Ring the bell for a long job that finished while the user was away
+        let running_time = self.job_details.get(&job_id).map(JobDetails::elapsed);
+        let notifications = &self.config.notifications;
+        if !self.terminal_focused
+            && !cancelled
+            && notifications.bell
+            && running_time.is_some_and(|time| notifications.should_notify(time))
+        {
+            self.bell_pending = true;
+        }
+    }
+
+    /// Note whether the terminal has focus.
+    pub fn set_terminal_focused(&mut self, focused: bool) {
+        self.terminal_focused = focused;
+    }
+
+    /// Take the pending terminal bell, if a job asked for one.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending)
+    }
+
+    /// Execute pending archive creation as a background job.
+    ///
+    /// The archive goes into the active pane's directory; an existing file of
+    /// the same name is reported instead of overwritten.
+    pub fn execute_compress(&mut self, sources: Vec<PathBuf>, name: String) {
+        let name = match archive_file_name(&name) {
+            Ok(name) => name,
+            Err(e) => {
+                self.set_status(e.to_string(), true);
+                return;
+            }
+        };
+        let destination = self.active().nav.current_path().join(&name);
+        if destination.exists() {
+            self.set_status(format!("'{}' already exists", name), true);
+            return;
+        }
+        self.submit_transfer(JobKind::Compress { sources, destination }, true);
+    }
+
+    /// Execute pending open-selected operation. `skipped` executables were
+    /// left out by the `executable_open` setting and are reported alongside.
+    pub fn execute_open_selected(
+        &mut self,
+        files: Vec<PathBuf>,
+        navigate_to: Option<PathBuf>,
+        skipped: usize,
+    ) {
+        let failures: Vec<String> = files
+            .iter()
+            .filter_map(|file| {
+                open_default(file).err().map(|e| {
+                    let name = file.file_name().unwrap_or_default().to_string_lossy();
+                    format!("{}: {}", name, e)
+                })
+            })
+            .collect();
+
+        if let Some(dir) = navigate_to {
+            self.navigate_to_path(dir);
+        }
+
+        let (mut message, is_error) = open_summary(files.len(), &failures);
+        if skipped > 0 {
+            message.push_str(&format!("; skipped {} executable(s)", skipped));
+        }
+        self.set_status(message, is_error);
+    }
+
+    /// Show an error message dialog.
+    pub fn show_error(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        self.dialog = Some(Dialog::error(title, message));
+    }
+
+    /// Show an info message dialog.
+    pub fn show_message(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        self.dialog = Some(Dialog::message(title, message));
+    }
+
+    /// Check if there's an active dialog.
+    pub fn has_dialog(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /// Close the current dialog.
+    pub fn close_dialog(&mut self) {
+        self.dialog = None;
+        self.pending_operation = None;
+    }
+
+    // ========== Transfers View ==========
+
+    /// Toggle transfers view.
+    pub fn toggle_transfers_view(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Browser => ViewMode::Transfers,
+            ViewMode::Transfers => ViewMode::Browser,
+        };
+        // Reset list selection when entering transfers view
+        if self.view_mode == ViewMode::Transfers {
+            self.jobs_list_state.select(Some(0));
+        }
+        self.report_history = None;
+    }
+
+    /// Switch the transfers view to its history tab, reading the stored
+    /// reports afresh.
+    pub fn show_report_history(&mut self) {
+        let storage = ReportStorage::new(self.reports_dir.clone());
+        self.report_history = Some(ReportHistory::load(&storage));
+    }
+
+    /// Check if the transfers view shows its history tab.
+    pub fn has_report_history(&self) -> bool {
+        self.report_history.is_some()
+    }
+
+    /// Switch the transfers view back to its jobs tab.
+    pub fn close_report_history(&mut self) {
+        self.report_history = None;
+    }
+
+    /// Queue the failed items of the highlighted history report as new jobs.
+    pub fn retry_failed_items(&mut self) {
+        let Some(report) = self.report_history.as_ref().and_then(|h| h.selected()) else {
+            return;
+        };
+        let jobs = report.retry_jobs();
+        if jobs.is_empty() {
+            self.set_status("No failed items to retry", false);
+            return;
+        }
+        let count = report.summary.failed;
+        for kind in jobs {
+            self.submit_transfer(kind, true);
+        }
+        self.set_status(format!("Retrying {count} failed item(s) (Tab to view jobs)"), false);
+    }
+
+    /// Offer to retry operations that were denied access as administrator.
+    ///
+    /// An open dialog isn't replaced; the user is only told about the
+    /// failure then.
+    pub fn offer_elevation(&mut self, job_id: Option<u64>, manifest: ElevationManifest) {
+        let what = match manifest.operations.as_slice() {
+            [operation] => {
+                let name = operation.source().file_name().unwrap_or_default();
+                format!("'{}'", name.to_string_lossy())
+            }
+            operations => format!("{} items", operations.len()),
+        };
+        if self.dialog.is_some() {
+            self.set_status(format!("Access denied to {what}"), true);
+            return;
+        }
+        let message = format!(
+            "Access to {what} was denied. Retry as administrator? \
+             Windows will ask for permission."
+        );
+        self.pending_operation = Some(PendingOperation::RetryElevated(job_id, manifest));
+        self.dialog = Some(Dialog::confirm("Access Denied", message));
+    }
+
+    /// Offer to retry a rename that was denied access as administrator.
+    pub fn offer_elevated_rename(&mut self, from: PathBuf, to: PathBuf) {
+        let manifest = ElevationManifest::new(vec![ElevatedOperation::Rename { from, to }]);
+        self.offer_elevation(None, manifest);
+    }
+
+    /// Carry out `manifest` in the elevated helper, off the UI thread.
+    pub fn retry_elevated(&mut self, job_id: Option<u64>, manifest: ElevationManifest) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        self.set_status(
+            format!("Running {} item(s) as administrator...", manifest.len()),
+            false,
+        );
+        let tx = self.event_tx.clone();
+        runtime.spawn_blocking(move || {
+            let result = run_elevated(&manifest).map_err(|e| match e {
+                ZError::Cancelled => "The administrator prompt was declined".to_string(),
+                e => e.to_string(),
+            });
+            let _ = tx.send(Event::ElevationFinished(job_id, result));
+        });
+    }
+
+    /// Fold the outcome of a retry as administrator into the report of the
+    /// job it came from.
+    pub fn apply_elevation_results(
+        &mut self,
+        job_id: Option<u64>,
+        result: Result<Vec<ElevatedResult>, String>,
+    ) {
+        let results = match result {
+            Ok(results) => results,
+            Err(message) => {
+                self.show_error("Retry as Administrator", message);
+                return;
+            }
+        };
+        let _ = self.event_tx.send(Event::RefreshAll);
+        if let Some(job_id) = job_id {
+            if let Some(details) = self.job_details.get_mut(&job_id) {
+                details.add_elevated(results.clone());
+                self.store_job_report(job_id);
+            }
+        }
+
+        let failed: Vec<&ElevatedResult> = results.iter().filter(|r| !r.is_success()).collect();
+        match failed.first().and_then(|result| result.error.as_deref()) {
+            None => self.set_status(
+                format!("Done as administrator: {} item(s)", results.len()),
+                false,
+            ),
+            Some(error) => self.show_error(
+                "Retry as Administrator",
+                format!("{} of {} item(s) failed again: {error}", failed.len(), results.len()),
+            ),
+        }
+    }
+
+    /// Keep the report of a finished job for the history tab.
+    fn store_job_report(&mut self, job_id: u64) {
+        let Some(details) = self.job_details.get(&job_id) else {
+            return;
+        };
+        let storage = ReportStorage::new(self.reports_dir.clone());
+        let stored = storage
+            .save(&details.report())
+            .and_then(|_| storage.cleanup(STORED_REPORTS));
+        if let Err(e) = stored {
+            warn!("Failed to store transfer report: {}", e);
+        }
+    }
+
+    /// Get the currently selected job ID (in transfers view).
+    pub fn selected_job(&self) -> Option<&JobInfo> {
+        self.jobs_list_state.selected()
+            .and_then(|i| self.jobs.get(i))
+    }
+
+    /// Pause the selected job.
+    pub fn pause_selected_job(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        if let Some(job) = self.selected_job() {
+            let _ = self.event_tx.send(Event::PauseJob(job.id.0));
+        }
+    }
+
+    /// Resume the selected job.
+    pub fn resume_selected_job(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        if let Some(job) = self.selected_job() {
+            let _ = self.event_tx.send(Event::ResumeJob(job.id.0));
+        }
+    }
+
+    /// Cancel the selected job.
+    pub fn cancel_selected_job(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        if let Some(job) = self.selected_job() {
+            let _ = self.event_tx.send(Event::CancelJob(job.id.0));
+        }
+    }
+
+    /// Queue the failed items of the selected job again.
+    pub fn retry_selected_job(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        let Some(job) = self.selected_job() else {
+            return;
+        };
+        if job.state != JobState::Failed {
+            self.set_status("Only failed jobs can be retried", true);
+            return;
+        }
+        let id = job.id.0;
+        self.control_job(id, JobControl::RetryFailed);
+        self.set_status("Retrying failed items", false);
+    }
+
+    /// Move the selected job to the next speed limit preset.
+    pub fn cycle_selected_speed_limit(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        let Some(job) = self.selected_job() else {
+            return;
+        };
+
+        // A limit set elsewhere restarts the cycle
+        let next = SPEED_LIMIT_PRESETS
+            .iter()
+            .position(|preset| *preset == job.speed_limit)
+            .map_or(0, |i| (i + 1) % SPEED_LIMIT_PRESETS.len());
+        let limit = SPEED_LIMIT_PRESETS[next];
+        let message = match limit {
+            Some(limit) => format!("Speed limit: {}/s", format_size(limit)),
+            None => "Speed limit: off".to_string(),
+        };
+        let _ = self.event_tx.send(Event::SetSpeedLimit(job.id.0, limit));
+        self.set_status(message, false);
+    }
+
+    /// Show or hide the selected job's details.
+    pub fn toggle_job_details(&mut self) {
+        self.show_job_details = !self.show_job_details;
+    }
+
+    /// The selected job and its details, when the transfers view shows them.
+    pub fn selected_job_details(&self) -> Option<(&JobInfo, &JobDetails)> {
+        if !self.show_job_details {
+            return None;
+        }
+        let job = self.selected_job()?;
+        Some((job, self.job_details.get(&job.id.0)?))
+    }
+
+    /// Write the selected job's report, as JSON and as text, to the reports
+    /// directory.
+    pub fn export_selected_job_report(&mut self) {
+        if self.view_mode != ViewMode::Transfers {
+            return;
+        }
+        let Some(details) = self
+            .selected_job()
+            .and_then(|job| self.job_details.get(&job.id.0))
+        else {
+            self.set_status("No report for this job yet", true);
+            return;
+        };
+
+        let report = details.report();
+        let saved = ReportStorage::new(self.reports_dir.clone())
+            .save(&report)
+            .and_then(|path| {
+                report.save_text(&path.with_extension("txt"))?;
+                Ok(path)
+            });
+        match saved {
+            Ok(path) => self.set_status(format!("Report saved to {}", path.display()), false),
+            Err(e) => self.set_status(format!("Failed to export report: {e}"), true),
+        }
+    }
+
+    /// Start following the details of a job that just started.
+    pub fn apply_job_started(&mut self, job_id: u64, operation: TransferOperation) {
+        self.job_details
+            .insert(job_id, JobDetails::new(JobId(job_id), operation));
+    }
+
+    /// Add an item a job finished to its details.
+    pub fn apply_job_item(&mut self, job_id: u64, item: TransferItemResult) {
+        if let Some(details) = self.job_details.get_mut(&job_id) {
+            details.add_item(item);
+        }
+    }
+
+    /// Record how many items a transfer's include and exclude patterns left
+    /// out.
+    pub fn apply_job_excluded(&mut self, job_id: u64, excluded: usize) {
+        if let Some(details) = self.job_details.get_mut(&job_id) {
+            details.set_excluded(excluded);
+        }
+    }
+
+    /// Update the jobs list.
+    pub fn update_jobs(&mut self, jobs: Vec<JobInfo>) {
+        self.jobs = jobs;
+        self.job_details
+            .retain(|id, _| self.jobs.iter().any(|job| job.id.0 == *id));
+        for job in &self.jobs {
+            if let Some(details) = self.job_details.get_mut(&job.id.0) {
+                details.observe(job);
+            }
+        }
+        // Ensure selection is valid
+        if let Some(selected) = self.jobs_list_state.selected() {
+            if selected >= self.jobs.len() && !self.jobs.is_empty() {
+                self.jobs_list_state.select(Some(self.jobs.len() - 1));
+            }
+        }
+    }
+
+    /// Move selection up in transfers view.
+    pub fn jobs_up(&mut self) {
+        if let Some(selected) = self.jobs_list_state.selected() {
+            if selected > 0 {
+                self.jobs_list_state.select(Some(selected - 1));
+            }
+        } else if !self.jobs.is_empty() {
+            self.jobs_list_state.select(Some(0));
+        }
+    }
+
+    /// Move selection down in transfers view.
+    pub fn jobs_down(&mut self) {
+        if let Some(selected) = self.jobs_list_state.selected() {
+            if selected < self.jobs.len().saturating_sub(1) {
+                self.jobs_list_state.select(Some(selected + 1));
+            }
+        } else if !self.jobs.is_empty() {
+            self.jobs_list_state.select(Some(0));
+        }
+    }
+
+    /// Show a status message, as an error or as information.
+    pub fn set_status(&mut self, message: impl Into<String>, is_error: bool) {
+        let level = if is_error {
+            StatusLevel::Error
+        } else {
+            StatusLevel::Info
+        };
+        self.set_status_level(message, level);
+    }
+
+    /// Show a status message of the given level. It waits behind messages
+    /// already up, and goes away on its own.
+    pub fn set_status_level(&mut self, message: impl Into<String>, level: StatusLevel) {
+        self.status.push(message, level, Instant::now());
+    }
+
+    /// The status message shown, if any.
+    pub fn status_message(&self) -> Option<&StatusMessage> {
+        self.status.current()
+    }
+
+    /// Number of status messages waiting behind the shown one.
+    pub fn queued_status_count(&self) -> usize {
+        self.status.waiting()
+    }
+
+    /// Retire the status message once its time is up. Returns whether the
+    /// status bar changed.
+    ///
+    /// Messages don't expire while the terminal is unfocused, so the ones
+    /// that arrived meanwhile are still there to read on return.
+    pub fn expire_status(&mut self) -> bool {
+        self.terminal_focused && self.status.tick(Instant::now())
+    }
+
+    /// Clear all status messages.
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    /// Check if in transfers view.
+    pub fn is_transfers_view(&self) -> bool {
+        self.view_mode == ViewMode::Transfers
+    }
+
+    /// Check if there's an active conflict modal.
+    pub fn has_conflict(&self) -> bool {
+        self.conflict_modal.is_some()
+    }
+
+    /// Close the conflict modal.
+    pub fn close_conflict(&mut self) {
+        self.conflict_modal = None;
+    }
+
+    /// Open the location bar for the active pane's directory.
+    pub fn open_location_bar(&mut self) {
+        let base = self.active().nav.current_path().to_path_buf();
+        self.location_bar = Some(LocationBar::new(base, self.path_history.clone()));
+    }
+
+    /// Check if the location bar is open.
+    pub fn has_location_bar(&self) -> bool {
+        self.location_bar.is_some()
+    }
+
+    /// Close the location bar.
+    pub fn close_location_bar(&mut self) {
+        self.location_bar = None;
+    }
+
+    /// Count a visit to `path` in the visit history, unless it is the
+    /// directory recorded last (a refresh) or a basket.
+    pub fn record_visit(&mut self, path: &Path) {
+        if self.last_visited.as_deref() == Some(path) || split_basket_path(path).is_some() {
+            return;
+        }
+        self.visit_history.record(path);
+        self.last_visited = Some(path.to_path_buf());
+    }
+
+    /// Save the visit history for the next run.
+    pub fn save_visit_history(&self) {
+        if let Err(e) = self.visit_history.save() {
+            warn!("Failed to save visit history: {}", e);
+        }
+    }
+
+    /// Open the directory history overlay.
+    pub fn open_history_view(&mut self) {
+        if self.visit_history.is_empty() {
+            self.set_status("No directories visited yet", false);
+            return;
+        }
+        self.history_view = Some(HistoryView::new(&self.visit_history));
+    }
+
+    /// Check if the directory history overlay is open.
+    pub fn has_history_view(&self) -> bool {
+        self.history_view.is_some()
+    }
+
+    /// Close the directory history overlay.
+    pub fn close_history_view(&mut self) {
+        self.history_view = None;
+    }
+
+    /// Remove the highlighted directory of the history overlay from the
+    /// visit history, closing the overlay once it is empty.
+    pub fn forget_visit(&mut self, path: &Path) {
+        self.visit_history.remove(path);
+        if self.last_visited.as_deref() == Some(path) {
+            self.last_visited = None;
+        }
+        if let Some(view) = self.history_view.as_mut() {
+            view.remove_selected();
+            if view.is_empty() {
+                self.history_view = None;
+            }
+        }
+    }
+
+    /// Queue a conflict a transfer is blocked on, showing it if no other
+    /// conflict is open.
+    pub fn apply_conflict_query(&mut self, query: ConflictQuery) {
+        self.conflict_queue.push_back(query);
+        if self.conflict_queue.len() == 1 {
+            self.show_next_conflict();
+        } else if let Some(modal) = self.conflict_modal.as_mut() {
+            modal.remaining = self.conflict_queue.len() - 1;
+        }
+    }
+
+    /// Answer the conflict shown in the modal and move on to the next one.
+    pub fn answer_conflict(&mut self, resolution: ConflictResolution, apply_to_all: bool) {
+        let Some(query) = self.conflict_queue.pop_front() else {
+            self.close_conflict();
+            return;
+        };
+
+        let policy = match resolution {
+            ConflictResolution::Overwrite => Some(ConflictPolicy::Overwrite),
+            ConflictResolution::Skip => Some(ConflictPolicy::Skip),
+            ConflictResolution::Rename => Some(ConflictPolicy::Rename),
+            ConflictResolution::KeepLarger => Some(ConflictPolicy::KeepLarger),
+            ConflictResolution::KeepNewer => Some(ConflictPolicy::KeepNewer),
+            ConflictResolution::Cancel => None,
+        };
+        let answer = match policy {
+            Some(policy) => ConflictAnswer::Resolve {
+                policy,
+                apply_to_all,
+            },
+            None => ConflictAnswer::Cancel,
+        };
+        // The transfer may have been cancelled while the modal was open
+        let _ = query.response.send(answer);
+        self.show_next_conflict();
+    }
+
+    /// Show the conflict at the front of the queue, or close the modal.
+    fn show_next_conflict(&mut self) {
+        self.conflict_modal = self.conflict_queue.front().map(|query| {
+            ConflictModal::new(
+                conflict_info(&query.conflict),
+                self.conflict_queue.len() - 1,
+            )
+        });
+    }
+
+    // ========== Sidebar / Quick Access ==========
+
+    /// Toggle sidebar visibility.
+    pub fn toggle_sidebar(&mut self) {
+        self.sidebar_visible = !self.sidebar_visible;
+        if self.sidebar_visible {
+            // Refresh drives when showing sidebar
+            self.drives = drives_with_devices(&self.devices);
+            self.reveal_in_sidebar_tree();
+        }
+    }
+
+    /// Show the active pane's directory in the sidebar's folder tree.
+    fn reveal_in_sidebar_tree(&mut self) {
+        let path = self.active().nav.current_path().to_path_buf();
+        self.sidebar_state.reveal_in_tree(&path, self.show_hidden);
+    }
+
+    /// Open the drive menu for `pane`.
+    pub fn show_drive_menu(&mut self, pane: Pane) {
+        let current = self.pane(pane).nav.current_path();
+        self.drive_menu = Some(DriveMenu::new(pane, &self.drives, current));
+    }
+
+    /// Check if the drive menu is open.
+    pub fn has_drive_menu(&self) -> bool {
+        self.drive_menu.is_some()
+    }
+
+    /// Close the drive menu.
+    pub fn close_drive_menu(&mut self) {
+        self.drive_menu = None;
+    }
+
+    /// Switch the menu's pane to the drive at `index` and make it active.
+    pub fn choose_drive(&mut self, index: usize) {
+        let Some(menu) = self.drive_menu.take() else {
+            return;
+        };
+        let Some(drive) = self.drives.get(index) else {
+            return;
+        };
+        if !drive.is_ready {
+            self.set_status("Drive is not ready", true);
+            return;
+        }
+        let path = drive.path.clone();
+        self.active_pane = menu.pane();
+        self.navigate_to_path(path);
+    }
+
+    /// Take the drive list after drives were plugged in or removed.
+    ///
+    /// Panes showing a removed drive move to the other pane's directory, or
+    /// the home directory if that is gone too. Returns the panes that moved,
+    /// whose new directory needs loading.
+    pub fn apply_drive_change(&mut self, change: DriveChange) -> Vec<Pane> {
+        let mut drives = change.drives;
+        drives.extend(self.devices.drives());
+        let old = std::mem::replace(&mut self.drives, drives);
+        for root in &change.added {
+            self.set_status(format!("Drive {} connected", root.display()), false);
+        }
+
+        let on_removed = |path: &Path| {
+            drive_for_path(path, &old).is_some_and(|d| change.removed.contains(&d.path))
+        };
+        let mut moved = Vec::new();
+        for pane in [Pane::Left, Pane::Right] {
+            let current = self.pane(pane).nav.current_path().to_path_buf();
+            if !on_removed(&current) {
+                continue;
+            }
+            let other = self.pane(pane.toggle()).nav.current_path().to_path_buf();
+            let fallback = if !on_removed(&other) && other.is_dir() {
+                other
+            } else if let Some(home) = dirs::home_dir().filter(|home| !on_removed(home)) {
+                home
+            } else if let Some(drive) = self.drives.iter().find(|d| d.is_ready) {
+                drive.path.clone()
+            } else {
+                continue;
+            };
+
+            let state = self.pane_mut(pane);
+            state.nav.navigate_to(&fallback);
+            state.selection.clear();
+            state.set_entries(Vec::new());
+            state.set_cursor(0);
+            self.set_status(
+                format!(
+                    "Drive of {} was removed; moved to {}",
+                    current.display(),
+                    fallback.display()
+                ),
+                true,
+            );
+            moved.push(pane);
+        }
+
+        if self.sidebar_visible {
+            self.reveal_in_sidebar_tree();
+        }
+        moved
+    }
+
+    /// Add current directory to favorites.
+    pub fn add_current_to_favorites(&mut self) {
+        let path = self.active().nav.current_path().to_path_buf();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Favorite")
+            .to_string();
+
+        let favorite = Favorite::new(name.clone(), path);
+        self.config.add_favorite(favorite);
+        self.favorites = self.config.favorites.clone();
+
+        // Try to save config
+        if let Err(e) = self.config.save() {
+            self.set_status(format!("Failed to save config: {}", e), true);
+        } else {
+            self.set_status(format!("Added '{}' to favorites", name), false);
+        }
+    }
+
+    /// Quick jump to a favorite by number (1-9).
+    pub fn quick_jump_to_favorite(&mut self, num: u8) {
+        // Only works when sidebar is visible
+        if !self.sidebar_visible || num == 0 {
+            return;
+        }
+
+        let idx = (num as usize).saturating_sub(1);
+        if idx < self.favorites.len() {
+            self.sidebar_state.select_by_number(num as usize, self.favorites.len());
+            // Navigate to the favorite
+            if let Some(fav) = self.favorites.get(idx) {
+                if fav.is_valid() {
+                    self.navigate_to_path(fav.path.clone());
+                } else {
+                    self.set_status(format!("Favorite '{}' is broken", fav.name), true);
+                }
+            }
+        }
+    }
+
+    /// Navigate to a specific path.
+    pub fn navigate_to_path(&mut self, path: PathBuf) {
+        let pane = self.active_mut();
+        pane.nav.navigate_to(&path);
+        pane.selection.clear();
+        pane.set_cursor(0);
+        let _ = self.event_tx.send(Event::DirectoryChanged(path));
+    }
+
+    /// Navigate to the selected sidebar item.
+    pub fn navigate_to_sidebar_selection(&mut self) {
+        match self.sidebar_state.section {
+            crate::ui::SidebarSection::Favorites => {
+                if let Some(idx) = self.sidebar_state.selected_favorite() {
+                    if let Some(fav) = self.favorites.get(idx) {
+                        if fav.is_valid() {
+                            self.navigate_to_path(fav.path.clone());
+                        } else {
+                            self.set_status(format!("Favorite '{}' is broken", fav.name), true);
+                        }
+                    }
+                }
+            }
+            crate::ui::SidebarSection::Drives => {
+                if let Some(idx) = self.sidebar_state.selected_drive() {
+                    if let Some(drive) = self.drives.get(idx) {
+                        if drive.is_ready {
+                            self.navigate_to_path(drive.path.clone());
+                        } else {
+                            self.set_status("Drive is not ready", true);
+                        }
+                    }
+                }
+            }
+            crate::ui::SidebarSection::Tree => {
+                let index = self.sidebar_state.selected_tree_node();
+                if let Some(node) = index.and_then(|i| self.sidebar_state.tree.get(i)) {
+                    self.navigate_to_path(node.path.clone());
+                }
+            }
+        }
+    }
+
+    /// Expand the selected folder in the sidebar tree.
+    pub fn sidebar_expand(&mut self) {
+        if let Err(e) = self.sidebar_state.expand_tree_node() {
+            self.set_status(format!("Cannot open folder: {}", e), true);
+        }
+    }
+
+    /// Collapse the selected folder in the sidebar tree.
+    pub fn sidebar_collapse(&mut self) {
+        self.sidebar_state.collapse_tree_node();
+    }
+
+    /// Move sidebar selection up.
+    pub fn sidebar_up(&mut self) {
+        self.sidebar_state.up(self.favorites.len(), self.drives.len());
+    }
+
+    /// Move sidebar selection down.
+    pub fn sidebar_down(&mut self) {
+        self.sidebar_state.down(self.favorites.len(), self.drives.len());
+    }
+
+    /// Toggle sidebar section.
+    pub fn sidebar_toggle_section(&mut self) {
+        self.sidebar_state.toggle_section();
+        if self.sidebar_state.section == crate::ui::SidebarSection::Tree {
+            self.reveal_in_sidebar_tree();
+        }
+    }
+
+    /// Show the volume details of the drive selected in the sidebar.
+    pub fn show_drive_details(&mut self) {
+        let drive = self.sidebar_state.selected_drive().and_then(|i| self.drives.get(i));
+        self.drive_details = drive.cloned();
+    }
+
+    /// Check if drive details are showing.
+    pub fn has_drive_details(&self) -> bool {
+        self.drive_details.is_some()
+    }
+
+    /// Close the drive details.
+    pub fn close_drive_details(&mut self) {
+        self.drive_details = None;
+    }
+
+    /// Remove selected favorite from sidebar.
+    pub fn remove_selected_favorite(&mut self) {
+        if let Some(idx) = self.sidebar_state.selected_favorite() {
+            if let Some(fav) = self.favorites.get(idx) {
+                let id = fav.id.clone();
+                let name = fav.name.clone();
+                self.config.remove_favorite(&id);
+                self.favorites = self.config.favorites.clone();
+
+                if let Err(e) = self.config.save() {
+                    self.set_status(format!("Failed to save config: {}", e), true);
+                } else {
+                    self.set_status(format!("Removed '{}' from favorites", name), false);
+                }
+            }
+        }
+    }
+
+    // ========== Properties ==========
+
+    /// Show properties for the current entry, or for the selection when
+    /// several items are selected.
+    pub fn show_properties(&mut self) {
+        let targets = self.get_operation_targets();
+        if targets.len() > 1 {
+            self.show_multi_properties(targets);
+            return;
+        }
+        if let Some(entry) = self.active().current_entry() {
+            match zmanager_core::get_properties(&entry.path) {
+                Ok(props) => {
+                    // Folder totals fill in while the panel is open
+                    if props.kind.is_directory() {
+                        self.start_size_job(props.path.clone());
+                    }
+                    self.properties = Some(props);
+                }
+                Err(e) => {
+                    self.set_status(format!("Failed to get properties: {}", e), true);
+                }
+            }
+        }
+    }
+
+    /// Show the combined properties of `paths`.
+    ///
+    /// The totals are calculated in the background; running totals come back
+    /// as [`Event::MultiPropertiesProgress`] and the result as
+    /// [`Event::MultiPropertiesFinished`]. Does nothing outside a Tokio
+    /// runtime.
+    fn show_multi_properties(&mut self, paths: Vec<PathBuf>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let id = self.next_multi_properties;
+        self.next_multi_properties += 1;
+        let token = CancellationToken::new();
+        self.multi_properties_job = Some((id, token.clone()));
+        self.multi_properties = Some(MultiProperties {
+            item_count: paths.len(),
+            ..Default::default()
+        });
+
+        let tx = self.event_tx.clone();
+        runtime.spawn_blocking(move || {
+            let mut last_progress: Option<Instant> = None;
+            let result = calculate_multi_properties_with_progress(&paths, &token, |totals| {
+                if last_progress.is_some_and(|t| t.elapsed() < JOB_PROGRESS_REFRESH) {
+                    return;
+                }
+                last_progress = Some(Instant::now());
+                let _ = tx.send(Event::MultiPropertiesProgress(id, totals.clone()));
+            });
+            let result = match result {
+                Ok(totals) => Ok(totals),
+                // The panel was closed
+                Err(ZError::Cancelled) => return,
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(Event::MultiPropertiesFinished(id, result));
+        });
+    }
+
+    /// Whether the multi-selection properties are still being calculated.
+    pub fn is_calculating_multi_properties(&self) -> bool {
+        self.multi_properties_job.is_some()
+    }
+
+    /// Show the running totals of a multi-selection properties calculation.
+    pub fn apply_multi_properties_progress(&mut self, id: u64, totals: MultiProperties) {
+        if self.multi_properties_job.as_ref().is_some_and(|(job, _)| *job == id) {
+            self.multi_properties = Some(totals);
+        }
+    }
+
+    /// Record the result of a multi-selection properties calculation.
+    pub fn apply_multi_properties_result(
+        &mut self,
+        id: u64,
+        result: Result<MultiProperties, String>,
+    ) {
+        if self.multi_properties_job.take_if(|(job, _)| *job == id).is_none() {
+            return;
+        }
+        match result {
+            Ok(totals) => self.multi_properties = Some(totals),
+            Err(message) => {
+                self.set_status(format!("Failed to get properties: {}", message), true);
+            }
+        }
+    }
+
+    /// Replace the properties panel with an editor for the item's attributes
+    /// and timestamps.
+    ///
+    /// Only a single item can be edited.
+    pub fn open_properties_editor(&mut self) {
+        if let Some(props) = self.properties.as_ref() {
+            self.properties_editor = Some(PropertiesEditor::new(props));
+            self.close_properties();
+        }
+    }
+
+    /// Check if the properties editor is open.
+    pub fn has_properties_editor(&self) -> bool {
+        self.properties_editor.is_some()
+    }
+
+    /// Close the properties editor.
+    pub fn close_properties_editor(&mut self) {
+        self.properties_editor = None;
+    }
+
+    /// Open the filter builder on the current filter.
+    pub fn show_filter_builder(&mut self) {
+        self.filter_builder = Some(FilterBuilder::new(&self.filter));
+    }
+
+    /// Check if the filter builder is open.
+    pub fn has_filter_builder(&self) -> bool {
+        self.filter_builder.is_some()
+    }
+
+    /// Close the filter builder.
+    pub fn close_filter_builder(&mut self) {
+        self.filter_builder = None;
+    }
+
+    /// Filter both panes with `filter` from the filter builder.
+    ///
+    /// Listings are filtered as they are read, so directories with many
+    /// entries only send the ones that match.
+    pub fn apply_filter(&mut self, filter: FilterSpec) {
+        self.filter_builder = None;
+        self.filter = filter;
+        // Hidden files follow their own toggle and aren't part of the summary
+        let summary = FilterSpec { show_hidden: false, ..self.filter.clone() };
+        match summary.active_filters_description() {
+            Some(description) => self.set_status(format!("Filter: {}", description), false),
+            None => self.set_status("Filter cleared", false),
+        }
+        let _ = self.event_tx.send(Event::RefreshAll);
+    }
+
+    /// Apply changes made in the properties editor to `path`.
+    pub fn apply_property_changes(&mut self, path: PathBuf, changes: PropertyChanges) {
+        self.properties_editor = None;
+        if changes.is_empty() {
+            self.set_status("No properties changed", false);
+            return;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        match set_properties(&path, &changes) {
+            Ok(()) => {
+                self.set_status(format!("Updated properties of {}", name), false);
+                let _ = self.event_tx.send(Event::RefreshAll);
+            }
+            Err(e) => self.show_error("Properties", format!("Could not update {}: {}", name, e)),
+        }
+    }
+
+    /// Switch the properties panel between its General and Security tabs,
+    /// reading the item's owner and permissions for the Security tab.
+    pub fn toggle_security_tab(&mut self) {
+        if self.security.take().is_some() {
+            return;
+        }
+        if let Some(props) = &self.properties {
+            self.security = Some(get_security(&props.path).map_err(|e| e.to_string()));
+        }
+    }
+
+    /// Check if the properties panel shows its Security tab.
+    pub fn has_security_tab(&self) -> bool {
+        self.security.is_some()
+    }
+
+    /// Ask before taking ownership of the item in the properties panel, or
+    /// of the selected items.
+    pub fn confirm_take_ownership(&mut self) {
+        let targets = match &self.properties {
+            Some(props) => vec![props.path.clone()],
+            None => self.get_operation_targets(),
+        };
+        let what = match targets.as_slice() {
+            [] => return,
+            [path] => format!("'{}'", path.file_name().unwrap_or_default().to_string_lossy()),
+            paths => format!("{} items", paths.len()),
+        };
+        // The confirmation replaces the properties panel
+        self.close_properties();
+        let message = format!(
+            "Take ownership of {what} and grant yourself full control, including \
+             everything inside folders? Windows will ask for permission."
+        );
+        self.pending_operation = Some(PendingOperation::TakeOwnership(targets));
+        self.dialog = Some(Dialog::confirm("Take Ownership", message));
+    }
+
+    /// Take ownership of `paths` in the elevated helper.
+    pub fn execute_take_ownership(&mut self, paths: Vec<PathBuf>) {
+        let operations = paths
+            .into_iter()
+            .map(|path| ElevatedOperation::TakeOwnership {
+                recursive: path.is_dir(),
+                path,
+            })
+            .collect();
+        self.retry_elevated(None, ElevationManifest::new(operations));
+    }
+
+    /// Close the properties panel.
+    pub fn close_properties(&mut self) {
+        self.properties = None;
+        self.security = None;
+        self.multi_properties = None;
+        if let Some((_, token)) = self.multi_properties_job.take() {
+            token.cancel();
+        }
+    }
+
+    /// Check if properties panel is visible.
+    pub fn has_properties(&self) -> bool {
+        self.properties.is_some() || self.multi_properties.is_some()
+    }
+
+    // ========== Checksums ==========
+
+    /// Open the checksum dialog for the selected files (or the one under the
+    /// cursor). Folders are skipped.
+    pub fn show_checksums(&mut self) {
+        let targets = self.get_operation_targets();
+        let count = targets.len();
+        let files: Vec<PathBuf> = targets.into_iter().filter(|p| p.is_file()).collect();
+        if files.is_empty() {
+            self.set_status("No files to compute checksums of", false);
+            return;
+        }
+        if files.len() < count {
+            self.set_status(format!("Skipped {} folder(s)", count - files.len()), false);
+        }
+        let algorithm = HashAlgorithm::default();
+        self.checksums = Some(ChecksumView::new(files, algorithm));
+        self.start_checksum_job(algorithm);
+    }
+
+    /// Hash the files in the checksum dialog with `algorithm`, cancelling
+    /// the job for the previous algorithm.
+    ///
+    /// Progress comes back as [`Event::ChecksumProgress`] and the result as
+    /// [`Event::ChecksumFinished`]. Does nothing outside a Tokio runtime.
+    pub fn start_checksum_job(&mut self, algorithm: HashAlgorithm) {
+        if let Some((_, token)) = self.checksum_job.take() {
+            token.cancel();
+        }
+        let Some(view) = self.checksums.as_mut() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        view.restart(algorithm);
+        let job = Job::new(JobKind::Checksum {
+            paths: view.paths().to_vec(),
+            algorithm,
+        });
+        self.checksum_job = Some((job.id, job.cancellation.clone()));
+        runtime.spawn(run_checksum_job(job, self.event_tx.clone()));
+    }
+
+    /// Show the progress of the checksum job.
+    pub fn apply_checksum_progress(&mut self, job_id: JobId, progress: Progress) {
+        if self.checksum_job.as_ref().is_some_and(|(job, _)| *job == job_id) {
+            if let Some(view) = self.checksums.as_mut() {
+                view.set_progress(progress);
+            }
+        }
+    }
+
+    /// Show the result of the checksum job in the dialog.
+    pub fn apply_checksum_result(&mut self, job_id: JobId, result: Result<Vec<FileHash>, String>) {
+        if self.checksum_job.take_if(|(job, _)| *job == job_id).is_none() {
+            return;
+        }
+        if let Some(view) = self.checksums.as_mut() {
+            view.finish(result);
+        }
+    }
+
+    /// Check if the checksum dialog is open.
+    pub fn has_checksums(&self) -> bool {
+        self.checksums.is_some()
+    }
+
+    /// Close the checksum dialog, cancelling its job.
+    pub fn close_checksums(&mut self) {
+        self.checksums = None;
+        if let Some((_, token)) = self.checksum_job.take() {
+            token.cancel();
+        }
+    }
+
+    // ========== Comparing files ==========
+
+    /// The file a pane offers for comparison: its only selected entry, or
+    /// the one under the cursor.
+    fn compare_target(&self, pane: Pane) -> Option<PathBuf> {
+        let pane = self.pane(pane);
+        let selected = pane.selection.selected_entries(&pane.entries);
+        let entry = match selected.as_slice() {
+            [entry] => Some(*entry),
+            _ => pane.current_entry(),
+        }?;
+        Some(entry.path.clone()).filter(|path| path.is_file())
+    }
+
+    /// Open the compare dialog for a file from each pane and compare them
+    /// byte by byte.
+    pub fn show_compare(&mut self) {
+        let left = self.compare_target(Pane::Left);
+        let right = self.compare_target(Pane::Right);
+        let (Some(left), Some(right)) = (left, right) else {
+            self.set_status("Pick a file in each pane to compare", false);
+            return;
+        };
+        if left == right {
+            self.set_status("Both panes point at the same file", false);
+            return;
+        }
+        let mode = CompareMode::default();
+        self.compare = Some(CompareView::new(left, right, mode));
+        self.start_compare_job(mode);
+    }
+
+    /// Compare the files in the compare dialog in `mode`, cancelling the
+    /// job for the previous mode.
+    ///
+    /// Progress comes back as [`Event::CompareProgress`] and the result as
+    /// [`Event::CompareFinished`]. Does nothing outside a Tokio runtime.
+    pub fn start_compare_job(&mut self, mode: CompareMode) {
+        if let Some((_, token)) = self.compare_job.take() {
+            token.cancel();
+        }
+        let Some(view) = self.compare.as_mut() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        view.restart(mode);
+        let (left, right) = view.paths();
+        let job = Job::new(JobKind::Compare {
+            left: left.to_path_buf(),
+            right: right.to_path_buf(),
+            mode,
+        });
+        self.compare_job = Some((job.id, job.cancellation.clone()));
+        runtime.spawn(run_compare_job(job, self.event_tx.clone()));
+    }
+
+    /// Show the progress of the compare job.
+    pub fn apply_compare_progress(&mut self, job_id: JobId, progress: Progress) {
+        if self.compare_job.as_ref().is_some_and(|(job, _)| *job == job_id) {
+            if let Some(view) = self.compare.as_mut() {
+                view.set_progress(progress);
+            }
+        }
+    }
+
+    /// Show the result of the compare job in the dialog.
+    pub fn apply_compare_result(&mut self, job_id: JobId, result: Result<FileComparison, String>) {
+        if self.compare_job.take_if(|(job, _)| *job == job_id).is_none() {
+            return;
+        }
+        if let Some(view) = self.compare.as_mut() {
+            view.finish(result);
+        }
+    }
+
+    /// Check if the compare dialog is open.
+    pub fn has_compare(&self) -> bool {
+        self.compare.is_some()
+    }
+
+    /// Close the compare dialog, cancelling its job.
+    pub fn close_compare(&mut self) {
+        self.compare = None;
+        if let Some((_, token)) = self.compare_job.take() {
+            token.cancel();
+        }
+    }
+
+    // ========== Converting text files ==========
+
+    /// Open the convert dialog for the selected files (or the one under the
+    /// cursor). Folders are skipped.
+    pub fn show_convert(&mut self) {
+        let targets = self.get_operation_targets();
+        let count = targets.len();
+        let files: Vec<PathBuf> = targets.into_iter().filter(|p| p.is_file()).collect();
+        if files.is_empty() {
+            self.set_status("No files to convert", false);
+            return;
+        }
+        if files.len() < count {
+            self.set_status(format!("Skipped {} folder(s)", count - files.len()), false);
+        }
+        self.convert = Some(ConvertView::new(files));
+    }
+
+    /// Convert the files in the convert dialog.
+    ///
+    /// Progress comes back as [`Event::ConvertProgress`] and the result as
+    /// [`Event::ConvertFinished`]. Does nothing outside a Tokio runtime.
+    pub fn start_convert_job(&mut self, conversion: TextConversion) {
+        let Some(view) = self.convert.as_mut() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        view.start();
+        let job = Job::new(JobKind::ConvertText {
+            paths: view.paths().to_vec(),
+            conversion,
+        });
+        self.convert_job = Some((job.id, job.cancellation.clone()));
+        runtime.spawn(run_convert_job(job, self.event_tx.clone()));
+    }
+
+    /// Show the progress of the convert job.
+    pub fn apply_convert_progress(&mut self, job_id: JobId, progress: Progress) {
+        if self.convert_job.as_ref().is_some_and(|(job, _)| *job == job_id) {
+            if let Some(view) = self.convert.as_mut() {
+                view.set_progress(progress);
+            }
+        }
+    }
+
+    /// Show the outcome of the convert job in the dialog.
+    ///
+    /// The panes are refreshed, as converting changes sizes and backups add
+    /// files.
+    pub fn apply_convert_result(
+        &mut self,
+        job_id: JobId,
+        result: Result<Vec<FileConversion>, String>,
+    ) {
+        if self.convert_job.take_if(|(job, _)| *job == job_id).is_none() {
+            return;
+        }
+        if let Some(view) = self.convert.as_mut() {
+            view.finish(result);
+        }
+        let path = self.active().nav.current_path().to_path_buf();
+        let _ = self.event_tx.send(Event::DirectoryChanged(path));
+    }
+
+    /// Check if the convert dialog is open.
+    pub fn has_convert(&self) -> bool {
+        self.convert.is_some()
+    }
+
+    /// Close the convert dialog, stopping its job after the file in progress.
+    pub fn close_convert(&mut self) {
+        self.convert = None;
+        if let Some((_, token)) = self.convert_job.take() {
+            token.cancel();
+            // Files converted before the stop still changed
+            let path = self.active().nav.current_path().to_path_buf();
+            let _ = self.event_tx.send(Event::DirectoryChanged(path));
+        }
+    }
+
+    /// Close the help screen.
+    pub fn close_help(&mut self) {
+        self.show_help = false;
+    }
+}
+
+/// Whether a transfer reads from or writes to a network share or mapped
+/// network drive.
+fn touches_network(sources: &[PathBuf], destination: &Path, drives: &[DriveInfo]) -> bool {
+    std::iter::once(destination)
+        .chain(sources.iter().map(PathBuf::as_path))
+        .any(|path| is_network_path(path, drives))
+}
+
+/// The system's drives, followed by the devices connected to it.
+fn drives_with_devices(devices: &MtpFileSystem) -> Vec<DriveInfo> {
+    let mut drives = zmanager_core::list_drives().unwrap_or_default();
+    drives.extend(devices.drives());
+    drives
+}
+
+/// Turn clipboard text into a file name.
+///
+/// Surrounding whitespace (such as the trailing newline many apps append) is
+/// trimmed; anything else must pass the shared filename validator, which also
+/// rejects multi-line text.
+fn clipboard_text_to_name(text: &str) -> ZResult<String> {
+    let name = text.trim();
+    validate_filename(name)?;
+    Ok(name.to_string())
+}
+
+/// Grouping key for same-extension navigation.
+///
+/// Files without an extension form their own group, and directories are kept
+/// apart from files so that `build` (a folder) and `build` (a script) don't
+/// match.
+fn extension_group(entry: &EntryMeta) -> (bool, Option<&str>) {
+    (entry.kind.is_directory(), entry.extension.as_deref())
+}
+
+/// Suggest an archive name: the item's own name for a single item, the
+/// current folder's name for several.
+fn default_archive_name(files: &[PathBuf], directory: &Path) -> String {
+    let base = match files {
+        [single] if single.is_dir() => single.file_name(),
+        [single] => single.file_stem(),
+        _ => directory.file_name(),
+    };
+    let base = base.map(|b| b.to_string_lossy().into_owned());
+    format!("{}.zip", base.as_deref().unwrap_or("Archive"))
+}
+
+/// Turn the typed archive name into a file name, adding `.zip` if needed.
+fn archive_file_name(input: &str) -> ZResult<String> {
+    let name = input.trim();
+    validate_filename(name)?;
+    if is_browsable_archive(name) {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{}.zip", name))
+    }
+}
+
+/// List the renames of a plan for the confirmation dialog.
+fn bulk_rename_preview(plan: &RenamePlan) -> String {
+    let name = |path: &Path| path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let changed: Vec<_> = plan.items.iter().filter(|item| !item.is_unchanged()).collect();
+
+    let mut lines: Vec<String> = changed
+        .iter()
+        .take(BULK_RENAME_PREVIEW_LINES)
+        .map(|item| format!("{} -> {}", name(&item.from), name(&item.to)))
+        .collect();
+    if changed.len() > BULK_RENAME_PREVIEW_LINES {
+        lines.push(format!("... and {} more", changed.len() - BULK_RENAME_PREVIEW_LINES));
+    }
+    lines.push(format!("Rename {} item(s)?", changed.len()));
+    lines.join("\n")
+}
+
+/// Start transfers as the scheduler hands out slots and keep the transfers
+/// view in step with the job list.
+async fn watch_scheduler(
+    scheduler: SchedulerHandle,
+    transfer_config: FolderTransferConfig,
+    network_concurrency: usize,
+    delete_config: DeleteConfig,
+    drives: Vec<DriveInfo>,
+    tx: mpsc::UnboundedSender<Event>,
+) {
+    let mut events = scheduler.subscribe();
+    let mut last_progress_refresh: Option<Instant> = None;
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => Some(event),
+            Err(RecvError::Lagged(_)) => None,
+            Err(RecvError::Closed) => break,
+        };
+
+        if let Some(SchedulerEvent::JobStarted(id)) = event {
+            if let Some(job) = scheduler.job(id).await {
+                match job.kind {
+                    JobKind::Copy { ref sources, ref destination }
+                    | JobKind::Move { ref sources, ref destination } => {
+                        // Transfers to or from a network drive copy fewer
+                        // files at once
+                        let mut config = transfer_config.clone();
+                        if touches_network(sources, destination, &drives) {
+                            config.concurrency = network_concurrency;
+                        }
+                        tokio::spawn(run_transfer_job(job, config, scheduler.clone(), tx.clone()));
+                    }
+                    JobKind::Delete { .. } | JobKind::DeletePermanent { .. } => {
+                        let config = delete_config.clone();
+                        tokio::spawn(run_delete_job(job, config, scheduler.clone(), tx.clone()));
+                    }
+                    JobKind::Compress { .. } => {
+                        tokio::spawn(run_compress_job(job, scheduler.clone()));
+                    }
+                    JobKind::Extract { .. } => {
+                        tokio::spawn(run_extract_job(job, scheduler.clone()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Progress can arrive for every file; don't redraw for each one
+        if let Some(SchedulerEvent::JobProgress(..)) = event {
+            if last_progress_refresh.is_some_and(|t| t.elapsed() < JOB_PROGRESS_REFRESH) {
+                continue;
+            }
+            last_progress_refresh = Some(Instant::now());
+        }
+
+        let mut jobs = scheduler.list_jobs().await;
+        jobs.sort_by_key(|job| job.id.0);
+        if tx.send(Event::JobsUpdated(jobs)).is_err() {
+            break;
+        }
+
+        let finished = match event {
+            Some(SchedulerEvent::JobCompleted(id)) => Some((id, true)),
+            Some(SchedulerEvent::JobFailed(id, _) | SchedulerEvent::JobCancelled(id)) => {
+                Some((id, false))
+            }
+            _ => None,
+        };
+        if let Some((id, success)) = finished {
+            let _ = tx.send(Event::JobCompleted {
+                job_id: id.0,
+                success,
+            });
+        }
+    }
+}
+
+/// Run a copy or move job, reporting its progress and outcome to the
+/// scheduler and passing its conflicts to the event loop for the user to
+/// answer.
+async fn run_transfer_job(
+    job: Job,
+    config: FolderTransferConfig,
+    scheduler: SchedulerHandle,
+    tx: mpsc::UnboundedSender<Event>,
+) {
+    let operation = match job.kind {
+        JobKind::Move { .. } => TransferOperation::Move,
+        _ => TransferOperation::Copy,
+    };
+    let _ = tx.send(Event::JobStarted(job.id.0, operation));
+
+    let mut executor = FolderTransferExecutor::with_config(config);
+    let item_tx = tx.clone();
+    if let Some(mut queries) = executor.take_conflict_receiver() {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            while let Some(query) = queries.recv().await {
+                let _ = tx.send(Event::ConflictAsked(query));
+            }
+        });
+    }
+    let mut events = executor.subscribe();
+    let progress_scheduler = scheduler.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(FolderTransferEvent::Progress { job_id, progress }) => {
+                    progress_scheduler.report_progress(job_id, progress).await;
+                }
+                Ok(FolderTransferEvent::Started { job_id, stats }) if stats.excluded > 0 => {
+                    let _ = item_tx.send(Event::JobExcluded(job_id.0, stats.excluded));
+                }
+                Ok(FolderTransferEvent::ItemCompleted { job_id, result }) => {
+                    let item = TransferItemResult::from(&result);
+                    let _ = item_tx.send(Event::JobItemFinished(job_id.0, item));
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // The default policy asks the user about every file conflict
+    let resolver = Arc::new(Mutex::new(ConflictResolver::new()));
+    let result = executor.execute_job(&job, resolver).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+
+    match result {
+        Ok(report) if report.failed == 0 => scheduler.complete(job.id).await,
+        Ok(report) => {
+            let message = format!("{} of {} item(s) failed", report.failed, report.items.len());
+            let mut builder = ReportBuilder::new(job.id, operation);
+            builder.set_excluded(report.excluded);
+            for item in &report.items {
+                builder.add_item(TransferItemResult::from(item));
+            }
+            let report = builder.build();
+            offer_elevation(&job, &report, &tx);
+            let retry = retry_jobs(&job, &report);
+            scheduler.fail_items(job.id, message, retry).await;
+        }
+        // Cancelled from the transfers view, the scheduler already knows
+        Err(ZError::Cancelled) if job.cancellation.is_cancelled() => {}
+        // Cancelled from the conflict modal
+        Err(ZError::Cancelled) => {
+            scheduler.cancel(job.id).await;
+        }
+        Err(e) => scheduler.fail(job.id, e.to_string()).await,
+    }
+}
+
+/// Run a delete job, reporting its progress and outcome to the scheduler
+/// and its items to the event loop.
+async fn run_delete_job(
+    job: Job,
+    config: DeleteConfig,
+    scheduler: SchedulerHandle,
+    tx: mpsc::UnboundedSender<Event>,
+) {
+    let _ = tx.send(Event::JobStarted(job.id.0, TransferOperation::Delete));
+    let executor = DeleteExecutor::with_config(config);
+    let mut events = executor.subscribe();
+    let progress_scheduler = scheduler.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(DeleteEvent::Progress { job_id, progress }) => {
+                    progress_scheduler.report_progress(job_id, progress).await;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let result = executor.execute_job(&job, job.cancellation.clone()).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+
+    if let Ok(report) = &result {
+        for item in &report.items {
+            let _ = tx.send(Event::JobItemFinished(job.id.0, item.clone()));
+        }
+    }
+
+    match result {
+        // Cancelled from the transfers view, the scheduler already knows
+        Ok(report) if report.was_cancelled => {}
+        Ok(report) if report.summary.failed == 0 => scheduler.complete(job.id).await,
+        Ok(report) => {
+            let first = report.failed_items().next().and_then(|item| item.reason.clone());
+            let message = format!(
+                "{} of {} item(s) could not be deleted: {}",
+                report.summary.failed,
+                report.summary.total_items,
+                first.unwrap_or_default()
+            );
+            offer_elevation(&job, &report, &tx);
+            let retry = retry_jobs(&job, &report);
+            scheduler.fail_items(job.id, message, retry).await;
+        }
+        Err(e) => scheduler.fail(job.id, e.to_string()).await,
+    }
+}
+
+/// Run a compress job, reporting its progress and outcome to the scheduler.
+async fn run_compress_job(job: Job, scheduler: SchedulerHandle) {
+    let executor = CompressExecutor::new();
+    let mut events = executor.subscribe();
+    let progress_scheduler = scheduler.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(CompressEvent::Progress { job_id, progress }) => {
+                    progress_scheduler.report_progress(job_id, progress).await;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let result = executor.execute_job(&job, job.cancellation.clone()).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+
+    match result {
+        Ok(report) => match report.skipped.first() {
+            None => scheduler.complete(job.id).await,
+            Some((path, reason)) => {
+                let message = format!(
+                    "{} item(s) left out of the archive, e.g. {}: {}",
+                    report.skipped.len(),
+                    path.display(),
+                    reason
+                );
+                scheduler.fail_items(job.id, message, Vec::new()).await;
+            }
+        },
+        // Cancelled from the transfers view, the scheduler already knows
+        Err(ZError::Cancelled) if job.cancellation.is_cancelled() => {}
+        Err(e) => scheduler.fail(job.id, e.to_string()).await,
+    }
+}
+
+/// Run an extract job, reporting its progress and outcome to the scheduler.
+async fn run_extract_job(job: Job, scheduler: SchedulerHandle) {
+    let executor = ExtractExecutor::new();
+    let mut events = executor.subscribe();
+    let progress_scheduler = scheduler.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(ExtractEvent::Progress { job_id, progress }) => {
+                    progress_scheduler.report_progress(job_id, progress).await;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // Nobody is asked during an extraction; files already there are kept
+    let resolver = ConflictResolver::skip_all();
+    let result = executor.execute_job(&job, resolver, job.cancellation.clone()).await;
+    // Dropping the executor closes the event channel, ending the forwarder
+    drop(executor);
+    let _ = forward.await;
+
+    match result {
+        Ok(_) => scheduler.complete(job.id).await,
+        // Cancelled from the transfers view, the scheduler already knows
+        Err(ZError::Cancelled) if job.cancellation.is_cancelled() => {}
+        Err(e) => scheduler.fail(job.id, e.to_string()).await,
+    }
+}
+
+/// Offer to retry the items of `report` that were denied access as
+/// administrator.
+fn offer_elevation(job: &Job, report: &DetailedTransferReport, tx: &mpsc::UnboundedSender<Event>) {
+    let permanent = matches!(job.kind, JobKind::DeletePermanent { .. });
+    let manifest = ElevationManifest::from_report(report, permanent);
+    if !manifest.is_empty() {
+        let _ = tx.send(Event::AccessDenied(job.id.0, manifest));
+    }
+}
+
+/// Jobs that redo the failed items of `report`, keeping a permanent delete
+/// permanent.
+fn retry_jobs(job: &Job, report: &DetailedTransferReport) -> Vec<JobKind> {
+    let permanent = matches!(job.kind, JobKind::DeletePermanent { .. });
+    report
+        .retry_jobs()
+        .into_iter()
+        .map(|kind| match kind {
+            JobKind::Delete { paths } if permanent => JobKind::DeletePermanent { paths },
+            kind => kind,
+        })
+        .collect()
+}
+
+/// Describe a transfer conflict for the conflict modal.
+fn conflict_info(conflict: &Conflict) -> ConflictInfo {
+    let format_time = |time: Option<std::time::SystemTime>| {
+        time.map(|time| {
+            chrono::DateTime::<chrono::Local>::from(time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+    };
+    ConflictInfo {
+        source: conflict.source.clone(),
+        destination: conflict.destination.clone(),
+        source_size: conflict.source_size,
+        dest_size: conflict.dest_size,
+        source_modified: format_time(conflict.source_modified),
+        dest_modified: format_time(conflict.dest_modified),
+    }
+}
+
+/// Run a job to its end while `forward` turns the events of its executor
+/// into events for the event loop.
+///
+/// `run` executes the job and owns the executor, so the event channel closes
+/// once it finishes. Returns the outcome, or `None` for a cancelled job,
+/// since whatever cancelled it already updated the view.
+async fn run_forwarded<E, T>(
+    mut events: broadcast::Receiver<E>,
+    run: impl Future<Output = ZResult<T>>,
+    tx: &mpsc::UnboundedSender<Event>,
+    mut forward: impl FnMut(E) -> Option<Event> + Send + 'static,
+) -> Option<Result<T, String>>
+where
+    E: Clone + Send + 'static,
+{
+    let progress_tx = tx.clone();
+    let forwarder = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Some(event) = forward(event) {
+                        let _ = progress_tx.send(event);
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let result = run.await;
+    let _ = forwarder.await;
+
+    match result {
+        Ok(output) => Some(Ok(output)),
+        Err(ZError::Cancelled) => None,
+        Err(e) => Some(Err(e.to_string())),
+    }
+}
+
+/// Run a size job, forwarding its running totals to the event loop.
+async fn run_size_job(job: Job, tx: mpsc::UnboundedSender<Event>) {
+    let JobKind::CalculateSize { path } = job.kind.clone() else {
+        return;
+    };
+
+    let executor = SizeExecutor::new();
+    let events = executor.subscribe();
+    let progress_path = path.clone();
+    let forward = move |event| match event {
+        SizeEvent::Progress { stats, .. } => {
+            Some(Event::FolderSizeProgress(progress_path.clone(), stats))
+        }
+        _ => None,
+    };
+    let run = async move { executor.execute_job(&job, job.cancellation.clone()).await };
+
+    if let Some(result) = run_forwarded(events, run, &tx, forward).await {
+        let _ = tx.send(Event::FolderSizeFinished(path, result));
+    }
+}
+
+/// Run the size job of a folder listed in a pane once one of `slots` is
+/// free, sending the size to the event loop unless `cancel` stops it.
+async fn run_directory_size_job(
+    job: Job,
+    cancel: CancellationToken,
+    slots: Arc<Semaphore>,
+    tx: mpsc::UnboundedSender<Event>,
+) {
+    let JobKind::CalculateSize { path } = job.kind.clone() else {
+        return;
+    };
+    let Ok(_slot) = slots.acquire_owned().await else {
+        return;
+    };
+    if cancel.is_cancelled() {
+        return;
+    }
+
+    let size = match SizeExecutor::new().execute_job(&job, cancel).await {
+        Ok(stats) => stats.total_size,
+        Err(ZError::Cancelled) => return,
+        // Unreadable folders still resolve, so they stop showing as pending
+        Err(_) => 0,
+    };
+    let _ = tx.send(Event::DirectorySizeComputed(path, size));
+}
+
+/// Run a checksum job, forwarding its progress to the event loop.
+async fn run_checksum_job(job: Job, tx: mpsc::UnboundedSender<Event>) {
+    let job_id = job.id;
+    let executor = ChecksumExecutor::new();
+    let events = executor.subscribe();
+    let forward = |event| match event {
+        ChecksumEvent::Progress { job_id, progress } => {
+            Some(Event::ChecksumProgress(job_id, progress))
+        }
+        _ => None,
+    };
+    let run = async move { executor.execute_job(&job, job.cancellation.clone()).await };
+
+    if let Some(result) = run_forwarded(events, run, &tx, forward).await {
+        let _ = tx.send(Event::ChecksumFinished(job_id, result));
+    }
+}
+
+/// Run a compare job, forwarding its progress to the event loop.
+async fn run_compare_job(job: Job, tx: mpsc::UnboundedSender<Event>) {
+    let job_id = job.id;
+    let executor = CompareExecutor::new();
+    let events = executor.subscribe();
+    let forward = |event| match event {
+        CompareEvent::Progress { job_id, progress } => {
+            Some(Event::CompareProgress(job_id, progress))
+        }
+        _ => None,
+    };
+    let run = async move { executor.execute_job(&job, job.cancellation.clone()).await };
+
+    if let Some(result) = run_forwarded(events, run, &tx, forward).await {
+        let _ = tx.send(Event::CompareFinished(job_id, result));
+    }
+}
+
+/// Run a text conversion job, forwarding its progress to the event loop.
+async fn run_convert_job(job: Job, tx: mpsc::UnboundedSender<Event>) {
+    let job_id = job.id;
+    let executor = ConvertExecutor::new();
+    let events = executor.subscribe();
+    let forward = |event| match event {
+        ConvertEvent::Progress { job_id, progress } => {
+            Some(Event::ConvertProgress(job_id, progress))
+        }
+        _ => None,
+    };
+    let run = async move { executor.execute_job(&job, job.cancellation.clone()).await };
+
+    if let Some(result) = run_forwarded(events, run, &tx, forward).await {
+        let _ = tx.send(Event::ConvertFinished(job_id, result));
+    }
+}
+
+/// Check `files` against an index of `root`, reusing the cached index when it
+/// was built for the same directory.
+fn scan_for_duplicates(
+    cache: &Mutex<Option<DuplicateIndex>>,
+    root: &Path,
+    files: &[EntryMeta],
+    cancel: &CancellationToken,
+) -> ZResult<DuplicateReport> {
+    let mut cache = cache.lock().map_err(|_| ZError::Internal {
+        message: "Duplicate index lock poisoned".to_string(),
+    })?;
+    let index = match cache.take() {
+        Some(index) if index.root() == root => index,
+        _ => DuplicateIndex::build(root, cancel)?,
+    };
+    cache.insert(index).find_duplicates(files, cancel)
+}
+
+/// Summarize an open-selected run for the status bar.
+///
+/// Only the first failure is spelled out; the status bar has a single line.
+fn open_summary(total: usize, failures: &[String]) -> (String, bool) {
+    let opened = total - failures.len();
+    match failures {
+        [] => (format!("Opened {} file(s)", opened), false),
+        [only] => (format!("Opened {} of {} file(s); failed {}", opened, total, only), true),
+        [first, rest @ ..] => (
+            format!(
+                "Opened {} of {} file(s); failed {} (and {} more)",
+                opened,
+                total,
+                first,
+                rest.len()
+            ),
+            true,
+        ),
+    }
+}
+
+/// Confirmation text for running the selected `executables`, listed by full
+/// path, alongside `others` ordinary files.
+fn run_selected_message(executables: &[PathBuf], others: usize) -> String {
+    let mut message = String::from("Run these programs?\n");
+    for path in executables {
+        message.push_str(&format!("\n{}", path.display()));
+    }
+    if others > 0 {
+        message.push_str(&format!("\n\nand open {} other file(s)", others));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::DialogKind;
+
+    fn create_test_app() -> App {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx)
+    }
+
+    /// Text and level of the newest status message, shown or waiting.
+    fn latest_status(app: &App) -> Option<(&str, StatusLevel)> {
+        app.status.latest().map(|status| (status.text.as_str(), status.level))
+    }
+
+    #[test]
+    fn app_starts_with_left_pane_active() {
+        let app = create_test_app();
+        assert_eq!(app.active_pane, Pane::Left);
+    }
+
+    #[test]
+    fn switch_pane_toggles() {
+        let mut app = create_test_app();
+        app.switch_pane();
+        assert_eq!(app.active_pane, Pane::Right);
+        app.switch_pane();
+        assert_eq!(app.active_pane, Pane::Left);
+    }
+
+    #[test]
+    fn tabs_keep_their_own_location() {
+        let mut pane = PaneState::new(PathBuf::from("C:\\"));
+        pane.open_tab();
+        assert_eq!(pane.tabs().len(), 2);
+        assert_eq!(pane.active_tab(), 1);
+
+        pane.nav.navigate_to("C:\\Users");
+        pane.next_tab();
+        assert_eq!(pane.active_tab(), 0);
+        assert_eq!(pane.nav.current_path(), Path::new("C:\\"));
+        pane.prev_tab();
+        assert_eq!(pane.nav.current_path(), Path::new("C:\\Users"));
+
+        assert!(pane.close_tab());
+        assert_eq!(pane.tabs().len(), 1);
+        assert_eq!(pane.nav.current_path(), Path::new("C:\\"));
+        assert!(!pane.close_tab());
+    }
+
+    #[test]
+    fn session_round_trips_through_restore() {
+        let root = tempfile::TempDir::new().unwrap();
+        let mut app = create_test_app();
+        app.config.general.restore_session = true;
+        app.left = PaneState::new(root.path().to_path_buf());
+        app.sort = SortSpec::by_size();
+        app.show_hidden = true;
+        app.switch_pane();
+
+        let mut session = app.session_state();
+        session.last_right_dir = Some(root.path().join("deleted"));
+
+        let mut restored = create_test_app();
+        restored.config.general.restore_session = true;
+        restored.config.session = Some(session);
+        restored.restore_session();
+        assert_eq!(restored.left.nav.current_path(), root.path());
+        assert_eq!(restored.right.nav.current_path(), Path::new("D:\\"));
+        assert_eq!(restored.sort.field, CoreSortField::Size);
+        assert!(restored.show_hidden);
+        assert_eq!(restored.active_pane, Pane::Right);
+
+        let mut disabled = create_test_app();
+        disabled.config.general.restore_session = false;
+        disabled.config.session = restored.config.session.clone();
+        disabled.restore_session();
+        assert_eq!(disabled.left.nav.current_path(), Path::new("C:\\"));
+        assert!(!disabled.show_hidden);
+    }
+
+    #[test]
+    fn picking_the_sorted_field_again_reverses_the_order() {
+        let mut app = create_test_app();
+        assert_eq!(app.sort.order, SortOrder::Ascending);
+        app.apply_sort(SortField::Name);
+        assert_eq!(app.sort.order, SortOrder::Descending);
+        app.apply_sort(SortField::Size);
+        assert_eq!((app.sort.field, app.sort.order), (CoreSortField::Size, SortOrder::Ascending));
+        assert_eq!(SortSettings::from_spec(&app.sort).field, "size");
+    }
+
+    #[test]
+    fn sort_menu_options_toggle_and_cycle() {
+        let mut app = create_test_app();
+        app.toggle_sort_option(SortOption::Natural);
+        app.toggle_sort_option(SortOption::DirectoriesFirst);
+        assert!(app.sort.natural && !app.sort.directories_first);
+
+        // The secondary field cycles through every field and back to none
+        let mut seen = Vec::new();
+        loop {
+            app.toggle_sort_option(SortOption::ThenBy);
+            match app.sort.then_by {
+                Some(field) => seen.push(field),
+                None => break,
+            }
+        }
+        assert_eq!(seen.len(), 5);
+        assert_eq!(seen[0], CoreSortField::Name);
+    }
+
+    #[test]
+    fn quit_action_sets_flag() {
+        let mut app = create_test_app();
+        app.handle_action(Action::Quit).unwrap();
+        assert!(app.should_quit);
+    }
+
+    fn select_files(app: &mut App, count: usize) {
+        let entries = (0..count)
+            .map(|i| {
+                let name = format!("file{}.txt", i);
+                EntryMeta::new(name.clone(), PathBuf::from(&name), zmanager_core::EntryKind::File)
+            })
+            .collect();
+        app.left.set_entries(entries);
+        app.left.select_all();
+    }
+
+    #[test]
+    fn open_selected_asks_above_threshold() {
+        let mut app = create_test_app();
+        app.config.general.open_selected_confirm_threshold = 2;
+        select_files(&mut app, 3);
+
+        app.handle_action(Action::OpenSelected).unwrap();
+        assert!(app.has_dialog());
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::OpenSelected(ref files, None, 0)) if files.len() == 3
+        ));
+    }
+
+    #[test]
+    fn open_selected_prompts_before_running_executables() {
+        let mut app = create_test_app();
+        let path = PathBuf::from("C:\\tools\\setup.exe");
+        let mut exe = entry("setup.exe", zmanager_core::EntryKind::File);
+        exe.path = path.clone();
+        app.left.set_entries(vec![exe, entry("notes.txt", zmanager_core::EntryKind::File)]);
+        app.left.select_all();
+
+        app.handle_action(Action::OpenSelected).unwrap();
+        // Nothing is opened until the user confirms
+        assert!(latest_status(&app).is_none());
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::OpenSelected(ref files, None, 0)) if files.len() == 2
+        ));
+        assert!(matches!(
+            app.dialog.as_ref().map(|d| &d.kind),
+            Some(crate::ui::dialog::DialogKind::Confirm { title, message })
+                if title == "Run Program" && message.contains(&path.display().to_string())
+        ));
+    }
+
+    #[test]
+    fn open_selected_skips_executables_when_disabled() {
+        let mut app = create_test_app();
+        app.config.general.executable_open = ExecutableOpenBehavior::Nothing;
+        app.config.general.open_selected_confirm_threshold = 0;
+        app.left.set_entries(vec![
+            entry("setup.exe", zmanager_core::EntryKind::File),
+            entry("notes.txt", zmanager_core::EntryKind::File),
+        ]);
+        app.left.select_all();
+
+        app.handle_action(Action::OpenSelected).unwrap();
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::OpenSelected(ref files, None, 1))
+                if *files == [PathBuf::from("notes.txt")]
+        ));
+
+        // A selection of executables alone opens nothing
+        app.close_dialog();
+        app.left.set_entries(vec![entry("setup.exe", zmanager_core::EntryKind::File)]);
+        app.left.select_all();
+        app.handle_action(Action::OpenSelected).unwrap();
+        assert!(app.pending_operation.is_none());
+        assert!(matches!(
+            latest_status(&app),
+            Some((text, StatusLevel::Error)) if text.contains("Skipped 1 executable")
+        ));
+    }
+
+    #[test]
+    fn open_selected_refuses_above_cap() {
+        let mut app = create_test_app();
+        app.config.general.open_selected_max = 4;
+        select_files(&mut app, 5);
+
+        app.handle_action(Action::OpenSelected).unwrap();
+        assert!(!app.has_dialog());
+        assert!(app.pending_operation.is_none());
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+    }
+
+    #[test]
+    fn open_summary_reports_failures() {
+        assert_eq!(open_summary(2, &[]), ("Opened 2 file(s)".to_string(), false));
+
+        let failures = vec!["a.txt: denied".to_string(), "b.txt: denied".to_string()];
+        let (message, is_error) = open_summary(3, &failures);
+        assert!(is_error);
+        assert_eq!(message, "Opened 1 of 3 file(s); failed a.txt: denied (and 1 more)");
+    }
+
+    #[test]
+    fn count_prefix_goes_up_multiple_levels() {
+        let root = tempfile::TempDir::new().unwrap();
+        let deep = root.path().join("a").join("b").join("c").join("d");
+        std::fs::create_dir_all(&deep).unwrap();
+
+        let mut app = create_test_app();
+        app.left.nav = NavigationState::new(&deep);
+        app.left.set_entries(vec![EntryMeta::new(
+            "x".to_string(),
+            deep.join("x"),
+            zmanager_core::EntryKind::File,
+        )]);
+        app.left.select_all();
+
+        app.handle_action(Action::QuickJump(3)).unwrap();
+        app.handle_action(Action::GoParent).unwrap();
+        assert_eq!(app.left.nav.current_path(), root.path().join("a"));
+        assert!(app.left.selection.is_empty());
+        assert!(latest_status(&app).is_none());
+
+        // The count is consumed; the next parent key goes up one level
+        app.handle_action(Action::GoParent).unwrap();
+        assert_eq!(app.left.nav.current_path(), root.path());
+
+        // Back returns to the start of the jump in one step
+        app.left.nav.go_back();
+        app.left.nav.go_back();
+        assert_eq!(app.left.nav.current_path(), deep);
+    }
+
+    #[test]
+    fn count_prefix_accumulates_and_is_discarded_by_other_actions() {
+        let mut app = create_test_app();
+        app.handle_action(Action::QuickJump(0)).unwrap();
+        assert_eq!(app.pending_count, None);
+
+        app.handle_action(Action::QuickJump(1)).unwrap();
+        app.handle_action(Action::QuickJump(0)).unwrap();
+        assert_eq!(app.pending_count, Some(10));
+
+        app.handle_action(Action::Down).unwrap();
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn drive_menu_switches_its_pane() {
+        let mut app = create_test_app();
+        let drive = |path: &str, is_ready| DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type: zmanager_core::DriveType::Fixed,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        };
+        app.drives = vec![drive("C:\\", true), drive("D:\\", true), drive("E:\\", false)];
+
+        app.handle_action(Action::DriveMenuRight).unwrap();
+        assert!(app.has_drive_menu());
+        app.choose_drive(2);
+        assert!(!app.has_drive_menu());
+        assert_eq!(app.right.nav.current_path(), Path::new("D:\\"));
+
+        app.handle_action(Action::DriveMenuRight).unwrap();
+        app.choose_drive(0);
+        assert_eq!(app.active_pane, Pane::Right);
+        assert_eq!(app.right.nav.current_path(), Path::new("C:\\"));
+        assert_eq!(app.left.nav.current_path(), Path::new("C:\\"));
+    }
+
+    #[test]
+    fn sidebar_shows_selected_drive_details() {
+        let mut app = create_test_app();
+        let drive = |path: &str| DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type: zmanager_core::DriveType::Fixed,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: vec![PathBuf::from(path)],
+            bitlocker: None,
+            cluster_size: None,
+        };
+        app.drives = vec![drive("C:\\"), drive("D:\\")];
+        app.sidebar_state.section = crate::ui::SidebarSection::Drives;
+        app.sidebar_down();
+
+        app.show_drive_details();
+        let shown = app.drive_details.as_ref().map(|d| d.path.clone());
+        assert_eq!(shown, Some(PathBuf::from("D:\\")));
+
+        app.close_drive_details();
+        assert!(!app.has_drive_details());
+    }
+
+    #[test]
+    fn removed_drive_moves_panes_off_it() {
+        let mut app = create_test_app();
+        let drive = |path: &str| DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type: zmanager_core::DriveType::Removable,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        };
+        app.drives = vec![drive("C:\\"), drive("D:\\")];
+        app.right.nav.navigate_to(Path::new("D:\\Photos"));
+
+        let moved = app.apply_drive_change(DriveChange {
+            added: vec![],
+            removed: vec![PathBuf::from("D:\\")],
+            drives: vec![drive("C:\\")],
+        });
+        assert_eq!(moved, [Pane::Right]);
+        assert_eq!(app.drives.len(), 1);
+        assert!(!app.right.nav.current_path().starts_with("D:\\"));
+        assert_eq!(app.left.nav.current_path(), Path::new("C:\\"));
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+
+        let moved = app.apply_drive_change(DriveChange {
+            added: vec![PathBuf::from("E:\\")],
+            removed: vec![],
+            drives: vec![drive("C:\\"), drive("E:\\")],
+        });
+        assert!(moved.is_empty());
+        assert_eq!(app.drives.len(), 2);
+    }
+
+    #[test]
+    fn network_transfer_notice_follows_config() {
+        let mut app = create_test_app();
+        app.drives = vec![DriveInfo {
+            path: PathBuf::from("Z:\\"),
+            label: String::new(),
+            drive_type: zmanager_core::DriveType::Network,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        }];
+        let sources = vec![PathBuf::from("C:\\data\\a.txt")];
+        let share = Path::new("Z:\\backup");
+
+        assert!(!app.involves_network(&sources, share));
+        app.config.operations.confirm_network_transfers = true;
+        assert!(app.involves_network(&sources, share));
+        assert!(!app.involves_network(&sources, Path::new("C:\\backup")));
+    }
+
+    #[test]
+    fn transfers_touch_the_network_from_either_end() {
+        let app = create_test_app();
+        let drives = vec![DriveInfo {
+            path: PathBuf::from("Z:\\"),
+            label: String::new(),
+            drive_type: zmanager_core::DriveType::Network,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        }];
+        let local = vec![PathBuf::from("C:\\data\\a.txt")];
+        let shared = vec![PathBuf::from("Z:\\data\\a.txt")];
+
+        assert!(touches_network(&local, Path::new("Z:\\backup"), &drives));
+        assert!(touches_network(&shared, Path::new("C:\\backup"), &drives));
+        assert!(touches_network(&local, Path::new("\\\\server\\share"), &drives));
+        assert!(!touches_network(&local, Path::new("D:\\backup"), &drives));
+
+        // Such transfers run at the lower network limit
+        let mut ops = app.config.operations.clone();
+        ops.max_concurrent_jobs = 4;
+        ops.network_concurrent_jobs = 1;
+        assert_eq!(ops.concurrency_for(false), 4);
+        assert_eq!(ops.concurrency_for(true), 1);
+    }
+
+    #[test]
+    fn invert_filter_toggles_active_pane_only() {
+        let mut app = create_test_app();
+
+        app.handle_action(Action::InvertFilter).unwrap();
+        assert!(app.left.filter_inverted);
+        assert!(!app.right.filter_inverted);
+
+        app.handle_action(Action::InvertFilter).unwrap();
+        assert!(!app.left.filter_inverted);
+        assert_eq!(latest_status(&app), Some(("Filter restored", StatusLevel::Info)));
+    }
+
+    #[test]
+    fn flat_view_toggles_for_current_folder() {
+        let mut app = create_test_app();
+
+        app.handle_action(Action::ToggleFlatView).unwrap();
+        assert_eq!(app.left.flat_base(), Some(Path::new("C:\\")));
+        let mut tab = TabState::new(PathBuf::from("C:\\Users"));
+        tab.flat_view = app.left.flat_view.clone();
+        assert_eq!(tab.flat_base(), None);
+
+        app.handle_action(Action::ToggleFlatView).unwrap();
+        assert_eq!(app.left.flat_base(), None);
+        assert_eq!(latest_status(&app), Some(("Flat view off", StatusLevel::Info)));
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(basket_path("Work"), PathBuf::from("D:\\"), tx);
+        app.handle_action(Action::ToggleFlatView).unwrap();
+        assert_eq!(app.left.flat_view, None);
+    }
+
+    fn entry(name: &str, kind: zmanager_core::EntryKind) -> EntryMeta {
+        EntryMeta::new(name.to_string(), PathBuf::from(name), kind)
+    }
+
+    #[test]
+    fn same_extension_navigation_wraps() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let mut pane = PaneState::new(PathBuf::from("."));
+        pane.set_entries(vec![
+            entry("logs", Directory),
+            entry("a.log", File),
+            entry("b.png", File),
+            entry("c.LOG", File),
+            entry("Makefile", File),
+            entry("d.log", File),
+            entry("README", File),
+        ]);
+
+        pane.set_cursor(1);
+        assert!(pane.next_same_ext());
+        assert_eq!(pane.cursor(), 3);
+        assert!(pane.next_same_ext());
+        assert_eq!(pane.cursor(), 5);
+        assert!(pane.next_same_ext());
+        assert_eq!(pane.cursor(), 1);
+        assert!(pane.prev_same_ext());
+        assert_eq!(pane.cursor(), 5);
+
+        // Extension-less files are their own group
+        pane.set_cursor(4);
+        assert!(pane.next_same_ext());
+        assert_eq!(pane.cursor(), 6);
+
+        // Nothing else shares the extension: the cursor stays put
+        pane.set_cursor(2);
+        assert!(!pane.prev_same_ext());
+        assert_eq!(pane.cursor(), 2);
+
+        // The only directory has no group mates
+        pane.set_cursor(0);
+        assert!(!pane.next_same_ext());
+    }
+
+    #[test]
+    fn computed_directory_size_resorts_pane() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let mut app = create_test_app();
+        app.sort = SortSpec { dirs_by_computed_size: true, ..SortSpec::by_size() };
+        let mut file = entry("file.bin", File);
+        file.size = 100;
+        app.update_entries(Pane::Left, vec![entry("folder", Directory), file]);
+        assert_eq!(app.left.entries[0].name, "file.bin");
+        app.left.set_cursor(0);
+
+        app.apply_directory_size(PathBuf::from("folder"), 1000);
+        assert_eq!(app.left.entries[0].name, "folder");
+        assert_eq!(app.left.entries[0].computed_size, Some(1000));
+        // Cursor followed the file it was on
+        assert_eq!(app.left.current_entry().unwrap().name, "file.bin");
+
+        // A reload picks the cached size back up
+        app.update_entries(Pane::Left, vec![entry("folder", Directory), entry("file.bin", File)]);
+        assert_eq!(app.left.entries[0].computed_size, Some(1000));
+    }
+
+    #[test]
+    fn folder_size_job_updates_properties_and_list() {
+        use zmanager_core::EntryKind::Directory;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let folder = root.path().join("folder");
+        std::fs::create_dir(&folder).unwrap();
+        let mut app = create_test_app();
+        let mut listed = entry("folder", Directory);
+        listed.path = folder.clone();
+        app.update_entries(Pane::Left, vec![listed]);
+        app.properties = Some(zmanager_core::get_properties(&folder).unwrap());
+
+        let stats = |total_size, file_count| FolderStats {
+            total_size,
+            file_count,
+            folder_count: 0,
+        };
+        app.apply_folder_size_progress(folder.clone(), stats(10, 1));
+        assert_eq!(app.properties.as_ref().unwrap().size, Some(10));
+        assert_eq!(app.left.entries[0].computed_size, None);
+
+        app.apply_folder_size_result(folder.clone(), Ok(stats(25, 2)));
+        let props = app.properties.as_ref().unwrap();
+        assert_eq!((props.size, props.file_count), (Some(25), Some(2)));
+        assert_eq!(app.left.entries[0].computed_size, Some(25));
+        assert!(!app.is_calculating_size(&folder));
+
+        // Sizes stay known when the listing is reloaded without size sorting
+        let mut listed = entry("folder", Directory);
+        listed.path = folder;
+        app.update_entries(Pane::Left, vec![listed]);
+        assert_eq!(app.left.entries[0].computed_size, Some(25));
+    }
+
+    #[tokio::test]
+    async fn properties_of_several_items_are_combined() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let root = tempfile::TempDir::new().unwrap();
+        let file = root.path().join("a.txt");
+        let folder = root.path().join("folder");
+        std::fs::write(&file, [0u8; 10]).unwrap();
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(folder.join("b.txt"), [0u8; 5]).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        let mut listed = vec![entry("a.txt", File), entry("folder", Directory)];
+        listed[0].path = file;
+        listed[1].path = folder;
+        app.update_entries(Pane::Left, listed);
+        app.left.select_all();
+
+        app.show_properties();
+        assert!(app.properties.is_none());
+        assert_eq!(app.multi_properties.as_ref().unwrap().item_count, 2);
+        assert!(app.is_calculating_multi_properties());
+
+        let totals = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::MultiPropertiesProgress(id, totals) => {
+                        app.apply_multi_properties_progress(id, totals);
+                    }
+                    Event::MultiPropertiesFinished(id, result) => {
+                        app.apply_multi_properties_result(id, result);
+                        return app.multi_properties.clone().unwrap();
+                    }
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        assert!(!app.is_calculating_multi_properties());
+        assert_eq!((totals.selected_files, totals.selected_folders), (1, 1));
+        assert_eq!((totals.total_size, totals.file_count), (15, 2));
+
+        // Results of a calculation for a closed panel are dropped
+        app.close_properties();
+        assert!(!app.has_properties());
+        app.apply_multi_properties_result(0, Ok(totals));
+        assert!(app.multi_properties.is_none());
+    }
+
+    #[test]
+    fn watch_locations_follow_the_panes() {
+        let root = tempfile::TempDir::new().unwrap();
+        // Only the name and that it is a file matter
+        let archive = root.path().join("files.zip");
+        std::fs::write(&archive, b"").unwrap();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        assert_eq!(app.watch_locations(), vec![root.path().to_path_buf()]);
+
+        app.right.nav.navigate_to(archive.join("docs"));
+        assert_eq!(app.watch_locations(), vec![root.path().to_path_buf()]);
+
+        let sub = root.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        app.right.nav.navigate_to(&sub);
+        assert_eq!(app.watch_locations(), vec![root.path().to_path_buf(), sub]);
+    }
+
+    #[test]
+    fn unfinished_transfers_are_offered_and_abandoned() {
+        let root = tempfile::TempDir::new().unwrap();
+        let journals = root.path().join("journals");
+        let dest = root.path().join("dest");
+        std::fs::create_dir(&dest).unwrap();
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(root.path().join(name), name).unwrap();
+            let plan = zmanager_transfer_win::TransferPlanBuilder::new(&dest)
+                .add_source(root.path().join(name))
+                .build()
+                .unwrap();
+            TransferJournal::create(&journals, &plan).unwrap();
+            // Each crashed while copying its file, only the first resumably
+            std::fs::write(dest.join(name), "").unwrap();
+            if name == "b.txt" {
+                continue;
+            }
+            let partial = zmanager_transfer_win::PartialCopy {
+                source: root.path().join(name),
+                source_size: 5,
+                source_modified: None,
+                offset: 0,
+                prefix_hash: String::new(),
+            };
+            partial.save(dest.join(name)).unwrap();
+        }
+        let partial_copies = || std::fs::read_dir(&dest).unwrap().count();
+        assert_eq!(partial_copies(), 3);
+        let mut app = create_test_app();
+
+        app.check_unfinished_transfers(&journals);
+        assert!(app.has_dialog());
+        assert!(matches!(app.pending_operation, Some(PendingOperation::ResumeTransfer)));
+
+        // Abandoning the first offers the second
+        app.abandon_unfinished_transfer();
+        assert!(app.has_dialog());
+        assert_eq!(find_unfinished_transfers(&journals).len(), 1);
+        assert!(partial_copies() < 3);
+
+        app.abandon_unfinished_transfer();
+        assert!(!app.has_dialog());
+        assert!(find_unfinished_transfers(&journals).is_empty());
+        assert_eq!(partial_copies(), 0);
+    }
+
+    #[tokio::test]
+    async fn checksums_compare_selected_files() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let root = tempfile::TempDir::new().unwrap();
+        let mut listed = vec![
+            entry("a.txt", File),
+            entry("b.txt", File),
+            entry("folder", Directory),
+        ];
+        for item in &mut listed {
+            item.path = root.path().join(&item.name);
+        }
+        std::fs::write(&listed[0].path, "same").unwrap();
+        std::fs::write(&listed[1].path, "same").unwrap();
+        std::fs::create_dir(&listed[2].path).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.update_entries(Pane::Left, listed);
+        app.left.select_all();
+
+        app.handle_action(Action::Checksum).unwrap();
+        let view = app.checksums.as_ref().unwrap();
+        assert_eq!(view.paths().len(), 2);
+        assert!(view.is_calculating());
+
+        // Switching algorithm drops the first job's result
+        app.start_checksum_job(HashAlgorithm::Md5);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::ChecksumProgress(job_id, progress) => {
+                        app.apply_checksum_progress(job_id, progress);
+                    }
+                    Event::ChecksumFinished(job_id, result) => {
+                        app.apply_checksum_result(job_id, result);
+                        if !app.checksums.as_ref().unwrap().is_calculating() {
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        let view = app.checksums.as_ref().unwrap();
+        assert_eq!(view.algorithm(), HashAlgorithm::Md5);
+        assert_eq!(view.files_match(), Some(true));
+        app.close_checksums();
+        assert!(!app.has_checksums());
+    }
+
+    #[tokio::test]
+    async fn compares_a_file_from_each_pane() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let root = tempfile::TempDir::new().unwrap();
+        let mut left = vec![entry("folder", Directory), entry("old.txt", File)];
+        let mut right = vec![entry("new.txt", File)];
+        for item in left.iter_mut().chain(&mut right) {
+            item.path = root.path().join(&item.name);
+        }
+        std::fs::create_dir(&left[0].path).unwrap();
+        std::fs::write(&left[1].path, "one\ntwo\n").unwrap();
+        std::fs::write(&right[0].path, "one\n2\n").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.update_entries(Pane::Left, left);
+        app.update_entries(Pane::Right, right);
+
+        // A folder can't be compared
+        app.handle_action(Action::Compare).unwrap();
+        assert!(!app.has_compare());
+
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::Compare).unwrap();
+        assert_eq!(app.compare.as_ref().unwrap().mode(), CompareMode::Bytes);
+
+        // Switching mode drops the first job's result
+        app.start_compare_job(CompareMode::Lines);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                if let Event::CompareFinished(job_id, result) = event {
+                    app.apply_compare_result(job_id, result);
+                    if !app.compare.as_ref().unwrap().is_comparing() {
+                        return;
+                    }
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        let view = app.compare.as_ref().unwrap();
+        assert_eq!(view.mode(), CompareMode::Lines);
+        let comparison = view.comparison().unwrap();
+        assert_eq!(comparison.first_difference, Some(4));
+        assert_eq!(comparison.text.as_ref().unwrap().stats().added, 1);
+        app.close_compare();
+        assert!(!app.has_compare());
+    }
+
+    #[tokio::test]
+    async fn converts_line_endings_of_the_file_under_the_cursor() {
+        use zmanager_core::EntryKind::{Directory, File};
+        use zmanager_core::{ConversionOutcome, LineEnding};
+
+        let root = tempfile::TempDir::new().unwrap();
+        let mut listed = vec![entry("folder", Directory), entry("notes.txt", File)];
+        for item in &mut listed {
+            item.path = root.path().join(&item.name);
+        }
+        std::fs::create_dir(&listed[0].path).unwrap();
+        std::fs::write(&listed[1].path, "one\ntwo\n").unwrap();
+        let file = listed[1].path.clone();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.update_entries(Pane::Left, listed);
+
+        // A folder can't be converted
+        app.handle_action(Action::ConvertText).unwrap();
+        assert!(!app.has_convert());
+
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::ConvertText).unwrap();
+        assert_eq!(app.convert.as_ref().unwrap().paths(), std::slice::from_ref(&file));
+
+        app.start_convert_job(TextConversion {
+            encoding: None,
+            line_ending: Some(LineEnding::CrLf),
+            backup: true,
+        });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                if let Event::ConvertFinished(job_id, result) = event {
+                    app.apply_convert_result(job_id, result);
+                    return;
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        let results = app.convert.as_ref().unwrap().results().unwrap();
+        let backup = root.path().join("notes.txt.bak");
+        let converted = ConversionOutcome::Converted {
+            backup: Some(backup.clone()),
+        };
+        assert_eq!(results[0].outcome, Ok(converted));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\r\ntwo\r\n");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "one\ntwo\n");
+        app.close_convert();
+        assert!(!app.has_convert());
+    }
+
+    #[tokio::test]
+    async fn viewer_searches_in_the_background() {
+        use zmanager_core::EntryKind::{Directory, File};
+
+        let root = tempfile::TempDir::new().unwrap();
+        let mut listed = vec![entry("folder", Directory), entry("log.txt", File)];
+        for item in &mut listed {
+            item.path = root.path().join(&item.name);
+        }
+        std::fs::create_dir(&listed[0].path).unwrap();
+        std::fs::write(&listed[1].path, "started\nfailed: disk full\n").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.update_entries(Pane::Left, listed);
+
+        // Folders can't be viewed
+        app.handle_action(Action::View).unwrap();
+        assert!(!app.has_viewer());
+
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::View).unwrap();
+        assert!(app.has_viewer());
+
+        let search = ContentSearch {
+            text: "FAILED".to_string(),
+            match_case: false,
+            backward: false,
+        };
+        app.start_viewer_search(search, 0);
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap();
+        let Some(Event::ViewerSearchFinished(id, result)) = event else {
+            panic!("expected the search result");
+        };
+        assert_eq!(result, Ok(Some(8..14)));
+        app.apply_viewer_search(id, result);
+        assert_eq!(app.viewer.as_ref().unwrap().top(), 8);
+
+        app.close_viewer();
+        assert!(!app.has_viewer());
+    }
+
+    #[tokio::test]
+    async fn small_text_files_open_in_the_text_editor() {
+        use crossterm::event::{KeyCode, KeyEvent};
+        use zmanager_core::EntryKind::File;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let mut listed = vec![entry("big.txt", File), entry("image.bin", File)];
+        listed.push(entry("notes.txt", File));
+        for item in &mut listed {
+            item.path = root.path().join(&item.name);
+        }
+        std::fs::write(&listed[0].path, "x".repeat(2048)).unwrap();
+        std::fs::write(&listed[1].path, b"\x89PNG\0\0").unwrap();
+        std::fs::write(&listed[2].path, "todo\n").unwrap();
+        listed[0].size = 2048;
+        listed[2].size = 5;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.config.tools.quick_edit_max_kb = 1;
+        // Anything the built-in editor doesn't take goes nowhere in tests
+        app.config.tools.editor = String::new();
+        app.update_entries(Pane::Left, listed);
+
+        // Too large, then binary
+        app.handle_action(Action::Edit).unwrap();
+        assert!(!app.has_text_editor());
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::Edit).unwrap();
+        assert!(!app.has_text_editor());
+
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::Edit).unwrap();
+        assert!(app.has_text_editor());
+        let editor = app.text_editor.as_mut().unwrap();
+        editor.handle_key(KeyEvent::from(KeyCode::Char('-')));
+        app.save_text_editor();
+        let saved = std::fs::read_to_string(root.path().join("notes.txt")).unwrap();
+        assert_eq!(saved, "-todo\n");
+        assert!(matches!(rx.try_recv(), Ok(Event::DirectoryChanged(_))));
+
+        app.close_text_editor();
+        assert!(!app.has_text_editor());
+    }
+
+    #[test]
+    fn properties_editor_applies_changes() {
+        let root = tempfile::TempDir::new().unwrap();
+        let file = root.path().join("notes.txt");
+        std::fs::write(&file, b"x").unwrap();
+        let mut app = create_test_app();
+
+        // Only a single item's properties can be edited
+        app.open_properties_editor();
+        assert!(!app.has_properties_editor());
+
+        app.properties = Some(zmanager_core::get_properties(&file).unwrap());
+        app.open_properties_editor();
+        assert!(app.has_properties_editor() && !app.has_properties());
+
+        let changes = PropertyChanges {
+            readonly: Some(true),
+            ..Default::default()
+        };
+        app.apply_property_changes(file.clone(), changes);
+        assert!(!app.has_properties_editor());
+        assert!(zmanager_core::get_properties(&file).unwrap().readonly);
+        assert_eq!(latest_status(&app).unwrap().0, "Updated properties of notes.txt");
+
+        let changes = PropertyChanges {
+            readonly: Some(false),
+            ..Default::default()
+        };
+        app.apply_property_changes(root.path().join("gone"), changes.clone());
+        assert!(app.has_dialog());
+        app.apply_property_changes(file, changes);
+    }
+
+    #[test]
+    fn filter_builder_filters_listings() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx);
+        app.handle_action(Action::FilterBuilder).unwrap();
+        assert!(app.has_filter_builder());
+
+        let filter = FilterSpec::new().with_size_range(Some(1024), None).with_readonly(Some(true));
+        app.apply_filter(filter.clone());
+        assert!(!app.has_filter_builder());
+        assert_eq!(app.filter, filter);
+        assert_eq!(latest_status(&app).unwrap().0, "Filter: > 1.00 KB, read-only");
+        assert!(matches!(rx.try_recv(), Ok(Event::RefreshAll)));
+
+        app.apply_filter(FilterSpec::new());
+        assert_eq!(latest_status(&app).unwrap().0, "Filter cleared");
+    }
+
+    #[test]
+    fn copy_to_previous_uses_history() {
+        let root = tempfile::TempDir::new().unwrap();
+        let child = root.path().join("child");
+        std::fs::create_dir(&child).unwrap();
+
+        let mut app = create_test_app();
+        app.left.nav = NavigationState::new(root.path());
+        app.handle_action(Action::CopyToPrevious).unwrap();
+        assert!(!app.has_dialog());
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+
+        app.left.nav.navigate_to(&child);
+        app.left.set_entries(vec![entry("a.txt", zmanager_core::EntryKind::File)]);
+        app.handle_action(Action::CopyToPrevious).unwrap();
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::Copy(_, ref dest)) if dest == root.path()
+        ));
+    }
+
+    #[test]
+    fn copy_dialog_edits_the_destination() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (src, dest) = (root.path().join("src"), root.path().join("dest"));
+        std::fs::create_dir(&src).unwrap();
+        std::fs::create_dir(&dest).unwrap();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(src.clone(), dest.clone(), tx);
+        app.left.set_entries(vec![entry("a.txt", zmanager_core::EntryKind::File)]);
+
+        app.handle_action(Action::Copy).unwrap();
+        let Some(Dialog { kind: DialogKind::Input { value, .. } }) = &app.dialog else {
+            panic!("expected a destination dialog");
+        };
+        let suggested = value.clone();
+        assert_eq!(Path::new(&suggested), dest.join("a.txt"));
+        let source = vec![src.join("a.txt")];
+
+        // Left as suggested, the file goes into the folder under its name
+        let target = app.transfer_destination(&source, dest.clone(), &suggested);
+        assert_eq!(target, Some(dest.clone()));
+        assert_eq!(app.transfer_destination(&source, dest.clone(), " "), Some(dest.clone()));
+
+        // Any other path renames it, relative to the active pane
+        let renamed = dest.join("b.txt").display().to_string();
+        let target = app.transfer_destination(&source, dest.clone(), &renamed);
+        assert_eq!(target, Some(dest.join("b.txt")));
+        let target = app.transfer_destination(&source, dest.clone(), "copy.txt");
+        assert_eq!(target, Some(src.join("copy.txt")));
+
+        // Several items need a folder, made when missing
+        let sources = vec![src.join("a.txt"), src.join("b.txt")];
+        let folder = dest.join("new").display().to_string();
+        let target = app.transfer_destination(&sources, dest.clone(), &folder);
+        assert_eq!(target, Some(dest.join("new")));
+        assert!(dest.join("new").is_dir());
+    }
+
+    #[test]
+    fn move_to_previous_rejects_missing_directory() {
+        let root = tempfile::TempDir::new().unwrap();
+        let gone = root.path().join("gone");
+        std::fs::create_dir(&gone).unwrap();
+
+        let mut app = create_test_app();
+        app.left.nav = NavigationState::new(&gone);
+        app.left.nav.navigate_to(root.path());
+        app.left.set_entries(vec![entry("a.txt", zmanager_core::EntryKind::File)]);
+        std::fs::remove_dir(&gone).unwrap();
+
+        app.handle_action(Action::MoveToPrevious).unwrap();
+        assert!(app.pending_operation.is_none());
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+    }
+
+    #[test]
+    fn enter_auto_descends_single_child_chain() {
+        let root = tempfile::TempDir::new().unwrap();
+        let pkg = root.path().join("pkg");
+        let inner = pkg.join("pkg-1.0");
+        std::fs::create_dir_all(inner.join("src")).unwrap();
+        std::fs::write(inner.join("Cargo.toml"), "").unwrap();
+
+        let mut app = create_test_app();
+        app.config.general.auto_descend_single_child = true;
+        app.left.nav = NavigationState::new(root.path());
+        let mut pkg_entry = entry("pkg", zmanager_core::EntryKind::Directory);
+        pkg_entry.path = pkg.clone();
+        app.left.set_entries(vec![pkg_entry.clone()]);
+
+        app.handle_action(Action::Enter).unwrap();
+        assert_eq!(app.left.nav.current_path(), inner);
+
+        // The whole jump is one history step
+        app.handle_action(Action::GoBack).unwrap();
+        assert_eq!(app.left.nav.current_path(), root.path());
+
+        app.config.general.auto_descend_single_child = false;
+        app.left.set_entries(vec![pkg_entry]);
+        app.handle_action(Action::Enter).unwrap();
+        assert_eq!(app.left.nav.current_path(), pkg);
+    }
+
+    #[test]
+    fn duplicate_scan_reuses_index_for_same_root() {
+        let active = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let copy = active.path().join("copy.txt");
+        std::fs::write(&copy, "shared").unwrap();
+        std::fs::create_dir(other.path().join("nested")).unwrap();
+        std::fs::write(other.path().join("nested").join("orig.txt"), "shared").unwrap();
+
+        let cache = Mutex::new(None);
+        let files = vec![zmanager_core::get_entry_meta(&copy).unwrap()];
+        let token = CancellationToken::new();
+
+        let report = scan_for_duplicates(&cache, other.path(), &files, &token).unwrap();
+        assert_eq!(report.count(), 1);
+        assert_eq!(report.reclaimable_bytes, 6);
+
+        // A second scan of the same root reuses the index instead of walking again
+        std::fs::write(other.path().join("late.txt"), "late!!").unwrap();
+        scan_for_duplicates(&cache, other.path(), &files, &token).unwrap();
+        assert_eq!(cache.lock().unwrap().as_ref().unwrap().file_count(), 1);
+
+        // A different root rebuilds it
+        scan_for_duplicates(&cache, active.path(), &files, &token).unwrap();
+        let index_root = cache.lock().unwrap().as_ref().map(|i| i.root().to_path_buf());
+        assert_eq!(index_root.as_deref(), Some(active.path()));
+    }
+
+    #[test]
+    fn duplicate_scan_result_selects_matches() {
+        let mut app = create_test_app();
+        app.left.set_entries(vec![
+            entry("a.txt", zmanager_core::EntryKind::File),
+            entry("b.txt", zmanager_core::EntryKind::File),
+        ]);
+
+        let found = vec![PathBuf::from("b.txt")];
+        app.apply_duplicate_scan(PathBuf::from("C:\\"), Ok((found.clone(), 2048)));
+
+        assert!(app.left.selection.is_selected(&found[0]));
+        assert_eq!(app.left.selection.count(), 1);
+        assert_eq!(
+            latest_status(&app),
+            Some(("1 duplicate(s) found, 2.00 KB reclaimable", StatusLevel::Info))
+        );
+
+        app.apply_duplicate_scan(PathBuf::from("C:\\"), Err("denied".to_string()));
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+    }
+
+    #[test]
+    fn access_denied_items_are_retried_as_administrator() {
+        let mut app = create_test_app();
+        let reports = tempfile::TempDir::new().unwrap();
+        app.reports_dir = reports.path().to_path_buf();
+
+        app.offer_elevated_rename(PathBuf::from("C:\\a.txt"), PathBuf::from("C:\\b.txt"));
+        let Some(PendingOperation::RetryElevated(None, manifest)) = app.pending_operation.take()
+        else {
+            panic!("expected an offer to retry as administrator");
+        };
+        assert_eq!(manifest.len(), 1);
+        assert!(app.has_dialog());
+        app.close_dialog();
+
+        let (source, destination) = (PathBuf::from("C:\\src.txt"), PathBuf::from("D:\\src.txt"));
+        app.apply_job_started(7, TransferOperation::Copy);
+        app.apply_job_item(
+            7,
+            TransferItemResult::failed(source.clone(), destination.clone(), "Permission denied"),
+        );
+        let results = vec![ElevatedResult {
+            operation: ElevatedOperation::Copy {
+                source,
+                destination,
+            },
+            error: None,
+        }];
+        app.apply_elevation_results(Some(7), Ok(results));
+
+        let report = app.job_details[&7].report();
+        assert_eq!((report.summary.succeeded, report.summary.failed), (1, 0));
+        assert_eq!(
+            latest_status(&app),
+            Some(("Done as administrator: 1 item(s)", StatusLevel::Info))
+        );
+
+        app.apply_elevation_results(None, Err("declined".to_string()));
+        assert!(app.has_dialog());
+    }
+
+    #[test]
+    fn security_tab_offers_to_take_ownership() {
+        let mut app = create_test_app();
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("locked.txt");
+        std::fs::write(&file, "content").unwrap();
+        app.properties = Some(zmanager_core::get_properties(&file).unwrap());
+
+        app.toggle_security_tab();
+        assert!(app.has_security_tab());
+        assert!(matches!(&app.security, Some(Ok(info)) if info.owner.is_some()));
+        app.toggle_security_tab();
+        assert!(!app.has_security_tab());
+
+        app.toggle_security_tab();
+        app.confirm_take_ownership();
+        assert!(!app.has_properties());
+        assert!(!app.has_security_tab());
+        assert!(app.has_dialog());
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::TakeOwnership(ref paths))
+                if paths == std::slice::from_ref(&file)
+        ));
+    }
+
+    #[test]
+    fn delete_and_permanent_delete_are_distinct() {
+        let mut app = create_test_app();
+        app.left.set_entries(vec![entry("a.txt", zmanager_core::EntryKind::File)]);
+        let title = |app: &App| match app.dialog.as_ref().map(|d| &d.kind) {
+            Some(crate::ui::dialog::DialogKind::Confirm { title, .. }) => Some(title.clone()),
+            _ => None,
+        };
+
+        app.handle_action(Action::Delete).unwrap();
+        assert!(matches!(app.pending_operation, Some(PendingOperation::Delete(_))));
+        assert_eq!(title(&app).as_deref(), Some("Confirm Delete"));
+
+        app.handle_action(Action::DeletePermanent).unwrap();
+        assert!(matches!(app.pending_operation, Some(PendingOperation::DeletePermanent(_))));
+        assert_eq!(title(&app).as_deref(), Some("Confirm Permanent Delete"));
+
+        app.config.operations.wipe_permanent_deletes = true;
+        app.handle_action(Action::DeletePermanent).unwrap();
+        assert_eq!(title(&app).as_deref(), Some("Confirm Wipe"));
+    }
+
+    #[test]
+    fn open_executable_prompts_with_full_path() {
+        let mut app = create_test_app();
+        let path = PathBuf::from("C:\\tools\\setup.exe");
+        let mut exe = entry("setup.exe", zmanager_core::EntryKind::File);
+        exe.path = path.clone();
+        app.left.set_entries(vec![exe]);
+
+        app.handle_action(Action::Open).unwrap();
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::RunExecutable(ref p)) if *p == path
+        ));
+        let expected = format!("Run {}?", path.display());
+        assert!(matches!(
+            app.dialog.as_ref().map(|d| &d.kind),
+            Some(crate::ui::dialog::DialogKind::Confirm { message, .. }) if *message == expected
+        ));
+    }
+
+    #[test]
+    fn open_executable_can_be_disabled() {
+        let mut app = create_test_app();
+        app.config.general.executable_open = ExecutableOpenBehavior::Nothing;
+        app.left.set_entries(vec![
+            entry("setup.exe", zmanager_core::EntryKind::File),
+            entry("notes.txt", zmanager_core::EntryKind::File),
+        ]);
+
+        app.handle_action(Action::Open).unwrap();
+        assert!(!app.has_dialog());
+        assert!(app.pending_operation.is_none());
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Info))));
+
+        // The explicit run action refuses non-executables
+        app.left.set_cursor(1);
+        app.handle_action(Action::Run).unwrap();
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+    }
+
+    #[test]
+    fn deleted_directory_recovers_to_nearest_ancestor() {
+        let root = tempfile::TempDir::new().unwrap();
+        let doomed = root.path().join("a").join("b");
+        std::fs::create_dir_all(&doomed).unwrap();
+
+        let mut app = create_test_app();
+        app.right.nav = NavigationState::new(&doomed);
+        app.right.set_entries(vec![entry("old.txt", zmanager_core::EntryKind::File)]);
+        assert_eq!(app.recover_missing_directory(Pane::Right), None);
+
+        std::fs::remove_dir_all(root.path().join("a")).unwrap();
+        let recovered = app.recover_missing_directory(Pane::Right);
+
+        assert_eq!(recovered.as_deref(), Some(root.path()));
+        assert_eq!(app.right.nav.current_path(), root.path());
+        assert!(app.right.entries.is_empty());
+        assert!(matches!(latest_status(&app), Some((_, StatusLevel::Error))));
+    }
+
+    #[test]
+    fn going_back_after_recovery_skips_the_deleted_directory() {
+        let root = tempfile::TempDir::new().unwrap();
+        let other = root.path().join("other");
+        let doomed = root.path().join("a").join("b");
+        std::fs::create_dir_all(&doomed).unwrap();
+        std::fs::create_dir(&other).unwrap();
+
+        let mut app = create_test_app();
+        app.right.nav = NavigationState::new(&other);
+        app.right.nav.navigate_to(&doomed);
+
+        std::fs::remove_dir_all(root.path().join("a")).unwrap();
+        app.recover_missing_directory(Pane::Right);
+
+        assert_eq!(app.right.nav.go_back(), Some(other.as_path()));
+        assert!(!app.right.nav.can_go_back());
+    }
+
+    #[test]
+    fn watcher_event_for_deleted_directory_recovers_the_pane() {
+        let root = tempfile::TempDir::new().unwrap();
+        let doomed = root.path().join("doomed");
+        std::fs::create_dir(&doomed).unwrap();
+        let mut app = create_test_app();
+        app.right.nav = NavigationState::new(&doomed);
+        let watcher = DirectoryWatcher::new().unwrap();
+        watcher.sync(&app.watch_locations());
+        let directory = doomed.canonicalize().unwrap();
+
+        // The events of the deletion arrive once the directory is gone
+        std::fs::remove_dir(&doomed).unwrap();
+        assert!(watcher.sync(&app.watch_locations()).is_empty());
+        let event = WatchEvent {
+            directory,
+            kind: zmanager_core::WatchEventKind::Deleted,
+            paths: Vec::new(),
+            renames: Vec::new(),
+        };
+
+        assert_eq!(app.apply_watch_event(&watcher, &event), vec![Pane::Right]);
+        assert_eq!(app.right.nav.current_path(), root.path());
+    }
+
+    #[test]
+    fn enter_browses_into_zip_archive() {
+        let mut app = create_test_app();
+        let mut archive = entry("bundle.zip", zmanager_core::EntryKind::File);
+        archive.path = std::env::temp_dir().join("zmanager-missing-bundle.zip");
+        app.left.set_entries(vec![archive.clone()]);
+
+        // Entering goes by the name; the archive is read when the pane reloads
+        app.handle_action(Action::Enter).unwrap();
+        assert_eq!(app.left.nav.current_path(), archive.path);
+
+        app.left.set_entries(vec![entry("notes.txt", zmanager_core::EntryKind::File)]);
+        app.handle_action(Action::Enter).unwrap();
+        assert_eq!(app.left.nav.current_path(), archive.path);
+    }
+
+    #[test]
+    fn copy_menu_copies_the_selected_items() {
+        let mut app = create_test_app();
+        let file = PathBuf::from("C:\\notes.txt");
+        let kind = zmanager_core::EntryKind::File;
+        app.left.set_entries(vec![EntryMeta::new("notes.txt".into(), file.clone(), kind)]);
+
+        app.handle_action(Action::CopyMenu).unwrap();
+        let menu = app.copy_menu.as_ref().expect("copy menu should open");
+        assert_eq!(menu.paths(), std::slice::from_ref(&file));
+
+        // A local file has no UNC path
+        app.copy_menu_items(CopyFormat::Unc);
+        assert!(!app.has_copy_menu());
+        let (text, level) = latest_status(&app).unwrap();
+        assert!(text.starts_with("Cannot copy:"));
+        assert_eq!(level, StatusLevel::Error);
+    }
+
+    #[test]
+    fn context_menu_runs_operations_on_the_cursor_entry() {
+        let mut app = create_test_app();
+        let folder = PathBuf::from("C:\\docs");
+        let kind = zmanager_core::EntryKind::Directory;
+        app.left.set_entries(vec![EntryMeta::new("docs".into(), folder, kind)]);
+
+        app.handle_action(Action::ContextMenu).unwrap();
+        let menu = app.context_menu.as_ref().expect("context menu should open");
+        let actions: Vec<Action> = menu.actions().iter().map(|entry| entry.action).collect();
+        assert!(actions.contains(&Action::Rename));
+        assert!(!actions.contains(&Action::OpenWith));
+
+        app.run_context_action(Action::Rename).unwrap();
+        assert!(!app.has_context_menu());
+        assert!(app.is_renaming_inline());
+    }
+
+    #[test]
+    fn plugin_commands_join_the_context_menu() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("images.toml"),
+            "[[commands]]\nname = \"Optimize PNG\"\ncommand = \"oxipng {path}\"\n\
+             patterns = [\"*.png\"]\n",
+        )
+        .unwrap();
+        let mut app = create_test_app();
+        app.plugins = Plugins::load_from(temp.path()).0;
+        let kind = zmanager_core::EntryKind::File;
+        app.left.set_entries(vec![
+            EntryMeta::new("a.png".into(), PathBuf::from("C:\\a.png"), kind),
+            EntryMeta::new("b.txt".into(), PathBuf::from("C:\\b.txt"), kind),
+        ]);
+
+        app.handle_action(Action::ContextMenu).unwrap();
+        let menu = app.context_menu.take().unwrap();
+        let last = menu.actions().last().unwrap();
+        assert_eq!((last.action, last.label.as_ref()), (Action::Plugin(0), "Optimize PNG"));
+
+        app.handle_action(Action::Down).unwrap();
+        app.handle_action(Action::ContextMenu).unwrap();
+        let menu = app.context_menu.take().unwrap();
+        assert!(!menu.actions().iter().any(|entry| entry.action == Action::Plugin(0)));
+        app.handle_action(Action::Plugin(0)).unwrap();
+        assert_eq!(
+            latest_status(&app),
+            Some(("Optimize PNG doesn't apply to the selected items", StatusLevel::Error))
+        );
+    }
+
+    #[test]
+    fn clipboard_name_is_trimmed() {
+        assert_eq!(clipboard_text_to_name("  report.txt\r\n").unwrap(), "report.txt");
     }
 
-    /// Move selection up in transfers view.
-    pub fn jobs_up(&mut self) {
-        if let Some(selected) = self.jobs_list_state.selected() {
-            if selected > 0 {
-                self.jobs_list_state.select(Some(selected - 1));
-            }
-        } else if !self.jobs.is_empty() {
-            self.jobs_list_state.select(Some(0));
-        }
+    #[test]
+    fn clipboard_name_rejects_multiline_and_illegal_chars() {
+        assert!(clipboard_text_to_name("first\nsecond").is_err());
+        assert!(clipboard_text_to_name("bad:name").is_err());
+        assert!(clipboard_text_to_name("\n").is_err());
     }
 
-    /// Move selection down in transfers view.
-    pub fn jobs_down(&mut self) {
-        if let Some(selected) = self.jobs_list_state.selected() {
-            if selected < self.jobs.len().saturating_sub(1) {
-                self.jobs_list_state.select(Some(selected + 1));
-            }
-        } else if !self.jobs.is_empty() {
-            self.jobs_list_state.select(Some(0));
+    #[test]
+    fn basket_collects_files_from_several_directories() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (one, two) = (root.path().join("one"), root.path().join("two"));
+        for dir in [&one, &two] {
+            std::fs::create_dir(dir).unwrap();
+            std::fs::write(dir.join("a.txt"), "a").unwrap();
         }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(one.clone(), two.clone(), tx);
+        app.baskets = Baskets::default();
+
+        app.execute_add_to_basket(vec![one.join("a.txt")], "Work".to_string());
+        app.execute_add_to_basket(vec![two.join("a.txt"), one.join("a.txt")], " Work ".to_string());
+        assert_eq!(app.baskets.get("Work").unwrap().len(), 2);
+
+        app.execute_show_basket("Work".to_string());
+        assert_eq!(app.left.nav.current_path(), basket_path("Work"));
+        assert!(matches!(rx.try_recv(), Ok(Event::DirectoryChanged(_))));
+        app.list_basket(Pane::Left, "Work");
+        assert_eq!(app.left.entries.len(), 2);
+        assert!(app.watch_locations().iter().all(|p| *p == two));
+
+        // Copying into a pane showing the basket adds to it
+        std::fs::write(two.join("b.txt"), "b").unwrap();
+        app.switch_pane();
+        app.right.set_entries(vec![zmanager_core::get_entry_meta(two.join("b.txt")).unwrap()]);
+        app.handle_action(Action::Copy).unwrap();
+        assert!(!app.has_dialog());
+        assert_eq!(app.left.entries.len(), 3);
+
+        // Removing everything deletes the basket
+        app.switch_pane();
+        app.left.select_all();
+        app.handle_action(Action::RemoveFromBasket).unwrap();
+        assert!(app.baskets.get("Work").is_none());
+        assert!(app.left.entries.is_empty());
     }
 
-    /// Set a status message (will be shown in status bar).
-    pub fn set_status(&mut self, message: impl Into<String>, is_error: bool) {
-        self.status_message = Some((message.into(), is_error));
+    #[test]
+    fn select_pattern_asks_then_selects() {
+        let mut app = create_test_app();
+        select_files(&mut app, 3);
+        app.left.clear_selection();
+
+        app.handle_action(Action::SelectPattern).unwrap();
+        assert!(app.has_dialog());
+        assert!(matches!(app.pending_operation, Some(PendingOperation::SelectPattern)));
+        app.close_dialog();
+
+        app.execute_select_pattern("file1*; file?.txt".to_string(), true);
+        assert_eq!(app.left.selection.count(), 3);
+        app.execute_select_pattern("*1.*".to_string(), false);
+        assert_eq!(app.left.selection.count(), 2);
+
+        // Every entry has the cursor's extension
+        app.left.clear_selection();
+        app.handle_action(Action::SelectSameExt).unwrap();
+        assert_eq!(app.left.selection.count(), 3);
     }
 
-    /// Clear the status message.
-    pub fn clear_status(&mut self) {
-        self.status_message = None;
+    #[test]
+    fn rename_edits_the_name_in_place() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx);
+        select_files(&mut app, 2);
+        app.left.selection.clear();
+
+        app.handle_action(Action::Rename).unwrap();
+        assert!(app.is_renaming_inline());
+        assert!(!app.has_dialog());
+
+        // Committing the name unchanged renames nothing
+        app.finish_inline_rename("file0.txt".to_string());
+        assert!(!app.is_renaming_inline());
+        assert!(rx.try_recv().is_err());
+
+        app.handle_action(Action::Rename).unwrap();
+        app.finish_inline_rename("first.txt".to_string());
+        let Ok(Event::ExecuteRename(from, to)) = rx.try_recv() else {
+            panic!("expected a rename");
+        };
+        assert_eq!(from, PathBuf::from("file0.txt"));
+        assert_eq!(to, PathBuf::from("first.txt"));
+
+        // An entry that disappears from the listing ends its rename
+        app.handle_action(Action::Rename).unwrap();
+        app.left.set_entries(Vec::new());
+        assert!(!app.is_renaming_inline());
     }
 
-    /// Check if in transfers view.
-    pub fn is_transfers_view(&self) -> bool {
-        self.view_mode == ViewMode::Transfers
+    #[test]
+    fn rename_with_selection_asks_for_pattern() {
+        let mut app = create_test_app();
+        select_files(&mut app, 3);
+
+        app.handle_action(Action::Rename).unwrap();
+        assert!(app.has_dialog());
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::BulkRename(ref files)) if files.len() == 3
+        ));
     }
 
-    /// Check if there's an active conflict modal.
-    pub fn has_conflict(&self) -> bool {
-        self.conflict_modal.is_some()
+    #[test]
+    fn bulk_rename_previews_then_renames() {
+        let root = tempfile::TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = ["b.txt", "a.txt"]
+            .iter()
+            .map(|name| {
+                let path = root.path().join(name);
+                std::fs::write(&path, name).unwrap();
+                path
+            })
+            .collect();
+        let mut app = create_test_app();
+
+        app.preview_bulk_rename(paths.clone(), "bad[X]".to_string());
+        assert!(app.pending_operation.is_none());
+
+        app.preview_bulk_rename(paths, "[N]_[C:2].[E]".to_string());
+        let Some(PendingOperation::ConfirmBulkRename(plan)) = app.pending_operation.take() else {
+            panic!("expected a rename preview");
+        };
+        assert_eq!(
+            bulk_rename_preview(&plan),
+            "b.txt -> b_01.txt\na.txt -> a_02.txt\nRename 2 item(s)?"
+        );
+
+        app.execute_bulk_rename(plan);
+        assert_eq!(latest_status(&app), Some(("Renamed 2 item(s)", StatusLevel::Info)));
+        assert!(root.path().join("b_01.txt").exists());
+        assert!(root.path().join("a_02.txt").exists());
     }
 
-    /// Close the conflict modal.
-    pub fn close_conflict(&mut self) {
-        self.conflict_modal = None;
+    #[test]
+    fn compress_asks_for_archive_name() {
+        let mut app = create_test_app();
+        select_files(&mut app, 3);
+
+        app.handle_action(Action::Compress).unwrap();
+        assert!(app.has_dialog());
+        assert!(matches!(
+            app.pending_operation,
+            Some(PendingOperation::Compress(ref files)) if files.len() == 3
+        ));
     }
 
-    // ========== Sidebar / Quick Access ==========
+    #[test]
+    fn compress_archive_names() {
+        let docs = Path::new("docs");
+        assert_eq!(default_archive_name(&[PathBuf::from("report.txt")], docs), "report.zip");
+        assert_eq!(
+            default_archive_name(&[PathBuf::from("a"), PathBuf::from("b")], docs),
+            "docs.zip"
+        );
+        assert_eq!(archive_file_name(" backup ").unwrap(), "backup.zip");
+        assert_eq!(archive_file_name("old.ZIP").unwrap(), "old.ZIP");
+        assert!(archive_file_name("bad:name").is_err());
+    }
 
-    /// Toggle sidebar visibility.
-    pub fn toggle_sidebar(&mut self) {
-        self.sidebar_visible = !self.sidebar_visible;
-        if self.sidebar_visible {
-            // Refresh drives when showing sidebar
-            self.drives = zmanager_core::list_drives().unwrap_or_default();
-        }
+    #[test]
+    fn compress_refuses_existing_archive() {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::write(root.path().join("backup.zip"), b"keep").unwrap();
+        let mut app = create_test_app();
+        app.left.nav = NavigationState::new(root.path());
+
+        app.execute_compress(vec![root.path().join("file.txt")], "backup".to_string());
+        assert_eq!(
+            latest_status(&app),
+            Some(("'backup.zip' already exists", StatusLevel::Error))
+        );
+        // No job was submitted, which would fail without a scheduler
+        assert!(!app.has_dialog());
+        assert_eq!(std::fs::read(root.path().join("backup.zip")).unwrap(), b"keep");
     }
 
-    /// Add current directory to favorites.
-    pub fn add_current_to_favorites(&mut self) {
-        let path = self.active().nav.current_path().to_path_buf();
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("Favorite")
-            .to_string();
+    #[tokio::test]
+    async fn compress_runs_as_scheduled_job() {
+        let root = tempfile::TempDir::new().unwrap();
+        let source = root.path().join("source.txt");
+        std::fs::write(&source, b"hello").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        app.execute_compress(vec![source], "backup".to_string());
+        let finished = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::JobCompleted { job_id, success } => return (job_id, success),
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        assert!(finished.1);
+        let entries = zmanager_core::list_archive(root.path().join("backup.zip"), "").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "source.txt");
+        app.apply_job_finished(finished.0, finished.1);
+        assert!(latest_status(&app).unwrap().0.starts_with("Done: Compressing"));
+
+        handle.shutdown().await;
+    }
 
-        let favorite = Favorite::new(name.clone(), path);
-        self.config.add_favorite(favorite);
-        self.favorites = self.config.favorites.clone();
+    fn filter_test_app() -> App {
+        use zmanager_core::EntryKind::{Directory, File};
+        let mut app = create_test_app();
+        app.left.set_entries(vec![
+            entry("Docs", Directory),
+            entry("alpha.txt", File),
+            entry("beta.md", File),
+            entry("gamma.TXT", File),
+        ]);
+        app
+    }
 
-        // Try to save config
-        if let Err(e) = self.config.save() {
-            self.set_status(format!("Failed to save config: {}", e), true);
-        } else {
-            self.set_status(format!("Added '{}' to favorites", name), false);
-        }
+    fn names(pane: &PaneState) -> Vec<&str> {
+        pane.entries.iter().map(|e| e.name.as_str()).collect()
     }
 
-    /// Quick jump to a favorite by number (1-9).
-    pub fn quick_jump_to_favorite(&mut self, num: u8) {
-        // Only works when sidebar is visible
-        if !self.sidebar_visible {
-            return;
+    #[test]
+    fn quick_filter_narrows_as_you_type() {
+        let mut app = filter_test_app();
+        app.handle_action(Action::FilterMenu).unwrap();
+        assert!(app.is_typing_filter());
+
+        for c in ".tx".chars() {
+            app.push_filter_char(c);
         }
+        assert_eq!(names(&app.left), ["alpha.txt", "gamma.TXT"]);
+        assert_eq!(app.left.cursor(), 0);
+
+        app.pop_filter_char();
+        app.pop_filter_char();
+        assert_eq!(names(&app.left), ["alpha.txt", "beta.md", "gamma.TXT"]);
+
+        // Enter keeps the filter but hands keys back to the pane
+        app.finish_filter_input();
+        assert!(!app.is_typing_filter());
+        assert_eq!(app.left.entries.len(), 3);
+        app.handle_action(Action::Down).unwrap();
+
+        // Esc clears it, leaving the cursor where it was
+        app.handle_action(Action::ClearSelection).unwrap();
+        assert!(app.left.quick_filter.is_none());
+        assert_eq!(app.left.entries.len(), 4);
+        assert_eq!(app.left.current_entry().unwrap().name, "beta.md");
+    }
 
-        let idx = (num as usize).saturating_sub(1);
-        if idx < self.favorites.len() {
-            self.sidebar_state.select_by_number(num as usize, self.favorites.len());
-            // Navigate to the favorite
-            if let Some(fav) = self.favorites.get(idx) {
-                if fav.is_valid() {
-                    self.navigate_to_path(fav.path.clone());
-                } else {
-                    self.set_status(format!("Favorite '{}' is broken", fav.name), true);
+    #[test]
+    fn quick_filter_follows_reload_but_not_navigation() {
+        use zmanager_core::EntryKind::File;
+        let mut app = filter_test_app();
+        app.handle_action(Action::FilterMenu).unwrap();
+        app.push_filter_char('a');
+        app.finish_filter_input();
+
+        // A refresh of the same directory is narrowed again
+        app.left.set_entries(vec![entry("delta.rs", File), entry("echo.rs", File)]);
+        assert_eq!(names(&app.left), ["delta.rs"]);
+
+        app.left.nav.navigate_to("D:\\other");
+        app.left.set_entries(vec![entry("delta.rs", File), entry("echo.rs", File)]);
+        assert!(app.left.quick_filter.is_none());
+        assert_eq!(app.left.entries.len(), 2);
+    }
+
+    #[test]
+    fn backspace_on_empty_quick_filter_leaves_filter_mode() {
+        let mut app = filter_test_app();
+        app.handle_action(Action::FilterMenu).unwrap();
+        app.pop_filter_char();
+        assert!(app.left.quick_filter.is_none());
+        assert_eq!(app.left.entries.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn copy_runs_as_scheduled_job() {
+        let root = tempfile::TempDir::new().unwrap();
+        let source = root.path().join("source.txt");
+        let destination = root.path().join("dest");
+        std::fs::write(&source, b"hello").unwrap();
+        std::fs::create_dir(&destination).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), destination.clone(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        app.execute_copy(vec![source], destination.clone(), true);
+        let finished = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::JobCompleted { job_id, success } => return (job_id, success),
+                    _ => {}
                 }
             }
-        }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        assert!(finished.1);
+        assert_eq!(std::fs::read(destination.join("source.txt")).unwrap(), b"hello");
+        assert_eq!(app.jobs.len(), 1);
+        assert_eq!(app.jobs[0].state, JobState::Completed);
+        app.apply_job_finished(finished.0, finished.1);
+        assert!(latest_status(&app).unwrap().0.starts_with("Done: Copying"));
+
+        handle.shutdown().await;
     }
 
-    /// Navigate to a specific path.
-    pub fn navigate_to_path(&mut self, path: PathBuf) {
-        let pane = self.active_mut();
-        pane.nav.navigate_to(&path);
-        pane.selection.clear();
-        pane.set_cursor(0);
-        let _ = self.event_tx.send(Event::DirectoryChanged(path));
+    #[tokio::test]
+    async fn extract_runs_as_scheduled_job() {
+        let root = tempfile::TempDir::new().unwrap();
+        let archive = root.path().join("bundle.zip");
+        let destination = root.path().join("dest");
+        let sources = vec![root.path().join("a.txt"), root.path().join("b.txt")];
+        std::fs::write(&sources[0], b"new").unwrap();
+        std::fs::write(&sources[1], b"bee").unwrap();
+        zmanager_core::create_archive(&sources, &archive, &CancellationToken::new(), |_| {})
+            .unwrap();
+        std::fs::create_dir(&destination).unwrap();
+        std::fs::write(destination.join("a.txt"), b"old").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), destination.clone(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+        let name = "bundle.zip".to_string();
+        app.left.set_entries(vec![EntryMeta::new(name, archive, zmanager_core::EntryKind::File)]);
+
+        app.handle_action(Action::Extract).unwrap();
+        let finished = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::JobCompleted { job_id, success } => return (job_id, success),
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        assert!(finished.1);
+        assert_eq!(std::fs::read(destination.join("b.txt")).unwrap(), b"bee");
+        // The conflict resolver keeps the file that was already there
+        assert_eq!(std::fs::read(destination.join("a.txt")).unwrap(), b"old");
+        app.apply_job_finished(finished.0, finished.1);
+        assert!(latest_status(&app).unwrap().0.starts_with("Done: Extracting"));
+
+        handle.shutdown().await;
     }
 
-    /// Navigate to the selected sidebar item.
-    pub fn navigate_to_sidebar_selection(&mut self) {
-        match self.sidebar_state.section {
-            crate::ui::SidebarSection::Favorites => {
-                if let Some(idx) = self.sidebar_state.selected_favorite() {
-                    if let Some(fav) = self.favorites.get(idx) {
-                        if fav.is_valid() {
-                            self.navigate_to_path(fav.path.clone());
-                        } else {
-                            self.set_status(format!("Favorite '{}' is broken", fav.name), true);
-                        }
+    #[test]
+    fn finished_jobs_are_kept_in_history() {
+        let root = tempfile::TempDir::new().unwrap();
+        let mut app = create_test_app();
+        app.reports_dir = root.path().to_path_buf();
+        app.apply_job_started(7, TransferOperation::Copy);
+        let failed = TransferItemResult::failed("a.txt".into(), "out/a.txt".into(), "Locked");
+        app.apply_job_item(7, failed);
+        app.apply_job_finished(7, false);
+
+        app.toggle_transfers_view();
+        app.show_report_history();
+        assert_eq!(app.report_history.as_ref().unwrap().len(), 1);
+
+        // Without a scheduler the retry can't be queued
+        app.retry_failed_items();
+        assert!(app.has_dialog());
+
+        app.toggle_transfers_view();
+        assert!(!app.has_report_history());
+    }
+
+    #[tokio::test]
+    async fn foreground_copy_shows_progress_until_done() {
+        let root = tempfile::TempDir::new().unwrap();
+        let source = root.path().join("source.txt");
+        let destination = root.path().join("dest");
+        std::fs::write(&source, b"hello").unwrap();
+        std::fs::create_dir(&destination).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), destination.clone(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        app.execute_copy(vec![source], destination.clone(), false);
+        let mut shown = false;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::ForegroundJobStarted(job_id) => {
+                        app.apply_foreground_job(job_id);
+                        shown |= app.has_foreground_job();
+                    }
+                    Event::JobCompleted { job_id, success } => {
+                        app.apply_job_finished(job_id, success);
+                        return;
                     }
+                    _ => {}
                 }
             }
-            crate::ui::SidebarSection::Drives => {
-                if let Some(idx) = self.sidebar_state.selected_drive() {
-                    if let Some(drive) = self.drives.get(idx) {
-                        if drive.is_ready {
-                            self.navigate_to_path(drive.path.clone());
-                        } else {
-                            self.set_status("Drive is not ready", true);
-                        }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        // The dialog shows while the job runs and closes when it is done
+        assert!(shown || app.jobs.iter().all(|job| job.state.is_terminal()));
+        assert!(!app.has_foreground_job());
+        assert!(destination.join("source.txt").exists());
+
+        // A job sent to the background leaves the dialog
+        app.foreground_job = Some(42);
+        app.background_foreground_job();
+        assert!(!app.has_foreground_job());
+        assert!(latest_status(&app).unwrap().0.contains("background"));
+
+        handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn finished_job_report_is_exported() {
+        let root = tempfile::TempDir::new().unwrap();
+        let source = root.path().join("source.txt");
+        let destination = root.path().join("dest");
+        let scratch = root.path().join("scratch.tmp");
+        std::fs::write(&source, b"hello").unwrap();
+        std::fs::write(&scratch, b"").unwrap();
+        std::fs::create_dir(&destination).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), destination.clone(), tx);
+        app.reports_dir = root.path().join("reports");
+        app.config.operations.exclude_patterns = vec!["*.tmp".to_string()];
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        app.execute_copy(vec![source, scratch], destination.clone(), true);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::JobStarted(job_id, operation) => {
+                        app.apply_job_started(job_id, operation)
                     }
+                    Event::JobItemFinished(job_id, item) => app.apply_job_item(job_id, item),
+                    Event::JobExcluded(job_id, excluded) => {
+                        app.apply_job_excluded(job_id, excluded);
+                    }
+                    Event::JobCompleted { .. } => return,
+                    _ => {}
                 }
             }
-        }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        app.toggle_transfers_view();
+        app.toggle_job_details();
+        let (_, details) = app.selected_job_details().unwrap();
+        assert_eq!(details.items().len(), 1);
+
+        app.export_selected_job_report();
+        let reports = ReportStorage::new(root.path().join("reports")).list().unwrap();
+        assert_eq!(reports.len(), 1);
+        let text = root.path().join("reports").join(&reports[0]).with_extension("txt");
+        let text = std::fs::read_to_string(text).unwrap();
+        assert!(text.contains("=== Copy Report ==="));
+        assert!(text.contains("Excluded: 1"));
+        assert!(!destination.join("scratch.tmp").exists());
+
+        handle.shutdown().await;
     }
 
-    /// Move sidebar selection up.
-    pub fn sidebar_up(&mut self) {
-        self.sidebar_state.up(self.favorites.len(), self.drives.len());
+    #[tokio::test]
+    async fn delete_runs_as_scheduled_job() {
+        let root = tempfile::TempDir::new().unwrap();
+        let file = root.path().join("old.txt");
+        let folder = root.path().join("old");
+        std::fs::write(&file, b"bye").unwrap();
+        std::fs::create_dir_all(folder.join("inner")).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        let files = vec![file.clone(), folder.clone(), root.path().join("gone")];
+        app.execute_delete(files, true);
+        let finished = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::JobCompleted { job_id, success } => return (job_id, success),
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        // The missing item fails the job without stopping the others
+        assert!(!finished.1);
+        assert!(!file.exists() && !folder.exists());
+        assert_eq!(app.jobs[0].state, JobState::Failed);
+        app.apply_job_finished(finished.0, finished.1);
+        let status = latest_status(&app).unwrap().0;
+        assert!(status.contains("1 of 3 item(s) could not be deleted"), "{status}");
+
+        handle.shutdown().await;
     }
 
-    /// Move sidebar selection down.
-    pub fn sidebar_down(&mut self) {
-        self.sidebar_state.down(self.favorites.len(), self.drives.len());
-    }
+    #[tokio::test]
+    async fn streamed_listing_fills_pane_in_batches() {
+        let root = tempfile::TempDir::new().unwrap();
+        for name in ["e.txt", "d.txt", "c.txt", "b.txt", "a.txt"] {
+            std::fs::write(root.path().join(name), b"x").unwrap();
+        }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+
+        // Superseded before any of its batches are applied
+        let stale = zmanager_core::list_directory_streamed(root.path(), None, 2).unwrap();
+        app.stream_listing(Pane::Left, root.path(), stale);
+        let batches = zmanager_core::list_directory_streamed(root.path(), None, 2).unwrap();
+        app.stream_listing(Pane::Left, root.path(), batches);
+
+        let mut seen = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::ListingBatch(pane, id, entries) => {
+                        app.apply_listing_batch(pane, id, entries);
+                        seen.push(app.left.loading_count());
+                    }
+                    Event::ListingFinished(pane, id) => {
+                        app.finish_listing(pane, id);
+                        if app.left.loading_count().is_none() {
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .unwrap();
 
-    /// Toggle sidebar section.
-    pub fn sidebar_toggle_section(&mut self) {
-        self.sidebar_state.toggle_section();
+        assert_eq!(seen.iter().flatten().max(), Some(&5));
+        let names: Vec<&str> = app.left.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"]);
     }
 
-    /// Remove selected favorite from sidebar.
-    pub fn remove_selected_favorite(&mut self) {
-        if let Some(idx) = self.sidebar_state.selected_favorite() {
-            if let Some(fav) = self.favorites.get(idx) {
-                let id = fav.id.clone();
-                let name = fav.name.clone();
-                self.config.remove_favorite(&id);
-                self.favorites = self.config.favorites.clone();
+    #[tokio::test]
+    async fn directory_sizes_stop_when_the_pane_leaves() {
+        use zmanager_core::EntryKind::Directory;
 
-                if let Err(e) = self.config.save() {
-                    self.set_status(format!("Failed to save config: {}", e), true);
-                } else {
-                    self.set_status(format!("Removed '{}' from favorites", name), false);
-                }
-            }
+        let root = tempfile::TempDir::new().unwrap();
+        for name in ["a", "b"] {
+            std::fs::create_dir(root.path().join(name)).unwrap();
+            std::fs::write(root.path().join(name).join("file.txt"), b"12345").unwrap();
         }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.sort = SortSpec { dirs_by_computed_size: true, ..SortSpec::by_size() };
+        let folder = |name: &str| {
+            let mut folder = entry(name, Directory);
+            folder.path = root.path().join(name);
+            folder
+        };
+
+        app.update_entries(Pane::Left, vec![folder("a")]);
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap();
+        let Some(Event::DirectorySizeComputed(path, size)) = event else {
+            panic!("expected the folder size");
+        };
+        assert_eq!((path, size), (root.path().join("a"), 5));
+
+        // Listing another directory cancels what is still calculating
+        app.update_entries(Pane::Left, vec![folder("b")]);
+        let cancel = app.left.dir_sizes.as_ref().unwrap().cancel.clone();
+        let sub = root.path().join("a");
+        app.left.nav.navigate_to(&sub);
+        let batches = zmanager_core::list_directory_streamed(&sub, None, 10).unwrap();
+        app.stream_listing(Pane::Left, &sub, batches);
+        assert!(cancel.is_cancelled());
+        assert!(app.left.dir_sizes.is_none());
     }
 
-    // ========== Properties ==========
+    #[tokio::test]
+    async fn streamed_listing_of_empty_directory_clears_pane() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        app.left.set_entries(vec![entry("gone.txt", zmanager_core::EntryKind::File)]);
+
+        let batches = zmanager_core::list_directory_streamed(root.path(), None, 10).unwrap();
+        app.stream_listing(Pane::Left, root.path(), batches);
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap();
+        let Some(Event::ListingFinished(pane, id)) = event else {
+            panic!("expected the listing to finish");
+        };
+        app.finish_listing(pane, id);
 
-    /// Show properties for the current entry.
-    pub fn show_properties(&mut self) {
-        if let Some(entry) = self.active().current_entry() {
-            match zmanager_core::get_properties(&entry.path) {
-                Ok(props) => {
-                    self.properties = Some(props);
-                }
-                Err(e) => {
-                    self.set_status(format!("Failed to get properties: {}", e), true);
+        assert!(app.left.entries.is_empty());
+        assert_eq!(app.left.loading_count(), None);
+    }
+
+    #[tokio::test]
+    async fn refresh_keeps_cursor_and_selection() {
+        let root = tempfile::TempDir::new().unwrap();
+        let dir = root.path().to_path_buf();
+        for name in ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(dir.clone(), dir.clone(), tx);
+
+        let mut refresh = async |app: &mut App| {
+            let batches = zmanager_core::list_directory_streamed(&dir, None, 2).unwrap();
+            app.stream_listing(Pane::Left, &dir, batches);
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while let Some(event) = rx.recv().await {
+                    match event {
+                        Event::ListingBatch(pane, id, entries) => {
+                            app.apply_listing_batch(pane, id, entries);
+                        }
+                        Event::ListingFinished(pane, id) => {
+                            app.finish_listing(pane, id);
+                            return;
+                        }
+                        _ => {}
+                    }
                 }
-            }
+            })
+            .await
+            .unwrap();
+        };
+        refresh(&mut app).await;
+        for index in [3, 4] {
+            app.left.set_cursor(index);
+            app.left.toggle_select();
         }
+        app.left.set_cursor(1);
+
+        // Changed outside the app; the watcher reports the rename
+        std::fs::write(dir.join("0.txt"), b"x").unwrap();
+        std::fs::remove_file(dir.join("e.txt")).unwrap();
+        std::fs::rename(dir.join("d.txt"), dir.join("y.txt")).unwrap();
+        let canonical = dir.canonicalize().unwrap();
+        let renames = [(canonical.join("d.txt"), canonical.join("y.txt"))];
+        app.follow_renames(Pane::Left, &canonical, &renames);
+        refresh(&mut app).await;
+
+        assert_eq!(app.left.current_entry().unwrap().name, "b.txt");
+        let selected: Vec<&str> = app
+            .left
+            .selected_indices()
+            .into_iter()
+            .map(|i| app.left.entries[i].name.as_str())
+            .collect();
+        assert_eq!(selected, ["y.txt"]);
+        assert!(!app.left.selection.is_selected(&dir.join("e.txt")));
     }
 
-    /// Close the properties panel.
-    pub fn close_properties(&mut self) {
-        self.properties = None;
+    #[tokio::test]
+    async fn failed_listing_goes_back() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+        let share = PathBuf::from(r"\\server\share");
+        app.navigate_to_path(share.clone());
+        app.left.set_entries(vec![entry("stale.txt", zmanager_core::EntryKind::File)]);
+
+        let (batches_tx, batches) = mpsc::channel(1);
+        batches_tx
+            .try_send(Err(ZError::NotFound { path: share.clone() }))
+            .unwrap();
+        app.stream_listing(Pane::Left, &share, batches);
+        let (pane, id, message) = loop {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap();
+            if let Some(Event::ListingFailed(pane, id, message)) = event {
+                break (pane, id, message);
+            }
+        };
+        let back = app.fail_listing(pane, id, message);
+
+        assert_eq!(back.as_deref(), Some(root.path()));
+        assert_eq!(app.left.nav.current_path(), root.path());
+        assert!(app.left.entries.is_empty());
+        assert_eq!(app.left.loading_count(), None);
+        assert!(latest_status(&app).unwrap().0.starts_with("Cannot open"));
+        // A listing that is no longer loading is left alone
+        assert_eq!(app.fail_listing(Pane::Left, id, String::new()), None);
     }
 
-    /// Check if properties panel is visible.
-    pub fn has_properties(&self) -> bool {
-        self.properties.is_some()
+    #[test]
+    fn visits_are_recorded_and_forgotten() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx);
+        app.visit_history = VisitHistory::default();
+        let (a, b) = (Path::new("C:\\a"), Path::new("C:\\b"));
+
+        app.record_visit(a);
+        app.record_visit(a); // refresh
+        app.record_visit(b);
+        app.record_visit(a);
+        let visits = app.visit_history.visits();
+        let count = |path| visits.iter().find(|v| v.path == path).unwrap().count;
+        assert_eq!((count(a), count(b)), (2, 1));
+
+        app.handle_action(Action::DirectoryHistory).unwrap();
+        assert_eq!(app.history_view.as_ref().unwrap().len(), 2);
+        for _ in 0..2 {
+            let path = app.history_view.as_ref().unwrap().selected().unwrap().clone();
+            app.forget_visit(&path);
+        }
+        assert!(app.visit_history.is_empty());
+        assert!(!app.has_history_view());
+
+        app.handle_action(Action::DirectoryHistory).unwrap();
+        assert!(!app.has_history_view());
     }
 
-    /// Close the help screen.
-    pub fn close_help(&mut self) {
-        self.show_help = false;
+    #[test]
+    fn go_to_path_resolves_relative_paths() {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(root.path().join("sub")).unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+
+        app.handle_action(Action::GoToPath).unwrap();
+        assert!(app.has_location_bar());
+
+        app.execute_go_to_path(" sub ".to_string());
+        assert_eq!(app.left.nav.current_path(), root.path().join("sub"));
+        assert!(matches!(rx.try_recv(), Ok(Event::DirectoryChanged(_))));
+
+        app.execute_go_to_path("missing".to_string());
+        assert_eq!(app.left.nav.current_path(), root.path().join("sub"));
+        assert_eq!(latest_status(&app).unwrap().1, StatusLevel::Error);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn go_to_path_opens_servers() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+
+        // Taken as typed, not under the current directory, and not checked
+        app.execute_go_to_path("sftp://me@example.com:2222/srv/../data/".to_string());
+        let remote = PathBuf::from("sftp://me@example.com:2222/data");
+        assert_eq!(app.left.nav.current_path(), remote);
+        assert!(matches!(rx.try_recv(), Ok(Event::DirectoryChanged(path)) if path == remote));
+        assert!(!app.watch_locations().contains(&remote));
+
+        app.execute_go_to_path("davs://cloud.example.com/Files/./Photos".to_string());
+        let dav = PathBuf::from("davs://cloud.example.com/Files/Photos");
+        assert_eq!(app.left.nav.current_path(), dav);
+        assert!(!app.watch_locations().contains(&dav));
+    }
 
-    fn create_test_app() -> App {
+    #[test]
+    fn go_to_path_opens_devices() {
+        let root = tempfile::TempDir::new().unwrap();
         let (tx, _rx) = mpsc::unbounded_channel();
-        App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx)
+        let mut app = App::new(root.path().to_path_buf(), root.path().to_path_buf(), tx);
+
+        app.execute_go_to_path(r"mtp://Pixel 7/Internal storage\DCIM/".to_string());
+        let phone = PathBuf::from("mtp://Pixel 7/Internal storage/DCIM");
+        assert_eq!(app.left.nav.current_path(), phone);
+        assert!(!app.watch_locations().contains(&phone));
+        assert!(app.directory_provider(&phone).is_some());
+        assert!(app.directory_provider(root.path()).is_none());
     }
 
     #[test]
-    fn app_starts_with_left_pane_active() {
-        let app = create_test_app();
-        assert_eq!(app.active_pane, Pane::Left);
+    fn speed_limit_cycles_through_presets() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx);
+        let job = Job::new(JobKind::CalculateSize {
+            path: PathBuf::from("C:\\"),
+        });
+        app.toggle_transfers_view();
+
+        let mut limits = Vec::new();
+        for _ in 0..SPEED_LIMIT_PRESETS.len() {
+            app.update_jobs(vec![JobInfo::from(&job)]);
+            app.cycle_selected_speed_limit();
+            let Ok(Event::SetSpeedLimit(id, limit)) = rx.try_recv() else {
+                panic!("no speed limit request");
+            };
+            assert_eq!(id, job.id.0);
+            job.speed_limit.set(limit);
+            limits.push(limit);
+        }
+
+        assert_eq!(limits, [Some(50 << 20), Some(10 << 20), Some(1 << 20), None]);
+        assert_eq!(latest_status(&app).unwrap().0, "Speed limit: off");
     }
 
     #[test]
-    fn switch_pane_toggles() {
-        let mut app = create_test_app();
-        app.switch_pane();
-        assert_eq!(app.active_pane, Pane::Right);
-        app.switch_pane();
-        assert_eq!(app.active_pane, Pane::Left);
+    fn finished_job_rings_bell_while_unfocused() {
+        let reports = tempfile::TempDir::new().unwrap();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut app = App::new(PathBuf::from("C:\\"), PathBuf::from("D:\\"), tx);
+        app.reports_dir = reports.path().to_path_buf();
+        app.config.notifications.min_duration_secs = 0;
+        let mut job = Job::new(JobKind::CalculateSize {
+            path: PathBuf::from("C:\\"),
+        });
+        job.start();
+        job.complete();
+        app.update_jobs(vec![JobInfo::from(&job)]);
+
+        app.apply_job_started(job.id.0, TransferOperation::Copy);
+        app.apply_job_finished(job.id.0, true);
+        assert!(!app.take_bell());
+
+        app.set_terminal_focused(false);
+        app.apply_job_finished(job.id.0, true);
+        assert!(app.take_bell());
+        assert!(!app.take_bell());
+
+        app.config.notifications.bell = false;
+        app.apply_job_finished(job.id.0, true);
+        assert!(!app.take_bell());
     }
 
-    #[test]
-    fn quit_action_sets_flag() {
-        let mut app = create_test_app();
-        app.handle_action(Action::Quit).unwrap();
-        assert!(app.should_quit);
+    /// Copy `source.txt` onto an existing file, answering the conflict with
+    /// `resolution`; returns the app, whether the job succeeded and the
+    /// destination file.
+    async fn copy_with_conflict(
+        root: &Path,
+        resolution: ConflictResolution,
+    ) -> (App, bool, PathBuf) {
+        let source = root.join("source.txt");
+        let destination = root.join("dest");
+        std::fs::write(&source, b"new").unwrap();
+        std::fs::create_dir(&destination).unwrap();
+        std::fs::write(destination.join("source.txt"), b"old").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut app = App::new(root.to_path_buf(), destination.clone(), tx);
+        let (scheduler, handle) = zmanager_core::Scheduler::with_defaults();
+        tokio::spawn(scheduler.run());
+        app.attach_scheduler(handle.clone());
+
+        app.execute_copy(vec![source], destination.clone(), true);
+        let (job_id, success) = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Event::JobsUpdated(jobs) => app.update_jobs(jobs),
+                    Event::ConflictAsked(query) => {
+                        app.apply_conflict_query(query);
+                        assert!(app.has_conflict());
+                        app.answer_conflict(resolution, false);
+                    }
+                    Event::JobCompleted { job_id, success } => return (job_id, success),
+                    _ => {}
+                }
+            }
+            panic!("event channel closed");
+        })
+        .await
+        .unwrap();
+
+        app.apply_job_finished(job_id, success);
+        assert!(!app.has_conflict());
+        handle.shutdown().await;
+        (app, success, destination.join("source.txt"))
+    }
+
+    #[tokio::test]
+    async fn conflict_answer_reaches_transfer() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (_, success, copied) =
+            copy_with_conflict(root.path(), ConflictResolution::Overwrite).await;
+
+        assert!(success);
+        assert_eq!(std::fs::read(copied).unwrap(), b"new");
+    }
+
+    #[tokio::test]
+    async fn conflict_cancel_cancels_job() {
+        let root = tempfile::TempDir::new().unwrap();
+        let (app, success, copied) =
+            copy_with_conflict(root.path(), ConflictResolution::Cancel).await;
+
+        assert!(!success);
+        assert_eq!(std::fs::read(copied).unwrap(), b"old");
+        assert_eq!(app.jobs[0].state, JobState::Cancelled);
+        assert!(latest_status(&app).unwrap().0.starts_with("Cancelled: "));
     }
 }
@@ -9,9 +9,14 @@ use std::time::Duration;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 use tokio::sync::mpsc;
 use tracing::debug;
+use zmanager_transfer_win::{
+    ConflictQuery, ElevatedResult, ElevationManifest, TransferItemResult, TransferOperation,
+};
+
+use crate::ui::Pane;
 
 /// Application events.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum Event {
     /// Terminal key event.
     Key(KeyEvent),
@@ -19,10 +24,50 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize event.
     Resize(u16, u16),
+    /// Terminal gained (true) or lost (false) focus.
+    FocusChanged(bool),
     /// Tick event for periodic updates.
     Tick,
     /// Directory contents changed.
     DirectoryChanged(PathBuf),
+    /// A batch of a directory listing streaming into a pane (pane, listing
+    /// id, unsorted entries).
+    ListingBatch(Pane, u64, Vec<zmanager_core::EntryMeta>),
+    /// A streamed directory listing is complete (pane, listing id).
+    ListingFinished(Pane, u64),
+    /// A streamed directory listing could not be read, e.g. an unreachable
+    /// network share (pane, listing id, error message).
+    ListingFailed(Pane, u64, String),
+    /// Background size calculation for a directory finished (path, total bytes).
+    DirectorySizeComputed(PathBuf, u64),
+    /// Duplicate scan finished (scanned directory, duplicate paths and
+    /// reclaimable bytes, or an error message).
+    DuplicateScanFinished(PathBuf, Result<(Vec<PathBuf>, u64), String>),
+    /// Running totals of a folder size job (folder, totals so far).
+    FolderSizeProgress(PathBuf, zmanager_core::FolderStats),
+    /// Folder size job finished (folder, totals, or an error message).
+    FolderSizeFinished(PathBuf, Result<zmanager_core::FolderStats, String>),
+    /// Running totals of a multi-selection properties calculation (id,
+    /// totals so far).
+    MultiPropertiesProgress(u64, zmanager_core::MultiProperties),
+    /// Multi-selection properties calculation finished (id, totals, or an
+    /// error message).
+    MultiPropertiesFinished(u64, Result<zmanager_core::MultiProperties, String>),
+    /// Progress of a checksum job.
+    ChecksumProgress(zmanager_core::JobId, zmanager_core::Progress),
+    /// Checksum job finished (checksum per file, or an error message).
+    ChecksumFinished(zmanager_core::JobId, Result<Vec<zmanager_core::FileHash>, String>),
+    /// Progress of a compare job.
+    CompareProgress(zmanager_core::JobId, zmanager_core::Progress),
+    /// Compare job finished (how the files differ, or an error message).
+    CompareFinished(zmanager_core::JobId, Result<zmanager_core::FileComparison, String>),
+    /// Progress of a text conversion job.
+    ConvertProgress(zmanager_core::JobId, zmanager_core::Progress),
+    /// Text conversion job finished (outcome per file, or an error message).
+    ConvertFinished(zmanager_core::JobId, Result<Vec<zmanager_core::FileConversion>, String>),
+    /// Search in the file viewer finished (search id, bytes of the match,
+    /// or an error message).
+    ViewerSearchFinished(u64, Result<Option<std::ops::Range<u64>>, String>),
     /// Job progress update.
     JobProgress {
         job_id: u64,
@@ -32,6 +77,8 @@ pub enum Event {
     },
     /// Job completed.
     JobCompleted { job_id: u64, success: bool },
+    /// A transfer hit a conflict and is waiting for the user's answer.
+    ConflictAsked(ConflictQuery),
     /// Error message to display.
     Error(String),
     /// Request to quit the application.
@@ -39,16 +86,12 @@ pub enum Event {
 
     // ========== File Operation Events ==========
 
-    /// Execute delete operation on the specified paths.
-    ExecuteDelete(Vec<PathBuf>),
     /// Execute rename operation (old path, new path).
     ExecuteRename(PathBuf, PathBuf),
     /// Execute mkdir operation at the specified path.
     ExecuteMkdir(PathBuf),
-    /// Execute copy operation (sources, destination).
-    ExecuteCopy(Vec<PathBuf>, PathBuf),
-    /// Execute move operation (sources, destination).
-    ExecuteMove(Vec<PathBuf>, PathBuf),
+    /// Create a new file at the specified path.
+    ExecuteNewFile(PathBuf),
     /// Refresh all panes.
     RefreshAll,
 
@@ -60,8 +103,26 @@ pub enum Event {
     ResumeJob(u64),
     /// Cancel a job by ID.
     CancelJob(u64),
+    /// Change a job's speed limit (bytes per second, None for unlimited).
+    SetSpeedLimit(u64, Option<u64>),
     /// Jobs list updated.
     JobsUpdated(Vec<zmanager_core::JobInfo>),
+    /// A transfer or delete job started (job id, operation).
+    JobStarted(u64, TransferOperation),
+    /// A job finished an item (job id, outcome).
+    JobItemFinished(u64, TransferItemResult),
+    /// A transfer to show in a progress dialog until it finishes was queued
+    /// (job id).
+    ForegroundJobStarted(u64),
+    /// A transfer's include and exclude patterns left items out (job id,
+    /// number of files and folders).
+    JobExcluded(u64, usize),
+    /// Items of a transfer or delete failed because access was denied (job
+    /// id, the operations to retry as administrator).
+    AccessDenied(u64, ElevationManifest),
+    /// A retry as administrator finished (job id, or `None` for a rename;
+    /// results, or an error message).
+    ElevationFinished(Option<u64>, Result<Vec<ElevatedResult>, String>),
 }
 
 /// Event handler that polls for terminal events.
@@ -117,6 +178,16 @@ impl EventHandler {
                                 break;
                             }
                         }
+                        Ok(CrosstermEvent::FocusGained) => {
+                            if tx.send(Event::FocusChanged(true)).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(CrosstermEvent::FocusLost) => {
+                            if tx.send(Event::FocusChanged(false)).is_err() {
+                                break;
+                            }
+                        }
                         Ok(_) => {}
                         Err(_) => break,
                     }
@@ -1,11 +1,17 @@
 //! Input handling and key mappings.
 //!
-//! This module defines the key bindings and input actions.
+//! This module defines the input actions and the keymap that binds key
+//! chords to them. The built-in bindings can be overridden per action from
+//! the `[keybindings]` section of the config.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 /// Actions that can be performed in the TUI.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Action {
     /// Quit the application.
     Quit,
@@ -25,6 +31,10 @@ pub enum Action {
     GoBack,
     /// Go forward in history.
     GoForward,
+    /// Go to a typed path, including UNC paths.
+    GoToPath,
+    /// Show visited directories ranked by frecency.
+    DirectoryHistory,
     /// Toggle selection on current item.
     ToggleSelect,
     /// Select all items.
@@ -33,6 +43,14 @@ pub enum Action {
     InvertSelection,
     /// Clear selection.
     ClearSelection,
+    /// Select entries matching a wildcard pattern.
+    SelectPattern,
+    /// Deselect entries matching a wildcard pattern.
+    DeselectPattern,
+    /// Select files with the same extension as the current one.
+    SelectSameExt,
+    /// Select files newer than the selected ones.
+    SelectNewer,
     /// Page up.
     PageUp,
     /// Page down.
@@ -41,30 +59,97 @@ pub enum Action {
     GoFirst,
     /// Go to last item.
     GoLast,
+    /// Jump to the next entry with the same extension.
+    NextSameExt,
+    /// Jump to the previous entry with the same extension.
+    PrevSameExt,
     /// Toggle hidden files.
     ToggleHidden,
     /// Refresh current directory.
     Refresh,
     /// Switch focus to other pane.
     SwitchPane,
+    /// Open a new tab in the active pane.
+    NewTab,
+    /// Close the active pane's current tab.
+    CloseTab,
+    /// Show the next tab in the active pane.
+    NextTab,
+    /// Show the previous tab in the active pane.
+    PrevTab,
     /// Copy selected items.
     Copy,
     /// Move selected items.
     Move,
-    /// Delete selected items.
+    /// Copy selected items to the previous directory in history.
+    CopyToPrevious,
+    /// Move selected items to the previous directory in history.
+    MoveToPrevious,
+    /// Move selected items to the Recycle Bin.
     Delete,
+    /// Delete selected items permanently.
+    DeletePermanent,
     /// Rename current item.
     Rename,
+    /// Rename current item to the text on the clipboard.
+    RenameFromClipboard,
     /// Create new directory.
     MakeDir,
+    /// Create a new file, from a template if one matches.
+    NewFile,
     /// Open file with default application.
     Open,
+    /// Choose the application to open the file at the cursor with.
+    OpenWith,
+    /// Open all selected files with their default applications.
+    OpenSelected,
+    /// Run the executable at the cursor.
+    Run,
+    /// Open the configured terminal in the current directory.
+    OpenTerminal,
+    /// Show the item at the cursor selected in Windows Explorer.
+    RevealInExplorer,
+    /// Copy the full paths of the selected items to the clipboard.
+    CopyPath,
+    /// Copy the names of the selected items to the clipboard.
+    CopyName,
+    /// Choose how to copy the selected items to the clipboard as text.
+    CopyMenu,
+    /// Show the operations that apply to the selected entries.
+    ContextMenu,
+    /// Open the item at the cursor in the configured editor.
+    Edit,
+    /// Show the file at the cursor in the built-in viewer.
+    View,
+    /// Pack the selected items into a new zip archive.
+    Compress,
+    /// Unpack the archive at the cursor, or the selected entries of the
+    /// archive being browsed, into the other pane.
+    Extract,
+    /// Take ownership of the selected items, as administrator.
+    TakeOwnership,
     /// Show file properties.
     Properties,
     /// Open sort menu.
     SortMenu,
-    /// Open filter menu.
+    /// Start typing a filter that narrows the active pane by name.
     FilterMenu,
+    /// Toggle the active pane between the filter and its complement.
+    InvertFilter,
+    /// Filter listings by size, modification date and attributes.
+    FilterBuilder,
+    /// Toggle listing the files of all subfolders in one flat list.
+    ToggleFlatView,
+    /// Mark files that also exist in the other pane's tree.
+    FindDuplicates,
+    /// Calculate the size of the selected folders.
+    CalculateSize,
+    /// Compute, compare and verify checksums of the selected files.
+    Checksum,
+    /// Compare the contents of a file from each pane.
+    Compare,
+    /// Convert the selected text files to another encoding or line ending.
+    ConvertText,
     /// Open help.
     Help,
     /// Toggle transfers view.
@@ -75,113 +160,532 @@ pub enum Action {
     ResumeJob,
     /// Cancel selected job.
     CancelJob,
+    /// Step the selected job through the speed limit presets.
+    CycleSpeedLimit,
+    /// Write the selected job's report to the reports folder.
+    ExportReport,
+    /// Queue the failed items of the selected job again.
+    RetryFailed,
     /// Toggle sidebar.
     ToggleSidebar,
+    /// Pick the left pane's drive from a menu.
+    DriveMenuLeft,
+    /// Pick the right pane's drive from a menu.
+    DriveMenuRight,
     /// Add current directory to favorites.
     AddFavorite,
-    /// Quick jump to favorite (1-9).
+    /// Add the selected items to a named basket.
+    AddToBasket,
+    /// Show a basket in the active pane.
+    ShowBasket,
+    /// Take the selected items out of the basket the pane shows.
+    RemoveFromBasket,
+    /// Quick jump to favorite (1-9) in the sidebar; a count prefix digit
+    /// (0-9) in the file panes.
     QuickJump(u8),
+    /// Run the plugin command at this index of the loaded plugins.
+    Plugin(usize),
     /// No action.
     None,
 }
 
-/// Map a key event to an action.
-pub fn map_key(key: KeyEvent) -> Action {
-    match (key.modifiers, key.code) {
-        // Quit
-        (KeyModifiers::NONE, KeyCode::Char('q')) => Action::Quit,
-        (KeyModifiers::CONTROL, KeyCode::Char('c')) => Action::Quit,
-        (KeyModifiers::CONTROL, KeyCode::Char('q')) => Action::Quit,
-
-        // Navigation - Arrow keys
-        (KeyModifiers::NONE, KeyCode::Up) => Action::Up,
-        (KeyModifiers::NONE, KeyCode::Down) => Action::Down,
-        (KeyModifiers::NONE, KeyCode::Left) => Action::GoParent,
-        (KeyModifiers::NONE, KeyCode::Right) => Action::Enter,
-
-        // Navigation - Vim keys
-        (KeyModifiers::NONE, KeyCode::Char('k')) => Action::Up,
-        (KeyModifiers::NONE, KeyCode::Char('j')) => Action::Down,
-        (KeyModifiers::NONE, KeyCode::Char('h')) => Action::GoParent,
-        (KeyModifiers::NONE, KeyCode::Char('l')) => Action::Enter,
-
-        // Enter directory
-        (KeyModifiers::NONE, KeyCode::Enter) => Action::Enter,
-
-        // Parent directory
-        (KeyModifiers::NONE, KeyCode::Backspace) => Action::GoParent,
-        (KeyModifiers::ALT, KeyCode::Up) => Action::GoParent,
-
-        // History
-        (KeyModifiers::ALT, KeyCode::Left) => Action::GoBack,
-        (KeyModifiers::ALT, KeyCode::Right) => Action::GoForward,
-        (KeyModifiers::NONE, KeyCode::Char('[')) => Action::GoBack,
-        (KeyModifiers::NONE, KeyCode::Char(']')) => Action::GoForward,
-
-        // Selection
-        (KeyModifiers::NONE, KeyCode::Char(' ')) => Action::ToggleSelect,
-        (KeyModifiers::CONTROL, KeyCode::Char('a')) => Action::SelectAll,
-        (KeyModifiers::NONE, KeyCode::Char('*')) => Action::InvertSelection,
-        (KeyModifiers::NONE, KeyCode::Esc) => Action::ClearSelection,
-
-        // Page navigation
-        (KeyModifiers::NONE, KeyCode::PageUp) => Action::PageUp,
-        (KeyModifiers::NONE, KeyCode::PageDown) => Action::PageDown,
-        (KeyModifiers::CONTROL, KeyCode::Char('u')) => Action::PageUp,
-        (KeyModifiers::CONTROL, KeyCode::Char('d')) => Action::PageDown,
-        (KeyModifiers::NONE, KeyCode::Home) => Action::GoFirst,
-        (KeyModifiers::NONE, KeyCode::End) => Action::GoLast,
-        (KeyModifiers::NONE, KeyCode::Char('g')) => Action::GoFirst,
-        (KeyModifiers::SHIFT, KeyCode::Char('G')) => Action::GoLast,
-
-        // View toggles
-        (KeyModifiers::NONE, KeyCode::Char('.')) => Action::ToggleHidden,
-        (KeyModifiers::NONE, KeyCode::F(5)) => Action::Refresh,
-        (KeyModifiers::CONTROL, KeyCode::Char('r')) => Action::Refresh,
-
-        // Pane switching
-        (KeyModifiers::NONE, KeyCode::Tab) => Action::SwitchPane,
-
-        // File operations
-        (KeyModifiers::SHIFT, KeyCode::Char('C')) => Action::Copy,
-        (KeyModifiers::SHIFT, KeyCode::Char('M')) => Action::Move,
-        (KeyModifiers::NONE, KeyCode::Char('d')) => Action::Delete,
-        (KeyModifiers::NONE, KeyCode::Delete) => Action::Delete,
-        (KeyModifiers::NONE, KeyCode::Char('r')) => Action::Rename,
-        (KeyModifiers::NONE, KeyCode::F(2)) => Action::Rename,
-        (KeyModifiers::NONE, KeyCode::Char('n')) => Action::MakeDir,
-        (KeyModifiers::NONE, KeyCode::Char('o')) => Action::Open,
-
-        // Info
-        (KeyModifiers::NONE, KeyCode::Char('p')) => Action::Properties,
-        (KeyModifiers::NONE, KeyCode::Char('i')) => Action::Properties,
-        (KeyModifiers::NONE, KeyCode::Char('s')) => Action::SortMenu,
-        (KeyModifiers::NONE, KeyCode::Char('f')) => Action::FilterMenu,
-        (KeyModifiers::NONE, KeyCode::Char('?')) => Action::Help,
-        (KeyModifiers::NONE, KeyCode::F(1)) => Action::Help,
-
-        // Transfers view
-        (KeyModifiers::NONE, KeyCode::Char('t')) => Action::ToggleTransfers,
-        (KeyModifiers::SHIFT, KeyCode::Char('P')) => Action::PauseJob,
-        (KeyModifiers::SHIFT, KeyCode::Char('R')) => Action::ResumeJob,
-        (KeyModifiers::SHIFT, KeyCode::Char('X')) => Action::CancelJob,
-
-        // Sidebar / Quick Access
-        (KeyModifiers::CONTROL, KeyCode::Char('b')) => Action::ToggleSidebar,
-        (KeyModifiers::SHIFT, KeyCode::Char('D')) => Action::AddFavorite,
-
-        // Quick jump to favorites (1-9)
-        (KeyModifiers::NONE, KeyCode::Char('1')) => Action::QuickJump(1),
-        (KeyModifiers::NONE, KeyCode::Char('2')) => Action::QuickJump(2),
-        (KeyModifiers::NONE, KeyCode::Char('3')) => Action::QuickJump(3),
-        (KeyModifiers::NONE, KeyCode::Char('4')) => Action::QuickJump(4),
-        (KeyModifiers::NONE, KeyCode::Char('5')) => Action::QuickJump(5),
-        (KeyModifiers::NONE, KeyCode::Char('6')) => Action::QuickJump(6),
-        (KeyModifiers::NONE, KeyCode::Char('7')) => Action::QuickJump(7),
-        (KeyModifiers::NONE, KeyCode::Char('8')) => Action::QuickJump(8),
-        (KeyModifiers::NONE, KeyCode::Char('9')) => Action::QuickJump(9),
-
-        // Default
-        _ => Action::None,
+/// Built-in bindings: each action with its config name and default chords.
+const DEFAULT_BINDINGS: &[(Action, &str, &[&str])] = &[
+    // Quit
+    (Action::Quit, "quit", &["q", "ctrl+c", "ctrl+q"]),
+    // Navigation
+    (Action::Up, "up", &["up", "k"]),
+    (Action::Down, "down", &["down", "j"]),
+    (Action::Enter, "enter", &["right", "l", "enter"]),
+    (Action::GoParent, "go_parent", &["left", "h", "backspace", "alt+up"]),
+    // History
+    (Action::GoBack, "go_back", &["alt+left", "["]),
+    (Action::GoForward, "go_forward", &["alt+right", "]"]),
+    (Action::GoToPath, "go_to_path", &["ctrl+l", ":"]),
+    (Action::DirectoryHistory, "directory_history", &["ctrl+h"]),
+    // Selection
+    (Action::ToggleSelect, "toggle_select", &["space"]),
+    (Action::SelectAll, "select_all", &["ctrl+a"]),
+    (Action::InvertSelection, "invert_selection", &["*"]),
+    (Action::ClearSelection, "clear_selection", &["esc"]),
+    (Action::SelectPattern, "select_pattern", &["+"]),
+    (Action::DeselectPattern, "deselect_pattern", &["-"]),
+    (Action::SelectSameExt, "select_same_ext", &["alt++"]),
+    (Action::SelectNewer, "select_newer", &["alt+n"]),
+    // Page navigation
+    (Action::PageUp, "page_up", &["pageup", "ctrl+u"]),
+    (Action::PageDown, "page_down", &["pagedown", "ctrl+d"]),
+    (Action::GoFirst, "go_first", &["home", "g"]),
+    (Action::GoLast, "go_last", &["end", "G"]),
+    (Action::NextSameExt, "next_same_ext", &["e"]),
+    (Action::PrevSameExt, "prev_same_ext", &["E"]),
+    // View toggles
+    (Action::ToggleHidden, "toggle_hidden", &["."]),
+    (Action::Refresh, "refresh", &["f5", "ctrl+r"]),
+    // Panes and tabs
+    (Action::SwitchPane, "switch_pane", &["tab"]),
+    (Action::NewTab, "new_tab", &["ctrl+t"]),
+    (Action::CloseTab, "close_tab", &["ctrl+w"]),
+    (Action::NextTab, "next_tab", &["ctrl+pagedown"]),
+    (Action::PrevTab, "prev_tab", &["ctrl+pageup"]),
+    // File operations
+    (Action::Copy, "copy", &["C"]),
+    (Action::Move, "move", &["M"]),
+    (Action::CopyToPrevious, "copy_to_previous", &["alt+c"]),
+    (Action::MoveToPrevious, "move_to_previous", &["alt+m"]),
+    (Action::Delete, "delete", &["d", "delete"]),
+    (Action::DeletePermanent, "delete_permanent", &["shift+delete"]),
+    (Action::Rename, "rename", &["r", "f2"]),
+    (Action::RenameFromClipboard, "rename_from_clipboard", &["alt+r"]),
+    (Action::MakeDir, "make_dir", &["n"]),
+    (Action::NewFile, "new_file", &["N", "shift+f4"]),
+    (Action::Open, "open", &["o"]),
+    (Action::OpenWith, "open_with", &["shift+enter", "alt+o"]),
+    (Action::OpenSelected, "open_selected", &["O"]),
+    (Action::Run, "run", &["x"]),
+    (Action::OpenTerminal, "open_terminal", &["T"]),
+    (Action::RevealInExplorer, "reveal_in_explorer", &["ctrl+e"]),
+    (Action::CopyPath, "copy_path", &["Y"]),
+    (Action::CopyName, "copy_name", &["alt+y"]),
+    (Action::CopyMenu, "copy_menu", &["c"]),
+    (Action::ContextMenu, "context_menu", &["m", "shift+f10"]),
+    (Action::Edit, "edit", &["f4"]),
+    (Action::View, "view", &["f3"]),
+    (Action::Compress, "compress", &["z"]),
+    (Action::Extract, "extract", &["Z"]),
+    (Action::TakeOwnership, "take_ownership", &["alt+t"]),
+    // Info
+    (Action::Properties, "properties", &["p", "i"]),
+    (Action::SortMenu, "sort_menu", &["s"]),
+    (Action::FilterMenu, "filter_menu", &["f", "/"]),
+    (Action::InvertFilter, "invert_filter", &["F"]),
+    (Action::FilterBuilder, "filter_builder", &["alt+f"]),
+    (Action::ToggleFlatView, "toggle_flat_view", &["ctrl+f"]),
+    (Action::FindDuplicates, "find_duplicates", &["u"]),
+    (Action::CalculateSize, "calculate_size", &["S"]),
+    (Action::Checksum, "checksum", &["H"]),
+    (Action::Compare, "compare", &["="]),
+    (Action::ConvertText, "convert_text", &["alt+e"]),
+    (Action::Help, "help", &["?", "f1"]),
+    // Transfers view
+    (Action::ToggleTransfers, "toggle_transfers", &["t"]),
+    (Action::PauseJob, "pause_job", &["P"]),
+    (Action::ResumeJob, "resume_job", &["R"]),
+    (Action::CancelJob, "cancel_job", &["X"]),
+    (Action::CycleSpeedLimit, "cycle_speed_limit", &["L"]),
+    (Action::ExportReport, "export_report", &["ctrl+s"]),
+    (Action::RetryFailed, "retry_failed", &["A"]),
+    // Sidebar / Quick Access
+    (Action::ToggleSidebar, "toggle_sidebar", &["ctrl+b"]),
+    (Action::DriveMenuLeft, "drive_menu_left", &["alt+f1"]),
+    (Action::DriveMenuRight, "drive_menu_right", &["alt+f2"]),
+    (Action::AddFavorite, "add_favorite", &["D"]),
+    (Action::AddToBasket, "add_to_basket", &["b"]),
+    (Action::ShowBasket, "show_basket", &["B"]),
+    (Action::RemoveFromBasket, "remove_from_basket", &["alt+b"]),
+    // Quick jump to favorites (1-9), or count prefix digits
+    (Action::QuickJump(0), "quick_jump_0", &["0"]),
+    (Action::QuickJump(1), "quick_jump_1", &["1"]),
+    (Action::QuickJump(2), "quick_jump_2", &["2"]),
+    (Action::QuickJump(3), "quick_jump_3", &["3"]),
+    (Action::QuickJump(4), "quick_jump_4", &["4"]),
+    (Action::QuickJump(5), "quick_jump_5", &["5"]),
+    (Action::QuickJump(6), "quick_jump_6", &["6"]),
+    (Action::QuickJump(7), "quick_jump_7", &["7"]),
+    (Action::QuickJump(8), "quick_jump_8", &["8"]),
+    (Action::QuickJump(9), "quick_jump_9", &["9"]),
+];
+
+impl Action {
+    /// The name the action goes by in the `[keybindings]` config section.
+    pub fn name(self) -> Option<&'static str> {
+        DEFAULT_BINDINGS
+            .iter()
+            .find(|(action, _, _)| *action == self)
+            .map(|(_, name, _)| *name)
+    }
+
+    /// Look up an action by its config name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        DEFAULT_BINDINGS
+            .iter()
+            .find(|(_, action_name, _)| *action_name == name)
+            .map(|(action, _, _)| *action)
+    }
+}
+
+/// A key together with the modifiers held while pressing it.
+///
+/// Written in the config as modifiers and key joined by `+`, e.g. `ctrl+t`,
+/// `alt+left` or `f5`. An uppercase letter implies Shift, so `G` and
+/// `shift+g` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    /// The key pressed.
+    pub code: KeyCode,
+    /// Modifiers held with it.
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Create a chord, folding Shift into letters the way terminals report them.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        match code {
+            KeyCode::Char(c) if c.is_ascii_uppercase() => Self {
+                code,
+                modifiers: modifiers | KeyModifiers::SHIFT,
+            },
+            KeyCode::Char(c) if c.is_ascii_lowercase() && shift => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers,
+            },
+            _ => Self { code, modifiers },
+        }
+    }
+
+    /// The chord of a key event.
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid key '{s}'");
+        // "+" alone, or as the last part of e.g. "ctrl++", is the plus key
+        let (mods, key) = match s.strip_suffix("++") {
+            Some(mods) => (Some(mods), "+"),
+            None if s == "+" => (None, "+"),
+            None => match s.rsplit_once('+') {
+                Some((mods, key)) => (Some(mods), key),
+                None => (None, s),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in mods.into_iter().flat_map(|mods| mods.split('+')) {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        // Shift is already visible in an uppercase letter
+        let shifted_letter = matches!(self.code, KeyCode::Char(c) if c.is_ascii_uppercase());
+        if self.modifiers.contains(KeyModifiers::SHIFT) && !shifted_letter {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Key chords bound to actions.
+///
+/// Starts from the built-in bindings. An action configured in the
+/// `[keybindings]` section gets exactly the chords listed there, which are
+/// taken away from any other action they were bound to by default.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Chords of every action, in the order of the built-in bindings.
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+    by_chord: HashMap<KeyChord, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keybindings]` config section.
+    ///
+    /// Returns the keymap along with any problems found: unknown action
+    /// names, chords that don't parse and chords claimed by more than one
+    /// action. Faulty entries are left out; everything else still applies.
+    pub fn from_config(config: &BTreeMap<String, Vec<String>>) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut custom: HashMap<Action, Vec<KeyChord>> = HashMap::new();
+        let mut claimed: HashMap<KeyChord, Action> = HashMap::new();
+
+        for (name, chords) in config {
+            let Some(action) = Action::from_name(name) else {
+                problems.push(format!("Unknown action '{name}'"));
+                continue;
+            };
+            let mut bound = Vec::new();
+            for text in chords {
+                let chord = match text.parse::<KeyChord>() {
+                    Ok(chord) => chord,
+                    Err(e) => {
+                        problems.push(format!("{name}: {e}"));
+                        continue;
+                    }
+                };
+                match claimed.get(&chord) {
+                    Some(&other) if other != action => problems.push(format!(
+                        "'{text}' is bound to both {} and {name}",
+                        other.name().unwrap_or_default()
+                    )),
+                    Some(_) => {}
+                    None => {
+                        claimed.insert(chord, action);
+                        bound.push(chord);
+                    }
+                }
+            }
+            // An empty list unbinds the action; one with only faulty chords
+            // keeps its defaults
+            if !bound.is_empty() || chords.is_empty() {
+                custom.insert(action, bound);
+            }
+        }
+
+        let bindings: Vec<(Action, Vec<KeyChord>)> = DEFAULT_BINDINGS
+            .iter()
+            .map(|&(action, _, defaults)| {
+                let chords = custom.remove(&action).unwrap_or_else(|| {
+                    defaults
+                        .iter()
+                        .filter_map(|text| text.parse().ok())
+                        .filter(|chord| !claimed.contains_key(chord))
+                        .collect()
+                });
+                (action, chords)
+            })
+            .collect();
+
+        let by_chord = bindings
+            .iter()
+            .flat_map(|(action, chords)| chords.iter().map(move |chord| (*chord, *action)))
+            .collect();
+
+        (Self { bindings, by_chord }, problems)
+    }
+
+    /// Bind `chords` to an action without a built-in binding, such as a
+    /// plugin command called `name`.
+    ///
+    /// Returns the problems found, as [`from_config`](Self::from_config)
+    /// does; chords that don't parse or are already bound are left out.
+    pub fn bind(&mut self, action: Action, name: &str, chords: &[String]) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut bound = Vec::new();
+        for text in chords {
+            let chord = match text.parse::<KeyChord>() {
+                Ok(chord) => chord,
+                Err(e) => {
+                    problems.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+            match self.by_chord.get(&chord) {
+                Some(&other) if other != action => {
+                    let other = other.name().unwrap_or("another command");
+                    problems.push(format!("'{text}' is bound to both {other} and {name}"));
+                }
+                Some(_) => {}
+                None => {
+                    self.by_chord.insert(chord, action);
+                    bound.push(chord);
+                }
+            }
+        }
+        self.bindings.push((action, bound));
+        problems
+    }
+
+    /// The action bound to a key event.
+    pub fn action(&self, key: KeyEvent) -> Action {
+        self.by_chord
+            .get(&KeyChord::from_event(key))
+            .copied()
+            .unwrap_or(Action::None)
+    }
+
+    /// The chords bound to an action.
+    pub fn chords(&self, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, chords)| chords.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Map a key event to an action using the given keymap.
+pub fn map_key(keymap: &Keymap, key: KeyEvent) -> Action {
+    keymap.action(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn default_keymap_matches_builtin_bindings() {
+        let keymap = Keymap::default();
+        let plain = |code| map_key(&keymap, key(code, KeyModifiers::NONE));
+
+        assert_eq!(plain(KeyCode::Char('q')), Action::Quit);
+        assert_eq!(map_key(&keymap, key(KeyCode::Up, KeyModifiers::ALT)), Action::GoParent);
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('G'), KeyModifiers::SHIFT)), Action::GoLast);
+        assert_eq!(plain(KeyCode::F(5)), Action::Refresh);
+        assert_eq!(plain(KeyCode::Char('7')), Action::QuickJump(7));
+        assert_eq!(plain(KeyCode::Char('y')), Action::None);
+    }
+
+    #[test]
+    fn extra_bindings_skip_taken_chords() {
+        let mut keymap = Keymap::default();
+        let chords = ["alt+1".to_string(), "q".to_string()];
+        let problems = keymap.bind(Action::Plugin(0), "Optimize PNG", &chords);
+
+        assert_eq!(problems, ["'q' is bound to both quit and Optimize PNG"]);
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('1'), KeyModifiers::ALT)), Action::Plugin(0));
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('q'), KeyModifiers::NONE)), Action::Quit);
+        assert_eq!(keymap.chords(Action::Plugin(0)).len(), 1);
+    }
+
+    #[test]
+    fn parse_key_chords() {
+        let chord = |s: &str| s.parse::<KeyChord>().unwrap();
+
+        assert_eq!(chord("ctrl+t"), KeyChord::new(KeyCode::Char('t'), KeyModifiers::CONTROL));
+        assert_eq!(chord("Alt+Left"), KeyChord::new(KeyCode::Left, KeyModifiers::ALT));
+        assert_eq!(chord("shift+g"), chord("G"));
+        assert_eq!(chord("F12"), KeyChord::new(KeyCode::F(12), KeyModifiers::NONE));
+        assert_eq!(chord("ctrl++"), KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("f99".parse::<KeyChord>().is_err());
+        assert!("ctrl+".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn chord_display_round_trips() {
+        for text in ["Ctrl+t", "Alt+←", "G", "F5", "Space", "Shift+Tab"] {
+            let chord = match text {
+                "Alt+←" => "alt+left".parse::<KeyChord>().unwrap(),
+                "Shift+Tab" => "shift+tab".parse().unwrap(),
+                text => text.parse().unwrap(),
+            };
+            assert_eq!(chord.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn config_replaces_action_bindings() {
+        let config = BTreeMap::from([("copy".to_string(), vec!["x".to_string()])]);
+        let (keymap, problems) = Keymap::from_config(&config);
+
+        assert!(problems.is_empty());
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('x'), KeyModifiers::NONE)), Action::Copy);
+        // The old chord is gone, and Run lost 'x' to it
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('C'), KeyModifiers::SHIFT)), Action::None);
+        assert!(keymap.chords(Action::Run).is_empty());
+        // Everything else keeps its defaults
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('M'), KeyModifiers::SHIFT)), Action::Move);
+    }
+
+    #[test]
+    fn config_reports_problems() {
+        let config = BTreeMap::from([
+            ("copy".to_string(), vec!["ctrl+x".to_string()]),
+            ("move".to_string(), vec!["ctrl+x".to_string(), "ctrl+m".to_string()]),
+            ("teleport".to_string(), vec!["t".to_string()]),
+            ("delete".to_string(), vec!["meta+d".to_string()]),
+        ]);
+        let (keymap, problems) = Keymap::from_config(&config);
+
+        assert_eq!(problems.len(), 3);
+        assert!(problems.contains(&"'ctrl+x' is bound to both copy and move".to_string()));
+        assert!(problems.contains(&"Unknown action 'teleport'".to_string()));
+        assert!(problems.iter().any(|p| p.starts_with("delete: invalid key")));
+        // The first claim wins; the valid parts of the others still apply
+        let ctrl = |c| key(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(map_key(&keymap, ctrl('x')), Action::Copy);
+        assert_eq!(map_key(&keymap, ctrl('m')), Action::Move);
+        // Delete had no usable chords, so it keeps its defaults
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('d'), KeyModifiers::NONE)), Action::Delete);
+    }
+
+    #[test]
+    fn config_can_unbind_action() {
+        let config = BTreeMap::from([("quit".to_string(), Vec::new())]);
+        let (keymap, problems) = Keymap::from_config(&config);
+
+        assert!(problems.is_empty());
+        assert!(keymap.chords(Action::Quit).is_empty());
+        assert_eq!(map_key(&keymap, key(KeyCode::Char('q'), KeyModifiers::NONE)), Action::None);
+    }
+
+    #[test]
+    fn action_names_round_trip() {
+        for &(action, name, _) in DEFAULT_BINDINGS {
+            assert_eq!(Action::from_name(name), Some(action));
+            assert_eq!(action.name(), Some(name));
+        }
+        assert_eq!(Action::None.name(), None);
     }
 }
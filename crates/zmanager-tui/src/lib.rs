@@ -7,6 +7,7 @@ pub mod app;
 pub mod crash;
 pub mod event;
 pub mod input;
+pub mod status;
 pub mod terminal;
 pub mod ui;
 
@@ -7,21 +7,29 @@ use std::path::PathBuf;
 use anyhow::Result;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use zmanager_core::{list_directory, DirectoryWatcher};
+use zmanager_core::{
+    create_file, find_template, list_directory_flat_streamed, list_directory_streamed,
+    list_provider_streamed, split_basket_path, Config, DirectoryWatcher, DriveWatcher, Scheduler,
+    SchedulerConfig, LISTING_BATCH_SIZE,
+};
+use zmanager_transfer_win::TransferJournal;
 use zmanager_tui::{
-    app::{App, PendingOperation, ViewMode},
+    app::{App, JobControl, PaneState, PendingOperation, QuickFilter, ViewMode},
     check_for_crash_dumps, clear_crash_dump,
     event::{Event, EventHandler},
     input::{map_key, Action},
     install_panic_hook,
+    status::StatusLevel,
     terminal::Tui,
     ui::{
         file_list::FileList,
-        handle_help_key, handle_properties_key,
+        handle_help_key, handle_properties_key, is_edit_key, is_tab_key, is_take_ownership_key,
         header::Header,
         layout::{AppLayout, Pane},
-        status_bar::StatusBar,
-        DialogResult, HelpScreen, PropertiesPanel, Sidebar, TransfersView,
+        status_bar::{JobsSummary, StatusBar},
+        tab_bar::TabBar,
+        Dialog, DialogResult, DriveBar, DriveDetails, ForegroundProgress, HelpScreen,
+        PropertiesPanel, ReportHistoryResult, Sidebar, Styles, TransfersView,
     },
 };
 
@@ -67,8 +75,23 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
     let mut event_handler = EventHandler::new(200);
     let event_tx = event_handler.sender();
 
-    // Create application state
-    let mut app = App::new(left_path.clone(), right_path.clone(), event_tx.clone());
+    // Create application state, picking up where the last run left off
+    let mut app = App::new(left_path, right_path, event_tx.clone());
+    app.restore_session();
+
+    // Start the scheduler that runs copy and move transfers
+    let (scheduler, jobs) = Scheduler::new(SchedulerConfig {
+        max_concurrent_jobs: app.config.operations.concurrency_for(false),
+        ..Default::default()
+    });
+    tokio::spawn(scheduler.run());
+    app.attach_scheduler(jobs);
+
+    // Offer to resume transfers a crash cut short
+    app.check_unfinished_transfers(&TransferJournal::default_dir());
+
+    let left_path = app.left.nav.current_path().to_path_buf();
+    let right_path = app.right.nav.current_path().to_path_buf();
 
     // Initialize terminal
     let mut tui = Tui::new()?;
@@ -77,59 +100,118 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
     // Start event handler
     event_handler.start();
 
-    // Set up directory watcher for auto-refresh
-    let watcher = DirectoryWatcher::new()?;
-    
-    // Watch both pane directories
-    watcher.watch(&left_path)?;
-    watcher.watch(&right_path)?;
-    
-    // Subscribe to watcher events
+    // Set up directory watcher for auto-refresh; it follows the panes as
+    // they navigate
+    let mut watcher = DirectoryWatcher::new()?;
     let mut watch_rx = watcher.subscribe();
+    watcher.start()?;
+    let mut watched: Vec<PathBuf> = Vec::new();
+
+    // Follow drives being plugged in or removed
+    let mut drive_watcher = DriveWatcher::new();
+    let mut drive_rx = drive_watcher.subscribe();
+    drive_watcher.start();
 
     // Load initial directory contents
     load_directory(&mut app, Pane::Left, &left_path)?;
     load_directory(&mut app, Pane::Right, &right_path)?;
+    sync_watcher(&mut app, &watcher, &mut watched);
 
-    // Main event loop
+    // Main event loop. Redraw only after something that can change what's on
+    // screen; a bare tick leaves the frame as it is.
+    let mut needs_redraw = true;
     loop {
         // Render
-        tui.draw(|frame| {
-            render(&app, frame);
-        })?;
+        if needs_redraw {
+            tui.draw(|frame| {
+                render(&app, frame);
+            })?;
+            needs_redraw = false;
+        }
+        if app.take_bell() {
+            let _ = tui.bell();
+        }
 
         // Handle events from multiple sources using tokio::select
         tokio::select! {
             // Handle TUI events
             event = event_handler.next() => {
+                // Job progress and results arrive as their own events, so
+                // transfers still refresh as soon as they report.
+                needs_redraw = !matches!(event, Some(Event::Tick) | Some(Event::Mouse(_)));
                 match event {
                     Some(Event::Key(key)) => {
                         // Check for modal overlays first (in order of priority)
-                        if app.show_help {
+                        if app.has_viewer() {
+                            handle_viewer_key(&mut app, key);
+                        } else if app.has_text_editor() {
+                            handle_text_editor_key(&mut app, key);
+                        } else if app.show_help {
                             if handle_help_key(key) {
                                 app.close_help();
                             }
+                        } else if app.has_properties_editor() {
+                            handle_properties_editor_key(&mut app, key);
+                        } else if app.has_filter_builder() {
+                            handle_filter_builder_key(&mut app, key);
                         } else if app.has_properties() {
-                            if handle_properties_key(key) {
+                            if app.properties.is_some() && is_tab_key(key) {
+                                app.toggle_security_tab();
+                            } else if app.has_security_tab() && is_take_ownership_key(key) {
+                                app.confirm_take_ownership();
+                            } else if app.properties.is_some() && is_edit_key(key) {
+                                app.open_properties_editor();
+                            } else if handle_properties_key(key) {
                                 app.close_properties();
                             }
+                        } else if app.has_checksums() {
+                            handle_checksum_key(&mut app, key);
+                        } else if app.has_compare() {
+                            handle_compare_key(&mut app, key);
+                        } else if app.has_convert() {
+                            handle_convert_key(&mut app, key);
+                        } else if app.has_open_with() {
+                            handle_open_with_key(&mut app, key);
+                        } else if app.has_copy_menu() {
+                            handle_copy_menu_key(&mut app, key);
+                        } else if app.has_context_menu() {
+                            handle_context_menu_key(&mut app, key)?;
+                        } else if app.has_drive_menu() {
+                            handle_drive_menu_key(&mut app, key);
+                        } else if app.has_drive_details() {
+                            if handle_properties_key(key) {
+                                app.close_drive_details();
+                            }
                         } else if app.has_conflict() {
                             handle_conflict_key(&mut app, key);
                         } else if app.has_dialog() {
                             handle_dialog_key(&mut app, key);
+                        } else if app.has_foreground_job() {
+                            handle_foreground_key(&mut app, key);
+                        } else if app.has_location_bar() {
+                            handle_location_key(&mut app, key);
+                        } else if app.has_history_view() {
+                            handle_history_key(&mut app, key);
                         } else if app.view_mode == ViewMode::Transfers {
                             handle_transfers_key(&mut app, key);
+                        } else if app.is_renaming_inline() {
+                            handle_inline_rename_key(&mut app, key);
+                        } else if app.is_typing_filter() {
+                            handle_filter_key(&mut app, key)?;
                         } else if app.sidebar_visible {
                             handle_sidebar_key(&mut app, key)?;
                         } else {
-                            let action = map_key(key);
+                            let action = map_key(&app.keymap, key);
                             debug!("Key: {:?} -> Action: {:?}", key, action);
                             app.handle_action(action)?;
                         }
                     }
                     Some(Event::Tick) => {
-                        // Clear old status messages after 3 seconds
-                        // (Would need timestamp tracking for proper implementation)
+                        // Retire status messages whose time is up
+                        needs_redraw = app.expire_status();
+                    }
+                    Some(Event::FocusChanged(focused)) => {
+                        app.set_terminal_focused(focused);
                     }
                     Some(Event::Resize(_, _)) => {
                         // Terminal resized, will re-render on next loop
@@ -137,12 +219,62 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
                     Some(Event::DirectoryChanged(path)) => {
                         // Reload directory contents
                         let pane = app.active_pane;
-                        if let Err(e) = load_directory(&mut app, pane, &path) {
-                            error!("Failed to load directory: {}", e);
+                        match load_directory(&mut app, pane, &path) {
+                            Ok(()) => app.record_visit(&path),
+                            Err(e) => error!("Failed to load directory: {}", e),
+                        }
+                    }
+                    Some(Event::ListingBatch(pane, id, entries)) => {
+                        app.apply_listing_batch(pane, id, entries);
+                    }
+                    Some(Event::ListingFinished(pane, id)) => {
+                        app.finish_listing(pane, id);
+                    }
+                    Some(Event::ListingFailed(pane, id, message)) => {
+                        if let Some(path) = app.fail_listing(pane, id, message) {
+                            if let Err(e) = load_directory(&mut app, pane, &path) {
+                                error!("Failed to load directory: {}", e);
+                            }
                         }
                     }
-                    Some(Event::ExecuteDelete(files)) => {
-                        execute_delete(&mut app, files);
+                    Some(Event::DirectorySizeComputed(path, size)) => {
+                        app.apply_directory_size(path, size);
+                    }
+                    Some(Event::DuplicateScanFinished(directory, result)) => {
+                        app.apply_duplicate_scan(directory, result);
+                    }
+                    Some(Event::FolderSizeProgress(path, stats)) => {
+                        app.apply_folder_size_progress(path, stats);
+                    }
+                    Some(Event::FolderSizeFinished(path, result)) => {
+                        app.apply_folder_size_result(path, result);
+                    }
+                    Some(Event::MultiPropertiesProgress(id, totals)) => {
+                        app.apply_multi_properties_progress(id, totals);
+                    }
+                    Some(Event::MultiPropertiesFinished(id, result)) => {
+                        app.apply_multi_properties_result(id, result);
+                    }
+                    Some(Event::ViewerSearchFinished(id, result)) => {
+                        app.apply_viewer_search(id, result);
+                    }
+                    Some(Event::ChecksumProgress(job_id, progress)) => {
+                        app.apply_checksum_progress(job_id, progress);
+                    }
+                    Some(Event::ChecksumFinished(job_id, result)) => {
+                        app.apply_checksum_result(job_id, result);
+                    }
+                    Some(Event::CompareProgress(job_id, progress)) => {
+                        app.apply_compare_progress(job_id, progress);
+                    }
+                    Some(Event::CompareFinished(job_id, result)) => {
+                        app.apply_compare_result(job_id, result);
+                    }
+                    Some(Event::ConvertProgress(job_id, progress)) => {
+                        app.apply_convert_progress(job_id, progress);
+                    }
+                    Some(Event::ConvertFinished(job_id, result)) => {
+                        app.apply_convert_result(job_id, result);
                     }
                     Some(Event::ExecuteRename(old_path, new_path)) => {
                         execute_rename(&mut app, old_path, new_path);
@@ -150,27 +282,52 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
                     Some(Event::ExecuteMkdir(path)) => {
                         execute_mkdir(&mut app, path);
                     }
-                    Some(Event::ExecuteCopy(sources, dest)) => {
-                        execute_copy(&mut app, sources, dest);
-                    }
-                    Some(Event::ExecuteMove(sources, dest)) => {
-                        execute_move(&mut app, sources, dest);
+                    Some(Event::ExecuteNewFile(path)) => {
+                        execute_new_file(&mut app, path);
                     }
                     Some(Event::PauseJob(job_id)) => {
                         debug!("Pausing job {}", job_id);
-                        app.set_status(format!("Paused job {}", job_id), false);
+                        app.control_job(job_id, JobControl::Pause);
                     }
                     Some(Event::ResumeJob(job_id)) => {
                         debug!("Resuming job {}", job_id);
-                        app.set_status(format!("Resumed job {}", job_id), false);
+                        app.control_job(job_id, JobControl::Resume);
                     }
                     Some(Event::CancelJob(job_id)) => {
                         debug!("Cancelling job {}", job_id);
-                        app.set_status(format!("Cancelled job {}", job_id), false);
+                        app.control_job(job_id, JobControl::Cancel);
+                    }
+                    Some(Event::SetSpeedLimit(job_id, limit)) => {
+                        debug!("Setting speed limit of job {} to {:?}", job_id, limit);
+                        app.control_job(job_id, JobControl::SpeedLimit(limit));
                     }
                     Some(Event::JobsUpdated(jobs)) => {
                         app.update_jobs(jobs);
                     }
+                    Some(Event::JobStarted(job_id, operation)) => {
+                        app.apply_job_started(job_id, operation);
+                    }
+                    Some(Event::JobItemFinished(job_id, item)) => {
+                        app.apply_job_item(job_id, item);
+                    }
+                    Some(Event::JobExcluded(job_id, excluded)) => {
+                        app.apply_job_excluded(job_id, excluded);
+                    }
+                    Some(Event::AccessDenied(job_id, manifest)) => {
+                        app.offer_elevation(Some(job_id), manifest);
+                    }
+                    Some(Event::ElevationFinished(job_id, result)) => {
+                        app.apply_elevation_results(job_id, result);
+                    }
+                    Some(Event::ForegroundJobStarted(job_id)) => {
+                        app.apply_foreground_job(job_id);
+                    }
+                    Some(Event::JobCompleted { job_id, success }) => {
+                        app.apply_job_finished(job_id, success);
+                    }
+                    Some(Event::ConflictAsked(query)) => {
+                        app.apply_conflict_query(query);
+                    }
                     Some(Event::RefreshAll) => {
                         let left = app.left.nav.current_path().to_path_buf();
                         let right = app.right.nav.current_path().to_path_buf();
@@ -196,20 +353,33 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
             watch_event = watch_rx.recv() => {
                 if let Ok(event) = watch_event {
                     debug!("File watcher event: {:?}", event);
-                    // Auto-refresh the appropriate pane
-                    let left_dir = app.left.nav.current_path().to_path_buf();
-                    let right_dir = app.right.nav.current_path().to_path_buf();
-                    
-                    if event.directory == left_dir {
-                        if let Err(e) = load_directory(&mut app, Pane::Left, &left_dir) {
-                            warn!("Auto-refresh failed for left pane: {}", e);
+                    // Auto-refresh the panes showing the directory
+                    for pane in app.apply_watch_event(&watcher, &event) {
+                        let path = match pane {
+                            Pane::Left => app.left.nav.current_path().to_path_buf(),
+                            Pane::Right => app.right.nav.current_path().to_path_buf(),
+                        };
+                        if let Err(e) = load_directory(&mut app, pane, &path) {
+                            warn!("Auto-refresh failed for {:?} pane: {}", pane, e);
                         }
+                        needs_redraw = true;
                     }
-                    if event.directory == right_dir {
-                        if let Err(e) = load_directory(&mut app, Pane::Right, &right_dir) {
-                            warn!("Auto-refresh failed for right pane: {}", e);
+                }
+            }
+
+            drive_change = drive_rx.recv() => {
+                if let Ok(change) = drive_change {
+                    debug!("Drive change: {:?}", change);
+                    for pane in app.apply_drive_change(change) {
+                        let path = match pane {
+                            Pane::Left => app.left.nav.current_path().to_path_buf(),
+                            Pane::Right => app.right.nav.current_path().to_path_buf(),
+                        };
+                        if let Err(e) = load_directory(&mut app, pane, &path) {
+                            warn!("Failed to load directory after drive change: {}", e);
                         }
                     }
+                    needs_redraw = true;
                 }
             }
         }
@@ -217,16 +387,42 @@ async fn run(left_path: PathBuf, right_path: PathBuf) -> Result<()> {
         if app.should_quit {
             break;
         }
+        sync_watcher(&mut app, &watcher, &mut watched);
     }
 
     // Cleanup
     tui.exit()?;
+    app.save_session();
+    app.save_visit_history();
+    app.save_baskets();
     Ok(())
 }
 
+/// Point the watcher at the directories the panes show, once they change.
+///
+/// A directory that can't be watched (some network shares don't report
+/// changes) is noted in the status bar; it can still be refreshed by hand.
+fn sync_watcher(app: &mut App, watcher: &DirectoryWatcher, watched: &mut Vec<PathBuf>) {
+    let locations = app.watch_locations();
+    if *watched == locations {
+        return;
+    }
+    for (path, e) in watcher.sync(&locations) {
+        warn!("Cannot watch {:?}: {}", path, e);
+        app.set_status_level(
+            format!("Changes in {} won't show until refreshed (F5)", path.display()),
+            StatusLevel::Warning,
+        );
+    }
+    *watched = locations;
+}
+
 fn load_directory(app: &mut App, pane: Pane, path: &PathBuf) -> Result<()> {
-    let sort = Some(&app.sort);
-    
+    if let Some(name) = split_basket_path(path) {
+        app.list_basket(pane, name);
+        return Ok(());
+    }
+
     // Apply hidden filter if needed
     let mut filter = app.filter.clone();
     if !app.show_hidden {
@@ -236,19 +432,94 @@ fn load_directory(app: &mut App, pane: Pane, path: &PathBuf) -> Result<()> {
         filter.show_hidden = true;
     }
     
-    let filter_ref = if filter.is_default() && app.show_hidden { None } else { Some(&filter) };
-    let listing = list_directory(path, sort, filter_ref)?;
-    app.update_entries(pane, listing.entries);
-    debug!("Loaded {} entries from {:?}", app.active().entries.len(), path);
+    filter.inverted = match pane {
+        Pane::Left => app.left.filter_inverted,
+        Pane::Right => app.right.filter_inverted,
+    };
+
+    let flat_view = match pane {
+        Pane::Left => &app.left.flat_view,
+        Pane::Right => &app.right.flat_view,
+    };
+    let flat = flat_view.as_ref() == Some(path);
+
+    let filter = if filter.is_default() && app.show_hidden { None } else { Some(filter) };
+    let listing = if let Some(provider) = app.directory_provider(path) {
+        // Servers and devices are listed one directory at a time, never flat
+        Ok(list_provider_streamed(provider, path, filter, LISTING_BATCH_SIZE))
+    } else if flat {
+        list_directory_flat_streamed(path, filter, LISTING_BATCH_SIZE)
+    } else {
+        list_directory_streamed(path, filter, LISTING_BATCH_SIZE)
+    };
+    let batches = match listing {
+        Ok(batches) => batches,
+        Err(e) => {
+            // The directory may have been deleted out from under the pane
+            if let Some(ancestor) = app.recover_missing_directory(pane) {
+                return load_directory(app, pane, &ancestor);
+            }
+            return Err(e.into());
+        }
+    };
+    app.stream_listing(pane, path, batches);
+    debug!("Listing {:?}", path);
     Ok(())
 }
 
+/// Pane title shown while a pane displays the complement of the filter.
+const INVERTED_FILTER_TITLE: &str = " Inverted filter ";
+
+/// Pane title while a pane lists the files of all subfolders.
+const FLAT_VIEW_TITLE: &str = " Flat view ";
+
+/// Pane title for a quick filter, with a cursor while it is being typed.
+fn quick_filter_title(filter: &QuickFilter) -> String {
+    let cursor = if filter.editing { "_" } else { "" };
+    format!(" /{}{} ", filter.text, cursor)
+}
+
+/// Build a pane's file list, titled and highlighted for its filters, with
+/// any inline rename drawn over its entry.
+fn pane_file_list<'a>(
+    pane: &'a PaneState,
+    selected: &'a [usize],
+    is_active: bool,
+    filter_title: Option<&'a str>,
+) -> FileList<'a> {
+    let mut list = FileList::new(&pane.entries, selected, is_active);
+    if let Some(editor) = &pane.inline_rename {
+        list = list.renaming(editor);
+    }
+    if let Some(base) = pane.flat_base() {
+        list = list.relative_to(base);
+    }
+    match (&pane.quick_filter, filter_title) {
+        (Some(filter), Some(title)) => list.title(title).highlight(&filter.text),
+        _ if pane.filter_inverted => list.title(INVERTED_FILTER_TITLE),
+        _ if pane.flat_base().is_some() => list.title(FLAT_VIEW_TITLE),
+        _ => list,
+    }
+}
+
 fn render(app: &App, frame: &mut ratatui::Frame) {
     use ratatui::layout::{Constraint, Direction, Layout};
     
-    let layout = AppLayout::new(frame);
+    let show_tabs = app.left.tabs().len() > 1 || app.right.tabs().len() > 1;
+    let show_drives = app.config.appearance.show_drive_bar;
+    let layout = AppLayout::with_bars(frame, show_tabs, show_drives);
     let (base_left_area, right_area) = layout.dual_panes();
 
+    // The file viewer and text editor take the whole screen
+    if let Some(ref viewer) = app.viewer {
+        viewer.render(frame.area(), frame.buffer_mut());
+        return;
+    }
+    if let Some(ref editor) = app.text_editor {
+        editor.render(frame.area(), frame.buffer_mut());
+        return;
+    }
+
     // Check if we're in transfers view mode
     if app.view_mode == ViewMode::Transfers {
         render_transfers_view(app, frame, &layout);
@@ -274,34 +545,89 @@ fn render(app: &App, frame: &mut ratatui::Frame) {
         frame.render_stateful_widget(sidebar, sidebar_rect, &mut sidebar_state);
     }
 
+    if show_drives {
+        for (pane, area, side) in [
+            (&app.left, layout.left_drives, Pane::Left),
+            (&app.right, layout.right_drives, Pane::Right),
+        ] {
+            let is_active = app.active_pane == side;
+            let drive_bar = DriveBar::new(&app.drives, pane.nav.current_path(), is_active);
+            frame.render_widget(drive_bar, area);
+        }
+    }
+
+    // Render tab bars when either pane has more than one tab
+    if show_tabs {
+        for (pane, area, side) in [
+            (&app.left, layout.left_tabs, Pane::Left),
+            (&app.right, layout.right_tabs, Pane::Right),
+        ] {
+            let paths = pane.tabs().iter().map(|tab| tab.nav.current_path()).collect();
+            let tab_bar = TabBar::new(paths, pane.active_tab(), app.active_pane == side);
+            frame.render_widget(tab_bar, area);
+        }
+    }
+
     // Render left pane header
-    let left_header = Header::new(app.left.nav.current_path(), app.active_pane == Pane::Left);
+    let left_header = Header::new(app.left.nav.current_path(), app.active_pane == Pane::Left)
+        .loading(app.left.loading_count())
+        .sort(&app.sort);
     frame.render_widget(left_header, layout.left_header);
 
     // Render right pane header
-    let right_header = Header::new(app.right.nav.current_path(), app.active_pane == Pane::Right);
+    let right_header = Header::new(app.right.nav.current_path(), app.active_pane == Pane::Right)
+        .loading(app.right.loading_count())
+        .sort(&app.sort);
     frame.render_widget(right_header, layout.right_header);
 
     // Render left file list
     let left_selected = app.left.selected_indices();
-    let left_list = FileList::new(&app.left.entries, &left_selected, app.active_pane == Pane::Left);
+    let left_title = app.left.quick_filter.as_ref().map(quick_filter_title);
+    let left_list = pane_file_list(
+        &app.left,
+        &left_selected,
+        app.active_pane == Pane::Left,
+        left_title.as_deref(),
+    );
     let mut left_state = app.left.list_state.clone();
     frame.render_stateful_widget(left_list, left_area, &mut left_state);
 
     // Render right file list
     let right_selected = app.right.selected_indices();
-    let right_list = FileList::new(&app.right.entries, &right_selected, app.active_pane == Pane::Right);
+    let right_title = app.right.quick_filter.as_ref().map(quick_filter_title);
+    let right_list = pane_file_list(
+        &app.right,
+        &right_selected,
+        app.active_pane == Pane::Right,
+        right_title.as_deref(),
+    );
     let mut right_state = app.right.list_state.clone();
     frame.render_stateful_widget(right_list, right_area, &mut right_state);
 
     // Render status bar (may include status message)
     render_status_bar(app, frame, &layout);
 
+    // Render a foreground transfer's progress, under its conflicts
+    if app.has_foreground_job() {
+        let progress = ForegroundProgress::new(app.foreground_job_info());
+        frame.render_widget(progress, frame.area());
+    }
+
     // Render conflict modal on top if present
     if let Some(ref modal) = app.conflict_modal {
         modal.render(frame.area(), frame.buffer_mut());
     }
 
+    // Render location bar on top if open
+    if let Some(ref bar) = app.location_bar {
+        bar.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render directory history on top if open
+    if let Some(ref view) = app.history_view {
+        view.render(frame.area(), frame.buffer_mut());
+    }
+
     // Render dialog on top if present
     if let Some(dialog) = &app.dialog {
         dialog.render(frame.area(), frame.buffer_mut());
@@ -309,15 +635,75 @@ fn render(app: &App, frame: &mut ratatui::Frame) {
 
     // Render help screen on top if shown
     if app.show_help {
-        let help = HelpScreen;
+        let help = HelpScreen::new(&app.keymap);
         frame.render_widget(help, frame.area());
     }
 
     // Render properties panel on top if shown
     if let Some(ref props) = app.properties {
-        let panel = PropertiesPanel::new(props);
+        let panel = PropertiesPanel::new(props)
+            .calculating(app.is_calculating_size(&props.path))
+            .security(app.security.as_ref());
+        frame.render_widget(panel, frame.area());
+    } else if let Some(ref props) = app.multi_properties {
+        let panel =
+            PropertiesPanel::multiple(props).calculating(app.is_calculating_multi_properties());
         frame.render_widget(panel, frame.area());
     }
+
+    // Render checksum dialog on top if open
+    if let Some(ref view) = app.checksums {
+        view.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render compare dialog, or its side-by-side diff, on top if open
+    if let Some(ref view) = app.compare {
+        view.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render convert dialog on top if open
+    if let Some(ref view) = app.convert {
+        view.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render open-with menu on top if open
+    if let Some(ref menu) = app.open_with {
+        menu.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render context menu on top if open
+    if let Some(ref menu) = app.context_menu {
+        menu.render(&app.keymap, frame.area(), frame.buffer_mut());
+    }
+
+    // Render copy menu on top if open
+    if let Some(ref menu) = app.copy_menu {
+        menu.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render drive menu over its pane if open
+    if let Some(ref menu) = app.drive_menu {
+        let area = match menu.pane() {
+            Pane::Left => layout.left_content,
+            Pane::Right => layout.right_content,
+        };
+        menu.render(&app.drives, area, frame.buffer_mut());
+    }
+
+    // Render drive details on top if open
+    if let Some(ref drive) = app.drive_details {
+        frame.render_widget(DriveDetails::new(drive), frame.area());
+    }
+
+    // Render properties editor on top if open
+    if let Some(ref editor) = app.properties_editor {
+        editor.render(frame.area(), frame.buffer_mut());
+    }
+
+    // Render filter builder on top if open
+    if let Some(ref builder) = app.filter_builder {
+        builder.render(frame.area(), frame.buffer_mut());
+    }
 }
 
 fn render_transfers_view(app: &App, frame: &mut ratatui::Frame, layout: &AppLayout) {
@@ -330,28 +716,36 @@ fn render_transfers_view(app: &App, frame: &mut ratatui::Frame, layout: &AppLayo
         height: left_area.height + layout.left_header.height,
     };
     
-    // Create transfers view
-    let transfers = TransfersView::new(&app.jobs, true);
-    let mut list_state = app.jobs_list_state.clone();
-    frame.render_stateful_widget(transfers, full_area, &mut list_state);
+    if let Some(history) = &app.report_history {
+        history.render(full_area, frame.buffer_mut());
+    } else {
+        let transfers = TransfersView::new(&app.jobs, true).details(app.selected_job_details());
+        let mut list_state = app.jobs_list_state.clone();
+        frame.render_stateful_widget(transfers, full_area, &mut list_state);
+    }
     
     // Render status bar
     render_status_bar(app, frame, layout);
 }
 
 fn render_status_bar(app: &App, frame: &mut ratatui::Frame, layout: &AppLayout) {
-    use ratatui::style::{Color, Style};
-    use ratatui::text::Span;
+    use ratatui::style::Style;
+    use ratatui::text::{Line, Span};
     use ratatui::widgets::Paragraph;
     
     // Check for status message first
-    if let Some((ref message, is_error)) = app.status_message {
-        let style = if is_error {
-            Style::default().fg(Color::Red)
-        } else {
-            Style::default().fg(Color::Green)
+    if let Some(message) = app.status_message() {
+        let style = match message.level {
+            StatusLevel::Info => Styles::success(),
+            StatusLevel::Warning => Styles::warning(),
+            StatusLevel::Error => Style::default().fg(Styles::theme().error),
         };
-        let status = Paragraph::new(Span::styled(message.as_str(), style));
+        let mut spans = vec![Span::styled(message.text.as_str(), style)];
+        let waiting = app.queued_status_count();
+        if waiting > 0 {
+            spans.push(Span::styled(format!("  (+{waiting} more)"), Styles::muted()));
+        }
+        let status = Paragraph::new(Line::from(spans));
         frame.render_widget(status, layout.status);
     } else if app.view_mode == ViewMode::Transfers {
         // Show transfers-specific status bar
@@ -370,7 +764,8 @@ fn render_status_bar(app: &App, frame: &mut ratatui::Frame, layout: &AppLayout)
             active.entries.len(),
             active.selected_indices().len(),
             active.selected_size(),
-        );
+        )
+        .jobs(JobsSummary::from_jobs(&app.jobs));
         frame.render_widget(status, layout.status);
     }
 }
@@ -378,7 +773,20 @@ fn render_status_bar(app: &App, frame: &mut ratatui::Frame, layout: &AppLayout)
 // ========== Dialog Handling ==========
 
 fn handle_transfers_key(app: &mut App, key: crossterm::event::KeyEvent) {
-    let action = map_key(key);
+    let action = map_key(&app.keymap, key);
+
+    if let Some(history) = app.report_history.as_mut() {
+        match action {
+            Action::ToggleTransfers => app.toggle_transfers_view(),
+            Action::Quit => app.should_quit = true,
+            _ => match history.handle_key(key) {
+                ReportHistoryResult::Open => {}
+                ReportHistoryResult::Retry => app.retry_failed_items(),
+                ReportHistoryResult::Closed => app.close_report_history(),
+            },
+        }
+        return;
+    }
     
     match action {
         Action::Up => app.jobs_up(),
@@ -387,6 +795,11 @@ fn handle_transfers_key(app: &mut App, key: crossterm::event::KeyEvent) {
         Action::PauseJob => app.pause_selected_job(),
         Action::ResumeJob => app.resume_selected_job(),
         Action::CancelJob => app.cancel_selected_job(),
+        Action::CycleSpeedLimit => app.cycle_selected_speed_limit(),
+        Action::Enter => app.toggle_job_details(),
+        Action::SwitchPane => app.show_report_history(),
+        Action::ExportReport => app.export_selected_job_report(),
+        Action::RetryFailed => app.retry_selected_job(),
         Action::Quit => app.should_quit = true,
         _ => {}
     }
@@ -394,9 +807,25 @@ fn handle_transfers_key(app: &mut App, key: crossterm::event::KeyEvent) {
 
 fn handle_sidebar_key(app: &mut App, key: crossterm::event::KeyEvent) -> anyhow::Result<()> {
     use crossterm::event::KeyCode;
+    use zmanager_tui::ui::SidebarSection;
     
-    let action = map_key(key);
+    let action = map_key(&app.keymap, key);
     
+    // In the folder tree, Right/Left expand and collapse; Enter navigates
+    if app.sidebar_state.section == SidebarSection::Tree {
+        match action {
+            Action::Enter if key.code != KeyCode::Enter => {
+                app.sidebar_expand();
+                return Ok(());
+            }
+            Action::GoParent => {
+                app.sidebar_collapse();
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+
     match action {
         Action::Up => app.sidebar_up(),
         Action::Down => app.sidebar_down(),
@@ -407,6 +836,9 @@ fn handle_sidebar_key(app: &mut App, key: crossterm::event::KeyEvent) -> anyhow:
         // QuickJump still works when sidebar is visible
         Action::QuickJump(n) => app.quick_jump_to_favorite(n),
         // Let other actions through to normal handling (like Properties, Help)
+        Action::Properties if app.sidebar_state.section == SidebarSection::Drives => {
+            app.show_drive_details()
+        }
         Action::Properties => app.show_properties(),
         Action::Help => app.show_help = true,
         // Fallback for tab key to toggle section (not in Action enum)
@@ -420,29 +852,247 @@ fn handle_sidebar_key(app: &mut App, key: crossterm::event::KeyEvent) -> anyhow:
     Ok(())
 }
 
+/// Keys while typing a quick filter: text edits the filter, Enter keeps it
+/// applied, Esc clears it, and the arrow keys still move the cursor.
+fn handle_inline_rename_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::InlineRenameResult;
+
+    let Some(ref mut editor) = app.active_mut().inline_rename else {
+        return;
+    };
+    match editor.handle_key(key) {
+        InlineRenameResult::Editing => {}
+        InlineRenameResult::Commit(name) => app.finish_inline_rename(name),
+        InlineRenameResult::Cancelled => app.cancel_inline_rename(),
+    }
+}
+
+fn handle_filter_key(app: &mut App, key: crossterm::event::KeyEvent) -> anyhow::Result<()> {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    match key.code {
+        KeyCode::Esc => app.clear_quick_filter(),
+        KeyCode::Enter => app.finish_filter_input(),
+        KeyCode::Backspace => app.pop_filter_char(),
+        KeyCode::Up => app.handle_action(Action::Up)?,
+        KeyCode::Down => app.handle_action(Action::Down)?,
+        KeyCode::Char(c)
+            if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            app.push_filter_char(c);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 fn handle_conflict_key(app: &mut App, key: crossterm::event::KeyEvent) {
-    use zmanager_tui::ui::{ConflictResolution, ConflictResult};
-    
+    use zmanager_tui::ui::ConflictResult;
+
     if let Some(ref mut modal) = app.conflict_modal {
-        let result = modal.handle_key(key);
-        match result {
+        match modal.handle_key(key) {
             ConflictResult::Open => {
                 // Modal is still open, nothing to do
             }
             ConflictResult::Resolved(resolution, apply_to_all) => {
-                if resolution == ConflictResolution::Cancel {
-                    app.close_conflict();
-                } else {
-                    debug!("Conflict resolved: {:?}, apply_to_all: {}", resolution, apply_to_all);
-                    // TODO: Apply resolution to transfer engine
-                    app.set_status(format!("Conflict resolution: {:?}", resolution), false);
-                    app.close_conflict();
-                }
+                debug!("Conflict resolved: {:?}, apply_to_all: {}", resolution, apply_to_all);
+                app.answer_conflict(resolution, apply_to_all);
             }
         }
     }
 }
 
+fn handle_location_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::LocationResult;
+
+    let Some(ref mut bar) = app.location_bar else {
+        return;
+    };
+    match bar.handle_key(key) {
+        LocationResult::Open => {}
+        LocationResult::Go(path) => {
+            app.close_location_bar();
+            app.execute_go_to_path(path);
+        }
+        LocationResult::Cancelled => app.close_location_bar(),
+    }
+}
+
+fn handle_properties_editor_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::EditorResult;
+
+    let Some(ref mut editor) = app.properties_editor else {
+        return;
+    };
+    match editor.handle_key(key) {
+        EditorResult::Open => {}
+        EditorResult::Apply(path, changes) => app.apply_property_changes(path, changes),
+        EditorResult::Cancelled => app.close_properties_editor(),
+    }
+}
+
+fn handle_filter_builder_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::FilterBuilderResult;
+
+    let Some(ref mut builder) = app.filter_builder else {
+        return;
+    };
+    match builder.handle_key(key) {
+        FilterBuilderResult::Open => {}
+        FilterBuilderResult::Apply(filter) => app.apply_filter(filter),
+        FilterBuilderResult::Cancelled => app.close_filter_builder(),
+    }
+}
+
+fn handle_foreground_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use crossterm::event::KeyCode;
+
+    match key.code {
+        KeyCode::Char('p') | KeyCode::Char(' ') => app.toggle_foreground_pause(),
+        KeyCode::Char('b') => app.background_foreground_job(),
+        KeyCode::Esc | KeyCode::Char('c') => app.cancel_foreground_job(),
+        _ => {}
+    }
+}
+
+fn handle_checksum_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::ChecksumResult;
+
+    let Some(ref mut view) = app.checksums else {
+        return;
+    };
+    match view.handle_key(key) {
+        ChecksumResult::Open => {}
+        ChecksumResult::Recalculate(algorithm) => app.start_checksum_job(algorithm),
+        ChecksumResult::Closed => app.close_checksums(),
+    }
+}
+
+fn handle_compare_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::CompareResult;
+
+    let Some(ref mut view) = app.compare else {
+        return;
+    };
+    match view.handle_key(key) {
+        CompareResult::Open => {}
+        CompareResult::Recompare(mode) => app.start_compare_job(mode),
+        CompareResult::Closed => app.close_compare(),
+    }
+}
+
+fn handle_convert_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::ConvertResult;
+
+    let Some(ref mut view) = app.convert else {
+        return;
+    };
+    match view.handle_key(key) {
+        ConvertResult::Open => {}
+        ConvertResult::Convert(conversion) => app.start_convert_job(conversion),
+        ConvertResult::Closed => app.close_convert(),
+    }
+}
+
+fn handle_open_with_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::OpenWithResult;
+
+    let Some(ref mut menu) = app.open_with else {
+        return;
+    };
+    match menu.handle_key(key) {
+        OpenWithResult::Open => {}
+        OpenWithResult::Launch(handler) => app.launch_open_with(handler),
+        OpenWithResult::ChooseOther => app.choose_other_app(),
+        OpenWithResult::Closed => app.close_open_with(),
+    }
+}
+
+fn handle_context_menu_key(app: &mut App, key: crossterm::event::KeyEvent) -> anyhow::Result<()> {
+    use zmanager_tui::ui::ContextMenuResult;
+
+    let Some(ref mut menu) = app.context_menu else {
+        return Ok(());
+    };
+    match menu.handle_key(key) {
+        ContextMenuResult::Open => {}
+        ContextMenuResult::Run(action) => app.run_context_action(action)?,
+        ContextMenuResult::Closed => app.close_context_menu(),
+    }
+    Ok(())
+}
+
+fn handle_copy_menu_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::CopyMenuResult;
+
+    let Some(ref mut menu) = app.copy_menu else {
+        return;
+    };
+    match menu.handle_key(key) {
+        CopyMenuResult::Open => {}
+        CopyMenuResult::Copy(format) => app.copy_menu_items(format),
+        CopyMenuResult::Closed => app.close_copy_menu(),
+    }
+}
+
+fn handle_viewer_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::ViewerResult;
+
+    let Some(ref mut viewer) = app.viewer else {
+        return;
+    };
+    match viewer.handle_key(key) {
+        ViewerResult::Open => {}
+        ViewerResult::Search(search, from) => app.start_viewer_search(search, from),
+        ViewerResult::Closed => app.close_viewer(),
+    }
+}
+
+fn handle_text_editor_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::TextEditorResult;
+
+    let Some(ref mut editor) = app.text_editor else {
+        return;
+    };
+    match editor.handle_key(key) {
+        TextEditorResult::Open => {}
+        TextEditorResult::Save => app.save_text_editor(),
+        TextEditorResult::Closed => app.close_text_editor(),
+    }
+}
+
+fn handle_drive_menu_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::DriveMenuResult;
+
+    let Some(ref mut menu) = app.drive_menu else {
+        return;
+    };
+    match menu.handle_key(key, &app.drives) {
+        DriveMenuResult::Open => {}
+        DriveMenuResult::Choose(index) => app.choose_drive(index),
+        DriveMenuResult::Closed => app.close_drive_menu(),
+    }
+}
+
+fn handle_history_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    use zmanager_tui::ui::HistoryResult;
+
+    let Some(ref mut view) = app.history_view else {
+        return;
+    };
+    match view.handle_key(key) {
+        HistoryResult::Open => {}
+        HistoryResult::Go(path) => {
+            // A directory that is gone is handled by the reload, which moves
+            // up to the nearest one that still exists
+            app.close_history_view();
+            app.navigate_to_path(path);
+        }
+        HistoryResult::Forget(path) => app.forget_visit(&path),
+        HistoryResult::Closed => app.close_history_view(),
+    }
+}
+
 fn handle_dialog_key(app: &mut App, key: crossterm::event::KeyEvent) {
     let result = if let Some(ref mut dialog) = app.dialog {
         dialog.handle_key(key)
@@ -455,67 +1105,110 @@ fn handle_dialog_key(app: &mut App, key: crossterm::event::KeyEvent) {
             // Dialog still active, nothing to do
         }
         DialogResult::Cancelled => {
-            app.close_dialog();
+            if matches!(app.pending_operation, Some(PendingOperation::ResumeTransfer)) {
+                app.abandon_unfinished_transfer();
+            } else {
+                app.close_dialog();
+            }
         }
         DialogResult::Confirmed(value) => {
+            // Close first so an operation can open a follow-up dialog
+            let pending = app.pending_operation.take();
+            let background = app.dialog.as_ref().and_then(Dialog::toggle).unwrap_or(true);
+            app.close_dialog();
             // Handle based on pending operation
-            if let Some(op) = app.pending_operation.take() {
+            if let Some(op) = pending {
                 match op {
                     PendingOperation::Delete(files) => {
-                        app.execute_delete(files);
+                        app.execute_delete(files, false);
+                    }
+                    PendingOperation::DeletePermanent(files) => {
+                        app.execute_delete(files, true);
+                    }
+                    PendingOperation::RenameFromClipboard(old_path, new_name) => {
+                        app.execute_rename(old_path, new_name);
+                    }
+                    PendingOperation::BulkRename(paths) => {
+                        app.preview_bulk_rename(paths, value);
                     }
-                    PendingOperation::Rename(old_path) => {
-                        app.execute_rename(old_path, value);
+                    PendingOperation::ConfirmBulkRename(plan) => {
+                        app.execute_bulk_rename(plan);
                     }
                     PendingOperation::MakeDir => {
                         if !value.is_empty() {
                             app.execute_mkdir(value);
                         }
                     }
+                    PendingOperation::NewFile => {
+                        if !value.is_empty() {
+                            app.execute_new_file(value);
+                        }
+                    }
+                    PendingOperation::SelectPattern => {
+                        app.execute_select_pattern(value, true);
+                    }
+                    PendingOperation::DeselectPattern => {
+                        app.execute_select_pattern(value, false);
+                    }
                     PendingOperation::Copy(sources, dest) => {
-                        app.execute_copy(sources, dest);
+                        if let Some(dest) = app.transfer_destination(&sources, dest, &value) {
+                            app.execute_copy(sources, dest, background);
+                        }
                     }
                     PendingOperation::Move(sources, dest) => {
-                        app.execute_move(sources, dest);
+                        if let Some(dest) = app.transfer_destination(&sources, dest, &value) {
+                            app.execute_move(sources, dest, background);
+                        }
+                    }
+                    PendingOperation::OpenSelected(files, navigate_to, skipped) => {
+                        app.execute_open_selected(files, navigate_to, skipped);
+                    }
+                    PendingOperation::RunExecutable(path) => {
+                        app.execute_run(path);
+                    }
+                    PendingOperation::Compress(sources) => {
+                        app.execute_compress(sources, value);
+                    }
+                    PendingOperation::AddToBasket(files) => {
+                        app.execute_add_to_basket(files, value);
+                    }
+                    PendingOperation::ShowBasket => {
+                        app.execute_show_basket(value);
+                    }
+                    PendingOperation::ResumeTransfer => {
+                        app.resume_unfinished_transfer();
+                    }
+                    PendingOperation::RetryElevated(job_id, manifest) => {
+                        app.retry_elevated(job_id, manifest);
+                    }
+                    PendingOperation::TakeOwnership(paths) => {
+                        app.execute_take_ownership(paths);
                     }
                 }
             }
-            app.close_dialog();
         }
         DialogResult::SortSelected(field) => {
             app.apply_sort(field);
             app.close_dialog();
         }
+        DialogResult::SortOptionToggled(option) => {
+            app.toggle_sort_option(option);
+            app.close_dialog();
+        }
     }
 }
 
 // ========== File Operation Execution ==========
 
-fn execute_delete(app: &mut App, files: Vec<PathBuf>) {
-    for file in &files {
-        debug!("Deleting: {:?}", file);
-        if let Err(e) = std::fs::remove_file(file) {
-            // Try as directory
-            if let Err(e2) = std::fs::remove_dir_all(file) {
-                error!("Failed to delete {:?}: {} / {}", file, e, e2);
-                app.show_error("Delete Failed", format!("Could not delete: {}", e2));
-                return;
-            }
-        }
-    }
-    
-    // Refresh the active pane
-    let path = app.active().nav.current_path().to_path_buf();
-    let _ = load_directory(app, app.active_pane, &path);
-    
-    app.show_message("Deleted", format!("{} item(s) deleted", files.len()));
-}
-
 fn execute_rename(app: &mut App, old_path: PathBuf, new_path: PathBuf) {
     debug!("Renaming {:?} to {:?}", old_path, new_path);
     
     if let Err(e) = std::fs::rename(&old_path, &new_path) {
         error!("Failed to rename: {}", e);
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            app.offer_elevated_rename(old_path, new_path);
+            return;
+        }
         app.show_error("Rename Failed", format!("{}", e));
         return;
     }
@@ -539,98 +1232,22 @@ fn execute_mkdir(app: &mut App, path: PathBuf) {
     let _ = load_directory(app, app.active_pane, &parent);
 }
 
-fn execute_copy(app: &mut App, sources: Vec<PathBuf>, destination: PathBuf) {
-    debug!("Copying {} files to {:?}", sources.len(), destination);
-    
-    let mut success_count = 0;
-    for source in &sources {
-        let file_name = source.file_name().unwrap_or_default();
-        let dest_path = destination.join(file_name);
-        
-        if source.is_dir() {
-            // Use recursive copy for directories
-            if let Err(e) = copy_dir_recursive(source, &dest_path) {
-                error!("Failed to copy directory {:?}: {}", source, e);
-                app.show_error("Copy Failed", format!("Could not copy {}: {}", file_name.to_string_lossy(), e));
-                continue;
-            }
-        } else if let Err(e) = std::fs::copy(source, &dest_path) {
-            error!("Failed to copy {:?}: {}", source, e);
-            app.show_error("Copy Failed", format!("Could not copy {}: {}", file_name.to_string_lossy(), e));
-            continue;
-        }
-        success_count += 1;
-    }
-    
-    // Refresh both panes
-    let left = app.left.nav.current_path().to_path_buf();
-    let right = app.right.nav.current_path().to_path_buf();
-    let _ = load_directory(app, Pane::Left, &left);
-    let _ = load_directory(app, Pane::Right, &right);
-    
-    if success_count > 0 {
-        app.show_message("Copied", format!("{} item(s) copied", success_count));
-    }
-}
+fn execute_new_file(app: &mut App, path: PathBuf) {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let template = Config::templates_dir().ok().and_then(|dir| find_template(&dir, &name));
+    debug!("Creating file {:?} from template {:?}", path, template);
 
-fn execute_move(app: &mut App, sources: Vec<PathBuf>, destination: PathBuf) {
-    debug!("Moving {} files to {:?}", sources.len(), destination);
-    
-    let mut success_count = 0;
-    for source in &sources {
-        let file_name = source.file_name().unwrap_or_default();
-        let dest_path = destination.join(file_name);
-        
-        // Try rename first (works if same filesystem)
-        if std::fs::rename(source, &dest_path).is_err() {
-            // Fall back to copy + delete
-            if source.is_dir() {
-                if let Err(e) = copy_dir_recursive(source, &dest_path) {
-                    error!("Failed to move directory {:?}: {}", source, e);
-                    app.show_error("Move Failed", format!("Could not move {}: {}", file_name.to_string_lossy(), e));
-                    continue;
-                }
-                if let Err(e) = std::fs::remove_dir_all(source) {
-                    error!("Failed to remove source directory: {}", e);
-                }
-            } else {
-                if let Err(e) = std::fs::copy(source, &dest_path) {
-                    error!("Failed to move {:?}: {}", source, e);
-                    app.show_error("Move Failed", format!("Could not move {}: {}", file_name.to_string_lossy(), e));
-                    continue;
-                }
-                if let Err(e) = std::fs::remove_file(source) {
-                    error!("Failed to remove source file: {}", e);
-                }
-            }
-        }
-        success_count += 1;
+    if let Err(e) = create_file(&path, template.as_deref()) {
+        error!("Failed to create file: {}", e);
+        app.show_error("Create File Failed", format!("{}", e));
+        return;
     }
-    
-    // Refresh both panes
-    let left = app.left.nav.current_path().to_path_buf();
-    let right = app.right.nav.current_path().to_path_buf();
-    let _ = load_directory(app, Pane::Left, &left);
-    let _ = load_directory(app, Pane::Right, &right);
-    
-    if success_count > 0 {
-        app.show_message("Moved", format!("{} item(s) moved", success_count));
-    }
-}
-
-fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
-    std::fs::create_dir_all(dst)?;
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-        
-        if ty.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            std::fs::copy(&src_path, &dst_path)?;
-        }
+    if let Some(template) = template {
+        let template = template.file_name().unwrap_or_default().to_string_lossy();
+        app.set_status(format!("Created {name} from {template}"), false);
     }
-    Ok(())
+
+    // Refresh the active pane
+    let parent = app.active().nav.current_path().to_path_buf();
+    let _ = load_directory(app, app.active_pane, &parent);
 }
@@ -0,0 +1,199 @@
+//! Status bar messages.
+//!
+//! Messages expire on their own after a time that depends on their level,
+//! and queue up when several arrive at once, so a quick succession of them
+//! (say, a copy finishing right after a delete) doesn't overwrite one
+//! before it could be read.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Most messages kept waiting behind the one shown; the oldest waiting one
+/// is dropped when another arrives.
+const MAX_QUEUED: usize = 4;
+
+/// Least time a message is shown once others are waiting behind it.
+const MIN_DISPLAY: Duration = Duration::from_millis(1200);
+
+/// How serious a status message is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusLevel {
+    /// Something happened as asked.
+    Info,
+    /// Something worth noticing, though nothing failed.
+    Warning,
+    /// Something failed.
+    Error,
+}
+
+impl StatusLevel {
+    /// How long a message of this level stays up when nothing is waiting.
+    pub fn timeout(self) -> Duration {
+        match self {
+            Self::Info => Duration::from_secs(4),
+            Self::Warning => Duration::from_secs(6),
+            Self::Error => Duration::from_secs(10),
+        }
+    }
+}
+
+/// A message in the status bar.
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    /// Text shown.
+    pub text: String,
+    /// How serious the message is.
+    pub level: StatusLevel,
+    /// When the message was first shown; `None` while it's waiting.
+    shown_at: Option<Instant>,
+}
+
+impl StatusMessage {
+    /// Check if the message reports a failure.
+    pub fn is_error(&self) -> bool {
+        self.level == StatusLevel::Error
+    }
+}
+
+/// The status message shown and the ones waiting their turn.
+#[derive(Debug, Clone, Default)]
+pub struct StatusQueue {
+    messages: VecDeque<StatusMessage>,
+}
+
+impl StatusQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The message to show, if any.
+    pub fn current(&self) -> Option<&StatusMessage> {
+        self.messages.front()
+    }
+
+    /// The newest message, shown or waiting.
+    pub fn latest(&self) -> Option<&StatusMessage> {
+        self.messages.back()
+    }
+
+    /// Number of messages waiting behind the shown one.
+    pub fn waiting(&self) -> usize {
+        self.messages.len().saturating_sub(1)
+    }
+
+    /// Queue a message, shown straight away when nothing else is up.
+    ///
+    /// Repeating the newest message only restarts its time.
+    pub fn push(&mut self, text: impl Into<String>, level: StatusLevel, now: Instant) {
+        let text = text.into();
+        let len = self.messages.len();
+        let newest = self.messages.back_mut();
+        if let Some(last) = newest.filter(|last| last.text == text && last.level == level) {
+            if len == 1 {
+                last.shown_at = Some(now);
+            }
+            return;
+        }
+
+        if self.waiting() >= MAX_QUEUED {
+            self.messages.remove(1);
+        }
+        let shown_at = self.messages.is_empty().then_some(now);
+        self.messages.push_back(StatusMessage {
+            text,
+            level,
+            shown_at,
+        });
+    }
+
+    /// Retire the shown message once its time is up, bringing up the next
+    /// one. Returns whether the shown message changed.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let Some(current) = self.messages.front() else {
+            return false;
+        };
+        let shown_at = current.shown_at.unwrap_or(now);
+        let limit = if self.messages.len() > 1 {
+            MIN_DISPLAY.min(current.level.timeout())
+        } else {
+            current.level.timeout()
+        };
+        if now.duration_since(shown_at) < limit {
+            return false;
+        }
+
+        self.messages.pop_front();
+        if let Some(next) = self.messages.front_mut() {
+            next.shown_at = Some(now);
+        }
+        true
+    }
+
+    /// Drop every message.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_expires_after_its_timeout() {
+        let start = Instant::now();
+        let mut queue = StatusQueue::new();
+        queue.push("Copied 3 items", StatusLevel::Info, start);
+        assert_eq!(queue.current().unwrap().text, "Copied 3 items");
+
+        assert!(!queue.tick(start + Duration::from_secs(1)));
+        assert!(queue.tick(start + StatusLevel::Info.timeout()));
+        assert!(queue.current().is_none());
+        assert!(!queue.tick(start + Duration::from_secs(60)));
+
+        // Errors stay up longer
+        queue.push("Access denied", StatusLevel::Error, start);
+        assert!(!queue.tick(start + StatusLevel::Info.timeout()));
+        assert!(queue.current().unwrap().is_error());
+    }
+
+    #[test]
+    fn rapid_messages_wait_their_turn() {
+        let start = Instant::now();
+        let mut queue = StatusQueue::new();
+        queue.push("Deleted a.txt", StatusLevel::Info, start);
+        queue.push("Copy failed", StatusLevel::Error, start);
+        queue.push("Copy failed", StatusLevel::Error, start);
+        assert_eq!(queue.current().unwrap().text, "Deleted a.txt");
+        assert_eq!(queue.waiting(), 1);
+
+        // With a message waiting, the shown one only gets the minimum time
+        assert!(queue.tick(start + MIN_DISPLAY));
+        let current = queue.current().unwrap();
+        assert_eq!(current.text, "Copy failed");
+        assert_eq!(current.level, StatusLevel::Error);
+
+        // The next message's time starts when it comes up
+        assert!(!queue.tick(start + StatusLevel::Info.timeout()));
+        assert!(queue.tick(start + MIN_DISPLAY + StatusLevel::Error.timeout()));
+        assert!(queue.current().is_none());
+    }
+
+    #[test]
+    fn full_queue_drops_oldest_waiting() {
+        let now = Instant::now();
+        let mut queue = StatusQueue::new();
+        for i in 0..=MAX_QUEUED + 1 {
+            queue.push(format!("message {i}"), StatusLevel::Info, now);
+        }
+        assert_eq!(queue.waiting(), MAX_QUEUED);
+        assert_eq!(queue.current().unwrap().text, "message 0");
+
+        queue.tick(now + MIN_DISPLAY);
+        assert_eq!(queue.current().unwrap().text, "message 2");
+
+        queue.clear();
+        assert!(queue.current().is_none());
+    }
+}
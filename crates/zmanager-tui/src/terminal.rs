@@ -3,9 +3,10 @@
 //! This module handles raw terminal mode, alternate screen,
 //! and cleanup on exit.
 
-use std::io::{self, Stdout};
+use std::io::{self, Stdout, Write};
 
 use crossterm::{
+    event::{DisableFocusChange, EnableFocusChange},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -24,10 +25,11 @@ impl Tui {
         Ok(Self { terminal })
     }
 
-    /// Enter TUI mode (raw mode + alternate screen).
+    /// Enter TUI mode (raw mode + alternate screen), with focus changes
+    /// reported.
     pub fn enter(&mut self) -> io::Result<()> {
         enable_raw_mode()?;
-        execute!(io::stdout(), EnterAlternateScreen)?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableFocusChange)?;
         self.terminal.hide_cursor()?;
         self.terminal.clear()?;
         Ok(())
@@ -36,11 +38,18 @@ impl Tui {
     /// Exit TUI mode (restore terminal).
     pub fn exit(&mut self) -> io::Result<()> {
         self.terminal.show_cursor()?;
-        execute!(io::stdout(), LeaveAlternateScreen)?;
+        execute!(io::stdout(), DisableFocusChange, LeaveAlternateScreen)?;
         disable_raw_mode()?;
         Ok(())
     }
 
+    /// Ring the terminal bell.
+    pub fn bell(&mut self) -> io::Result<()> {
+        let stdout = self.terminal.backend_mut();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()
+    }
+
     /// Get mutable reference to the terminal.
     pub fn terminal(&mut self) -> &mut Terminal<CrosstermBackend<Stdout>> {
         &mut self.terminal
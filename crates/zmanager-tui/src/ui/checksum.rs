@@ -0,0 +1,333 @@
+//! Checksum dialog: file hashes, comparison and verification.
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::{hash_matches, normalize_hash, FileHash, HashAlgorithm, Progress};
+
+use super::styles::Styles;
+
+/// Result of a key press in the checksum dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumResult {
+    /// The dialog is still open.
+    Open,
+    /// Hash the files again with another algorithm.
+    Recalculate(HashAlgorithm),
+    /// The dialog was dismissed.
+    Closed,
+}
+
+/// Checksum dialog state.
+///
+/// Shows the checksum of each file once its job finishes. With two files it
+/// says whether they are identical; a checksum typed or pasted into the
+/// verify field is checked against every file.
+#[derive(Debug, Clone)]
+pub struct ChecksumView {
+    /// Files being hashed.
+    paths: Vec<PathBuf>,
+    /// Algorithm of the current job.
+    algorithm: HashAlgorithm,
+    /// Progress of the running job, if any.
+    progress: Option<Progress>,
+    /// Checksums of the finished job.
+    hashes: Option<Vec<FileHash>>,
+    /// Why the job failed.
+    error: Option<String>,
+    /// Expected checksum, as typed or pasted.
+    expected: String,
+}
+
+impl ChecksumView {
+    /// Open the dialog for `paths` while they are hashed with `algorithm`.
+    pub fn new(paths: Vec<PathBuf>, algorithm: HashAlgorithm) -> Self {
+        let progress = Some(Progress::new(paths.len(), None));
+        Self {
+            paths,
+            algorithm,
+            progress,
+            hashes: None,
+            error: None,
+            expected: String::new(),
+        }
+    }
+
+    /// Files being hashed.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Algorithm of the current job.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    /// Clear the results while the files are hashed with `algorithm`.
+    pub fn restart(&mut self, algorithm: HashAlgorithm) {
+        self.algorithm = algorithm;
+        self.progress = Some(Progress::new(self.paths.len(), None));
+        self.hashes = None;
+        self.error = None;
+    }
+
+    /// Check if the files are still being hashed.
+    pub fn is_calculating(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// Show the progress of the running job.
+    pub fn set_progress(&mut self, progress: Progress) {
+        if self.progress.is_some() {
+            self.progress = Some(progress);
+        }
+    }
+
+    /// Show the result of the job.
+    pub fn finish(&mut self, result: Result<Vec<FileHash>, String>) {
+        self.progress = None;
+        match result {
+            Ok(hashes) => self.hashes = Some(hashes),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Checksums of the finished job.
+    pub fn hashes(&self) -> Option<&[FileHash]> {
+        self.hashes.as_deref()
+    }
+
+    /// Whether the two hashed files are identical; None unless exactly two
+    /// files were hashed.
+    pub fn files_match(&self) -> Option<bool> {
+        match self.hashes.as_deref()? {
+            [a, b] => Some(a.matches(b)),
+            _ => None,
+        }
+    }
+
+    /// Files whose checksum is the expected one; None until something is
+    /// typed and the files are hashed.
+    pub fn verified(&self) -> Option<Vec<&Path>> {
+        let hashes = self.hashes.as_ref()?;
+        if normalize_hash(&self.expected).is_empty() {
+            return None;
+        }
+        let matching = hashes
+            .iter()
+            .filter(|h| h.hash.as_ref().is_ok_and(|hash| hash_matches(&self.expected, hash)))
+            .map(|h| h.path.as_path())
+            .collect();
+        Some(matching)
+    }
+
+    /// The algorithm the expected checksum looks like, when it isn't the one
+    /// the files were hashed with.
+    fn suggested_algorithm(&self) -> Option<HashAlgorithm> {
+        HashAlgorithm::detect(&self.expected).filter(|&a| a != self.algorithm)
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> ChecksumResult {
+        let all = HashAlgorithm::ALL;
+        let current = all.iter().position(|&a| a == self.algorithm).unwrap_or(0);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => return ChecksumResult::Closed,
+            (KeyModifiers::NONE, KeyCode::Tab) => {
+                return ChecksumResult::Recalculate(all[(current + 1) % all.len()]);
+            }
+            (_, KeyCode::BackTab) => {
+                return ChecksumResult::Recalculate(all[(current + all.len() - 1) % all.len()]);
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                if let Some(algorithm) = self.suggested_algorithm() {
+                    return ChecksumResult::Recalculate(algorithm);
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace) => {
+                self.expected.pop();
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => self.expected.clear(),
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                self.expected.push(c);
+            }
+            _ => {}
+        }
+        ChecksumResult::Open
+    }
+
+    /// Lines describing the checksum of each file.
+    fn file_lines(&self, limit: usize) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        let shown = if self.paths.len() * 2 > limit {
+            limit.saturating_sub(1) / 2
+        } else {
+            self.paths.len()
+        };
+        for (i, path) in self.paths.iter().take(shown).enumerate() {
+            let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+            lines.push(Line::from(Span::styled(name.into_owned(), Styles::normal())));
+            let hash = match self.hashes.as_ref().and_then(|hashes| hashes.get(i)) {
+                Some(FileHash { hash: Ok(hash), .. }) => Span::styled(hash.clone(), Styles::info()),
+                Some(FileHash { hash: Err(e), .. }) => Span::styled(e.clone(), Styles::error()),
+                None => Span::styled("…", Styles::muted()),
+            };
+            lines.push(Line::from(vec![Span::raw("  "), hash]));
+        }
+        if shown < self.paths.len() {
+            let more = format!("… and {} more", self.paths.len() - shown);
+            lines.push(Line::from(Span::styled(more, Styles::muted())));
+        }
+        lines
+    }
+
+    /// Line with the job's progress, its error, or the comparison result.
+    fn status_line(&self) -> Option<Line<'static>> {
+        if let Some(ref progress) = self.progress {
+            let percent = progress.percentage_int();
+            return Some(Line::from(Span::styled(
+                format!("Hashing… {percent}%"),
+                Styles::muted(),
+            )));
+        }
+        if let Some(ref error) = self.error {
+            return Some(Line::from(Span::styled(error.clone(), Styles::error())));
+        }
+        match self.files_match()? {
+            true => Some(Line::from(Span::styled("✓ Files are identical", Styles::success()))),
+            false => Some(Line::from(Span::styled("✗ Files differ", Styles::error()))),
+        }
+    }
+
+    /// Line with the outcome of checking the expected checksum.
+    fn verify_line(&self) -> Option<Line<'static>> {
+        if let Some(algorithm) = self.suggested_algorithm() {
+            let text = format!("Looks like {algorithm}; press Enter to switch");
+            return Some(Line::from(Span::styled(text, Styles::warning())));
+        }
+        let matching = self.verified()?;
+        let line = match matching.as_slice() {
+            [] => Span::styled("✗ No file matches", Styles::error()),
+            [_] if self.paths.len() == 1 => Span::styled("✓ Checksum matches", Styles::success()),
+            paths => {
+                let names: Vec<String> = paths
+                    .iter()
+                    .map(|p| p.file_name().unwrap_or_default().to_string_lossy().into_owned())
+                    .collect();
+                Span::styled(format!("✓ Matches {}", names.join(", ")), Styles::success())
+            }
+        };
+        Some(Line::from(line))
+    }
+
+    /// Render the dialog centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).clamp(40, 80).min(area.width);
+        let height = area.height.saturating_sub(4).clamp(10, 24).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(format!(" Checksums · {} ", self.algorithm))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // The files take what's left after the status, verify and hint lines
+        let limit = (inner.height as usize).saturating_sub(6);
+        let mut lines = self.file_lines(limit);
+        lines.push(Line::from(""));
+        lines.extend(self.status_line());
+        lines.push(Line::from(vec![
+            Span::styled("Verify: ", Styles::muted()),
+            Span::raw(self.expected.clone()),
+            Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+        ]));
+        lines.extend(self.verify_line());
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Tab algorithm · type or paste a checksum to verify · Esc close")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn hash(path: &str, hash: Result<&str, &str>) -> FileHash {
+        FileHash {
+            path: PathBuf::from(path),
+            size: 3,
+            hash: hash.map(str::to_string).map_err(str::to_string),
+        }
+    }
+
+    fn type_text(view: &mut ChecksumView, text: &str) {
+        for c in text.chars() {
+            view.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn compares_two_files() {
+        let paths = vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")];
+        let mut view = ChecksumView::new(paths, HashAlgorithm::Sha256);
+        assert!(view.is_calculating());
+        assert_eq!(view.files_match(), None);
+
+        view.finish(Ok(vec![hash("a.txt", Ok(ABC_SHA256)), hash("b.txt", Ok(ABC_SHA256))]));
+        assert!(!view.is_calculating());
+        assert_eq!(view.files_match(), Some(true));
+
+        view.restart(HashAlgorithm::Md5);
+        assert!(view.hashes().is_none());
+        view.finish(Ok(vec![hash("a.txt", Ok("01")), hash("b.txt", Err("Access denied"))]));
+        assert_eq!(view.files_match(), Some(false));
+    }
+
+    #[test]
+    fn verifies_a_pasted_checksum() {
+        let mut view = ChecksumView::new(vec![PathBuf::from("a.txt")], HashAlgorithm::Sha256);
+        type_text(&mut view, &ABC_SHA256.to_uppercase());
+        // Nothing to compare with until the file is hashed
+        assert_eq!(view.verified(), None);
+
+        view.finish(Ok(vec![hash("a.txt", Ok(ABC_SHA256))]));
+        assert_eq!(view.verified(), Some(vec![Path::new("a.txt")]));
+
+        view.handle_key(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(view.verified(), Some(vec![]));
+        assert_eq!(view.handle_key(KeyEvent::from(KeyCode::Esc)), ChecksumResult::Closed);
+    }
+
+    #[test]
+    fn switches_algorithms() {
+        let mut view = ChecksumView::new(vec![PathBuf::from("a.txt")], HashAlgorithm::Sha256);
+        let result = view.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(result, ChecksumResult::Recalculate(HashAlgorithm::XxHash));
+        let result = view.handle_key(KeyEvent::from(KeyCode::BackTab));
+        assert_eq!(result, ChecksumResult::Recalculate(HashAlgorithm::Sha1));
+
+        // A pasted MD5 offers to switch to MD5
+        type_text(&mut view, "900150983cd24fb0d6963f7d28e17f72");
+        let result = view.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(result, ChecksumResult::Recalculate(HashAlgorithm::Md5));
+    }
+}
@@ -0,0 +1,487 @@
+//! Compare dialog: whether two files match, and a side-by-side diff.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::{entry::format_size, CompareMode, DiffOp, FileComparison, Progress, TextDiff};
+
+use super::styles::Styles;
+
+/// Result of a key press in the compare dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareResult {
+    /// The dialog is still open.
+    Open,
+    /// Compare the files again in another mode.
+    Recompare(CompareMode),
+    /// The dialog was dismissed.
+    Closed,
+}
+
+/// A row of the side-by-side diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DiffRow {
+    /// Line of the left file, or None where lines were added.
+    left: Option<usize>,
+    /// Line of the right file, or None where lines were removed.
+    right: Option<usize>,
+    /// Whether the row shows a change rather than a line in both files.
+    changed: bool,
+}
+
+/// Compare dialog state.
+///
+/// Shows the outcome once the compare job finishes: whether the files are
+/// identical, where they first differ and by how much. Compared line by
+/// line, the differences can be paged through side by side.
+#[derive(Debug, Clone)]
+pub struct CompareView {
+    /// The file from the left pane.
+    left: PathBuf,
+    /// The file from the right pane.
+    right: PathBuf,
+    /// Mode of the current job.
+    mode: CompareMode,
+    /// Progress of the running job, if any.
+    progress: Option<Progress>,
+    /// Outcome of the finished job.
+    comparison: Option<FileComparison>,
+    /// Why the job failed.
+    error: Option<String>,
+    /// Rows of the side-by-side diff and the first one on screen, while
+    /// it is shown.
+    diff: Option<(Vec<DiffRow>, usize)>,
+    /// Rows the last render of the diff had room for.
+    page: Cell<usize>,
+}
+
+impl CompareView {
+    /// Open the dialog while `left` and `right` are compared in `mode`.
+    pub fn new(left: PathBuf, right: PathBuf, mode: CompareMode) -> Self {
+        Self {
+            left,
+            right,
+            mode,
+            progress: Some(Progress::new(2, None)),
+            comparison: None,
+            error: None,
+            diff: None,
+            page: Cell::new(0),
+        }
+    }
+
+    /// The files being compared, left then right.
+    pub fn paths(&self) -> (&Path, &Path) {
+        (&self.left, &self.right)
+    }
+
+    /// Mode of the current job.
+    pub fn mode(&self) -> CompareMode {
+        self.mode
+    }
+
+    /// Clear the outcome while the files are compared in `mode`.
+    pub fn restart(&mut self, mode: CompareMode) {
+        self.mode = mode;
+        self.progress = Some(Progress::new(2, None));
+        self.comparison = None;
+        self.error = None;
+        self.diff = None;
+    }
+
+    /// Check if the files are still being compared.
+    pub fn is_comparing(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// Show the progress of the running job.
+    pub fn set_progress(&mut self, progress: Progress) {
+        if self.progress.is_some() {
+            self.progress = Some(progress);
+        }
+    }
+
+    /// Show the outcome of the job.
+    pub fn finish(&mut self, result: Result<FileComparison, String>) {
+        self.progress = None;
+        match result {
+            Ok(comparison) => self.comparison = Some(comparison),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Outcome of the finished job.
+    pub fn comparison(&self) -> Option<&FileComparison> {
+        self.comparison.as_ref()
+    }
+
+    /// Check if the side-by-side diff is shown.
+    pub fn is_showing_diff(&self) -> bool {
+        self.diff.is_some()
+    }
+
+    /// Line differences of the finished job, unless the files are identical.
+    fn text_diff(&self) -> Option<&TextDiff> {
+        let comparison = self.comparison.as_ref()?;
+        comparison.text.as_ref().filter(|_| !comparison.is_identical())
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> CompareResult {
+        if self.diff.is_some() {
+            self.handle_diff_key(key);
+            return CompareResult::Open;
+        }
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => CompareResult::Closed,
+            (KeyModifiers::NONE, KeyCode::Tab) => CompareResult::Recompare(self.mode.toggle()),
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                if let Some(diff) = self.text_diff() {
+                    self.diff = Some((side_by_side(&diff.ops), 0));
+                }
+                CompareResult::Open
+            }
+            _ => CompareResult::Open,
+        }
+    }
+
+    fn handle_diff_key(&mut self, key: KeyEvent) {
+        let page = self.page.get().max(1);
+        let Some((ref rows, ref mut top)) = self.diff else {
+            return;
+        };
+        let last = rows.len().saturating_sub(page);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.diff = None;
+                return;
+            }
+            KeyCode::Up | KeyCode::Char('k') => *top = top.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => *top += 1,
+            KeyCode::PageUp => *top = top.saturating_sub(page),
+            KeyCode::PageDown | KeyCode::Char(' ') => *top += page,
+            KeyCode::Home | KeyCode::Char('g') => *top = 0,
+            KeyCode::End | KeyCode::Char('G') => *top = last,
+            KeyCode::Char('n') => {
+                // The start of the next run of changes below the top row
+                let next = (*top + 1..rows.len())
+                    .find(|&i| rows[i].changed && !rows[i - 1].changed);
+                if let Some(next) = next {
+                    *top = next;
+                }
+            }
+            KeyCode::Char('N') => {
+                let previous = (1..*top)
+                    .rev()
+                    .find(|&i| rows[i].changed && !rows[i - 1].changed);
+                *top = previous.unwrap_or(0);
+            }
+            _ => {}
+        }
+        *top = (*top).min(last);
+    }
+
+    /// Line describing a file: its name and size.
+    fn file_line(&self, label: &'static str, path: &Path, size: Option<u64>) -> Line<'static> {
+        let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+        let mut spans = vec![
+            Span::styled(label, Styles::muted()),
+            Span::styled(name.into_owned(), Styles::normal()),
+        ];
+        if let Some(size) = size {
+            spans.push(Span::styled(format!("  {}", format_size(size)), Styles::size()));
+        }
+        Line::from(spans)
+    }
+
+    /// Lines with the job's progress, its error, or the outcome.
+    fn result_lines(&self) -> Vec<Line<'static>> {
+        if let Some(ref progress) = self.progress {
+            let percent = progress.percentage_int();
+            let text = format!("Comparing… {percent}%");
+            return vec![Line::from(Span::styled(text, Styles::muted()))];
+        }
+        if let Some(ref error) = self.error {
+            return vec![Line::from(Span::styled(error.clone(), Styles::error()))];
+        }
+        let Some(ref comparison) = self.comparison else {
+            return Vec::new();
+        };
+        if comparison.is_identical() {
+            return vec![Line::from(Span::styled("✓ Files are identical", Styles::success()))];
+        }
+
+        let mut lines = vec![Line::from(Span::styled("✗ Files differ", Styles::error()))];
+        if let Some(first) = comparison.first_difference {
+            lines.push(Line::from(format!("First difference at byte {first} (0x{first:X})")));
+        }
+        let mut bytes = format!("{} differing bytes", comparison.differing_bytes);
+        if comparison.left_size != comparison.right_size {
+            let extra = comparison.left_size.abs_diff(comparison.right_size);
+            bytes.push_str(&format!(", {extra} more on the longer side"));
+        }
+        lines.push(Line::from(bytes));
+        if let Some(ref text) = comparison.text {
+            let stats = text.stats();
+            lines.push(Line::from(vec![
+                Span::styled(format!("+{} added", stats.added), Styles::success()),
+                Span::raw(" · "),
+                Span::styled(format!("−{} removed", stats.removed), Styles::error()),
+                Span::raw(format!(" · {} unchanged lines", stats.unchanged)),
+            ]));
+        }
+        lines
+    }
+
+    /// Render the dialog centered in `area`, or the diff over all of it.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if let Some((ref rows, top)) = self.diff {
+            self.render_diff(rows, top, area, buf);
+            return;
+        }
+
+        let width = area.width.saturating_sub(4).clamp(40, 72).min(area.width);
+        let height = 12.min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(format!(" Compare · {} ", self.mode))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let sizes = self.comparison.as_ref().map(|c| (c.left_size, c.right_size));
+        let mut lines = vec![
+            self.file_line("Left:  ", &self.left, sizes.map(|s| s.0)),
+            self.file_line("Right: ", &self.right, sizes.map(|s| s.1)),
+            Line::from(""),
+        ];
+        lines.extend(self.result_lines());
+        Paragraph::new(lines).render(inner, buf);
+
+        let mut hint = match self.mode {
+            CompareMode::Bytes => "Tab line-wise",
+            CompareMode::Lines => "Tab byte-wise",
+        }
+        .to_string();
+        if self.text_diff().is_some() {
+            hint.push_str(" · Enter side by side");
+        }
+        hint.push_str(" · Esc close");
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new(hint)
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+
+    fn render_diff(&self, rows: &[DiffRow], top: usize, area: Rect, buf: &mut Buffer) {
+        let Some(diff) = self.text_diff() else {
+            return;
+        };
+        if area.height < 3 {
+            return;
+        }
+        let body = Rect::new(area.x, area.y + 1, area.width, area.height - 2);
+        self.page.set(body.height as usize);
+        Clear.render(area, buf);
+
+        // Each side gets a line number column and half the width
+        let number_width = diff.left.len().max(diff.right.len()).to_string().len();
+        let half = (area.width as usize).saturating_sub(1) / 2;
+        let text_width = half.saturating_sub(number_width + 1);
+        let side = |lines: &[String], index: Option<usize>, style: Style| -> Vec<Span<'static>> {
+            let Some(index) = index else {
+                return vec![Span::raw(" ".repeat(half))];
+            };
+            let line = lines[index].replace('\t', "    ");
+            let text: String = line.chars().take(text_width).collect();
+            let pad = text_width - text.chars().count();
+            vec![
+                Span::styled(format!("{:>number_width$} ", index + 1), Styles::muted()),
+                Span::styled(format!("{text}{}", " ".repeat(pad)), style),
+            ]
+        };
+
+        let header = format!(
+            " {} ↔ {}",
+            self.left.file_name().unwrap_or_default().to_string_lossy(),
+            self.right.file_name().unwrap_or_default().to_string_lossy()
+        );
+        Paragraph::new(header)
+            .style(Styles::status_bar())
+            .render(Rect::new(area.x, area.y, area.width, 1), buf);
+
+        let lines: Vec<Line> = rows
+            .iter()
+            .skip(top)
+            .take(body.height as usize)
+            .map(|row| {
+                let (left_style, right_style) = if row.changed {
+                    (Styles::error(), Styles::success())
+                } else {
+                    (Style::default(), Style::default())
+                };
+                let mut spans = side(&diff.left, row.left, left_style);
+                spans.push(Span::styled("│", Styles::muted()));
+                spans.extend(side(&diff.right, row.right, right_style));
+                Line::from(spans)
+            })
+            .collect();
+        Paragraph::new(lines).render(body, buf);
+
+        let hint = " n/N next/previous change · PgUp/PgDn page · Esc back";
+        Paragraph::new(hint)
+            .style(Styles::hint())
+            .render(Rect::new(area.x, area.bottom() - 1, area.width, 1), buf);
+    }
+}
+
+/// Lay out the steps of a diff as rows, pairing lines removed with the
+/// lines added in their place.
+fn side_by_side(ops: &[DiffOp]) -> Vec<DiffRow> {
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let flush = |rows: &mut Vec<DiffRow>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for i in 0..removed.len().max(added.len()) {
+            rows.push(DiffRow {
+                left: removed.get(i).copied(),
+                right: added.get(i).copied(),
+                changed: true,
+            });
+        }
+        removed.clear();
+        added.clear();
+    };
+    for op in ops {
+        match *op {
+            DiffOp::Same(left, right) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    left: Some(left),
+                    right: Some(right),
+                    changed: false,
+                });
+            }
+            DiffOp::Removed(left) => removed.push(left),
+            DiffOp::Added(right) => added.push(right),
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn differing(left: &str, right: &str) -> FileComparison {
+        FileComparison {
+            left_size: left.len() as u64,
+            right_size: right.len() as u64,
+            first_difference: Some(2),
+            differing_bytes: 1,
+            text: Some(TextDiff::new(lines(left), lines(right))),
+        }
+    }
+
+    fn render(view: &CompareView, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect()
+    }
+
+    fn press(view: &mut CompareView, code: KeyCode) -> CompareResult {
+        view.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn pairs_changed_lines() {
+        let ops = [
+            DiffOp::Same(0, 0),
+            DiffOp::Removed(1),
+            DiffOp::Removed(2),
+            DiffOp::Added(1),
+            DiffOp::Same(3, 2),
+            DiffOp::Added(3),
+        ];
+        let rows: Vec<_> = side_by_side(&ops)
+            .into_iter()
+            .map(|row| (row.left, row.right, row.changed))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (Some(0), Some(0), false),
+                (Some(1), Some(1), true),
+                (Some(2), None, true),
+                (Some(3), Some(2), false),
+                (None, Some(3), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn shows_the_outcome() {
+        let mut view =
+            CompareView::new(PathBuf::from("a.txt"), PathBuf::from("b.txt"), CompareMode::Bytes);
+        assert!(view.is_comparing());
+        assert_eq!(press(&mut view, KeyCode::Tab), CompareResult::Recompare(CompareMode::Lines));
+
+        view.restart(CompareMode::Lines);
+        view.finish(Ok(differing("one\ntwo\nthree", "one\n2\nthree")));
+        let screen = render(&view, 80, 20).join("\n");
+        assert!(screen.contains("Compare · Line-wise"));
+        assert!(screen.contains("First difference at byte 2 (0x2)"));
+        assert!(screen.contains("+1 added · −1 removed · 2 unchanged lines"));
+
+        view.finish(Err("Access denied".to_string()));
+        assert!(render(&view, 80, 20).join("\n").contains("Access denied"));
+        assert_eq!(press(&mut view, KeyCode::Esc), CompareResult::Closed);
+    }
+
+    #[test]
+    fn pages_through_the_diff() {
+        let left: String = (1..=30).map(|i| format!("line {i}\n")).collect();
+        let right = left.replace("line 20\n", "changed\n");
+        let mut view =
+            CompareView::new(PathBuf::from("a.txt"), PathBuf::from("b.txt"), CompareMode::Lines);
+        view.finish(Ok(differing(&left, &right)));
+
+        press(&mut view, KeyCode::Enter);
+        assert!(view.is_showing_diff());
+        let screen = render(&view, 60, 10);
+        assert!(screen[0].contains("a.txt ↔ b.txt"));
+        assert!(screen[1].starts_with(" 1 line 1"));
+
+        press(&mut view, KeyCode::Char('n'));
+        let screen = render(&view, 60, 10);
+        assert!(screen[1].starts_with("20 line 20"));
+        assert!(screen[1].contains("│20 changed"));
+
+        // Esc goes back to the summary before closing
+        assert_eq!(press(&mut view, KeyCode::Esc), CompareResult::Open);
+        assert!(!view.is_showing_diff());
+        assert_eq!(press(&mut view, KeyCode::Esc), CompareResult::Closed);
+    }
+}
@@ -6,11 +6,13 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
+use super::styles::Styles;
+
 /// Conflict information for display.
 #[derive(Debug, Clone)]
 pub struct ConflictInfo {
@@ -176,7 +178,7 @@ impl ConflictModal {
         let title = format!(" File Conflict ({} remaining) ", self.remaining);
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
+            .border_style(Styles::warning())
             .title(title);
 
         let inner = block.inner(modal_area);
@@ -214,15 +216,15 @@ impl ConflictModal {
             Line::from(""),
             Line::from(vec![
                 Span::raw("Source:      "),
-                Span::styled(format_size(self.info.source_size), Style::default().fg(Color::Cyan)),
+                Span::styled(format_size(self.info.source_size), Styles::accent()),
                 Span::raw("  "),
-                Span::styled(&self.info.source_modified, Style::default().fg(Color::Gray)),
+                Span::styled(&self.info.source_modified, Styles::date()),
             ]),
             Line::from(vec![
                 Span::raw("Destination: "),
-                Span::styled(format_size(self.info.dest_size), Style::default().fg(Color::Cyan)),
+                Span::styled(format_size(self.info.dest_size), Styles::accent()),
                 Span::raw("  "),
-                Span::styled(&self.info.dest_modified, Style::default().fg(Color::Gray)),
+                Span::styled(&self.info.dest_modified, Styles::date()),
             ]),
         ];
 
@@ -232,21 +234,21 @@ impl ConflictModal {
     fn render_options(&self, area: Rect, buf: &mut Buffer) {
         // First row: O/S/R
         let row1 = Line::from(vec![
-            Span::styled("[O]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Green)),
+            Span::styled("[O]", Styles::success().add_modifier(Modifier::BOLD)),
             Span::raw("verwrite  "),
-            Span::styled("[S]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow)),
+            Span::styled("[S]", Styles::warning().add_modifier(Modifier::BOLD)),
             Span::raw("kip  "),
-            Span::styled("[R]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Blue)),
+            Span::styled("[R]", Styles::info().add_modifier(Modifier::BOLD)),
             Span::raw("ename  "),
         ]);
 
         // Second row: L/N/C
         let row2 = Line::from(vec![
-            Span::styled("[L]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan)),
+            Span::styled("[L]", Styles::accent().add_modifier(Modifier::BOLD)),
             Span::raw("arger  "),
-            Span::styled("[N]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta)),
+            Span::styled("[N]", Styles::special().add_modifier(Modifier::BOLD)),
             Span::raw("ewer  "),
-            Span::styled("[C]", Style::default().add_modifier(Modifier::BOLD).fg(Color::Red)),
+            Span::styled("[C]", Styles::error()),
             Span::raw("ancel  "),
         ]);
 
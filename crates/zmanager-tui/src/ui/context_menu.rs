@@ -0,0 +1,298 @@
+//! Context menu listing the operations that apply to the selected entries.
+//!
+//! The menu is built from [`CONTEXT_ACTIONS`], a registry of actions with
+//! their labels and the selections they apply to. Choosing a row runs the
+//! action exactly as its key would, so adding an entry to the registry is
+//! all it takes for an operation to show up in the menu. Plugin commands
+//! are added after the built-in operations.
+
+use std::borrow::Cow;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use super::styles::Styles;
+use crate::input::{Action, Keymap};
+
+/// Selections an operation in the context menu applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applies {
+    /// Any non-empty selection.
+    Any,
+    /// A single file or folder.
+    Single,
+    /// A single file.
+    SingleFile,
+    /// One or more items, all of them files.
+    Files,
+}
+
+/// An operation offered in the context menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextAction {
+    /// Action run when the row is chosen.
+    pub action: Action,
+    /// Text of the row.
+    pub label: Cow<'static, str>,
+    /// Selections the row is shown for.
+    pub applies: Applies,
+}
+
+const fn entry(action: Action, label: &'static str, applies: Applies) -> ContextAction {
+    ContextAction {
+        action,
+        label: Cow::Borrowed(label),
+        applies,
+    }
+}
+
+/// Operations of the context menu, in menu order.
+pub const CONTEXT_ACTIONS: &[ContextAction] = &[
+    entry(Action::Open, "Open", Applies::Any),
+    entry(Action::OpenWith, "Open with…", Applies::SingleFile),
+    entry(Action::View, "View", Applies::SingleFile),
+    entry(Action::Edit, "Edit", Applies::SingleFile),
+    entry(Action::RevealInExplorer, "Reveal in Explorer", Applies::Single),
+    entry(Action::Copy, "Copy to other pane", Applies::Any),
+    entry(Action::Move, "Move to other pane", Applies::Any),
+    entry(Action::Rename, "Rename", Applies::Any),
+    entry(Action::Delete, "Delete", Applies::Any),
+    entry(Action::DeletePermanent, "Delete permanently", Applies::Any),
+    entry(Action::CopyPath, "Copy path", Applies::Any),
+    entry(Action::CopyMenu, "Copy as text…", Applies::Any),
+    entry(Action::Compress, "Compress to zip", Applies::Any),
+    entry(Action::Extract, "Extract to other pane", Applies::Any),
+    entry(Action::Checksum, "Checksums", Applies::Files),
+    entry(Action::ConvertText, "Convert encoding or line endings", Applies::Files),
+    entry(Action::CalculateSize, "Calculate size", Applies::Any),
+    entry(Action::AddToBasket, "Add to basket", Applies::Any),
+    entry(Action::TakeOwnership, "Take ownership", Applies::Any),
+    entry(Action::Properties, "Properties", Applies::Any),
+];
+
+/// What the context menu was opened on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContextTargets {
+    /// Files among the targets.
+    pub files: usize,
+    /// Folders among the targets.
+    pub folders: usize,
+}
+
+impl ContextTargets {
+    /// Check whether an operation applies to these targets.
+    pub fn allows(&self, applies: Applies) -> bool {
+        let count = self.files + self.folders;
+        match applies {
+            Applies::Any => count > 0,
+            Applies::Single => count == 1,
+            Applies::SingleFile => self.files == 1 && self.folders == 0,
+            Applies::Files => self.files > 0 && self.folders == 0,
+        }
+    }
+}
+
+/// Result of a key press in the context menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuResult {
+    /// The menu is still open.
+    Open,
+    /// Run the chosen action.
+    Run(Action),
+    /// The menu was dismissed.
+    Closed,
+}
+
+/// Context menu state.
+#[derive(Debug, Clone)]
+pub struct ContextMenu {
+    /// Rows applying to the targets.
+    actions: Vec<ContextAction>,
+    /// Description of the targets for the title.
+    title: String,
+    /// Highlighted row.
+    cursor: usize,
+}
+
+impl ContextMenu {
+    /// List the operations applying to `targets`, described as `title`.
+    pub fn new(targets: ContextTargets, title: impl Into<String>) -> Self {
+        let actions = CONTEXT_ACTIONS
+            .iter()
+            .filter(|entry| targets.allows(entry.applies))
+            .cloned()
+            .collect();
+        Self {
+            actions,
+            title: title.into(),
+            cursor: 0,
+        }
+    }
+
+    /// Add rows for further operations known to apply to the targets, such
+    /// as plugin commands, after the built-in ones.
+    pub fn with_actions(mut self, actions: impl IntoIterator<Item = ContextAction>) -> Self {
+        self.actions.extend(actions);
+        self
+    }
+
+    /// The rows of the menu.
+    pub fn actions(&self) -> &[ContextAction] {
+        &self.actions
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> ContextMenuResult {
+        let last = self.actions.len().saturating_sub(1);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+                return ContextMenuResult::Closed;
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                return match self.actions.get(self.cursor) {
+                    Some(entry) => ContextMenuResult::Run(entry.action),
+                    None => ContextMenuResult::Closed,
+                };
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) => {
+                self.cursor = (self.cursor + 1).min(last);
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) => self.cursor = 0,
+            (_, KeyCode::End | KeyCode::Char('G')) => self.cursor = last,
+            _ => {}
+        }
+        ContextMenuResult::Open
+    }
+
+    /// Render the menu centered in `area`, with each action's first key.
+    pub fn render(&self, keymap: &Keymap, area: Rect, buf: &mut Buffer) {
+        let rows = self.actions.len();
+        let width = 48.min(area.width.saturating_sub(4));
+        let height = (rows as u16 + 3).clamp(5, 24).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // Rows above the hint line, scrolled to keep the cursor in view
+        let visible = inner.height.saturating_sub(1) as usize;
+        let skip = (self.cursor + 1).saturating_sub(visible);
+        let label_width = (inner.width as usize).saturating_sub(12);
+        let lines: Vec<Line> = self
+            .actions
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(i, entry)| {
+                let style = if i == self.cursor {
+                    Styles::cursor()
+                } else {
+                    Styles::normal()
+                };
+                let key = keymap
+                    .chords(entry.action)
+                    .first()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                Line::from(vec![
+                    Span::styled(format!("{:<label_width$}", entry.label.as_ref()), style),
+                    Span::styled(format!(" {key}"), Styles::muted()),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Enter run · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(menu: &mut ContextMenu, code: KeyCode) -> ContextMenuResult {
+        menu.handle_key(KeyEvent::from(code))
+    }
+
+    fn actions(targets: ContextTargets) -> Vec<Action> {
+        let menu = ContextMenu::new(targets, "");
+        menu.actions().iter().map(|entry| entry.action).collect()
+    }
+
+    #[test]
+    fn lists_operations_for_the_targets() {
+        let file = actions(ContextTargets { files: 1, folders: 0 });
+        assert!(file.contains(&Action::OpenWith));
+        assert!(file.contains(&Action::Checksum));
+
+        let folder = actions(ContextTargets { files: 0, folders: 1 });
+        assert!(folder.contains(&Action::RevealInExplorer));
+        assert!(!folder.contains(&Action::OpenWith));
+        assert!(!folder.contains(&Action::Checksum));
+
+        let mixed = actions(ContextTargets { files: 2, folders: 1 });
+        assert!(mixed.contains(&Action::Copy));
+        assert!(!mixed.contains(&Action::RevealInExplorer));
+        assert!(!mixed.contains(&Action::Checksum));
+
+        assert!(actions(ContextTargets::default()).is_empty());
+    }
+
+    #[test]
+    fn every_operation_has_a_default_key() {
+        let keymap = Keymap::default();
+        for entry in CONTEXT_ACTIONS {
+            assert!(!keymap.chords(entry.action).is_empty(), "{:?}", entry.action);
+        }
+    }
+
+    #[test]
+    fn runs_the_chosen_operation() {
+        let mut menu = ContextMenu::new(ContextTargets { files: 1, folders: 0 }, "notes.txt");
+
+        assert_eq!(press(&mut menu, KeyCode::Enter), ContextMenuResult::Run(Action::Open));
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Enter), ContextMenuResult::Run(Action::OpenWith));
+        press(&mut menu, KeyCode::End);
+        assert_eq!(
+            press(&mut menu, KeyCode::Enter),
+            ContextMenuResult::Run(Action::Properties)
+        );
+        assert_eq!(press(&mut menu, KeyCode::Esc), ContextMenuResult::Closed);
+    }
+
+    #[test]
+    fn extra_operations_follow_the_built_in_ones() {
+        let plugin = ContextAction {
+            action: Action::Plugin(0),
+            label: Cow::Owned("Optimize PNG".to_string()),
+            applies: Applies::Files,
+        };
+        let mut menu = ContextMenu::new(ContextTargets { files: 1, folders: 0 }, "a.png")
+            .with_actions([plugin]);
+
+        press(&mut menu, KeyCode::End);
+        assert_eq!(press(&mut menu, KeyCode::Enter), ContextMenuResult::Run(Action::Plugin(0)));
+    }
+}
@@ -0,0 +1,375 @@
+//! Convert dialog: rewrite text files in another encoding or line ending.
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::{ConversionOutcome, FileConversion, LineEnding, Progress, TextConversion};
+
+use super::styles::Styles;
+
+/// Result of a key press in the convert dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertResult {
+    /// The dialog is still open.
+    Open,
+    /// Convert the files.
+    Convert(TextConversion),
+    /// The dialog was dismissed.
+    Closed,
+}
+
+/// Options of the dialog, in the order they are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Encoding,
+    LineEnding,
+    Backup,
+}
+
+impl Field {
+    const ALL: [Self; 3] = [Self::Encoding, Self::LineEnding, Self::Backup];
+}
+
+/// Convert dialog state.
+///
+/// Picks the encoding and line ending to convert to, then shows the job's
+/// progress and what happened to each file.
+#[derive(Debug, Clone)]
+pub struct ConvertView {
+    /// Files to convert.
+    paths: Vec<PathBuf>,
+    /// What to convert them to.
+    conversion: TextConversion,
+    /// Option under the cursor.
+    field: usize,
+    /// Progress of the running job, if any.
+    progress: Option<Progress>,
+    /// Outcome of each file once the job finishes.
+    results: Option<Vec<FileConversion>>,
+    /// Why the job failed, or why it can't start.
+    error: Option<String>,
+}
+
+impl ConvertView {
+    /// Open the dialog for `paths`, converting to UTF-8 by default.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            conversion: TextConversion {
+                encoding: Some(TextConversion::ENCODINGS[0]),
+                line_ending: None,
+                backup: true,
+            },
+            field: 0,
+            progress: None,
+            results: None,
+            error: None,
+        }
+    }
+
+    /// Files to convert.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// What the files are converted to.
+    pub fn conversion(&self) -> TextConversion {
+        self.conversion
+    }
+
+    /// Show the job as started.
+    pub fn start(&mut self) {
+        self.progress = Some(Progress::new(self.paths.len(), None));
+        self.error = None;
+    }
+
+    /// Check if the files are being converted.
+    pub fn is_converting(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// Show the progress of the running job.
+    pub fn set_progress(&mut self, progress: Progress) {
+        if self.progress.is_some() {
+            self.progress = Some(progress);
+        }
+    }
+
+    /// Show the result of the job.
+    pub fn finish(&mut self, result: Result<Vec<FileConversion>, String>) {
+        self.progress = None;
+        match result {
+            Ok(results) => self.results = Some(results),
+            Err(message) => self.error = Some(message),
+        }
+    }
+
+    /// Outcome of each file once the job finishes.
+    pub fn results(&self) -> Option<&[FileConversion]> {
+        self.results.as_deref()
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> ConvertResult {
+        if key.code == KeyCode::Esc {
+            return ConvertResult::Closed;
+        }
+        if self.results.is_some() {
+            // Only the outcome is left to look at
+            return match key.code {
+                KeyCode::Enter | KeyCode::Char('q') => ConvertResult::Closed,
+                _ => ConvertResult::Open,
+            };
+        }
+        if self.progress.is_some() {
+            return ConvertResult::Open;
+        }
+
+        match key.code {
+            KeyCode::Up => self.field = self.field.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => self.field = (self.field + 1) % Field::ALL.len(),
+            KeyCode::Left => self.change(false),
+            KeyCode::Right | KeyCode::Char(' ') => self.change(true),
+            KeyCode::Enter => {
+                if self.conversion.encoding.is_none() && self.conversion.line_ending.is_none() {
+                    self.error = Some("Pick an encoding or a line ending".to_string());
+                } else {
+                    return ConvertResult::Convert(self.conversion);
+                }
+            }
+            _ => {}
+        }
+        ConvertResult::Open
+    }
+
+    /// Step the option under the cursor to its next or previous value.
+    fn change(&mut self, forward: bool) {
+        self.error = None;
+        match Field::ALL[self.field] {
+            Field::Encoding => {
+                let choices: Vec<_> = [None]
+                    .into_iter()
+                    .chain(TextConversion::ENCODINGS.map(Some))
+                    .collect();
+                self.conversion.encoding = step(&choices, self.conversion.encoding, forward);
+            }
+            Field::LineEnding => {
+                let choices = [None, Some(LineEnding::CrLf), Some(LineEnding::Lf)];
+                self.conversion.line_ending = step(&choices, self.conversion.line_ending, forward);
+            }
+            Field::Backup => self.conversion.backup = !self.conversion.backup,
+        }
+    }
+
+    /// Lines with the options, the one under the cursor highlighted.
+    fn option_lines(&self) -> Vec<Line<'static>> {
+        let conversion = &self.conversion;
+        let choosing = self.progress.is_none() && self.results.is_none();
+        Field::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let (label, value) = match field {
+                    Field::Encoding => (
+                        "Encoding:     ",
+                        conversion.encoding.map_or("Keep", |e| e.name()).to_string(),
+                    ),
+                    Field::LineEnding => (
+                        "Line ending:  ",
+                        conversion.line_ending.map_or("Keep", |e| e.name()).to_string(),
+                    ),
+                    Field::Backup => (
+                        "Backup:       ",
+                        if conversion.backup { "Yes, as name.bak" } else { "No" }.to_string(),
+                    ),
+                };
+                let value = if choosing { format!("‹ {value} ›") } else { value };
+                let style = if choosing && i == self.field {
+                    Styles::cursor()
+                } else {
+                    Styles::normal()
+                };
+                Line::from(vec![Span::styled(label, Styles::muted()), Span::styled(value, style)])
+            })
+            .collect()
+    }
+
+    /// Lines with the job's progress, its error, or each file's outcome.
+    fn status_lines(&self, limit: usize) -> Vec<Line<'static>> {
+        if let Some(ref progress) = self.progress {
+            let percent = progress.percentage_int();
+            let text = format!("Converting… {percent}%");
+            return vec![Line::from(Span::styled(text, Styles::muted()))];
+        }
+        if let Some(ref error) = self.error {
+            return vec![Line::from(Span::styled(error.clone(), Styles::error()))];
+        }
+        let Some(ref results) = self.results else {
+            return Vec::new();
+        };
+
+        let converted = results
+            .iter()
+            .filter(|r| matches!(r.outcome, Ok(ConversionOutcome::Converted { .. })))
+            .count();
+        let unchanged = results
+            .iter()
+            .filter(|r| r.outcome == Ok(ConversionOutcome::Unchanged))
+            .count();
+        let failed: Vec<&FileConversion> = results.iter().filter(|r| r.outcome.is_err()).collect();
+        let converted = format!("✓ {converted} converted");
+        let mut summary = vec![Span::styled(converted, Styles::success())];
+        if unchanged > 0 {
+            summary.push(Span::raw(format!(" · {unchanged} already converted")));
+        }
+        if !failed.is_empty() {
+            summary.push(Span::styled(format!(" · ✗ {} failed", failed.len()), Styles::error()));
+        }
+
+        let mut lines = vec![Line::from(summary)];
+        let shown = limit.saturating_sub(1).min(failed.len());
+        for result in &failed[..shown] {
+            let name = result.path.file_name().unwrap_or_default().to_string_lossy();
+            let error = result.outcome.as_ref().err().cloned().unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(format!("{name}: "), Styles::normal()),
+                Span::styled(error, Styles::error()),
+            ]));
+        }
+        if shown < failed.len() {
+            let more = format!("… and {} more", failed.len() - shown);
+            lines.push(Line::from(Span::styled(more, Styles::muted())));
+        }
+        lines
+    }
+
+    /// Render the dialog centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).clamp(40, 72).min(area.width);
+        let height = area.height.saturating_sub(4).clamp(10, 16).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let title = match self.paths.as_slice() {
+            [path] => path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            paths => format!("{} files", paths.len()),
+        };
+        let block = Block::default()
+            .title(format!(" Convert text · {title} "))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // The outcome takes what's left after the options, a gap and the hint
+        let limit = (inner.height as usize).saturating_sub(Field::ALL.len() + 2);
+        let mut lines = self.option_lines();
+        lines.push(Line::from(""));
+        lines.extend(self.status_lines(limit));
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint = if self.results.is_some() {
+            "Enter/Esc close"
+        } else if self.progress.is_some() {
+            "Esc stop"
+        } else {
+            "↑/↓ option · ←/→ change · Enter convert · Esc cancel"
+        };
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new(hint)
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+/// The choice after (or before) `current` in `choices`, wrapping around.
+fn step<T: PartialEq + Copy>(choices: &[T], current: T, forward: bool) -> T {
+    let len = choices.len();
+    let i = choices.iter().position(|&c| c == current).unwrap_or(0);
+    let next = if forward { (i + 1) % len } else { (i + len - 1) % len };
+    choices[next]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zmanager_core::TextEncoding;
+
+    fn press(view: &mut ConvertView, code: KeyCode) -> ConvertResult {
+        view.handle_key(KeyEvent::from(code))
+    }
+
+    fn render(view: &ConvertView) -> String {
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn picks_the_conversion() {
+        let mut view = ConvertView::new(vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+        assert!(render(&view).contains("Convert text · 2 files"));
+
+        press(&mut view, KeyCode::Right);
+        assert_eq!(view.conversion().encoding, Some(TextEncoding::Utf16Le));
+        press(&mut view, KeyCode::Down);
+        press(&mut view, KeyCode::Left);
+        press(&mut view, KeyCode::Down);
+        press(&mut view, KeyCode::Char(' '));
+
+        let expected = TextConversion {
+            encoding: Some(TextEncoding::Utf16Le),
+            line_ending: Some(LineEnding::Lf),
+            backup: false,
+        };
+        assert_eq!(press(&mut view, KeyCode::Enter), ConvertResult::Convert(expected));
+
+        // Keeping both is nothing to do
+        press(&mut view, KeyCode::Up);
+        press(&mut view, KeyCode::Right);
+        press(&mut view, KeyCode::Up);
+        press(&mut view, KeyCode::Right);
+        press(&mut view, KeyCode::Right);
+        assert_eq!(view.conversion().encoding, None);
+        assert_eq!(press(&mut view, KeyCode::Enter), ConvertResult::Open);
+        assert!(render(&view).contains("Pick an encoding or a line ending"));
+    }
+
+    #[test]
+    fn shows_each_files_outcome() {
+        let mut view = ConvertView::new(vec![PathBuf::from("a.txt"), PathBuf::from("b.bin")]);
+        view.start();
+        assert!(view.is_converting());
+        // Options can't change while converting
+        assert_eq!(press(&mut view, KeyCode::Enter), ConvertResult::Open);
+
+        view.finish(Ok(vec![
+            FileConversion {
+                path: PathBuf::from("a.txt"),
+                outcome: Ok(ConversionOutcome::Converted { backup: None }),
+            },
+            FileConversion {
+                path: PathBuf::from("b.bin"),
+                outcome: Err("Not a text file".to_string()),
+            },
+        ]));
+        let screen = render(&view);
+        assert!(screen.contains("✓ 1 converted · ✗ 1 failed"));
+        assert!(screen.contains("b.bin: Not a text file"));
+        assert_eq!(press(&mut view, KeyCode::Enter), ConvertResult::Closed);
+    }
+}
@@ -0,0 +1,159 @@
+//! "Copy as" menu choosing how the selected items go to the clipboard as
+//! text: paths, names, quoted arguments, UNC paths or file contents.
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::CopyFormat;
+
+use super::styles::Styles;
+
+/// Result of a key press in the copy menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMenuResult {
+    /// The menu is still open.
+    Open,
+    /// Copy the items in the chosen format.
+    Copy(CopyFormat),
+    /// The menu was dismissed.
+    Closed,
+}
+
+/// Copy menu state.
+#[derive(Debug, Clone)]
+pub struct CopyMenu {
+    /// Items to copy.
+    paths: Vec<PathBuf>,
+    /// Highlighted row in [`CopyFormat::ALL`].
+    cursor: usize,
+}
+
+impl CopyMenu {
+    /// Offer the ways of copying `paths`.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths, cursor: 0 }
+    }
+
+    /// The items being copied.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> CopyMenuResult {
+        let last = CopyFormat::ALL.len() - 1;
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+                return CopyMenuResult::Closed;
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                return CopyMenuResult::Copy(CopyFormat::ALL[self.cursor]);
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c @ '1'..='9')) => {
+                if let Some(&format) = CopyFormat::ALL.get(c as usize - '1' as usize) {
+                    return CopyMenuResult::Copy(format);
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) => {
+                self.cursor = (self.cursor + 1).min(last);
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) => self.cursor = 0,
+            (_, KeyCode::End | KeyCode::Char('G')) => self.cursor = last,
+            _ => {}
+        }
+        CopyMenuResult::Open
+    }
+
+    /// Render the menu centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let rows = CopyFormat::ALL.len();
+        let width = 44.min(area.width.saturating_sub(4));
+        let height = (rows as u16 + 3).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let title = match self.paths.as_slice() {
+            [path] => {
+                let name = path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+                format!(" Copy {name} as ")
+            }
+            paths => format!(" Copy {} items as ", paths.len()),
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // Contents can only be copied from a single file
+        let single_file = matches!(self.paths.as_slice(), [path] if !path.is_dir());
+        let lines: Vec<Line> = CopyFormat::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, &format)| {
+                let style = if i == self.cursor {
+                    Styles::cursor()
+                } else if format == CopyFormat::Contents && !single_file {
+                    Styles::muted()
+                } else {
+                    Styles::normal()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{} ", i + 1), Styles::muted()),
+                    Span::styled(format.label(), style),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Enter copy · 1-5 pick · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(menu: &mut CopyMenu, code: KeyCode) -> CopyMenuResult {
+        menu.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn picks_a_format() {
+        let mut menu = CopyMenu::new(vec![PathBuf::from("notes.txt")]);
+
+        assert_eq!(press(&mut menu, KeyCode::Enter), CopyMenuResult::Copy(CopyFormat::Paths));
+        press(&mut menu, KeyCode::Down);
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(
+            press(&mut menu, KeyCode::Enter),
+            CopyMenuResult::Copy(CopyFormat::QuotedArgs)
+        );
+        press(&mut menu, KeyCode::End);
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Enter), CopyMenuResult::Copy(CopyFormat::Contents));
+        assert_eq!(press(&mut menu, KeyCode::Char('4')), CopyMenuResult::Copy(CopyFormat::Unc));
+        assert_eq!(press(&mut menu, KeyCode::Char('6')), CopyMenuResult::Open);
+        assert_eq!(press(&mut menu, KeyCode::Esc), CopyMenuResult::Closed);
+    }
+}
@@ -9,6 +9,8 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
+use zmanager_core::SortSpec;
+
 use super::styles::Styles;
 
 /// Dialog type for different operations.
@@ -25,6 +27,8 @@ pub enum DialogKind {
         prompt: String,
         value: String,
         cursor_pos: usize,
+        /// A checkbox below the input (label, checked), flipped with Tab.
+        toggle: Option<(String, bool)>,
     },
     /// Information/error message.
     Message {
@@ -35,6 +39,8 @@ pub enum DialogKind {
     /// Sort selection menu.
     SortMenu {
         current: SortField,
+        /// Sorting in effect, for the state of each [`SortOption`].
+        spec: SortSpec,
     },
 }
 
@@ -84,6 +90,65 @@ impl SortField {
     }
 }
 
+/// Sort settings the sort menu switches besides the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOption {
+    /// Group files by type.
+    GroupByType,
+    /// List directories before files.
+    DirectoriesFirst,
+    /// Compare numbers in names by value.
+    Natural,
+    /// Sort accented letters next to their base letter.
+    LocaleCollation,
+    /// Cycle the secondary sort field.
+    ThenBy,
+}
+
+impl SortOption {
+    /// Get all options.
+    pub fn all() -> &'static [SortOption] {
+        &[
+            SortOption::GroupByType,
+            SortOption::DirectoriesFirst,
+            SortOption::Natural,
+            SortOption::LocaleCollation,
+            SortOption::ThenBy,
+        ]
+    }
+
+    /// Get the hotkey for this option.
+    pub fn hotkey(&self) -> char {
+        match self {
+            SortOption::GroupByType => 'g',
+            SortOption::DirectoriesFirst => 'd',
+            SortOption::Natural => 'u',
+            SortOption::LocaleCollation => 'l',
+            SortOption::ThenBy => 't',
+        }
+    }
+
+    /// Get the label for this option, showing its state in `spec`.
+    pub fn label(&self, spec: &SortSpec) -> String {
+        let check = |on: bool| if on { "[x]" } else { "[ ]" };
+        match self {
+            SortOption::GroupByType => format!("{} Group by type", check(spec.group_by_type)),
+            SortOption::DirectoriesFirst => {
+                format!("{} Folders first", check(spec.directories_first))
+            }
+            SortOption::Natural => format!("{} Numbers by value", check(spec.natural)),
+            SortOption::LocaleCollation => {
+                let on = spec.collation == zmanager_core::Collation::Locale;
+                format!("{} Accents with base letter", check(on))
+            }
+            SortOption::ThenBy => {
+                let field = spec.then_by.map_or("None", |field| field.label());
+                format!("Then by: {field}")
+            }
+        }
+    }
+}
+
 /// Result of dialog interaction.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DialogResult {
@@ -95,6 +160,8 @@ pub enum DialogResult {
     Cancelled,
     /// Sort field selected.
     SortSelected(SortField),
+    /// Sort option switched (or cycled, for the secondary field).
+    SortOptionToggled(SortOption),
 }
 
 /// Active dialog state.
@@ -124,10 +191,27 @@ impl Dialog {
                 prompt: prompt.into(),
                 value,
                 cursor_pos,
+                toggle: None,
             },
         }
     }
 
+    /// Add a checkbox labelled `label` below an input dialog's text.
+    pub fn with_toggle(mut self, label: impl Into<String>, checked: bool) -> Self {
+        if let DialogKind::Input { toggle, .. } = &mut self.kind {
+            *toggle = Some((label.into(), checked));
+        }
+        self
+    }
+
+    /// Whether the checkbox of an input dialog is checked, if it has one.
+    pub fn toggle(&self) -> Option<bool> {
+        match &self.kind {
+            DialogKind::Input { toggle, .. } => toggle.as_ref().map(|(_, checked)| *checked),
+            _ => None,
+        }
+    }
+
     /// Create a message dialog.
     pub fn message(title: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
@@ -151,9 +235,9 @@ impl Dialog {
     }
 
     /// Create a sort menu.
-    pub fn sort_menu(current: SortField) -> Self {
+    pub fn sort_menu(current: SortField, spec: SortSpec) -> Self {
         Self {
-            kind: DialogKind::SortMenu { current },
+            kind: DialogKind::SortMenu { current, spec },
         }
     }
 
@@ -168,14 +252,23 @@ impl Dialog {
                 _ => DialogResult::Open,
             },
             DialogKind::Input {
-                value, cursor_pos, ..
+                value,
+                cursor_pos,
+                toggle,
+                ..
             } => match (key.modifiers, key.code) {
                 (KeyModifiers::NONE, KeyCode::Enter) => DialogResult::Confirmed(value.clone()),
+                (KeyModifiers::NONE, KeyCode::Tab) => {
+                    if let Some((_, checked)) = toggle {
+                        *checked = !*checked;
+                    }
+                    DialogResult::Open
+                }
                 (KeyModifiers::NONE, KeyCode::Esc) => DialogResult::Cancelled,
                 (KeyModifiers::NONE, KeyCode::Backspace) => {
-                    if *cursor_pos > 0 {
-                        value.remove(*cursor_pos - 1);
-                        *cursor_pos -= 1;
+                    if let Some(c) = value[..*cursor_pos].chars().next_back() {
+                        *cursor_pos -= c.len_utf8();
+                        value.remove(*cursor_pos);
                     }
                     DialogResult::Open
                 }
@@ -186,11 +279,15 @@ impl Dialog {
                     DialogResult::Open
                 }
                 (KeyModifiers::NONE, KeyCode::Left) => {
-                    *cursor_pos = cursor_pos.saturating_sub(1);
+                    if let Some(c) = value[..*cursor_pos].chars().next_back() {
+                        *cursor_pos -= c.len_utf8();
+                    }
                     DialogResult::Open
                 }
                 (KeyModifiers::NONE, KeyCode::Right) => {
-                    *cursor_pos = (*cursor_pos + 1).min(value.len());
+                    if let Some(c) = value[*cursor_pos..].chars().next() {
+                        *cursor_pos += c.len_utf8();
+                    }
                     DialogResult::Open
                 }
                 (KeyModifiers::NONE, KeyCode::Home) => {
@@ -203,7 +300,7 @@ impl Dialog {
                 }
                 (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
                     value.insert(*cursor_pos, c);
-                    *cursor_pos += 1;
+                    *cursor_pos += c.len_utf8();
                     DialogResult::Open
                 }
                 _ => DialogResult::Open,
@@ -212,7 +309,7 @@ impl Dialog {
                 KeyCode::Enter | KeyCode::Esc | KeyCode::Char(' ') => DialogResult::Cancelled,
                 _ => DialogResult::Open,
             },
-            DialogKind::SortMenu { current } => match key.code {
+            DialogKind::SortMenu { current, .. } => match key.code {
                 KeyCode::Esc => DialogResult::Cancelled,
                 KeyCode::Char('n') => DialogResult::SortSelected(SortField::Name),
                 KeyCode::Char('s') => DialogResult::SortSelected(SortField::Size),
@@ -220,6 +317,10 @@ impl Dialog {
                 KeyCode::Char('e') => DialogResult::SortSelected(SortField::Extension),
                 KeyCode::Char('k') => DialogResult::SortSelected(SortField::Kind),
                 KeyCode::Enter => DialogResult::SortSelected(*current),
+                KeyCode::Char(c) => SortOption::all()
+                    .iter()
+                    .find(|option| option.hotkey() == c)
+                    .map_or(DialogResult::Open, |option| DialogResult::SortOptionToggled(*option)),
                 _ => DialogResult::Open,
             },
         }
@@ -230,10 +331,18 @@ impl Dialog {
         // Calculate dialog size and position (centered)
         let width = area.width.clamp(30, 60);
         let height = match &self.kind {
-            DialogKind::Confirm { .. } => 5,
-            DialogKind::Input { .. } => 5,
+            // Borders, options and one row per message line
+            DialogKind::Confirm { message, .. } => {
+                (message.lines().count().max(1) as u16 + 4).min(area.height)
+            }
+            // Borders, the input line, the checkbox and a blank one below
+            // the prompt
+            DialogKind::Input { prompt, toggle, .. } => {
+                let toggle = u16::from(toggle.is_some());
+                (prompt.lines().count().max(1) as u16 + 4 + toggle).min(area.height)
+            }
             DialogKind::Message { .. } => 5,
-            DialogKind::SortMenu { .. } => 9,
+            DialogKind::SortMenu { .. } => 14,
         };
 
         let x = area.x + (area.width.saturating_sub(width)) / 2;
@@ -252,8 +361,20 @@ impl Dialog {
                 prompt,
                 value,
                 cursor_pos,
+                toggle,
             } => {
                 self.render_input(dialog_area, buf, title, prompt, value, *cursor_pos);
+                if let Some((label, checked)) = toggle {
+                    let mark = if *checked { "[x]" } else { "[ ]" };
+                    let y = dialog_area.y + dialog_area.height.saturating_sub(2);
+                    let line = Line::from(vec![
+                        Span::raw(format!("{mark} {label} ")),
+                        Span::styled("(Tab)", Styles::hint()),
+                    ]);
+                    let width = dialog_area.width.saturating_sub(2);
+                    let row = Rect::new(dialog_area.x + 1, y, width, 1);
+                    Paragraph::new(line).render(row, buf);
+                }
             }
             DialogKind::Message {
                 title,
@@ -262,8 +383,8 @@ impl Dialog {
             } => {
                 self.render_message(dialog_area, buf, title, message, *is_error);
             }
-            DialogKind::SortMenu { current } => {
-                self.render_sort_menu(dialog_area, buf, *current);
+            DialogKind::SortMenu { current, spec } => {
+                self.render_sort_menu(dialog_area, buf, *current, spec);
             }
         }
     }
@@ -277,7 +398,7 @@ impl Dialog {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+        let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)])
             .split(inner);
 
         // Message
@@ -313,7 +434,8 @@ impl Dialog {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+        let prompt_height = prompt.lines().count().max(1) as u16;
+        let chunks = Layout::vertical([Constraint::Length(prompt_height), Constraint::Length(1)])
             .split(inner);
 
         // Prompt
@@ -322,10 +444,13 @@ impl Dialog {
         // Input field with cursor
         let input_area = chunks[1];
         let display_value = format!("{}_", value);
-        
+        // Scroll long values, like paths, to keep the cursor in view
+        let cursor_pos = value[..cursor_pos].chars().count();
+        let scroll = (cursor_pos + 1).saturating_sub(input_area.width as usize);
+
         // Show cursor by underlining the character at cursor position
         let mut spans = Vec::new();
-        for (i, c) in display_value.chars().enumerate() {
+        for (i, c) in display_value.chars().enumerate().skip(scroll) {
             let style = if i == cursor_pos {
                 Style::default().add_modifier(Modifier::REVERSED)
             } else {
@@ -368,7 +493,7 @@ impl Dialog {
             .render(chunks[1], buf);
     }
 
-    fn render_sort_menu(&self, area: Rect, buf: &mut Buffer, current: SortField) {
+    fn render_sort_menu(&self, area: Rect, buf: &mut Buffer, current: SortField, spec: &SortSpec) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Styles::active_border())
@@ -390,16 +515,38 @@ impl Dialog {
                 Style::default()
             };
 
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::raw(marker),
                 Span::styled(format!("[{}] ", field.hotkey()), Styles::header()),
                 Span::styled(field.label(), style),
-            ]);
+            ];
+            // Picking the current field again reverses this
+            if is_current {
+                spans.push(Span::styled(format!(" {}", spec.order.label()), Styles::accent()));
+            }
+            let line = Line::from(spans);
 
             let y = inner.y + i as u16;
             Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
         }
 
+        // Options apply on top of whichever field is picked
+        let fields = SortField::all().len();
+        for (i, option) in SortOption::all().iter().enumerate() {
+            if fields + i >= inner.height as usize {
+                break;
+            }
+
+            let line = Line::from(vec![
+                Span::raw("  "),
+                Span::styled(format!("[{}] ", option.hotkey()), Styles::header()),
+                Span::raw(option.label(spec)),
+            ]);
+
+            let y = inner.y + (fields + i) as u16;
+            Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+        }
+
         // Hint at bottom
         let hint_y = inner.y + inner.height.saturating_sub(1);
         Paragraph::new("Press key or Esc to cancel")
@@ -440,6 +587,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn input_dialog_edits_around_multibyte_characters() {
+        let mut dialog = Dialog::input("Copy", "Copy to:", "D:\\Café");
+
+        dialog.handle_key(KeyEvent::from(KeyCode::Backspace));
+        dialog.handle_key(KeyEvent::from(KeyCode::Char('è')));
+        dialog.handle_key(KeyEvent::from(KeyCode::Left));
+        dialog.handle_key(KeyEvent::from(KeyCode::Left));
+        dialog.handle_key(KeyEvent::from(KeyCode::Char('ñ')));
+        dialog.handle_key(KeyEvent::from(KeyCode::Right));
+        dialog.handle_key(KeyEvent::from(KeyCode::Delete));
+
+        let DialogKind::Input { value, .. } = &dialog.kind else {
+            panic!("Expected Input dialog");
+        };
+        assert_eq!(value, "D:\\Cañf");
+    }
+
+    #[test]
+    fn input_dialog_toggle_flips_with_tab() {
+        let mut dialog = Dialog::input("Copy", "Copy to:", "D:\\").with_toggle("Background", true);
+        assert_eq!(dialog.toggle(), Some(true));
+
+        dialog.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(dialog.toggle(), Some(false));
+        assert_eq!(Dialog::input("Rename", "New name:", "a").toggle(), None);
+    }
+
     #[test]
     fn input_dialog_backspace() {
         let mut dialog = Dialog::input("Rename", "New name:", "test");
@@ -455,8 +630,12 @@ mod tests {
 
     #[test]
     fn sort_menu_selection() {
-        let mut dialog = Dialog::sort_menu(SortField::Name);
+        let mut dialog = Dialog::sort_menu(SortField::Name, SortSpec::default());
         let result = dialog.handle_key(KeyEvent::from(KeyCode::Char('s')));
         assert_eq!(result, DialogResult::SortSelected(SortField::Size));
+        let result = dialog.handle_key(KeyEvent::from(KeyCode::Char('u')));
+        assert_eq!(result, DialogResult::SortOptionToggled(SortOption::Natural));
+        let result = dialog.handle_key(KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(result, DialogResult::Open);
     }
 }
@@ -0,0 +1,212 @@
+//! Directory tree for the sidebar, loaded one folder at a time.
+
+use std::path::{Path, PathBuf};
+
+use zmanager_core::{list_directory, FilterSpec, SortSpec, ZResult};
+
+/// A folder shown in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// Full path of the folder.
+    pub path: PathBuf,
+    /// Name shown in the tree.
+    pub name: String,
+    /// Nesting level; the root is 0.
+    pub depth: usize,
+    /// Whether the subfolders are listed below the node.
+    pub expanded: bool,
+    /// Whether the folder has subfolders, or `None` until it is read.
+    pub has_children: Option<bool>,
+}
+
+impl TreeNode {
+    fn new(path: PathBuf, name: String, depth: usize) -> Self {
+        Self {
+            path,
+            name,
+            depth,
+            expanded: false,
+            has_children: None,
+        }
+    }
+}
+
+/// Expandable tree of the folders under a root, such as a drive.
+///
+/// Only the visible nodes are kept, in display order: expanding a folder
+/// reads its subfolders and inserts them after it, collapsing removes them
+/// again. Nothing below a collapsed folder is read.
+#[derive(Debug, Clone, Default)]
+pub struct DirTree {
+    nodes: Vec<TreeNode>,
+    show_hidden: bool,
+}
+
+impl DirTree {
+    /// Create a tree of `root`, with its subfolders listed.
+    pub fn new(root: &Path, show_hidden: bool) -> Self {
+        let name = root
+            .file_name()
+            .map_or_else(|| root.display().to_string(), |n| n.to_string_lossy().to_string());
+        let mut tree = Self {
+            nodes: vec![TreeNode::new(root.to_path_buf(), name, 0)],
+            show_hidden,
+        };
+        // An unreadable root still shows, marked as having no subfolders
+        let _ = tree.expand(0);
+        tree
+    }
+
+    /// The folder the tree starts at.
+    pub fn root(&self) -> Option<&Path> {
+        self.nodes.first().map(|node| node.path.as_path())
+    }
+
+    /// Visible nodes in display order.
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    /// The visible node at `index`.
+    pub fn get(&self, index: usize) -> Option<&TreeNode> {
+        self.nodes.get(index)
+    }
+
+    /// Number of visible nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check if the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// List the subfolders of the node at `index` below it.
+    ///
+    /// A folder that can't be read is marked as having no subfolders.
+    pub fn expand(&mut self, index: usize) -> ZResult<()> {
+        let Some(node) = self.nodes.get(index).filter(|node| !node.expanded) else {
+            return Ok(());
+        };
+        let children = match self.subfolders(&node.path, node.depth + 1) {
+            Ok(children) => children,
+            Err(e) => {
+                self.nodes[index].has_children = Some(false);
+                return Err(e);
+            }
+        };
+        let node = &mut self.nodes[index];
+        node.expanded = true;
+        node.has_children = Some(!children.is_empty());
+        self.nodes.splice(index + 1..index + 1, children);
+        Ok(())
+    }
+
+    /// Hide the subfolders of the node at `index`.
+    pub fn collapse(&mut self, index: usize) {
+        let Some(node) = self.nodes.get_mut(index) else {
+            return;
+        };
+        node.expanded = false;
+        let end = self.descendants_end(index);
+        self.nodes.drain(index + 1..end);
+    }
+
+    /// Index of the folder containing the node at `index`.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        let depth = self.nodes.get(index)?.depth;
+        self.nodes[..index].iter().rposition(|node| node.depth < depth)
+    }
+
+    /// Expand the folders leading to `path` and return the index of the
+    /// deepest one shown, or `None` if `path` isn't under the root.
+    pub fn reveal(&mut self, path: &Path) -> Option<usize> {
+        let root = self.root()?.to_path_buf();
+        let relative = path.strip_prefix(&root).ok()?;
+        let mut index = 0;
+        let mut current = root;
+        for component in relative.components() {
+            current.push(component);
+            let _ = self.expand(index);
+            let end = self.descendants_end(index);
+            match self.nodes[index + 1..end].iter().position(|n| n.path == current) {
+                Some(offset) => index += 1 + offset,
+                None => break,
+            }
+        }
+        Some(index)
+    }
+
+    /// Index just past the nodes shown below the node at `index`.
+    fn descendants_end(&self, index: usize) -> usize {
+        let depth = self.nodes[index].depth;
+        self.nodes[index + 1..]
+            .iter()
+            .position(|node| node.depth <= depth)
+            .map_or(self.nodes.len(), |offset| index + 1 + offset)
+    }
+
+    /// Nodes for the subfolders of `path`, sorted by name.
+    fn subfolders(&self, path: &Path, depth: usize) -> ZResult<Vec<TreeNode>> {
+        let filter = FilterSpec::new().with_hidden(self.show_hidden);
+        let listing = list_directory(path, Some(&SortSpec::default()), Some(&filter))?;
+        Ok(listing
+            .entries
+            .into_iter()
+            .filter(|entry| entry.kind.is_directory())
+            .map(|entry| TreeNode::new(entry.path, entry.name, depth))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("alpha").join("inner")).unwrap();
+        std::fs::create_dir(dir.path().join("beta")).unwrap();
+        std::fs::write(dir.path().join("file.txt"), "x").unwrap();
+        dir
+    }
+
+    fn names(tree: &DirTree) -> Vec<(usize, &str)> {
+        tree.nodes().iter().map(|n| (n.depth, n.name.as_str())).collect()
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let dir = setup_tree();
+        let mut tree = DirTree::new(dir.path(), false);
+        let root = tree.get(0).unwrap().name.clone();
+        assert_eq!(names(&tree), vec![(0, root.as_str()), (1, "alpha"), (1, "beta")]);
+
+        tree.expand(1).unwrap();
+        assert_eq!(names(&tree)[1..], [(1, "alpha"), (2, "inner"), (1, "beta")]);
+        assert_eq!(tree.parent(2), Some(1));
+
+        tree.expand(3).unwrap();
+        assert_eq!(tree.get(3).unwrap().has_children, Some(false));
+
+        tree.collapse(1);
+        assert_eq!(names(&tree)[1..], [(1, "alpha"), (1, "beta")]);
+        assert_eq!(tree.get(1).unwrap().has_children, Some(true));
+    }
+
+    #[test]
+    fn reveal_expands_ancestors() {
+        let dir = setup_tree();
+        let mut tree = DirTree::new(dir.path(), false);
+
+        let index = tree.reveal(&dir.path().join("alpha").join("inner")).unwrap();
+        assert_eq!(tree.get(index).unwrap().name, "inner");
+        assert_eq!(tree.get(index).unwrap().depth, 2);
+
+        // A file stops at its folder
+        assert_eq!(tree.reveal(&dir.path().join("file.txt")), Some(0));
+        assert_eq!(tree.reveal(Path::new("/elsewhere")), None);
+    }
+}
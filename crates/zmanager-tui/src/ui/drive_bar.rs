@@ -0,0 +1,124 @@
+//! Row of drive letters shown above a pane.
+
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::Widget,
+};
+use zmanager_core::{drive_for_path, DriveInfo, DriveType};
+
+use super::styles::Styles;
+
+/// Short name of a drive: its letter on Windows (`C`), its mount point
+/// elsewhere, and a device's name.
+pub fn drive_letter(drive: &DriveInfo) -> String {
+    if drive.drive_type == DriveType::Portable {
+        return drive.label.clone();
+    }
+    let path = drive.path.to_string_lossy();
+    let letter = path.trim_end_matches(['\\', '/']).trim_end_matches(':');
+    if letter.is_empty() {
+        path.into_owned()
+    } else {
+        letter.to_string()
+    }
+}
+
+/// Drive bar widget: the drives as letters, the pane's own highlighted.
+pub struct DriveBar<'a> {
+    drives: &'a [DriveInfo],
+    current: &'a Path,
+    is_active: bool,
+}
+
+impl<'a> DriveBar<'a> {
+    /// Create a drive bar for a pane showing `current`.
+    pub fn new(drives: &'a [DriveInfo], current: &'a Path, is_active: bool) -> Self {
+        Self {
+            drives,
+            current,
+            is_active,
+        }
+    }
+}
+
+impl Widget for DriveBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let current = drive_for_path(self.current, self.drives).map(|d| d.path.as_path());
+        let spans: Vec<Span> = self
+            .drives
+            .iter()
+            .map(|drive| {
+                let style = if current == Some(drive.path.as_path()) {
+                    if self.is_active {
+                        Styles::cursor()
+                    } else {
+                        Styles::selected()
+                    }
+                } else if drive.is_ready {
+                    Styles::normal()
+                } else {
+                    Styles::muted()
+                };
+                Span::styled(format!(" {} ", drive_letter(drive)), style)
+            })
+            .collect();
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn drive(path: &str) -> DriveInfo {
+        DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type: DriveType::Fixed,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: None,
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        }
+    }
+
+    #[test]
+    fn letters_of_drives_and_mounts() {
+        assert_eq!(drive_letter(&drive("C:\\")), "C");
+        assert_eq!(drive_letter(&drive("/home")), "/home");
+        assert_eq!(drive_letter(&drive("/")), "/");
+
+        let phone = DriveInfo {
+            label: "Pixel 7".to_string(),
+            drive_type: DriveType::Portable,
+            ..drive("mtp://Pixel 7")
+        };
+        assert_eq!(drive_letter(&phone), "Pixel 7");
+    }
+
+    #[test]
+    fn render_highlights_current_drive() {
+        let drives = [drive("C:\\"), drive("D:\\")];
+        let area = Rect::new(0, 0, 20, 1);
+        let mut buf = Buffer::empty(area);
+
+        DriveBar::new(&drives, Path::new("D:\\Games"), true).render(area, &mut buf);
+
+        let row: String = (0..area.width).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(row.starts_with(" C  D "));
+        assert_eq!(buf[(4, 0)].style().bg, Styles::cursor().bg);
+        assert_ne!(buf[(1, 0)].style().bg, Styles::cursor().bg);
+    }
+}
@@ -0,0 +1,200 @@
+//! Details popup for a drive selected in the sidebar.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Widget},
+};
+use zmanager_core::DriveInfo;
+
+use super::styles::Styles;
+
+/// Share of a drive in use from which its usage bar warns.
+const USAGE_WARNING: f64 = 0.75;
+
+/// Share of a drive in use from which its usage bar shows as critical.
+const USAGE_CRITICAL: f64 = 0.90;
+
+/// Mount points listed before the rest are summarized.
+const MAX_MOUNT_POINTS_SHOWN: usize = 3;
+
+/// Volume details of a drive, with a bar of the space in use.
+pub struct DriveDetails<'a> {
+    drive: &'a DriveInfo,
+}
+
+impl<'a> DriveDetails<'a> {
+    /// Create the popup for `drive`.
+    pub fn new(drive: &'a DriveInfo) -> Self {
+        Self { drive }
+    }
+}
+
+impl Widget for DriveDetails<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let drive = self.drive;
+        let label_style = Styles::muted();
+        let value_style = Style::default();
+
+        let mut lines: Vec<Line> = Vec::new();
+        let mut field = |label: &'static str, value: String| {
+            lines.push(Line::from(vec![
+                Span::styled(label, label_style),
+                Span::styled(value, value_style),
+            ]));
+        };
+
+        field("Type:         ", drive.drive_type.description().to_string());
+        if let Some(ref file_system) = drive.file_system {
+            field("File system:  ", file_system.clone());
+        }
+        if let Some(serial) = drive.serial_display() {
+            field("Serial:       ", serial);
+        }
+        if let Some(cluster) = drive.cluster_size_display() {
+            field("Cluster size: ", cluster);
+        }
+        if let Some(bitlocker) = drive.bitlocker {
+            field("BitLocker:    ", bitlocker.description().to_string());
+        }
+        if !drive.is_ready {
+            field("Status:       ", "Not ready".to_string());
+        }
+
+        let mounts = &drive.mount_points;
+        for (i, mount) in mounts.iter().take(MAX_MOUNT_POINTS_SHOWN).enumerate() {
+            let label = if i == 0 { "Mounted at:   " } else { "              " };
+            field(label, mount.display().to_string());
+        }
+        if mounts.len() > MAX_MOUNT_POINTS_SHOWN {
+            field(
+                "              ",
+                format!("and {} more", mounts.len() - MAX_MOUNT_POINTS_SHOWN),
+            );
+        }
+
+        let usage = drive.usage_percent();
+        if usage.is_some() {
+            lines.push(Line::from(""));
+            // The usage bar goes on this line
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("Used ", label_style),
+                Span::styled(drive.used_space_display(), Styles::size()),
+                Span::styled(" · Free ", label_style),
+                Span::styled(drive.free_space_display(), Styles::size()),
+                Span::styled(" · Total ", label_style),
+                Span::styled(drive.total_space_display(), Styles::size()),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Press any key to close", Styles::hint())));
+
+        let width = 56.min(area.width.saturating_sub(4));
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", drive.display_name()))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let bar_row = lines.len().saturating_sub(4) as u16;
+        Paragraph::new(lines).render(inner, buf);
+
+        if let Some(usage) = usage.filter(|_| bar_row < inner.height) {
+            let bar = Rect::new(inner.x, inner.y + bar_row, inner.width, 1);
+            Gauge::default()
+                .ratio(usage.clamp(0.0, 1.0))
+                .label(format!("{:.0}% used", usage * 100.0))
+                .gauge_style(Style::default().fg(usage_color(usage)))
+                .render(bar, buf);
+        }
+    }
+}
+
+/// Color of a usage bar: calm while there is room, then warning, then
+/// critical as the drive fills up.
+fn usage_color(usage: f64) -> Color {
+    let theme = Styles::theme();
+    if usage >= USAGE_CRITICAL {
+        theme.error
+    } else if usage >= USAGE_WARNING {
+        theme.warning
+    } else {
+        theme.success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use zmanager_core::{BitLockerStatus, DriveType};
+
+    fn render(drive: &DriveInfo) -> (String, Buffer) {
+        let area = Rect::new(0, 0, 64, 24);
+        let mut buf = Buffer::empty(area);
+        DriveDetails::new(drive).render(area, &mut buf);
+        let text = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        (text, buf)
+    }
+
+    #[test]
+    fn details_show_volume_info_and_usage() {
+        let drive = DriveInfo {
+            path: PathBuf::from("C:\\"),
+            label: "Windows".to_string(),
+            drive_type: DriveType::Fixed,
+            file_system: Some("NTFS".to_string()),
+            total_bytes: Some(1000 * 1024 * 1024),
+            free_bytes: Some(50 * 1024 * 1024),
+            is_ready: true,
+            volume_serial: Some(0x1A2B_3C4D),
+            mount_points: vec![PathBuf::from("C:\\"), PathBuf::from("D:\\Mount\\System")],
+            bitlocker: Some(BitLockerStatus::On),
+            cluster_size: Some(4096),
+        };
+
+        let (text, buf) = render(&drive);
+        assert!(text.contains(" Windows (C:) "));
+        assert!(text.contains("File system:  NTFS"));
+        assert!(text.contains("Serial:       1A2B-3C4D"));
+        assert!(text.contains("Cluster size: 4.00 KB"));
+        assert!(text.contains("BitLocker:    On"));
+        assert!(text.contains("              D:\\Mount\\System"));
+        assert!(text.contains("95% used"));
+        assert!(text.contains("Used 950.00 MB · Free 50.00 MB · Total 1000.00 MB"));
+
+        // Nearly full, so the bar shows as critical
+        let row = text.lines().position(|line| line.contains("95% used")).unwrap();
+        let bar_cell = buf
+            .content()
+            .iter()
+            .skip(row * 64)
+            .take(64)
+            .find(|cell| cell.symbol() == "█")
+            .unwrap();
+        assert_eq!(bar_cell.fg, Styles::theme().error);
+    }
+
+    #[test]
+    fn usage_thresholds() {
+        let theme = Styles::theme();
+        assert_eq!(usage_color(0.5), theme.success);
+        assert_eq!(usage_color(0.8), theme.warning);
+        assert_eq!(usage_color(0.95), theme.error);
+    }
+}
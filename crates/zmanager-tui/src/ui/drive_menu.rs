@@ -0,0 +1,198 @@
+//! Drive menu for switching a pane to another drive.
+
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::{drive_for_path, DriveInfo};
+
+use super::drive_bar::drive_letter;
+use super::layout::Pane;
+use super::styles::Styles;
+
+/// Result of a key press in the drive menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveMenuResult {
+    /// The menu is still open.
+    Open,
+    /// Switch the pane to the drive at this index of the drive list.
+    Choose(usize),
+    /// The menu was dismissed.
+    Closed,
+}
+
+/// Drive menu state for one pane.
+///
+/// The drives themselves are passed in on each call, so the menu follows
+/// drives being added or removed while it is open.
+#[derive(Debug, Clone)]
+pub struct DriveMenu {
+    /// Pane switching drives.
+    pane: Pane,
+    /// Highlighted row.
+    cursor: usize,
+}
+
+impl DriveMenu {
+    /// Open the menu for `pane`, highlighting the drive of `current`.
+    pub fn new(pane: Pane, drives: &[DriveInfo], current: &Path) -> Self {
+        let cursor = drive_for_path(current, drives)
+            .and_then(|found| drives.iter().position(|d| d.path == found.path))
+            .unwrap_or(0);
+        Self { pane, cursor }
+    }
+
+    /// The pane switching drives.
+    pub fn pane(&self) -> Pane {
+        self.pane
+    }
+
+    /// Handle a key event. Typing a drive letter picks that drive.
+    pub fn handle_key(&mut self, key: KeyEvent, drives: &[DriveInfo]) -> DriveMenuResult {
+        let last = drives.len().saturating_sub(1);
+        self.cursor = self.cursor.min(last);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => return DriveMenuResult::Closed,
+            (KeyModifiers::NONE, KeyCode::Enter) if !drives.is_empty() => {
+                return DriveMenuResult::Choose(self.cursor);
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                let letter = c.to_ascii_uppercase().to_string();
+                if let Some(index) = drives.iter().position(|d| drive_letter(d) == letter) {
+                    return DriveMenuResult::Choose(index);
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Up) => self.cursor = self.cursor.saturating_sub(1),
+            (KeyModifiers::NONE, KeyCode::Down) => self.cursor = (self.cursor + 1).min(last),
+            (KeyModifiers::NONE, KeyCode::Home) => self.cursor = 0,
+            (KeyModifiers::NONE, KeyCode::End) => self.cursor = last,
+            _ => {}
+        }
+        DriveMenuResult::Open
+    }
+
+    /// Render the menu centered in `area`, the pane switching drives.
+    pub fn render(&self, drives: &[DriveInfo], area: Rect, buf: &mut Buffer) {
+        let width = 44.min(area.width.saturating_sub(2));
+        let height = (drives.len().max(1) as u16 + 3).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let title = match self.pane {
+            Pane::Left => " Left pane drive ",
+            Pane::Right => " Right pane drive ",
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // Rows above the hint line, scrolled to keep the cursor in view
+        let visible = inner.height.saturating_sub(1) as usize;
+        let skip = (self.cursor + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = Vec::new();
+        if drives.is_empty() {
+            lines.push(Line::from(Span::styled("No drives", Styles::muted())));
+        }
+        for (i, drive) in drives.iter().enumerate().skip(skip).take(visible) {
+            let free = match drive.free_bytes {
+                Some(_) => format!("{} free", drive.free_space_display()),
+                None => String::new(),
+            };
+            let style = if i == self.cursor {
+                Styles::cursor()
+            } else if drive.is_ready {
+                Styles::normal()
+            } else {
+                Styles::muted()
+            };
+            let name = drive.display_name();
+            let gap = (inner.width as usize).saturating_sub(name.chars().count() + free.len() + 1);
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}{}", name, " ".repeat(gap)), style),
+                Span::styled(format!("{} ", free), Styles::size()),
+            ]));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Enter open · letter pick · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use zmanager_core::DriveType;
+
+    fn drive(path: &str) -> DriveInfo {
+        DriveInfo {
+            path: PathBuf::from(path),
+            label: String::new(),
+            drive_type: DriveType::Fixed,
+            file_system: None,
+            total_bytes: None,
+            free_bytes: Some(1024),
+            is_ready: true,
+            volume_serial: None,
+            mount_points: Vec::new(),
+            bitlocker: None,
+            cluster_size: None,
+        }
+    }
+
+    #[test]
+    fn starts_on_current_drive_and_moves() {
+        let drives = [drive("C:\\"), drive("D:\\"), drive("E:\\")];
+        let mut menu = DriveMenu::new(Pane::Right, &drives, Path::new("d:\\Games"));
+
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Enter), &drives),
+            DriveMenuResult::Choose(1)
+        );
+        menu.handle_key(KeyEvent::from(KeyCode::Down), &drives);
+        menu.handle_key(KeyEvent::from(KeyCode::Down), &drives);
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Enter), &drives),
+            DriveMenuResult::Choose(2)
+        );
+        // A drive removed while the menu is open
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Enter), &drives[..1]),
+            DriveMenuResult::Choose(0)
+        );
+    }
+
+    #[test]
+    fn letters_pick_drives() {
+        let drives = [drive("C:\\"), drive("D:\\")];
+        let mut menu = DriveMenu::new(Pane::Left, &drives, Path::new("C:\\"));
+
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Char('d')), &drives),
+            DriveMenuResult::Choose(1)
+        );
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Char('x')), &drives),
+            DriveMenuResult::Open
+        );
+        assert_eq!(
+            menu.handle_key(KeyEvent::from(KeyCode::Esc), &drives),
+            DriveMenuResult::Closed
+        );
+    }
+}
@@ -0,0 +1,498 @@
+//! Full-screen editor for small text files.
+//!
+//! The whole file is held in memory as a [`TextFile`], so it is only used
+//! for files under the configured size; everything else goes to the
+//! external editor. Saving is asked for through [`TextEditorResult::Save`]
+//! and done with [`TextEditor::save`], which replaces the file atomically.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use zmanager_core::{TextFile, ZResult};
+
+use super::styles::Styles;
+
+/// Columns a tab advances to the next multiple of.
+const TAB_WIDTH: usize = 4;
+
+/// Result of a key press in the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEditorResult {
+    /// The editor is still open.
+    Open,
+    /// Save the file.
+    Save,
+    /// The editor was closed.
+    Closed,
+}
+
+/// Editor state: the file's lines, the cursor and the part on screen.
+#[derive(Debug)]
+pub struct TextEditor {
+    file: TextFile,
+    path: PathBuf,
+    /// Line of the cursor.
+    row: usize,
+    /// Character of the cursor within its line.
+    col: usize,
+    /// First line on screen.
+    top: usize,
+    /// Screen columns scrolled off to the left.
+    left: usize,
+    /// Rows and columns of text the last render had room for.
+    page: Cell<(usize, usize)>,
+    /// Whether there are unsaved changes.
+    modified: bool,
+    /// Whether closing was asked for once with unsaved changes.
+    confirm_close: bool,
+    /// Message shown in the footer until the next key.
+    message: Option<String>,
+}
+
+impl TextEditor {
+    /// Edit `file`, which was loaded from `path`.
+    pub fn new(file: TextFile, path: PathBuf) -> Self {
+        Self {
+            file,
+            path,
+            row: 0,
+            col: 0,
+            top: 0,
+            left: 0,
+            page: Cell::new((0, 0)),
+            modified: false,
+            confirm_close: false,
+            message: None,
+        }
+    }
+
+    /// The file being edited.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The lines being edited.
+    pub fn lines(&self) -> &[String] {
+        &self.file.lines
+    }
+
+    /// Line and character of the cursor.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// Whether there are unsaved changes.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Write the lines back to the file, showing the outcome in the footer.
+    pub fn save(&mut self) -> ZResult<()> {
+        match self.file.save(&self.path) {
+            Ok(()) => {
+                self.modified = false;
+                self.message = Some("Saved".to_string());
+                Ok(())
+            }
+            Err(e) => {
+                self.message = Some(format!("Cannot save: {e}"));
+                Err(e)
+            }
+        }
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> TextEditorResult {
+        self.message = None;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let closing = matches!(key.code, KeyCode::Esc)
+            || (ctrl && matches!(key.code, KeyCode::Char('q')));
+        if closing {
+            if !self.modified || self.confirm_close {
+                return TextEditorResult::Closed;
+            }
+            self.confirm_close = true;
+            self.message = Some("Unsaved changes: Ctrl+S saves, Esc again discards".to_string());
+            return TextEditorResult::Open;
+        }
+        self.confirm_close = false;
+
+        let rows = self.page.get().0.max(1);
+        match key.code {
+            KeyCode::Char('s') if ctrl => return TextEditorResult::Save,
+            KeyCode::Up => self.move_to_row(self.row.saturating_sub(1)),
+            KeyCode::Down => self.move_to_row(self.row + 1),
+            KeyCode::PageUp => self.move_to_row(self.row.saturating_sub(rows)),
+            KeyCode::PageDown => self.move_to_row(self.row + rows),
+            KeyCode::Left if ctrl => self.word_left(),
+            KeyCode::Right if ctrl => self.word_right(),
+            KeyCode::Left => self.left_one(),
+            KeyCode::Right => self.right_one(),
+            KeyCode::Home if ctrl => (self.row, self.col) = (0, 0),
+            KeyCode::End if ctrl => {
+                self.row = self.file.lines.len() - 1;
+                self.col = self.line_len();
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.insert(c)
+            }
+            KeyCode::Tab => self.insert('\t'),
+            KeyCode::Enter => self.split_line(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            _ => {}
+        }
+        self.scroll_to_cursor();
+        TextEditorResult::Open
+    }
+
+    /// Characters in the cursor's line.
+    fn line_len(&self) -> usize {
+        self.file.lines[self.row].chars().count()
+    }
+
+    /// Byte offset of the cursor in its line.
+    fn byte_col(&self) -> usize {
+        let line = &self.file.lines[self.row];
+        line.char_indices().nth(self.col).map_or(line.len(), |(i, _)| i)
+    }
+
+    fn move_to_row(&mut self, row: usize) {
+        self.row = row.min(self.file.lines.len() - 1);
+        self.col = self.col.min(self.line_len());
+    }
+
+    fn left_one(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+    }
+
+    fn right_one(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.file.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    /// Move to the start of the word before the cursor.
+    fn word_left(&mut self) {
+        if self.col == 0 {
+            self.left_one();
+            return;
+        }
+        let chars: Vec<char> = self.file.lines[self.row].chars().collect();
+        let mut col = self.col;
+        while col > 0 && !is_word_char(chars[col - 1]) {
+            col -= 1;
+        }
+        while col > 0 && is_word_char(chars[col - 1]) {
+            col -= 1;
+        }
+        self.col = col;
+    }
+
+    /// Move past the end of the word at or after the cursor.
+    fn word_right(&mut self) {
+        let chars: Vec<char> = self.file.lines[self.row].chars().collect();
+        if self.col == chars.len() {
+            self.right_one();
+            return;
+        }
+        let mut col = self.col;
+        while col < chars.len() && !is_word_char(chars[col]) {
+            col += 1;
+        }
+        while col < chars.len() && is_word_char(chars[col]) {
+            col += 1;
+        }
+        self.col = col;
+    }
+
+    fn insert(&mut self, c: char) {
+        let at = self.byte_col();
+        self.file.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.modified = true;
+    }
+
+    fn split_line(&mut self) {
+        let at = self.byte_col();
+        let rest = self.file.lines[self.row].split_off(at);
+        self.file.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+        self.modified = true;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.byte_col();
+            self.file.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.file.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.file.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let at = self.byte_col();
+            self.file.lines[self.row].remove(at);
+        } else if self.row + 1 < self.file.lines.len() {
+            let line = self.file.lines.remove(self.row + 1);
+            self.file.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    /// Scroll so the cursor is on screen.
+    fn scroll_to_cursor(&mut self) {
+        let (rows, cols) = self.page.get();
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        if self.row < self.top {
+            self.top = self.row;
+        } else if self.row >= self.top + rows {
+            self.top = self.row + 1 - rows;
+        }
+        let column = screen_column(&self.file.lines[self.row], self.col);
+        if column < self.left {
+            self.left = column;
+        } else if column >= self.left + cols {
+            self.left = column + 1 - cols;
+        }
+    }
+
+    /// Render the editor over all of `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height < 3 {
+            return;
+        }
+        let header = Rect::new(area.x, area.y, area.width, 1);
+        let body = Rect::new(area.x, area.y + 1, area.width, area.height - 2);
+        let footer = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        self.page.set((body.height as usize, body.width as usize));
+
+        self.render_header(header, buf);
+        let lines: Vec<Line> = self
+            .file
+            .lines
+            .iter()
+            .skip(self.top)
+            .take(body.height as usize)
+            .map(|line| {
+                let shown: String = expand_tabs(line).chars().skip(self.left).collect();
+                Line::from(shown)
+            })
+            .collect();
+        Paragraph::new(lines).render(body, buf);
+
+        let column = screen_column(&self.file.lines[self.row], self.col);
+        let x = column.saturating_sub(self.left);
+        let y = self.row.saturating_sub(self.top);
+        if self.row >= self.top && x < body.width as usize && y < body.height as usize {
+            let cell = &mut buf[(body.x + x as u16, body.y + y as u16)];
+            cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+        self.render_footer(footer, buf);
+    }
+
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let modified = if self.modified { " [modified]" } else { "" };
+        let details = format!(
+            "{} · {} · Ln {}, Col {} ",
+            self.file.encoding.name(),
+            self.file.line_ending.name(),
+            self.row + 1,
+            self.col + 1
+        );
+        let title = format!(" {name}{modified}");
+        let width = area.width as usize;
+        let gap = width.saturating_sub(title.chars().count() + details.chars().count());
+        let line = Line::from(vec![
+            Span::styled(title, Styles::header()),
+            Span::raw(" ".repeat(gap)),
+            Span::raw(details),
+        ]);
+        Paragraph::new(line).style(Styles::status_bar()).render(area, buf);
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let line = if let Some(ref message) = self.message {
+            Line::from(Span::styled(format!(" {message}"), Styles::warning()))
+        } else {
+            Line::from(Span::styled(
+                " Ctrl+S save · Ctrl+←/→ word · Ctrl+Home/End start/end · Esc close",
+                Styles::hint(),
+            ))
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// `line` with its tabs turned into spaces.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = TAB_WIDTH - expanded.chars().count() % TAB_WIDTH;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+        } else {
+            expanded.push(c);
+        }
+    }
+    expanded
+}
+
+/// Screen column of character `col` in `line`, with tabs expanded.
+fn screen_column(line: &str, col: usize) -> usize {
+    line.chars().take(col).fold(0, |column, c| {
+        if c == '\t' {
+            column + TAB_WIDTH - column % TAB_WIDTH
+        } else {
+            column + 1
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open(dir: &TempDir, text: &str) -> TextEditor {
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, text).unwrap();
+        let file = TextFile::load(&path, 1024).unwrap();
+        TextEditor::new(file, path)
+    }
+
+    fn render(editor: &TextEditor, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        editor.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect()
+    }
+
+    fn press(editor: &mut TextEditor, code: KeyCode) -> TextEditorResult {
+        editor.handle_key(KeyEvent::from(code))
+    }
+
+    fn ctrl(editor: &mut TextEditor, code: KeyCode) -> TextEditorResult {
+        editor.handle_key(KeyEvent::new(code, KeyModifiers::CONTROL))
+    }
+
+    fn type_text(editor: &mut TextEditor, text: &str) {
+        for c in text.chars() {
+            press(editor, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn edits_lines() {
+        let dir = TempDir::new().unwrap();
+        let mut editor = open(&dir, "hello world\nsecond\n");
+
+        ctrl(&mut editor, KeyCode::Right);
+        assert_eq!(editor.cursor(), (0, 5));
+        type_text(&mut editor, ",");
+        press(&mut editor, KeyCode::End);
+        press(&mut editor, KeyCode::Enter);
+        type_text(&mut editor, "new");
+        assert_eq!(editor.lines(), ["hello, world", "new", "second", ""]);
+
+        // Joining lines from either side
+        press(&mut editor, KeyCode::Home);
+        press(&mut editor, KeyCode::Backspace);
+        assert_eq!(editor.cursor(), (0, 12));
+        press(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::End);
+        press(&mut editor, KeyCode::Delete);
+        assert_eq!(editor.lines(), ["hello, worldnew", "second"]);
+        assert!(editor.is_modified());
+
+        ctrl(&mut editor, KeyCode::Home);
+        ctrl(&mut editor, KeyCode::Left);
+        assert_eq!(editor.cursor(), (0, 0));
+        ctrl(&mut editor, KeyCode::End);
+        assert_eq!(editor.cursor(), (1, 6));
+    }
+
+    #[test]
+    fn saves_in_the_file_format() {
+        let dir = TempDir::new().unwrap();
+        let mut editor = open(&dir, "one\r\ntwo\r\n");
+        press(&mut editor, KeyCode::Down);
+        type_text(&mut editor, "2: ");
+
+        assert_eq!(ctrl(&mut editor, KeyCode::Char('s')), TextEditorResult::Save);
+        editor.save().unwrap();
+        assert!(!editor.is_modified());
+        let saved = std::fs::read_to_string(editor.path()).unwrap();
+        assert_eq!(saved, "one\r\n2: two\r\n");
+    }
+
+    #[test]
+    fn asks_before_discarding_changes() {
+        let dir = TempDir::new().unwrap();
+        let mut editor = open(&dir, "text");
+        type_text(&mut editor, "more ");
+
+        assert_eq!(press(&mut editor, KeyCode::Esc), TextEditorResult::Open);
+        assert!(render(&editor, 60, 5)[4].contains("Unsaved changes"));
+        assert_eq!(press(&mut editor, KeyCode::Esc), TextEditorResult::Closed);
+        assert_eq!(std::fs::read_to_string(editor.path()).unwrap(), "text");
+    }
+
+    #[test]
+    fn scrolls_to_the_cursor() {
+        let dir = TempDir::new().unwrap();
+        let text: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let mut editor = open(&dir, &text);
+
+        let screen = render(&editor, 40, 7);
+        assert!(screen[0].contains("notes.txt"));
+        assert!(screen[0].contains("UTF-8 · LF · Ln 1, Col 1"));
+        assert_eq!(screen[1].trim_end(), "line 1");
+
+        press(&mut editor, KeyCode::PageDown);
+        press(&mut editor, KeyCode::PageDown);
+        assert_eq!(editor.cursor(), (10, 0));
+        assert_eq!(render(&editor, 40, 7)[5].trim_end(), "line 11");
+
+        // Tabs are expanded on screen
+        ctrl(&mut editor, KeyCode::Home);
+        press(&mut editor, KeyCode::Tab);
+        assert_eq!(render(&editor, 40, 7)[1].trim_end(), "    line 1");
+    }
+}
@@ -1,13 +1,17 @@
 //! File list widget for displaying directory entries.
 
+use std::ops::Range;
+use std::path::Path;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, StatefulWidget, Widget},
 };
-use zmanager_core::{EntryKind, EntryMeta};
+use zmanager_core::{EntryKind, EntryMeta, FileGroup};
 
+use super::inline_rename::InlineRename;
 use super::styles::Styles;
 
 /// File list widget for displaying a list of entries.
@@ -16,6 +20,30 @@ pub struct FileList<'a> {
     selected_indices: &'a [usize],
     is_active: bool,
     title: Option<&'a str>,
+    highlight: Option<&'a str>,
+    renaming: Option<&'a InlineRename>,
+    flat_base: Option<&'a Path>,
+}
+
+/// Find `needle` in `name`, ignoring case, returning the matched byte range.
+///
+/// An empty needle matches nothing.
+pub fn find_match(name: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    name.char_indices().find_map(|(start, _)| {
+        let mut end = start;
+        let mut rest = name[start..].chars();
+        for n in needle.chars() {
+            let c = rest.next()?;
+            if !c.to_lowercase().eq(n.to_lowercase()) {
+                return None;
+            }
+            end += c.len_utf8();
+        }
+        Some(start..end)
+    })
 }
 
 impl<'a> FileList<'a> {
@@ -26,9 +54,31 @@ impl<'a> FileList<'a> {
             selected_indices,
             is_active,
             title: None,
+            highlight: None,
+            renaming: None,
+            flat_base: None,
         }
     }
 
+    /// Highlight occurrences of `text` in entry names (a quick filter).
+    pub fn highlight(mut self, text: &'a str) -> Self {
+        self.highlight = Some(text);
+        self
+    }
+
+    /// Draw a rename editor over the name of the entry being renamed.
+    pub fn renaming(mut self, editor: &'a InlineRename) -> Self {
+        self.renaming = Some(editor);
+        self
+    }
+
+    /// Show each entry's folder relative to `base` in a column after the
+    /// name, for a flat listing of the files below `base`.
+    pub fn relative_to(mut self, base: &'a Path) -> Self {
+        self.flat_base = Some(base);
+        self
+    }
+
     /// Set the title for the file list.
     pub fn title(mut self, title: &'a str) -> Self {
         self.title = Some(title);
@@ -65,6 +115,28 @@ impl<'a> FileList<'a> {
         }
     }
 
+    /// Get the icon for a group of file types.
+    fn group_icon(group: FileGroup) -> &'static str {
+        match group {
+            FileGroup::Document => "📝",
+            FileGroup::Image => "📷",
+            FileGroup::Video => "🎬",
+            FileGroup::Audio => "🎵",
+            FileGroup::Code => "📜",
+            FileGroup::Archive => "📦",
+            FileGroup::Executable => "⚡",
+            FileGroup::Other => "📄",
+        }
+    }
+
+    /// Get the icon for an entry: its kind, or for files their type.
+    fn entry_icon(entry: &EntryMeta) -> &'static str {
+        match FileGroup::of(entry) {
+            Some(group) if entry.kind == EntryKind::File => Self::group_icon(group),
+            _ => Self::icon(entry.kind),
+        }
+    }
+
     /// Get style for an entry.
     fn entry_style(entry: &EntryMeta, is_selected: bool) -> ratatui::style::Style {
         let base = if entry.attributes.hidden {
@@ -90,35 +162,98 @@ impl<'a> FileList<'a> {
         }
     }
 
+    /// Scroll offset that keeps the cursor on screen, moving as little as possible.
+    fn visible_offset(offset: usize, cursor: Option<usize>, height: usize, len: usize) -> usize {
+        let max_offset = len.saturating_sub(height);
+        let offset = match cursor {
+            Some(cursor) if cursor < offset => cursor,
+            Some(cursor) if height > 0 && cursor >= offset + height => cursor + 1 - height,
+            _ => offset,
+        };
+        offset.min(max_offset)
+    }
+
+    /// Columns left for the name between the icon and the size.
+    fn name_width(width: u16) -> u16 {
+        let icon_width = 3; // icon + space
+        let size_width = 8;
+        width.saturating_sub(icon_width + size_width)
+    }
+
+    /// Columns taken from the name for the folder column of a flat listing.
+    fn folder_width(&self, name_width: u16) -> u16 {
+        if self.flat_base.is_some() {
+            name_width / 3
+        } else {
+            0
+        }
+    }
+
+    /// Truncate or pad `text` to exactly `width` columns.
+    fn fit(text: &str, width: usize) -> String {
+        if text.chars().count() > width {
+            let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+            format!("{}…", kept)
+        } else {
+            format!("{:width$}", text, width = width)
+        }
+    }
+
     /// Render an entry as a list item.
     fn render_entry(&self, entry: &EntryMeta, is_selected: bool, width: u16) -> ListItem<'a> {
-        let icon = Self::icon(entry.kind);
+        let icon = Self::entry_icon(entry);
         let name = &entry.name;
         let style = Self::entry_style(entry, is_selected);
 
         // Calculate available width for name
         // Format: "📁 name          12.3M"
-        let size_str = match entry.kind {
-            EntryKind::Directory => "<DIR>".to_string(),
+        let size_str = match (entry.kind, entry.computed_size) {
+            (EntryKind::Directory, Some(size)) => Self::format_size(size),
+            (EntryKind::Directory, None) => "<DIR>".to_string(),
             _ => Self::format_size(entry.size),
         };
 
-        let icon_width = 3; // icon + space
-        let size_width = 8;
-        let name_width = width.saturating_sub(icon_width + size_width) as usize;
+        let name_width = Self::name_width(width);
+        let folder_width = self.folder_width(name_width) as usize;
+        let name_width = name_width as usize - folder_width;
 
-        // Truncate or pad name
-        let display_name = if name.len() > name_width {
-            format!("{}…", &name[..name_width.saturating_sub(1)])
-        } else {
-            format!("{:width$}", name, width = name_width)
+        // Links show where they point: "name → target"
+        let label = match &entry.link_target {
+            Some(target) => format!("{} → {}", name, target.display()),
+            None => name.clone(),
         };
 
-        let line = Line::from(vec![
-            Span::raw(format!("{} ", icon)),
-            Span::styled(display_name, style),
-            Span::styled(format!("{:>7}", size_str), Styles::size()),
-        ]);
+        let display_name = Self::fit(&label, name_width);
+
+        // Only the name itself is matched, not a link's target
+        let highlight = self
+            .highlight
+            .and_then(|text| find_match(&display_name, text))
+            .filter(|range| range.end <= name.len());
+
+        let mut spans = vec![Span::raw(format!("{} ", icon))];
+        match highlight {
+            Some(range) => {
+                spans.push(Span::styled(display_name[..range.start].to_string(), style));
+                spans.push(Span::styled(
+                    display_name[range.clone()].to_string(),
+                    style.patch(Styles::filter_match()),
+                ));
+                spans.push(Span::styled(display_name[range.end..].to_string(), style));
+            }
+            None => spans.push(Span::styled(display_name, style)),
+        }
+        if let Some(base) = self.flat_base {
+            let folder = entry
+                .path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(base).ok())
+                .map(|folder| folder.display().to_string())
+                .unwrap_or_default();
+            spans.push(Span::styled(Self::fit(&folder, folder_width), Styles::muted()));
+        }
+        spans.push(Span::styled(format!("{:>7}", size_str), Styles::size()));
+        let line = Line::from(spans);
 
         ListItem::new(line)
     }
@@ -143,19 +278,40 @@ impl StatefulWidget for FileList<'_> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let items: Vec<ListItem> = self
-            .entries
+        // Only build items for the rows that fit. Large directories would
+        // otherwise format every entry on each frame.
+        let height = inner.height as usize;
+        let len = self.entries.len();
+        let offset = Self::visible_offset(state.offset(), state.selected(), height, len);
+        let end = (offset + height).min(len);
+
+        let items: Vec<ListItem> = self.entries[offset..end]
             .iter()
             .enumerate()
             .map(|(i, entry)| {
-                let is_selected = self.selected_indices.contains(&i);
+                // selected_indices comes from an in-order scan, so it's sorted
+                let is_selected = self.selected_indices.binary_search(&(offset + i)).is_ok();
                 self.render_entry(entry, is_selected, inner.width)
             })
             .collect();
 
         let list = List::new(items).highlight_style(Styles::cursor());
 
-        StatefulWidget::render(list, inner, buf, state);
+        let mut window_state = ListState::default()
+            .with_selected(state.selected().map(|cursor| cursor.saturating_sub(offset)));
+        StatefulWidget::render(list, inner, buf, &mut window_state);
+        *state.offset_mut() = offset;
+
+        if let Some(editor) = self.renaming {
+            let row = self.entries[offset..end]
+                .iter()
+                .position(|entry| entry.path == editor.path());
+            if let Some(row) = row {
+                let width = Self::name_width(inner.width);
+                let width = width - self.folder_width(width);
+                editor.render(Rect::new(inner.x + 3, inner.y + row as u16, width, 1), buf);
+            }
+        }
     }
 }
 
@@ -183,6 +339,132 @@ mod tests {
         assert_eq!(FileList::format_size(2 * 1024 * 1024 * 1024), "2.0G");
     }
 
+    #[test]
+    fn visible_offset_follows_cursor() {
+        // Cursor already on screen: keep the current scroll position
+        assert_eq!(FileList::visible_offset(10, Some(15), 10, 100), 10);
+        // Cursor below the window: scroll just enough to show it
+        assert_eq!(FileList::visible_offset(0, Some(25), 10, 100), 16);
+        // Cursor above the window
+        assert_eq!(FileList::visible_offset(50, Some(3), 10, 100), 3);
+        // Never scroll past the end of the list
+        assert_eq!(FileList::visible_offset(95, None, 10, 100), 90);
+    }
+
+    #[test]
+    fn render_shows_cursor_row_of_large_list() {
+        let entries: Vec<EntryMeta> = (0..5000)
+            .map(|i| {
+                let name = format!("file{:04}.txt", i);
+                EntryMeta::new(name.clone(), name.into(), EntryKind::File)
+            })
+            .collect();
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::default().with_selected(Some(4999));
+
+        FileList::new(&entries, &[4999], true).render(area, &mut buf, &mut state);
+
+        assert_eq!(state.offset(), 4990);
+        assert_eq!(state.selected(), Some(4999));
+        let last_row: String = (0..area.width).map(|x| buf[(x, 10)].symbol()).collect();
+        assert!(last_row.contains("file4999.txt"));
+    }
+
+    #[test]
+    fn find_match_ignores_case() {
+        assert_eq!(find_match("Report.TXT", "port"), Some(2..6));
+        assert_eq!(find_match("Report.TXT", ".txt"), Some(6..10));
+        assert_eq!(find_match("Ärger.md", "ärg"), Some(0..4));
+        assert_eq!(find_match("Report.TXT", "xyz"), None);
+        assert_eq!(find_match("Report.TXT", ""), None);
+    }
+
+    #[test]
+    fn render_highlights_filter_match() {
+        let entries = vec![EntryMeta::new("notes.txt".into(), "notes.txt".into(), EntryKind::File)];
+        let area = Rect::new(0, 0, 30, 3);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::default();
+
+        FileList::new(&entries, &[], true)
+            .highlight("TES")
+            .render(area, &mut buf, &mut state);
+
+        // Row 1 inside the border: icon, space, then "notes.txt"
+        let name_start = (1..area.width).find(|&x| buf[(x, 1)].symbol() == "n").unwrap();
+        let matched = buf[(name_start + 2, 1)].style();
+        assert_eq!(matched.add_modifier, Styles::filter_match().add_modifier);
+        assert_ne!(buf[(name_start, 1)].style(), matched);
+    }
+
+    #[test]
+    fn render_shows_link_target() {
+        let mut link = EntryMeta::new("latest".into(), "latest".into(), EntryKind::Symlink);
+        link.link_target = Some("releases/v2".into());
+        let entries = vec![link];
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::default();
+
+        FileList::new(&entries, &[], true)
+            .highlight("v2")
+            .render(area, &mut buf, &mut state);
+
+        let row: String = (0..area.width).map(|x| buf[(x, 1)].symbol()).collect();
+        assert!(row.contains("latest → releases/v2"));
+        // The target isn't part of the name, so it isn't highlighted
+        let target_start = (1..area.width).find(|&x| buf[(x, 1)].symbol() == "v").unwrap();
+        assert_ne!(
+            buf[(target_start, 1)].style().add_modifier,
+            Styles::filter_match().add_modifier
+        );
+    }
+
+    #[test]
+    fn render_draws_rename_editor_over_the_name() {
+        let entries = vec![
+            EntryMeta::new("a.txt".into(), "a.txt".into(), EntryKind::File),
+            EntryMeta::new("notes.txt".into(), "notes.txt".into(), EntryKind::File),
+        ];
+        let mut editor = InlineRename::new("notes.txt".into(), false);
+        editor.handle_key(crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char('x')));
+        let area = Rect::new(0, 0, 30, 4);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::default().with_selected(Some(1));
+
+        FileList::new(&entries, &[], true)
+            .renaming(&editor)
+            .render(area, &mut buf, &mut state);
+
+        let row: String = (0..area.width).map(|x| buf[(x, 2)].symbol()).collect();
+        assert!(row.contains("x.txt"));
+        assert!(!row.contains("notes"));
+    }
+
+    #[test]
+    fn render_shows_folder_of_flat_entries() {
+        let base = Path::new("/data");
+        let deep = base.join("a").join("b").join("deep.txt");
+        let entries = vec![
+            EntryMeta::new("top.txt".into(), base.join("top.txt"), EntryKind::File),
+            EntryMeta::new("deep.txt".into(), deep, EntryKind::File),
+        ];
+        let area = Rect::new(0, 0, 50, 4);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::default();
+
+        FileList::new(&entries, &[], true)
+            .relative_to(base)
+            .render(area, &mut buf, &mut state);
+
+        let row = |y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        let folder = Path::new("a").join("b").display().to_string();
+        assert!(!row(1).contains(&folder));
+        assert!(row(2).contains("deep.txt"));
+        assert!(row(2).contains(&folder));
+    }
+
     #[test]
     fn icon_for_directory() {
         assert_eq!(FileList::icon(EntryKind::Directory), "📁");
@@ -192,4 +474,16 @@ mod tests {
     fn icon_for_file() {
         assert_eq!(FileList::icon(EntryKind::File), "📄");
     }
+
+    #[test]
+    fn icons_and_colors_follow_the_file_type() {
+        let clip = EntryMeta::new("clip.MP4".into(), "clip.MP4".into(), EntryKind::File);
+        let readme = EntryMeta::new("README".into(), "README".into(), EntryKind::File);
+        let link = EntryMeta::new("clip.mp4".into(), "clip.mp4".into(), EntryKind::Symlink);
+        assert_eq!(FileList::entry_icon(&clip), "🎬");
+        assert_eq!(FileList::entry_icon(&readme), "📄");
+        assert_eq!(FileList::entry_icon(&link), "🔗");
+        assert_eq!(FileList::entry_style(&clip, false), Styles::video());
+        assert_eq!(FileList::entry_style(&readme, false), Styles::normal());
+    }
 }
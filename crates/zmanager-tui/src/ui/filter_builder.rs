@@ -0,0 +1,334 @@
+//! Filter builder: pick the sizes, dates and attributes listings show.
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::FilterSpec;
+
+use super::styles::Styles;
+
+/// Format dates are shown and typed in.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Text fields, in field order.
+const TEXTS: [&str; 6] = [
+    "Name contains",
+    "Extensions",
+    "Min size",
+    "Max size",
+    "Modified from",
+    "Modified before",
+];
+
+/// Read-only choices, after the text fields.
+const READONLY: [(Option<bool>, &str); 3] =
+    [(None, "Any"), (Some(true), "Only read-only"), (Some(false), "Only writable")];
+
+/// Result of a key press in the filter builder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterBuilderResult {
+    /// The builder is still open.
+    Open,
+    /// Filter listings with this spec.
+    Apply(FilterSpec),
+    /// The builder was dismissed.
+    Cancelled,
+}
+
+/// Filter builder state.
+///
+/// Sizes are typed as a number with an optional `K`, `M`, `G` or `T` suffix
+/// and dates as `YYYY-MM-DD` in local time. Empty fields don't filter.
+#[derive(Debug, Clone)]
+pub struct FilterBuilder {
+    /// The filter being edited, for the settings the builder doesn't show.
+    base: FilterSpec,
+    /// Text fields, as typed.
+    texts: [String; 6],
+    /// Index into [`READONLY`].
+    readonly: usize,
+    /// Whether system files show.
+    show_system: bool,
+    /// Focused field: the text fields, the read-only choice, then system.
+    focus: usize,
+    /// Why the last Enter couldn't apply the filter.
+    error: Option<String>,
+}
+
+impl FilterBuilder {
+    /// Start editing `filter`.
+    pub fn new(filter: &FilterSpec) -> Self {
+        let size = |size: Option<u64>| size.map(format_size).unwrap_or_default();
+        let date = |time: Option<DateTime<Utc>>| {
+            time.map(|t| t.with_timezone(&Local).format(DATE_FORMAT).to_string())
+                .unwrap_or_default()
+        };
+        Self {
+            base: filter.clone(),
+            texts: [
+                filter.pattern.clone().unwrap_or_default(),
+                filter.extensions.join(", "),
+                size(filter.min_size),
+                size(filter.max_size),
+                date(filter.modified_after),
+                date(filter.modified_before),
+            ],
+            readonly: READONLY.iter().position(|(r, _)| *r == filter.readonly).unwrap_or(0),
+            show_system: filter.show_system,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> FilterBuilderResult {
+        let fields = TEXTS.len() + 2;
+        let text = (self.focus < TEXTS.len()).then_some(self.focus);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => return FilterBuilderResult::Cancelled,
+            (KeyModifiers::NONE, KeyCode::Enter) => match self.filter() {
+                Ok(filter) => return FilterBuilderResult::Apply(filter),
+                Err(message) => self.error = Some(message),
+            },
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Tab) => {
+                self.focus = (self.focus + 1) % fields;
+            }
+            (_, KeyCode::Up | KeyCode::BackTab) => {
+                self.focus = (self.focus + fields - 1) % fields;
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                if let Some(i) = text {
+                    self.texts[i].clear();
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace) => {
+                if let Some(i) = text {
+                    self.texts[i].pop();
+                }
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) if text.is_some() => {
+                if let Some(i) = text {
+                    self.texts[i].push(c);
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Char(' ') | KeyCode::Right) => self.change(true),
+            (KeyModifiers::NONE, KeyCode::Left) => self.change(false),
+            _ => {}
+        }
+        FilterBuilderResult::Open
+    }
+
+    /// Step the read-only choice, or toggle system files.
+    fn change(&mut self, forward: bool) {
+        if self.focus == TEXTS.len() {
+            let len = READONLY.len();
+            let step = if forward { 1 } else { len - 1 };
+            self.readonly = (self.readonly + step) % len;
+        } else if self.focus == TEXTS.len() + 1 {
+            self.show_system = !self.show_system;
+        }
+    }
+
+    /// The filter as edited, or why a field can't be used.
+    fn filter(&self) -> Result<FilterSpec, String> {
+        let text = |i: usize| Some(self.texts[i].trim()).filter(|t| !t.is_empty());
+        let size = |i: usize| {
+            let invalid = || format!("{}: enter a size like 10M", TEXTS[i]);
+            text(i).map(|t| parse_size(t).ok_or_else(invalid)).transpose()
+        };
+        let date = |i: usize| {
+            let invalid = || format!("{}: enter a date as YYYY-MM-DD", TEXTS[i]);
+            text(i).map(|t| parse_date(t).ok_or_else(invalid)).transpose()
+        };
+        let extensions = text(1)
+            .map(|t| {
+                t.split([',', ' '])
+                    .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                    .filter(|ext| !ext.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(FilterSpec {
+            pattern: text(0).map(str::to_string),
+            extensions,
+            min_size: size(2)?,
+            max_size: size(3)?,
+            modified_after: date(4)?,
+            modified_before: date(5)?,
+            readonly: READONLY[self.readonly].0,
+            show_system: self.show_system,
+            ..self.base.clone()
+        })
+    }
+
+    /// Render the builder centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = 60.min(area.width.saturating_sub(4));
+        let height = 15.min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(" Filter ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let focused = |i: usize| {
+            if i == self.focus {
+                Styles::cursor()
+            } else {
+                Style::default()
+            }
+        };
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, label) in TEXTS.iter().enumerate() {
+            let mut spans = vec![
+                Span::styled(format!("{:<17}", format!("{label}:")), Styles::muted()),
+                Span::styled(self.texts[i].clone(), focused(i)),
+            ];
+            if i == self.focus {
+                spans.push(Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        let readonly = format!("Read-only:   ‹ {} ›", READONLY[self.readonly].1);
+        lines.push(Line::from(Span::styled(readonly, focused(TEXTS.len()))));
+        let mark = if self.show_system { "[x]" } else { "[ ]" };
+        let system = format!("{mark} Show system files");
+        lines.push(Line::from(Span::styled(system, focused(TEXTS.len() + 1))));
+        lines.push(Line::from(""));
+        if let Some(ref error) = self.error {
+            lines.push(Line::from(Span::styled(error.clone(), Styles::error())));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("↑↓ move · Space change · Ctrl+U clear · Enter apply · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+/// Parse a size typed as a number with an optional `K`, `M`, `G` or `T`
+/// suffix (powers of 1024, an optional trailing `B`).
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim().to_ascii_uppercase();
+    let text = text.strip_suffix('B').unwrap_or(&text).trim_end();
+    let (number, unit) = match text.char_indices().last()? {
+        (i, 'K') => (&text[..i], 1u64 << 10),
+        (i, 'M') => (&text[..i], 1 << 20),
+        (i, 'G') => (&text[..i], 1 << 30),
+        (i, 'T') => (&text[..i], 1 << 40),
+        _ => (text, 1),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    (number >= 0.0).then_some((number * unit as f64) as u64)
+}
+
+/// Show a size the way [`parse_size`] reads it back.
+fn format_size(size: u64) -> String {
+    let units = [(1u64 << 40, "T"), (1 << 30, "G"), (1 << 20, "M"), (1 << 10, "K")];
+    units
+        .iter()
+        .find(|(unit, _)| size >= *unit && size % unit == 0)
+        .map_or_else(|| size.to_string(), |(unit, suffix)| format!("{}{suffix}", size / unit))
+}
+
+/// Parse the start of a local day typed as `YYYY-MM-DD`.
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(text, DATE_FORMAT).ok()?;
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local).earliest()?;
+    Some(midnight.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(builder: &mut FilterBuilder, code: KeyCode) -> FilterBuilderResult {
+        builder.handle_key(KeyEvent::from(code))
+    }
+
+    fn type_text(builder: &mut FilterBuilder, text: &str) {
+        for c in text.chars() {
+            press(builder, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn builds_a_filter_from_the_fields() {
+        let mut builder = FilterBuilder::new(&FilterSpec::new().with_hidden(true));
+        type_text(&mut builder, "report");
+        press(&mut builder, KeyCode::Down);
+        type_text(&mut builder, ".PDF, docx");
+        press(&mut builder, KeyCode::Down);
+        type_text(&mut builder, "1.5k");
+        press(&mut builder, KeyCode::Down);
+        press(&mut builder, KeyCode::Down);
+        type_text(&mut builder, "2024-03-01");
+        press(&mut builder, KeyCode::Down);
+        press(&mut builder, KeyCode::Down);
+        press(&mut builder, KeyCode::Char(' '));
+        press(&mut builder, KeyCode::Down);
+        press(&mut builder, KeyCode::Char(' '));
+
+        let FilterBuilderResult::Apply(filter) = press(&mut builder, KeyCode::Enter) else {
+            panic!("expected the filter to apply");
+        };
+        assert_eq!(filter.pattern.as_deref(), Some("report"));
+        assert_eq!(filter.extensions, ["pdf", "docx"]);
+        assert_eq!((filter.min_size, filter.max_size), (Some(1536), None));
+        assert_eq!(filter.modified_after, parse_date("2024-03-01"));
+        assert_eq!(filter.modified_before, None);
+        assert_eq!(filter.readonly, Some(true));
+        assert!(filter.show_system);
+        // Settings the builder doesn't show are kept
+        assert!(filter.show_hidden);
+
+        // Reopening shows the same values
+        let reopened = FilterBuilder::new(&filter);
+        assert_eq!(reopened.texts[1], "pdf, docx");
+        assert_eq!(reopened.texts[2], "1536");
+        assert_eq!(reopened.texts[4], "2024-03-01");
+    }
+
+    #[test]
+    fn bad_values_keep_the_builder_open() {
+        let mut builder = FilterBuilder::new(&FilterSpec::new());
+        press(&mut builder, KeyCode::Down);
+        press(&mut builder, KeyCode::Down);
+        type_text(&mut builder, "lots");
+        assert_eq!(press(&mut builder, KeyCode::Enter), FilterBuilderResult::Open);
+        assert!(builder.error.as_deref().unwrap().starts_with("Min size:"));
+
+        builder.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert!(matches!(press(&mut builder, KeyCode::Enter), FilterBuilderResult::Apply(_)));
+        assert_eq!(press(&mut builder, KeyCode::Esc), FilterBuilderResult::Cancelled);
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("10M"), Some(10 << 20));
+        assert_eq!(parse_size("2 GB"), Some(2 << 30));
+        assert_eq!(parse_size("0.5k"), Some(512));
+        assert_eq!(parse_size("-1"), None);
+        assert_eq!(parse_size("big"), None);
+        assert_eq!(format_size(10 << 20), "10M");
+        assert_eq!(format_size(1536), "1536");
+    }
+}
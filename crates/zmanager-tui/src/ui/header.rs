@@ -8,6 +8,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Paragraph, Widget},
 };
+use zmanager_core::SortSpec;
 
 use super::styles::Styles;
 
@@ -15,12 +16,36 @@ use super::styles::Styles;
 pub struct Header<'a> {
     path: &'a Path,
     is_active: bool,
+    loading: Option<usize>,
+    sort: Option<&'a SortSpec>,
 }
 
 impl<'a> Header<'a> {
     /// Create a new header widget.
     pub fn new(path: &'a Path, is_active: bool) -> Self {
-        Self { path, is_active }
+        Self {
+            path,
+            is_active,
+            loading: None,
+            sort: None,
+        }
+    }
+
+    /// Show the sort field and order at the right end.
+    pub fn sort(mut self, spec: &'a SortSpec) -> Self {
+        self.sort = Some(spec);
+        self
+    }
+
+    /// The sort field and an arrow for its order, e.g. "Name ↑".
+    fn sort_indicator(spec: &SortSpec) -> String {
+        format!("{} {}", spec.field.label(), spec.order.label())
+    }
+
+    /// Show that the listing is still loading, with the entries read so far.
+    pub fn loading(mut self, received: Option<usize>) -> Self {
+        self.loading = received;
+        self
     }
 
     /// Build breadcrumb spans from path.
@@ -52,6 +77,10 @@ impl<'a> Header<'a> {
             ));
         }
 
+        if let Some(received) = self.loading {
+            spans.push(Span::styled(format!("  loading… {received}"), Styles::muted()));
+        }
+
         Line::from(spans)
     }
 }
@@ -59,7 +88,19 @@ impl<'a> Header<'a> {
 impl Widget for Header<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let line = self.breadcrumbs();
-        Paragraph::new(line).render(area, buf);
+        let indicator = self.sort.map(Self::sort_indicator);
+        let width = indicator.as_ref().map_or(0, |text| text.chars().count() as u16);
+
+        // The path keeps the header to itself when the pane is too narrow
+        match indicator {
+            Some(text) if area.width >= width + 20 => {
+                let path_area = Rect { width: area.width - width - 1, ..area };
+                let sort_area = Rect { x: area.right() - width, width, ..area };
+                Paragraph::new(line).render(path_area, buf);
+                Paragraph::new(text).style(Styles::muted()).render(sort_area, buf);
+            }
+            _ => Paragraph::new(line).render(area, buf),
+        }
     }
 }
 
@@ -85,4 +126,30 @@ mod tests {
         
         assert!(!line.spans.is_empty());
     }
+
+    #[test]
+    fn header_shows_loading_count() {
+        let path = Path::new("C:\\Big");
+        let line = Header::new(path, true).loading(Some(2000)).breadcrumbs();
+        assert!(line.to_string().ends_with("loading… 2000"));
+
+        let line = Header::new(path, true).loading(None).breadcrumbs();
+        assert!(!line.to_string().contains("loading"));
+    }
+
+    #[test]
+    fn header_shows_sort_indicator() {
+        let path = Path::new("C:\\Users");
+        let spec = SortSpec::by_size();
+        let render = |width| {
+            let area = Rect::new(0, 0, width, 1);
+            let mut buf = Buffer::empty(area);
+            Header::new(path, true).sort(&spec).render(area, &mut buf);
+            (0..width).map(|x| buf[(x, 0)].symbol()).collect::<String>()
+        };
+
+        assert!(render(40).ends_with("Size ↓"));
+        // Too narrow: only the path
+        assert!(!render(20).contains("Size"));
+    }
 }
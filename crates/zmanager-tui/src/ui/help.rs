@@ -3,28 +3,68 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
 };
 
-/// Help screen widget.
-pub struct HelpScreen;
+use super::styles::Styles;
+use crate::input::{Action, Keymap};
 
-impl HelpScreen {
-    /// Create a new help screen.
-    pub fn new() -> Self {
-        Self
+/// Widest the keys column grows before descriptions are pushed aside.
+const MAX_KEY_WIDTH: usize = 24;
+
+/// Keys column of a help row, looked up in the keymap.
+enum Keys {
+    /// Chords of each action, e.g. "↑/k, ↓/j".
+    Actions(&'static [Action]),
+    /// An action typed after a count, e.g. "N ←".
+    Counted(Action),
+    /// First and last of a run of actions, e.g. "1-9".
+    Range(Action, Action),
+}
+
+impl Keys {
+    fn describe(&self, keymap: &Keymap) -> String {
+        let first = |action| keymap.chords(action).first().map(ToString::to_string);
+        let text = match self {
+            Self::Actions(actions) => actions
+                .iter()
+                .map(|&action| {
+                    let chords: Vec<String> =
+                        keymap.chords(action).iter().map(ToString::to_string).collect();
+                    chords.join("/")
+                })
+                .filter(|chords| !chords.is_empty())
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Counted(action) => first(*action).map(|c| format!("N {c}")).unwrap_or_default(),
+            Self::Range(from, to) => match (first(*from), first(*to)) {
+                (Some(from), Some(to)) => format!("{from}-{to}"),
+                _ => String::new(),
+            },
+        };
+        if text.is_empty() {
+            "(unbound)".to_string()
+        } else {
+            text
+        }
     }
 }
 
-impl Default for HelpScreen {
-    fn default() -> Self {
-        Self::new()
+/// Help screen widget listing the keys of the active keymap.
+pub struct HelpScreen<'a> {
+    keymap: &'a Keymap,
+}
+
+impl<'a> HelpScreen<'a> {
+    /// Create a new help screen for the given keymap.
+    pub fn new(keymap: &'a Keymap) -> Self {
+        Self { keymap }
     }
 }
 
-impl Widget for HelpScreen {
+impl Widget for HelpScreen<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Clear and render centered modal
         let modal_width = 70.min(area.width.saturating_sub(4));
@@ -46,7 +86,7 @@ impl Widget for HelpScreen {
             .title(" Help - Keyboard Shortcuts ")
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
+            .border_style(Styles::accent());
 
         let inner = block.inner(modal_area);
         block.render(modal_area, buf);
@@ -54,57 +94,127 @@ impl Widget for HelpScreen {
         // Build help content
         let sections = [
             ("Navigation", vec![
-                ("↑/k, ↓/j", "Move cursor up/down"),
-                ("←/h, →/l", "Parent directory / Enter"),
-                ("Enter", "Enter directory / Open file"),
-                ("Backspace", "Go to parent directory"),
-                ("Tab", "Switch between panes"),
-                ("g/Home", "Go to first item"),
-                ("G/End", "Go to last item"),
-                ("Ctrl+u/PgUp", "Page up"),
-                ("Ctrl+d/PgDn", "Page down"),
-                ("[/]", "History back/forward"),
+                (Keys::Actions(&[Action::Up, Action::Down]), "Move cursor up/down"),
+                (Keys::Actions(&[Action::Enter]), "Enter directory or .zip / Open file"),
+                (Keys::Actions(&[Action::GoParent]), "Go to parent directory"),
+                (Keys::Counted(Action::GoParent), "Go up N levels (type the count first)"),
+                (Keys::Actions(&[Action::SwitchPane]), "Switch between panes"),
+                (Keys::Actions(&[Action::NewTab, Action::CloseTab]), "New tab / Close tab"),
+                (Keys::Actions(&[Action::NextTab, Action::PrevTab]), "Next/previous tab"),
+                (Keys::Actions(&[Action::GoFirst]), "Go to first item"),
+                (Keys::Actions(&[Action::GoLast]), "Go to last item"),
+                (
+                    Keys::Actions(&[Action::NextSameExt, Action::PrevSameExt]),
+                    "Next/previous with same extension",
+                ),
+                (Keys::Actions(&[Action::PageUp]), "Page up"),
+                (Keys::Actions(&[Action::PageDown]), "Page down"),
+                (Keys::Actions(&[Action::GoBack, Action::GoForward]), "History back/forward"),
+                (Keys::Actions(&[Action::GoToPath]), "Go to path (Tab completes, %VAR% expands)"),
+                (Keys::Actions(&[Action::DirectoryHistory]), "Frequent and recent directories"),
             ]),
             ("Selection", vec![
-                ("Space", "Toggle selection"),
-                ("Ctrl+a", "Select all"),
-                ("*", "Invert selection"),
-                ("Esc", "Clear selection"),
+                (Keys::Actions(&[Action::ToggleSelect]), "Toggle selection"),
+                (Keys::Actions(&[Action::SelectAll]), "Select all"),
+                (Keys::Actions(&[Action::InvertSelection]), "Invert selection"),
+                (Keys::Actions(&[Action::ClearSelection]), "Clear selection"),
+                (Keys::Actions(&[Action::SelectPattern]), "Select by pattern (*.jpg; *.png)"),
+                (Keys::Actions(&[Action::DeselectPattern]), "Deselect by pattern"),
+                (Keys::Actions(&[Action::SelectSameExt]), "Select files with the same extension"),
+                (Keys::Actions(&[Action::SelectNewer]), "Select files newer than the selected"),
             ]),
             ("File Operations", vec![
-                ("Shift+C", "Copy to other pane"),
-                ("Shift+M", "Move to other pane"),
-                ("d/Del", "Delete selected"),
-                ("r/F2", "Rename"),
-                ("n", "New directory"),
-                ("o", "Open with default app"),
+                (Keys::Actions(&[Action::Copy]), "Copy to other pane"),
+                (Keys::Actions(&[Action::Move]), "Move to other pane"),
+                (
+                    Keys::Actions(&[Action::CopyToPrevious, Action::MoveToPrevious]),
+                    "Copy/move to previous directory",
+                ),
+                (Keys::Actions(&[Action::Delete]), "Delete selected to Recycle Bin"),
+                (Keys::Actions(&[Action::DeletePermanent]), "Delete selected permanently"),
+                (Keys::Actions(&[Action::Rename]), "Rename (pattern rename if several selected)"),
+                (Keys::Actions(&[Action::RenameFromClipboard]), "Rename to clipboard text"),
+                (Keys::Actions(&[Action::MakeDir]), "New directory"),
+                (Keys::Actions(&[Action::NewFile]), "New file (from matching template)"),
+                (Keys::Actions(&[Action::Open]), "Open with default app"),
+                (Keys::Actions(&[Action::OpenWith]), "Open with another app"),
+                (Keys::Actions(&[Action::OpenSelected]), "Open all selected files"),
+                (Keys::Actions(&[Action::Run]), "Run executable"),
+                (Keys::Actions(&[Action::OpenTerminal]), "Open terminal here"),
+                (Keys::Actions(&[Action::RevealInExplorer]), "Reveal in Explorer"),
+                (Keys::Actions(&[Action::CopyPath]), "Copy full path"),
+                (Keys::Actions(&[Action::CopyName]), "Copy name"),
+                (Keys::Actions(&[Action::CopyMenu]), "Copy as UNC, arguments or contents"),
+                (Keys::Actions(&[Action::ContextMenu]), "Menu of operations on selected"),
+                (Keys::Actions(&[Action::View]), "View as text or hex (/ finds)"),
+                (Keys::Actions(&[Action::Edit]), "Edit (built-in for small text files)"),
+                (Keys::Actions(&[Action::Compress]), "Create zip from selected"),
+                (Keys::Actions(&[Action::Extract]), "Extract archive to other pane"),
+                (Keys::Actions(&[Action::TakeOwnership]), "Take ownership as administrator"),
             ]),
             ("Views & Panels", vec![
-                ("t", "Toggle transfers view"),
-                ("Ctrl+b", "Toggle sidebar"),
-                (".", "Toggle hidden files"),
-                ("s", "Sort menu"),
-                ("i", "Properties"),
-                ("?/F1", "This help screen"),
+                (Keys::Actions(&[Action::ToggleTransfers]), "Toggle transfers view"),
+                (Keys::Actions(&[Action::ToggleSidebar]), "Toggle sidebar"),
+                (Keys::Actions(&[Action::ToggleHidden]), "Toggle hidden files"),
+                (Keys::Actions(&[Action::FilterMenu]), "Filter as you type (Esc clears)"),
+                (Keys::Actions(&[Action::InvertFilter]), "Invert filter in pane"),
+                (Keys::Actions(&[Action::FilterBuilder]), "Filter by size, date, attributes"),
+                (Keys::Actions(&[Action::ToggleFlatView]), "Flat view of all subfolders"),
+                (Keys::Actions(&[Action::FindDuplicates]), "Mark duplicates of other pane"),
+                (Keys::Actions(&[Action::CalculateSize]), "Calculate folder sizes"),
+                (Keys::Actions(&[Action::Checksum]), "Checksums of selected files"),
+                (Keys::Actions(&[Action::Compare]), "Compare the files in both panes"),
+                (Keys::Actions(&[Action::ConvertText]), "Convert encoding or line endings"),
+                (Keys::Actions(&[Action::SortMenu]), "Sort menu"),
+                (Keys::Actions(&[Action::Properties]), "Properties (drive details in sidebar)"),
+                (Keys::Actions(&[Action::Help]), "This help screen"),
             ]),
             ("Transfers", vec![
-                ("Shift+P", "Pause job"),
-                ("Shift+R", "Resume job"),
-                ("Shift+X", "Cancel job"),
+                (Keys::Actions(&[Action::PauseJob]), "Pause job"),
+                (Keys::Actions(&[Action::ResumeJob]), "Resume job"),
+                (Keys::Actions(&[Action::CancelJob]), "Cancel job"),
+                (Keys::Actions(&[Action::CycleSpeedLimit]), "Cycle speed limit"),
+                (Keys::Actions(&[Action::Enter]), "Show or hide job details"),
+                (Keys::Actions(&[Action::ExportReport]), "Export job report"),
+                (Keys::Actions(&[Action::RetryFailed]), "Retry failed items of job"),
+                (Keys::Actions(&[Action::SwitchPane]), "History (r retries failed items)"),
             ]),
             ("Quick Access", vec![
-                ("Ctrl+d", "Add to favorites"),
-                ("1-9", "Quick jump to favorite"),
+                (Keys::Actions(&[Action::DriveMenuLeft]), "Change left pane drive"),
+                (Keys::Actions(&[Action::DriveMenuRight]), "Change right pane drive"),
+                (Keys::Actions(&[Action::AddFavorite]), "Add to favorites"),
+                (Keys::Actions(&[Action::AddToBasket]), "Add selected to a basket"),
+                (Keys::Actions(&[Action::ShowBasket]), "Show a basket (copy/move/delete all)"),
+                (Keys::Actions(&[Action::RemoveFromBasket]), "Remove selected from basket"),
+                (
+                    Keys::Range(Action::QuickJump(1), Action::QuickJump(9)),
+                    "Quick jump to favorite",
+                ),
             ]),
             ("General", vec![
-                ("q/Ctrl+c", "Quit"),
-                ("F5/Ctrl+r", "Refresh"),
+                (Keys::Actions(&[Action::Quit]), "Quit"),
+                (Keys::Actions(&[Action::Refresh]), "Refresh"),
             ]),
         ];
+        let sections: Vec<(&str, Vec<(String, &str)>)> = sections
+            .into_iter()
+            .map(|(name, rows)| {
+                let rows = rows
+                    .into_iter()
+                    .map(|(keys, desc)| (keys.describe(self.keymap), desc))
+                    .collect();
+                (name, rows)
+            })
+            .collect();
 
         // Calculate column layout
         let content_width = inner.width as usize;
-        let key_width = 14;
+        let key_width = sections
+            .iter()
+            .flat_map(|(_, rows)| rows.iter().map(|(keys, _)| keys.chars().count()))
+            .max()
+            .unwrap_or(0)
+            .min(MAX_KEY_WIDTH);
 
         let mut lines: Vec<Line> = Vec::new();
         
@@ -113,11 +223,11 @@ impl Widget for HelpScreen {
             lines.push(Line::from(vec![
                 Span::styled(
                     format!("─── {} ", section_name),
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Styles::section(),
                 ),
                 Span::styled(
                     "─".repeat(content_width.saturating_sub(section_name.len() + 5)),
-                    Style::default().fg(Color::DarkGray),
+                    Styles::muted(),
                 ),
             ]));
 
@@ -126,9 +236,9 @@ impl Widget for HelpScreen {
                 lines.push(Line::from(vec![
                     Span::styled(
                         format!("{:width$}", key, width = key_width),
-                        Style::default().fg(Color::Cyan),
+                        Styles::accent(),
                     ),
-                    Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(" │ ", Styles::muted()),
                     Span::styled(*desc, Style::default()),
                 ]));
             }
@@ -139,7 +249,7 @@ impl Widget for HelpScreen {
         // Footer
         lines.push(Line::from(Span::styled(
             "Press any key to close",
-            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            Styles::hint(),
         )));
 
         let paragraph = Paragraph::new(lines)
@@ -173,4 +283,23 @@ mod tests {
         let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
         assert!(handle_help_key(key));
     }
+
+    #[test]
+    fn help_screen_shows_custom_keys() {
+        use std::collections::BTreeMap;
+
+        let config = BTreeMap::from([("up".to_string(), vec!["ctrl+p".to_string()])]);
+        let (keymap, _) = Keymap::from_config(&config);
+        let area = Rect::new(0, 0, 80, 40);
+        let mut buf = Buffer::empty(area);
+
+        HelpScreen::new(&keymap).render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let row = rows.iter().find(|row| row.contains("Move cursor up/down")).unwrap();
+        assert!(row.contains("Ctrl+p, ↓/j"));
+        assert!(!rows.iter().any(|row| row.contains("↑/k")));
+    }
 }
@@ -0,0 +1,248 @@
+//! Directory history overlay listing visited directories by frecency.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::VisitHistory;
+
+use super::styles::Styles;
+
+/// Result of a key press in the history overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryResult {
+    /// The overlay is still open.
+    Open,
+    /// Go to the chosen directory.
+    Go(PathBuf),
+    /// Remove the chosen directory from the history.
+    Forget(PathBuf),
+    /// The overlay was dismissed.
+    Closed,
+}
+
+/// A directory listed in the overlay.
+#[derive(Debug, Clone)]
+struct HistoryRow {
+    path: PathBuf,
+    count: u32,
+    last_visit: DateTime<Utc>,
+}
+
+/// Directory history overlay state.
+#[derive(Debug, Clone)]
+pub struct HistoryView {
+    /// Visited directories, highest ranked first.
+    rows: Vec<HistoryRow>,
+    /// Highlighted row.
+    cursor: usize,
+    /// When the rows were ranked, for the "ago" column.
+    now: DateTime<Utc>,
+}
+
+impl HistoryView {
+    /// Snapshot `history` ranked by frecency.
+    pub fn new(history: &VisitHistory) -> Self {
+        let now = Utc::now();
+        let rows = history
+            .ranked_at(now)
+            .into_iter()
+            .map(|visit| HistoryRow {
+                path: visit.path.clone(),
+                count: visit.count,
+                last_visit: visit.last_visit,
+            })
+            .collect();
+        Self { rows, cursor: 0, now }
+    }
+
+    /// Number of directories listed.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check if no directories are listed.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The highlighted directory.
+    pub fn selected(&self) -> Option<&PathBuf> {
+        self.rows.get(self.cursor).map(|row| &row.path)
+    }
+
+    /// Drop a directory that was forgotten from the history.
+    pub fn remove_selected(&mut self) {
+        if self.cursor < self.rows.len() {
+            self.rows.remove(self.cursor);
+            self.cursor = self.cursor.min(self.rows.len().saturating_sub(1));
+        }
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> HistoryResult {
+        let last = self.rows.len().saturating_sub(1);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+                return HistoryResult::Closed;
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('h')) => return HistoryResult::Closed,
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                if let Some(path) = self.selected() {
+                    return HistoryResult::Go(path.clone());
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Delete | KeyCode::Char('d')) => {
+                if let Some(path) = self.selected() {
+                    return HistoryResult::Forget(path.clone());
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) => {
+                self.cursor = (self.cursor + 1).min(last);
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) => self.cursor = 0,
+            (_, KeyCode::End | KeyCode::Char('G')) => self.cursor = last,
+            _ => {}
+        }
+        HistoryResult::Open
+    }
+
+    /// Render the overlay centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).clamp(40, 90).min(area.width);
+        let height = area.height.saturating_sub(4).clamp(6, 24).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(" Directory History ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // Rows above the hint line, scrolled to keep the cursor in view
+        let visible = inner.height.saturating_sub(1) as usize;
+        let skip = (self.cursor + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = Vec::new();
+        if self.rows.is_empty() {
+            lines.push(Line::from(Span::styled("No directories visited yet", Styles::muted())));
+        }
+        for (i, row) in self.rows.iter().enumerate().skip(skip).take(visible) {
+            let count = format!("×{}", row.count);
+            let stats = format!("{count:>5}  {:>8}", ago(self.now, row.last_visit));
+            let path_width = (inner.width as usize).saturating_sub(stats.chars().count() + 2);
+            let path = truncate_start(&row.path.display().to_string(), path_width);
+            let style = if i == self.cursor {
+                Styles::cursor()
+            } else {
+                Styles::directory()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{path:<path_width$}"), style),
+                Span::raw("  "),
+                Span::styled(stats, Styles::muted()),
+            ]));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Enter go · d forget · Esc close")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+/// How long ago `then` was, e.g. "5m ago".
+fn ago(now: DateTime<Utc>, then: DateTime<Utc>) -> String {
+    let age = (now - then).num_seconds().max(0);
+    match age {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", age / 60),
+        3600..86_400 => format!("{}h ago", age / 3600),
+        _ => format!("{}d ago", age / 86_400),
+    }
+}
+
+/// Shorten `text` to `width` characters, keeping the end.
+fn truncate_start(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        text.to_string()
+    } else {
+        let tail: String = text.chars().skip(len + 1 - width.max(1)).collect();
+        format!("…{tail}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::path::Path;
+
+    fn history() -> VisitHistory {
+        let now = Utc::now();
+        let mut history = VisitHistory::default();
+        history.record_at(Path::new("/rare"), now - Duration::days(10));
+        history.record_at(Path::new("/often"), now);
+        history.record_at(Path::new("/often"), now);
+        history
+    }
+
+    #[test]
+    fn lists_by_frecency_and_picks_rows() {
+        let mut view = HistoryView::new(&history());
+        assert_eq!(view.len(), 2);
+        assert_eq!(view.selected().unwrap(), Path::new("/often"));
+
+        view.handle_key(KeyEvent::from(KeyCode::Down));
+        view.handle_key(KeyEvent::from(KeyCode::Down));
+        let result = view.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(result, HistoryResult::Go(PathBuf::from("/rare")));
+
+        let result = view.handle_key(KeyEvent::from(KeyCode::Char('d')));
+        assert_eq!(result, HistoryResult::Forget(PathBuf::from("/rare")));
+        view.remove_selected();
+        assert_eq!(view.selected().unwrap(), Path::new("/often"));
+
+        assert_eq!(view.handle_key(KeyEvent::from(KeyCode::Esc)), HistoryResult::Closed);
+    }
+
+    #[test]
+    fn renders_rows_with_stats() {
+        let view = HistoryView::new(&history());
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+
+        view.render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let often = rows.iter().position(|row| row.contains("/often")).unwrap();
+        let rare = rows.iter().position(|row| row.contains("/rare")).unwrap();
+        assert!(often < rare);
+        assert!(rows[often].contains("×2") && rows[often].contains("just now"));
+        assert!(rows[rare].contains("10d ago"));
+    }
+
+    #[test]
+    fn truncates_from_the_start() {
+        assert_eq!(truncate_start("short", 10), "short");
+        assert_eq!(truncate_start("C:\\very\\long\\päth", 8), "…ng\\päth");
+    }
+}
@@ -0,0 +1,248 @@
+//! Rename editor drawn in place of an entry's name in the file list.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Widget},
+};
+
+use super::styles::Styles;
+
+/// Result of a key press in the rename editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InlineRenameResult {
+    /// Still editing.
+    Editing,
+    /// Rename the entry to the typed name.
+    Commit(String),
+    /// Editing was abandoned.
+    Cancelled,
+}
+
+/// Inline rename state for one entry.
+///
+/// Starts with the name's stem selected, so typing replaces the name but
+/// keeps the extension. Typing or deleting replaces the selection; arrows
+/// and Home/End drop it.
+#[derive(Debug, Clone)]
+pub struct InlineRename {
+    /// Entry being renamed.
+    path: PathBuf,
+    /// The entry's current name.
+    original: String,
+    /// Name typed so far.
+    value: String,
+    /// Cursor position in characters.
+    cursor: usize,
+    /// Selected characters, replaced by the next edit.
+    selection: Option<Range<usize>>,
+}
+
+impl InlineRename {
+    /// Start renaming `path`, selecting the stem of its name.
+    ///
+    /// Directories and names with no extension (including dotfiles such as
+    /// `.gitignore`) start with the whole name selected.
+    pub fn new(path: PathBuf, is_dir: bool) -> Self {
+        let original = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let len = original.chars().count();
+        let stem = match original.rfind('.') {
+            Some(dot) if dot > 0 && !is_dir => original[..dot].chars().count(),
+            _ => len,
+        };
+        Self {
+            path,
+            value: original.clone(),
+            original,
+            cursor: stem,
+            selection: (stem > 0).then_some(0..stem),
+        }
+    }
+
+    /// The entry being renamed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The entry's name before editing.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The name typed so far.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> InlineRenameResult {
+        let len = self.value.chars().count();
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                return InlineRenameResult::Commit(self.value.clone());
+            }
+            (KeyModifiers::NONE, KeyCode::Esc) => return InlineRenameResult::Cancelled,
+            (KeyModifiers::NONE, KeyCode::Left) => {
+                self.cursor = match self.selection.take() {
+                    Some(selection) => selection.start,
+                    None => self.cursor.saturating_sub(1),
+                };
+            }
+            (KeyModifiers::NONE, KeyCode::Right) => {
+                self.cursor = match self.selection.take() {
+                    Some(selection) => selection.end,
+                    None => (self.cursor + 1).min(len),
+                };
+            }
+            (KeyModifiers::NONE, KeyCode::Home) => {
+                self.selection = None;
+                self.cursor = 0;
+            }
+            (KeyModifiers::NONE, KeyCode::End) => {
+                self.selection = None;
+                self.cursor = len;
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
+                self.selection = (len > 0).then_some(0..len);
+                self.cursor = len;
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace) => self.erase(self.cursor.checked_sub(1)),
+            (KeyModifiers::NONE, KeyCode::Delete) => {
+                self.erase((self.cursor < len).then_some(self.cursor));
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                self.delete_selection();
+                let at = self.byte_index(self.cursor);
+                self.value.insert(at, c);
+                self.cursor += 1;
+            }
+            _ => {}
+        }
+        InlineRenameResult::Editing
+    }
+
+    /// Remove the selected text or, with nothing selected, the character at
+    /// `index`, leaving the cursor in its place.
+    fn erase(&mut self, index: Option<usize>) {
+        if self.delete_selection() {
+            return;
+        }
+        if let Some(index) = index {
+            self.cursor = index;
+            self.value.remove(self.byte_index(index));
+        }
+    }
+
+    /// Remove the selected text, leaving the cursor in its place. Returns
+    /// whether there was a selection.
+    fn delete_selection(&mut self) -> bool {
+        let Some(selection) = self.selection.take() else {
+            return false;
+        };
+        let bytes = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.value.replace_range(bytes, "");
+        self.cursor = selection.start;
+        true
+    }
+
+    /// Byte offset of the character at `index` in the value.
+    fn byte_index(&self, index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(index)
+            .map_or(self.value.len(), |(i, _)| i)
+    }
+
+    /// Render the name being typed over `area`, a single row.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        // Scrolled so the cursor stays visible
+        let width = area.width as usize;
+        let chars: Vec<char> = self.value.chars().chain(std::iter::once(' ')).collect();
+        let start = (self.cursor + 1).saturating_sub(width);
+        let spans: Vec<Span> = chars
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(width)
+            .map(|(i, c)| {
+                let marked = match &self.selection {
+                    Some(selection) => selection.contains(&i),
+                    None => i == self.cursor,
+                };
+                let style = if marked {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Span::styled(c.to_string(), style)
+            })
+            .collect();
+        Clear.render(area, buf);
+        Paragraph::new(Line::from(spans))
+            .style(Styles::normal().patch(Styles::cursor()))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(editor: &mut InlineRename, text: &str) {
+        for c in text.chars() {
+            editor.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn typing_replaces_the_stem() {
+        let mut editor = InlineRename::new(PathBuf::from("report.final.txt"), false);
+        type_text(&mut editor, "summary");
+        assert_eq!(editor.value(), "summary.txt");
+        assert_eq!(
+            editor.handle_key(KeyEvent::from(KeyCode::Enter)),
+            InlineRenameResult::Commit("summary.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn whole_name_selected_without_an_extension() {
+        for (path, is_dir) in [("photos.2024", true), (".gitignore", false), ("Makefile", false)] {
+            let mut editor = InlineRename::new(PathBuf::from(path), is_dir);
+            type_text(&mut editor, "x");
+            assert_eq!(editor.value(), "x", "{path}");
+        }
+    }
+
+    #[test]
+    fn arrows_drop_the_selection() {
+        let mut editor = InlineRename::new(PathBuf::from("naïve.md"), false);
+        editor.handle_key(KeyEvent::from(KeyCode::Right));
+        type_text(&mut editor, "!");
+        assert_eq!(editor.value(), "naïve!.md");
+
+        editor.handle_key(KeyEvent::from(KeyCode::Home));
+        editor.handle_key(KeyEvent::from(KeyCode::Delete));
+        editor.handle_key(KeyEvent::from(KeyCode::End));
+        editor.handle_key(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(editor.value(), "aïve!.m");
+
+        editor.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        editor.handle_key(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(editor.value(), "");
+        assert_eq!(editor.original(), "naïve.md");
+        assert_eq!(
+            editor.handle_key(KeyEvent::from(KeyCode::Esc)),
+            InlineRenameResult::Cancelled
+        );
+    }
+}
@@ -8,6 +8,14 @@ use ratatui::{
 /// Layout areas for the application.
 #[derive(Debug, Clone, Copy)]
 pub struct AppLayout {
+    /// Left pane drive bar area (zero height when drive bars are hidden).
+    pub left_drives: Rect,
+    /// Right pane drive bar area (zero height when drive bars are hidden).
+    pub right_drives: Rect,
+    /// Left pane tab bar area (zero height when tab bars are hidden).
+    pub left_tabs: Rect,
+    /// Right pane tab bar area (zero height when tab bars are hidden).
+    pub right_tabs: Rect,
     /// Left pane header area.
     pub left_header: Rect,
     /// Right pane header area.
@@ -23,13 +31,30 @@ pub struct AppLayout {
 impl AppLayout {
     /// Create layout from the terminal frame.
     pub fn new(frame: &Frame) -> Self {
+        Self::with_tab_bars(frame, false)
+    }
+
+    /// Create layout from the terminal frame, reserving a row above each
+    /// pane header for its tab bar when `show_tabs` is set.
+    pub fn with_tab_bars(frame: &Frame, show_tabs: bool) -> Self {
+        Self::split(frame.area(), show_tabs, false)
+    }
+
+    /// Create layout from the terminal frame, reserving rows above each pane
+    /// header for its drive bar when `show_drives` is set and its tab bar
+    /// when `show_tabs` is set.
+    pub fn with_bars(frame: &Frame, show_tabs: bool, show_drives: bool) -> Self {
+        Self::split(frame.area(), show_tabs, show_drives)
+    }
+
+    fn split(area: Rect, show_tabs: bool, show_drives: bool) -> Self {
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(1),    // Content + headers
                 Constraint::Length(1), // Status bar
             ])
-            .split(frame.area());
+            .split(area);
 
         // Split content area into left and right panes
         let pane_chunks = Layout::default()
@@ -37,22 +62,26 @@ impl AppLayout {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(main_chunks[0]);
 
-        // Split each pane into header + content
-        let left_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Min(1)])
-            .split(pane_chunks[0]);
-
-        let right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Min(1)])
-            .split(pane_chunks[1]);
+        // Split each pane into drive bar + tab bar + header + content
+        let tab_height = u16::from(show_tabs);
+        let pane_layout = Layout::default().direction(Direction::Vertical).constraints([
+            Constraint::Length(u16::from(show_drives)),
+            Constraint::Length(tab_height),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ]);
+        let left_chunks = pane_layout.split(pane_chunks[0]);
+        let right_chunks = pane_layout.split(pane_chunks[1]);
 
         Self {
-            left_header: left_chunks[0],
-            right_header: right_chunks[0],
-            left_content: left_chunks[1],
-            right_content: right_chunks[1],
+            left_drives: left_chunks[0],
+            right_drives: right_chunks[0],
+            left_tabs: left_chunks[1],
+            right_tabs: right_chunks[1],
+            left_header: left_chunks[2],
+            right_header: right_chunks[2],
+            left_content: left_chunks[3],
+            right_content: right_chunks[3],
             status: main_chunks[1],
         }
     }
@@ -81,11 +110,13 @@ impl AppLayout {
 
     /// Get single pane (full width) - combines both panes.
     pub fn single_pane(&self) -> Rect {
+        // Include the drive bar, tab bar and header rows
+        let top = self.left_drives.y;
         Rect {
             x: self.left_content.x,
-            y: self.left_content.y.saturating_sub(1), // Include header
+            y: top,
             width: self.left_content.width + self.right_content.width,
-            height: self.left_content.height + 1,
+            height: self.left_content.bottom() - top,
         }
     }
 }
@@ -122,4 +153,32 @@ mod tests {
     fn pane_default_is_left() {
         assert_eq!(Pane::default(), Pane::Left);
     }
+
+    #[test]
+    fn tab_bars_take_a_row_above_headers() {
+        let area = Rect::new(0, 0, 80, 24);
+
+        let plain = AppLayout::split(area, false, false);
+        assert_eq!(plain.left_tabs.height, 0);
+        assert_eq!(plain.left_header.y, 0);
+        assert_eq!(plain.single_pane(), Rect::new(0, 0, 80, 23));
+
+        let tabbed = AppLayout::split(area, true, false);
+        assert_eq!(tabbed.right_tabs, Rect::new(40, 0, 40, 1));
+        assert_eq!(tabbed.right_header.y, 1);
+        assert_eq!(tabbed.left_content.y, 2);
+        assert_eq!(tabbed.single_pane(), Rect::new(0, 0, 80, 23));
+    }
+
+    #[test]
+    fn drive_bars_take_the_top_row() {
+        let area = Rect::new(0, 0, 80, 24);
+
+        let layout = AppLayout::split(area, true, true);
+        assert_eq!(layout.left_drives, Rect::new(0, 0, 40, 1));
+        assert_eq!(layout.right_tabs.y, 1);
+        assert_eq!(layout.right_header.y, 2);
+        assert_eq!(layout.left_content.y, 3);
+        assert_eq!(layout.single_pane(), Rect::new(0, 0, 80, 23));
+    }
 }
@@ -0,0 +1,313 @@
+//! Location bar for going to a typed or pasted path.
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::complete_path;
+
+use super::styles::Styles;
+
+/// Most suggestions listed under the input line.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Result of a key press in the location bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocationResult {
+    /// The bar is still open.
+    Open,
+    /// Go to the typed path (trimmed, not yet expanded).
+    Go(String),
+    /// The bar was dismissed.
+    Cancelled,
+}
+
+/// Location bar state.
+///
+/// Tab cycles through the directories the last path component could name
+/// (Shift+Tab goes back); once a single match is left, Tab descends into it.
+/// Up and Down walk the history of typed paths.
+#[derive(Debug, Clone)]
+pub struct LocationBar {
+    /// Text typed so far.
+    value: String,
+    /// Cursor position in characters.
+    cursor: usize,
+    /// Directory relative paths are resolved against.
+    base: PathBuf,
+    /// Previously typed paths, most recent first.
+    history: Vec<String>,
+    /// History entry shown, and the text typed before browsing history.
+    history_pos: Option<(usize, String)>,
+    /// Completions of the text before Tab was first pressed.
+    completions: Vec<String>,
+    /// Completion shown.
+    completion_pos: Option<usize>,
+}
+
+impl LocationBar {
+    /// Create an empty location bar resolving relative paths against `base`.
+    pub fn new(base: PathBuf, history: Vec<String>) -> Self {
+        Self {
+            value: String::new(),
+            cursor: 0,
+            base,
+            history,
+            history_pos: None,
+            completions: Vec::new(),
+            completion_pos: None,
+        }
+    }
+
+    /// The text typed so far.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> LocationResult {
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Enter) => {
+                return LocationResult::Go(self.value.trim().to_string());
+            }
+            (KeyModifiers::NONE, KeyCode::Esc) => return LocationResult::Cancelled,
+            (KeyModifiers::NONE, KeyCode::Tab) => self.complete(true),
+            (_, KeyCode::BackTab) => self.complete(false),
+            (KeyModifiers::NONE, KeyCode::Up) => self.recall(true),
+            (KeyModifiers::NONE, KeyCode::Down) => self.recall(false),
+            (KeyModifiers::NONE, KeyCode::Left) => self.cursor = self.cursor.saturating_sub(1),
+            (KeyModifiers::NONE, KeyCode::Right) => {
+                self.cursor = (self.cursor + 1).min(self.value.chars().count());
+            }
+            (KeyModifiers::NONE, KeyCode::Home) => self.cursor = 0,
+            (KeyModifiers::NONE, KeyCode::End) => self.cursor = self.value.chars().count(),
+            (KeyModifiers::NONE, KeyCode::Backspace) if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.value.remove(self.byte_index());
+                self.edited();
+            }
+            (KeyModifiers::NONE, KeyCode::Delete) if self.cursor < self.value.chars().count() => {
+                self.value.remove(self.byte_index());
+                self.edited();
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                self.set_value(String::new());
+                self.edited();
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                let at = self.byte_index();
+                self.value.insert(at, c);
+                self.cursor += 1;
+                self.edited();
+            }
+            _ => {}
+        }
+        LocationResult::Open
+    }
+
+    /// Byte offset of the cursor in the value.
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.value.len(), |(i, _)| i)
+    }
+
+    /// Replace the value, with the cursor at the end.
+    fn set_value(&mut self, value: String) {
+        self.cursor = value.chars().count();
+        self.value = value;
+    }
+
+    /// Forget completions and history browsing after the text changed.
+    fn edited(&mut self) {
+        self.completions.clear();
+        self.completion_pos = None;
+        self.history_pos = None;
+    }
+
+    /// Show the next (or previous) completion of the typed text.
+    fn complete(&mut self, forward: bool) {
+        // A single match has nothing to cycle through; go on into it
+        if self.completions.len() <= 1 {
+            self.completions = complete_path(&self.value, &self.base);
+            self.completion_pos = None;
+        }
+        self.history_pos = None;
+
+        let count = self.completions.len();
+        if count == 0 {
+            return;
+        }
+        let pos = match (self.completion_pos, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(pos), true) => (pos + 1) % count,
+            (Some(pos), false) => (pos + count - 1) % count,
+        };
+        self.completion_pos = Some(pos);
+        self.set_value(self.completions[pos].clone());
+    }
+
+    /// Show an older (or newer) typed path.
+    fn recall(&mut self, older: bool) {
+        self.completions.clear();
+        self.completion_pos = None;
+
+        let (pos, typed) = match (self.history_pos.take(), older) {
+            (None, true) if !self.history.is_empty() => (0, self.value.clone()),
+            (None, _) => return,
+            (Some((pos, typed)), true) => ((pos + 1).min(self.history.len() - 1), typed),
+            (Some((0, typed)), false) => {
+                // Back past the newest entry to what was typed
+                self.set_value(typed);
+                return;
+            }
+            (Some((pos, typed)), false) => (pos - 1, typed),
+        };
+        self.set_value(self.history[pos].clone());
+        self.history_pos = Some((pos, typed));
+    }
+
+    /// Suggestions listed under the input, and which one is shown.
+    fn suggestions(&self) -> (&[String], Option<usize>) {
+        if !self.completions.is_empty() {
+            (&self.completions, self.completion_pos)
+        } else {
+            (&self.history, self.history_pos.as_ref().map(|(pos, _)| *pos))
+        }
+    }
+
+    /// Render the location bar near the top of `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let (suggestions, current) = self.suggestions();
+        // Keep the shown suggestion in view
+        let skip = current.map_or(0, |pos| (pos + 1).saturating_sub(MAX_SUGGESTIONS));
+        let listed = suggestions.len().saturating_sub(skip).min(MAX_SUGGESTIONS);
+
+        let width = area.width.clamp(40, 90).min(area.width);
+        let height = (listed as u16 + 4).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height / 6).min(area.height.saturating_sub(height));
+        let bar_area = Rect::new(x, y, width, height);
+
+        Clear.render(bar_area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Styles::active_border())
+            .title(format!(" Go To ({}) ", self.base.display()));
+        let inner = block.inner(bar_area);
+        block.render(bar_area, buf);
+
+        // Input line, scrolled so the cursor stays visible
+        let width = inner.width.saturating_sub(2) as usize;
+        let chars: Vec<char> = self.value.chars().chain(std::iter::once(' ')).collect();
+        let start = (self.cursor + 1).saturating_sub(width);
+        let mut spans = vec![Span::styled("› ", Styles::accent())];
+        for (i, c) in chars.iter().enumerate().skip(start).take(width) {
+            let style = if i == self.cursor {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        let mut lines = vec![Line::from(spans)];
+
+        for (i, suggestion) in suggestions.iter().enumerate().skip(skip).take(listed) {
+            let style = if Some(i) == current {
+                Styles::cursor()
+            } else {
+                Styles::muted()
+            };
+            lines.push(Line::from(Span::styled(format!("  {suggestion}"), style)));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Tab complete · ↑↓ history · Enter go · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::MAIN_SEPARATOR;
+    use tempfile::TempDir;
+
+    fn type_text(bar: &mut LocationBar, text: &str) {
+        for c in text.chars() {
+            bar.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn tab_cycles_completions_then_descends() {
+        let temp = TempDir::new().unwrap();
+        for dir in ["alpha", "alps", "alpha/inner"] {
+            std::fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        let sep = MAIN_SEPARATOR;
+        let mut bar = LocationBar::new(temp.path().to_path_buf(), Vec::new());
+        type_text(&mut bar, "al");
+
+        bar.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(bar.value(), format!("alpha{sep}"));
+        bar.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(bar.value(), format!("alps{sep}"));
+        bar.handle_key(KeyEvent::from(KeyCode::BackTab));
+        assert_eq!(bar.value(), format!("alpha{sep}"));
+
+        // Typing starts over from the new text
+        type_text(&mut bar, "i");
+        bar.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(bar.value(), format!("alpha{sep}inner{sep}"));
+        bar.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(bar.value(), format!("alpha{sep}inner{sep}"));
+
+        let result = bar.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(result, LocationResult::Go(format!("alpha{sep}inner{sep}")));
+    }
+
+    #[test]
+    fn arrows_walk_history() {
+        let history = vec!["newest".to_string(), "oldest".to_string()];
+        let mut bar = LocationBar::new(PathBuf::from("."), history);
+        type_text(&mut bar, "typed");
+
+        bar.handle_key(KeyEvent::from(KeyCode::Up));
+        assert_eq!(bar.value(), "newest");
+        bar.handle_key(KeyEvent::from(KeyCode::Up));
+        bar.handle_key(KeyEvent::from(KeyCode::Up));
+        assert_eq!(bar.value(), "oldest");
+        bar.handle_key(KeyEvent::from(KeyCode::Down));
+        bar.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(bar.value(), "typed");
+        bar.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(bar.value(), "typed");
+    }
+
+    #[test]
+    fn editing_handles_multibyte_text() {
+        let mut bar = LocationBar::new(PathBuf::from("."), Vec::new());
+        type_text(&mut bar, "dé");
+        bar.handle_key(KeyEvent::from(KeyCode::Left));
+        type_text(&mut bar, "x");
+        assert_eq!(bar.value(), "dxé");
+        bar.handle_key(KeyEvent::from(KeyCode::Delete));
+        bar.handle_key(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(bar.value(), "d");
+        bar.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(bar.value(), "");
+        assert_eq!(bar.handle_key(KeyEvent::from(KeyCode::Esc)), LocationResult::Cancelled);
+    }
+}
@@ -3,26 +3,70 @@
 //! This module contains all the widgets and rendering logic
 //! for the TUI interface.
 
+pub mod checksum;
+pub mod compare;
+pub mod convert;
 pub mod conflict;
+pub mod context_menu;
+pub mod copy_menu;
 pub mod dialog;
+pub mod dir_tree;
+pub mod drive_bar;
+pub mod drive_details;
+pub mod drive_menu;
+pub mod editor;
 pub mod file_list;
+pub mod filter_builder;
 pub mod header;
 pub mod help;
+pub mod history;
+pub mod inline_rename;
 pub mod layout;
+pub mod location_bar;
+pub mod open_with;
 pub mod properties;
+pub mod properties_editor;
+pub mod report_history;
 pub mod sidebar;
 pub mod status_bar;
 pub mod styles;
+pub mod tab_bar;
+pub mod theme;
 pub mod transfers;
+pub mod viewer;
 
+pub use checksum::{ChecksumResult, ChecksumView};
+pub use compare::{CompareResult, CompareView};
+pub use convert::{ConvertResult, ConvertView};
 pub use conflict::{ConflictInfo, ConflictModal, ConflictResolution, ConflictResult};
-pub use dialog::{Dialog, DialogKind, DialogResult, SortField};
+pub use context_menu::{
+    Applies, ContextAction, ContextMenu, ContextMenuResult, ContextTargets, CONTEXT_ACTIONS,
+};
+pub use copy_menu::{CopyMenu, CopyMenuResult};
+pub use dialog::{Dialog, DialogKind, DialogResult, SortField, SortOption};
+pub use dir_tree::{DirTree, TreeNode};
+pub use drive_bar::DriveBar;
+pub use drive_details::DriveDetails;
+pub use drive_menu::{DriveMenu, DriveMenuResult};
+pub use editor::{TextEditor, TextEditorResult};
 pub use file_list::FileList;
+pub use filter_builder::{FilterBuilder, FilterBuilderResult};
 pub use header::Header;
 pub use help::{handle_help_key, HelpScreen};
+pub use history::{HistoryResult, HistoryView};
+pub use inline_rename::{InlineRename, InlineRenameResult};
 pub use layout::{AppLayout, Pane};
-pub use properties::{handle_properties_key, PropertiesPanel};
+pub use location_bar::{LocationBar, LocationResult};
+pub use open_with::{OpenWithMenu, OpenWithResult};
+pub use properties::{
+    handle_properties_key, is_edit_key, is_tab_key, is_take_ownership_key, PropertiesPanel,
+};
+pub use properties_editor::{EditorResult, PropertiesEditor};
+pub use report_history::{ReportHistory, ReportHistoryResult};
 pub use sidebar::{Sidebar, SidebarSection, SidebarState};
-pub use status_bar::StatusBar;
+pub use status_bar::{JobsSummary, StatusBar};
 pub use styles::Styles;
-pub use transfers::{TransferStatus, TransfersView};
+pub use tab_bar::TabBar;
+pub use theme::Theme;
+pub use transfers::{ForegroundProgress, JobDetails, TransferStatus, TransfersView};
+pub use viewer::{FileViewer, ViewerMode, ViewerResult};
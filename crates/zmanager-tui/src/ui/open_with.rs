@@ -0,0 +1,176 @@
+//! "Open with" menu listing the applications registered for a file.
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_transfer_win::OpenWithHandler;
+
+use super::styles::Styles;
+
+/// Result of a key press in the open-with menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenWithResult {
+    /// The menu is still open.
+    Open,
+    /// Open the file with the chosen application.
+    Launch(OpenWithHandler),
+    /// Let the user pick another program in the system dialog.
+    ChooseOther,
+    /// The menu was dismissed.
+    Closed,
+}
+
+/// Open-with menu state.
+///
+/// Lists the registered applications, default first, followed by a row
+/// for choosing another program.
+#[derive(Debug, Clone)]
+pub struct OpenWithMenu {
+    /// File to open.
+    path: PathBuf,
+    /// Registered applications.
+    handlers: Vec<OpenWithHandler>,
+    /// Highlighted row; one past the handlers is "Choose another app".
+    cursor: usize,
+}
+
+impl OpenWithMenu {
+    /// Offer `handlers` for opening `path`.
+    pub fn new(path: PathBuf, handlers: Vec<OpenWithHandler>) -> Self {
+        Self {
+            path,
+            handlers,
+            cursor: 0,
+        }
+    }
+
+    /// The file being opened.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> OpenWithResult {
+        let last = self.handlers.len();
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+                return OpenWithResult::Closed;
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => return self.choose(self.cursor),
+            (KeyModifiers::NONE, KeyCode::Char(c @ '1'..='9')) => {
+                let index = c as usize - '1' as usize;
+                if index <= last {
+                    return self.choose(index);
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) => {
+                self.cursor = (self.cursor + 1).min(last);
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) => self.cursor = 0,
+            (_, KeyCode::End | KeyCode::Char('G')) => self.cursor = last,
+            _ => {}
+        }
+        OpenWithResult::Open
+    }
+
+    fn choose(&self, index: usize) -> OpenWithResult {
+        match self.handlers.get(index) {
+            Some(handler) => OpenWithResult::Launch(handler.clone()),
+            None => OpenWithResult::ChooseOther,
+        }
+    }
+
+    /// Render the menu centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let rows = self.handlers.len() + 1;
+        let width = 50.min(area.width.saturating_sub(4));
+        let height = (rows as u16 + 3).clamp(6, 20).min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let block = Block::default()
+            .title(format!(" Open {name} with "))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        // Rows above the hint line, scrolled to keep the cursor in view
+        let visible = inner.height.saturating_sub(1) as usize;
+        let skip = (self.cursor + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = Vec::new();
+        for i in (0..rows).skip(skip).take(visible) {
+            let key = if i < 9 { format!("{} ", i + 1) } else { "  ".to_string() };
+            let label = match self.handlers.get(i) {
+                Some(handler) if handler.is_default => format!("{} (default)", handler.name),
+                Some(handler) => handler.name.clone(),
+                None => "Choose another app…".to_string(),
+            };
+            let style = if i == self.cursor {
+                Styles::cursor()
+            } else if i == self.handlers.len() {
+                Styles::muted()
+            } else {
+                Styles::normal()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(key, Styles::muted()),
+                Span::styled(label, style),
+            ]));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Enter open · 1-9 pick · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(name: &str, class: &str) -> OpenWithHandler {
+        OpenWithHandler {
+            name: name.to_string(),
+            class: class.to_string(),
+            is_default: false,
+        }
+    }
+
+    fn press(menu: &mut OpenWithMenu, code: KeyCode) -> OpenWithResult {
+        menu.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn picks_a_handler_or_another_app() {
+        let notepad = handler("Notepad", "txtfile");
+        let code = handler("Visual Studio Code", r"Applications\Code.exe");
+        let mut menu = OpenWithMenu::new(PathBuf::from("notes.txt"), vec![notepad, code.clone()]);
+
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Enter), OpenWithResult::Launch(code));
+        // The row after the handlers, and past the end of the list
+        press(&mut menu, KeyCode::Down);
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Enter), OpenWithResult::ChooseOther);
+        assert_eq!(press(&mut menu, KeyCode::Char('3')), OpenWithResult::ChooseOther);
+        assert_eq!(press(&mut menu, KeyCode::Char('4')), OpenWithResult::Open);
+        assert_eq!(press(&mut menu, KeyCode::Esc), OpenWithResult::Closed);
+    }
+}
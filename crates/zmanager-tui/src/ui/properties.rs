@@ -5,21 +5,62 @@ use std::path::Path;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
-use zmanager_core::Properties;
+use zmanager_core::{entry::format_size, MultiProperties, Properties, SecurityInfo};
+
+use super::styles::Styles;
+
+/// Alternate data streams listed before the rest are summarized.
+const MAX_STREAMS_SHOWN: usize = 3;
+
+/// What the properties panel describes.
+enum Subject<'a> {
+    /// A single file or folder.
+    Single(&'a Properties),
+    /// Several selected items together.
+    Multiple(&'a MultiProperties),
+}
 
 /// Properties panel widget.
 pub struct PropertiesPanel<'a> {
-    properties: &'a Properties,
+    subject: Subject<'a>,
+    calculating: bool,
+    security: Option<&'a Result<SecurityInfo, String>>,
 }
 
 impl<'a> PropertiesPanel<'a> {
     /// Create a new properties panel.
     pub fn new(properties: &'a Properties) -> Self {
-        Self { properties }
+        Self {
+            subject: Subject::Single(properties),
+            calculating: false,
+            security: None,
+        }
+    }
+
+    /// Create a properties panel for several selected items.
+    pub fn multiple(properties: &'a MultiProperties) -> Self {
+        Self {
+            subject: Subject::Multiple(properties),
+            calculating: false,
+            security: None,
+        }
+    }
+
+    /// Mark the folder totals as still being calculated.
+    pub fn calculating(mut self, calculating: bool) -> Self {
+        self.calculating = calculating;
+        self
+    }
+
+    /// Show the Security tab with the owner and access entries instead
+    /// of the general details.
+    pub fn security(mut self, security: Option<&'a Result<SecurityInfo, String>>) -> Self {
+        self.security = security;
+        self
     }
 }
 
@@ -41,25 +82,47 @@ impl Widget for PropertiesPanel<'_> {
 
         Clear.render(modal_area, buf);
 
-        let title = format!(" {} ", self.properties.name);
+        let title = match self.subject {
+            Subject::Single(properties) if self.security.is_some() => {
+                format!(" {} - Security ", properties.name)
+            }
+            Subject::Single(properties) => format!(" {} ", properties.name),
+            Subject::Multiple(properties) => format!(" {} items ", properties.item_count),
+        };
         let block = Block::default()
             .title(title)
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
+            .border_style(Styles::accent());
 
         let inner = block.inner(modal_area);
         block.render(modal_area, buf);
 
+        let properties = match self.subject {
+            Subject::Single(properties) => properties,
+            Subject::Multiple(properties) => {
+                let mut lines = multiple_lines(properties, self.calculating);
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled("Press any key to close", Styles::hint())));
+                Paragraph::new(lines).render(inner, buf);
+                return;
+            }
+        };
+
+        if let Some(security) = self.security {
+            Paragraph::new(security_lines(security, inner.width as usize)).render(inner, buf);
+            return;
+        }
+
         // Build properties content
-        let label_style = Style::default().fg(Color::DarkGray);
+        let label_style = Styles::muted();
         let value_style = Style::default();
-        let highlight_style = Style::default().fg(Color::Yellow);
+        let highlight_style = Styles::highlight();
 
         let mut lines: Vec<Line> = Vec::new();
 
         // Type
-        let is_dir = self.properties.kind.is_directory();
+        let is_dir = properties.kind.is_directory();
         let type_icon = if is_dir { "📁" } else { "📄" };
         let type_name = if is_dir { "Folder" } else { "File" };
         lines.push(Line::from(vec![
@@ -70,7 +133,7 @@ impl Widget for PropertiesPanel<'_> {
         ]));
 
         // Location
-        let parent = Path::new(&self.properties.path)
+        let parent = Path::new(&properties.path)
             .parent()
             .map(|p| p.display().to_string())
             .unwrap_or_default();
@@ -82,19 +145,26 @@ impl Widget for PropertiesPanel<'_> {
         lines.push(Line::from(""));
 
         // Size
-        let size_display = self.properties.size_display();
-        let size_bytes = self.properties.size.unwrap_or(0);
-        lines.push(Line::from(vec![
+        let size_display = properties.size_display();
+        let size_bytes = properties.size.unwrap_or(0);
+        let mut size_line = vec![
             Span::styled("Size:         ", label_style),
             Span::styled(&size_display, highlight_style),
             Span::styled(
                 format!(" ({} bytes)", format_bytes_with_commas(size_bytes)),
-                Style::default().fg(Color::DarkGray),
+                Styles::muted(),
             ),
-        ]));
+        ];
+        if self.calculating {
+            size_line.push(Span::styled(
+                " calculating...",
+                Styles::hint(),
+            ));
+        }
+        lines.push(Line::from(size_line));
 
         // Folder contents summary
-        if let Some(summary) = self.properties.contents_summary() {
+        if let Some(summary) = properties.contents_summary() {
             lines.push(Line::from(vec![
                 Span::styled("Contains:     ", label_style),
                 Span::styled(summary, value_style),
@@ -104,14 +174,14 @@ impl Widget for PropertiesPanel<'_> {
         lines.push(Line::from(""));
 
         // Dates
-        if let Some(created) = self.properties.created_display() {
+        if let Some(created) = properties.created_display() {
             lines.push(Line::from(vec![
                 Span::styled("Created:      ", label_style),
                 Span::styled(created, value_style),
             ]));
         }
 
-        if let Some(modified) = self.properties.modified_display() {
+        if let Some(modified) = properties.modified_display() {
             lines.push(Line::from(vec![
                 Span::styled("Modified:     ", label_style),
                 Span::styled(modified, value_style),
@@ -122,16 +192,16 @@ impl Widget for PropertiesPanel<'_> {
 
         // Attributes
         let mut attrs = Vec::new();
-        if self.properties.readonly {
+        if properties.readonly {
             attrs.push("Read-only");
         }
-        if self.properties.hidden {
+        if properties.hidden {
             attrs.push("Hidden");
         }
-        if self.properties.system {
+        if properties.system {
             attrs.push("System");
         }
-        if self.properties.link_target.is_some() {
+        if properties.link_target.is_some() {
             attrs.push("Symlink");
         }
 
@@ -143,7 +213,7 @@ impl Widget for PropertiesPanel<'_> {
         }
 
         // MIME type
-        if let Some(ref mime) = self.properties.mime_type {
+        if let Some(ref mime) = properties.mime_type {
             lines.push(Line::from(vec![
                 Span::styled("MIME Type:    ", label_style),
                 Span::styled(mime, value_style),
@@ -151,19 +221,42 @@ impl Widget for PropertiesPanel<'_> {
         }
 
         // Symlink target
-        if let Some(ref target) = self.properties.link_target {
+        if let Some(ref target) = properties.link_target {
             lines.push(Line::from(vec![
                 Span::styled("Target:       ", label_style),
                 Span::styled(target.display().to_string(), value_style),
             ]));
         }
 
+        // Alternate data streams
+        let streams = &properties.alternate_streams;
+        for (i, stream) in streams.iter().take(MAX_STREAMS_SHOWN).enumerate() {
+            let label = if i == 0 { "Streams:      " } else { "              " };
+            lines.push(Line::from(vec![
+                Span::styled(label, label_style),
+                Span::styled(stream.name.as_str(), value_style),
+                Span::styled(
+                    format!(" ({})", format_size(stream.size)),
+                    Styles::muted(),
+                ),
+            ]));
+        }
+        if streams.len() > MAX_STREAMS_SHOWN {
+            lines.push(Line::from(vec![
+                Span::styled("              ", label_style),
+                Span::styled(
+                    format!("and {} more", streams.len() - MAX_STREAMS_SHOWN),
+                    Styles::muted(),
+                ),
+            ]));
+        }
+
         lines.push(Line::from(""));
         
         // Footer
         lines.push(Line::from(Span::styled(
-            "Press any key to close",
-            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            "e edit · Tab security · any other key to close",
+            Styles::hint(),
         )));
 
         let paragraph = Paragraph::new(lines);
@@ -171,6 +264,153 @@ impl Widget for PropertiesPanel<'_> {
     }
 }
 
+/// Build the panel lines for several selected items.
+fn multiple_lines(properties: &MultiProperties, calculating: bool) -> Vec<Line<'static>> {
+    let label_style = Styles::muted();
+    let mut lines: Vec<Line> = Vec::new();
+
+    lines.push(Line::from(vec![
+        Span::styled("Selected:     ", label_style),
+        Span::raw(format!(
+            "{} files, {} folders",
+            properties.selected_files, properties.selected_folders
+        )),
+    ]));
+    if properties.unreadable > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("Unreadable:   ", label_style),
+            Span::styled(format!("{} item(s)", properties.unreadable), Styles::error()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+
+    let mut size_line = vec![
+        Span::styled("Size:         ", label_style),
+        Span::styled(properties.size_display(), Styles::highlight()),
+        Span::styled(
+            format!(" ({} bytes)", format_bytes_with_commas(properties.total_size)),
+            Styles::muted(),
+        ),
+    ];
+    if calculating {
+        size_line.push(Span::styled(" calculating...", Styles::hint()));
+    }
+    lines.push(Line::from(size_line));
+    lines.push(Line::from(vec![
+        Span::styled("Contains:     ", label_style),
+        Span::raw(properties.contents_summary()),
+    ]));
+
+    lines.push(Line::from(""));
+
+    if let Some(modified) = properties.modified_range_display() {
+        lines.push(Line::from(vec![
+            Span::styled("Modified:     ", label_style),
+            Span::raw(modified),
+        ]));
+    }
+
+    // Attributes every item has, and those only some have
+    let flags = [
+        ("Read-only", properties.readonly),
+        ("Hidden", properties.hidden),
+        ("System", properties.system),
+        ("Archive", properties.archive),
+    ];
+    let attrs: Vec<String> = flags
+        .iter()
+        .filter_map(|(name, state)| match state {
+            Some(true) => Some(name.to_string()),
+            Some(false) => None,
+            None => Some(format!("{name} (some)")),
+        })
+        .collect();
+    if !attrs.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Attributes:   ", label_style),
+            Span::raw(attrs.join(", ")),
+        ]));
+    }
+
+    lines
+}
+
+/// Build the Security tab lines: the owner, then one line per access entry.
+fn security_lines(security: &Result<SecurityInfo, String>, width: usize) -> Vec<Line<'static>> {
+    let label_style = Styles::muted();
+    let mut lines: Vec<Line> = Vec::new();
+
+    match security {
+        Ok(info) => {
+            lines.push(Line::from(vec![
+                Span::styled("Owner:        ", label_style),
+                Span::styled(
+                    info.owner.clone().unwrap_or_else(|| "Unknown".to_string()),
+                    Styles::highlight(),
+                ),
+            ]));
+            lines.push(Line::from(""));
+            if info.entries.is_empty() {
+                lines.push(Line::from(Span::styled("No access entries", Styles::muted())));
+            } else {
+                lines.push(Line::from(Span::styled("Access:", label_style)));
+            }
+            for entry in &info.entries {
+                let (kind, kind_style) = if entry.allow {
+                    ("Allow ", Styles::success())
+                } else {
+                    ("Deny  ", Styles::error())
+                };
+                let mut spans = vec![
+                    Span::raw("  "),
+                    Span::styled(kind, kind_style),
+                    Span::raw(format!("{:<15}", entry.access.label())),
+                    Span::raw(truncate_path(&entry.trustee, width.saturating_sub(23).max(8))),
+                ];
+                if entry.inherited {
+                    spans.push(Span::styled(" (inherited)", Styles::hint()));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        Err(message) => {
+            lines.push(Line::from(vec![
+                Span::styled("Security:     ", label_style),
+                Span::styled(message.clone(), Styles::error()),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "o take ownership · Tab general · any other key to close",
+        Styles::hint(),
+    )));
+    lines
+}
+
+/// Check if a key switches between the General and Security tabs.
+pub fn is_tab_key(key: crossterm::event::KeyEvent) -> bool {
+    use crossterm::event::KeyCode;
+
+    key.code == KeyCode::Tab
+}
+
+/// Check if a key takes ownership from the Security tab.
+pub fn is_take_ownership_key(key: crossterm::event::KeyEvent) -> bool {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    key.code == KeyCode::Char('o') && key.modifiers == KeyModifiers::NONE
+}
+
+/// Check if a key opens the editor from the properties panel.
+pub fn is_edit_key(key: crossterm::event::KeyEvent) -> bool {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    key.code == KeyCode::Char('e') && key.modifiers == KeyModifiers::NONE
+}
+
 /// Handle key input for properties panel.
 /// Returns true if the panel should be closed.
 pub fn handle_properties_key(_key: crossterm::event::KeyEvent) -> bool {
@@ -220,6 +460,108 @@ mod tests {
         assert_eq!(truncate_path("C:\\very\\long\\path\\to\\file", 15), "...path\\to\\file");
     }
 
+    #[test]
+    fn panel_lists_alternate_streams() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("download.txt");
+        std::fs::write(&file, "content").unwrap();
+        let mut properties = zmanager_core::get_properties(&file).unwrap();
+        properties.alternate_streams = (0..5)
+            .map(|i| zmanager_core::AlternateStream {
+                name: format!("stream{i}"),
+                size: 26,
+            })
+            .collect();
+
+        let area = Rect::new(0, 0, 64, 24);
+        let mut buf = Buffer::empty(area);
+        PropertiesPanel::new(&properties).render(area, &mut buf);
+
+        let text: String = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("Streams:      stream0 (26 B)"));
+        assert!(text.contains("stream2"));
+        assert!(!text.contains("stream3"));
+        assert!(text.contains("and 2 more"));
+    }
+
+    #[test]
+    fn panel_summarizes_several_items() {
+        let properties = MultiProperties {
+            item_count: 3,
+            selected_files: 2,
+            selected_folders: 1,
+            total_size: 2048,
+            file_count: 7,
+            folder_count: 2,
+            readonly: None,
+            hidden: Some(true),
+            system: Some(false),
+            archive: Some(false),
+            ..Default::default()
+        };
+
+        let area = Rect::new(0, 0, 64, 24);
+        let mut buf = Buffer::empty(area);
+        PropertiesPanel::multiple(&properties).calculating(true).render(area, &mut buf);
+
+        let text: String = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains(" 3 items "));
+        assert!(text.contains("Selected:     2 files, 1 folders"));
+        assert!(text.contains("2.00 KB (2,048 bytes) calculating..."));
+        assert!(text.contains("Contains:     7 files, 2 folders"));
+        assert!(text.contains("Attributes:   Read-only (some), Hidden"));
+        assert!(!text.contains("Unreadable"));
+    }
+
+    #[test]
+    fn security_tab_lists_owner_and_entries() {
+        use zmanager_core::{AccessEntry, AccessLevel};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("report.txt");
+        std::fs::write(&file, "content").unwrap();
+        let properties = zmanager_core::get_properties(&file).unwrap();
+        let security = Ok(SecurityInfo {
+            owner: Some("BUILTIN\\Administrators".to_string()),
+            entries: vec![
+                AccessEntry {
+                    trustee: "NT AUTHORITY\\SYSTEM".to_string(),
+                    allow: true,
+                    access: AccessLevel::FullControl,
+                    inherited: true,
+                },
+                AccessEntry {
+                    trustee: "Everyone".to_string(),
+                    allow: false,
+                    access: AccessLevel::Write,
+                    inherited: false,
+                },
+            ],
+        });
+
+        let area = Rect::new(0, 0, 64, 24);
+        let mut buf = Buffer::empty(area);
+        PropertiesPanel::new(&properties)
+            .security(Some(&security))
+            .render(area, &mut buf);
+
+        let text: String = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains(" report.txt - Security "));
+        assert!(text.contains("Owner:        BUILTIN\\Administrators"));
+        assert!(text.contains("Allow Full control   NT AUTHORITY\\SYSTEM (inherited)"));
+        assert!(text.contains("Deny  Write          Everyone"));
+        assert!(!text.contains("Size:"));
+    }
+
     #[test]
     fn properties_panel_closes_on_any_key() {
         use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -0,0 +1,274 @@
+//! Editor for a file's attributes and timestamps.
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use zmanager_core::{Properties, PropertyChanges};
+
+use super::styles::Styles;
+
+/// Format timestamps are shown and typed in.
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Attribute checkboxes, in field order.
+const FLAGS: [&str; 3] = ["Read-only", "Hidden", "System"];
+
+/// Timestamp fields, after the checkboxes.
+const TIMES: [&str; 2] = ["Created", "Modified"];
+
+/// Result of a key press in the properties editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditorResult {
+    /// The editor is still open.
+    Open,
+    /// Apply the changes to the path.
+    Apply(PathBuf, PropertyChanges),
+    /// The editor was dismissed.
+    Cancelled,
+}
+
+/// Properties editor state.
+///
+/// Space toggles an attribute; timestamps are typed as
+/// `YYYY-MM-DD HH:MM:SS` in local time. Only fields that were changed are
+/// applied.
+#[derive(Debug, Clone)]
+pub struct PropertiesEditor {
+    /// Item being edited.
+    path: PathBuf,
+    /// Display name of the item.
+    name: String,
+    /// Read-only, hidden and system, as edited.
+    flags: [bool; 3],
+    /// The attributes before editing.
+    original_flags: [bool; 3],
+    /// Created and modified times, as typed.
+    times: [String; 2],
+    /// The times before editing.
+    original_times: [String; 2],
+    /// Focused field: the checkboxes, then the times.
+    focus: usize,
+    /// Why the last Enter couldn't apply the changes.
+    error: Option<String>,
+}
+
+impl PropertiesEditor {
+    /// Start editing the item described by `properties`.
+    pub fn new(properties: &Properties) -> Self {
+        let flags = [properties.readonly, properties.hidden, properties.system];
+        let times = [
+            properties.created_display().unwrap_or_default(),
+            properties.modified_display().unwrap_or_default(),
+        ];
+        Self {
+            path: properties.path.clone(),
+            name: properties.name.clone(),
+            flags,
+            original_flags: flags,
+            times: times.clone(),
+            original_times: times,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> EditorResult {
+        let fields = FLAGS.len() + TIMES.len();
+        let time = self.focus.checked_sub(FLAGS.len());
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => return EditorResult::Cancelled,
+            (KeyModifiers::NONE, KeyCode::Enter) => match self.changes() {
+                Ok(changes) => return EditorResult::Apply(self.path.clone(), changes),
+                Err(message) => self.error = Some(message),
+            },
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Tab) => {
+                self.focus = (self.focus + 1) % fields;
+            }
+            (_, KeyCode::Up | KeyCode::BackTab) => {
+                self.focus = (self.focus + fields - 1) % fields;
+            }
+            (KeyModifiers::NONE, KeyCode::Char(' ')) if time.is_none() => {
+                self.flags[self.focus] = !self.flags[self.focus];
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace) => {
+                if let Some(i) = time {
+                    self.times[i].pop();
+                }
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                if let Some(i) = time {
+                    self.times[i].clear();
+                }
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                if let Some(i) = time {
+                    self.times[i].push(c);
+                }
+            }
+            _ => {}
+        }
+        EditorResult::Open
+    }
+
+    /// The changes made so far, or why a timestamp can't be used.
+    fn changes(&self) -> Result<PropertyChanges, String> {
+        let flag = |i: usize| (self.flags[i] != self.original_flags[i]).then_some(self.flags[i]);
+        let time = |i: usize| {
+            let text = self.times[i].trim();
+            if text == self.original_times[i] {
+                return Ok(None);
+            }
+            parse_time(text)
+                .map(Some)
+                .ok_or_else(|| format!("{}: enter a time as YYYY-MM-DD HH:MM:SS", TIMES[i]))
+        };
+        Ok(PropertyChanges {
+            readonly: flag(0),
+            hidden: flag(1),
+            system: flag(2),
+            created: time(0)?,
+            modified: time(1)?,
+        })
+    }
+
+    /// Render the editor centered in `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = 56.min(area.width.saturating_sub(4));
+        let height = 12.min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(format!(" Edit {} ", self.name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let focused = |i: usize| {
+            if i == self.focus {
+                Styles::cursor()
+            } else {
+                Style::default()
+            }
+        };
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, label) in FLAGS.iter().enumerate() {
+            let mark = if self.flags[i] { "[x]" } else { "[ ]" };
+            lines.push(Line::from(Span::styled(format!("{mark} {label}"), focused(i))));
+        }
+        lines.push(Line::from(""));
+        for (i, label) in TIMES.iter().enumerate() {
+            let field = FLAGS.len() + i;
+            let mut spans = vec![
+                Span::styled(format!("{:<10}", format!("{label}:")), Styles::muted()),
+                Span::styled(self.times[i].clone(), focused(field)),
+            ];
+            if field == self.focus {
+                spans.push(Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        if let Some(ref error) = self.error {
+            lines.push(Line::from(Span::styled(error.clone(), Styles::error())));
+        }
+        Paragraph::new(lines).render(inner, buf);
+
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new("Space toggle · ↑↓ move · Enter apply · Esc cancel")
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+}
+
+/// Parse a local time typed as `YYYY-MM-DD HH:MM:SS`.
+///
+/// A time skipped by a daylight saving change doesn't exist and is rejected;
+/// an ambiguous one resolves to the earlier instant.
+fn parse_time(text: &str) -> Option<SystemTime> {
+    let naive = NaiveDateTime::parse_from_str(text, TIME_FORMAT).ok()?;
+    Local.from_local_datetime(&naive).earliest().map(SystemTime::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(path: &std::path::Path) -> PropertiesEditor {
+        PropertiesEditor::new(&zmanager_core::get_properties(path).unwrap())
+    }
+
+    fn press(editor: &mut PropertiesEditor, code: KeyCode) -> EditorResult {
+        editor.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn only_changed_fields_are_applied() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("file.txt");
+        std::fs::write(&file, "content").unwrap();
+        let mut editor = editor(&file);
+
+        let result = press(&mut editor, KeyCode::Enter);
+        assert_eq!(result, EditorResult::Apply(file.clone(), PropertyChanges::default()));
+
+        // Toggle read-only, then retype the modified time
+        press(&mut editor, KeyCode::Char(' '));
+        for _ in 0..FLAGS.len() + 1 {
+            press(&mut editor, KeyCode::Down);
+        }
+        editor.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        for c in "2021-03-04 05:06:07".chars() {
+            press(&mut editor, KeyCode::Char(c));
+        }
+
+        let EditorResult::Apply(path, changes) = press(&mut editor, KeyCode::Enter) else {
+            panic!("expected changes to apply");
+        };
+        assert_eq!(path, file);
+        assert_eq!(changes.readonly, Some(true));
+        assert_eq!(changes.hidden, None);
+        assert_eq!(changes.created, None);
+        let expected = Local.with_ymd_and_hms(2021, 3, 4, 5, 6, 7).unwrap();
+        assert_eq!(changes.modified, Some(SystemTime::from(expected)));
+    }
+
+    #[test]
+    fn bad_times_keep_the_editor_open() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut editor = editor(temp.path());
+
+        press(&mut editor, KeyCode::Up);
+        press(&mut editor, KeyCode::Backspace);
+        // Space types into a time field instead of toggling
+        press(&mut editor, KeyCode::Char(' '));
+        press(&mut editor, KeyCode::Char('x'));
+        assert_eq!(press(&mut editor, KeyCode::Enter), EditorResult::Open);
+        assert!(editor.error.as_deref().unwrap().starts_with("Modified:"));
+        assert_eq!(editor.flags, editor.original_flags);
+        assert_eq!(press(&mut editor, KeyCode::Esc), EditorResult::Cancelled);
+    }
+
+    #[test]
+    fn parses_local_times() {
+        let expected = Local.with_ymd_and_hms(2024, 12, 31, 23, 59, 0).unwrap();
+        assert_eq!(parse_time("2024-12-31 23:59:00"), Some(SystemTime::from(expected)));
+        assert_eq!(parse_time("2024-12-31"), None);
+        assert_eq!(parse_time("yesterday"), None);
+    }
+}
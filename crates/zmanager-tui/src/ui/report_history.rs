@@ -0,0 +1,273 @@
+//! History tab of the transfers view, listing the stored transfer reports.
+
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use tracing::warn;
+use zmanager_transfer_win::{DetailedTransferReport, ReportStorage};
+
+use super::styles::Styles;
+
+/// Result of a key press in the history tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportHistoryResult {
+    /// The tab is still shown.
+    Open,
+    /// Queue the failed items of the highlighted report again.
+    Retry,
+    /// Go back to the jobs tab.
+    Closed,
+}
+
+/// History tab state: the stored reports, newest first.
+#[derive(Debug, Clone)]
+pub struct ReportHistory {
+    /// Stored reports, newest first.
+    reports: Vec<DetailedTransferReport>,
+    /// Highlighted report.
+    cursor: usize,
+    /// Whether the highlighted report is open to show its failed items.
+    inspecting: bool,
+}
+
+impl ReportHistory {
+    /// Read the reports kept in `storage`; unreadable ones are skipped.
+    pub fn load(storage: &ReportStorage) -> Self {
+        let names = storage.list().unwrap_or_else(|e| {
+            warn!("Failed to list transfer reports: {}", e);
+            Vec::new()
+        });
+        let mut reports: Vec<DetailedTransferReport> = names
+            .iter()
+            .filter_map(|name| match storage.load(name) {
+                Ok(report) => Some(report),
+                Err(e) => {
+                    warn!("Skipping transfer report {}: {}", name, e);
+                    None
+                }
+            })
+            .collect();
+        reports.sort_by_key(|report| std::cmp::Reverse(report.started_at));
+        Self {
+            reports,
+            cursor: 0,
+            inspecting: false,
+        }
+    }
+
+    /// Number of reports listed.
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Check if no reports are listed.
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// The highlighted report.
+    pub fn selected(&self) -> Option<&DetailedTransferReport> {
+        self.reports.get(self.cursor)
+    }
+
+    /// Check if the highlighted report is open.
+    pub fn is_inspecting(&self) -> bool {
+        self.inspecting && self.selected().is_some()
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> ReportHistoryResult {
+        let last = self.reports.len().saturating_sub(1);
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Left | KeyCode::Backspace)
+                if self.inspecting =>
+            {
+                self.inspecting = false;
+            }
+            (_, KeyCode::Esc | KeyCode::Tab) => return ReportHistoryResult::Closed,
+            (KeyModifiers::NONE, KeyCode::Enter | KeyCode::Right) => {
+                self.inspecting = !self.reports.is_empty();
+            }
+            (KeyModifiers::NONE, KeyCode::Char('r')) if self.selected().is_some() => {
+                return ReportHistoryResult::Retry;
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) if !self.inspecting => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) if !self.inspecting => {
+                self.cursor = (self.cursor + 1).min(last);
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) if !self.inspecting => {
+                self.cursor = 0;
+            }
+            (_, KeyCode::End | KeyCode::Char('G')) if !self.inspecting => self.cursor = last,
+            _ => {}
+        }
+        ReportHistoryResult::Open
+    }
+
+    /// Render the tab into `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Styles::active_border())
+            .title(" Transfer History (Tab for jobs, t to close) ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let list_area = Rect::new(inner.x, inner.y, inner.width, inner.height.saturating_sub(1));
+        match self.selected() {
+            Some(report) if self.inspecting => render_report(report, list_area, buf),
+            _ => self.render_list(list_area, buf),
+        }
+
+        let hint = if self.is_inspecting() {
+            "r retry failed · Esc back"
+        } else {
+            "Enter inspect · r retry failed · Tab jobs"
+        };
+        let hint_y = inner.y + inner.height.saturating_sub(1);
+        Paragraph::new(hint)
+            .style(Styles::hint())
+            .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+    }
+
+    /// Render one row per report, scrolled to keep the cursor in view.
+    fn render_list(&self, area: Rect, buf: &mut Buffer) {
+        if self.reports.is_empty() {
+            Paragraph::new("No finished transfers yet")
+                .alignment(Alignment::Center)
+                .style(Style::default().add_modifier(Modifier::DIM))
+                .render(area, buf);
+            return;
+        }
+
+        let visible = area.height as usize;
+        let skip = (self.cursor + 1).saturating_sub(visible);
+        let lines: Vec<Line> = self
+            .reports
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(i, report)| {
+                let summary = &report.summary;
+                let (symbol, symbol_style) = if report.was_cancelled {
+                    ("⊘", Styles::muted())
+                } else if summary.is_complete_success() {
+                    ("✓", Styles::success())
+                } else {
+                    ("✗", Styles::error())
+                };
+                let text = format!(
+                    " {:<6} {}  {:>6.1}%  {} item(s), {} failed",
+                    report.operation.label(),
+                    format_date(report),
+                    summary.success_percentage(),
+                    summary.total_items,
+                    summary.failed,
+                );
+                let style = if i == self.cursor {
+                    Styles::cursor()
+                } else {
+                    Styles::normal()
+                };
+                Line::from(vec![Span::styled(symbol, symbol_style), Span::styled(text, style)])
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+/// Render a report's summary and its failed items.
+fn render_report(report: &DetailedTransferReport, area: Rect, buf: &mut Buffer) {
+    let summary = &report.summary;
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} on {}", report.operation.label(), format_date(report)),
+            Styles::accent(),
+        )),
+        Line::from(format!(
+            "{} succeeded, {} skipped, {} failed in {}",
+            summary.succeeded,
+            summary.skipped,
+            summary.failed,
+            summary.duration_display(),
+        )),
+    ];
+    if summary.excluded > 0 {
+        let excluded = format!("{} excluded by include and exclude patterns", summary.excluded);
+        lines.push(Line::from(Span::styled(excluded, Styles::muted())));
+    }
+    lines.push(Line::from(""));
+
+    let failed: Vec<_> = report.failed_items().collect();
+    if failed.is_empty() {
+        lines.push(Line::from(Span::styled("No failed items", Styles::muted())));
+    }
+    for item in failed {
+        lines.push(Line::from(Span::styled(
+            format!("✗ {}", item.source.display()),
+            Styles::error(),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", item.reason.as_deref().unwrap_or("Unknown error")),
+            Styles::muted(),
+        )));
+    }
+    Paragraph::new(lines).render(area, buf);
+}
+
+/// Local date and time a report's transfer started.
+fn format_date(report: &DetailedTransferReport) -> String {
+    DateTime::<Local>::from(report.started_at)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use zmanager_core::JobId;
+    use zmanager_transfer_win::{ReportBuilder, TransferItemResult, TransferOperation};
+
+    #[test]
+    fn lists_stored_reports_and_inspects_one() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = ReportStorage::new(dir.path().to_path_buf());
+        let mut builder = ReportBuilder::new(JobId::new(), TransferOperation::Copy);
+        builder.add_item(TransferItemResult::failed(
+            PathBuf::from("a.txt"),
+            PathBuf::from("out/a.txt"),
+            "Access denied",
+        ));
+        storage.save(&builder.build()).unwrap();
+        std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+
+        let mut history = ReportHistory::load(&storage);
+        assert_eq!(history.len(), 1);
+        assert!(!history.is_inspecting());
+
+        history.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(history.is_inspecting());
+        assert_eq!(
+            history.handle_key(KeyEvent::from(KeyCode::Char('r'))),
+            ReportHistoryResult::Retry
+        );
+        history.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert!(!history.is_inspecting());
+        assert_eq!(
+            history.handle_key(KeyEvent::from(KeyCode::Esc)),
+            ReportHistoryResult::Closed
+        );
+    }
+}
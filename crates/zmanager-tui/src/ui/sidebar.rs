@@ -1,14 +1,17 @@
-//! Quick Access sidebar with favorites and drives.
+//! Quick Access sidebar with favorites, drives and a folder tree.
 
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
 };
-use zmanager_core::{DriveInfo, Favorite};
+use std::path::Path;
 
+use zmanager_core::{DriveInfo, Favorite, ZResult};
+
+use super::dir_tree::DirTree;
 use super::styles::Styles;
 
 /// Which section of the sidebar is focused.
@@ -17,14 +20,16 @@ pub enum SidebarSection {
     #[default]
     Favorites,
     Drives,
+    Tree,
 }
 
 impl SidebarSection {
-    /// Toggle between sections.
+    /// Move on to the next section.
     pub fn toggle(&self) -> Self {
         match self {
             Self::Favorites => Self::Drives,
-            Self::Drives => Self::Favorites,
+            Self::Drives => Self::Tree,
+            Self::Tree => Self::Favorites,
         }
     }
 }
@@ -54,9 +59,10 @@ impl StatefulWidget for Sidebar<'_> {
         // Clear the area first
         Clear.render(area, buf);
 
-        // Split into favorites section and drives section
+        // Split into favorites, drives and folder tree sections
         let chunks = Layout::vertical([
-            Constraint::Percentage(60),
+            Constraint::Percentage(35),
+            Constraint::Percentage(25),
             Constraint::Percentage(40),
         ])
         .split(area);
@@ -68,7 +74,7 @@ impl StatefulWidget for Sidebar<'_> {
             .border_style(if self.active_section == SidebarSection::Favorites {
                 Styles::selected()
             } else {
-                Style::default().fg(Color::DarkGray)
+                Styles::muted()
             });
 
         let favorites_inner = favorites_block.inner(chunks[0]);
@@ -77,7 +83,7 @@ impl StatefulWidget for Sidebar<'_> {
         if self.favorites.is_empty() {
             let empty_msg = Line::from(Span::styled(
                 "No favorites",
-                Style::default().fg(Color::DarkGray),
+                Styles::muted(),
             ));
             buf.set_line(
                 favorites_inner.x + 1,
@@ -98,12 +104,12 @@ impl StatefulWidget for Sidebar<'_> {
                     };
                     let icon = if fav.is_broken() { "⚠" } else { "📁" };
                     let style = if fav.is_broken() {
-                        Style::default().fg(Color::Red)
+                        Style::default().fg(Styles::theme().error)
                     } else {
                         Style::default()
                     };
                     ListItem::new(Line::from(vec![
-                        Span::styled(number, Style::default().fg(Color::DarkGray)),
+                        Span::styled(number, Styles::muted()),
                         Span::raw(icon),
                         Span::raw(" "),
                         Span::styled(&fav.name, style),
@@ -126,7 +132,7 @@ impl StatefulWidget for Sidebar<'_> {
             .border_style(if self.active_section == SidebarSection::Drives {
                 Styles::selected()
             } else {
-                Style::default().fg(Color::DarkGray)
+                Styles::muted()
             });
 
         let drives_inner = drives_block.inner(chunks[1]);
@@ -135,7 +141,7 @@ impl StatefulWidget for Sidebar<'_> {
         if self.drives.is_empty() {
             let empty_msg = Line::from(Span::styled(
                 "No drives",
-                Style::default().fg(Color::DarkGray),
+                Styles::muted(),
             ));
             buf.set_line(
                 drives_inner.x + 1,
@@ -156,7 +162,7 @@ impl StatefulWidget for Sidebar<'_> {
                         .unwrap_or_default();
 
                     let style = if !drive.is_ready {
-                        Style::default().fg(Color::DarkGray)
+                        Styles::muted()
                     } else {
                         Style::default()
                     };
@@ -165,7 +171,7 @@ impl StatefulWidget for Sidebar<'_> {
                         Span::raw(icon),
                         Span::raw(" "),
                         Span::styled(label, style),
-                        Span::styled(free, Style::default().fg(Color::DarkGray)),
+                        Span::styled(free, Styles::muted()),
                     ]))
                 })
                 .collect();
@@ -177,6 +183,44 @@ impl StatefulWidget for Sidebar<'_> {
             let mut drives_state = state.drives_state.clone();
             StatefulWidget::render(list, drives_inner, buf, &mut drives_state);
         }
+
+        // Render folder tree section
+        let tree_block = Block::default()
+            .title(" 📂 Folders ")
+            .borders(Borders::ALL)
+            .border_style(if self.active_section == SidebarSection::Tree {
+                Styles::selected()
+            } else {
+                Styles::muted()
+            });
+
+        let tree_inner = tree_block.inner(chunks[2]);
+        tree_block.render(chunks[2], buf);
+
+        let items: Vec<ListItem> = state
+            .tree
+            .nodes()
+            .iter()
+            .map(|node| {
+                let marker = match (node.expanded, node.has_children) {
+                    (true, _) => "▾ ",
+                    (false, Some(false)) => "  ",
+                    (false, _) => "▸ ",
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw("  ".repeat(node.depth)),
+                    Span::styled(marker, Styles::muted()),
+                    Span::styled(node.name.as_str(), Styles::directory()),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Styles::selected())
+            .highlight_symbol("▶ ");
+
+        let mut tree_state = state.tree_state.clone();
+        StatefulWidget::render(list, tree_inner, buf, &mut tree_state);
     }
 }
 
@@ -189,6 +233,10 @@ pub struct SidebarState {
     pub favorites_state: ListState,
     /// Drives list state.
     pub drives_state: ListState,
+    /// Folder tree of the active pane's drive.
+    pub tree: DirTree,
+    /// Folder tree list state.
+    pub tree_state: ListState,
 }
 
 impl SidebarState {
@@ -221,6 +269,12 @@ impl SidebarState {
                     self.drives_state.select(Some(current - 1));
                 }
             }
+            SidebarSection::Tree => {
+                let current = self.tree_state.selected().unwrap_or(0);
+                if current > 0 {
+                    self.tree_state.select(Some(current - 1));
+                }
+            }
         }
     }
 
@@ -245,6 +299,12 @@ impl SidebarState {
                     self.drives_state.select(Some(current + 1));
                 }
             }
+            SidebarSection::Tree => {
+                let current = self.tree_state.selected().unwrap_or(0);
+                if current < self.tree.len().saturating_sub(1) {
+                    self.tree_state.select(Some(current + 1));
+                }
+            }
         }
     }
 
@@ -263,6 +323,60 @@ impl SidebarState {
         self.drives_state.selected()
     }
 
+    /// Get the selected folder tree node index.
+    pub fn selected_tree_node(&self) -> Option<usize> {
+        self.tree_state.selected()
+    }
+
+    /// Show the folder tree of the drive holding `path`, expanded down to
+    /// `path`, which gets selected.
+    ///
+    /// The tree is rebuilt when the drive changes; otherwise folders the
+    /// user expanded stay open.
+    pub fn reveal_in_tree(&mut self, path: &Path, show_hidden: bool) {
+        // Virtual paths such as baskets have no drive to show
+        let Some(root) = path.ancestors().last().filter(|root| root.has_root()) else {
+            return;
+        };
+        if self.tree.root() != Some(root) {
+            self.tree = DirTree::new(root, show_hidden);
+        }
+        if let Some(index) = self.tree.reveal(path) {
+            self.tree_state.select(Some(index));
+        }
+    }
+
+    /// Expand the selected tree node, or step into its first subfolder if
+    /// it is already expanded.
+    pub fn expand_tree_node(&mut self) -> ZResult<()> {
+        let Some(index) = self.selected_tree_node() else {
+            return Ok(());
+        };
+        match self.tree.get(index) {
+            Some(node) if node.expanded => {
+                if node.has_children == Some(true) {
+                    self.tree_state.select(Some(index + 1));
+                }
+                Ok(())
+            }
+            Some(_) => self.tree.expand(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Collapse the selected tree node, or step out to its parent if it is
+    /// already collapsed.
+    pub fn collapse_tree_node(&mut self) {
+        let Some(index) = self.selected_tree_node() else {
+            return;
+        };
+        if self.tree.get(index).is_some_and(|node| node.expanded) {
+            self.tree.collapse(index);
+        } else if let Some(parent) = self.tree.parent(index) {
+            self.tree_state.select(Some(parent));
+        }
+    }
+
     /// Select a favorite by number (1-9).
     pub fn select_by_number(&mut self, num: usize, favorites_count: usize) {
         if num > 0 && num <= favorites_count && num <= 9 {
@@ -281,6 +395,7 @@ fn drive_icon(drive: &DriveInfo) -> &'static str {
         DriveType::Network => "🌐",
         DriveType::CdRom => "📀",
         DriveType::RamDisk => "🔧",
+        DriveType::Portable => "📱",
         _ => "💿",
     }
 }
@@ -317,10 +432,43 @@ mod tests {
         state.toggle_section();
         assert_eq!(state.section, SidebarSection::Drives);
 
+        state.toggle_section();
+        assert_eq!(state.section, SidebarSection::Tree);
+
         state.toggle_section();
         assert_eq!(state.section, SidebarSection::Favorites);
     }
 
+    #[test]
+    fn sidebar_tree_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let inner = dir.path().join("alpha").join("inner");
+        std::fs::create_dir_all(&inner).unwrap();
+
+        let mut state = SidebarState::new();
+        state.section = SidebarSection::Tree;
+        // Temporary folders can be hidden dot-folders
+        state.reveal_in_tree(&inner, true);
+        let selected = |state: &SidebarState| {
+            let node = state.tree.get(state.selected_tree_node().unwrap()).unwrap();
+            node.path.clone()
+        };
+        assert_eq!(selected(&state), inner);
+
+        // Left steps out to the parent, then collapses it
+        state.collapse_tree_node();
+        assert_eq!(selected(&state), dir.path().join("alpha"));
+        state.collapse_tree_node();
+        assert!(!state.tree.get(state.selected_tree_node().unwrap()).unwrap().expanded);
+
+        // Right expands, then steps in
+        state.expand_tree_node().unwrap();
+        state.expand_tree_node().unwrap();
+        assert_eq!(selected(&state), inner);
+        state.up(0, 0);
+        assert_eq!(selected(&state), dir.path().join("alpha"));
+    }
+
     #[test]
     fn sidebar_up_down() {
         let mut state = SidebarState::new();
@@ -6,8 +6,76 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Paragraph, Widget},
 };
+use zmanager_core::{JobInfo, JobState};
 
 use super::styles::Styles;
+use super::transfers::format_speed;
+
+/// Combined progress of the jobs running in the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobsSummary {
+    /// Number of running jobs.
+    pub running: usize,
+    /// Overall completion, when any running job knows its size.
+    pub percent: Option<u8>,
+    /// Combined transfer speed in bytes per second.
+    pub bytes_per_sec: u64,
+}
+
+impl JobsSummary {
+    /// Summarize the running jobs, or `None` when nothing is running.
+    ///
+    /// Completion is weighted by bytes across the jobs that know their total
+    /// size, falling back to items when none do.
+    pub fn from_jobs(jobs: &[JobInfo]) -> Option<Self> {
+        let running: Vec<&JobInfo> = jobs
+            .iter()
+            .filter(|job| job.state == JobState::Running)
+            .collect();
+        if running.is_empty() {
+            return None;
+        }
+
+        let (bytes_done, total_bytes) = running
+            .iter()
+            .filter_map(|job| job.total_bytes.map(|total| (job.bytes_done.min(total), total)))
+            .fold((0u64, 0u64), |(done, total), (d, t)| (done + d, total + t));
+        let (items_done, total_items) = running
+            .iter()
+            .fold((0, 0), |(done, total), job| {
+                (done + job.items_done.min(job.total_items), total + job.total_items)
+            });
+        let percent = if total_bytes > 0 {
+            Some((bytes_done as f64 / total_bytes as f64 * 100.0) as u8)
+        } else if total_items > 0 {
+            Some((items_done as f64 / total_items as f64 * 100.0) as u8)
+        } else {
+            None
+        };
+
+        Some(Self {
+            running: running.len(),
+            percent,
+            bytes_per_sec: running.iter().filter_map(|job| job.speed_bytes_per_sec).sum(),
+        })
+    }
+
+    /// Text shown in the status bar, like "2 jobs, 43%, 120.0 MB/s".
+    pub fn label(&self) -> String {
+        let mut label = if self.running == 1 {
+            "1 job".to_string()
+        } else {
+            format!("{} jobs", self.running)
+        };
+        if let Some(percent) = self.percent {
+            label.push_str(&format!(", {percent}%"));
+        }
+        if self.bytes_per_sec > 0 {
+            label.push_str(&format!(", {}", format_speed(self.bytes_per_sec)));
+        }
+        label
+    }
+}
 
 /// Status bar showing current state information.
 pub struct StatusBar<'a> {
@@ -19,8 +87,8 @@ pub struct StatusBar<'a> {
     selected_size: u64,
     /// Optional status message.
     message: Option<&'a str>,
-    /// Whether a job is in progress.
-    job_in_progress: bool,
+    /// Progress of the running jobs, if any.
+    jobs: Option<JobsSummary>,
 }
 
 impl<'a> StatusBar<'a> {
@@ -31,7 +99,7 @@ impl<'a> StatusBar<'a> {
             selected_count,
             selected_size,
             message: None,
-            job_in_progress: false,
+            jobs: None,
         }
     }
 
@@ -41,9 +109,9 @@ impl<'a> StatusBar<'a> {
         self
     }
 
-    /// Set job in progress flag.
-    pub fn job_in_progress(mut self, in_progress: bool) -> Self {
-        self.job_in_progress = in_progress;
+    /// Set the progress of the running jobs.
+    pub fn jobs(mut self, jobs: Option<JobsSummary>) -> Self {
+        self.jobs = jobs;
         self
     }
 
@@ -87,8 +155,8 @@ impl Widget for StatusBar<'_> {
         }
 
         // Job indicator
-        if self.job_in_progress {
-            spans.push(Span::styled(" | ⏳ Working...", Styles::warning()));
+        if let Some(jobs) = self.jobs {
+            spans.push(Span::styled(format!(" | ⏳ {}", jobs.label()), Styles::warning()));
         }
 
         // Message (if any)
@@ -136,4 +204,45 @@ mod tests {
         // Just ensure it doesn't panic
         assert_eq!(bar.selected_count, 5);
     }
+
+    fn job(state: JobState, bytes: (u64, Option<u64>), speed: Option<u64>) -> JobInfo {
+        JobInfo {
+            id: zmanager_core::JobId(1),
+            description: "Copying 1 item(s)".to_string(),
+            state,
+            priority: zmanager_core::Priority::Normal,
+            progress_percent: 0,
+            items_done: 1,
+            total_items: 4,
+            bytes_done: bytes.0,
+            total_bytes: bytes.1,
+            current_item: None,
+            speed_bytes_per_sec: speed,
+            speed_limit: None,
+            eta_secs: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn jobs_summary_combines_running_jobs() {
+        assert_eq!(JobsSummary::from_jobs(&[]), None);
+        let paused = job(JobState::Paused, (0, Some(100)), None);
+        assert_eq!(JobsSummary::from_jobs(std::slice::from_ref(&paused)), None);
+
+        let jobs = [
+            job(JobState::Running, (30, Some(100)), Some(60 * 1024 * 1024)),
+            job(JobState::Running, (70, Some(100)), Some(60 * 1024 * 1024)),
+            job(JobState::Running, (5, None), None),
+            paused,
+        ];
+        let summary = JobsSummary::from_jobs(&jobs).unwrap();
+        assert_eq!(summary.running, 3);
+        assert_eq!(summary.percent, Some(50));
+        assert_eq!(summary.label(), "3 jobs, 50%, 120.0 MB/s");
+
+        // Without byte totals, completion comes from items
+        let summary = JobsSummary::from_jobs(&[job(JobState::Running, (5, None), None)]).unwrap();
+        assert_eq!(summary.label(), "1 job, 25%");
+    }
 }
@@ -1,160 +1,204 @@
 //! Color and style definitions.
 
-use ratatui::style::{Color, Modifier, Style};
+use std::sync::RwLock;
 
-/// Application color scheme and styles.
-pub struct Styles;
-
-impl Styles {
-    // === Colors ===
-
-    /// Background color.
-    pub const BG: Color = Color::Reset;
-
-    /// Primary foreground color.
-    pub const FG: Color = Color::White;
-
-    /// Accent color for highlights.
-    pub const ACCENT: Color = Color::Cyan;
-
-    /// Secondary accent.
-    pub const ACCENT_DIM: Color = Color::DarkGray;
-
-    /// Directory color.
-    pub const DIR: Color = Color::Blue;
-
-    /// Executable/program color.
-    pub const EXE: Color = Color::Green;
-
-    /// Archive color.
-    pub const ARCHIVE: Color = Color::Red;
+use ratatui::style::{Modifier, Style};
+use zmanager_core::FileGroup;
 
-    /// Image color.
-    pub const IMAGE: Color = Color::Magenta;
+use super::theme::Theme;
 
-    /// Hidden file color.
-    pub const HIDDEN: Color = Color::DarkGray;
+/// Theme the styles are built from.
+static THEME: RwLock<Theme> = RwLock::new(Theme::DARK);
 
-    /// Error color.
-    pub const ERROR: Color = Color::Red;
-
-    /// Warning color.
-    pub const WARNING: Color = Color::Yellow;
+/// Application color scheme and styles.
+pub struct Styles;
 
-    /// Success color.
-    pub const SUCCESS: Color = Color::Green;
+impl Styles {
+    // === Theme ===
 
-    /// Selection background.
-    pub const SELECTION_BG: Color = Color::DarkGray;
+    /// Make `theme` the source of all styles.
+    pub fn set_theme(theme: Theme) {
+        *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+    }
 
-    /// Cursor background.
-    pub const CURSOR_BG: Color = Color::Rgb(50, 50, 80);
+    /// The active theme.
+    pub fn theme() -> Theme {
+        *THEME.read().unwrap_or_else(|e| e.into_inner())
+    }
 
     // === Styles ===
 
     /// Normal text style.
     pub fn normal() -> Style {
-        Style::default().fg(Self::FG)
+        Style::default().fg(Self::theme().fg)
     }
 
     /// Header style.
     pub fn header() -> Style {
-        Style::default().fg(Self::ACCENT).add_modifier(Modifier::BOLD)
+        Style::default().fg(Self::theme().accent).add_modifier(Modifier::BOLD)
     }
 
     /// Directory entry style.
     pub fn directory() -> Style {
-        Style::default().fg(Self::DIR).add_modifier(Modifier::BOLD)
+        Style::default().fg(Self::theme().directory).add_modifier(Modifier::BOLD)
     }
 
     /// Executable file style.
     pub fn executable() -> Style {
-        Style::default().fg(Self::EXE)
+        Style::default().fg(Self::theme().executable)
     }
 
     /// Archive file style.
     pub fn archive() -> Style {
-        Style::default().fg(Self::ARCHIVE)
+        Style::default().fg(Self::theme().archive)
     }
 
     /// Image file style.
     pub fn image() -> Style {
-        Style::default().fg(Self::IMAGE)
+        Style::default().fg(Self::theme().image)
+    }
+
+    /// Video file style.
+    pub fn video() -> Style {
+        Style::default().fg(Self::theme().video)
+    }
+
+    /// Audio file style.
+    pub fn audio() -> Style {
+        Style::default().fg(Self::theme().audio)
+    }
+
+    /// Source code file style.
+    pub fn code() -> Style {
+        Style::default().fg(Self::theme().code)
+    }
+
+    /// Document file style.
+    pub fn document() -> Style {
+        Style::default().fg(Self::theme().document)
     }
 
     /// Hidden file style.
     pub fn hidden() -> Style {
-        Style::default().fg(Self::HIDDEN)
+        Style::default().fg(Self::theme().hidden)
     }
 
     /// Selected item style.
     pub fn selected() -> Style {
-        Style::default().bg(Self::SELECTION_BG).add_modifier(Modifier::BOLD)
+        Style::default().bg(Self::theme().selection_bg).add_modifier(Modifier::BOLD)
     }
 
     /// Cursor (focused) item style.
     pub fn cursor() -> Style {
-        Style::default().bg(Self::CURSOR_BG)
+        Style::default().bg(Self::theme().cursor_bg)
     }
 
     /// Cursor + selected style.
     pub fn cursor_selected() -> Style {
         Style::default()
-            .bg(Self::CURSOR_BG)
+            .bg(Self::theme().cursor_bg)
             .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
     }
 
     /// Status bar style.
     pub fn status_bar() -> Style {
-        Style::default().bg(Color::DarkGray).fg(Color::White)
+        let theme = Self::theme();
+        Style::default().bg(theme.status_bg).fg(theme.status_fg)
     }
 
     /// Active pane border.
     pub fn active_border() -> Style {
-        Style::default().fg(Self::ACCENT)
+        Style::default().fg(Self::theme().accent)
     }
 
     /// Inactive pane border.
     pub fn inactive_border() -> Style {
-        Style::default().fg(Self::ACCENT_DIM)
+        Style::default().fg(Self::theme().muted)
+    }
+
+    /// Keys and other accented values.
+    pub fn accent() -> Style {
+        Style::default().fg(Self::theme().accent)
+    }
+
+    /// Secondary text such as labels and separators.
+    pub fn muted() -> Style {
+        Style::default().fg(Self::theme().muted)
+    }
+
+    /// Footer hints such as "Press any key to close".
+    pub fn hint() -> Style {
+        Self::muted().add_modifier(Modifier::ITALIC)
+    }
+
+    /// Section title inside a modal.
+    pub fn section() -> Style {
+        Style::default().fg(Self::theme().warning).add_modifier(Modifier::BOLD)
+    }
+
+    /// Value singled out in a panel.
+    pub fn highlight() -> Style {
+        Style::default().fg(Self::theme().warning)
     }
 
     /// Error message style.
     pub fn error() -> Style {
-        Style::default().fg(Self::ERROR).add_modifier(Modifier::BOLD)
+        Style::default().fg(Self::theme().error).add_modifier(Modifier::BOLD)
     }
 
     /// Warning message style.
     pub fn warning() -> Style {
-        Style::default().fg(Self::WARNING)
+        Style::default().fg(Self::theme().warning)
     }
 
     /// Success message style.
     pub fn success() -> Style {
-        Style::default().fg(Self::SUCCESS)
+        Style::default().fg(Self::theme().success)
+    }
+
+    /// Informational style.
+    pub fn info() -> Style {
+        Style::default().fg(Self::theme().info)
+    }
+
+    /// Rarely used accent.
+    pub fn special() -> Style {
+        Style::default().fg(Self::theme().special)
+    }
+
+    /// Part of a name matching the quick filter.
+    pub fn filter_match() -> Style {
+        Style::default()
+            .fg(Self::theme().warning)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
     }
 
     /// Size column style.
     pub fn size() -> Style {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(Self::theme().size)
     }
 
     /// Date column style.
     pub fn date() -> Style {
-        Style::default().fg(Color::Gray)
+        Style::default().fg(Self::theme().date)
     }
 
     /// Get style for a file by extension.
     pub fn for_extension(ext: &str) -> Style {
-        match ext.to_lowercase().as_str() {
-            // Executables
-            "exe" | "bat" | "cmd" | "ps1" | "msi" => Self::executable(),
-            // Archives
-            "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" => Self::archive(),
-            // Images
-            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "svg" | "ico" => Self::image(),
-            // Default
-            _ => Self::normal(),
+        Self::for_group(FileGroup::from_extension(ext))
+    }
+
+    /// Get style for a group of file types.
+    pub fn for_group(group: FileGroup) -> Style {
+        match group {
+            FileGroup::Document => Self::document(),
+            FileGroup::Image => Self::image(),
+            FileGroup::Video => Self::video(),
+            FileGroup::Audio => Self::audio(),
+            FileGroup::Code => Self::code(),
+            FileGroup::Archive => Self::archive(),
+            FileGroup::Executable => Self::executable(),
+            FileGroup::Other => Self::normal(),
         }
     }
 }
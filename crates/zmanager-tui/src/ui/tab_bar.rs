@@ -0,0 +1,62 @@
+//! Tab bar widget listing the open tabs of a pane.
+
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{Tabs, Widget},
+};
+
+use super::styles::Styles;
+
+/// Tab bar showing one title per tab, with the current tab highlighted.
+pub struct TabBar<'a> {
+    paths: Vec<&'a Path>,
+    active: usize,
+    is_active: bool,
+}
+
+impl<'a> TabBar<'a> {
+    /// Create a tab bar from the tab locations and the current tab index.
+    pub fn new(paths: Vec<&'a Path>, active: usize, is_active: bool) -> Self {
+        Self {
+            paths,
+            active,
+            is_active,
+        }
+    }
+}
+
+/// Title for a tab: the last path component, or the whole path for roots.
+fn tab_title(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+impl Widget for TabBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let highlight = if self.is_active {
+            Styles::cursor()
+        } else {
+            Styles::selected()
+        };
+        Tabs::new(self.paths.iter().map(|path| tab_title(path)))
+            .select(self.active)
+            .style(Styles::normal())
+            .highlight_style(highlight)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_title_uses_last_component() {
+        assert_eq!(tab_title(Path::new("/home/user/docs")), "docs");
+        assert_eq!(tab_title(Path::new("/")), "/");
+    }
+}
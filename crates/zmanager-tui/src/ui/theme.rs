@@ -0,0 +1,222 @@
+//! Color themes.
+//!
+//! The theme named by `appearance.theme` in the config is either built in
+//! (`default`, `light`) or read from `themes/<name>.toml` next to the
+//! config file. A theme file only needs the colors it changes; the rest
+//! come from the default theme:
+//!
+//! ```toml
+//! directory = "light-blue"
+//! selection_bg = "#3a3a3a"
+//! status_bg = "236"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+use zmanager_core::{Config, ZError, ZResult};
+
+/// Names of the built-in themes.
+pub const BUILTIN_THEMES: &[&str] = &["default", "light"];
+
+/// Colors used by the widgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Primary foreground color.
+    pub fg: Color,
+    /// Accent for borders, keys and highlights.
+    pub accent: Color,
+    /// Secondary text: hints, separators, inactive borders.
+    pub muted: Color,
+    /// Directory names.
+    pub directory: Color,
+    /// Executables and scripts.
+    pub executable: Color,
+    /// Archives.
+    pub archive: Color,
+    /// Images.
+    pub image: Color,
+    /// Video files.
+    pub video: Color,
+    /// Audio files.
+    pub audio: Color,
+    /// Source code and configuration files.
+    pub code: Color,
+    /// Documents and plain text.
+    pub document: Color,
+    /// Hidden files.
+    pub hidden: Color,
+    /// Errors and failed jobs.
+    pub error: Color,
+    /// Warnings and section titles.
+    pub warning: Color,
+    /// Success messages and finished jobs.
+    pub success: Color,
+    /// Informational accents, e.g. paused jobs.
+    pub info: Color,
+    /// Rarely used accent, e.g. ETAs.
+    pub special: Color,
+    /// Background of selected entries.
+    pub selection_bg: Color,
+    /// Background of the cursor row.
+    pub cursor_bg: Color,
+    /// Status bar background.
+    pub status_bg: Color,
+    /// Status bar foreground.
+    pub status_fg: Color,
+    /// Size column.
+    pub size: Color,
+    /// Date column.
+    pub date: Color,
+}
+
+impl Theme {
+    /// Built-in theme for dark terminals.
+    pub const DARK: Self = Self {
+        fg: Color::White,
+        accent: Color::Cyan,
+        muted: Color::DarkGray,
+        directory: Color::Blue,
+        executable: Color::Green,
+        archive: Color::Red,
+        image: Color::Magenta,
+        video: Color::LightMagenta,
+        audio: Color::LightCyan,
+        code: Color::LightGreen,
+        document: Color::LightYellow,
+        hidden: Color::DarkGray,
+        error: Color::Red,
+        warning: Color::Yellow,
+        success: Color::Green,
+        info: Color::Blue,
+        special: Color::Magenta,
+        selection_bg: Color::DarkGray,
+        cursor_bg: Color::Rgb(50, 50, 80),
+        status_bg: Color::DarkGray,
+        status_fg: Color::White,
+        size: Color::Yellow,
+        date: Color::Gray,
+    };
+
+    /// Built-in theme for light terminals.
+    pub const LIGHT: Self = Self {
+        fg: Color::Black,
+        accent: Color::Blue,
+        muted: Color::Rgb(120, 120, 120),
+        directory: Color::Blue,
+        executable: Color::Rgb(0, 128, 0),
+        archive: Color::Rgb(170, 0, 0),
+        image: Color::Magenta,
+        video: Color::Rgb(140, 0, 140),
+        audio: Color::Rgb(0, 120, 140),
+        code: Color::Rgb(0, 100, 60),
+        document: Color::Rgb(130, 100, 0),
+        hidden: Color::Rgb(150, 150, 150),
+        error: Color::Rgb(190, 0, 0),
+        warning: Color::Rgb(175, 95, 0),
+        success: Color::Rgb(0, 128, 0),
+        info: Color::Blue,
+        special: Color::Magenta,
+        selection_bg: Color::Rgb(210, 210, 210),
+        cursor_bg: Color::Rgb(200, 215, 245),
+        status_bg: Color::Rgb(200, 200, 200),
+        status_fg: Color::Black,
+        size: Color::Rgb(175, 95, 0),
+        date: Color::Rgb(90, 90, 90),
+    };
+
+    /// Look up a built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            _ => None,
+        }
+    }
+
+    /// Load the named theme, looking in the themes directory next to the
+    /// config file for anything that isn't built in.
+    pub fn load(name: &str) -> ZResult<Self> {
+        match Self::builtin(name) {
+            Some(theme) => Ok(theme),
+            None => Self::load_user(&themes_dir()?, name),
+        }
+    }
+
+    /// Load the user theme `<name>.toml` from `dir`.
+    pub fn load_user(dir: &Path, name: &str) -> ZResult<Self> {
+        let path = dir.join(format!("{name}.toml"));
+        if !path.is_file() {
+            return Err(ZError::Config {
+                message: format!(
+                    "Unknown theme '{name}' (built in: {}; no {})",
+                    BUILTIN_THEMES.join(", "),
+                    path.display()
+                ),
+            });
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| ZError::io(&path, e))?;
+        toml::from_str(&content).map_err(|e| ZError::Config {
+            message: format!("Invalid theme {}: {e}", path.display()),
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+/// Directory holding user theme files.
+fn themes_dir() -> ZResult<PathBuf> {
+    let config_path = Config::default_path()?;
+    Ok(config_path.with_file_name("themes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn builtin_themes_load_by_name() {
+        for name in BUILTIN_THEMES {
+            assert!(Theme::builtin(name).is_some());
+        }
+        assert_eq!(Theme::load("default").unwrap(), Theme::DARK);
+        assert_eq!(Theme::load("light").unwrap(), Theme::LIGHT);
+        assert_eq!(Theme::default(), Theme::DARK);
+    }
+
+    #[test]
+    fn user_theme_overrides_default_colors() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("ocean.toml"),
+            "directory = \"light-cyan\"\nselection_bg = \"#102030\"\nstatus_bg = \"236\"\n",
+        )
+        .unwrap();
+
+        let theme = Theme::load_user(temp.path(), "ocean").unwrap();
+
+        assert_eq!(theme.directory, Color::LightCyan);
+        assert_eq!(theme.selection_bg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.status_bg, Color::Indexed(236));
+        assert_eq!(theme.error, Theme::DARK.error);
+    }
+
+    #[test]
+    fn invalid_themes_are_errors() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("bad.toml"), "directory = \"not-a-color\"\n").unwrap();
+        std::fs::write(temp.path().join("typo.toml"), "directroy = \"blue\"\n").unwrap();
+
+        assert!(Theme::load_user(temp.path(), "missing").is_err());
+        assert!(Theme::load_user(temp.path(), "bad").is_err());
+        assert!(Theme::load_user(temp.path(), "typo").is_err());
+    }
+}
@@ -1,26 +1,156 @@
 //! Transfers view widget showing active jobs and progress.
 
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Rect},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
 };
-use zmanager_core::{JobInfo, JobState};
+use zmanager_core::{JobId, JobInfo, JobState};
+use zmanager_transfer_win::{
+    merge_elevated_results, DetailedTransferReport, ElevatedResult, ReportBuilder,
+    TransferItemResult, TransferOperation,
+};
 
 use super::styles::Styles;
 
+/// Rows of the details pane, hint line included.
+const DETAILS_HEIGHT: u16 = 12;
+
+/// Time a file must have been copying before its speed is shown.
+const FILE_SPEED_WARMUP: Duration = Duration::from_millis(500);
+
 /// Transfers view panel showing active/completed jobs.
 pub struct TransfersView<'a> {
     jobs: &'a [JobInfo],
     is_active: bool,
+    details: Option<(&'a JobInfo, &'a JobDetails)>,
 }
 
 impl<'a> TransfersView<'a> {
     /// Create a new transfers view.
     pub fn new(jobs: &'a [JobInfo], is_active: bool) -> Self {
-        Self { jobs, is_active }
+        Self {
+            jobs,
+            is_active,
+            details: None,
+        }
+    }
+
+    /// Show the details of a job below the list.
+    pub fn details(mut self, details: Option<(&'a JobInfo, &'a JobDetails)>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// What the transfers view shows about a job beyond its progress line: the
+/// items it finished, adding up to its report, and the file being copied.
+#[derive(Debug, Clone)]
+pub struct JobDetails {
+    /// Items finished so far.
+    report: ReportBuilder,
+    /// When the job started.
+    started: Instant,
+    /// When the job ended, once it has.
+    finished: Option<(Instant, SystemTime)>,
+    /// File being copied, when it started and the job's bytes done then.
+    current_file: Option<(PathBuf, Instant, u64)>,
+    /// Speed of the file being copied, once it has run long enough.
+    file_speed: Option<u64>,
+    /// Outcomes of failed items retried as administrator.
+    elevated: Vec<ElevatedResult>,
+}
+
+impl JobDetails {
+    /// Start following a job that just started.
+    pub fn new(job_id: JobId, operation: TransferOperation) -> Self {
+        Self {
+            report: ReportBuilder::new(job_id, operation),
+            started: Instant::now(),
+            finished: None,
+            current_file: None,
+            file_speed: None,
+            elevated: Vec::new(),
+        }
+    }
+
+    /// Add an item the job finished.
+    pub fn add_item(&mut self, item: TransferItemResult) {
+        self.report.add_item(item);
+    }
+
+    /// Set how many items the job's include and exclude patterns left out.
+    pub fn set_excluded(&mut self, excluded: usize) {
+        self.report.set_excluded(excluded);
+    }
+
+    /// Record the outcomes of failed items retried as administrator.
+    pub fn add_elevated(&mut self, results: Vec<ElevatedResult>) {
+        self.elevated.extend(results);
+    }
+
+    /// Catch up with the job's latest progress.
+    pub fn observe(&mut self, job: &JobInfo) {
+        if job.state.is_terminal() {
+            if self.finished.is_none() {
+                self.finished = Some((Instant::now(), SystemTime::now()));
+                self.report.set_cancelled(job.state == JobState::Cancelled);
+            }
+            self.current_file = None;
+            self.file_speed = None;
+            return;
+        }
+
+        match (&self.current_file, &job.current_item) {
+            (Some((path, since, start_bytes)), Some(current)) if path == current => {
+                let elapsed = since.elapsed();
+                if elapsed >= FILE_SPEED_WARMUP {
+                    let bytes = job.bytes_done.saturating_sub(*start_bytes);
+                    self.file_speed = Some((bytes as f64 / elapsed.as_secs_f64()) as u64);
+                }
+            }
+            (_, current) => {
+                self.current_file = current
+                    .clone()
+                    .map(|path| (path, Instant::now(), job.bytes_done));
+                self.file_speed = None;
+            }
+        }
+    }
+
+    /// The items finished so far.
+    pub fn items(&self) -> &[TransferItemResult] {
+        self.report.items()
+    }
+
+    /// Time the job has been running, or ran for.
+    pub fn elapsed(&self) -> Duration {
+        match self.finished {
+            Some((ended, _)) => ended.duration_since(self.started),
+            None => self.started.elapsed(),
+        }
+    }
+
+    /// Speed of the file being copied.
+    pub fn file_speed(&self) -> Option<u64> {
+        self.file_speed
+    }
+
+    /// The job's report, as it stands.
+    pub fn report(&self) -> DetailedTransferReport {
+        let mut report = match self.finished {
+            Some((_, ended)) => self.report.clone().build_at(ended),
+            None => self.report.clone().build(),
+        };
+        if !self.elevated.is_empty() {
+            merge_elevated_results(&mut report, &self.elevated);
+        }
+        report
     }
 }
 
@@ -41,11 +171,19 @@ impl StatefulWidget for TransfersView<'_> {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(" Transfers (t to close) ");
+            .title(" Transfers (Tab for history, t to close) ");
 
-        let inner = block.inner(area);
+        let mut inner = block.inner(area);
         block.render(area, buf);
 
+        if let Some((job, details)) = self.details {
+            let height = DETAILS_HEIGHT.min(inner.height.saturating_sub(3));
+            let [list, details_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(height)]).areas(inner);
+            inner = list;
+            render_details(job, details, details_area, buf);
+        }
+
         if self.jobs.is_empty() {
             // Show empty message
             Paragraph::new("No active transfers")
@@ -72,12 +210,12 @@ impl StatefulWidget for TransfersView<'_> {
 fn create_job_item(job: &JobInfo) -> ListItem<'static> {
     // Format: [State] Description | Progress Bar | Speed | ETA
     let state_span = match job.state {
-        JobState::Pending => Span::styled("⏳", Style::default().fg(Color::Yellow)),
-        JobState::Running => Span::styled("▶", Style::default().fg(Color::Green)),
-        JobState::Paused => Span::styled("⏸", Style::default().fg(Color::Blue)),
-        JobState::Completed => Span::styled("✓", Style::default().fg(Color::Green)),
-        JobState::Failed => Span::styled("✗", Style::default().fg(Color::Red)),
-        JobState::Cancelled => Span::styled("⊘", Style::default().fg(Color::DarkGray)),
+        JobState::Pending => Span::styled("⏳", Styles::warning()),
+        JobState::Running => Span::styled("▶", Styles::success()),
+        JobState::Paused => Span::styled("⏸", Styles::info()),
+        JobState::Completed => Span::styled("✓", Styles::success()),
+        JobState::Failed => Span::styled("✗", Style::default().fg(Styles::theme().error)),
+        JobState::Cancelled => Span::styled("⊘", Styles::muted()),
     };
 
     let desc = Span::raw(format!(" {} ", truncate_string(&job.description, 30)));
@@ -91,33 +229,118 @@ fn create_job_item(job: &JobInfo) -> ListItem<'static> {
     let speed = job.speed_bytes_per_sec
         .map(format_speed)
         .unwrap_or_else(|| "---".to_string());
-    let speed_span = Span::styled(format!(" {:>10}", speed), Style::default().fg(Color::Cyan));
+    let speed_span = Span::styled(format!(" {:>10}", speed), Styles::accent());
+    let limit_span = match job.speed_limit {
+        Some(limit) => Span::styled(
+            format!(" (max {})", format_speed(limit)),
+            Styles::muted(),
+        ),
+        None => Span::raw(""),
+    };
 
     let eta = job.eta_secs
         .map(format_eta)
         .unwrap_or_else(|| "---".to_string());
-    let eta_span = Span::styled(format!(" {:>8}", eta), Style::default().fg(Color::Magenta));
+    let eta_span = Span::styled(format!(" {:>8}", eta), Styles::special());
 
     ListItem::new(Line::from(vec![
         state_span,
         desc,
         progress_span,
         speed_span,
+        limit_span,
         eta_span,
     ]))
 }
 
+/// Render the details of `job` into `area`: its current file, timings,
+/// failures first, then the latest items it finished.
+fn render_details(job: &JobInfo, details: &JobDetails, area: Rect, buf: &mut Buffer) {
+    if area.height == 0 {
+        return;
+    }
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Styles::inactive_border())
+        .title(format!(" {} ", truncate_string(&job.description, 50)));
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let current = match &job.current_item {
+        Some(path) => path.display().to_string(),
+        None => "---".to_string(),
+    };
+    let file_speed = details
+        .file_speed()
+        .map(format_speed)
+        .unwrap_or_else(|| "---".to_string());
+    let eta = job.eta_secs.map(format_eta).unwrap_or_else(|| "---".to_string());
+
+    let items = details.items();
+    let failed = items.iter().filter(|item| item.is_failed()).count();
+    let skipped = items.iter().filter(|item| !item.is_success() && !item.is_failed()).count();
+    let done = items.len() - failed - skipped;
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Current  ", Styles::muted()),
+            Span::raw(current),
+        ]),
+        Line::from(vec![
+            Span::styled("File     ", Styles::muted()),
+            Span::styled(file_speed, Styles::accent()),
+            Span::styled("   Elapsed ", Styles::muted()),
+            Span::styled(format_eta(details.elapsed().as_secs()), Styles::special()),
+            Span::styled("   ETA ", Styles::muted()),
+            Span::styled(eta, Styles::special()),
+        ]),
+        Line::from(vec![
+            Span::styled("Items    ", Styles::muted()),
+            Span::styled(format!("{done} done"), Styles::success()),
+            Span::raw(", "),
+            Span::styled(format!("{failed} failed"), Styles::error()),
+            Span::raw(format!(", {skipped} skipped")),
+        ]),
+    ];
+
+    // Failures matter most; fill the rest with the latest items
+    let rows = (inner.height as usize).saturating_sub(lines.len() + 1);
+    let mut shown: Vec<&TransferItemResult> =
+        items.iter().filter(|item| item.is_failed()).take(rows).collect();
+    let latest = rows - shown.len();
+    shown.extend(items.iter().rev().filter(|item| !item.is_failed()).take(latest));
+    for item in shown {
+        let style = if item.is_failed() {
+            Styles::error()
+        } else if item.is_success() {
+            Styles::normal()
+        } else {
+            Styles::muted()
+        };
+        let mut text = format!("{} {}", item.status.symbol(), item.source.display());
+        if let Some(reason) = &item.reason {
+            text.push_str(&format!(" ({reason})"));
+        }
+        lines.push(Line::from(Span::styled(text, style)));
+    }
+    Paragraph::new(lines).render(inner, buf);
+
+    let hint_y = inner.y + inner.height.saturating_sub(1);
+    Paragraph::new("Enter hide details · ctrl+s export report")
+        .style(Styles::hint())
+        .render(Rect::new(inner.x, hint_y, inner.width, 1), buf);
+}
+
 fn progress_color(percent: u8) -> Color {
+    let theme = Styles::theme();
     match percent {
-        0..=25 => Color::Red,
-        26..=50 => Color::Yellow,
-        51..=75 => Color::LightYellow,
-        76..=99 => Color::LightGreen,
-        _ => Color::Green,
+        0..=25 => theme.error,
+        26..=75 => theme.warning,
+        _ => theme.success,
     }
 }
 
-fn format_speed(bytes_per_sec: u64) -> String {
+pub(super) fn format_speed(bytes_per_sec: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -172,6 +395,92 @@ impl Widget for JobProgressBar {
     }
 }
 
+/// Progress dialog of a transfer run in the foreground, drawn centered
+/// over the panes until the transfer finishes.
+pub struct ForegroundProgress<'a> {
+    /// The transfer, once the scheduler has listed it.
+    job: Option<&'a JobInfo>,
+}
+
+impl<'a> ForegroundProgress<'a> {
+    /// Create the dialog for `job`.
+    pub fn new(job: Option<&'a JobInfo>) -> Self {
+        Self { job }
+    }
+}
+
+impl Widget for ForegroundProgress<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = 64.min(area.width.saturating_sub(4));
+        let height = 9.min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let modal_area = Rect::new(x, y, width, height);
+
+        Clear.render(modal_area, buf);
+
+        let title = match self.job {
+            Some(job) if job.state == JobState::Paused => {
+                format!(" {} (paused) ", truncate_string(&job.description, 40))
+            }
+            Some(job) => format!(" {} ", truncate_string(&job.description, 50)),
+            None => " Starting transfer ".to_string(),
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Styles::accent());
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let [current, progress, counts, _, hint] = Layout::vertical([Constraint::Length(1); 5])
+            .areas(inner);
+
+        let Some(job) = self.job else {
+            Paragraph::new("Waiting for the transfer to start...")
+                .style(Styles::muted())
+                .render(current, buf);
+            return;
+        };
+
+        let name = job
+            .current_item
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "---".to_string());
+        Paragraph::new(Line::from(vec![
+            Span::styled("Current  ", Styles::muted()),
+            Span::raw(name),
+        ]))
+        .render(current, buf);
+
+        JobProgressBar {
+            percent: job.progress_percent,
+            label: format!("{}%", job.progress_percent),
+        }
+        .render(progress, buf);
+
+        let speed = job.speed_bytes_per_sec.map(format_speed).unwrap_or_else(|| "---".into());
+        let eta = job.eta_secs.map(format_eta).unwrap_or_else(|| "---".to_string());
+        Paragraph::new(Line::from(vec![
+            Span::styled("Items ", Styles::muted()),
+            Span::raw(format!("{}/{}", job.items_done, job.total_items)),
+            Span::styled("   Speed ", Styles::muted()),
+            Span::styled(speed, Styles::accent()),
+            Span::styled("   ETA ", Styles::muted()),
+            Span::styled(eta, Styles::special()),
+        ]))
+        .render(counts, buf);
+
+        let pause = if job.state == JobState::Paused { "resume" } else { "pause" };
+        Paragraph::new(format!("p {pause} · b run in background · Esc cancel"))
+            .style(Styles::hint())
+            .alignment(Alignment::Center)
+            .render(hint, buf);
+    }
+}
+
 /// Transfer status indicator for the status bar.
 #[derive(Debug, Clone)]
 pub struct TransferStatus {
@@ -252,6 +561,63 @@ mod tests {
         assert_eq!(format_eta(3661), "1h 01m");
     }
 
+    fn job(state: JobState, current: Option<&str>, bytes_done: u64) -> JobInfo {
+        JobInfo {
+            id: JobId(1),
+            description: "Copying 2 item(s)".to_string(),
+            state,
+            priority: zmanager_core::Priority::Normal,
+            progress_percent: 0,
+            items_done: 0,
+            total_items: 2,
+            bytes_done,
+            total_bytes: None,
+            current_item: current.map(PathBuf::from),
+            speed_bytes_per_sec: None,
+            speed_limit: None,
+            eta_secs: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn job_details_follow_the_job() {
+        let mut details = JobDetails::new(JobId(1), TransferOperation::Copy);
+        details.observe(&job(JobState::Running, Some("a.bin"), 0));
+        assert_eq!(details.file_speed(), None);
+        details.add_item(TransferItemResult::success("a".into(), "b".into(), 5));
+        details.add_item(TransferItemResult::failed("c".into(), "d".into(), "Access denied"));
+
+        details.observe(&job(JobState::Cancelled, None, 5));
+        let elapsed = details.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(details.elapsed(), elapsed);
+
+        let report = details.report();
+        assert!(report.was_cancelled);
+        assert_eq!(report.summary.succeeded, 1);
+        assert_eq!(report.summary.failed, 1);
+    }
+
+    #[test]
+    fn details_pane_lists_failures() {
+        let mut details = JobDetails::new(JobId(1), TransferOperation::Copy);
+        details.add_item(TransferItemResult::failed("bad.txt".into(), "x".into(), "Locked"));
+        let jobs = [job(JobState::Running, Some("now.bin"), 0)];
+        let area = Rect::new(0, 0, 60, 20);
+        let mut buf = Buffer::empty(area);
+
+        let view = TransfersView::new(&jobs, true).details(Some((&jobs[0], &details)));
+        StatefulWidget::render(view, area, &mut buf, &mut ListState::default());
+
+        let text: String = (0..area.height)
+            .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert!(text.contains("now.bin"));
+        assert!(text.contains("bad.txt (Locked)"));
+    }
+
     #[test]
     fn transfer_status_format() {
         let status = TransferStatus::new(2, 3, 1);
@@ -0,0 +1,572 @@
+//! Full-screen viewer paging through a file as text or hex.
+//!
+//! Only the rows on screen are read, so files of any size open at once.
+//! Searches run in the background: the viewer asks for one through
+//! [`ViewerResult::Search`] and is told the outcome with
+//! [`FileViewer::search_finished`].
+
+use std::cell::Cell;
+use std::ops::Range;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use zmanager_core::viewer::HEX_ROW_BYTES;
+use zmanager_core::{ContentLine, ContentSearch, FileContent, TextEncoding, ZResult};
+
+use super::styles::Styles;
+
+/// Result of a key press in the viewer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViewerResult {
+    /// The viewer is still open.
+    Open,
+    /// Search the file from an offset.
+    Search(ContentSearch, u64),
+    /// The viewer was closed.
+    Closed,
+}
+
+/// How the viewer shows the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewerMode {
+    /// Decoded text, line by line.
+    Text,
+    /// Offsets, hex bytes and their ASCII characters.
+    Hex,
+}
+
+/// Viewer state: the open file and the part of it on screen.
+#[derive(Debug)]
+pub struct FileViewer {
+    content: FileContent,
+    mode: ViewerMode,
+    /// Whether long lines wrap at the screen edge in text mode.
+    wrap: bool,
+    /// Offset of the first row on screen.
+    top: u64,
+    /// Characters scrolled off to the left of unwrapped lines.
+    column: usize,
+    /// Rows and columns of text the last render had room for.
+    page: Cell<(usize, usize)>,
+    /// Search text being typed, while the search prompt is open.
+    prompt: Option<String>,
+    /// Whether searches match case.
+    match_case: bool,
+    /// Text of the last search, for finding the next match.
+    last_search: Option<String>,
+    /// Bytes of the last match.
+    found: Option<Range<u64>>,
+    /// Whether a search is running.
+    searching: bool,
+    /// Message shown in the footer until the next key.
+    message: Option<String>,
+}
+
+impl FileViewer {
+    /// Open `path`, in hex mode if it looks like binary data.
+    pub fn open(path: &Path) -> ZResult<Self> {
+        let content = FileContent::open(path)?;
+        let mode = if content.is_binary() { ViewerMode::Hex } else { ViewerMode::Text };
+        Ok(Self {
+            top: content.text_start(),
+            content,
+            mode,
+            wrap: true,
+            column: 0,
+            page: Cell::new((0, 0)),
+            prompt: None,
+            match_case: false,
+            last_search: None,
+            found: None,
+            searching: false,
+            message: None,
+        })
+    }
+
+    /// The file being viewed.
+    pub fn path(&self) -> &Path {
+        self.content.path()
+    }
+
+    /// Encoding the file's text is decoded with.
+    pub fn encoding(&self) -> TextEncoding {
+        self.content.encoding()
+    }
+
+    /// How the file is shown.
+    pub fn mode(&self) -> ViewerMode {
+        self.mode
+    }
+
+    /// Offset of the first row on screen.
+    pub fn top(&self) -> u64 {
+        self.top
+    }
+
+    /// Handle a key event.
+    pub fn handle_key(&mut self, key: KeyEvent) -> ViewerResult {
+        self.message = None;
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        let rows = self.rows();
+        let result = match (key.modifiers, key.code) {
+            (_, KeyCode::Esc | KeyCode::F(3)) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+                return ViewerResult::Closed;
+            }
+            (KeyModifiers::NONE, KeyCode::Up | KeyCode::Char('k')) => self.scroll_up(1),
+            (KeyModifiers::NONE, KeyCode::Down | KeyCode::Char('j')) => self.scroll_down(1),
+            (KeyModifiers::NONE, KeyCode::PageUp | KeyCode::Char('b')) => self.scroll_up(rows),
+            (KeyModifiers::NONE, KeyCode::PageDown | KeyCode::Char(' ')) => {
+                self.scroll_down(rows)
+            }
+            (KeyModifiers::NONE, KeyCode::Home | KeyCode::Char('g')) => {
+                self.top = self.start();
+                Ok(())
+            }
+            (_, KeyCode::End | KeyCode::Char('G')) => self.scroll_to_end(),
+            (KeyModifiers::NONE, KeyCode::Left) => {
+                self.column = self.column.saturating_sub(8);
+                Ok(())
+            }
+            (KeyModifiers::NONE, KeyCode::Right) if !self.wrap => {
+                self.column += 8;
+                Ok(())
+            }
+            (KeyModifiers::NONE, KeyCode::Char('x')) => self.toggle_hex(),
+            (KeyModifiers::NONE, KeyCode::Char('w')) => self.toggle_wrap(),
+            (KeyModifiers::NONE, KeyCode::Char('e')) => self.cycle_encoding(),
+            (KeyModifiers::NONE, KeyCode::Char('/' | 'f')) => {
+                self.prompt = Some(String::new());
+                Ok(())
+            }
+            (_, KeyCode::Char('n')) => return self.search_again(false),
+            (_, KeyCode::Char('N')) => return self.search_again(true),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            self.message = Some(e.to_string());
+        }
+        ViewerResult::Open
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) -> ViewerResult {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return ViewerResult::Open;
+        };
+        match key.code {
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Tab => self.match_case = !self.match_case,
+            KeyCode::Backspace => {
+                prompt.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => prompt.push(c),
+            KeyCode::Enter => {
+                let text = self.prompt.take().unwrap_or_default();
+                if text.is_empty() {
+                    return ViewerResult::Open;
+                }
+                self.last_search = Some(text);
+                self.found = None;
+                return self.search_again(false);
+            }
+            _ => {}
+        }
+        ViewerResult::Open
+    }
+
+    /// Look for the last search text again, after the last match or from
+    /// the top of the screen.
+    fn search_again(&mut self, backward: bool) -> ViewerResult {
+        let Some(text) = self.last_search.clone() else {
+            self.prompt = Some(String::new());
+            return ViewerResult::Open;
+        };
+        if self.searching {
+            return ViewerResult::Open;
+        }
+        let from = match (&self.found, backward) {
+            (Some(found), false) => found.end,
+            (Some(found), true) => found.start,
+            (None, _) => self.top,
+        };
+        self.searching = true;
+        let search = ContentSearch {
+            text,
+            match_case: self.match_case,
+            backward,
+        };
+        ViewerResult::Search(search, from)
+    }
+
+    /// Whether a search is running.
+    pub fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    /// Show the outcome of a search: scroll to the match, or say there is
+    /// none.
+    pub fn search_finished(&mut self, result: Result<Option<Range<u64>>, String>) {
+        self.searching = false;
+        match result {
+            Ok(Some(found)) => {
+                let top = match self.mode {
+                    ViewerMode::Text => self.content.line_start(found.start, self.wrap_width()),
+                    ViewerMode::Hex => Ok(found.start - found.start % HEX_ROW_BYTES),
+                };
+                match top {
+                    Ok(top) => self.top = top,
+                    Err(e) => self.message = Some(e.to_string()),
+                }
+                self.column = 0;
+                self.found = Some(found);
+            }
+            Ok(None) => {
+                let text = self.last_search.as_deref().unwrap_or_default();
+                self.message = Some(format!("Not found: {text}"));
+            }
+            Err(message) => self.message = Some(format!("Search failed: {message}")),
+        }
+    }
+
+    /// Rows on screen, at least one.
+    fn rows(&self) -> usize {
+        self.page.get().0.max(1)
+    }
+
+    /// Width lines wrap at, or `None` when they don't.
+    fn wrap_width(&self) -> Option<usize> {
+        let width = self.page.get().1;
+        (self.mode == ViewerMode::Text && self.wrap && width > 0).then_some(width)
+    }
+
+    /// Offset of the first row of the file.
+    fn start(&self) -> u64 {
+        match self.mode {
+            ViewerMode::Text => self.content.text_start(),
+            ViewerMode::Hex => 0,
+        }
+    }
+
+    /// Hex row the view stops scrolling at.
+    fn last_hex_page(&self) -> u64 {
+        let rows = self.content.len().div_ceil(HEX_ROW_BYTES);
+        rows.saturating_sub(self.rows() as u64) * HEX_ROW_BYTES
+    }
+
+    fn scroll_down(&mut self, count: usize) -> ZResult<()> {
+        match self.mode {
+            ViewerMode::Text => {
+                let rows = self.rows();
+                let lines = self.content.lines(self.top, count + rows, self.wrap_width())?;
+                let index = count.min(lines.len().saturating_sub(rows));
+                if index > 0 {
+                    self.top = lines[index].offset;
+                }
+            }
+            ViewerMode::Hex => {
+                let top = self.top + count as u64 * HEX_ROW_BYTES;
+                self.top = top.min(self.last_hex_page()).max(self.top);
+            }
+        }
+        Ok(())
+    }
+
+    fn scroll_up(&mut self, count: usize) -> ZResult<()> {
+        match self.mode {
+            ViewerMode::Text => {
+                for _ in 0..count {
+                    self.top = self.content.prev_line(self.top, self.wrap_width())?;
+                }
+            }
+            ViewerMode::Hex => {
+                self.top = self.top.saturating_sub(count as u64 * HEX_ROW_BYTES);
+            }
+        }
+        Ok(())
+    }
+
+    fn scroll_to_end(&mut self) -> ZResult<()> {
+        self.top = match self.mode {
+            ViewerMode::Text => self.content.last_page(self.rows(), self.wrap_width())?,
+            ViewerMode::Hex => self.last_hex_page(),
+        };
+        Ok(())
+    }
+
+    fn toggle_hex(&mut self) -> ZResult<()> {
+        match self.mode {
+            ViewerMode::Text => {
+                self.mode = ViewerMode::Hex;
+                self.top -= self.top % HEX_ROW_BYTES;
+            }
+            ViewerMode::Hex => {
+                self.mode = ViewerMode::Text;
+                self.top = self.content.line_start(self.top, self.wrap_width())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn toggle_wrap(&mut self) -> ZResult<()> {
+        self.wrap = !self.wrap;
+        self.column = 0;
+        if self.mode == ViewerMode::Text {
+            self.top = self.content.line_start(self.top, self.wrap_width())?;
+        }
+        Ok(())
+    }
+
+    fn cycle_encoding(&mut self) -> ZResult<()> {
+        let encoding = self.content.encoding().next();
+        self.content.set_encoding(encoding);
+        self.found = None;
+        if self.mode == ViewerMode::Text {
+            self.top = self.content.line_start(self.top, self.wrap_width())?;
+        }
+        Ok(())
+    }
+
+    /// Render the viewer over all of `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height < 3 {
+            return;
+        }
+        let header = Rect::new(area.x, area.y, area.width, 1);
+        let body = Rect::new(area.x, area.y + 1, area.width, area.height - 2);
+        let footer = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        self.page.set((body.height as usize, body.width as usize));
+
+        self.render_header(header, buf);
+        let lines = match self.mode {
+            ViewerMode::Text => self.text_lines(body),
+            ViewerMode::Hex => self.hex_lines(body),
+        };
+        match lines {
+            Ok(lines) => Paragraph::new(lines).render(body, buf),
+            Err(e) => Paragraph::new(e.to_string()).style(Styles::error()).render(body, buf),
+        }
+        self.render_footer(footer, buf);
+    }
+
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let len = self.content.len();
+        let percent = (self.top * 100).checked_div(len).unwrap_or(100);
+        let mode = match self.mode {
+            ViewerMode::Text if self.wrap => "Text, wrapped",
+            ViewerMode::Text => "Text",
+            ViewerMode::Hex => "Hex",
+        };
+        let name = self.path().file_name().unwrap_or_default().to_string_lossy();
+        let details = format!(
+            "{} · {} · {} bytes · {}% ",
+            self.content.encoding().name(),
+            mode,
+            len,
+            percent
+        );
+        let width = area.width as usize;
+        let gap = width.saturating_sub(name.chars().count() + details.chars().count() + 1);
+        let line = Line::from(vec![
+            Span::styled(format!(" {name}"), Styles::header()),
+            Span::raw(" ".repeat(gap)),
+            Span::raw(details),
+        ]);
+        Paragraph::new(line).style(Styles::status_bar()).render(area, buf);
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let line = if let Some(ref prompt) = self.prompt {
+            let case = if self.match_case { "match case" } else { "ignore case" };
+            Line::from(vec![
+                Span::styled(" Find: ", Styles::accent()),
+                Span::raw(format!("{prompt}_")),
+                Span::styled(format!("  ({case}, Tab toggles)"), Styles::hint()),
+            ])
+        } else if self.searching {
+            Line::from(Span::styled(" Searching…", Styles::info()))
+        } else if let Some(ref message) = self.message {
+            Line::from(Span::styled(format!(" {message}"), Styles::warning()))
+        } else {
+            Line::from(Span::styled(
+                " x hex · w wrap · e encoding · / find · n/N next/previous · Esc close",
+                Styles::hint(),
+            ))
+        };
+        Paragraph::new(line).render(area, buf);
+    }
+
+    fn text_lines(&self, body: Rect) -> ZResult<Vec<Line<'static>>> {
+        let lines = self.content.lines(self.top, body.height as usize, self.wrap_width())?;
+        let column = if self.wrap { 0 } else { self.column };
+        Ok(lines.iter().map(|line| self.text_line(line, column)).collect())
+    }
+
+    /// A line of text, scrolled by `column`, with the match highlighted.
+    fn text_line(&self, line: &ContentLine, column: usize) -> Line<'static> {
+        let chars: Vec<char> = line.text.chars().skip(column).collect();
+        let found = self.found.as_ref().and_then(|found| line.char_range(found));
+        let Some(found) = found else {
+            return Line::from(chars.into_iter().collect::<String>());
+        };
+        let start = found.start.saturating_sub(column).min(chars.len());
+        let end = found.end.saturating_sub(column).min(chars.len());
+        Line::from(vec![
+            Span::raw(chars[..start].iter().collect::<String>()),
+            Span::styled(chars[start..end].iter().collect::<String>(), Styles::filter_match()),
+            Span::raw(chars[end..].iter().collect::<String>()),
+        ])
+    }
+
+    fn hex_lines(&self, body: Rect) -> ZResult<Vec<Line<'static>>> {
+        let rows = self.content.hex_rows(self.top, body.height as usize)?;
+        let found = self.found.clone().unwrap_or_default();
+        let style = |offset: u64| {
+            if found.contains(&offset) {
+                Styles::filter_match()
+            } else {
+                Style::default()
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let offset = format!("{:010X}  ", row.offset);
+                let mut spans = vec![Span::styled(offset, Styles::muted())];
+                for i in 0..HEX_ROW_BYTES as usize {
+                    let text = match row.bytes.get(i) {
+                        Some(byte) => format!("{byte:02X}"),
+                        None => "  ".to_string(),
+                    };
+                    spans.push(Span::styled(text, style(row.offset + i as u64)));
+                    spans.push(Span::raw(if i == 7 { "  " } else { " " }));
+                }
+                spans.push(Span::raw(" "));
+                for (i, &byte) in row.bytes.iter().enumerate() {
+                    let shown = byte.is_ascii_graphic() || byte == b' ';
+                    let c = if shown { byte as char } else { '.' };
+                    spans.push(Span::styled(c.to_string(), style(row.offset + i as u64)));
+                }
+                Line::from(spans)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open(dir: &TempDir, data: &[u8]) -> FileViewer {
+        let path = dir.path().join("view.txt");
+        std::fs::write(&path, data).unwrap();
+        FileViewer::open(&path).unwrap()
+    }
+
+    fn render(viewer: &FileViewer, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        viewer.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect()
+    }
+
+    fn press(viewer: &mut FileViewer, code: KeyCode) -> ViewerResult {
+        viewer.handle_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn pages_through_text() {
+        let dir = TempDir::new().unwrap();
+        let text: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let mut viewer = open(&dir, text.as_bytes());
+
+        let screen = render(&viewer, 40, 7);
+        assert!(screen[0].contains("view.txt"));
+        assert!(screen[0].contains("UTF-8"));
+        assert_eq!(screen[1].trim_end(), "line 1");
+        assert_eq!(screen[5].trim_end(), "line 5");
+
+        press(&mut viewer, KeyCode::PageDown);
+        assert_eq!(render(&viewer, 40, 7)[1].trim_end(), "line 6");
+        press(&mut viewer, KeyCode::Up);
+        assert_eq!(render(&viewer, 40, 7)[1].trim_end(), "line 5");
+        // The end shows a full last page
+        press(&mut viewer, KeyCode::End);
+        let screen = render(&viewer, 40, 7);
+        assert_eq!(screen[1].trim_end(), "line 16");
+        assert_eq!(screen[5].trim_end(), "line 20");
+        press(&mut viewer, KeyCode::Down);
+        assert_eq!(render(&viewer, 40, 7)[1].trim_end(), "line 16");
+
+        assert_eq!(press(&mut viewer, KeyCode::Esc), ViewerResult::Closed);
+    }
+
+    #[test]
+    fn toggles_hex_and_wrap() {
+        let dir = TempDir::new().unwrap();
+        let mut viewer = open(&dir, b"abcdefghijklmnopqrstuvwxyz");
+
+        assert_eq!(render(&viewer, 10, 5)[2].trim_end(), "klmnopqrst");
+        press(&mut viewer, KeyCode::Char('w'));
+        assert_eq!(render(&viewer, 10, 5)[2].trim_end(), "");
+
+        press(&mut viewer, KeyCode::Char('x'));
+        assert_eq!(viewer.mode(), ViewerMode::Hex);
+        let screen = render(&viewer, 80, 5);
+        assert!(screen[1].starts_with("0000000000  61 62 63"));
+        assert!(screen[1].contains("abcdefghijklmnop"));
+        assert!(screen[2].starts_with("0000000010  71 72"));
+    }
+
+    #[test]
+    fn binary_files_open_in_hex() {
+        let dir = TempDir::new().unwrap();
+        let viewer = open(&dir, b"MZ\0\0\x03\0");
+        assert_eq!(viewer.mode(), ViewerMode::Hex);
+    }
+
+    #[test]
+    fn search_scrolls_to_the_match() {
+        let dir = TempDir::new().unwrap();
+        let text: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let mut viewer = open(&dir, text.as_bytes());
+        render(&viewer, 40, 7);
+
+        press(&mut viewer, KeyCode::Char('/'));
+        for c in "LINE 12".chars() {
+            press(&mut viewer, KeyCode::Char(c));
+        }
+        let ViewerResult::Search(search, from) = press(&mut viewer, KeyCode::Enter) else {
+            panic!("expected a search");
+        };
+        assert_eq!(search.text, "LINE 12");
+        assert!(!search.match_case && !search.backward);
+        assert_eq!(from, 0);
+        assert!(viewer.is_searching());
+
+        let offset = text.find("line 12").unwrap() as u64;
+        viewer.search_finished(Ok(Some(offset..offset + 7)));
+        assert_eq!(viewer.top(), offset);
+        assert_eq!(render(&viewer, 40, 7)[1].trim_end(), "line 12");
+
+        // The next search starts after the match
+        let ViewerResult::Search(_, from) = press(&mut viewer, KeyCode::Char('n')) else {
+            panic!("expected a search");
+        };
+        assert_eq!(from, offset + 7);
+        viewer.search_finished(Ok(None));
+        assert!(render(&viewer, 40, 7)[6].contains("Not found: LINE 12"));
+    }
+}